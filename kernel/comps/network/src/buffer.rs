@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{collections::linked_list::LinkedList, sync::Arc};
+use alloc::{collections::linked_list::LinkedList, sync::Arc, vec::Vec};
 
 use ostd::{
     mm::{
@@ -12,7 +12,10 @@ use ostd::{
 };
 use spin::Once;
 
-use crate::dma_pool::{DmaPool, DmaSegment};
+use crate::{
+    checksum::PartialChecksum,
+    dma_pool::{DmaPool, DmaSegment},
+};
 
 pub struct TxBuffer {
     dma_stream: DmaStream,
@@ -66,6 +69,14 @@ impl TxBuffer {
     pub fn nbytes(&self) -> usize {
         self.nbytes
     }
+
+    /// Overwrites the bytes starting at `offset` of the buffer.
+    pub fn write_at(&mut self, offset: usize, bytes: &[u8]) {
+        assert!(offset + bytes.len() <= self.nbytes);
+        let mut writer = self.dma_stream.writer().unwrap().skip(offset);
+        writer.write(&mut VmReader::from(bytes));
+        self.dma_stream.sync(offset..offset + bytes.len()).unwrap();
+    }
 }
 
 impl HasDaddr for TxBuffer {
@@ -84,6 +95,11 @@ pub struct RxBuffer {
     segment: DmaSegment,
     header_len: usize,
     packet_len: usize,
+    /// The buffers that hold the remaining parts of the packet.
+    ///
+    /// A large packet can span multiple buffers if the device merges receive buffers.
+    merged_buffers: Vec<RxBuffer>,
+    partial_checksum: Option<PartialChecksum>,
 }
 
 impl RxBuffer {
@@ -94,6 +110,8 @@ impl RxBuffer {
             segment,
             header_len,
             packet_len: 0,
+            merged_buffers: Vec::new(),
+            partial_checksum: None,
         }
     }
 
@@ -106,6 +124,43 @@ impl RxBuffer {
         self.packet_len = packet_len;
     }
 
+    /// Appends a buffer that holds the next `packet_len` bytes of the packet.
+    ///
+    /// The appended buffer contains no header, so its header space is used for the packet.
+    pub fn merge(&mut self, mut buffer: RxBuffer, packet_len: usize) {
+        debug_assert!(buffer.merged_buffers.is_empty());
+        buffer.header_len = 0;
+        buffer.set_packet_len(packet_len);
+        self.merged_buffers.push(buffer);
+    }
+
+    /// Returns the length of the packet, including the parts in the merged buffers.
+    pub fn total_packet_len(&self) -> usize {
+        self.merged_buffers
+            .iter()
+            .fold(self.packet_len, |len, buffer| len + buffer.packet_len)
+    }
+
+    /// Reads the whole packet, including the parts in the merged buffers.
+    pub fn read_packet(&self, writer: &mut VmWriter<'_, Infallible>) {
+        writer.write(&mut self.packet());
+        for buffer in self.merged_buffers.iter() {
+            writer.write(&mut buffer.packet());
+        }
+    }
+
+    /// Returns the partial checksum of the packet that needs to be completed.
+    pub fn partial_checksum(&self) -> Option<PartialChecksum> {
+        self.partial_checksum
+    }
+
+    /// Marks the checksum of the packet as partial.
+    ///
+    /// See [`PartialChecksum`] for details.
+    pub fn set_partial_checksum(&mut self, partial_checksum: PartialChecksum) {
+        self.partial_checksum = Some(partial_checksum);
+    }
+
     pub fn packet(&self) -> VmReader<'_, Infallible> {
         self.segment
             .sync(self.header_len..self.header_len + self.packet_len)
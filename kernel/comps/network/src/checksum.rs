@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Checksum offloading support.
+//!
+//! A network device that supports checksum offloading can send and receive TCP and UDP packets
+//! with _partial_ checksums. The checksum field of such a packet only contains the checksum of
+//! the pseudo header, and the checksum is completed by computing the internet checksum from the
+//! start of the TCP or UDP header to the end of the packet.
+
+const ETHERNET_HEADER_LEN: usize = 14;
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const ETHER_TYPE_IPV6: u16 = 0x86DD;
+
+const IPV6_HEADER_LEN: usize = 40;
+
+const IP_PROTOCOL_TCP: u8 = 6;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+/// The offset of the checksum field in the TCP header.
+const TCP_CHECKSUM_OFFSET: usize = 16;
+/// The offset of the checksum field in the UDP header.
+const UDP_CHECKSUM_OFFSET: usize = 6;
+
+/// A partial checksum in a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialChecksum {
+    /// The position where the checksumming starts.
+    pub start: usize,
+    /// The offset of the checksum field, relative to `start`.
+    pub offset: usize,
+}
+
+impl PartialChecksum {
+    /// Prepares a partial checksum for an Ethernet frame whose TCP or UDP checksum is left to be
+    /// computed by the device.
+    ///
+    /// On success, this method returns the partial checksum and the value that should be stored
+    /// in the checksum field, i.e., the checksum of the pseudo header. It returns `None` if the
+    /// frame does not carry a complete TCP or UDP packet, or if the checksum field is not zero
+    /// (which means that the checksum has already been computed).
+    pub fn for_ethernet_frame(frame: &[u8]) -> Option<(Self, u16)> {
+        if frame.len() < ETHERNET_HEADER_LEN {
+            return None;
+        }
+        let ip_packet = &frame[ETHERNET_HEADER_LEN..];
+
+        let (ip_header_len, protocol, pseudo_header_sum) =
+            match read_u16(frame, ETHERNET_HEADER_LEN - 2) {
+                ETHER_TYPE_IPV4 => parse_ipv4_header(ip_packet)?,
+                ETHER_TYPE_IPV6 => parse_ipv6_header(ip_packet)?,
+                _ => return None,
+            };
+
+        let offset = match protocol {
+            IP_PROTOCOL_TCP => TCP_CHECKSUM_OFFSET,
+            IP_PROTOCOL_UDP => UDP_CHECKSUM_OFFSET,
+            _ => return None,
+        };
+        let start = ETHERNET_HEADER_LEN + ip_header_len;
+        if frame.len() < start + offset + 2 || read_u16(frame, start + offset) != 0 {
+            return None;
+        }
+
+        Some((Self { start, offset }, fold(pseudo_header_sum)))
+    }
+
+    /// Completes the checksum of the packet.
+    ///
+    /// The checksum field must already contain the checksum of the pseudo header.
+    pub fn complete(&self, packet: &mut [u8]) {
+        let pos = self.start + self.offset;
+        if packet.len() < pos + 2 {
+            return;
+        }
+
+        let checksum = !fold(add_words(&packet[self.start..], 0));
+        packet[pos..pos + 2].copy_from_slice(&checksum.to_be_bytes());
+    }
+}
+
+/// Parses an IPv4 header and returns the header length, the protocol, and the sum of the pseudo
+/// header.
+fn parse_ipv4_header(packet: &[u8]) -> Option<(usize, u8, u64)> {
+    if packet.len() < 20 {
+        return None;
+    }
+
+    let header_len = ((packet[0] & 0xF) as usize) * 4;
+    let total_len = read_u16(packet, 2) as usize;
+    if header_len < 20 || total_len < header_len || packet.len() < total_len {
+        return None;
+    }
+
+    // The checksum of a fragment cannot be computed without other fragments.
+    let fragment = read_u16(packet, 6);
+    if fragment & 0x3FFF != 0 {
+        return None;
+    }
+
+    let protocol = packet[9];
+    let payload_len = (total_len - header_len) as u64;
+    let sum = add_words(&packet[12..20], protocol as u64 + payload_len);
+
+    Some((header_len, protocol, sum))
+}
+
+/// Parses an IPv6 header and returns the header length, the protocol, and the sum of the pseudo
+/// header.
+fn parse_ipv6_header(packet: &[u8]) -> Option<(usize, u8, u64)> {
+    if packet.len() < IPV6_HEADER_LEN {
+        return None;
+    }
+
+    let payload_len = read_u16(packet, 4) as usize;
+    if packet.len() < IPV6_HEADER_LEN + payload_len {
+        return None;
+    }
+
+    // Extension headers are not supported.
+    let protocol = packet[6];
+    let sum = add_words(&packet[8..40], protocol as u64 + payload_len as u64);
+
+    Some((IPV6_HEADER_LEN, protocol, sum))
+}
+
+/// Adds the 16-bit words in `data` to `sum`.
+fn add_words(data: &[u8], mut sum: u64) -> u64 {
+    let mut chunks = data.chunks_exact(2);
+    for chunk in chunks.by_ref() {
+        sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u64;
+    }
+    if let [last] = chunks.remainder() {
+        sum += u16::from_be_bytes([*last, 0]) as u64;
+    }
+    sum
+}
+
+/// Folds the sum to a 16-bit ones' complement sum.
+fn fold(mut sum: u64) -> u16 {
+    while sum >> 16 != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    sum as u16
+}
+
+fn read_u16(data: &[u8], pos: usize) -> u16 {
+    u16::from_be_bytes([data[pos], data[pos + 1]])
+}
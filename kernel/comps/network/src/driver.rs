@@ -39,9 +39,12 @@ impl device::RxToken for RxToken {
     where
         F: FnOnce(&[u8]) -> R,
     {
-        let mut packet = self.0.packet();
-        let mut buffer = vec![0u8; packet.remain()];
-        packet.read(&mut VmWriter::from(&mut buffer as &mut [u8]));
+        let mut buffer = vec![0u8; self.0.total_packet_len()];
+        self.0
+            .read_packet(&mut VmWriter::from(&mut buffer as &mut [u8]));
+        if let Some(partial_checksum) = self.0.partial_checksum() {
+            partial_checksum.complete(&mut buffer);
+        }
         f(&buffer)
     }
 }
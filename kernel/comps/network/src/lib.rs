@@ -7,6 +7,7 @@
 #![feature(linked_list_cursors)]
 
 mod buffer;
+pub mod checksum;
 pub mod dma_pool;
 mod driver;
 
@@ -43,8 +43,25 @@ bitflags! {
 }
 
 impl NetworkFeatures {
+    /// Returns the features supported by the driver.
+    ///
+    /// The TCP segmentation offload is only supported in the receive direction, i.e.,
+    /// `VIRTIO_NET_F_GUEST_TSO4` and `VIRTIO_NET_F_GUEST_TSO6`, where the large segments
+    /// coalesced by the device are received into the mergeable buffers. The transmit direction,
+    /// i.e., `VIRTIO_NET_F_HOST_TSO4` and `VIRTIO_NET_F_HOST_TSO6`, is not negotiated, since the
+    /// network stack never generates TCP segments that are larger than the MTU.
+    //
+    // TODO: Support the transmit direction once the network stack can generate large segments.
     pub fn support_features() -> Self {
-        NetworkFeatures::VIRTIO_NET_F_MAC | NetworkFeatures::VIRTIO_NET_F_STATUS
+        NetworkFeatures::VIRTIO_NET_F_MAC
+            | NetworkFeatures::VIRTIO_NET_F_STATUS
+            | NetworkFeatures::VIRTIO_NET_F_CSUM
+            | NetworkFeatures::VIRTIO_NET_F_GUEST_CSUM
+            | NetworkFeatures::VIRTIO_NET_F_GUEST_TSO4
+            | NetworkFeatures::VIRTIO_NET_F_GUEST_TSO6
+            | NetworkFeatures::VIRTIO_NET_F_MRG_RXBUF
+            | NetworkFeatures::VIRTIO_NET_F_CTRL_VQ
+            | NetworkFeatures::VIRTIO_NET_F_MQ
     }
 }
 
@@ -62,7 +79,7 @@ bitflags! {
 pub struct VirtioNetConfig {
     pub mac: EthernetAddr,
     pub status: Status,
-    max_virtqueue_pairs: u16,
+    pub max_virtqueue_pairs: u16,
     pub mtu: u16,
     speed: u32,
     duplex: u8,
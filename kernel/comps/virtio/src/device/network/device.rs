@@ -1,23 +1,32 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{
-    boxed::Box, collections::linked_list::LinkedList, string::ToString, sync::Arc, vec::Vec,
+use alloc::{boxed::Box, collections::linked_list::LinkedList, format, sync::Arc, vec::Vec};
+use core::{
+    fmt::Debug,
+    hint::spin_loop,
+    mem::size_of,
+    sync::atomic::{AtomicUsize, Ordering},
 };
-use core::{fmt::Debug, mem::size_of};
 
 use aster_bigtcp::device::{Checksum, DeviceCapabilities, Medium};
 use aster_network::{
-    AnyNetworkDevice, EthernetAddr, RxBuffer, TxBuffer, VirtioNetError, RX_BUFFER_POOL,
+    checksum::PartialChecksum, AnyNetworkDevice, EthernetAddr, RxBuffer, TxBuffer, VirtioNetError,
+    RX_BUFFER_POOL,
 };
 use aster_util::slot_vec::SlotVec;
-use log::debug;
+use log::{debug, warn};
 use ostd::{
-    mm::DmaStream,
+    cpu::PinCurrentCpu,
+    mm::{DmaDirection, DmaStream, DmaStreamSlice, FrameAllocOptions, VmIo},
     sync::{LocalIrqDisabled, SpinLock},
+    task::disable_preempt,
     trap::TrapFrame,
 };
 
-use super::{config::VirtioNetConfig, header::VirtioNetHdr};
+use super::{
+    config::VirtioNetConfig,
+    header::{Flags, VirtioNetCtrlMq, VirtioNetHdr, VIRTIO_NET_OK},
+};
 use crate::{
     device::{network::config::NetworkFeatures, VirtioDeviceError},
     queue::{QueueError, VirtQueue},
@@ -26,24 +35,54 @@ use crate::{
 
 pub struct NetworkDevice {
     config: VirtioNetConfig,
+    features: NetworkFeatures,
     // For smoltcp use
     caps: DeviceCapabilities,
     mac_addr: EthernetAddr,
-    send_queue: VirtQueue,
-    recv_queue: VirtQueue,
-    // Since the virtio net header remains consistent for each sending packet,
-    // we store it to avoid recreating the header repeatedly.
-    header: VirtioNetHdr,
-    tx_buffers: Vec<Option<TxBuffer>>,
-    rx_buffers: SlotVec<RxBuffer>,
+    recv_queues: Vec<RecvQueue>,
+    send_queues: Vec<SendQueue>,
+    /// The index of the receive queue that will be polled first.
+    next_recv_queue: usize,
+    // The control queue is only used during initialization. But it is kept since the device may
+    // still access it.
+    ctrl_queue: Option<VirtQueue>,
     transport: Box<dyn VirtioTransport>,
 }
 
+struct RecvQueue {
+    queue: VirtQueue,
+    buffers: SlotVec<RxBuffer>,
+}
+
+struct SendQueue {
+    queue: VirtQueue,
+    buffers: Vec<Option<TxBuffer>>,
+}
+
 impl NetworkDevice {
     pub(crate) fn negotiate_features(device_features: u64) -> u64 {
         let device_features = NetworkFeatures::from_bits_truncate(device_features);
         let supported_features = NetworkFeatures::support_features();
-        let network_features = device_features & supported_features;
+        let mut network_features = device_features & supported_features;
+
+        // Receiving TSO packets requires `VIRTIO_NET_F_GUEST_CSUM` (see "5.1.3.1 Feature bit
+        // requirements" in the virtio specification). We also require `VIRTIO_NET_F_MRG_RXBUF`
+        // so that large packets can be received with page-sized receive buffers.
+        if !network_features.contains(
+            NetworkFeatures::VIRTIO_NET_F_GUEST_CSUM | NetworkFeatures::VIRTIO_NET_F_MRG_RXBUF,
+        ) {
+            network_features.remove(
+                NetworkFeatures::VIRTIO_NET_F_GUEST_TSO4 | NetworkFeatures::VIRTIO_NET_F_GUEST_TSO6,
+            );
+        }
+        // The control virtqueue is only used to enable multiple queues.
+        if !network_features
+            .contains(NetworkFeatures::VIRTIO_NET_F_CTRL_VQ | NetworkFeatures::VIRTIO_NET_F_MQ)
+        {
+            network_features
+                .remove(NetworkFeatures::VIRTIO_NET_F_CTRL_VQ | NetworkFeatures::VIRTIO_NET_F_MQ);
+        }
+
         debug!("{:?}", network_features);
         network_features.bits()
     }
@@ -61,38 +100,49 @@ impl NetworkDevice {
         debug!("mac addr = {:x?}, status = {:?}", mac_addr, config.status);
         let caps = init_caps(&features, &config);
 
-        let send_queue = VirtQueue::new(QUEUE_SEND, QUEUE_SIZE, transport.as_mut())
-            .expect("create send queue fails");
-
-        let mut recv_queue = VirtQueue::new(QUEUE_RECV, QUEUE_SIZE, transport.as_mut())
-            .expect("creating recv queue fails");
+        // Use one pair of queues for each CPU if possible.
+        let num_queue_pairs = if features.contains(NetworkFeatures::VIRTIO_NET_F_MQ) {
+            (config.max_virtqueue_pairs as usize)
+                .min(ostd::cpu::num_cpus() as usize)
+                .max(1)
+        } else {
+            1
+        };
 
-        let tx_buffers = (0..QUEUE_SIZE).map(|_| None).collect();
+        let mut recv_queues = Vec::with_capacity(num_queue_pairs);
+        let mut send_queues = Vec::with_capacity(num_queue_pairs);
+        for i in 0..num_queue_pairs {
+            let recv_queue = VirtQueue::new(recv_queue_index(i), QUEUE_SIZE, transport.as_mut())
+                .expect("creating recv queue fails");
+            recv_queues.push(RecvQueue::new(recv_queue)?);
 
-        let mut rx_buffers = SlotVec::new();
-        for i in 0..QUEUE_SIZE {
-            let rx_pool = RX_BUFFER_POOL.get().unwrap();
-            let rx_buffer = RxBuffer::new(size_of::<VirtioNetHdr>(), rx_pool);
-            // FIEME: Replace rx_buffer with VM segment-based data structure to use dma mapping.
-            let token = recv_queue.add_dma_buf(&[], &[&rx_buffer])?;
-            assert_eq!(i, token);
-            assert_eq!(rx_buffers.put(rx_buffer) as u16, i);
+            let send_queue = VirtQueue::new(send_queue_index(i), QUEUE_SIZE, transport.as_mut())
+                .expect("create send queue fails");
+            send_queues.push(SendQueue::new(send_queue));
         }
 
-        if recv_queue.should_notify() {
-            debug!("notify receive queue");
-            recv_queue.notify();
-        }
+        let ctrl_queue = if features.contains(NetworkFeatures::VIRTIO_NET_F_CTRL_VQ) {
+            // The control queue follows all the receive and send queues.
+            let ctrl_queue = VirtQueue::new(
+                config.max_virtqueue_pairs * 2,
+                CTRL_QUEUE_SIZE,
+                transport.as_mut(),
+            )
+            .expect("creating control queue fails");
+            Some(ctrl_queue)
+        } else {
+            None
+        };
 
         let mut device = Self {
             config,
+            features,
             caps,
             mac_addr,
-            send_queue,
-            recv_queue,
-            header: VirtioNetHdr::default(),
-            tx_buffers,
-            rx_buffers,
+            recv_queues,
+            send_queues,
+            next_recv_queue: 0,
+            ctrl_queue,
             transport,
         };
 
@@ -101,46 +151,113 @@ impl NetworkDevice {
             debug!("network device config space change");
         }
 
-        /// Interrupt handlers if network device receives/sends some packet
-        fn handle_send_event(_: &TrapFrame) {
-            aster_network::handle_send_irq(super::DEVICE_NAME);
-        }
-        fn handle_recv_event(_: &TrapFrame) {
-            aster_network::handle_recv_irq(super::DEVICE_NAME);
-        }
+        // Interrupt handlers if network device receives/sends some packet
+        let name = format!(
+            "{}{}",
+            super::DEVICE_NAME,
+            NEXT_UNIT.fetch_add(1, Ordering::Relaxed)
+        );
+        let handle_send_event = {
+            let name = name.clone();
+            move |_: &TrapFrame| aster_network::handle_send_irq(&name)
+        };
+        let handle_recv_event = {
+            let name = name.clone();
+            move |_: &TrapFrame| aster_network::handle_recv_irq(&name)
+        };
 
         device
             .transport
             .register_cfg_callback(Box::new(config_space_change))
             .unwrap();
-        device
-            .transport
-            .register_queue_callback(QUEUE_SEND, Box::new(handle_send_event), false)
-            .unwrap();
-        device
-            .transport
-            .register_queue_callback(QUEUE_RECV, Box::new(handle_recv_event), false)
-            .unwrap();
+        for i in 0..num_queue_pairs {
+            device
+                .transport
+                .register_queue_callback(
+                    send_queue_index(i),
+                    Box::new(handle_send_event.clone()),
+                    false,
+                )
+                .unwrap();
+
+            // Try to give each receive queue its own IRQ line, so that the interrupts of
+            // different queues can be handled by different CPUs.
+            let index = recv_queue_index(i);
+            if device
+                .transport
+                .register_queue_callback(index, Box::new(handle_recv_event.clone()), true)
+                .is_ok()
+            {
+                // The interrupts are still handled by the bootstrap processor on failure.
+                if let Err(err) = device.transport.set_queue_interrupt_cpu(index, i as u32) {
+                    debug!(
+                        "failed to steer the interrupts of queue {} to CPU {}: {:?}",
+                        index, i, err
+                    );
+                }
+            } else {
+                device
+                    .transport
+                    .register_queue_callback(index, Box::new(handle_recv_event.clone()), false)
+                    .unwrap();
+            }
+        }
 
         device.transport.finish_init();
 
-        aster_network::register_device(
-            super::DEVICE_NAME.to_string(),
-            Arc::new(SpinLock::new(device)),
-        );
+        if num_queue_pairs > 1 {
+            if let Err(err) = device.set_queue_pairs(num_queue_pairs as u16) {
+                warn!("failed to enable multiple queues: {:?}", err);
+                device.recv_queues.truncate(1);
+                device.send_queues.truncate(1);
+            }
+        }
+
+        aster_network::register_device(name, Arc::new(SpinLock::new(device)));
         Ok(())
     }
 
-    /// Add a rx buffer to recv queue
-    /// FIEME: Replace rx_buffer with VM segment-based data structure to use dma mapping.
-    fn add_rx_buffer(&mut self, rx_buffer: RxBuffer) -> Result<(), VirtioNetError> {
-        let token = self
-            .recv_queue
-            .add_dma_buf(&[], &[&rx_buffer])
+    /// Enables the first `num_pairs` pairs of receive and send queues.
+    ///
+    /// This sends a command through the control queue and waits for the device to handle it.
+    fn set_queue_pairs(&mut self, num_pairs: u16) -> Result<(), VirtioNetError> {
+        let ctrl_queue = self.ctrl_queue.as_mut().ok_or(VirtioNetError::NotReady)?;
+
+        let stream = {
+            let segment = FrameAllocOptions::new(1).alloc_contiguous().unwrap();
+            DmaStream::map(segment, DmaDirection::Bidirectional, false).unwrap()
+        };
+        let command_slice = {
+            let command_slice = DmaStreamSlice::new(&stream, 0, size_of::<VirtioNetCtrlMq>());
+            command_slice
+                .write_val(0, &VirtioNetCtrlMq::new(num_pairs))
+                .unwrap();
+            command_slice.sync().unwrap();
+            command_slice
+        };
+        let ack_slice = {
+            let ack_slice = DmaStreamSlice::new(&stream, size_of::<VirtioNetCtrlMq>(), 1);
+            ack_slice.write_val(0, &u8::MAX).unwrap();
+            ack_slice.sync().unwrap();
+            ack_slice
+        };
+
+        let token = ctrl_queue
+            .add_dma_buf(&[&command_slice], &[&ack_slice])
             .map_err(queue_to_network_error)?;
-        assert!(self.rx_buffers.put_at(token as usize, rx_buffer).is_none());
-        if self.recv_queue.should_notify() {
-            self.recv_queue.notify();
+        if ctrl_queue.should_notify() {
+            ctrl_queue.notify();
+        }
+        while !ctrl_queue.can_pop() {
+            spin_loop();
+        }
+        ctrl_queue
+            .pop_used_with_token(token)
+            .map_err(queue_to_network_error)?;
+
+        ack_slice.sync().unwrap();
+        if ack_slice.read_val::<u8>(0).unwrap() != VIRTIO_NET_OK {
+            return Err(VirtioNetError::Unknown);
         }
         Ok(())
     }
@@ -148,18 +265,47 @@ impl NetworkDevice {
     /// Receive a packet from network. If packet is ready, returns a RxBuffer containing the packet.
     /// Otherwise, return NotReady error.
     fn receive(&mut self) -> Result<RxBuffer, VirtioNetError> {
-        let (token, len) = self.recv_queue.pop_used().map_err(queue_to_network_error)?;
-        debug!("receive packet: token = {}, len = {}", token, len);
-        let mut rx_buffer = self
-            .rx_buffers
-            .remove(token as usize)
-            .ok_or(VirtioNetError::WrongToken)?;
-        rx_buffer.set_packet_len(len as usize);
-        // FIXME: Ideally, we can reuse the returned buffer without creating new buffer.
-        // But this requires locking device to be compatible with smoltcp interface.
-        let rx_pool = RX_BUFFER_POOL.get().unwrap();
-        let new_rx_buffer = RxBuffer::new(size_of::<VirtioNetHdr>(), rx_pool);
-        self.add_rx_buffer(new_rx_buffer)?;
+        // Poll the receive queues in a round-robin manner.
+        let num_queues = self.recv_queues.len();
+        for _ in 0..num_queues {
+            let index = self.next_recv_queue;
+            self.next_recv_queue = (index + 1) % num_queues;
+            if self.recv_queues[index].queue.can_pop() {
+                return self.receive_from(index);
+            }
+        }
+        Err(VirtioNetError::NotReady)
+    }
+
+    fn receive_from(&mut self, index: usize) -> Result<RxBuffer, VirtioNetError> {
+        let recv_queue = &mut self.recv_queues[index];
+
+        let (mut rx_buffer, len) = recv_queue.pop_buffer()?;
+        let packet_len = len
+            .checked_sub(size_of::<VirtioNetHdr>())
+            .ok_or(VirtioNetError::Unknown)?;
+        rx_buffer.set_packet_len(packet_len);
+        let header: VirtioNetHdr = rx_buffer.buf().read_val().unwrap();
+
+        // With `VIRTIO_NET_F_MRG_RXBUF`, a large packet can span multiple buffers.
+        if self
+            .features
+            .contains(NetworkFeatures::VIRTIO_NET_F_MRG_RXBUF)
+        {
+            for _ in 1..header.num_buffers() {
+                let (buffer, len) = recv_queue.pop_buffer()?;
+                rx_buffer.merge(buffer, len);
+            }
+        }
+
+        // With `VIRTIO_NET_F_GUEST_CSUM`, the checksum of a packet can be partial.
+        if header.flags().contains(Flags::VIRTIO_NET_HDR_F_NEEDS_CSUM) {
+            rx_buffer.set_partial_checksum(PartialChecksum {
+                start: header.csum_start() as usize,
+                offset: header.csum_offset() as usize,
+            });
+        }
+
         Ok(rx_buffer)
     }
 
@@ -170,23 +316,125 @@ impl NetworkDevice {
             return Err(VirtioNetError::Busy);
         }
 
-        let tx_buffer = TxBuffer::new(&self.header, packet, &TX_BUFFER_POOL);
+        // With `VIRTIO_NET_F_CSUM`, the TCP and UDP checksums can be computed by the device.
+        let partial_checksum = if self.features.contains(NetworkFeatures::VIRTIO_NET_F_CSUM) {
+            PartialChecksum::for_ethernet_frame(packet)
+        } else {
+            None
+        };
 
-        let token = self
-            .send_queue
+        let tx_buffer = if let Some((partial_checksum, pseudo_header_checksum)) = partial_checksum {
+            let header = VirtioNetHdr::with_partial_checksum(
+                partial_checksum.start as u16,
+                partial_checksum.offset as u16,
+            );
+            let mut tx_buffer = TxBuffer::new(&header, packet, &TX_BUFFER_POOL);
+            tx_buffer.write_at(
+                size_of::<VirtioNetHdr>() + partial_checksum.start + partial_checksum.offset,
+                &pseudo_header_checksum.to_be_bytes(),
+            );
+            tx_buffer
+        } else {
+            TxBuffer::new(&VirtioNetHdr::default(), packet, &TX_BUFFER_POOL)
+        };
+
+        let send_queue = &mut self.send_queues[current_send_queue(self.send_queues.len())];
+        let token = send_queue
+            .queue
             .add_dma_buf(&[&tx_buffer], &[])
             .map_err(queue_to_network_error)?;
-        if self.send_queue.should_notify() {
-            self.send_queue.notify();
+        if send_queue.queue.should_notify() {
+            send_queue.queue.notify();
         }
 
-        debug_assert!(self.tx_buffers[token as usize].is_none());
-        self.tx_buffers[token as usize] = Some(tx_buffer);
+        debug_assert!(send_queue.buffers[token as usize].is_none());
+        send_queue.buffers[token as usize] = Some(tx_buffer);
+
+        Ok(())
+    }
+}
+
+impl RecvQueue {
+    fn new(mut queue: VirtQueue) -> Result<Self, VirtioDeviceError> {
+        let mut buffers = SlotVec::new();
+        for i in 0..QUEUE_SIZE {
+            let rx_pool = RX_BUFFER_POOL.get().unwrap();
+            let rx_buffer = RxBuffer::new(size_of::<VirtioNetHdr>(), rx_pool);
+            // FIEME: Replace rx_buffer with VM segment-based data structure to use dma mapping.
+            let token = queue.add_dma_buf(&[], &[&rx_buffer])?;
+            assert_eq!(i, token);
+            assert_eq!(buffers.put(rx_buffer) as u16, i);
+        }
+
+        if queue.should_notify() {
+            debug!("notify receive queue");
+            queue.notify();
+        }
+
+        Ok(Self { queue, buffers })
+    }
+
+    /// Pops a used buffer and the number of bytes written to it.
+    ///
+    /// A new buffer is added to the queue in place of the popped one.
+    fn pop_buffer(&mut self) -> Result<(RxBuffer, usize), VirtioNetError> {
+        let (token, len) = self.queue.pop_used().map_err(queue_to_network_error)?;
+        debug!("receive packet: token = {}, len = {}", token, len);
+        let rx_buffer = self
+            .buffers
+            .remove(token as usize)
+            .ok_or(VirtioNetError::WrongToken)?;
+        // FIXME: Ideally, we can reuse the returned buffer without creating new buffer.
+        // But this requires locking device to be compatible with smoltcp interface.
+        let rx_pool = RX_BUFFER_POOL.get().unwrap();
+        let new_rx_buffer = RxBuffer::new(size_of::<VirtioNetHdr>(), rx_pool);
+        self.add_buffer(new_rx_buffer)?;
+        Ok((rx_buffer, len as usize))
+    }
 
+    /// Add a rx buffer to recv queue
+    /// FIEME: Replace rx_buffer with VM segment-based data structure to use dma mapping.
+    fn add_buffer(&mut self, rx_buffer: RxBuffer) -> Result<(), VirtioNetError> {
+        let token = self
+            .queue
+            .add_dma_buf(&[], &[&rx_buffer])
+            .map_err(queue_to_network_error)?;
+        assert!(self.buffers.put_at(token as usize, rx_buffer).is_none());
+        if self.queue.should_notify() {
+            self.queue.notify();
+        }
         Ok(())
     }
 }
 
+impl SendQueue {
+    fn new(queue: VirtQueue) -> Self {
+        let buffers = (0..QUEUE_SIZE).map(|_| None).collect();
+        Self { queue, buffers }
+    }
+
+    fn free_processed_buffers(&mut self) {
+        while let Ok((token, _)) = self.queue.pop_used() {
+            self.buffers[token as usize] = None;
+        }
+    }
+}
+
+/// Returns the index of the send queue used by the current CPU.
+fn current_send_queue(num_queues: usize) -> usize {
+    disable_preempt().current_cpu() as usize % num_queues
+}
+
+/// Returns the virtqueue index of the `i`-th receive queue.
+const fn recv_queue_index(i: usize) -> u16 {
+    (i * 2) as u16
+}
+
+/// Returns the virtqueue index of the `i`-th send queue.
+const fn send_queue_index(i: usize) -> u16 {
+    (i * 2 + 1) as u16
+}
+
 fn queue_to_network_error(err: QueueError) -> VirtioNetError {
     match err {
         QueueError::NotReady => VirtioNetError::NotReady,
@@ -206,29 +454,32 @@ fn init_caps(features: &NetworkFeatures, config: &VirtioNetConfig) -> DeviceCapa
         // If `VIRTIO_NET_F_MTU` is negotiated, the MTU is decided by the device.
         caps.max_transmission_unit = config.mtu as usize;
     } else {
-        // We do not support these features,
-        // so this asserts that they are _not_ negotiated.
+        // We do not support this feature,
+        // so this asserts that it is _not_ negotiated.
         //
-        // Without these features, the MTU is 1514 bytes per the virtio-net specification
+        // Without this feature, the MTU is 1514 bytes per the virtio-net specification
         // (see "5.1.6.3 Setting Up Receive Buffers" and "5.1.6.2 Packet Transmission").
-        assert!(
-            !features.contains(NetworkFeatures::VIRTIO_NET_F_GUEST_TSO4)
-                && !features.contains(NetworkFeatures::VIRTIO_NET_F_GUEST_TSO6)
-                && !features.contains(NetworkFeatures::VIRTIO_NET_F_GUEST_UFO)
-        );
+        // Note that TSO packets larger than the MTU can still be received
+        // if `VIRTIO_NET_F_GUEST_TSO4` or `VIRTIO_NET_F_GUEST_TSO6` is negotiated,
+        // but this does not affect the packets that we send.
+        assert!(!features.contains(NetworkFeatures::VIRTIO_NET_F_GUEST_UFO));
         caps.max_transmission_unit = 1514;
     }
 
-    // We do not support checksum offloading.
-    // So the features must not be negotiated,
-    // and we must deliver fully checksummed packets to the device
-    // and validate all checksums for packets from the device.
-    assert!(
-        !features.contains(NetworkFeatures::VIRTIO_NET_F_CSUM)
-            && !features.contains(NetworkFeatures::VIRTIO_NET_F_GUEST_CSUM)
-    );
-    caps.checksum.tcp = Checksum::Both;
-    caps.checksum.udp = Checksum::Both;
+    // If `VIRTIO_NET_F_CSUM` is negotiated, the device computes the TCP and UDP checksums
+    // for the packets that we send.
+    //
+    // If `VIRTIO_NET_F_GUEST_CSUM` is negotiated, the packets from the device may have
+    // partial checksums. They are completed before the packets are delivered to the network
+    // stack (see `RxBuffer::partial_checksum`), so we still validate all checksums for packets
+    // from the device.
+    let l4_checksum = if features.contains(NetworkFeatures::VIRTIO_NET_F_CSUM) {
+        Checksum::Rx
+    } else {
+        Checksum::Both
+    };
+    caps.checksum.tcp = l4_checksum;
+    caps.checksum.udp = l4_checksum;
     caps.checksum.ipv4 = Checksum::Both;
     caps.checksum.icmpv4 = Checksum::Both;
 
@@ -245,11 +496,14 @@ impl AnyNetworkDevice for NetworkDevice {
     }
 
     fn can_receive(&self) -> bool {
-        self.recv_queue.can_pop()
+        self.recv_queues
+            .iter()
+            .any(|recv_queue| recv_queue.queue.can_pop())
     }
 
     fn can_send(&self) -> bool {
-        self.send_queue.available_desc() >= 1
+        let send_queue = &self.send_queues[current_send_queue(self.send_queues.len())];
+        send_queue.queue.available_desc() >= 1
     }
 
     fn receive(&mut self) -> Result<RxBuffer, VirtioNetError> {
@@ -261,8 +515,8 @@ impl AnyNetworkDevice for NetworkDevice {
     }
 
     fn free_processed_tx_buffers(&mut self) {
-        while let Ok((token, _)) = self.send_queue.pop_used() {
-            self.tx_buffers[token as usize] = None;
+        for send_queue in self.send_queues.iter_mut() {
+            send_queue.free_processed_buffers();
         }
     }
 }
@@ -272,8 +526,8 @@ impl Debug for NetworkDevice {
         f.debug_struct("NetworkDevice")
             .field("config", &self.config)
             .field("mac_addr", &self.mac_addr)
-            .field("send_queue", &self.send_queue)
-            .field("recv_queue", &self.recv_queue)
+            .field("features", &self.features)
+            .field("num_queue_pairs", &self.recv_queues.len())
             .field("transport", &self.transport)
             .finish()
     }
@@ -282,7 +536,8 @@ impl Debug for NetworkDevice {
 static TX_BUFFER_POOL: SpinLock<LinkedList<DmaStream>, LocalIrqDisabled> =
     SpinLock::new(LinkedList::new());
 
-const QUEUE_RECV: u16 = 0;
-const QUEUE_SEND: u16 = 1;
+/// The unit number of the next virtio-net device.
+static NEXT_UNIT: AtomicUsize = AtomicUsize::new(0);
 
 const QUEUE_SIZE: u16 = 64;
+const CTRL_QUEUE_SIZE: u16 = 8;
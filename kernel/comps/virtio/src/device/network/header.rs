@@ -22,6 +22,40 @@ pub struct VirtioNetHdr {
                       // padding_reserved: u16,  // Only if VIRTIO_NET_F_HASH_REPORT negotiated
 }
 
+impl VirtioNetHdr {
+    /// Creates a header for a packet with a partial checksum.
+    ///
+    /// The device will compute the checksum from `csum_start` to the end of the packet and store
+    /// it at `csum_start + csum_offset`.
+    pub fn with_partial_checksum(csum_start: u16, csum_offset: u16) -> Self {
+        Self {
+            flags: Flags::VIRTIO_NET_HDR_F_NEEDS_CSUM,
+            csum_start,
+            csum_offset,
+            ..Default::default()
+        }
+    }
+
+    pub fn flags(&self) -> Flags {
+        self.flags
+    }
+
+    pub fn csum_start(&self) -> u16 {
+        self.csum_start
+    }
+
+    pub fn csum_offset(&self) -> u16 {
+        self.csum_offset
+    }
+
+    /// Returns the number of buffers that the packet spans.
+    ///
+    /// This is only meaningful if `VIRTIO_NET_F_MRG_RXBUF` is negotiated.
+    pub fn num_buffers(&self) -> u16 {
+        self.num_buffers
+    }
+}
+
 bitflags! {
     #[repr(C)]
     #[derive(Default, Pod)]
@@ -44,3 +78,28 @@ pub enum GsoType {
     VIRTIO_NET_HDR_GSO_UDP_L4 = 5,
     VIRTIO_NET_HDR_GSO_ECN = 0x80,
 }
+
+/// A command that sets the number of virtqueue pairs, sent through the control virtqueue.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct VirtioNetCtrlMq {
+    pub class: u8,
+    pub command: u8,
+    pub virtqueue_pairs: u16,
+}
+
+impl VirtioNetCtrlMq {
+    const VIRTIO_NET_CTRL_MQ: u8 = 4;
+    const VIRTIO_NET_CTRL_MQ_VQ_PAIRS_SET: u8 = 0;
+
+    pub fn new(virtqueue_pairs: u16) -> Self {
+        Self {
+            class: Self::VIRTIO_NET_CTRL_MQ,
+            command: Self::VIRTIO_NET_CTRL_MQ_VQ_PAIRS_SET,
+            virtqueue_pairs,
+        }
+    }
+}
+
+/// The acknowledgement that the device writes back for a control command.
+pub const VIRTIO_NET_OK: u8 = 0;
@@ -4,4 +4,8 @@ pub mod config;
 pub mod device;
 pub mod header;
 
+/// The name prefix of virtio-net devices.
+///
+/// Since there can be multiple virtio-net devices, each device is named by the prefix followed by
+/// its unit number (e.g., `Virtio-Net0`).
 pub static DEVICE_NAME: &str = "Virtio-Net";
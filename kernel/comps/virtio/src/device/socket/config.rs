@@ -9,13 +9,13 @@ use crate::transport::VirtioTransport;
 bitflags! {
     pub struct VsockFeatures: u64 {
         const VIRTIO_VSOCK_F_STREAM = 1 << 0; // stream socket type is supported.
-        const VIRTIO_VSOCK_F_SEQPACKET = 1 << 1; //seqpacket socket type is not supported now.
+        const VIRTIO_VSOCK_F_SEQPACKET = 1 << 1; // seqpacket socket type is supported.
     }
 }
 
 impl VsockFeatures {
     pub const fn supported_features() -> Self {
-        VsockFeatures::VIRTIO_VSOCK_F_STREAM
+        VsockFeatures::VIRTIO_VSOCK_F_STREAM.union(VsockFeatures::VIRTIO_VSOCK_F_SEQPACKET)
     }
 }
 
@@ -28,7 +28,7 @@
 
 use super::{
     error::SocketError,
-    header::{VirtioVsockHdr, VirtioVsockOp, VsockDeviceAddr},
+    header::{SeqPacketFlags, VirtioVsockHdr, VirtioVsockOp, VsockDeviceAddr, VsockType},
 };
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -63,6 +63,8 @@ pub enum VsockEventType {
     Received {
         /// The length of the data in bytes.
         length: usize,
+        /// The message boundary flags, which only make sense for seqpacket sockets.
+        flags: SeqPacketFlags,
     },
     /// The peer requests us to send a credit update.
     CreditRequest,
@@ -79,6 +81,8 @@ pub struct VsockEvent {
     pub destination: VsockDeviceAddr,
     /// The peer's buffer status for the connection.
     pub buffer_status: VsockBufferStatus,
+    /// The socket type of the connection.
+    pub socket_type: VsockType,
     /// The type of event.
     pub event_type: VsockEventType,
 }
@@ -99,6 +103,7 @@ impl VsockEvent {
         };
         let source = header.source();
         let destination = header.destination();
+        let socket_type = header.socket_type()?;
 
         let event_type = match op {
             VirtioVsockOp::Request => {
@@ -124,6 +129,7 @@ impl VsockEvent {
             }
             VirtioVsockOp::Rw => VsockEventType::Received {
                 length: header.len() as usize,
+                flags: SeqPacketFlags::from_bits_truncate(header.flags),
             },
             VirtioVsockOp::CreditRequest => {
                 header.check_data_is_empty()?;
@@ -136,6 +142,7 @@ impl VsockEvent {
             source,
             destination,
             buffer_status,
+            socket_type,
             event_type,
         })
     }
@@ -145,6 +152,8 @@ impl VsockEvent {
 pub struct ConnectionInfo {
     pub dst: VsockDeviceAddr,
     pub src_port: u32,
+    /// The socket type of the connection.
+    pub socket_type: VsockType,
     /// The last `buf_alloc` value the peer sent to us, indicating how much receive buffer space in
     /// bytes it has allocated for packet bodies.
     peer_buf_alloc: u32,
@@ -158,6 +167,8 @@ pub struct ConnectionInfo {
     pub buf_alloc: u32,
     /// The number of bytes of packet bodies which we have received from the peer and handled.
     pub fwd_cnt: u32,
+    /// The last `fwd_cnt` value we sent to the peer.
+    last_fwd_cnt: u32,
     /// Whether we have recently requested credit from the peer.
     ///
     /// This is set to true when we send a `VIRTIO_VSOCK_OP_CREDIT_REQUEST`, and false when we
@@ -190,13 +201,36 @@ impl ConnectionInfo {
     /// This should be called once received data has been passed to the client, so there is buffer
     /// space available for more.
     pub fn done_forwarding(&mut self, length: usize) {
-        self.fwd_cnt += length as u32;
+        self.fwd_cnt = self.fwd_cnt.wrapping_add(length as u32);
+    }
+
+    /// Returns whether the peer should be told how much buffer space we have freed.
+    ///
+    /// The peer only learns our `fwd_cnt` from the packets we send. If we have nothing to send, the
+    /// peer will stop sending data to us once it runs out of credit, so a credit update should be
+    /// sent when half of the buffer space has been freed since the last report.
+    pub fn needs_credit_update(&self) -> bool {
+        self.fwd_cnt.wrapping_sub(self.last_fwd_cnt) >= self.buf_alloc / 2
+    }
+
+    /// Records that the current `fwd_cnt` value has been sent to the peer.
+    pub fn done_reporting_credit(&mut self) {
+        self.last_fwd_cnt = self.fwd_cnt;
+    }
+
+    /// Returns the number of bytes of RX buffer space the peer has allocated to receive packet
+    /// body data from us.
+    pub fn peer_buf_alloc(&self) -> u32 {
+        self.peer_buf_alloc
     }
 
     /// Returns the number of bytes of RX buffer space the peer has available to receive packet body
     /// data from us.
     pub fn peer_free(&self) -> u32 {
-        self.peer_buf_alloc - (self.tx_cnt - self.peer_fwd_cnt)
+        // The counters are free-running, so they may wrap around. A misbehaving peer may also
+        // shrink its buffer while some data is in flight.
+        self.peer_buf_alloc
+            .saturating_sub(self.tx_cnt.wrapping_sub(self.peer_fwd_cnt))
     }
 
     pub fn new_header(&self, src_cid: u64) -> VirtioVsockHdr {
@@ -205,6 +239,7 @@ impl ConnectionInfo {
             dst_cid: self.dst.cid,
             src_port: self.src_port,
             dst_port: self.dst.port,
+            socket_type: self.socket_type as u16,
             buf_alloc: self.buf_alloc,
             fwd_cnt: self.fwd_cnt,
             ..Default::default()
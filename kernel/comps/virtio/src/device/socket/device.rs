@@ -3,7 +3,7 @@
 use alloc::{boxed::Box, string::ToString, sync::Arc, vec, vec::Vec};
 use core::{fmt::Debug, hint::spin_loop, mem::size_of};
 
-use aster_network::{RxBuffer, TxBuffer};
+use aster_network::{RxBuffer, TxBuffer, TX_BUFFER_LEN};
 use aster_util::{field_ptr, slot_vec::SlotVec};
 use log::debug;
 use ostd::{mm::VmWriter, offset_of, sync::SpinLock, trap::TrapFrame, Pod};
@@ -12,7 +12,7 @@ use super::{
     config::{VirtioVsockConfig, VsockFeatures},
     connect::{ConnectionInfo, VsockEvent},
     error::SocketError,
-    header::{VirtioVsockHdr, VirtioVsockOp, VIRTIO_VSOCK_HDR_LEN},
+    header::{SeqPacketFlags, VirtioVsockHdr, VirtioVsockOp, VIRTIO_VSOCK_HDR_LEN},
     VsockDeviceIrqHandler,
 };
 use crate::{
@@ -32,6 +32,9 @@ const QUEUE_RECV: u16 = 0;
 const QUEUE_SEND: u16 = 1;
 const QUEUE_EVENT: u16 = 2;
 
+/// The maximum length of the body of a packet that fits in a TX buffer.
+const MAX_PAYLOAD_LEN: usize = TX_BUFFER_LEN - VIRTIO_VSOCK_HDR_LEN;
+
 /// Vsock device driver
 pub struct SocketDevice {
     config: VirtioVsockConfig,
@@ -242,21 +245,41 @@ impl SocketDevice {
     }
 
     /// Sends the buffer to the destination.
+    ///
+    /// The buffer is split into packets that fit in the TX buffers. The `flags` are only set in
+    /// the last packet, so they can mark the end of a seqpacket message.
     pub fn send(
         &mut self,
         buffer: &[u8],
         connection_info: &mut ConnectionInfo,
+        flags: SeqPacketFlags,
     ) -> Result<(), SocketError> {
         self.check_peer_buffer_is_sufficient(connection_info, buffer.len())?;
 
-        let len = buffer.len() as u32;
-        let header = VirtioVsockHdr {
-            op: VirtioVsockOp::Rw as u16,
-            len,
-            ..connection_info.new_header(self.guest_cid)
-        };
-        connection_info.tx_cnt += len;
-        self.send_packet_to_tx_queue(&header, buffer)
+        let mut offset = 0;
+        loop {
+            let end = buffer.len().min(offset + MAX_PAYLOAD_LEN);
+            let is_last = end == buffer.len();
+
+            let len = (end - offset) as u32;
+            let header = VirtioVsockHdr {
+                op: VirtioVsockOp::Rw as u16,
+                len,
+                flags: if is_last { flags.bits() } else { 0 },
+                ..connection_info.new_header(self.guest_cid)
+            };
+            connection_info.tx_cnt = connection_info.tx_cnt.wrapping_add(len);
+            self.send_packet_to_tx_queue(&header, &buffer[offset..end])?;
+
+            if is_last {
+                break;
+            }
+            offset = end;
+        }
+
+        // The packets carry our latest `fwd_cnt` value.
+        connection_info.done_reporting_credit();
+        Ok(())
     }
 
     /// Receive bytes from peer, returns the header
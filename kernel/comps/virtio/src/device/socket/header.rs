@@ -100,6 +100,10 @@ impl VirtioVsockHdr {
         }
     }
 
+    pub fn socket_type(&self) -> error::Result<VsockType> {
+        VsockType::try_from(self.socket_type).map_err(|err| err.into())
+    }
+
     pub fn destination(&self) -> VsockDeviceAddr {
         VsockDeviceAddr {
             cid: self.dst_cid,
@@ -152,11 +156,24 @@ bitflags! {
     }
 }
 
-/// Currently only stream sockets are supported. type is 1 for stream socket types.
-#[derive(Copy, Clone, Debug)]
+bitflags! {
+    #[repr(C)]
+    #[derive(Default, Pod)]
+    /// Header flags field type makes sense when seqpacket socket sends or receives VIRTIO_VSOCK_OP_RW.
+    pub struct SeqPacketFlags: u32 {
+        /// The packet is the last packet of a message.
+        const VIRTIO_VSOCK_SEQ_EOM = 1 << 0;
+        /// The packet is the last packet of a record, i.e. the message is sent with `MSG_EOR`.
+        const VIRTIO_VSOCK_SEQ_EOR = 1 << 1;
+    }
+}
+
+/// The socket type. type is 1 for stream socket types and 2 for seqpacket socket types.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, TryFromInt)]
 #[repr(u16)]
 pub enum VsockType {
     /// Stream sockets provide in-order, guaranteed, connection-oriented delivery without message boundaries.
+    #[default]
     Stream = 1,
     /// seqpacket socket type introduced in virtio-v1.2.
     SeqPacket = 2,
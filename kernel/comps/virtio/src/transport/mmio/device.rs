@@ -279,6 +279,15 @@ impl VirtioTransport for VirtioMmioTransport {
         Ok(())
     }
 
+    fn set_queue_interrupt_cpu(
+        &mut self,
+        _index: u16,
+        _cpu_id: u32,
+    ) -> Result<(), VirtioTransportError> {
+        // All the virtqueues share the same IRQ line.
+        Err(VirtioTransportError::NotEnoughResources)
+    }
+
     fn register_cfg_callback(
         &mut self,
         func: Box<IrqCallbackFunction>,
@@ -87,6 +87,14 @@ pub trait VirtioTransport: Sync + Send + Debug {
         single_interrupt: bool,
     ) -> Result<(), VirtioTransportError>;
 
+    /// Set the CPU that handles the interrupts of a virtqueue. This only works if the virtqueue
+    /// owns a single IRQ line, i.e., its callback is registered with `single_interrupt` set.
+    fn set_queue_interrupt_cpu(
+        &mut self,
+        index: u16,
+        cpu_id: u32,
+    ) -> Result<(), VirtioTransportError>;
+
     /// Register configuration space change interrupt callback.
     fn register_cfg_callback(
         &mut self,
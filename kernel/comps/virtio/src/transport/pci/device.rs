@@ -230,6 +230,26 @@ impl VirtioTransport for VirtioPciTransport {
         Ok(())
     }
 
+    fn set_queue_interrupt_cpu(
+        &mut self,
+        index: u16,
+        cpu_id: u32,
+    ) -> Result<(), VirtioTransportError> {
+        if index >= self.num_queues() {
+            return Err(VirtioTransportError::InvalidArgs);
+        }
+        field_ptr!(&self.common_cfg, VirtioPciCommonCfg, queue_select)
+            .write_once(&index)
+            .unwrap();
+        let vector = field_ptr!(&self.common_cfg, VirtioPciCommonCfg, queue_msix_vector)
+            .read_once()
+            .unwrap();
+        if !self.msix_manager.set_irq_cpu(vector, cpu_id) {
+            return Err(VirtioTransportError::InvalidArgs);
+        }
+        Ok(())
+    }
+
     fn register_cfg_callback(
         &mut self,
         func: Box<IrqCallbackFunction>,
@@ -62,4 +62,13 @@ impl VirtioMsixManager {
         self.used_msix_vectors.push(vector);
         Some((vector, self.msix.irq_mut(vector as usize).unwrap()))
     }
+
+    /// Sets the CPU that handles the interrupts of a used MSI-X vector.
+    ///
+    /// This function will return `false` if the vector is not popped by `pop_unused_irq`, since
+    /// the config vector and the shared vector are not owned by any single virtqueue. It will
+    /// also return `false` if the interrupts cannot be delivered to the CPU.
+    pub fn set_irq_cpu(&mut self, vector: u16, cpu_id: u32) -> bool {
+        self.used_msix_vectors.contains(&vector) && self.msix.set_interrupt_cpu(vector, cpu_id)
+    }
 }
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub use smoltcp::iface::RouteTableFull;
+
 /// An error describing the reason why `bind` failed.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum BindError {
@@ -9,6 +11,19 @@ pub enum BindError {
     InUse,
 }
 
+/// An error returned by [`Iface::inject_frame`].
+///
+/// [`Iface::inject_frame`]: crate::iface::Iface::inject_frame
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InjectError {
+    /// The iface does not support injecting frames.
+    Unsupported,
+    /// The frame is larger than the MTU of the iface.
+    TooLarge,
+    /// The transmit queue of the device is full.
+    BufferFull,
+}
+
 pub mod tcp {
     pub use smoltcp::socket::tcp::{ConnectError, ListenError, RecvError, SendError};
 }
@@ -26,3 +41,49 @@ pub mod udp {
         BufferFull,
     }
 }
+
+pub mod ping {
+    /// An error returned by [`BoundPingSocket::send`].
+    ///
+    /// [`BoundPingSocket::send`]: crate::socket::BoundPingSocket::send
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum SendError {
+        TooLarge,
+        Unaddressable,
+        BufferFull,
+        /// The message is not an ICMP echo request.
+        InvalidMessage,
+    }
+
+    /// An error returned by [`BoundPingSocket::recv`].
+    ///
+    /// [`BoundPingSocket::recv`]: crate::socket::BoundPingSocket::recv
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum RecvError {
+        Exhausted,
+    }
+}
+
+pub mod raw {
+    /// An error returned by [`BoundRawIpSocket::send`] and
+    /// [`BoundRawIpSocket::send_with_header`].
+    ///
+    /// [`BoundRawIpSocket::send`]: crate::socket::BoundRawIpSocket::send
+    /// [`BoundRawIpSocket::send_with_header`]: crate::socket::BoundRawIpSocket::send_with_header
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum SendError {
+        TooLarge,
+        Unaddressable,
+        BufferFull,
+        /// The IP header included in the packet is invalid.
+        InvalidHeader,
+    }
+
+    /// An error returned by [`BoundRawIpSocket::recv`].
+    ///
+    /// [`BoundRawIpSocket::recv`]: crate::socket::BoundRawIpSocket::recv
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum RecvError {
+        Exhausted,
+    }
+}
@@ -7,35 +7,48 @@ use alloc::{
         btree_set::BTreeSet,
     },
     sync::Arc,
+    vec::Vec,
 };
 
 use keyable_arc::KeyableArc;
 use ostd::sync::{LocalIrqDisabled, PreemptDisabled, SpinLock, SpinLockGuard};
 use smoltcp::{
-    iface::{packet::Packet, Context},
+    iface::{packet::Packet, Context, Route, RouteTableFull},
     phy::Device,
-    wire::{Ipv4Address, Ipv4Packet},
+    wire::{IpAddress, IpCidr, Ipv4Address, Ipv6Address},
 };
 
 use super::{
-    poll::{FnHelper, PollContext},
+    filter::PacketFilter,
+    poll::{FnHelper, IpPacket, PollContext},
     port::BindPortConfig,
+    stats::IfaceStats,
     time::get_network_timestamp,
     Iface,
 };
 use crate::{
     errors::BindError,
     socket::{
-        BoundTcpSocket, BoundTcpSocketInner, BoundUdpSocket, BoundUdpSocketInner, UnboundTcpSocket,
-        UnboundUdpSocket,
+        BoundPingSocket, BoundPingSocketInner, BoundRawIpSocket, BoundRawIpSocketInner,
+        BoundTcpSocket, BoundTcpSocketInner, BoundUdpSocket, BoundUdpSocketInner, TcpSocketInfo,
+        UdpSocketInfo, UnboundPingSocket, UnboundRawIpSocket, UnboundTcpSocket, UnboundUdpSocket,
     },
 };
 
+/// The used ports and the number of sockets that use each port.
+type UsedPorts = SpinLock<BTreeMap<u16, usize>, PreemptDisabled>;
+
 pub struct IfaceCommon<E> {
     interface: SpinLock<smoltcp::iface::Interface, LocalIrqDisabled>,
-    used_ports: SpinLock<BTreeMap<u16, usize>, PreemptDisabled>,
+    used_ports: UsedPorts,
+    /// The identifiers used by ping sockets, which are independent of TCP/UDP ports.
+    used_ping_idents: UsedPorts,
     tcp_sockets: SpinLock<BTreeSet<KeyableArc<BoundTcpSocketInner<E>>>, LocalIrqDisabled>,
     udp_sockets: SpinLock<BTreeSet<KeyableArc<BoundUdpSocketInner<E>>>, LocalIrqDisabled>,
+    ping_sockets: SpinLock<BTreeSet<KeyableArc<BoundPingSocketInner<E>>>, LocalIrqDisabled>,
+    raw_ip_sockets: SpinLock<BTreeSet<KeyableArc<BoundRawIpSocketInner<E>>>, LocalIrqDisabled>,
+    filter: PacketFilter,
+    stats: IfaceStats,
     ext: E,
 }
 
@@ -44,8 +57,13 @@ impl<E> IfaceCommon<E> {
         Self {
             interface: SpinLock::new(interface),
             used_ports: SpinLock::new(BTreeMap::new()),
+            used_ping_idents: SpinLock::new(BTreeMap::new()),
             tcp_sockets: SpinLock::new(BTreeSet::new()),
             udp_sockets: SpinLock::new(BTreeSet::new()),
+            ping_sockets: SpinLock::new(BTreeSet::new()),
+            raw_ip_sockets: SpinLock::new(BTreeSet::new()),
+            filter: PacketFilter::new(),
+            stats: IfaceStats::default(),
             ext,
         }
     }
@@ -54,9 +72,97 @@ impl<E> IfaceCommon<E> {
         self.interface.lock().ipv4_addr()
     }
 
+    pub(super) fn ipv6_addr(&self) -> Option<Ipv6Address> {
+        self.interface.lock().ipv6_addr()
+    }
+
+    pub(super) fn ip_cidr<T>(&self, filter: impl FnMut(IpCidr) -> Option<T>) -> Option<T> {
+        self.interface
+            .lock()
+            .ip_addrs()
+            .iter()
+            .copied()
+            .find_map(filter)
+    }
+
+    pub(super) fn set_ip_cidr(&self, cidr: IpCidr) {
+        let version = cidr.address().version();
+
+        self.interface.lock().update_ip_addrs(|ip_addrs| {
+            if let Some(old_cidr) = ip_addrs
+                .iter_mut()
+                .find(|old_cidr| old_cidr.address().version() == version)
+            {
+                *old_cidr = cidr;
+            } else {
+                // There is at most one address per address family, so there is always room for
+                // the new address.
+                ip_addrs.push(cidr).unwrap();
+            }
+        });
+    }
+
+    pub(super) fn add_route(&self, cidr: IpCidr, gateway: IpAddress) -> Result<(), RouteTableFull> {
+        let mut result = Ok(());
+
+        self.interface.lock().routes_mut().update(|routes| {
+            if let Some(route) = routes.iter_mut().find(|route| route.cidr == cidr) {
+                route.via_router = gateway;
+                return;
+            }
+
+            let route = Route {
+                cidr,
+                via_router: gateway,
+                preferred_until: None,
+                expires_at: None,
+            };
+            result = routes.push(route).map_err(|_| RouteTableFull);
+        });
+
+        result
+    }
+
+    pub(super) fn remove_route(&self, cidr: &IpCidr) -> bool {
+        let mut removed = false;
+
+        self.interface.lock().routes_mut().update(|routes| {
+            let old_len = routes.len();
+            routes.retain(|route| route.cidr != *cidr);
+            removed = routes.len() != old_len;
+        });
+
+        removed
+    }
+
     pub(super) fn ext(&self) -> &E {
         &self.ext
     }
+
+    pub(crate) fn filter(&self) -> &PacketFilter {
+        &self.filter
+    }
+
+    pub(crate) fn stats(&self) -> &IfaceStats {
+        &self.stats
+    }
+
+    pub(super) fn tcp_socket_infos(&self) -> Vec<TcpSocketInfo> {
+        self.tcp_sockets
+            .lock()
+            .iter()
+            .filter(|socket| !socket.is_dead())
+            .map(|socket| socket.info())
+            .collect()
+    }
+
+    pub(super) fn udp_socket_infos(&self) -> Vec<UdpSocketInfo> {
+        self.udp_sockets
+            .lock()
+            .iter()
+            .map(|socket| socket.info())
+            .collect()
+    }
 }
 
 impl<E> IfaceCommon<E> {
@@ -74,15 +180,16 @@ impl<E> IfaceCommon<E> {
         &self,
         iface: Arc<dyn Iface<E>>,
         socket: Box<UnboundTcpSocket>,
+        local_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<BoundTcpSocket<E>, (BindError, Box<UnboundTcpSocket>)> {
-        let port = match self.bind_port(config) {
+        let port = match bind_port(&self.used_ports, config) {
             Ok(port) => port,
             Err(err) => return Err((err, socket)),
         };
 
         let (raw_socket, observer) = socket.into_raw();
-        let bound_socket = BoundTcpSocket::new(iface, port, raw_socket, observer);
+        let bound_socket = BoundTcpSocket::new(iface, local_addr, port, raw_socket, observer);
 
         let inserted = self
             .tcp_sockets
@@ -97,15 +204,16 @@ impl<E> IfaceCommon<E> {
         &self,
         iface: Arc<dyn Iface<E>>,
         socket: Box<UnboundUdpSocket>,
+        local_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<BoundUdpSocket<E>, (BindError, Box<UnboundUdpSocket>)> {
-        let port = match self.bind_port(config) {
+        let port = match bind_port(&self.used_ports, config) {
             Ok(port) => port,
             Err(err) => return Err((err, socket)),
         };
 
         let (raw_socket, observer) = socket.into_raw();
-        let bound_socket = BoundUdpSocket::new(iface, port, raw_socket, observer);
+        let bound_socket = BoundUdpSocket::new(iface, local_addr, port, raw_socket, observer);
 
         let inserted = self
             .udp_sockets
@@ -116,46 +224,107 @@ impl<E> IfaceCommon<E> {
         Ok(bound_socket)
     }
 
-    /// Allocates an unused ephemeral port.
+    /// Binds a ping socket.
     ///
-    /// We follow the port range that many Linux kernels use by default, which is 32768-60999.
+    /// The port of a ping socket is the identifier of the ICMP echo messages that it sends.
+    pub(super) fn bind_ping(
+        &self,
+        iface: Arc<dyn Iface<E>>,
+        socket: Box<UnboundPingSocket>,
+        local_addr: IpAddress,
+        config: BindPortConfig,
+    ) -> core::result::Result<BoundPingSocket<E>, (BindError, Box<UnboundPingSocket>)> {
+        let ident = match bind_port(&self.used_ping_idents, config) {
+            Ok(ident) => ident,
+            Err(err) => return Err((err, socket)),
+        };
+
+        let (raw_socket, observer) = socket.into_raw();
+        let bound_socket = BoundPingSocket::new(iface, local_addr, ident, raw_socket, observer);
+
+        let inserted = self
+            .ping_sockets
+            .lock()
+            .insert(KeyableArc::from(bound_socket.inner().clone()));
+        assert!(inserted);
+
+        Ok(bound_socket)
+    }
+
+    /// Binds a raw IP socket.
     ///
-    /// See <https://en.wikipedia.org/wiki/Ephemeral_port>.
-    fn alloc_ephemeral_port(&self) -> Option<u16> {
-        let mut used_ports = self.used_ports.lock();
-        for port in IP_LOCAL_PORT_START..=IP_LOCAL_PORT_END {
-            if let Entry::Vacant(e) = used_ports.entry(port) {
-                e.insert(0);
-                return Some(port);
-            }
+    /// The port of a raw IP socket is the protocol of the IP packets that it sends and receives.
+    /// Raw IP sockets do not occupy any ports, so binding them never fails.
+    pub(super) fn bind_raw_ip(
+        &self,
+        iface: Arc<dyn Iface<E>>,
+        socket: Box<UnboundRawIpSocket>,
+        local_addr: IpAddress,
+        protocol: u8,
+    ) -> BoundRawIpSocket<E> {
+        let (raw_socket, observer) = socket.into_raw();
+        let bound_socket =
+            BoundRawIpSocket::new(iface, local_addr, protocol as u16, raw_socket, observer);
+
+        let inserted = self
+            .raw_ip_sockets
+            .lock()
+            .insert(KeyableArc::from(bound_socket.inner().clone()));
+        assert!(inserted);
+
+        bound_socket
+    }
+}
+
+/// Allocates an unused ephemeral port.
+///
+/// We follow the port range that many Linux kernels use by default, which is 32768-60999.
+///
+/// See <https://en.wikipedia.org/wiki/Ephemeral_port>.
+fn alloc_ephemeral_port(used_ports: &UsedPorts) -> Option<u16> {
+    let mut used_ports = used_ports.lock();
+    for port in IP_LOCAL_PORT_START..=IP_LOCAL_PORT_END {
+        if let Entry::Vacant(e) = used_ports.entry(port) {
+            e.insert(0);
+            return Some(port);
         }
-        None
     }
+    None
+}
 
-    fn bind_port(&self, config: BindPortConfig) -> Result<u16, BindError> {
-        let port = if let Some(port) = config.port() {
-            port
-        } else {
-            match self.alloc_ephemeral_port() {
-                Some(port) => port,
-                None => return Err(BindError::Exhausted),
-            }
-        };
+fn bind_port(used_ports: &UsedPorts, config: BindPortConfig) -> Result<u16, BindError> {
+    let port = if let Some(port) = config.port() {
+        port
+    } else {
+        match alloc_ephemeral_port(used_ports) {
+            Some(port) => port,
+            None => return Err(BindError::Exhausted),
+        }
+    };
 
-        let mut used_ports = self.used_ports.lock();
+    let mut used_ports = used_ports.lock();
 
-        if let Some(used_times) = used_ports.get_mut(&port) {
-            if *used_times == 0 || config.can_reuse() {
-                // FIXME: Check if the previous socket was bound with SO_REUSEADDR.
-                *used_times += 1;
-            } else {
-                return Err(BindError::InUse);
-            }
+    if let Some(used_times) = used_ports.get_mut(&port) {
+        if *used_times == 0 || config.can_reuse() {
+            // FIXME: Check if the previous socket was bound with SO_REUSEADDR.
+            *used_times += 1;
         } else {
-            used_ports.insert(port, 1);
+            return Err(BindError::InUse);
         }
+    } else {
+        used_ports.insert(port, 1);
+    }
+
+    Ok(port)
+}
 
-        Ok(port)
+/// Releases the port so that it can be used again (if it is not being reused).
+fn release_port(used_ports: &UsedPorts, port: u16) {
+    let mut used_ports = used_ports.lock();
+    if let Some(used_times) = used_ports.remove(&port) {
+        if used_times != 1 {
+            used_ports.insert(port, used_times - 1);
+        }
     }
 }
 
@@ -164,7 +333,7 @@ impl<E> IfaceCommon<E> {
     fn remove_dead_tcp_sockets(&self, sockets: &mut BTreeSet<KeyableArc<BoundTcpSocketInner<E>>>) {
         sockets.retain(|socket| {
             if socket.is_dead() {
-                self.release_port(socket.port());
+                release_port(&self.used_ports, socket.port());
                 false
             } else {
                 true
@@ -178,17 +347,23 @@ impl<E> IfaceCommon<E> {
         let removed = self.udp_sockets.lock().remove(&keyable_socket);
         assert!(removed);
 
-        self.release_port(keyable_socket.port());
+        release_port(&self.used_ports, keyable_socket.port());
     }
 
-    /// Releases the port so that it can be used again (if it is not being reused).
-    fn release_port(&self, port: u16) {
-        let mut used_ports = self.used_ports.lock();
-        if let Some(used_times) = used_ports.remove(&port) {
-            if used_times != 1 {
-                used_ports.insert(port, used_times - 1);
-            }
-        }
+    pub(crate) fn remove_ping_socket(&self, socket: &Arc<BoundPingSocketInner<E>>) {
+        let keyable_socket = KeyableArc::from(socket.clone());
+
+        let removed = self.ping_sockets.lock().remove(&keyable_socket);
+        assert!(removed);
+
+        release_port(&self.used_ping_idents, keyable_socket.port());
+    }
+
+    pub(crate) fn remove_raw_ip_socket(&self, socket: &Arc<BoundRawIpSocketInner<E>>) {
+        let keyable_socket = KeyableArc::from(socket.clone());
+
+        let removed = self.raw_ip_sockets.lock().remove(&keyable_socket);
+        assert!(removed);
     }
 }
 
@@ -205,7 +380,7 @@ impl<E> IfaceCommon<E> {
             &'pkt [u8],
             &'cx mut Context,
             D::TxToken<'tx>,
-            Option<(Ipv4Packet<&'pkt [u8]>, D::TxToken<'tx>)>,
+            Option<(IpPacket<'pkt>, D::TxToken<'tx>)>,
         >,
         Q: FnMut(&Packet, &mut Context, D::TxToken<'_>),
     {
@@ -214,8 +389,18 @@ impl<E> IfaceCommon<E> {
 
         let mut tcp_sockets = self.tcp_sockets.lock();
         let udp_sockets = self.udp_sockets.lock();
-
-        let mut context = PollContext::new(interface.context(), &tcp_sockets, &udp_sockets);
+        let ping_sockets = self.ping_sockets.lock();
+        let raw_ip_sockets = self.raw_ip_sockets.lock();
+
+        let mut context = PollContext::new(
+            interface.context(),
+            &tcp_sockets,
+            &udp_sockets,
+            &ping_sockets,
+            &raw_ip_sockets,
+            &self.filter,
+            &self.stats,
+        );
         context.poll_ingress(device, process_phy, &mut dispatch_phy);
         context.poll_egress(device, dispatch_phy);
 
@@ -229,24 +414,28 @@ impl<E> IfaceCommon<E> {
                 socket.on_iface_events();
             }
         });
+        ping_sockets.iter().for_each(|socket| {
+            if socket.has_new_events() {
+                socket.on_iface_events();
+            }
+        });
+        raw_ip_sockets.iter().for_each(|socket| {
+            if socket.has_new_events() {
+                socket.on_iface_events();
+            }
+        });
 
         self.remove_dead_tcp_sockets(&mut tcp_sockets);
 
-        match (
-            tcp_sockets
-                .iter()
-                .map(|socket| socket.next_poll_at_ms())
-                .min(),
-            udp_sockets
-                .iter()
-                .map(|socket| socket.next_poll_at_ms())
-                .min(),
-        ) {
-            (Some(tcp_poll_at), Some(udp_poll_at)) if tcp_poll_at <= udp_poll_at => {
-                Some(tcp_poll_at)
-            }
-            (tcp_poll_at, None) => tcp_poll_at,
-            (_, udp_poll_at) => udp_poll_at,
-        }
+        let tcp_poll_at = tcp_sockets.iter().map(|socket| socket.next_poll_at_ms());
+        let udp_poll_at = udp_sockets.iter().map(|socket| socket.next_poll_at_ms());
+        let ping_poll_at = ping_sockets.iter().map(|socket| socket.next_poll_at_ms());
+        let raw_ip_poll_at = raw_ip_sockets.iter().map(|socket| socket.next_poll_at_ms());
+
+        tcp_poll_at
+            .chain(udp_poll_at)
+            .chain(ping_poll_at)
+            .chain(raw_ip_poll_at)
+            .min()
     }
 }
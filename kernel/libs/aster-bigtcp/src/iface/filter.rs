@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Packet filtering.
+//!
+//! Each iface has a [`PacketFilter`] that holds a chain of [`FilterRule`]s for each
+//! [`FilterHook`]. An IP packet received by the iface is checked against the ingress chain
+//! before it is processed, and an IP packet is checked against the egress chain before it is
+//! transmitted by the iface. The first rule in the chain that matches the packet determines the
+//! verdict. If no rules match, the packet is accepted.
+
+use alloc::{vec, vec::Vec};
+
+use ostd::sync::RwLock;
+use smoltcp::{
+    iface::packet::Packet,
+    phy::DeviceCapabilities,
+    wire::{IpCidr, IpProtocol, IpRepr},
+};
+
+/// The point at which packets are checked by the packet filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterHook {
+    /// Checks the packets received by the iface.
+    Ingress,
+    /// Checks the packets transmitted by the iface.
+    Egress,
+}
+
+/// The verdict of a filter rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterVerdict {
+    /// Lets the packet pass.
+    Accept,
+    /// Discards the packet silently.
+    Drop,
+    /// Discards the packet and replies with an ICMP "port unreachable" message.
+    ///
+    /// At the egress hook, the ICMP message cannot be delivered to the local sender, so this is
+    /// the same as [`FilterVerdict::Drop`].
+    Reject,
+}
+
+/// A filter rule.
+///
+/// A packet matches the rule if it matches all the conditions that are not `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterRule {
+    pub hook: FilterHook,
+    /// The protocol of the IP payload.
+    pub protocol: Option<IpProtocol>,
+    /// The subnet of the source address.
+    pub src_cidr: Option<IpCidr>,
+    /// The subnet of the destination address.
+    pub dst_cidr: Option<IpCidr>,
+    /// The source port.
+    ///
+    /// Only TCP and UDP packets can match the rules that have port conditions.
+    pub src_port: Option<u16>,
+    /// The destination port.
+    ///
+    /// Only TCP and UDP packets can match the rules that have port conditions.
+    pub dst_port: Option<u16>,
+    pub verdict: FilterVerdict,
+}
+
+impl FilterRule {
+    fn matches(&self, ip_repr: &IpRepr, ports: Option<(u16, u16)>) -> bool {
+        if self
+            .protocol
+            .is_some_and(|protocol| protocol != ip_repr.next_header())
+        {
+            return false;
+        }
+
+        if self
+            .src_cidr
+            .is_some_and(|cidr| !cidr.contains_addr(&ip_repr.src_addr()))
+        {
+            return false;
+        }
+        if self
+            .dst_cidr
+            .is_some_and(|cidr| !cidr.contains_addr(&ip_repr.dst_addr()))
+        {
+            return false;
+        }
+
+        if self.src_port.is_none() && self.dst_port.is_none() {
+            return true;
+        }
+        let Some((src_port, dst_port)) = ports else {
+            return false;
+        };
+        self.src_port.map_or(true, |port| port == src_port)
+            && self.dst_port.map_or(true, |port| port == dst_port)
+    }
+}
+
+/// The packet filter of an iface.
+pub struct PacketFilter {
+    rules: RwLock<Vec<FilterRule>>,
+}
+
+impl PacketFilter {
+    pub(crate) fn new() -> Self {
+        Self {
+            rules: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Returns all the rules.
+    ///
+    /// The rules of the same hook are returned in the order in which they are checked.
+    pub fn rules(&self) -> Vec<FilterRule> {
+        self.rules.read().clone()
+    }
+
+    /// Appends a rule to the end of the chain of its hook.
+    pub fn append_rule(&self, rule: FilterRule) {
+        // We may hold the read lock in IRQ handlers, so we must disable IRQs when we get the
+        // write lock.
+        self.rules.write_irq_disabled().push(rule);
+    }
+
+    /// Inserts a rule at the beginning of the chain of its hook.
+    pub fn prepend_rule(&self, rule: FilterRule) {
+        self.rules.write_irq_disabled().insert(0, rule);
+    }
+
+    /// Removes the first rule that is equal to `rule`.
+    ///
+    /// This method returns whether such a rule existed.
+    pub fn remove_rule(&self, rule: &FilterRule) -> bool {
+        let mut rules = self.rules.write_irq_disabled();
+
+        let Some(pos) = rules.iter().position(|old_rule| old_rule == rule) else {
+            return false;
+        };
+        rules.remove(pos);
+        true
+    }
+
+    /// Checks a packet against the chain of `hook`.
+    ///
+    /// The `ports` callback returns the source and destination ports of the packet, if any. It
+    /// is only called if the chain is not empty.
+    pub(crate) fn check<F>(&self, hook: FilterHook, ip_repr: &IpRepr, ports: F) -> FilterVerdict
+    where
+        F: FnOnce() -> Option<(u16, u16)>,
+    {
+        let rules = self.rules.read();
+
+        let mut chain = rules.iter().filter(|rule| rule.hook == hook).peekable();
+        if chain.peek().is_none() {
+            return FilterVerdict::Accept;
+        }
+
+        let ports = ports();
+        chain
+            .find(|rule| rule.matches(ip_repr, ports))
+            .map_or(FilterVerdict::Accept, |rule| rule.verdict)
+    }
+
+    /// Checks an outgoing packet against the chain of [`FilterHook::Egress`].
+    ///
+    /// This method returns whether the packet should be transmitted.
+    pub(crate) fn check_egress(&self, pkt: &Packet, caps: &DeviceCapabilities) -> bool {
+        let ip_repr = pkt.ip_repr();
+
+        let verdict = self.check(FilterHook::Egress, &ip_repr, || {
+            if !matches!(ip_repr.next_header(), IpProtocol::Tcp | IpProtocol::Udp) {
+                return None;
+            }
+
+            // The payload has not been emitted yet, so we have to emit it to find the ports.
+            let mut payload = vec![0; ip_repr.payload_len()];
+            pkt.emit_payload(&ip_repr, &mut payload, caps);
+            transport_ports(ip_repr.next_header(), &payload)
+        });
+
+        verdict == FilterVerdict::Accept
+    }
+}
+
+/// Gets the source and destination ports from the header of a TCP or UDP packet.
+pub(crate) fn transport_ports(protocol: IpProtocol, payload: &[u8]) -> Option<(u16, u16)> {
+    // Both TCP and UDP headers start with the source port and the destination port.
+    match protocol {
+        IpProtocol::Tcp | IpProtocol::Udp if payload.len() >= 4 => Some((
+            u16::from_be_bytes([payload[0], payload[1]]),
+            u16::from_be_bytes([payload[2], payload[3]]),
+        )),
+        _ => None,
+    }
+}
@@ -1,13 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{
+    boxed::Box,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
 
-use smoltcp::wire::Ipv4Address;
+use smoltcp::wire::{
+    HardwareAddress, IpAddress, IpCidr, Ipv4Address, Ipv4Cidr, Ipv6Address, Ipv6Cidr,
+};
 
-use super::port::BindPortConfig;
+use super::{port::BindPortConfig, IfaceStats, PacketFilter, PacketTap};
 use crate::{
-    errors::BindError,
-    socket::{BoundTcpSocket, BoundUdpSocket, UnboundTcpSocket, UnboundUdpSocket},
+    errors::{BindError, InjectError, RouteTableFull},
+    socket::{
+        BoundPingSocket, BoundRawIpSocket, BoundTcpSocket, BoundUdpSocket, TcpSocketInfo,
+        UdpSocketInfo, UnboundPingSocket, UnboundRawIpSocket, UnboundTcpSocket, UnboundUdpSocket,
+    },
 };
 
 /// A network interface.
@@ -23,6 +32,28 @@ pub trait Iface<E>: internal::IfaceInternal<E> + Send + Sync {
     /// performed, or `None` if no next poll is required. It's up to the caller to determine the
     /// mechanism to ensure that the next poll happens at the right time (e.g. by setting a timer).
     fn raw_poll(&self, schedule_next_poll: &dyn Fn(Option<u64>));
+
+    /// Gets the maximum transmission unit (MTU) of the iface.
+    ///
+    /// This is the maximum size of the IP packets that the iface can transmit, excluding the
+    /// link-layer headers.
+    fn mtu(&self) -> usize;
+
+    /// Adds a packet tap that observes the link-layer frames received and transmitted by the
+    /// iface.
+    ///
+    /// The tap is removed automatically after it is dropped. This method returns `false` if the
+    /// iface does not support packet taps. Currently, only Ethernet ifaces support packet taps.
+    fn add_tap(&self, _tap: Weak<dyn PacketTap>) -> bool {
+        false
+    }
+
+    /// Transmits a link-layer frame as is, bypassing the network stack.
+    ///
+    /// Currently, only Ethernet ifaces support injecting frames.
+    fn inject_frame(&self, _frame: &[u8]) -> Result<(), InjectError> {
+        Err(InjectError::Unsupported)
+    }
 }
 
 impl<E> dyn Iface<E> {
@@ -39,25 +70,58 @@ impl<E> dyn Iface<E> {
     /// If [`BindPortConfig::Ephemeral`] is specified, the iface will pick up an ephemeral port for
     /// the socket.
     ///
+    /// The socket is bound to `local_addr`, which should be one of the IP addresses of the iface.
+    /// It determines the address family of the local endpoint of the socket.
+    ///
     /// FIXME: The reason for binding the socket and the iface together is because there are
     /// limitations inside smoltcp. See discussion at
     /// <https://github.com/smoltcp-rs/smoltcp/issues/779>.
     pub fn bind_tcp(
         self: &Arc<Self>,
         socket: Box<UnboundTcpSocket>,
+        local_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<BoundTcpSocket<E>, (BindError, Box<UnboundTcpSocket>)> {
         let common = self.common();
-        common.bind_tcp(self.clone(), socket, config)
+        common.bind_tcp(self.clone(), socket, local_addr, config)
     }
 
     pub fn bind_udp(
         self: &Arc<Self>,
         socket: Box<UnboundUdpSocket>,
+        local_addr: IpAddress,
         config: BindPortConfig,
     ) -> core::result::Result<BoundUdpSocket<E>, (BindError, Box<UnboundUdpSocket>)> {
         let common = self.common();
-        common.bind_udp(self.clone(), socket, config)
+        common.bind_udp(self.clone(), socket, local_addr, config)
+    }
+
+    /// Binds a ping socket to the iface.
+    ///
+    /// The port that the socket is bound to is used as the identifier of the ICMP echo messages.
+    /// Ping sockets have their own identifier space, which is independent of the TCP/UDP ports.
+    pub fn bind_ping(
+        self: &Arc<Self>,
+        socket: Box<UnboundPingSocket>,
+        local_addr: IpAddress,
+        config: BindPortConfig,
+    ) -> core::result::Result<BoundPingSocket<E>, (BindError, Box<UnboundPingSocket>)> {
+        let common = self.common();
+        common.bind_ping(self.clone(), socket, local_addr, config)
+    }
+
+    /// Binds a raw IP socket to the iface.
+    ///
+    /// The socket will send and receive the IP packets of `protocol`, which is also used as the
+    /// port of the socket. Currently, raw IP sockets can only receive IPv4 packets.
+    pub fn bind_raw_ip(
+        self: &Arc<Self>,
+        socket: Box<UnboundRawIpSocket>,
+        local_addr: IpAddress,
+        protocol: u8,
+    ) -> BoundRawIpSocket<E> {
+        let common = self.common();
+        common.bind_raw_ip(self.clone(), socket, local_addr, protocol)
     }
 
     /// Gets the IPv4 address of the iface, if any.
@@ -66,6 +130,80 @@ impl<E> dyn Iface<E> {
     pub fn ipv4_addr(&self) -> Option<Ipv4Address> {
         self.common().ipv4_addr()
     }
+
+    /// Gets the IPv6 address of the iface, if any.
+    ///
+    /// FIXME: One iface may have multiple IPv6 addresses.
+    pub fn ipv6_addr(&self) -> Option<Ipv6Address> {
+        self.common().ipv6_addr()
+    }
+
+    /// Gets the IPv4 address of the iface and the prefix length of its subnet, if any.
+    pub fn ipv4_cidr(&self) -> Option<Ipv4Cidr> {
+        self.common().ip_cidr(|cidr| match cidr {
+            IpCidr::Ipv4(ipv4_cidr) => Some(ipv4_cidr),
+            IpCidr::Ipv6(_) => None,
+        })
+    }
+
+    /// Gets the IPv6 address of the iface and the prefix length of its subnet, if any.
+    pub fn ipv6_cidr(&self) -> Option<Ipv6Cidr> {
+        self.common().ip_cidr(|cidr| match cidr {
+            IpCidr::Ipv4(_) => None,
+            IpCidr::Ipv6(ipv6_cidr) => Some(ipv6_cidr),
+        })
+    }
+
+    /// Sets the IP address of the iface and the prefix length of its subnet.
+    ///
+    /// The existing IP address in the same address family, if any, will be replaced. Sockets that
+    /// have been bound to the old address are not affected, but they will no longer receive
+    /// packets.
+    pub fn set_ip_cidr(&self, cidr: IpCidr) {
+        self.common().set_ip_cidr(cidr)
+    }
+
+    /// Adds a route that forwards the packets to the destinations in `cidr` via `gateway`.
+    ///
+    /// The existing route to the same destinations, if any, will be replaced.
+    ///
+    /// Note that the routes of an iface only determine the next hop of the packets that have
+    /// already been sent through the iface. It's up to the caller to select the iface.
+    pub fn add_route(&self, cidr: IpCidr, gateway: IpAddress) -> Result<(), RouteTableFull> {
+        self.common().add_route(cidr, gateway)
+    }
+
+    /// Removes the route to the destinations in `cidr`.
+    ///
+    /// This method returns whether the route existed.
+    pub fn remove_route(&self, cidr: &IpCidr) -> bool {
+        self.common().remove_route(cidr)
+    }
+
+    /// Gets the hardware address of the iface.
+    pub fn hardware_addr(&self) -> HardwareAddress {
+        self.common().interface().hardware_addr()
+    }
+
+    /// Gets the packet filter of the iface.
+    pub fn filter(&self) -> &PacketFilter {
+        self.common().filter()
+    }
+
+    /// Gets the statistics of the iface.
+    pub fn stats(&self) -> &IfaceStats {
+        self.common().stats()
+    }
+
+    /// Gets the information of the TCP sockets bound to the iface.
+    pub fn tcp_socket_infos(&self) -> Vec<TcpSocketInfo> {
+        self.common().tcp_socket_infos()
+    }
+
+    /// Gets the information of the UDP sockets bound to the iface.
+    pub fn udp_socket_infos(&self) -> Vec<UdpSocketInfo> {
+        self.common().udp_socket_infos()
+    }
 }
 
 pub(super) mod internal {
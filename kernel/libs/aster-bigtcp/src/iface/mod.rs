@@ -1,13 +1,18 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod common;
+mod filter;
 #[allow(clippy::module_inception)]
 mod iface;
 mod phy;
 mod poll;
 mod port;
+mod stats;
 mod time;
 
+pub use filter::{FilterHook, FilterRule, FilterVerdict, PacketFilter};
 pub use iface::Iface;
-pub use phy::{EtherIface, IpIface};
+pub use phy::{EtherIface, IpIface, PacketTap, TapDirection};
 pub use port::BindPortConfig;
+pub use stats::{Counter, IfaceStats};
+pub(crate) use time::get_network_timestamp;
@@ -1,21 +1,31 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{collections::btree_map::BTreeMap, sync::Arc};
+use alloc::{
+    collections::btree_map::BTreeMap,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
 
 use ostd::sync::{LocalIrqDisabled, SpinLock};
 use smoltcp::{
-    iface::{packet::Packet, Config, Context},
-    phy::{DeviceCapabilities, TxToken},
+    iface::{
+        packet::{IpPayload, Packet},
+        Config, Context,
+    },
+    phy::{Device, DeviceCapabilities, TxToken},
     wire::{
         self, ArpOperation, ArpPacket, ArpRepr, EthernetAddress, EthernetFrame, EthernetProtocol,
-        EthernetRepr, IpAddress, Ipv4Address, Ipv4Cidr, Ipv4Packet,
+        EthernetRepr, Icmpv6Packet, Icmpv6Repr, IpAddress, IpProtocol, Ipv4Address, Ipv4Packet,
+        Ipv6Address, Ipv6Packet, Ipv6Repr, NdiscNeighborFlags, NdiscRepr, RawHardwareAddress,
     },
 };
 
 use crate::{
     device::WithDevice,
+    errors::InjectError,
     iface::{
-        common::IfaceCommon, iface::internal::IfaceInternal, time::get_network_timestamp, Iface,
+        common::IfaceCommon, iface::internal::IfaceInternal, poll::IpPacket,
+        time::get_network_timestamp, Iface,
     },
 };
 
@@ -23,31 +33,50 @@ pub struct EtherIface<D, E> {
     driver: D,
     common: IfaceCommon<E>,
     ether_addr: EthernetAddress,
-    arp_table: SpinLock<BTreeMap<Ipv4Address, EthernetAddress>, LocalIrqDisabled>,
+    neighbor_table: SpinLock<BTreeMap<IpAddress, EthernetAddress>, LocalIrqDisabled>,
+    taps: SpinLock<Vec<Weak<dyn PacketTap>>, LocalIrqDisabled>,
+}
+
+/// The direction of a frame observed by a [`PacketTap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapDirection {
+    /// The frame is received by the iface.
+    Incoming,
+    /// The frame is transmitted by the iface.
+    Outgoing,
+}
+
+/// A packet tap, which observes the Ethernet frames received and transmitted by an iface.
+///
+/// Packet taps are used to implement packet sockets (i.e., `AF_PACKET` sockets) for network
+/// diagnostics. A tap sees every frame that passes the iface, including the frames that are not
+/// destined to the iface and the frames that are dropped later.
+pub trait PacketTap: Send + Sync {
+    /// Called with each frame that is received or transmitted by the iface.
+    ///
+    /// This method is called with the device locked, so it must not call back into the iface.
+    fn on_frame(&self, frame: &[u8], direction: TapDirection);
+}
+
+/// A packet that resolves the Ethernet addresses of the neighbors.
+enum NeighborPacket {
+    /// An ARP packet, which is used by IPv4.
+    Arp(ArpRepr),
+    /// An NDISC packet, which is used by IPv6, and its destination Ethernet address.
+    Ndisc(Ipv6Repr, NdiscRepr<'static>, EthernetAddress),
 }
 
 impl<D: WithDevice, E> EtherIface<D, E> {
-    pub fn new(
-        driver: D,
-        ether_addr: EthernetAddress,
-        ip_cidr: Ipv4Cidr,
-        gateway: Ipv4Address,
-        ext: E,
-    ) -> Arc<Self> {
+    /// Creates a new Ethernet iface.
+    ///
+    /// The iface has no IP addresses and no routes initially. They should be configured later
+    /// via `set_ip_cidr` and `add_route`.
+    pub fn new(driver: D, ether_addr: EthernetAddress, ext: E) -> Arc<Self> {
         let interface = driver.with(|device| {
             let config = Config::new(wire::HardwareAddress::Ethernet(ether_addr));
             let now = get_network_timestamp();
 
-            let mut interface = smoltcp::iface::Interface::new(config, device, now);
-            interface.update_ip_addrs(|ip_addrs| {
-                debug_assert!(ip_addrs.is_empty());
-                ip_addrs.push(wire::IpCidr::Ipv4(ip_cidr)).unwrap();
-            });
-            interface
-                .routes_mut()
-                .add_default_ipv4_route(gateway)
-                .unwrap();
-            interface
+            smoltcp::iface::Interface::new(config, device, now)
         });
 
         let common = IfaceCommon::new(interface, ext);
@@ -56,7 +85,8 @@ impl<D: WithDevice, E> EtherIface<D, E> {
             driver,
             common,
             ether_addr,
-            arp_table: SpinLock::new(BTreeMap::new()),
+            neighbor_table: SpinLock::new(BTreeMap::new()),
+            taps: SpinLock::new(Vec::new()),
         })
     }
 }
@@ -78,48 +108,118 @@ impl<D: WithDevice + 'static, E: Send + Sync> Iface<E> for EtherIface<D, E> {
             schedule_next_poll(next_poll);
         });
     }
+
+    fn mtu(&self) -> usize {
+        self.driver.with(|device| device.capabilities().ip_mtu())
+    }
+
+    fn add_tap(&self, tap: Weak<dyn PacketTap>) -> bool {
+        self.taps.lock().push(tap);
+        true
+    }
+
+    fn inject_frame(&self, frame: &[u8]) -> Result<(), InjectError> {
+        self.driver.with(|device| {
+            // For Ethernet devices, the MTU includes the Ethernet header.
+            if frame.len() > device.capabilities().max_transmission_unit {
+                return Err(InjectError::TooLarge);
+            }
+
+            let Some(tx_token) = device.transmit(get_network_timestamp()) else {
+                return Err(InjectError::BufferFull);
+            };
+            tx_token.consume(frame.len(), |buffer| buffer.copy_from_slice(frame));
+            self.observe_frame(frame, TapDirection::Outgoing);
+
+            Ok(())
+        })
+    }
 }
 
 impl<D, E> EtherIface<D, E> {
+    /// Counts the frame in the statistics and passes it to all packet taps.
+    ///
+    /// The packet taps that have been dropped are removed.
+    fn observe_frame(&self, frame: &[u8], direction: TapDirection) {
+        let stats = self.common.stats();
+        match direction {
+            TapDirection::Incoming => stats.count_rx(frame.len()),
+            TapDirection::Outgoing => stats.count_tx(frame.len()),
+        }
+
+        self.taps.lock().retain(|tap| {
+            let Some(tap) = tap.upgrade() else {
+                return false;
+            };
+            tap.on_frame(frame, direction);
+            true
+        });
+    }
+
     fn process<'pkt, T: TxToken>(
         &self,
         data: &'pkt [u8],
         iface_cx: &mut Context,
         tx_token: T,
-    ) -> Option<(Ipv4Packet<&'pkt [u8]>, T)> {
-        match self.parse_ip_or_process_arp(data, iface_cx) {
+    ) -> Option<(IpPacket<'pkt>, T)> {
+        self.observe_frame(data, TapDirection::Incoming);
+
+        match self.parse_ip_or_process_neighbor(data, iface_cx) {
             Ok(pkt) => Some((pkt, tx_token)),
-            Err(Some(arp)) => {
-                Self::emit_arp(&arp, tx_token);
+            Err(Some(neighbor)) => {
+                self.emit_neighbor(&neighbor, &iface_cx.caps, tx_token);
                 None
             }
             Err(None) => None,
         }
     }
 
-    fn parse_ip_or_process_arp<'pkt>(
+    fn parse_ip_or_process_neighbor<'pkt>(
         &self,
         data: &'pkt [u8],
         iface_cx: &mut Context,
-    ) -> Result<Ipv4Packet<&'pkt [u8]>, Option<ArpRepr>> {
+    ) -> Result<IpPacket<'pkt>, Option<NeighborPacket>> {
         // Parse the Ethernet header. Ignore the packet if the header is ill-formed.
         let frame = EthernetFrame::new_checked(data).map_err(|_| None)?;
         let repr = EthernetRepr::parse(&frame).map_err(|_| None)?;
 
-        // Ignore the Ethernet frame if it is not sent to us.
-        if !repr.dst_addr.is_broadcast() && repr.dst_addr != self.ether_addr {
+        // Ignore the Ethernet frame if it is not sent to us. Note that multicast frames are
+        // accepted because IPv6 neighbor discovery relies on them.
+        if !repr.dst_addr.is_multicast() && repr.dst_addr != self.ether_addr {
             return Err(None);
         }
 
         // Ignore the Ethernet frame if the protocol is not supported.
         match repr.ethertype {
-            EthernetProtocol::Ipv4 => {
-                Ok(Ipv4Packet::new_checked(frame.payload()).map_err(|_| None)?)
+            EthernetProtocol::Ipv4 => Ok(IpPacket::Ipv4(
+                Ipv4Packet::new_checked(frame.payload()).map_err(|_| None)?,
+            )),
+            EthernetProtocol::Ipv6 => {
+                let pkt = Ipv6Packet::new_checked(frame.payload()).map_err(|_| None)?;
+                let ipv6_repr = Ipv6Repr::parse(&pkt).map_err(|_| None)?;
+
+                // NDISC packets are ICMPv6 packets, but they are handled here since they are
+                // used to resolve Ethernet addresses, just like ARP packets.
+                if ipv6_repr.next_header == IpProtocol::Icmpv6 {
+                    let icmp_pkt = Icmpv6Packet::new_checked(pkt.payload()).map_err(|_| None)?;
+                    let icmp_repr = Icmpv6Repr::parse(
+                        &ipv6_repr.src_addr,
+                        &ipv6_repr.dst_addr,
+                        &icmp_pkt,
+                        &iface_cx.checksum_caps(),
+                    )
+                    .map_err(|_| None)?;
+                    if let Icmpv6Repr::Ndisc(ndisc_repr) = icmp_repr {
+                        return Err(self.process_ndisc(&ipv6_repr, &ndisc_repr, iface_cx));
+                    }
+                }
+
+                Ok(IpPacket::Ipv6(pkt))
             }
             EthernetProtocol::Arp => {
                 let pkt = ArpPacket::new_checked(frame.payload()).map_err(|_| None)?;
                 let arp = ArpRepr::parse(&pkt).map_err(|_| None)?;
-                Err(self.process_arp(&arp, iface_cx))
+                Err(self.process_arp(&arp, iface_cx).map(NeighborPacket::Arp))
             }
             _ => Err(None),
         }
@@ -143,9 +243,10 @@ impl<D, E> EtherIface<D, E> {
                 // Insert the mapping between the Ethernet address and the IP address.
                 //
                 // TODO: Remove the mapping if it expires.
-                self.arp_table
-                    .lock()
-                    .insert(*source_protocol_addr, *source_hardware_addr);
+                self.neighbor_table.lock().insert(
+                    IpAddress::Ipv4(*source_protocol_addr),
+                    *source_hardware_addr,
+                );
 
                 None
             }
@@ -181,52 +282,186 @@ impl<D, E> EtherIface<D, E> {
         }
     }
 
+    fn process_ndisc(
+        &self,
+        ipv6_repr: &Ipv6Repr,
+        ndisc_repr: &NdiscRepr,
+        iface_cx: &mut Context,
+    ) -> Option<NeighborPacket> {
+        // Ignore the NDISC packet if it may have been forwarded by a router. See
+        // <https://datatracker.ietf.org/doc/html/rfc4861#section-7.1.1>.
+        if ipv6_repr.hop_limit != 255 {
+            return None;
+        }
+
+        match ndisc_repr {
+            NdiscRepr::NeighborAdvert {
+                target_addr,
+                lladdr,
+                ..
+            } => {
+                // Ignore the NDISC packet if the target addresses are not unicast or not local.
+                let target_ether = lladdr.and_then(parse_unicast_ether_addr)?;
+                if !target_addr.is_unicast()
+                    || !iface_cx.in_same_network(&IpAddress::Ipv6(*target_addr))
+                {
+                    return None;
+                }
+
+                // Insert the mapping between the Ethernet address and the IP address.
+                //
+                // TODO: Remove the mapping if it expires.
+                self.neighbor_table
+                    .lock()
+                    .insert(IpAddress::Ipv6(*target_addr), target_ether);
+
+                None
+            }
+            NdiscRepr::NeighborSolicit {
+                target_addr,
+                lladdr,
+            } => {
+                // Ignore the NDISC packet if the source addresses are not unicast.
+                //
+                // TODO: Support duplicate address detection, where the source IP address is
+                // unspecified.
+                let source_ether = lladdr.and_then(parse_unicast_ether_addr)?;
+                if !ipv6_repr.src_addr.is_unicast() {
+                    return None;
+                }
+
+                // Ignore the NDISC packet if we do not own the target address.
+                if !iface_cx.has_ip_addr(*target_addr) {
+                    return None;
+                }
+
+                // The solicitation contains the Ethernet address of the source. Remember it since
+                // we are going to reply to the source.
+                self.neighbor_table
+                    .lock()
+                    .insert(IpAddress::Ipv6(ipv6_repr.src_addr), source_ether);
+
+                let advert_repr = NdiscRepr::NeighborAdvert {
+                    flags: NdiscNeighborFlags::SOLICITED | NdiscNeighborFlags::OVERRIDE,
+                    target_addr: *target_addr,
+                    lladdr: Some(RawHardwareAddress::from_bytes(self.ether_addr.as_bytes())),
+                };
+                let ipv6_repr = Ipv6Repr {
+                    src_addr: *target_addr,
+                    dst_addr: ipv6_repr.src_addr,
+                    next_header: IpProtocol::Icmpv6,
+                    payload_len: Icmpv6Repr::Ndisc(advert_repr).buffer_len(),
+                    hop_limit: 255,
+                };
+
+                Some(NeighborPacket::Ndisc(ipv6_repr, advert_repr, source_ether))
+            }
+            _ => None,
+        }
+    }
+
     fn dispatch<T: TxToken>(&self, pkt: &Packet, iface_cx: &mut Context, tx_token: T) {
-        match self.resolve_ether_or_generate_arp(pkt, iface_cx) {
-            Ok(ether) => Self::emit_ip(&ether, pkt, &iface_cx.caps, tx_token),
-            Err(Some(arp)) => Self::emit_arp(&arp, tx_token),
+        if !self.common.filter().check_egress(pkt, &iface_cx.caps) {
+            return;
+        }
+        self.common
+            .stats()
+            .count_ip_out(pkt.ip_repr().next_header());
+
+        match self.resolve_ether_or_generate_neighbor(pkt, iface_cx) {
+            Ok(ether) => self.emit_ip(&ether, pkt, &iface_cx.caps, tx_token),
+            Err(Some(neighbor)) => self.emit_neighbor(&neighbor, &iface_cx.caps, tx_token),
             Err(None) => (),
         }
     }
 
-    fn resolve_ether_or_generate_arp(
+    fn resolve_ether_or_generate_neighbor(
         &self,
         pkt: &Packet,
         iface_cx: &mut Context,
-    ) -> Result<EthernetRepr, Option<ArpRepr>> {
-        // Resolve the next-hop IP address.
-        let next_hop_ip = match iface_cx.route(&pkt.ip_repr().dst_addr(), iface_cx.now()) {
-            Some(IpAddress::Ipv4(next_hop_ip)) => next_hop_ip,
-            None => return Err(None),
+    ) -> Result<EthernetRepr, Option<NeighborPacket>> {
+        let dst_addr = pkt.ip_repr().dst_addr();
+
+        // Resolve the next-hop IP address. IPv6 multicast packets are sent to the link directly.
+        let next_hop_ip = match dst_addr {
+            IpAddress::Ipv6(dst_addr) if dst_addr.is_multicast() => IpAddress::Ipv6(dst_addr),
+            _ => match iface_cx.route(&dst_addr, iface_cx.now()) {
+                Some(next_hop_ip) => next_hop_ip,
+                None => return Err(None),
+            },
         };
 
         // Resolve the next-hop Ethernet address.
-        let next_hop_ether = if next_hop_ip.is_broadcast() {
-            EthernetAddress::BROADCAST
-        } else if let Some(next_hop_ether) = self.arp_table.lock().get(&next_hop_ip) {
-            *next_hop_ether
-        } else {
-            // If the next-hop Ethernet address cannot be resolved, we drop the original packet and
-            // send an ARP packet instead. The upper layer should be responsible for detecting the
-            // packet loss and retrying later to see if the Ethernet address is ready.
-            return Err(Some(ArpRepr::EthernetIpv4 {
-                operation: ArpOperation::Request,
-                source_hardware_addr: self.ether_addr,
-                source_protocol_addr: iface_cx.ipv4_addr().unwrap_or(Ipv4Address::UNSPECIFIED),
-                target_hardware_addr: EthernetAddress::BROADCAST,
-                target_protocol_addr: next_hop_ip,
-            }));
+        let next_hop_ether = match next_hop_ip {
+            IpAddress::Ipv4(next_hop_ip) if next_hop_ip.is_broadcast() => {
+                EthernetAddress::BROADCAST
+            }
+            IpAddress::Ipv6(next_hop_ip) if next_hop_ip.is_multicast() => {
+                ipv6_multicast_ether_addr(&next_hop_ip)
+            }
+            _ => match self.neighbor_table.lock().get(&next_hop_ip) {
+                Some(next_hop_ether) => *next_hop_ether,
+                // If the next-hop Ethernet address cannot be resolved, we drop the original packet
+                // and send an ARP or NDISC packet instead. The upper layer should be responsible
+                // for detecting the packet loss and retrying later to see if the Ethernet address
+                // is ready.
+                None => return Err(Some(self.generate_neighbor_request(next_hop_ip, iface_cx))),
+            },
+        };
+
+        let ethertype = match dst_addr {
+            IpAddress::Ipv4(_) => EthernetProtocol::Ipv4,
+            IpAddress::Ipv6(_) => EthernetProtocol::Ipv6,
         };
 
         Ok(EthernetRepr {
             src_addr: self.ether_addr,
             dst_addr: next_hop_ether,
-            ethertype: EthernetProtocol::Ipv4,
+            ethertype,
         })
     }
 
+    fn generate_neighbor_request(
+        &self,
+        next_hop_ip: IpAddress,
+        iface_cx: &mut Context,
+    ) -> NeighborPacket {
+        match next_hop_ip {
+            IpAddress::Ipv4(next_hop_ip) => NeighborPacket::Arp(ArpRepr::EthernetIpv4 {
+                operation: ArpOperation::Request,
+                source_hardware_addr: self.ether_addr,
+                source_protocol_addr: iface_cx.ipv4_addr().unwrap_or(Ipv4Address::UNSPECIFIED),
+                target_hardware_addr: EthernetAddress::BROADCAST,
+                target_protocol_addr: next_hop_ip,
+            }),
+            IpAddress::Ipv6(next_hop_ip) => {
+                // The solicitation is sent to the solicited-node multicast address of the target.
+                // See <https://datatracker.ietf.org/doc/html/rfc4861#section-7.2.2>.
+                let solicit_repr = NdiscRepr::NeighborSolicit {
+                    target_addr: next_hop_ip,
+                    lladdr: Some(RawHardwareAddress::from_bytes(self.ether_addr.as_bytes())),
+                };
+                let dst_addr = next_hop_ip.solicited_node();
+                let ipv6_repr = Ipv6Repr {
+                    src_addr: iface_cx.ipv6_addr().unwrap_or(Ipv6Address::UNSPECIFIED),
+                    dst_addr,
+                    next_header: IpProtocol::Icmpv6,
+                    payload_len: Icmpv6Repr::Ndisc(solicit_repr).buffer_len(),
+                    hop_limit: 255,
+                };
+
+                NeighborPacket::Ndisc(
+                    ipv6_repr,
+                    solicit_repr,
+                    ipv6_multicast_ether_addr(&dst_addr),
+                )
+            }
+        }
+    }
+
     /// Consumes the token and emits an IP packet.
     fn emit_ip<T: TxToken>(
+        &self,
         ether_repr: &EthernetRepr,
         ip_pkt: &Packet,
         caps: &DeviceCapabilities,
@@ -235,7 +470,7 @@ impl<D, E> EtherIface<D, E> {
         tx_token.consume(
             ether_repr.buffer_len() + ip_pkt.ip_repr().buffer_len(),
             |buffer| {
-                let mut frame = EthernetFrame::new_unchecked(buffer);
+                let mut frame = EthernetFrame::new_unchecked(&mut *buffer);
                 ether_repr.emit(&mut frame);
 
                 let ip_repr = ip_pkt.ip_repr();
@@ -245,12 +480,38 @@ impl<D, E> EtherIface<D, E> {
                     &mut frame.payload_mut()[ip_repr.header_len()..],
                     caps,
                 );
+
+                self.observe_frame(buffer, TapDirection::Outgoing);
             },
         );
     }
 
+    /// Consumes the token and emits an ARP or NDISC packet.
+    fn emit_neighbor<T: TxToken>(
+        &self,
+        neighbor: &NeighborPacket,
+        caps: &DeviceCapabilities,
+        tx_token: T,
+    ) {
+        match neighbor {
+            NeighborPacket::Arp(arp_repr) => self.emit_arp(arp_repr, tx_token),
+            NeighborPacket::Ndisc(ipv6_repr, ndisc_repr, dst_ether) => {
+                let ether_repr = EthernetRepr {
+                    src_addr: self.ether_addr,
+                    dst_addr: *dst_ether,
+                    ethertype: EthernetProtocol::Ipv6,
+                };
+                let ip_pkt = Packet::new_ipv6(
+                    *ipv6_repr,
+                    IpPayload::Icmpv6(Icmpv6Repr::Ndisc(*ndisc_repr)),
+                );
+                self.emit_ip(&ether_repr, &ip_pkt, caps, tx_token);
+            }
+        }
+    }
+
     /// Consumes the token and emits an ARP packet.
-    fn emit_arp<T: TxToken>(arp_repr: &ArpRepr, tx_token: T) {
+    fn emit_arp<T: TxToken>(&self, arp_repr: &ArpRepr, tx_token: T) {
         let ether_repr = match arp_repr {
             ArpRepr::EthernetIpv4 {
                 source_hardware_addr,
@@ -265,11 +526,31 @@ impl<D, E> EtherIface<D, E> {
         };
 
         tx_token.consume(ether_repr.buffer_len() + arp_repr.buffer_len(), |buffer| {
-            let mut frame = EthernetFrame::new_unchecked(buffer);
+            let mut frame = EthernetFrame::new_unchecked(&mut *buffer);
             ether_repr.emit(&mut frame);
 
             let mut pkt = ArpPacket::new_unchecked(frame.payload_mut());
             arp_repr.emit(&mut pkt);
+
+            self.observe_frame(buffer, TapDirection::Outgoing);
         });
     }
 }
+
+/// Maps an IPv6 multicast address to the corresponding Ethernet multicast address.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc2464#section-7>.
+fn ipv6_multicast_ether_addr(addr: &Ipv6Address) -> EthernetAddress {
+    let bytes = addr.as_bytes();
+    EthernetAddress([0x33, 0x33, bytes[12], bytes[13], bytes[14], bytes[15]])
+}
+
+/// Parses the link-layer address in an NDISC option as a unicast Ethernet address.
+fn parse_unicast_ether_addr(lladdr: RawHardwareAddress) -> Option<EthernetAddress> {
+    if lladdr.len() != 6 {
+        return None;
+    }
+
+    let ether_addr = EthernetAddress::from_bytes(lladdr.as_bytes());
+    ether_addr.is_unicast().then_some(ether_addr)
+}
@@ -4,14 +4,15 @@ use alloc::sync::Arc;
 
 use smoltcp::{
     iface::Config,
-    phy::TxToken,
-    wire::{self, Ipv4Cidr, Ipv4Packet},
+    phy::{Device, TxToken},
+    wire::{self, Ipv4Cidr, Ipv6Cidr},
 };
 
 use crate::{
     device::WithDevice,
     iface::{
-        common::IfaceCommon, iface::internal::IfaceInternal, time::get_network_timestamp, Iface,
+        common::IfaceCommon, iface::internal::IfaceInternal, poll::IpPacket,
+        time::get_network_timestamp, Iface,
     },
 };
 
@@ -21,7 +22,7 @@ pub struct IpIface<D, E> {
 }
 
 impl<D: WithDevice, E> IpIface<D, E> {
-    pub fn new(driver: D, ip_cidr: Ipv4Cidr, ext: E) -> Arc<Self> {
+    pub fn new(driver: D, ip_cidr: Ipv4Cidr, ipv6_cidr: Ipv6Cidr, ext: E) -> Arc<Self> {
         let interface = driver.with(|device| {
             let config = Config::new(smoltcp::wire::HardwareAddress::Ip);
             let now = get_network_timestamp();
@@ -30,6 +31,7 @@ impl<D: WithDevice, E> IpIface<D, E> {
             interface.update_ip_addrs(|ip_addrs| {
                 debug_assert!(ip_addrs.is_empty());
                 ip_addrs.push(wire::IpCidr::Ipv4(ip_cidr)).unwrap();
+                ip_addrs.push(wire::IpCidr::Ipv6(ipv6_cidr)).unwrap();
             });
             interface
         });
@@ -51,9 +53,18 @@ impl<D: WithDevice + 'static, E: Send + Sync> Iface<E> for IpIface<D, E> {
         self.driver.with(|device| {
             let next_poll = self.common.poll(
                 device,
-                |data, _iface_cx, tx_token| Some((Ipv4Packet::new_checked(data).ok()?, tx_token)),
+                |data, _iface_cx, tx_token| {
+                    self.common.stats().count_rx(data.len());
+                    Some((IpPacket::new_checked(data)?, tx_token))
+                },
                 |pkt, iface_cx, tx_token| {
+                    if !self.common.filter().check_egress(pkt, &iface_cx.caps) {
+                        return;
+                    }
+
                     let ip_repr = pkt.ip_repr();
+                    self.common.stats().count_ip_out(ip_repr.next_header());
+                    self.common.stats().count_tx(ip_repr.buffer_len());
                     tx_token.consume(ip_repr.buffer_len(), |buffer| {
                         ip_repr.emit(&mut buffer[..], &iface_cx.checksum_caps());
                         pkt.emit_payload(
@@ -67,4 +78,8 @@ impl<D: WithDevice + 'static, E: Send + Sync> Iface<E> for IpIface<D, E> {
             schedule_next_poll(next_poll);
         });
     }
+
+    fn mtu(&self) -> usize {
+        self.driver.with(|device| device.capabilities().ip_mtu())
+    }
 }
@@ -10,18 +10,30 @@ use smoltcp::{
     },
     phy::{ChecksumCapabilities, Device, RxToken, TxToken},
     wire::{
-        Icmpv4DstUnreachable, Icmpv4Repr, IpAddress, IpProtocol, IpRepr, Ipv4Address, Ipv4Packet,
-        Ipv4Repr, TcpControl, TcpPacket, TcpRepr, UdpPacket, UdpRepr, IPV4_HEADER_LEN,
-        IPV4_MIN_MTU,
+        Icmpv4DstUnreachable, Icmpv4Packet, Icmpv4Repr, Icmpv6DstUnreachable, Icmpv6Packet,
+        Icmpv6Repr, IpAddress, IpEndpoint, IpProtocol, IpRepr, IpVersion, Ipv4Address, Ipv4Packet,
+        Ipv4Repr, Ipv6Address, Ipv6Packet, Ipv6Repr, TcpControl, TcpPacket, TcpRepr, UdpPacket,
+        UdpRepr, IPV4_HEADER_LEN, IPV4_MIN_MTU, IPV6_HEADER_LEN, IPV6_MIN_MTU,
     },
 };
 
-use crate::socket::{BoundTcpSocketInner, BoundUdpSocketInner, TcpProcessResult};
+use super::{
+    filter::{transport_ports, FilterHook, FilterVerdict, PacketFilter},
+    stats::IfaceStats,
+};
+use crate::socket::{
+    BoundPingSocketInner, BoundRawIpSocketInner, BoundTcpSocketInner, BoundUdpSocketInner,
+    EchoRepr, TcpProcessResult,
+};
 
 pub(super) struct PollContext<'a, E> {
     iface_cx: &'a mut Context,
     tcp_sockets: &'a BTreeSet<KeyableArc<BoundTcpSocketInner<E>>>,
     udp_sockets: &'a BTreeSet<KeyableArc<BoundUdpSocketInner<E>>>,
+    ping_sockets: &'a BTreeSet<KeyableArc<BoundPingSocketInner<E>>>,
+    raw_ip_sockets: &'a BTreeSet<KeyableArc<BoundRawIpSocketInner<E>>>,
+    filter: &'a PacketFilter,
+    stats: &'a IfaceStats,
 }
 
 impl<'a, E> PollContext<'a, E> {
@@ -30,11 +42,65 @@ impl<'a, E> PollContext<'a, E> {
         iface_cx: &'a mut Context,
         tcp_sockets: &'a BTreeSet<KeyableArc<BoundTcpSocketInner<E>>>,
         udp_sockets: &'a BTreeSet<KeyableArc<BoundUdpSocketInner<E>>>,
+        ping_sockets: &'a BTreeSet<KeyableArc<BoundPingSocketInner<E>>>,
+        raw_ip_sockets: &'a BTreeSet<KeyableArc<BoundRawIpSocketInner<E>>>,
+        filter: &'a PacketFilter,
+        stats: &'a IfaceStats,
     ) -> Self {
         Self {
             iface_cx,
             tcp_sockets,
             udp_sockets,
+            ping_sockets,
+            raw_ip_sockets,
+            filter,
+            stats,
+        }
+    }
+}
+
+/// An IP packet that is received from the physical layer.
+pub(super) enum IpPacket<'pkt> {
+    Ipv4(Ipv4Packet<&'pkt [u8]>),
+    Ipv6(Ipv6Packet<&'pkt [u8]>),
+}
+
+impl<'pkt> IpPacket<'pkt> {
+    /// Parses the IP packet according to the IP version in the first byte.
+    ///
+    /// This method returns `None` if the packet is ill-formed.
+    pub(super) fn new_checked(data: &'pkt [u8]) -> Option<Self> {
+        match IpVersion::of_packet(data).ok()? {
+            IpVersion::Ipv4 => Some(Self::Ipv4(Ipv4Packet::new_checked(data).ok()?)),
+            IpVersion::Ipv6 => Some(Self::Ipv6(Ipv6Packet::new_checked(data).ok()?)),
+        }
+    }
+}
+
+/// The reason why a destination is unreachable.
+///
+/// This will be converted to [`Icmpv4DstUnreachable`] or [`Icmpv6DstUnreachable`] according to
+/// the IP version of the packet that triggers the ICMP message.
+#[derive(Clone, Copy)]
+enum DstUnreachable {
+    Host,
+    Port,
+}
+
+impl From<DstUnreachable> for Icmpv4DstUnreachable {
+    fn from(value: DstUnreachable) -> Self {
+        match value {
+            DstUnreachable::Host => Self::HostUnreachable,
+            DstUnreachable::Port => Self::PortUnreachable,
+        }
+    }
+}
+
+impl From<DstUnreachable> for Icmpv6DstUnreachable {
+    fn from(value: DstUnreachable) -> Self {
+        match value {
+            DstUnreachable::Host => Self::AddrUnreachable,
+            DstUnreachable::Port => Self::PortUnreachable,
         }
     }
 }
@@ -56,7 +122,7 @@ impl<'a, E> PollContext<'a, E> {
             &'pkt [u8],
             &'cx mut Context,
             D::TxToken<'tx>,
-            Option<(Ipv4Packet<&'pkt [u8]>, D::TxToken<'tx>)>,
+            Option<(IpPacket<'pkt>, D::TxToken<'tx>)>,
         >,
         Q: FnMut(&Packet, &mut Context, D::TxToken<'_>),
     {
@@ -66,7 +132,11 @@ impl<'a, E> PollContext<'a, E> {
                     return;
                 };
 
-                let Some(reply) = self.parse_and_process_ipv4(pkt) else {
+                let reply = match pkt {
+                    IpPacket::Ipv4(pkt) => self.parse_and_process_ipv4(pkt),
+                    IpPacket::Ipv6(pkt) => self.parse_and_process_ipv6(pkt),
+                };
+                let Some(reply) = reply else {
                     return;
                 };
 
@@ -81,15 +151,35 @@ impl<'a, E> PollContext<'a, E> {
     ) -> Option<Packet<'pkt>> {
         // Parse the IP header. Ignore the packet if the header is ill-formed.
         let repr = Ipv4Repr::parse(&pkt, &self.iface_cx.checksum_caps()).ok()?;
+        self.stats.ip_in_receives.inc();
 
         if !repr.dst_addr.is_broadcast() && !self.is_unicast_local(IpAddress::Ipv4(repr.dst_addr)) {
+            self.stats.ip_in_addr_errors.inc();
             return self.generate_icmp_unreachable(
                 &IpRepr::Ipv4(repr),
                 pkt.payload(),
-                Icmpv4DstUnreachable::HostUnreachable,
+                DstUnreachable::Host,
             );
         }
 
+        match self.check_ingress(&IpRepr::Ipv4(repr), pkt.payload()) {
+            FilterVerdict::Accept => (),
+            FilterVerdict::Drop => return None,
+            FilterVerdict::Reject => {
+                return self.generate_icmp_unreachable(
+                    &IpRepr::Ipv4(repr),
+                    pkt.payload(),
+                    DstUnreachable::Port,
+                )
+            }
+        }
+
+        self.stats.ip_in_delivers.inc();
+
+        // Raw IP sockets receive a copy of the packet, regardless of whether the packet is
+        // processed by other sockets.
+        self.process_raw_ip(&repr, pkt.payload());
+
         match repr.next_header {
             IpProtocol::Tcp => self.parse_and_process_tcp(
                 &IpRepr::Ipv4(repr),
@@ -101,6 +191,65 @@ impl<'a, E> PollContext<'a, E> {
                 pkt.payload(),
                 &self.iface_cx.checksum_caps(),
             ),
+            IpProtocol::Icmp => {
+                self.parse_and_process_icmpv4(&repr, pkt.payload(), &self.iface_cx.checksum_caps())
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_and_process_ipv6<'pkt>(
+        &mut self,
+        pkt: Ipv6Packet<&'pkt [u8]>,
+    ) -> Option<Packet<'pkt>> {
+        // Parse the IP header. Ignore the packet if the header is ill-formed.
+        let repr = Ipv6Repr::parse(&pkt).ok()?;
+        self.stats.ip_in_receives.inc();
+
+        if !self.is_unicast_local(IpAddress::Ipv6(repr.dst_addr)) {
+            // We have not joined any multicast groups except those used by neighbor discovery,
+            // which is handled by the physical layer. So the multicast packet is ignored.
+            if repr.dst_addr.is_multicast() {
+                return None;
+            }
+            self.stats.ip_in_addr_errors.inc();
+            return self.generate_icmp_unreachable(
+                &IpRepr::Ipv6(repr),
+                pkt.payload(),
+                DstUnreachable::Host,
+            );
+        }
+
+        match self.check_ingress(&IpRepr::Ipv6(repr), pkt.payload()) {
+            FilterVerdict::Accept => (),
+            FilterVerdict::Drop => return None,
+            FilterVerdict::Reject => {
+                return self.generate_icmp_unreachable(
+                    &IpRepr::Ipv6(repr),
+                    pkt.payload(),
+                    DstUnreachable::Port,
+                )
+            }
+        }
+
+        self.stats.ip_in_delivers.inc();
+
+        // TODO: Support IPv6 extension headers. Currently, the packet is ignored if the next
+        // header is not an upper-layer protocol that we can handle.
+        match repr.next_header {
+            IpProtocol::Tcp => self.parse_and_process_tcp(
+                &IpRepr::Ipv6(repr),
+                pkt.payload(),
+                &self.iface_cx.checksum_caps(),
+            ),
+            IpProtocol::Udp => self.parse_and_process_udp(
+                &IpRepr::Ipv6(repr),
+                pkt.payload(),
+                &self.iface_cx.checksum_caps(),
+            ),
+            IpProtocol::Icmpv6 => {
+                self.parse_and_process_icmpv6(&repr, pkt.payload(), &self.iface_cx.checksum_caps())
+            }
             _ => None,
         }
     }
@@ -127,6 +276,7 @@ impl<'a, E> PollContext<'a, E> {
             checksum_caps,
         )
         .ok()?;
+        self.stats.tcp_in_segs.inc();
 
         self.process_tcp_until_outgoing(ip_repr, &tcp_repr)
             .map(|(ip_repr, tcp_repr)| Packet::new(ip_repr, IpPayload::Tcp(tcp_repr)))
@@ -175,6 +325,7 @@ impl<'a, E> PollContext<'a, E> {
             return None;
         }
 
+        self.stats.tcp_out_rsts.inc();
         Some(smoltcp::socket::tcp::Socket::rst_reply(ip_repr, tcp_repr))
     }
 
@@ -195,12 +346,10 @@ impl<'a, E> PollContext<'a, E> {
         .ok()?;
 
         if !self.process_udp(ip_repr, &udp_repr, udp_pkt.payload()) {
-            return self.generate_icmp_unreachable(
-                ip_repr,
-                ip_payload,
-                Icmpv4DstUnreachable::PortUnreachable,
-            );
+            self.stats.udp_no_ports.inc();
+            return self.generate_icmp_unreachable(ip_repr, ip_payload, DstUnreachable::Port);
         }
+        self.stats.udp_in_datagrams.inc();
 
         None
     }
@@ -222,11 +371,249 @@ impl<'a, E> PollContext<'a, E> {
         processed
     }
 
+    fn parse_and_process_icmpv4<'pkt>(
+        &self,
+        ip_repr: &Ipv4Repr,
+        ip_payload: &'pkt [u8],
+        checksum_caps: &ChecksumCapabilities,
+    ) -> Option<Packet<'pkt>> {
+        // Parse the ICMP header. Ignore the packet if the header is ill-formed.
+        let icmp_pkt = Icmpv4Packet::new_checked(ip_payload).ok()?;
+        let icmp_repr = Icmpv4Repr::parse(&icmp_pkt, checksum_caps).ok()?;
+        self.stats.icmp_in_msgs.inc();
+
+        let src_addr = IpAddress::Ipv4(ip_repr.src_addr);
+        let dst_addr = IpAddress::Ipv4(ip_repr.dst_addr);
+
+        match icmp_repr {
+            Icmpv4Repr::EchoRequest {
+                ident,
+                seq_no,
+                data,
+            } => {
+                let echo_repr = EchoRepr {
+                    is_request: true,
+                    ident,
+                    seq_no,
+                    data,
+                };
+                self.process_echo_until_outgoing(src_addr, dst_addr, &echo_repr)
+            }
+            Icmpv4Repr::EchoReply {
+                ident,
+                seq_no,
+                data,
+            } => {
+                let echo_repr = EchoRepr {
+                    is_request: false,
+                    ident,
+                    seq_no,
+                    data,
+                };
+                self.process_echo_until_outgoing(src_addr, dst_addr, &echo_repr)
+            }
+            Icmpv4Repr::DstUnreachable {
+                reason: Icmpv4DstUnreachable::PortUnreachable,
+                header,
+                data,
+            } => {
+                self.process_port_unreachable(&IpRepr::Ipv4(header), data);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_and_process_icmpv6<'pkt>(
+        &self,
+        ip_repr: &Ipv6Repr,
+        ip_payload: &'pkt [u8],
+        checksum_caps: &ChecksumCapabilities,
+    ) -> Option<Packet<'pkt>> {
+        // Parse the ICMPv6 header. Ignore the packet if the header is ill-formed.
+        let icmp_pkt = Icmpv6Packet::new_checked(ip_payload).ok()?;
+        let icmp_repr = Icmpv6Repr::parse(
+            &ip_repr.src_addr,
+            &ip_repr.dst_addr,
+            &icmp_pkt,
+            checksum_caps,
+        )
+        .ok()?;
+        self.stats.icmp_in_msgs.inc();
+
+        let src_addr = IpAddress::Ipv6(ip_repr.src_addr);
+        let dst_addr = IpAddress::Ipv6(ip_repr.dst_addr);
+
+        match icmp_repr {
+            Icmpv6Repr::EchoRequest {
+                ident,
+                seq_no,
+                data,
+            } => {
+                let echo_repr = EchoRepr {
+                    is_request: true,
+                    ident,
+                    seq_no,
+                    data,
+                };
+                self.process_echo_until_outgoing(src_addr, dst_addr, &echo_repr)
+            }
+            Icmpv6Repr::EchoReply {
+                ident,
+                seq_no,
+                data,
+            } => {
+                let echo_repr = EchoRepr {
+                    is_request: false,
+                    ident,
+                    seq_no,
+                    data,
+                };
+                self.process_echo_until_outgoing(src_addr, dst_addr, &echo_repr)
+            }
+            Icmpv6Repr::DstUnreachable {
+                reason: Icmpv6DstUnreachable::PortUnreachable,
+                header,
+                data,
+            } => {
+                self.process_port_unreachable(&IpRepr::Ipv6(header), data);
+                None
+            }
+            // NDISC packets have been handled by the physical layer.
+            _ => None,
+        }
+    }
+
+    fn process_echo_until_outgoing<'pkt>(
+        &self,
+        src_addr: IpAddress,
+        dst_addr: IpAddress,
+        echo_repr: &EchoRepr<'pkt>,
+    ) -> Option<Packet<'pkt>> {
+        let (src_addr, dst_addr, reply_repr) = self.process_echo(src_addr, dst_addr, echo_repr)?;
+
+        if self.is_unicast_local(dst_addr) {
+            // The reply is destined to a local IP address, so it is processed here directly. An
+            // echo reply never generates another reply.
+            self.process_raw_echo(src_addr, dst_addr, &reply_repr);
+            self.process_echo(src_addr, dst_addr, &reply_repr);
+            return None;
+        }
+
+        Some(reply_repr.to_packet(src_addr, dst_addr))
+    }
+
+    /// Processes an echo request or reply.
+    ///
+    /// For an echo request, this method returns the echo reply, along with its source and
+    /// destination addresses. For an echo reply, this method delivers it to the ping socket.
+    fn process_echo<'pkt>(
+        &self,
+        src_addr: IpAddress,
+        dst_addr: IpAddress,
+        echo_repr: &EchoRepr<'pkt>,
+    ) -> Option<(IpAddress, IpAddress, EchoRepr<'pkt>)> {
+        if echo_repr.is_request {
+            // Like Linux, echo requests to broadcast or multicast addresses are ignored. See the
+            // `icmp_echo_ignore_broadcasts` option in
+            // <https://www.kernel.org/doc/Documentation/networking/ip-sysctl.txt>.
+            if !dst_addr.is_unicast() {
+                return None;
+            }
+
+            let reply_repr = EchoRepr {
+                is_request: false,
+                ..*echo_repr
+            };
+            return Some((dst_addr, src_addr, reply_repr));
+        }
+
+        for socket in self.ping_sockets.iter() {
+            if socket.can_process(echo_repr.ident) && socket.process(src_addr, dst_addr, echo_repr)
+            {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// Delivers a copy of an echo message that never reaches the physical layer to raw IP sockets.
+    fn process_raw_echo(&self, src_addr: IpAddress, dst_addr: IpAddress, echo_repr: &EchoRepr) {
+        let (IpAddress::Ipv4(src_addr), IpAddress::Ipv4(dst_addr)) = (src_addr, dst_addr) else {
+            return;
+        };
+
+        let message = echo_repr.to_message(IpAddress::Ipv4(src_addr), IpAddress::Ipv4(dst_addr));
+        let ip_repr = Ipv4Repr {
+            src_addr,
+            dst_addr,
+            next_header: IpProtocol::Icmp,
+            payload_len: message.len(),
+            hop_limit: 64,
+        };
+        self.process_raw_ip(&ip_repr, &message);
+    }
+
+    /// Delivers a copy of an IPv4 packet to the raw IP sockets of the protocol.
+    fn process_raw_ip(&self, ip_repr: &Ipv4Repr, ip_payload: &[u8]) {
+        let protocol = u8::from(ip_repr.next_header) as u16;
+        if !self
+            .raw_ip_sockets
+            .iter()
+            .any(|socket| socket.can_process(protocol))
+        {
+            return;
+        }
+
+        // Raw IP sockets receive the IP header along with the IP payload.
+        let mut packet = vec![0; ip_repr.buffer_len() + ip_payload.len()];
+        ip_repr.emit(
+            &mut Ipv4Packet::new_unchecked(&mut packet),
+            &ChecksumCapabilities::default(),
+        );
+        packet[ip_repr.buffer_len()..].copy_from_slice(ip_payload);
+
+        let src_addr = IpAddress::Ipv4(ip_repr.src_addr);
+        let dst_addr = IpAddress::Ipv4(ip_repr.dst_addr);
+        for socket in self.raw_ip_sockets.iter() {
+            if socket.can_process(protocol) {
+                socket.process(src_addr, dst_addr, &packet);
+            }
+        }
+    }
+
+    /// Processes an ICMP "port unreachable" message.
+    ///
+    /// The `header` and `data` are the IP header and the beginning of the IP payload of the
+    /// packet that triggers the ICMP message.
+    fn process_port_unreachable(&self, header: &IpRepr, data: &[u8]) {
+        // Only UDP sockets care about unreachable ports. The beginning of the UDP header contains
+        // the source and destination ports.
+        if header.next_header() != IpProtocol::Udp || data.len() < 4 {
+            return;
+        }
+
+        let local_endpoint =
+            IpEndpoint::new(header.src_addr(), u16::from_be_bytes([data[0], data[1]]));
+        let remote_endpoint =
+            IpEndpoint::new(header.dst_addr(), u16::from_be_bytes([data[2], data[3]]));
+        self.process_udp_unreachable(local_endpoint, remote_endpoint);
+    }
+
+    fn process_udp_unreachable(&self, local_endpoint: IpEndpoint, remote_endpoint: IpEndpoint) {
+        for socket in self.udp_sockets.iter() {
+            if socket.can_process(local_endpoint.port) {
+                socket.process_unreachable(local_endpoint.addr, remote_endpoint);
+            }
+        }
+    }
+
     fn generate_icmp_unreachable<'pkt>(
         &self,
         ip_repr: &IpRepr,
         ip_payload: &'pkt [u8],
-        reason: Icmpv4DstUnreachable,
+        reason: DstUnreachable,
     ) -> Option<Packet<'pkt>> {
         if !ip_repr.src_addr().is_unicast() || !ip_repr.dst_addr().is_unicast() {
             return None;
@@ -234,48 +621,83 @@ impl<'a, E> PollContext<'a, E> {
 
         if self.is_unicast_local(ip_repr.src_addr()) {
             // In this case, the generating ICMP message will have a local IP address as the
-            // destination. However, since we don't have the ability to handle ICMP messages, we'll
-            // just skip the generation.
-            //
-            // TODO: Generate the ICMP message here once we're able to handle incoming ICMP
-            // messages.
+            // destination. So we process the ICMP message here directly instead of generating it.
+            if matches!(reason, DstUnreachable::Port) {
+                self.process_port_unreachable(ip_repr, ip_payload);
+            }
             return None;
         }
 
-        let IpRepr::Ipv4(ipv4_repr) = ip_repr;
+        match ip_repr {
+            IpRepr::Ipv4(ipv4_repr) => {
+                let reply_len =
+                    icmp_reply_payload_len(ip_payload.len(), IPV4_MIN_MTU, IPV4_HEADER_LEN);
+                let icmp_repr = Icmpv4Repr::DstUnreachable {
+                    reason: reason.into(),
+                    header: *ipv4_repr,
+                    data: &ip_payload[..reply_len],
+                };
+
+                Some(Packet::new_ipv4(
+                    Ipv4Repr {
+                        src_addr: self
+                            .iface_cx
+                            .ipv4_addr()
+                            .unwrap_or(Ipv4Address::UNSPECIFIED),
+                        dst_addr: ipv4_repr.src_addr,
+                        next_header: IpProtocol::Icmp,
+                        payload_len: icmp_repr.buffer_len(),
+                        hop_limit: 64,
+                    },
+                    IpPayload::Icmpv4(icmp_repr),
+                ))
+            }
+            IpRepr::Ipv6(ipv6_repr) => {
+                let reply_len =
+                    icmp_reply_payload_len(ip_payload.len(), IPV6_MIN_MTU, IPV6_HEADER_LEN);
+                let icmp_repr = Icmpv6Repr::DstUnreachable {
+                    reason: reason.into(),
+                    header: *ipv6_repr,
+                    data: &ip_payload[..reply_len],
+                };
 
-        let reply_len = icmp_reply_payload_len(ip_payload.len(), IPV4_MIN_MTU, IPV4_HEADER_LEN);
-        let icmp_repr = Icmpv4Repr::DstUnreachable {
-            reason,
-            header: *ipv4_repr,
-            data: &ip_payload[..reply_len],
-        };
+                Some(Packet::new_ipv6(
+                    Ipv6Repr {
+                        src_addr: self
+                            .iface_cx
+                            .ipv6_addr()
+                            .unwrap_or(Ipv6Address::UNSPECIFIED),
+                        dst_addr: ipv6_repr.src_addr,
+                        next_header: IpProtocol::Icmpv6,
+                        payload_len: icmp_repr.buffer_len(),
+                        hop_limit: 64,
+                    },
+                    IpPayload::Icmpv6(icmp_repr),
+                ))
+            }
+        }
+    }
 
-        Some(Packet::new_ipv4(
-            Ipv4Repr {
-                src_addr: self
-                    .iface_cx
-                    .ipv4_addr()
-                    .unwrap_or(Ipv4Address::UNSPECIFIED),
-                dst_addr: ipv4_repr.src_addr,
-                next_header: IpProtocol::Icmp,
-                payload_len: icmp_repr.buffer_len(),
-                hop_limit: 64,
-            },
-            IpPayload::Icmpv4(icmp_repr),
-        ))
+    /// Checks an incoming packet against the chain of [`FilterHook::Ingress`].
+    fn check_ingress(&self, ip_repr: &IpRepr, ip_payload: &[u8]) -> FilterVerdict {
+        self.filter.check(FilterHook::Ingress, ip_repr, || {
+            transport_ports(ip_repr.next_header(), ip_payload)
+        })
     }
 
     /// Returns whether the destination address is the unicast address of a local interface.
     ///
     /// Note: "local" means that the IP address belongs to the local interface, not to be confused
-    /// with the localhost IP (127.0.0.1).
+    /// with the localhost IP (127.0.0.1 or ::1).
     fn is_unicast_local(&self, dst_addr: IpAddress) -> bool {
         match dst_addr {
             IpAddress::Ipv4(dst_addr) => self
                 .iface_cx
                 .ipv4_addr()
                 .is_some_and(|addr| addr == dst_addr),
+            IpAddress::Ipv6(dst_addr) => {
+                dst_addr.is_unicast() && self.iface_cx.has_ip_addr(dst_addr)
+            }
         }
     }
 }
@@ -287,13 +709,13 @@ impl<'a, E> PollContext<'a, E> {
         Q: FnMut(&Packet, &mut Context, D::TxToken<'_>),
     {
         while let Some(tx_token) = device.transmit(self.iface_cx.now()) {
-            if !self.dispatch_ipv4(tx_token, &mut dispatch_phy) {
+            if !self.dispatch_ip(tx_token, &mut dispatch_phy) {
                 break;
             }
         }
     }
 
-    fn dispatch_ipv4<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> bool
+    fn dispatch_ip<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> bool
     where
         T: TxToken,
         Q: FnMut(&Packet, &mut Context, T),
@@ -304,9 +726,21 @@ impl<'a, E> PollContext<'a, E> {
             return did_something_tcp;
         };
 
-        let (did_something_udp, _tx_token) = self.dispatch_udp(tx_token, dispatch_phy);
+        let (did_something_udp, tx_token) = self.dispatch_udp(tx_token, dispatch_phy);
+
+        let Some(tx_token) = tx_token else {
+            return did_something_tcp || did_something_udp;
+        };
+
+        let (did_something_ping, tx_token) = self.dispatch_ping(tx_token, dispatch_phy);
+
+        let Some(tx_token) = tx_token else {
+            return did_something_tcp || did_something_udp || did_something_ping;
+        };
+
+        let (did_something_raw_ip, _tx_token) = self.dispatch_raw_ip(tx_token, dispatch_phy);
 
-        did_something_tcp || did_something_udp
+        did_something_tcp || did_something_udp || did_something_ping || did_something_raw_ip
     }
 
     fn dispatch_tcp<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> (bool, Option<T>)
@@ -329,7 +763,15 @@ impl<'a, E> PollContext<'a, E> {
             let mut deferred = None;
 
             let reply = socket.dispatch(self.iface_cx, |cx, ip_repr, tcp_repr| {
-                let mut this = PollContext::new(cx, self.tcp_sockets, self.udp_sockets);
+                let mut this = PollContext::new(
+                    cx,
+                    self.tcp_sockets,
+                    self.udp_sockets,
+                    self.ping_sockets,
+                    self.raw_ip_sockets,
+                    self.filter,
+                    self.stats,
+                );
 
                 if !this.is_unicast_local(ip_repr.dst_addr()) {
                     dispatch_phy(
@@ -420,7 +862,15 @@ impl<'a, E> PollContext<'a, E> {
             let mut deferred = None;
 
             socket.dispatch(self.iface_cx, |cx, ip_repr, udp_repr, udp_payload| {
-                let mut this = PollContext::new(cx, self.tcp_sockets, self.udp_sockets);
+                let mut this = PollContext::new(
+                    cx,
+                    self.tcp_sockets,
+                    self.udp_sockets,
+                    self.ping_sockets,
+                    self.raw_ip_sockets,
+                    self.filter,
+                    self.stats,
+                );
 
                 if ip_repr.dst_addr().is_broadcast() || !this.is_unicast_local(ip_repr.dst_addr()) {
                     dispatch_phy(
@@ -434,9 +884,15 @@ impl<'a, E> PollContext<'a, E> {
                 }
 
                 if !socket.can_process(udp_repr.dst_port) {
-                    // TODO: Generate the ICMP message here once we're able to handle incoming ICMP
-                    // messages.
-                    let _ = this.process_udp(ip_repr, udp_repr, udp_payload);
+                    let processed = this.process_udp(ip_repr, udp_repr, udp_payload);
+                    if !processed && ip_repr.dst_addr().is_unicast() {
+                        // The ICMP message would be destined to a local IP address, so we process
+                        // it here directly instead of generating it.
+                        this.process_udp_unreachable(
+                            IpEndpoint::new(ip_repr.src_addr(), udp_repr.src_port),
+                            IpEndpoint::new(ip_repr.dst_addr(), udp_repr.dst_port),
+                        );
+                    }
                     return;
                 }
 
@@ -473,4 +929,90 @@ impl<'a, E> PollContext<'a, E> {
 
         (did_something, tx_token)
     }
+
+    fn dispatch_ping<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> (bool, Option<T>)
+    where
+        T: TxToken,
+        Q: FnMut(&Packet, &mut Context, T),
+    {
+        let mut tx_token = Some(tx_token);
+        let mut did_something = false;
+
+        for socket in self.ping_sockets.iter() {
+            if !socket.need_dispatch(self.iface_cx.now()) {
+                continue;
+            }
+
+            did_something = true;
+
+            socket.dispatch(|src_addr, dst_addr, echo_repr| {
+                let packet = if self.is_unicast_local(dst_addr) {
+                    self.process_raw_echo(src_addr, dst_addr, echo_repr);
+                    self.process_echo_until_outgoing(src_addr, dst_addr, echo_repr)
+                } else {
+                    Some(echo_repr.to_packet(src_addr, dst_addr))
+                };
+
+                if let Some(packet) = packet {
+                    dispatch_phy(&packet, self.iface_cx, tx_token.take().unwrap());
+                }
+            });
+
+            if tx_token.is_none() {
+                break;
+            }
+        }
+
+        (did_something, tx_token)
+    }
+
+    fn dispatch_raw_ip<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> (bool, Option<T>)
+    where
+        T: TxToken,
+        Q: FnMut(&Packet, &mut Context, T),
+    {
+        let mut tx_token = Some(tx_token);
+        let mut did_something = false;
+
+        let raw_ip_sockets = self.raw_ip_sockets;
+        for socket in raw_ip_sockets.iter() {
+            if !socket.need_dispatch(self.iface_cx.now()) {
+                continue;
+            }
+
+            did_something = true;
+
+            socket.dispatch(|ip_repr, ip_payload| {
+                if !self.is_unicast_local(ip_repr.dst_addr()) {
+                    dispatch_phy(
+                        &Packet::new(ip_repr.clone(), IpPayload::Raw(ip_payload)),
+                        self.iface_cx,
+                        tx_token.take().unwrap(),
+                    );
+                    return;
+                }
+
+                // The packet is destined to a local IP address, so we build the packet and
+                // process it here directly, as if it were received from the physical layer.
+                let mut data = vec![0; ip_repr.buffer_len()];
+                ip_repr.emit(&mut data[..], &ChecksumCapabilities::default());
+                data[ip_repr.header_len()..].copy_from_slice(ip_payload);
+
+                let reply = match IpPacket::new_checked(&data) {
+                    Some(IpPacket::Ipv4(pkt)) => self.parse_and_process_ipv4(pkt),
+                    Some(IpPacket::Ipv6(pkt)) => self.parse_and_process_ipv6(pkt),
+                    None => None,
+                };
+                if let Some(reply) = reply {
+                    dispatch_phy(&reply, self.iface_cx, tx_token.take().unwrap());
+                }
+            });
+
+            if tx_token.is_none() {
+                break;
+            }
+        }
+
+        (did_something, tx_token)
+    }
 }
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use smoltcp::wire::IpProtocol;
+
+/// A monotonically increasing counter.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    /// Gets the current value of the counter.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add(&self, n: usize) {
+        self.0.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc(&self) {
+        self.add(1);
+    }
+}
+
+/// The statistics of an iface.
+///
+/// The link-layer counters count the frames received and transmitted by the device. The other
+/// counters follow the naming of the MIB-II counters (see RFC 1213), but only count the packets
+/// that go through this iface.
+#[derive(Debug, Default)]
+pub struct IfaceStats {
+    pub rx_packets: Counter,
+    pub rx_bytes: Counter,
+    pub tx_packets: Counter,
+    pub tx_bytes: Counter,
+
+    pub ip_in_receives: Counter,
+    pub ip_in_addr_errors: Counter,
+    pub ip_in_delivers: Counter,
+    pub ip_out_requests: Counter,
+
+    pub icmp_in_msgs: Counter,
+    pub icmp_out_msgs: Counter,
+
+    pub tcp_in_segs: Counter,
+    pub tcp_out_segs: Counter,
+    pub tcp_out_rsts: Counter,
+
+    pub udp_in_datagrams: Counter,
+    pub udp_no_ports: Counter,
+    pub udp_out_datagrams: Counter,
+}
+
+impl IfaceStats {
+    pub(crate) fn count_rx(&self, len: usize) {
+        self.rx_packets.inc();
+        self.rx_bytes.add(len);
+    }
+
+    pub(crate) fn count_tx(&self, len: usize) {
+        self.tx_packets.inc();
+        self.tx_bytes.add(len);
+    }
+
+    /// Counts an outgoing IP packet that carries a `protocol` message.
+    pub(crate) fn count_ip_out(&self, protocol: IpProtocol) {
+        self.ip_out_requests.inc();
+
+        match protocol {
+            IpProtocol::Tcp => self.tcp_out_segs.inc(),
+            IpProtocol::Udp => self.udp_out_datagrams.inc(),
+            IpProtocol::Icmp | IpProtocol::Icmpv6 => self.icmp_out_msgs.inc(),
+            _ => (),
+        }
+    }
+}
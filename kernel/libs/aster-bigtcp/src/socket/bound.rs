@@ -3,6 +3,7 @@
 use alloc::{
     boxed::Box,
     sync::{Arc, Weak},
+    vec::Vec,
 };
 use core::{
     ops::{Deref, DerefMut},
@@ -12,17 +13,27 @@ use core::{
 use ostd::sync::{LocalIrqDisabled, RwLock, SpinLock, SpinLockGuard};
 use smoltcp::{
     iface::Context,
+    phy::ChecksumCapabilities,
     socket::{udp::UdpMetadata, PollAt},
     time::Instant,
-    wire::{IpAddress, IpEndpoint, IpRepr, TcpRepr, UdpRepr},
+    wire::{
+        IpAddress, IpEndpoint, IpProtocol, IpRepr, Ipv4Packet, Ipv4Repr, TcpRepr, UdpRepr,
+        IPV4_HEADER_LEN, TCP_HEADER_LEN,
+    },
 };
 
-use super::{event::SocketEventObserver, RawTcpSocket, RawUdpSocket};
-use crate::iface::Iface;
+use super::{
+    event::SocketEventObserver,
+    option::{to_smoltcp_duration, CORK_TIMEOUT},
+    ping::{EchoPacket, EchoRepr},
+    raw::{RawIpHeader, RawIpPacket},
+    RawIpSocket, RawPingSocket, RawTcpOption, RawTcpSocket, RawTcpState, RawUdpSocket,
+};
+use crate::iface::{get_network_timestamp, Iface};
 
 pub struct BoundSocket<T: AnySocket, E>(Arc<BoundSocketInner<T, E>>);
 
-/// [`TcpSocket`] or [`UdpSocket`].
+/// [`TcpSocket`], [`UdpSocket`], [`PingSocket`], or [`RawIpSocketState`].
 pub trait AnySocket {
     type RawSocket;
 
@@ -37,10 +48,13 @@ pub trait AnySocket {
 
 pub type BoundTcpSocket<E> = BoundSocket<TcpSocket, E>;
 pub type BoundUdpSocket<E> = BoundSocket<UdpSocket, E>;
+pub type BoundPingSocket<E> = BoundSocket<PingSocket, E>;
+pub type BoundRawIpSocket<E> = BoundSocket<RawIpSocketState, E>;
 
 /// Common states shared by [`BoundTcpSocketInner`] and [`BoundUdpSocketInner`].
 pub struct BoundSocketInner<T, E> {
     iface: Arc<dyn Iface<E>>,
+    local_addr: IpAddress,
     port: u16,
     socket: T,
     observer: RwLock<Weak<dyn SocketEventObserver>>,
@@ -54,7 +68,13 @@ pub struct TcpSocket {
     is_dead: AtomicBool,
 }
 
-struct RawTcpSocketExt {
+/// A [`RawTcpSocket`] with the states needed to implement [`RawTcpOption`].
+///
+/// Some TCP options (e.g., keep-alive probes and `TCP_CORK`) are not supported by smoltcp in the
+/// way that Linux behaves, so they are implemented here. For this purpose, methods like
+/// [`Self::send`], [`Self::process`], and [`Self::dispatch`] shadow the methods of the same names
+/// in [`RawTcpSocket`].
+pub struct RawTcpSocketExt {
     socket: Box<RawTcpSocket>,
     /// Whether the socket is in the background.
     ///
@@ -63,6 +83,20 @@ struct RawTcpSocketExt {
     /// state of waiting for certain network events (e.g., remote FIN/ACK packets), so
     /// [`BoundSocketInner`] may still be alive for a while.
     in_background: bool,
+    option: RawTcpOption,
+    /// The maximum segment size, or `None` if it has not been negotiated yet.
+    mss: Option<usize>,
+    /// The time since which the connection has been idle.
+    ///
+    /// This is the time when the last packet was received or when keep-alive was enabled,
+    /// whichever is later.
+    idle_since: Option<Instant>,
+    /// Whether keep-alive probes are being sent.
+    is_probing: bool,
+    /// The data that is held back because the socket is corked.
+    corked: Vec<u8>,
+    /// The time when the data in [`Self::corked`] started to be held back.
+    corked_since: Option<Instant>,
 }
 
 impl Deref for RawTcpSocketExt {
@@ -79,6 +113,223 @@ impl DerefMut for RawTcpSocketExt {
     }
 }
 
+impl RawTcpSocketExt {
+    pub(super) fn new(socket: Box<RawTcpSocket>) -> Self {
+        Self {
+            socket,
+            in_background: false,
+            option: RawTcpOption::default(),
+            mss: None,
+            idle_since: None,
+            is_probing: false,
+            corked: Vec::new(),
+            corked_since: None,
+        }
+    }
+
+    /// Replaces the underlying [`RawTcpSocket`] while preserving the TCP options.
+    pub(super) fn set_raw(&mut self, socket: RawTcpSocket) {
+        *self.socket = socket;
+        self.socket.set_nagle_enabled(self.option.is_nagle_enabled);
+    }
+
+    /// Sets the TCP options.
+    pub(super) fn set_raw_option(&mut self, option: &RawTcpOption) {
+        self.socket.set_nagle_enabled(option.is_nagle_enabled);
+
+        if option.keep_alive != self.option.keep_alive {
+            // Like Linux, enabling keep-alive restarts the idle timer.
+            if self.option.keep_alive.is_none() {
+                self.idle_since = Some(get_network_timestamp());
+            }
+            self.stop_probing();
+        }
+
+        self.option = *option;
+
+        if !option.is_corked {
+            self.flush_corked();
+        }
+    }
+
+    fn send<F, R>(&mut self, f: F) -> Result<R, smoltcp::socket::tcp::SendError>
+    where
+        F: FnOnce(&mut [u8]) -> (usize, R),
+    {
+        if !self.option.is_corked || !self.socket.may_send() {
+            return self.socket.send(f);
+        }
+
+        // The corked data is always small enough to fit in the send buffer of the socket, so it
+        // can be flushed at any time.
+        let start = self.corked.len();
+        let free = self.socket.send_capacity() - self.socket.send_queue() - start;
+
+        self.corked.resize(start + free, 0);
+        let (size, result) = f(&mut self.corked[start..]);
+        self.corked.truncate(start + size);
+
+        if size > 0 && self.corked_since.is_none() {
+            self.corked_since = Some(get_network_timestamp());
+        }
+
+        // Full-sized segments are never held back.
+        if let Some(mss) = self.mss {
+            self.send_corked(self.corked.len() / mss * mss);
+        }
+
+        Ok(result)
+    }
+
+    fn can_send(&self) -> bool {
+        self.socket.may_send() && self.send_queue() < self.socket.send_capacity()
+    }
+
+    fn send_queue(&self) -> usize {
+        self.socket.send_queue() + self.corked.len()
+    }
+
+    fn close(&mut self) {
+        self.flush_corked();
+        self.socket.close();
+    }
+
+    fn process(
+        &mut self,
+        cx: &mut Context,
+        ip_repr: &IpRepr,
+        tcp_repr: &TcpRepr,
+    ) -> Option<(IpRepr, TcpRepr<'static>)> {
+        // Any incoming packet shows that the remote endpoint is still alive.
+        self.idle_since = Some(cx.now());
+        self.stop_probing();
+
+        if let Some(max_seg_size) = tcp_repr.max_seg_size {
+            let local_mss = cx
+                .caps
+                .ip_mtu()
+                .saturating_sub(ip_repr.header_len() + TCP_HEADER_LEN);
+            self.mss = Some(local_mss.min(max_seg_size as usize));
+        }
+
+        self.socket.process(cx, ip_repr, tcp_repr)
+    }
+
+    fn dispatch<F, E>(&mut self, cx: &mut Context, emit: F) -> Result<(), E>
+    where
+        F: FnOnce(&mut Context, (IpRepr, TcpRepr)) -> Result<(), E>,
+    {
+        let now = cx.now();
+
+        if self
+            .corked_since
+            .is_some_and(|corked_since| now >= corked_since + to_smoltcp_duration(CORK_TIMEOUT))
+        {
+            self.flush_corked();
+        }
+
+        if let Some(deadline) = self.keep_alive_deadline()
+            && now >= deadline
+        {
+            if self.is_probing {
+                // Too many probes are unanswered. Aborting the socket will send a RST packet.
+                self.stop_probing();
+                self.socket.abort();
+            } else {
+                self.start_probing();
+            }
+        }
+
+        self.socket.dispatch(cx, emit)
+    }
+
+    fn poll_at(&self, cx: &mut Context) -> PollAt {
+        let mut poll_at = self.socket.poll_at(cx);
+
+        if let Some(corked_since) = self.corked_since {
+            poll_at = min_poll_at(poll_at, corked_since + to_smoltcp_duration(CORK_TIMEOUT));
+        }
+        if let Some(deadline) = self.keep_alive_deadline() {
+            poll_at = min_poll_at(poll_at, deadline);
+        }
+
+        poll_at
+    }
+
+    /// Moves the first `len` bytes of the corked data to the send buffer of the socket.
+    fn send_corked(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+
+        // This fails only if the connection is no longer able to send data, in which case the
+        // corked data will never be sent anyway.
+        let sent = self.socket.send_slice(&self.corked[..len]).unwrap_or(len);
+        self.corked.drain(..sent);
+
+        if self.corked.is_empty() {
+            self.corked_since = None;
+        }
+    }
+
+    fn flush_corked(&mut self) {
+        self.send_corked(self.corked.len());
+    }
+
+    /// Returns the time when the next keep-alive action should be taken.
+    ///
+    /// If no probes have been sent, this is the time to send the first probe. Otherwise, this is
+    /// the time to abort the connection because the probes are unanswered.
+    fn keep_alive_deadline(&self) -> Option<Instant> {
+        let keep_alive = self.option.keep_alive.as_ref()?;
+        let idle_since = self.idle_since?;
+
+        if !matches!(
+            self.socket.state(),
+            RawTcpState::Established | RawTcpState::CloseWait
+        ) {
+            return None;
+        }
+
+        let deadline = if self.is_probing {
+            keep_alive.idle + keep_alive.interval * keep_alive.count
+        } else {
+            keep_alive.idle
+        };
+        Some(idle_since + to_smoltcp_duration(deadline))
+    }
+
+    fn start_probing(&mut self) {
+        let Some(keep_alive) = self.option.keep_alive.as_ref() else {
+            return;
+        };
+
+        // Setting the keep-alive interval in smoltcp winds up its keep-alive timer, so the first
+        // probe is sent immediately. Subsequent probes are sent at the interval until a packet
+        // is received from the remote endpoint.
+        self.is_probing = true;
+        self.socket
+            .set_keep_alive(Some(to_smoltcp_duration(keep_alive.interval)));
+    }
+
+    fn stop_probing(&mut self) {
+        if !self.is_probing {
+            return;
+        }
+
+        self.is_probing = false;
+        self.socket.set_keep_alive(None);
+    }
+}
+
+fn min_poll_at(poll_at: PollAt, instant: Instant) -> PollAt {
+    match poll_at {
+        PollAt::Now => PollAt::Now,
+        PollAt::Time(time) => PollAt::Time(time.min(instant)),
+        PollAt::Ingress => PollAt::Time(instant),
+    }
+}
+
 impl TcpSocket {
     fn lock(&self) -> SpinLockGuard<RawTcpSocketExt, LocalIrqDisabled> {
         self.socket.lock()
@@ -106,16 +357,11 @@ impl TcpSocket {
 }
 
 impl AnySocket for TcpSocket {
-    type RawSocket = RawTcpSocket;
+    type RawSocket = RawTcpSocketExt;
 
     fn new(socket: Box<Self::RawSocket>) -> Self {
-        let socket_ext = RawTcpSocketExt {
-            socket,
-            in_background: false,
-        };
-
         Self {
-            socket: SpinLock::new(socket_ext),
+            socket: SpinLock::new(*socket),
             is_dead: AtomicBool::new(false),
         }
     }
@@ -134,13 +380,26 @@ impl AnySocket for TcpSocket {
 }
 
 /// States needed by [`BoundUdpSocketInner`] but not [`BoundTcpSocketInner`].
-type UdpSocket = SpinLock<Box<RawUdpSocket>, LocalIrqDisabled>;
+pub struct UdpSocket {
+    socket: SpinLock<Box<RawUdpSocket>, LocalIrqDisabled>,
+    /// The remote endpoint that is most recently reported as unreachable by ICMP messages.
+    unreachable: SpinLock<Option<IpEndpoint>, LocalIrqDisabled>,
+}
+
+impl UdpSocket {
+    fn lock(&self) -> SpinLockGuard<Box<RawUdpSocket>, LocalIrqDisabled> {
+        self.socket.lock()
+    }
+}
 
 impl AnySocket for UdpSocket {
     type RawSocket = RawUdpSocket;
 
     fn new(socket: Box<Self::RawSocket>) -> Self {
-        Self::new(socket)
+        Self {
+            socket: SpinLock::new(socket),
+            unreachable: SpinLock::new(None),
+        }
     }
 
     fn on_drop<E>(this: &Arc<BoundSocketInner<Self, E>>) {
@@ -151,6 +410,38 @@ impl AnySocket for UdpSocket {
     }
 }
 
+/// States needed by [`BoundPingSocketInner`].
+type PingSocket = SpinLock<Box<RawPingSocket>, LocalIrqDisabled>;
+
+impl AnySocket for PingSocket {
+    type RawSocket = RawPingSocket;
+
+    fn new(socket: Box<Self::RawSocket>) -> Self {
+        Self::new(socket)
+    }
+
+    fn on_drop<E>(this: &Arc<BoundSocketInner<Self, E>>) {
+        // A ping socket can be removed immediately.
+        this.iface.common().remove_ping_socket(this);
+    }
+}
+
+/// States needed by [`BoundRawIpSocketInner`].
+type RawIpSocketState = SpinLock<Box<RawIpSocket>, LocalIrqDisabled>;
+
+impl AnySocket for RawIpSocketState {
+    type RawSocket = RawIpSocket;
+
+    fn new(socket: Box<Self::RawSocket>) -> Self {
+        Self::new(socket)
+    }
+
+    fn on_drop<E>(this: &Arc<BoundSocketInner<Self, E>>) {
+        // A raw IP socket can be removed immediately.
+        this.iface.common().remove_raw_ip_socket(this);
+    }
+}
+
 impl<T: AnySocket, E> Drop for BoundSocket<T, E> {
     fn drop(&mut self) {
         T::on_drop(&self.0);
@@ -159,16 +450,20 @@ impl<T: AnySocket, E> Drop for BoundSocket<T, E> {
 
 pub(crate) type BoundTcpSocketInner<E> = BoundSocketInner<TcpSocket, E>;
 pub(crate) type BoundUdpSocketInner<E> = BoundSocketInner<UdpSocket, E>;
+pub(crate) type BoundPingSocketInner<E> = BoundSocketInner<PingSocket, E>;
+pub(crate) type BoundRawIpSocketInner<E> = BoundSocketInner<RawIpSocketState, E>;
 
 impl<T: AnySocket, E> BoundSocket<T, E> {
     pub(crate) fn new(
         iface: Arc<dyn Iface<E>>,
+        local_addr: IpAddress,
         port: u16,
         socket: Box<T::RawSocket>,
         observer: Weak<dyn SocketEventObserver>,
     ) -> Self {
         Self(Arc::new(BoundSocketInner {
             iface,
+            local_addr,
             port,
             socket: T::new(socket),
             observer: RwLock::new(observer),
@@ -204,12 +499,9 @@ impl<T: AnySocket, E> BoundSocket<T, E> {
         self.0.observer.read().clone()
     }
 
-    pub fn local_endpoint(&self) -> Option<IpEndpoint> {
-        let ip_addr = {
-            let ipv4_addr = self.0.iface.ipv4_addr()?;
-            IpAddress::Ipv4(ipv4_addr)
-        };
-        Some(IpEndpoint::new(ip_addr, self.0.port))
+    /// Returns the local endpoint, i.e., the IP address and the port that the socket is bound to.
+    pub fn local_endpoint(&self) -> IpEndpoint {
+        IpEndpoint::new(self.0.local_addr, self.0.port)
     }
 
     pub fn iface(&self) -> &Arc<dyn Iface<E>> {
@@ -219,16 +511,25 @@ impl<T: AnySocket, E> BoundSocket<T, E> {
 
 impl<E> BoundTcpSocket<E> {
     /// Connects to a remote endpoint.
+    ///
+    /// The remote endpoint must be in the same address family as the local endpoint. Otherwise,
+    /// [`ConnectError::Unaddressable`] will be returned.
+    ///
+    /// [`ConnectError::Unaddressable`]: smoltcp::socket::tcp::ConnectError::Unaddressable
     pub fn connect(
         &self,
         remote_endpoint: IpEndpoint,
     ) -> Result<(), smoltcp::socket::tcp::ConnectError> {
+        if remote_endpoint.addr.version() != self.0.local_addr.version() {
+            return Err(smoltcp::socket::tcp::ConnectError::Unaddressable);
+        }
+
         let common = self.iface().common();
         let mut iface = common.interface();
 
         let mut socket = self.0.socket.lock();
 
-        let result = socket.connect(iface.context(), remote_endpoint, self.0.port);
+        let result = socket.connect(iface.context(), remote_endpoint, self.local_endpoint());
         self.0
             .update_next_poll_at_ms(socket.poll_at(iface.context()));
 
@@ -269,6 +570,20 @@ impl<E> BoundTcpSocket<E> {
         result
     }
 
+    /// Peeks at the received data without removing it from the receive buffer.
+    ///
+    /// Note that the `f` may be called with only a part of the received data, since the data in
+    /// the receive buffer may not be contiguous.
+    pub fn peek<F, R>(&self, f: F) -> Result<R, smoltcp::socket::tcp::RecvError>
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let mut socket = self.0.socket.lock();
+
+        let data = socket.peek(usize::MAX)?;
+        Ok(f(data))
+    }
+
     pub fn close(&self) {
         let mut socket = self.0.socket.lock();
 
@@ -276,6 +591,31 @@ impl<E> BoundTcpSocket<E> {
         self.0.update_next_poll_at_ms(PollAt::Now);
     }
 
+    /// Returns whether the send buffer has space for more data.
+    ///
+    /// Unlike [`RawTcpSocket::can_send`], this takes into account the data that is held back
+    /// because the socket is corked.
+    pub fn can_send(&self) -> bool {
+        let socket = self.0.socket.lock();
+
+        socket.can_send()
+    }
+
+    /// Sets the TCP options.
+    ///
+    /// Note that the keep-alive and cork options may change when the socket should be polled next
+    /// time, so the next polling time is updated accordingly.
+    pub fn set_raw_option(&self, option: &RawTcpOption) {
+        let common = self.iface().common();
+        let mut iface = common.interface();
+
+        let mut socket = self.0.socket.lock();
+
+        socket.set_raw_option(option);
+        self.0
+            .update_next_poll_at_ms(socket.poll_at(iface.context()));
+    }
+
     /// Calls `f` with an immutable reference to the associated [`RawTcpSocket`].
     //
     // NOTE: If a mutable reference is required, add a method above that correctly updates the next
@@ -310,6 +650,11 @@ impl<E> BoundUdpSocket<E> {
 
         use crate::errors::udp::SendError;
 
+        let meta = meta.into();
+        if meta.endpoint.addr.version() != self.0.local_addr.version() {
+            return Err(SendError::Unaddressable);
+        }
+
         let mut socket = self.0.socket.lock();
 
         if size > socket.packet_send_capacity() {
@@ -340,6 +685,25 @@ impl<E> BoundUdpSocket<E> {
         Ok(result)
     }
 
+    /// Peeks at the first received packet without removing it from the receive buffer.
+    pub fn peek<F, R>(&self, f: F) -> Result<R, smoltcp::socket::udp::RecvError>
+    where
+        F: FnOnce(&[u8], UdpMetadata) -> R,
+    {
+        let mut socket = self.0.socket.lock();
+
+        let (data, meta) = socket.peek()?;
+        Ok(f(data, *meta))
+    }
+
+    /// Takes the remote endpoint that is most recently reported as unreachable.
+    ///
+    /// A remote endpoint is reported as unreachable if an ICMP "port unreachable" message is
+    /// received in response to a packet sent from this socket to the remote endpoint.
+    pub fn take_unreachable(&self) -> Option<IpEndpoint> {
+        self.0.socket.unreachable.lock().take()
+    }
+
     /// Calls `f` with an immutable reference to the associated [`RawUdpSocket`].
     //
     // NOTE: If a mutable reference is required, add a method above that correctly updates the next
@@ -353,6 +717,261 @@ impl<E> BoundUdpSocket<E> {
     }
 }
 
+impl<E> BoundPingSocket<E> {
+    /// Sends an ICMP echo request to `remote_addr`.
+    ///
+    /// The `message` should be an ICMP echo request (or an ICMPv6 echo request if the socket is
+    /// bound to an IPv6 address). Its identifier will be replaced by the port that the socket is
+    /// bound to, and its checksum will be recomputed.
+    pub fn send(
+        &self,
+        remote_addr: IpAddress,
+        message: &[u8],
+    ) -> Result<(), crate::errors::ping::SendError> {
+        use crate::errors::ping::SendError;
+
+        if remote_addr.version() != self.0.local_addr.version() {
+            return Err(SendError::Unaddressable);
+        }
+
+        let is_ipv4 = matches!(remote_addr, IpAddress::Ipv4(_));
+        let Some(echo_repr) = EchoRepr::parse_request(is_ipv4, message) else {
+            return Err(SendError::InvalidMessage);
+        };
+
+        let mut socket = self.0.socket.lock();
+
+        if echo_repr.data.len() > socket.payload_send_capacity() {
+            return Err(SendError::TooLarge);
+        }
+
+        let packet = EchoPacket {
+            addr: remote_addr,
+            seq_no: echo_repr.seq_no,
+            data: echo_repr.data.to_vec(),
+        };
+        if !socket.send(packet) {
+            return Err(SendError::BufferFull);
+        }
+        self.0.update_next_poll_at_ms(PollAt::Now);
+
+        Ok(())
+    }
+
+    /// Receives an ICMP echo reply.
+    ///
+    /// The `f` will be called with the ICMP (or ICMPv6) echo reply message and the address of the
+    /// remote host that sends the reply.
+    pub fn recv<F, R>(&self, f: F) -> Result<R, crate::errors::ping::RecvError>
+    where
+        F: FnOnce(&[u8], IpAddress) -> R,
+    {
+        let mut socket = self.0.socket.lock();
+
+        let Some(packet) = socket.recv() else {
+            return Err(crate::errors::ping::RecvError::Exhausted);
+        };
+        self.0.update_next_poll_at_ms(socket.poll_at());
+
+        drop(socket);
+
+        let echo_repr = EchoRepr {
+            is_request: false,
+            ident: self.0.port,
+            seq_no: packet.seq_no,
+            data: &packet.data,
+        };
+        let message = echo_repr.to_message(packet.addr, self.0.local_addr);
+
+        Ok(f(&message, packet.addr))
+    }
+
+    /// Peeks at the first received ICMP echo reply without removing it from the receive queue.
+    ///
+    /// The `f` will be called in the same way as in [`Self::recv`].
+    pub fn peek<F, R>(&self, f: F) -> Result<R, crate::errors::ping::RecvError>
+    where
+        F: FnOnce(&[u8], IpAddress) -> R,
+    {
+        let socket = self.0.socket.lock();
+
+        let Some(packet) = socket.peek() else {
+            return Err(crate::errors::ping::RecvError::Exhausted);
+        };
+
+        let echo_repr = EchoRepr {
+            is_request: false,
+            ident: self.0.port,
+            seq_no: packet.seq_no,
+            data: &packet.data,
+        };
+        let message = echo_repr.to_message(packet.addr, self.0.local_addr);
+
+        Ok(f(&message, packet.addr))
+    }
+
+    /// Calls `f` with an immutable reference to the associated [`RawPingSocket`].
+    //
+    // NOTE: If a mutable reference is required, add a method above that correctly updates the next
+    // polling time.
+    pub fn raw_with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&RawPingSocket) -> R,
+    {
+        let socket = self.0.socket.lock();
+        f(&socket)
+    }
+}
+
+impl<E> BoundRawIpSocket<E> {
+    /// Sends an IP packet to `remote_addr`.
+    ///
+    /// The `payload` is the IP payload. The IP header will be generated by the iface, where the
+    /// protocol is the port that the socket is bound to.
+    pub fn send(
+        &self,
+        remote_addr: IpAddress,
+        payload: &[u8],
+    ) -> Result<(), crate::errors::raw::SendError> {
+        use crate::errors::raw::SendError;
+
+        if remote_addr.version() != self.0.local_addr.version() {
+            return Err(SendError::Unaddressable);
+        }
+
+        let mut socket = self.0.socket.lock();
+
+        if payload.len() > socket.payload_send_capacity() {
+            return Err(SendError::TooLarge);
+        }
+
+        let packet = RawIpPacket {
+            addr: remote_addr,
+            data: payload.to_vec(),
+            header: None,
+        };
+        if !socket.send(packet) {
+            return Err(SendError::BufferFull);
+        }
+        self.0.update_next_poll_at_ms(PollAt::Now);
+
+        Ok(())
+    }
+
+    /// Sends an IPv4 packet whose IP header is provided in `packet`.
+    ///
+    /// Like Linux, the total length and the checksum in the header are always filled in by the
+    /// iface, and an unspecified source address is replaced by the address that the socket is
+    /// bound to. The destination address in the header is where the packet is sent.
+    ///
+    /// The IP options, the identification, and the type of service in the header are not
+    /// preserved, since the IP header is emitted again when the packet is dispatched.
+    pub fn send_with_header(&self, packet: &[u8]) -> Result<(), crate::errors::raw::SendError> {
+        use crate::errors::raw::SendError;
+
+        if !matches!(self.0.local_addr, IpAddress::Ipv4(_)) {
+            return Err(SendError::Unaddressable);
+        }
+
+        let mut packet = packet.to_vec();
+        let Ok(total_len) = u16::try_from(packet.len()) else {
+            return Err(SendError::TooLarge);
+        };
+        if packet.len() < IPV4_HEADER_LEN {
+            return Err(SendError::InvalidHeader);
+        }
+        Ipv4Packet::new_unchecked(packet.as_mut_slice()).set_total_len(total_len);
+
+        let Ok(ipv4_packet) = Ipv4Packet::new_checked(packet.as_slice()) else {
+            return Err(SendError::InvalidHeader);
+        };
+        let Ok(ipv4_repr) = Ipv4Repr::parse(&ipv4_packet, &ChecksumCapabilities::ignored()) else {
+            return Err(SendError::InvalidHeader);
+        };
+
+        let mut socket = self.0.socket.lock();
+
+        let payload = ipv4_packet.payload();
+        if payload.len() > socket.payload_send_capacity() {
+            return Err(SendError::TooLarge);
+        }
+
+        let src_addr = if ipv4_repr.src_addr.is_unspecified() {
+            self.0.local_addr
+        } else {
+            IpAddress::Ipv4(ipv4_repr.src_addr)
+        };
+        let packet = RawIpPacket {
+            addr: IpAddress::Ipv4(ipv4_repr.dst_addr),
+            data: payload.to_vec(),
+            header: Some(RawIpHeader {
+                src_addr,
+                protocol: ipv4_repr.next_header,
+                hop_limit: ipv4_repr.hop_limit,
+            }),
+        };
+        if !socket.send(packet) {
+            return Err(SendError::BufferFull);
+        }
+        self.0.update_next_poll_at_ms(PollAt::Now);
+
+        Ok(())
+    }
+
+    /// Sets the ICMP types to drop, as a bit mask.
+    pub fn set_icmp_filter(&self, icmp_filter: u32) {
+        self.0.socket.lock().set_icmp_filter(icmp_filter);
+    }
+
+    /// Receives an IP packet.
+    ///
+    /// The `f` will be called with the whole IP packet (including the IP header) and the address
+    /// of the remote host that sends the packet.
+    pub fn recv<F, R>(&self, f: F) -> Result<R, crate::errors::raw::RecvError>
+    where
+        F: FnOnce(&[u8], IpAddress) -> R,
+    {
+        let mut socket = self.0.socket.lock();
+
+        let Some(packet) = socket.recv() else {
+            return Err(crate::errors::raw::RecvError::Exhausted);
+        };
+        self.0.update_next_poll_at_ms(socket.poll_at());
+
+        drop(socket);
+
+        Ok(f(&packet.data, packet.addr))
+    }
+
+    /// Peeks at the first received IP packet without removing it from the receive queue.
+    ///
+    /// The `f` will be called in the same way as in [`Self::recv`].
+    pub fn peek<F, R>(&self, f: F) -> Result<R, crate::errors::raw::RecvError>
+    where
+        F: FnOnce(&[u8], IpAddress) -> R,
+    {
+        let socket = self.0.socket.lock();
+
+        let Some(packet) = socket.peek() else {
+            return Err(crate::errors::raw::RecvError::Exhausted);
+        };
+
+        Ok(f(&packet.data, packet.addr))
+    }
+
+    /// Calls `f` with an immutable reference to the associated [`RawIpSocket`].
+    //
+    // NOTE: If a mutable reference is required, add a method above that correctly updates the next
+    // polling time.
+    pub fn raw_with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&RawIpSocket) -> R,
+    {
+        let socket = self.0.socket.lock();
+        f(&socket)
+    }
+}
+
 impl<T, E> BoundSocketInner<T, E> {
     pub(crate) fn has_new_events(&self) -> bool {
         self.has_new_events.load(Ordering::Relaxed)
@@ -402,6 +1021,58 @@ impl<T, E> BoundSocketInner<T, E> {
     }
 }
 
+/// The information of a bound TCP socket.
+#[derive(Debug, Clone, Copy)]
+pub struct TcpSocketInfo {
+    pub local_endpoint: IpEndpoint,
+    /// The remote endpoint, or `None` if the socket is not connected or connecting.
+    pub remote_endpoint: Option<IpEndpoint>,
+    pub state: RawTcpState,
+    /// The number of bytes in the send buffer.
+    pub send_queue: usize,
+    /// The number of bytes in the receive buffer.
+    pub recv_queue: usize,
+}
+
+/// The information of a bound UDP socket.
+#[derive(Debug, Clone, Copy)]
+pub struct UdpSocketInfo {
+    pub local_endpoint: IpEndpoint,
+    /// The number of bytes in the send buffer.
+    pub send_queue: usize,
+    /// The number of bytes in the receive buffer.
+    pub recv_queue: usize,
+}
+
+impl<E> BoundTcpSocketInner<E> {
+    pub(crate) fn info(&self) -> TcpSocketInfo {
+        let socket = self.socket.lock();
+
+        TcpSocketInfo {
+            // A listening socket has no local endpoint in smoltcp.
+            local_endpoint: socket
+                .local_endpoint()
+                .unwrap_or(IpEndpoint::new(self.local_addr, self.port)),
+            remote_endpoint: socket.remote_endpoint(),
+            state: socket.state(),
+            send_queue: socket.send_queue(),
+            recv_queue: socket.recv_queue(),
+        }
+    }
+}
+
+impl<E> BoundUdpSocketInner<E> {
+    pub(crate) fn info(&self) -> UdpSocketInfo {
+        let socket = self.socket.lock();
+
+        UdpSocketInfo {
+            local_endpoint: IpEndpoint::new(self.local_addr, self.port),
+            send_queue: socket.send_queue(),
+            recv_queue: socket.recv_queue(),
+        }
+    }
+}
+
 impl<E> BoundTcpSocketInner<E> {
     /// Returns whether the TCP socket is dead.
     ///
@@ -524,6 +1195,19 @@ impl<E> BoundUdpSocketInner<E> {
         true
     }
 
+    /// Records that `remote_endpoint` is unreachable.
+    ///
+    /// This method should be called when an ICMP "port unreachable" message is received in
+    /// response to a packet sent from `local_addr` to `remote_endpoint`.
+    pub(crate) fn process_unreachable(&self, local_addr: IpAddress, remote_endpoint: IpEndpoint) {
+        if !self.local_addr.is_unspecified() && self.local_addr != local_addr {
+            return;
+        }
+
+        *self.socket.unreachable.lock() = Some(remote_endpoint);
+        self.has_new_events.store(true, Ordering::Relaxed);
+    }
+
     /// Tries to generate an outgoing packet and dispatches the generated packet.
     pub(crate) fn dispatch<D>(&self, cx: &mut Context, dispatch: D)
     where
@@ -540,3 +1224,124 @@ impl<E> BoundUdpSocketInner<E> {
         self.update_next_poll_at_ms(socket.poll_at(cx));
     }
 }
+
+impl<E> BoundPingSocketInner<E> {
+    /// Tries to process an incoming echo reply and returns whether the reply is processed.
+    pub(crate) fn process(
+        &self,
+        src_addr: IpAddress,
+        dst_addr: IpAddress,
+        echo_repr: &EchoRepr,
+    ) -> bool {
+        if echo_repr.is_request || echo_repr.ident != self.port || dst_addr != self.local_addr {
+            return false;
+        }
+
+        let mut socket = self.socket.lock();
+
+        socket.process(EchoPacket {
+            addr: src_addr,
+            seq_no: echo_repr.seq_no,
+            data: echo_repr.data.to_vec(),
+        });
+        self.update_next_poll_at_ms(socket.poll_at());
+
+        true
+    }
+
+    /// Tries to generate an outgoing echo request and dispatches the generated request.
+    ///
+    /// The `dispatch` will be called with the source address, the destination address, and the
+    /// echo request. Unlike other sockets, the socket lock is released before `dispatch` is
+    /// called, so the request can be processed directly even if the reply is destined to the
+    /// socket itself.
+    pub(crate) fn dispatch<D>(&self, dispatch: D)
+    where
+        D: FnOnce(IpAddress, IpAddress, &EchoRepr),
+    {
+        let mut socket = self.socket.lock();
+
+        let packet = socket.dispatch();
+        self.update_next_poll_at_ms(socket.poll_at());
+
+        drop(socket);
+
+        let Some(packet) = packet else {
+            return;
+        };
+
+        let echo_repr = EchoRepr {
+            is_request: true,
+            ident: self.port,
+            seq_no: packet.seq_no,
+            data: &packet.data,
+        };
+        dispatch(self.local_addr, packet.addr, &echo_repr);
+    }
+}
+
+impl<E> BoundRawIpSocketInner<E> {
+    /// Tries to process an incoming IP packet and returns whether the packet is processed.
+    ///
+    /// The `packet` is the whole IP packet, including the IP header. The caller should make sure
+    /// that the protocol of the packet matches the port that the socket is bound to.
+    pub(crate) fn process(&self, src_addr: IpAddress, dst_addr: IpAddress, packet: &[u8]) -> bool {
+        if dst_addr.is_unicast() && dst_addr != self.local_addr {
+            return false;
+        }
+
+        let mut socket = self.socket.lock();
+
+        if self.port == u8::from(IpProtocol::Icmp) as u16 && socket.is_icmp_filtered(packet) {
+            return false;
+        }
+
+        socket.process(RawIpPacket {
+            addr: src_addr,
+            data: packet.to_vec(),
+            header: None,
+        });
+        self.update_next_poll_at_ms(socket.poll_at());
+
+        true
+    }
+
+    /// Tries to generate an outgoing IP packet and dispatches the generated packet.
+    ///
+    /// The `dispatch` will be called with the IP header and the IP payload. Like ping sockets,
+    /// the socket lock is released before `dispatch` is called, so the packet can be processed
+    /// directly even if it is destined to the socket itself.
+    pub(crate) fn dispatch<D>(&self, dispatch: D)
+    where
+        D: FnOnce(&IpRepr, &[u8]),
+    {
+        let mut socket = self.socket.lock();
+
+        let packet = socket.dispatch();
+        self.update_next_poll_at_ms(socket.poll_at());
+
+        drop(socket);
+
+        let Some(packet) = packet else {
+            return;
+        };
+
+        let ip_repr = match packet.header {
+            Some(header) => IpRepr::new(
+                header.src_addr,
+                packet.addr,
+                header.protocol,
+                packet.data.len(),
+                header.hop_limit,
+            ),
+            None => IpRepr::new(
+                self.local_addr,
+                packet.addr,
+                IpProtocol::from(self.port as u8),
+                packet.data.len(),
+                64,
+            ),
+        };
+        dispatch(&ip_repr, &packet.data);
+    }
+}
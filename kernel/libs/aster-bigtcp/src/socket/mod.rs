@@ -2,15 +2,30 @@
 
 mod bound;
 mod event;
+mod option;
+mod ping;
+mod queue;
+mod raw;
 mod unbound;
 
-pub use bound::{BoundTcpSocket, BoundUdpSocket};
-pub(crate) use bound::{BoundTcpSocketInner, BoundUdpSocketInner, TcpProcessResult};
+pub use bound::{
+    BoundPingSocket, BoundRawIpSocket, BoundTcpSocket, BoundUdpSocket, TcpSocketInfo, UdpSocketInfo,
+};
+pub(crate) use bound::{
+    BoundPingSocketInner, BoundRawIpSocketInner, BoundTcpSocketInner, BoundUdpSocketInner,
+    TcpProcessResult,
+};
 pub use event::SocketEventObserver;
+pub use option::{KeepAlive, RawTcpOption};
+pub(crate) use ping::EchoRepr;
+pub use ping::RawPingSocket;
+pub use raw::RawIpSocket;
 pub use unbound::{
-    UnboundTcpSocket, UnboundUdpSocket, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN, UDP_RECV_PAYLOAD_LEN,
-    UDP_SEND_PAYLOAD_LEN,
+    UnboundPingSocket, UnboundRawIpSocket, UnboundTcpSocket, UnboundUdpSocket,
+    PING_RECV_PAYLOAD_LEN, PING_SEND_PAYLOAD_LEN, RAW_IP_RECV_PAYLOAD_LEN, RAW_IP_SEND_PAYLOAD_LEN,
+    TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN, UDP_RECV_PAYLOAD_LEN, UDP_SEND_PAYLOAD_LEN,
 };
 
 pub type RawTcpSocket = smoltcp::socket::tcp::Socket<'static>;
+pub type RawTcpState = smoltcp::socket::tcp::State;
 pub type RawUdpSocket = smoltcp::socket::udp::Socket<'static>;
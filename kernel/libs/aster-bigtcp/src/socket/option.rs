@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+/// TCP options that are applied to the underlying [`RawTcpSocket`].
+///
+/// The options can be applied to a socket at any time, even when it is not yet bound (see
+/// [`UnboundTcpSocket::set_raw_option`]) or already connected (see
+/// [`BoundTcpSocket::set_raw_option`]).
+///
+/// [`RawTcpSocket`]: super::RawTcpSocket
+/// [`UnboundTcpSocket::set_raw_option`]: super::UnboundTcpSocket::set_raw_option
+/// [`BoundTcpSocket::set_raw_option`]: super::BoundTcpSocket::set_raw_option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawTcpOption {
+    /// Whether Nagle's algorithm is enabled.
+    pub is_nagle_enabled: bool,
+    /// Whether the socket is corked.
+    ///
+    /// A corked socket only sends full-sized segments. A partial segment is held back until the
+    /// socket is uncorked, the socket is closed, or 200 milliseconds have elapsed.
+    pub is_corked: bool,
+    /// The keep-alive configuration, or `None` if keep-alive is disabled.
+    pub keep_alive: Option<KeepAlive>,
+}
+
+/// The keep-alive configuration of a TCP socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAlive {
+    /// The duration that the connection must stay idle before the first probe is sent.
+    pub idle: Duration,
+    /// The interval between two consecutive probes.
+    pub interval: Duration,
+    /// The number of unanswered probes after which the connection is aborted.
+    pub count: u32,
+}
+
+impl Default for RawTcpOption {
+    fn default() -> Self {
+        Self {
+            is_nagle_enabled: true,
+            is_corked: false,
+            keep_alive: None,
+        }
+    }
+}
+
+/// The maximum duration for which a partial segment is held back by a corked socket.
+///
+/// Reference: <https://man7.org/linux/man-pages/man7/tcp.7.html>.
+pub(super) const CORK_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub(super) fn to_smoltcp_duration(duration: Duration) -> smoltcp::time::Duration {
+    smoltcp::time::Duration::from_micros(duration.as_micros() as u64)
+}
@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::{vec, vec::Vec};
+
+use smoltcp::{
+    iface::packet::{IpPayload, Packet},
+    phy::ChecksumCapabilities,
+    socket::PollAt,
+    wire::{
+        Icmpv4Message, Icmpv4Packet, Icmpv4Repr, Icmpv6Message, Icmpv6Packet, Icmpv6Repr,
+        IpAddress, IpProtocol, Ipv4Repr, Ipv6Repr,
+    },
+};
+
+use super::queue::{PacketQueue, QueuedPacket};
+
+/// A ping socket, which sends ICMP echo requests and receives ICMP echo replies.
+///
+/// The identifier of the echo messages is the port that the socket is bound to, so it is not
+/// stored here.
+pub struct RawPingSocket {
+    rx_queue: PacketQueue<EchoPacket>,
+    tx_queue: PacketQueue<EchoPacket>,
+}
+
+/// An echo message waiting in the receive or send queue.
+pub(crate) struct EchoPacket {
+    /// The remote address, i.e., the source address for received messages and the destination
+    /// address for messages to send.
+    pub(crate) addr: IpAddress,
+    pub(crate) seq_no: u16,
+    pub(crate) data: Vec<u8>,
+}
+
+impl QueuedPacket for EchoPacket {
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl RawPingSocket {
+    pub(crate) fn new(rx_capacity: usize, tx_capacity: usize) -> Self {
+        Self {
+            rx_queue: PacketQueue::new(rx_capacity),
+            tx_queue: PacketQueue::new(tx_capacity),
+        }
+    }
+
+    /// Returns whether there are received echo replies.
+    pub fn can_recv(&self) -> bool {
+        !self.rx_queue.is_empty()
+    }
+
+    /// Returns whether there is free space in the send queue.
+    pub fn can_send(&self) -> bool {
+        !self.tx_queue.is_full()
+    }
+
+    /// Returns the maximum length of the data in an echo request.
+    pub fn payload_send_capacity(&self) -> usize {
+        self.tx_queue.capacity()
+    }
+
+    /// Queues an echo request to send.
+    ///
+    /// This method returns `false` if there is no enough space in the send queue.
+    pub(crate) fn send(&mut self, packet: EchoPacket) -> bool {
+        self.tx_queue.push(packet)
+    }
+
+    /// Returns the first received echo reply without dequeuing it.
+    pub(crate) fn peek(&self) -> Option<&EchoPacket> {
+        self.rx_queue.peek()
+    }
+
+    /// Dequeues a received echo reply.
+    pub(crate) fn recv(&mut self) -> Option<EchoPacket> {
+        self.rx_queue.pop()
+    }
+
+    /// Queues an incoming echo reply.
+    ///
+    /// The reply is silently dropped if there is no enough space in the receive queue.
+    pub(crate) fn process(&mut self, packet: EchoPacket) {
+        let _ = self.rx_queue.push(packet);
+    }
+
+    /// Dequeues an echo request to send.
+    pub(crate) fn dispatch(&mut self) -> Option<EchoPacket> {
+        self.tx_queue.pop()
+    }
+
+    pub(crate) fn poll_at(&self) -> PollAt {
+        if self.tx_queue.is_empty() {
+            PollAt::Ingress
+        } else {
+            PollAt::Now
+        }
+    }
+}
+
+/// An ICMP or ICMPv6 echo request or reply.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EchoRepr<'a> {
+    pub(crate) is_request: bool,
+    pub(crate) ident: u16,
+    pub(crate) seq_no: u16,
+    pub(crate) data: &'a [u8],
+}
+
+impl<'a> EchoRepr<'a> {
+    /// Parses an echo request specified by the user.
+    ///
+    /// The identifier and the checksum in the message are ignored, since they are always filled
+    /// in by the network stack. This method returns `None` if the message is not an echo request
+    /// of the IP version.
+    pub(crate) fn parse_request(is_ipv4: bool, message: &'a [u8]) -> Option<Self> {
+        let (seq_no, data) = if is_ipv4 {
+            let packet = Icmpv4Packet::new_checked(message).ok()?;
+            if packet.msg_type() != Icmpv4Message::EchoRequest || packet.msg_code() != 0 {
+                return None;
+            }
+            (packet.echo_seq_no(), packet.data())
+        } else {
+            let packet = Icmpv6Packet::new_checked(message).ok()?;
+            if packet.msg_type() != Icmpv6Message::EchoRequest || packet.msg_code() != 0 {
+                return None;
+            }
+            (packet.echo_seq_no(), packet.payload())
+        };
+
+        Some(Self {
+            is_request: true,
+            ident: 0,
+            seq_no,
+            data,
+        })
+    }
+
+    /// Builds the IP packet that carries the echo message.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `src_addr` and `dst_addr` are in different address families.
+    pub(crate) fn to_packet(self, src_addr: IpAddress, dst_addr: IpAddress) -> Packet<'a> {
+        match (src_addr, dst_addr) {
+            (IpAddress::Ipv4(src_addr), IpAddress::Ipv4(dst_addr)) => {
+                let icmp_repr = self.to_icmpv4_repr();
+                Packet::new_ipv4(
+                    Ipv4Repr {
+                        src_addr,
+                        dst_addr,
+                        next_header: IpProtocol::Icmp,
+                        payload_len: icmp_repr.buffer_len(),
+                        hop_limit: 64,
+                    },
+                    IpPayload::Icmpv4(icmp_repr),
+                )
+            }
+            (IpAddress::Ipv6(src_addr), IpAddress::Ipv6(dst_addr)) => {
+                let icmp_repr = self.to_icmpv6_repr();
+                Packet::new_ipv6(
+                    Ipv6Repr {
+                        src_addr,
+                        dst_addr,
+                        next_header: IpProtocol::Icmpv6,
+                        payload_len: icmp_repr.buffer_len(),
+                        hop_limit: 64,
+                    },
+                    IpPayload::Icmpv6(icmp_repr),
+                )
+            }
+            _ => panic!("the source and destination addresses are in different address families"),
+        }
+    }
+
+    /// Emits the ICMP message that is reported to the user.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `src_addr` and `dst_addr` are in different address families.
+    pub(crate) fn to_message(self, src_addr: IpAddress, dst_addr: IpAddress) -> Vec<u8> {
+        match (src_addr, dst_addr) {
+            (IpAddress::Ipv4(_), IpAddress::Ipv4(_)) => {
+                let icmp_repr = self.to_icmpv4_repr();
+                let mut message = vec![0; icmp_repr.buffer_len()];
+                icmp_repr.emit(
+                    &mut Icmpv4Packet::new_unchecked(message.as_mut_slice()),
+                    &ChecksumCapabilities::default(),
+                );
+                message
+            }
+            (IpAddress::Ipv6(src_addr), IpAddress::Ipv6(dst_addr)) => {
+                let icmp_repr = self.to_icmpv6_repr();
+                let mut message = vec![0; icmp_repr.buffer_len()];
+                icmp_repr.emit(
+                    &src_addr,
+                    &dst_addr,
+                    &mut Icmpv6Packet::new_unchecked(message.as_mut_slice()),
+                    &ChecksumCapabilities::default(),
+                );
+                message
+            }
+            _ => panic!("the source and destination addresses are in different address families"),
+        }
+    }
+
+    fn to_icmpv4_repr(self) -> Icmpv4Repr<'a> {
+        if self.is_request {
+            Icmpv4Repr::EchoRequest {
+                ident: self.ident,
+                seq_no: self.seq_no,
+                data: self.data,
+            }
+        } else {
+            Icmpv4Repr::EchoReply {
+                ident: self.ident,
+                seq_no: self.seq_no,
+                data: self.data,
+            }
+        }
+    }
+
+    fn to_icmpv6_repr(self) -> Icmpv6Repr<'a> {
+        if self.is_request {
+            Icmpv6Repr::EchoRequest {
+                ident: self.ident,
+                seq_no: self.seq_no,
+                data: self.data,
+            }
+        } else {
+            Icmpv6Repr::EchoReply {
+                ident: self.ident,
+                seq_no: self.seq_no,
+                data: self.data,
+            }
+        }
+    }
+}
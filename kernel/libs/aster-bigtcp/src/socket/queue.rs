@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::vec_deque::VecDeque;
+
+/// A packet that can be queued in a [`PacketQueue`].
+pub(super) trait QueuedPacket {
+    /// Returns the length of the data, which is counted against the capacity of the queue.
+    fn data_len(&self) -> usize;
+}
+
+/// A queue of packets whose total data length is limited.
+pub(super) struct PacketQueue<P> {
+    packets: VecDeque<P>,
+    /// The total length of the data in `packets`.
+    len: usize,
+    capacity: usize,
+}
+
+impl<P: QueuedPacket> PacketQueue<P> {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            packets: VecDeque::new(),
+            len: 0,
+            capacity,
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    pub(super) fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Pushes a packet to the back of the queue.
+    ///
+    /// This method returns `false` if there is no enough space for the packet.
+    pub(super) fn push(&mut self, packet: P) -> bool {
+        if self.len + packet.data_len() > self.capacity {
+            return false;
+        }
+
+        self.len += packet.data_len();
+        self.packets.push_back(packet);
+
+        true
+    }
+
+    pub(super) fn peek(&self) -> Option<&P> {
+        self.packets.front()
+    }
+
+    pub(super) fn pop(&mut self) -> Option<P> {
+        let packet = self.packets.pop_front()?;
+        self.len -= packet.data_len();
+
+        Some(packet)
+    }
+}
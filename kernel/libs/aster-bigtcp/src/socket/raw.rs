@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::vec::Vec;
+
+use smoltcp::{
+    socket::PollAt,
+    wire::{IpAddress, IpProtocol, Ipv4Packet},
+};
+
+use super::queue::{PacketQueue, QueuedPacket};
+
+/// A raw IP socket, which sends and receives IP packets of a specific protocol.
+///
+/// The protocol is the port that the socket is bound to, so it is not stored here.
+pub struct RawIpSocket {
+    rx_queue: PacketQueue<RawIpPacket>,
+    tx_queue: PacketQueue<RawIpPacket>,
+    /// The ICMP types to drop, where the bit `1 << type` represents the ICMP type `type`.
+    ///
+    /// The filter only applies to raw IP sockets of the ICMP protocol.
+    icmp_filter: u32,
+}
+
+/// An IP packet waiting in the receive or send queue.
+pub(crate) struct RawIpPacket {
+    /// The remote address, i.e., the source address for received packets and the destination
+    /// address for packets to send.
+    pub(crate) addr: IpAddress,
+    /// The whole IP packet for received packets, or the IP payload for packets to send.
+    pub(crate) data: Vec<u8>,
+    /// The IP header fields specified by the user for packets to send, or `None` if the IP
+    /// header should be generated from the socket.
+    pub(crate) header: Option<RawIpHeader>,
+}
+
+/// The IP header fields of a packet to send whose header is included by the user.
+#[derive(Clone, Copy)]
+pub(crate) struct RawIpHeader {
+    pub(crate) src_addr: IpAddress,
+    pub(crate) protocol: IpProtocol,
+    pub(crate) hop_limit: u8,
+}
+
+impl QueuedPacket for RawIpPacket {
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl RawIpSocket {
+    pub(crate) fn new(rx_capacity: usize, tx_capacity: usize) -> Self {
+        Self {
+            rx_queue: PacketQueue::new(rx_capacity),
+            tx_queue: PacketQueue::new(tx_capacity),
+            icmp_filter: 0,
+        }
+    }
+
+    /// Returns whether there are received packets.
+    pub fn can_recv(&self) -> bool {
+        !self.rx_queue.is_empty()
+    }
+
+    /// Returns whether there is free space in the send queue.
+    pub fn can_send(&self) -> bool {
+        !self.tx_queue.is_full()
+    }
+
+    /// Returns the ICMP types to drop, as a bit mask.
+    pub fn icmp_filter(&self) -> u32 {
+        self.icmp_filter
+    }
+
+    /// Sets the ICMP types to drop, as a bit mask.
+    pub fn set_icmp_filter(&mut self, icmp_filter: u32) {
+        self.icmp_filter = icmp_filter;
+    }
+
+    /// Returns whether the ICMP message in the IPv4 `packet` should be dropped by the filter.
+    ///
+    /// Like Linux, the packet is dropped if it is too short to contain the ICMP type, while the
+    /// ICMP types that cannot be represented in the bit mask are never dropped.
+    pub(crate) fn is_icmp_filtered(&self, packet: &[u8]) -> bool {
+        let header_len = Ipv4Packet::new_unchecked(packet).header_len() as usize;
+        let Some(&icmp_type) = packet.get(header_len) else {
+            return true;
+        };
+
+        icmp_type < 32 && self.icmp_filter & (1 << icmp_type) != 0
+    }
+
+    /// Returns the maximum length of the IP payload in a packet to send.
+    pub fn payload_send_capacity(&self) -> usize {
+        self.tx_queue.capacity()
+    }
+
+    /// Queues a packet to send.
+    ///
+    /// This method returns `false` if there is no enough space in the send queue.
+    pub(crate) fn send(&mut self, packet: RawIpPacket) -> bool {
+        self.tx_queue.push(packet)
+    }
+
+    /// Returns the first received packet without dequeuing it.
+    pub(crate) fn peek(&self) -> Option<&RawIpPacket> {
+        self.rx_queue.peek()
+    }
+
+    /// Dequeues a received packet.
+    pub(crate) fn recv(&mut self) -> Option<RawIpPacket> {
+        self.rx_queue.pop()
+    }
+
+    /// Queues an incoming packet.
+    ///
+    /// The packet is silently dropped if there is no enough space in the receive queue.
+    pub(crate) fn process(&mut self, packet: RawIpPacket) {
+        let _ = self.rx_queue.push(packet);
+    }
+
+    /// Dequeues a packet to send.
+    pub(crate) fn dispatch(&mut self) -> Option<RawIpPacket> {
+        self.tx_queue.pop()
+    }
+
+    pub(crate) fn poll_at(&self) -> PollAt {
+        if self.tx_queue.is_empty() {
+            PollAt::Ingress
+        } else {
+            PollAt::Now
+        }
+    }
+}
@@ -2,28 +2,67 @@
 
 use alloc::{boxed::Box, sync::Weak, vec};
 
-use super::{event::SocketEventObserver, RawTcpSocket, RawUdpSocket};
+use super::{
+    bound::RawTcpSocketExt, event::SocketEventObserver, RawIpSocket, RawPingSocket, RawTcpOption,
+    RawTcpSocket, RawUdpSocket,
+};
 
 pub struct UnboundSocket<T> {
     socket: Box<T>,
     observer: Weak<dyn SocketEventObserver>,
 }
 
-pub type UnboundTcpSocket = UnboundSocket<RawTcpSocket>;
+pub type UnboundTcpSocket = UnboundSocket<RawTcpSocketExt>;
 pub type UnboundUdpSocket = UnboundSocket<RawUdpSocket>;
+pub type UnboundPingSocket = UnboundSocket<RawPingSocket>;
+pub type UnboundRawIpSocket = UnboundSocket<RawIpSocket>;
 
 impl UnboundTcpSocket {
     pub fn new(observer: Weak<dyn SocketEventObserver>) -> Self {
-        let raw_tcp_socket = {
-            let rx_buffer = smoltcp::socket::tcp::SocketBuffer::new(vec![0u8; TCP_RECV_BUF_LEN]);
-            let tx_buffer = smoltcp::socket::tcp::SocketBuffer::new(vec![0u8; TCP_SEND_BUF_LEN]);
-            RawTcpSocket::new(rx_buffer, tx_buffer)
-        };
+        Self::new_with_buf_len(observer, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN)
+    }
+
+    /// Creates a socket with the specified lengths of the receive buffer and the send buffer.
+    pub fn new_with_buf_len(
+        observer: Weak<dyn SocketEventObserver>,
+        recv_buf_len: usize,
+        send_buf_len: usize,
+    ) -> Self {
         Self {
-            socket: Box::new(raw_tcp_socket),
+            socket: Box::new(RawTcpSocketExt::new(Box::new(new_raw_tcp_socket(
+                recv_buf_len,
+                send_buf_len,
+            )))),
             observer,
         }
     }
+
+    /// Changes the lengths of the receive buffer and the send buffer.
+    ///
+    /// The options that have been set on the socket are preserved.
+    pub fn set_buf_len(&mut self, recv_buf_len: usize, send_buf_len: usize) {
+        if self.socket.recv_capacity() == recv_buf_len
+            && self.socket.send_capacity() == send_buf_len
+        {
+            return;
+        }
+
+        let mut raw_tcp_socket = new_raw_tcp_socket(recv_buf_len, send_buf_len);
+        raw_tcp_socket.set_hop_limit(self.socket.hop_limit());
+
+        self.socket.set_raw(raw_tcp_socket);
+    }
+
+    /// Sets the TCP options.
+    pub fn set_raw_option(&mut self, option: &RawTcpOption) {
+        self.socket.set_raw_option(option);
+    }
+}
+
+fn new_raw_tcp_socket(recv_buf_len: usize, send_buf_len: usize) -> RawTcpSocket {
+    let rx_buffer = smoltcp::socket::tcp::SocketBuffer::new(vec![0u8; recv_buf_len]);
+    let tx_buffer = smoltcp::socket::tcp::SocketBuffer::new(vec![0u8; send_buf_len]);
+    RawTcpSocket::new(rx_buffer, tx_buffer)
 }
 
 impl UnboundUdpSocket {
@@ -47,6 +86,31 @@ impl UnboundUdpSocket {
     }
 }
 
+impl UnboundPingSocket {
+    pub fn new(observer: Weak<dyn SocketEventObserver>) -> Self {
+        let raw_ping_socket = RawPingSocket::new(PING_RECV_PAYLOAD_LEN, PING_SEND_PAYLOAD_LEN);
+        Self {
+            socket: Box::new(raw_ping_socket),
+            observer,
+        }
+    }
+}
+
+impl UnboundRawIpSocket {
+    pub fn new(observer: Weak<dyn SocketEventObserver>) -> Self {
+        let raw_ip_socket = RawIpSocket::new(RAW_IP_RECV_PAYLOAD_LEN, RAW_IP_SEND_PAYLOAD_LEN);
+        Self {
+            socket: Box::new(raw_ip_socket),
+            observer,
+        }
+    }
+
+    /// Sets the ICMP types to drop, as a bit mask.
+    pub fn set_icmp_filter(&mut self, icmp_filter: u32) {
+        self.socket.set_icmp_filter(icmp_filter);
+    }
+}
+
 impl<T> UnboundSocket<T> {
     pub(crate) fn into_raw(self) -> (Box<T>, Weak<dyn SocketEventObserver>) {
         (self.socket, self.observer)
@@ -65,7 +129,8 @@ impl<T> UnboundSocket<T> {
 // abnormally (see <https://github.com/asterinas/asterinas/pull/1396>). So the socket buffer size
 // is increased from 64K to 128K.
 //
-// TODO: Consider allowing user programs to set the socket buffer length via `setsockopt` system calls.
+// These are only the default values. User programs can change the buffer lengths via the
+// `SO_RCVBUF` and `SO_SNDBUF` socket options before the socket is bound.
 pub const TCP_RECV_BUF_LEN: usize = 65536 * 2;
 pub const TCP_SEND_BUF_LEN: usize = 65536 * 2;
 
@@ -73,3 +138,11 @@ pub const TCP_SEND_BUF_LEN: usize = 65536 * 2;
 pub const UDP_SEND_PAYLOAD_LEN: usize = 65536;
 pub const UDP_RECV_PAYLOAD_LEN: usize = 65536;
 const UDP_METADATA_LEN: usize = 256;
+
+// Ping socket buffer sizes:
+pub const PING_SEND_PAYLOAD_LEN: usize = 65536;
+pub const PING_RECV_PAYLOAD_LEN: usize = 65536;
+
+// Raw IP socket buffer sizes:
+pub const RAW_IP_SEND_PAYLOAD_LEN: usize = 65536;
+pub const RAW_IP_RECV_PAYLOAD_LEN: usize = 65536;
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The architecture-specific parts of `ptrace`.
+
+use ostd::{
+    cpu::{UserContext, UserContextApi},
+    Pod,
+};
+
+use crate::{cpu::LinuxAbi, prelude::*};
+
+/// The registers of a traced thread, in the layout of `struct user_regs_struct` in Linux.
+#[derive(Debug, Clone, Copy, Pod, Default)]
+#[repr(C)]
+pub struct UserRegs {
+    pub pc: usize,
+    pub ra: usize,
+    pub sp: usize,
+    pub gp: usize,
+    pub tp: usize,
+    pub t0: usize,
+    pub t1: usize,
+    pub t2: usize,
+    pub s0: usize,
+    pub s1: usize,
+    pub a0: usize,
+    pub a1: usize,
+    pub a2: usize,
+    pub a3: usize,
+    pub a4: usize,
+    pub a5: usize,
+    pub a6: usize,
+    pub a7: usize,
+    pub s2: usize,
+    pub s3: usize,
+    pub s4: usize,
+    pub s5: usize,
+    pub s6: usize,
+    pub s7: usize,
+    pub s8: usize,
+    pub s9: usize,
+    pub s10: usize,
+    pub s11: usize,
+    pub t3: usize,
+    pub t4: usize,
+    pub t5: usize,
+    pub t6: usize,
+}
+
+macro_rules! copy_user_regs {
+    ($src: ident, $dst: ident) => {
+        $dst.ra = $src.ra;
+        $dst.sp = $src.sp;
+        $dst.gp = $src.gp;
+        $dst.tp = $src.tp;
+        $dst.t0 = $src.t0;
+        $dst.t1 = $src.t1;
+        $dst.t2 = $src.t2;
+        $dst.s0 = $src.s0;
+        $dst.s1 = $src.s1;
+        $dst.a0 = $src.a0;
+        $dst.a1 = $src.a1;
+        $dst.a2 = $src.a2;
+        $dst.a3 = $src.a3;
+        $dst.a4 = $src.a4;
+        $dst.a5 = $src.a5;
+        $dst.a6 = $src.a6;
+        $dst.a7 = $src.a7;
+        $dst.s2 = $src.s2;
+        $dst.s3 = $src.s3;
+        $dst.s4 = $src.s4;
+        $dst.s5 = $src.s5;
+        $dst.s6 = $src.s6;
+        $dst.s7 = $src.s7;
+        $dst.s8 = $src.s8;
+        $dst.s9 = $src.s9;
+        $dst.s10 = $src.s10;
+        $dst.s11 = $src.s11;
+        $dst.t3 = $src.t3;
+        $dst.t4 = $src.t4;
+        $dst.t5 = $src.t5;
+        $dst.t6 = $src.t6;
+    };
+}
+
+impl UserRegs {
+    /// Gets the registers from the user context.
+    ///
+    /// On RISC-V, the syscall number is kept in `a7`, so `syscall_num` is ignored.
+    pub fn from_context(user_ctx: &UserContext, _syscall_num: Option<usize>) -> Self {
+        let regs = user_ctx.general_regs();
+        let mut user_regs = Self {
+            pc: user_ctx.instruction_pointer(),
+            ..Default::default()
+        };
+        copy_user_regs!(regs, user_regs);
+        user_regs
+    }
+
+    /// Sets the registers to the user context.
+    ///
+    /// Returns the new number of the syscall that the thread is executing,
+    /// which can be changed by the tracer at the syscall-entry stop.
+    pub fn copy_to_context(&self, user_ctx: &mut UserContext) -> Result<usize> {
+        user_ctx.set_instruction_pointer(self.pc);
+        let regs = user_ctx.general_regs_mut();
+        copy_user_regs!(self, regs);
+
+        Ok(user_ctx.syscall_num())
+    }
+}
+
+/// Enables or disables the single-stepping of the user context.
+pub fn set_single_step(_user_ctx: &mut UserContext, is_enabled: bool) -> Result<()> {
+    if is_enabled {
+        // RISC-V has no hardware single-stepping for the user space.
+        return_errno_with_message!(Errno::EIO, "single-stepping is not supported");
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The architecture-specific parts of `ptrace`.
+
+use ostd::{cpu::UserContext, Pod};
+
+use crate::prelude::*;
+
+/// The registers of a traced thread, in the layout of `struct user_regs_struct` in Linux.
+#[derive(Debug, Clone, Copy, Pod, Default)]
+#[repr(C)]
+pub struct UserRegs {
+    pub r15: usize,
+    pub r14: usize,
+    pub r13: usize,
+    pub r12: usize,
+    pub rbp: usize,
+    pub rbx: usize,
+    pub r11: usize,
+    pub r10: usize,
+    pub r9: usize,
+    pub r8: usize,
+    pub rax: usize,
+    pub rcx: usize,
+    pub rdx: usize,
+    pub rsi: usize,
+    pub rdi: usize,
+    pub orig_rax: usize,
+    pub rip: usize,
+    pub cs: usize,
+    pub rflags: usize,
+    pub rsp: usize,
+    pub ss: usize,
+    pub fs_base: usize,
+    pub gs_base: usize,
+    pub ds: usize,
+    pub es: usize,
+    pub fs: usize,
+    pub gs: usize,
+}
+
+// The segment selectors of the user space, which are the same as Linux.
+const USER_CS: usize = 0x33;
+const USER_SS: usize = 0x2b;
+
+/// The flags in `rflags` that can be modified by the tracer.
+const USER_RFLAGS_MASK: usize = 0x1 // CF
+    | 0x4 // PF
+    | 0x10 // AF
+    | 0x40 // ZF
+    | 0x80 // SF
+    | 0x100 // TF
+    | 0x400 // DF
+    | 0x800 // OF
+    | 0x40000; // AC
+
+/// The trap flag, which makes the CPU raise a debug exception after executing one instruction.
+const RFLAGS_TF: usize = 0x100;
+
+impl UserRegs {
+    /// Gets the registers from the user context.
+    ///
+    /// `syscall_num` is the number of the syscall that the thread is executing, if any.
+    pub fn from_context(user_ctx: &UserContext, syscall_num: Option<usize>) -> Self {
+        let regs = user_ctx.general_regs();
+        Self {
+            r15: regs.r15,
+            r14: regs.r14,
+            r13: regs.r13,
+            r12: regs.r12,
+            rbp: regs.rbp,
+            rbx: regs.rbx,
+            r11: regs.r11,
+            r10: regs.r10,
+            r9: regs.r9,
+            r8: regs.r8,
+            rax: regs.rax,
+            rcx: regs.rcx,
+            rdx: regs.rdx,
+            rsi: regs.rsi,
+            rdi: regs.rdi,
+            orig_rax: syscall_num.unwrap_or(usize::MAX),
+            rip: regs.rip,
+            cs: USER_CS,
+            rflags: regs.rflags,
+            rsp: regs.rsp,
+            ss: USER_SS,
+            fs_base: regs.fsbase,
+            gs_base: regs.gsbase,
+            ds: 0,
+            es: 0,
+            fs: 0,
+            gs: 0,
+        }
+    }
+
+    /// Sets the registers to the user context.
+    ///
+    /// Returns the new number of the syscall that the thread is executing,
+    /// which can be changed by the tracer at the syscall-entry stop.
+    pub fn copy_to_context(&self, user_ctx: &mut UserContext) -> Result<usize> {
+        if !is_canonical(self.fs_base) || !is_canonical(self.gs_base) {
+            return_errno_with_message!(Errno::EIO, "the segment base is not canonical");
+        }
+
+        let regs = user_ctx.general_regs_mut();
+        regs.r15 = self.r15;
+        regs.r14 = self.r14;
+        regs.r13 = self.r13;
+        regs.r12 = self.r12;
+        regs.rbp = self.rbp;
+        regs.rbx = self.rbx;
+        regs.r11 = self.r11;
+        regs.r10 = self.r10;
+        regs.r9 = self.r9;
+        regs.r8 = self.r8;
+        regs.rax = self.rax;
+        regs.rcx = self.rcx;
+        regs.rdx = self.rdx;
+        regs.rsi = self.rsi;
+        regs.rdi = self.rdi;
+        regs.rip = self.rip;
+        regs.rflags = (regs.rflags & !USER_RFLAGS_MASK) | (self.rflags & USER_RFLAGS_MASK);
+        regs.rsp = self.rsp;
+        regs.fsbase = self.fs_base;
+        regs.gsbase = self.gs_base;
+
+        Ok(self.orig_rax)
+    }
+}
+
+fn is_canonical(addr: usize) -> bool {
+    let high_bits = addr >> 47;
+    high_bits == 0 || high_bits == (usize::MAX >> 47)
+}
+
+/// Enables or disables the single-stepping of the user context.
+pub fn set_single_step(user_ctx: &mut UserContext, is_enabled: bool) -> Result<()> {
+    let regs = user_ctx.general_regs_mut();
+    if is_enabled {
+        regs.rflags |= RFLAGS_TF;
+    } else {
+        regs.rflags &= !RFLAGS_TF;
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use aster_util::slot_vec::SlotVec;
+use inherit_methods_macro::inherit_methods;
+
+use super::{alloc_ino, file::CgroupFile, CgroupFs, Common, BLOCK_SIZE, CGROUP2_ROOT_INO};
+use crate::{
+    fs::utils::{
+        DirentVisitor, FileSystem, Inode, InodeMode, InodeType, Metadata, MknodType, NAME_MAX,
+    },
+    prelude::*,
+    process::{cgroup::Cgroup, Gid, Uid},
+};
+
+/// A directory of the cgroup2 file system, which represents a cgroup.
+///
+/// The children are the interface files of the cgroup, followed by the child cgroups.
+pub struct CgroupDir {
+    cgroup: Arc<Cgroup>,
+    this: Weak<CgroupDir>,
+    parent: Option<Weak<CgroupDir>>,
+    children: RwMutex<SlotVec<(String, Arc<dyn Inode>)>>,
+    common: Common,
+}
+
+impl CgroupDir {
+    pub(super) fn new_root(fs: Weak<CgroupFs>) -> Arc<Self> {
+        Self::new(Cgroup::root().clone(), CGROUP2_ROOT_INO, None, fs)
+    }
+
+    fn new(
+        cgroup: Arc<Cgroup>,
+        ino: u64,
+        parent: Option<Weak<CgroupDir>>,
+        fs: Weak<CgroupFs>,
+    ) -> Arc<Self> {
+        let metadata = Metadata::new_dir(ino, InodeMode::from_bits_truncate(0o755), BLOCK_SIZE);
+        let files = CgroupFile::new_all(&cgroup, &fs)
+            .into_iter()
+            .map(|file| (String::from(file.name()), file as Arc<dyn Inode>))
+            .collect::<Vec<_>>();
+        let mut children = SlotVec::with_capacity(files.len());
+        for file in files {
+            children.put(file);
+        }
+
+        Arc::new_cyclic(|weak_self| Self {
+            cgroup,
+            this: weak_self.clone(),
+            parent,
+            children: RwMutex::new(children),
+            common: Common::new(metadata, fs),
+        })
+    }
+
+    pub fn cgroup(&self) -> &Arc<Cgroup> {
+        &self.cgroup
+    }
+
+    fn this(&self) -> Arc<CgroupDir> {
+        self.this.upgrade().unwrap()
+    }
+
+    fn parent(&self) -> Option<Arc<CgroupDir>> {
+        self.parent.as_ref().and_then(|parent| parent.upgrade())
+    }
+
+    /// Returns whether the directory has any child cgroups.
+    fn has_child_cgroups(&self) -> bool {
+        self.children
+            .read()
+            .iter()
+            .any(|(_, child)| child.type_() == InodeType::Dir)
+    }
+}
+
+#[inherit_methods(from = "self.common")]
+impl Inode for CgroupDir {
+    fn size(&self) -> usize;
+    fn metadata(&self) -> Metadata;
+    fn ino(&self) -> u64;
+    fn mode(&self) -> Result<InodeMode>;
+    fn set_mode(&self, mode: InodeMode) -> Result<()>;
+    fn owner(&self) -> Result<Uid>;
+    fn set_owner(&self, uid: Uid) -> Result<()>;
+    fn group(&self) -> Result<Gid>;
+    fn set_group(&self, gid: Gid) -> Result<()>;
+    fn atime(&self) -> Duration;
+    fn set_atime(&self, time: Duration);
+    fn mtime(&self) -> Duration;
+    fn set_mtime(&self, time: Duration);
+    fn ctime(&self) -> Duration;
+    fn set_ctime(&self, time: Duration);
+    fn fs(&self) -> Arc<dyn FileSystem>;
+
+    fn resize(&self, _new_size: usize) -> Result<()> {
+        Err(Error::new(Errno::EISDIR))
+    }
+
+    fn type_(&self) -> InodeType {
+        InodeType::Dir
+    }
+
+    fn create(&self, name: &str, type_: InodeType, _mode: InodeMode) -> Result<Arc<dyn Inode>> {
+        if type_ != InodeType::Dir {
+            return_errno_with_message!(Errno::EPERM, "only directories can be created in cgroupfs");
+        }
+        if name.len() > NAME_MAX {
+            return_errno!(Errno::ENAMETOOLONG);
+        }
+
+        let mut children = self.children.write();
+        if children.iter().any(|(child_name, _)| child_name == name) {
+            return_errno_with_message!(Errno::EEXIST, "the cgroup already exists");
+        }
+
+        let child = Self::new(
+            self.cgroup.new_child(name),
+            alloc_ino(),
+            Some(self.this.clone()),
+            self.common.fs.clone(),
+        );
+        children.put((String::from(name), child.clone()));
+        Ok(child)
+    }
+
+    fn mknod(&self, _name: &str, _mode: InodeMode, _type_: MknodType) -> Result<Arc<dyn Inode>> {
+        Err(Error::new(Errno::EPERM))
+    }
+
+    fn readdir_at(&self, offset: usize, visitor: &mut dyn DirentVisitor) -> Result<usize> {
+        let try_readdir = |offset: &mut usize, visitor: &mut dyn DirentVisitor| -> Result<()> {
+            // Read the two special entries.
+            if *offset == 0 {
+                visitor.visit(".", self.ino(), self.type_(), *offset)?;
+                *offset += 1;
+            }
+            if *offset == 1 {
+                let parent_inode = self.parent().unwrap_or(self.this());
+                visitor.visit("..", parent_inode.ino(), parent_inode.type_(), *offset)?;
+                *offset += 1;
+            }
+
+            // Read the normal child entries.
+            let children = self.children.read();
+            let start_offset = *offset;
+            for (idx, (name, child)) in children
+                .idxes_and_items()
+                .map(|(idx, (name, child))| (idx + 2, (name, child)))
+                .skip_while(|(idx, _)| idx < &start_offset)
+            {
+                visitor.visit(name.as_ref(), child.ino(), child.type_(), idx)?;
+                *offset = idx + 1;
+            }
+            Ok(())
+        };
+
+        let mut iterate_offset = offset;
+        match try_readdir(&mut iterate_offset, visitor) {
+            Err(e) if iterate_offset == offset => Err(e),
+            _ => Ok(iterate_offset - offset),
+        }
+    }
+
+    fn link(&self, _old: &Arc<dyn Inode>, _name: &str) -> Result<()> {
+        Err(Error::new(Errno::EPERM))
+    }
+
+    fn unlink(&self, _name: &str) -> Result<()> {
+        Err(Error::new(Errno::EPERM))
+    }
+
+    fn rmdir(&self, name: &str) -> Result<()> {
+        let mut children = self.children.write();
+        let (idx, child) = children
+            .idxes_and_items()
+            .find(|(_, (child_name, _))| child_name == name)
+            .map(|(idx, (_, child))| (idx, child.clone()))
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "the cgroup does not exist"))?;
+        let Some(child_dir) = child.downcast_ref::<CgroupDir>() else {
+            return_errno_with_message!(Errno::ENOTDIR, "the file is not a cgroup");
+        };
+
+        // Like Linux, a cgroup can only be removed if it is not populated.
+        if child_dir.has_child_cgroups() || child_dir.cgroup.has_processes() {
+            return_errno_with_message!(Errno::EBUSY, "the cgroup is populated");
+        }
+
+        children.remove(idx);
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>> {
+        let inode: Arc<dyn Inode> = match name {
+            "." => self.this(),
+            ".." => self.parent().unwrap_or(self.this()),
+            name => self
+                .children
+                .read()
+                .iter()
+                .find(|(child_name, _)| child_name == name)
+                .map(|(_, child)| child.clone())
+                .ok_or(Error::new(Errno::ENOENT))?,
+        };
+        Ok(inode)
+    }
+
+    fn rename(&self, _old_name: &str, _target: &Arc<dyn Inode>, _new_name: &str) -> Result<()> {
+        Err(Error::new(Errno::EPERM))
+    }
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+use core::time::Duration;
+
+use inherit_methods_macro::inherit_methods;
+
+use super::{alloc_ino, CgroupFs, Common, BLOCK_SIZE};
+use crate::{
+    fs::utils::{FileSystem, Inode, InodeMode, InodeType, IoctlCmd, Metadata},
+    prelude::*,
+    process::{cgroup::Cgroup, Gid, Pid, Uid},
+};
+
+/// The names of the controllers, which are always enabled.
+const CONTROLLERS: [&str; 3] = ["cpu", "memory", "pids"];
+
+/// An interface file of a cgroup.
+pub struct CgroupFile {
+    kind: ControlFile,
+    cgroup: Arc<Cgroup>,
+    common: Common,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ControlFile {
+    CgroupControllers,
+    CgroupProcs,
+    CgroupSubtreeControl,
+    CpuMax,
+    CpuWeight,
+    MemoryCurrent,
+    MemoryMax,
+    PidsCurrent,
+    PidsMax,
+}
+
+impl ControlFile {
+    const ALL: [Self; 9] = [
+        Self::CgroupControllers,
+        Self::CgroupProcs,
+        Self::CgroupSubtreeControl,
+        Self::CpuMax,
+        Self::CpuWeight,
+        Self::MemoryCurrent,
+        Self::MemoryMax,
+        Self::PidsCurrent,
+        Self::PidsMax,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::CgroupControllers => "cgroup.controllers",
+            Self::CgroupProcs => "cgroup.procs",
+            Self::CgroupSubtreeControl => "cgroup.subtree_control",
+            Self::CpuMax => "cpu.max",
+            Self::CpuWeight => "cpu.weight",
+            Self::MemoryCurrent => "memory.current",
+            Self::MemoryMax => "memory.max",
+            Self::PidsCurrent => "pids.current",
+            Self::PidsMax => "pids.max",
+        }
+    }
+
+    fn is_writable(&self) -> bool {
+        !matches!(
+            self,
+            Self::CgroupControllers | Self::MemoryCurrent | Self::PidsCurrent
+        )
+    }
+
+    /// Returns whether the file exists in the root cgroup.
+    ///
+    /// Like Linux, the resources of the root cgroup cannot be limited,
+    /// so the interface files of the controllers only exist in the non-root cgroups.
+    fn exists_in_root(&self) -> bool {
+        matches!(
+            self,
+            Self::CgroupControllers | Self::CgroupProcs | Self::CgroupSubtreeControl
+        )
+    }
+}
+
+impl CgroupFile {
+    /// Creates all the interface files of the `cgroup`.
+    pub(super) fn new_all(cgroup: &Arc<Cgroup>, fs: &Weak<CgroupFs>) -> Vec<Arc<Self>> {
+        ControlFile::ALL
+            .into_iter()
+            .filter(|kind| !cgroup.is_root() || kind.exists_in_root())
+            .map(|kind| Self::new(kind, cgroup.clone(), fs.clone()))
+            .collect()
+    }
+
+    fn new(kind: ControlFile, cgroup: Arc<Cgroup>, fs: Weak<CgroupFs>) -> Arc<Self> {
+        let mode = if kind.is_writable() { 0o644 } else { 0o444 };
+        let metadata =
+            Metadata::new_file(alloc_ino(), InodeMode::from_bits_truncate(mode), BLOCK_SIZE);
+        Arc::new(Self {
+            kind,
+            cgroup,
+            common: Common::new(metadata, fs),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    fn data(&self) -> String {
+        match self.kind {
+            ControlFile::CgroupControllers | ControlFile::CgroupSubtreeControl => {
+                format!("{}\n", CONTROLLERS.join(" "))
+            }
+            ControlFile::CgroupProcs => {
+                // The PIDs are shown in the PID namespace of the reader
+                let pid_ns = current!().pid_ns().clone();
+                self.cgroup
+                    .processes()
+                    .iter()
+                    .filter_map(|process| pid_ns.pid_of(process.pid()))
+                    .map(|pid| format!("{}\n", pid))
+                    .collect()
+            }
+            ControlFile::CpuMax => {
+                let (quota, period) = self.cgroup.cpu().max();
+                format!(
+                    "{} {}\n",
+                    format_max(quota.map(|quota| quota.as_micros())),
+                    period.as_micros()
+                )
+            }
+            ControlFile::CpuWeight => format!("{}\n", self.cgroup.cpu().weight()),
+            ControlFile::MemoryCurrent => format!("{}\n", self.cgroup.memory().current()),
+            ControlFile::MemoryMax => format!("{}\n", format_max(self.cgroup.memory().max())),
+            ControlFile::PidsCurrent => format!("{}\n", self.cgroup.pids().current()),
+            ControlFile::PidsMax => format!("{}\n", format_max(self.cgroup.pids().max())),
+        }
+    }
+
+    fn write_data(&self, data: &str) -> Result<()> {
+        match self.kind {
+            ControlFile::CgroupProcs => {
+                let pid = parse_int::<Pid>(data)?;
+                let process = if pid == 0 {
+                    current!()
+                } else {
+                    current!().pid_ns().get_process(pid).ok_or_else(|| {
+                        Error::with_message(Errno::ESRCH, "the process does not exist")
+                    })?
+                };
+                process.move_to_cgroup(self.cgroup.clone())
+            }
+            ControlFile::CgroupSubtreeControl => {
+                for token in data.split_whitespace() {
+                    // All the controllers are always enabled, so they cannot be disabled.
+                    let Some(name) = token.strip_prefix('+') else {
+                        return_errno_with_message!(
+                            Errno::EINVAL,
+                            "the controllers can only be enabled"
+                        );
+                    };
+                    if !CONTROLLERS.contains(&name) {
+                        return_errno_with_message!(Errno::EINVAL, "the controller does not exist");
+                    }
+                }
+                Ok(())
+            }
+            ControlFile::CpuMax => {
+                let mut tokens = data.split_whitespace();
+                let quota = match tokens.next() {
+                    Some(quota) => parse_max::<u64>(quota)?.map(Duration::from_micros),
+                    None => return_errno_with_message!(Errno::EINVAL, "the CPU quota is missing"),
+                };
+                let period = match tokens.next() {
+                    Some(period) => Duration::from_micros(parse_int(period)?),
+                    None => self.cgroup.cpu().max().1,
+                };
+                if tokens.next().is_some() {
+                    return_errno_with_message!(Errno::EINVAL, "too many arguments for cpu.max");
+                }
+                self.cgroup.cpu().set_max(quota, period)
+            }
+            ControlFile::CpuWeight => self.cgroup.cpu().set_weight(parse_int(data)?),
+            ControlFile::MemoryMax => {
+                self.cgroup.memory().set_max(parse_max(data)?);
+                Ok(())
+            }
+            ControlFile::PidsMax => {
+                self.cgroup.pids().set_max(parse_max(data)?);
+                Ok(())
+            }
+            ControlFile::CgroupControllers
+            | ControlFile::MemoryCurrent
+            | ControlFile::PidsCurrent => {
+                return_errno_with_message!(Errno::EACCES, "the file is read-only")
+            }
+        }
+    }
+}
+
+#[inherit_methods(from = "self.common")]
+impl Inode for CgroupFile {
+    fn size(&self) -> usize;
+    fn metadata(&self) -> Metadata;
+    fn ino(&self) -> u64;
+    fn mode(&self) -> Result<InodeMode>;
+    fn set_mode(&self, mode: InodeMode) -> Result<()>;
+    fn owner(&self) -> Result<Uid>;
+    fn set_owner(&self, uid: Uid) -> Result<()>;
+    fn group(&self) -> Result<Gid>;
+    fn set_group(&self, gid: Gid) -> Result<()>;
+    fn atime(&self) -> Duration;
+    fn set_atime(&self, time: Duration);
+    fn mtime(&self) -> Duration;
+    fn set_mtime(&self, time: Duration);
+    fn ctime(&self) -> Duration;
+    fn set_ctime(&self, time: Duration);
+    fn fs(&self) -> Arc<dyn FileSystem>;
+
+    fn resize(&self, _new_size: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn type_(&self) -> InodeType {
+        InodeType::File
+    }
+
+    fn read_at(&self, offset: usize, writer: &mut VmWriter) -> Result<usize> {
+        let data = self.data();
+        let data = data.as_bytes();
+        let start = data.len().min(offset);
+        let end = data.len().min(offset + writer.avail());
+        let len = end - start;
+        writer.write_fallible(&mut (&data[start..end]).into())?;
+        Ok(len)
+    }
+
+    fn read_direct_at(&self, offset: usize, writer: &mut VmWriter) -> Result<usize> {
+        self.read_at(offset, writer)
+    }
+
+    fn write_at(&self, _offset: usize, reader: &mut VmReader) -> Result<usize> {
+        let mut buf = vec![0u8; reader.remain().min(PAGE_SIZE)];
+        let len = reader.read_fallible(&mut VmWriter::from(buf.as_mut_slice()))?;
+        let data = core::str::from_utf8(&buf[..len])
+            .map_err(|_| Error::with_message(Errno::EINVAL, "the data is not valid UTF-8"))?;
+        self.write_data(data.trim())?;
+        Ok(len)
+    }
+
+    fn write_direct_at(&self, offset: usize, reader: &mut VmReader) -> Result<usize> {
+        self.write_at(offset, reader)
+    }
+
+    fn read_link(&self) -> Result<String> {
+        Err(Error::new(Errno::EINVAL))
+    }
+
+    fn write_link(&self, _target: &str) -> Result<()> {
+        Err(Error::new(Errno::EINVAL))
+    }
+
+    fn ioctl(&self, _cmd: IoctlCmd, _arg: usize) -> Result<i32> {
+        Err(Error::new(Errno::EPERM))
+    }
+}
+
+/// Formats a value that may be unlimited, in which case it is `None` and formatted as "max".
+fn format_max<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| String::from("max"), |value| value.to_string())
+}
+
+/// Parses a value that may be unlimited, in which case it is "max" and parsed as `None`.
+fn parse_max<T: core::str::FromStr>(data: &str) -> Result<Option<T>> {
+    if data == "max" {
+        return Ok(None);
+    }
+    parse_int(data).map(Some)
+}
+
+fn parse_int<T: core::str::FromStr>(data: &str) -> Result<T> {
+    data.parse()
+        .map_err(|_| Error::with_message(Errno::EINVAL, "the value is not a valid integer"))
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The cgroup2 file system.
+//!
+//! Each directory of the file system represents a cgroup, and the files in the directory
+//! are the interfaces of the cgroup and its controllers. Creating and removing a directory
+//! creates and removes a child cgroup.
+
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use spin::Once;
+
+use self::dir::CgroupDir;
+use crate::{
+    fs::utils::{FileSystem, FsFlags, Inode, InodeMode, Metadata, SuperBlock, NAME_MAX},
+    prelude::*,
+    process::{cgroup::Cgroup, Gid, Uid},
+};
+
+mod dir;
+mod file;
+
+/// Magic number.
+const CGROUP2_MAGIC: u64 = 0x63677270;
+/// Root Inode ID.
+const CGROUP2_ROOT_INO: u64 = 1;
+/// Block size.
+const BLOCK_SIZE: usize = 1024;
+
+/// The cgroup2 file system.
+///
+/// There is only one cgroup hierarchy, so all the mounts share the same file system.
+pub struct CgroupFs {
+    sb: SuperBlock,
+    root: Arc<CgroupDir>,
+}
+
+static CGROUPFS_SINGLETON: Once<Arc<CgroupFs>> = Once::new();
+
+impl CgroupFs {
+    /// Returns the cgroup2 file system.
+    pub fn singleton() -> &'static Arc<CgroupFs> {
+        CGROUPFS_SINGLETON.call_once(|| {
+            Arc::new_cyclic(|weak_fs| Self {
+                sb: SuperBlock::new(CGROUP2_MAGIC, BLOCK_SIZE, NAME_MAX),
+                root: CgroupDir::new_root(weak_fs.clone()),
+            })
+        })
+    }
+}
+
+/// Allocates an inode number.
+///
+/// Since the file system is a singleton, the allocator is global.
+fn alloc_ino() -> u64 {
+    static INODE_ALLOCATOR: AtomicU64 = AtomicU64::new(CGROUP2_ROOT_INO + 1);
+    INODE_ALLOCATOR.fetch_add(1, Ordering::SeqCst)
+}
+
+impl FileSystem for CgroupFs {
+    fn sync(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn root_inode(&self) -> Arc<dyn Inode> {
+        self.root.clone()
+    }
+
+    fn sb(&self) -> SuperBlock {
+        self.sb.clone()
+    }
+
+    fn flags(&self) -> FsFlags {
+        FsFlags::empty()
+    }
+}
+
+/// Returns the cgroup represented by the `inode`,
+/// or `None` if the inode is not a directory of the cgroup2 file system.
+pub fn get_cgroup_of_inode(inode: &Arc<dyn Inode>) -> Option<Arc<Cgroup>> {
+    inode
+        .downcast_ref::<CgroupDir>()
+        .map(|dir| dir.cgroup().clone())
+}
+
+struct Common {
+    metadata: RwLock<Metadata>,
+    fs: Weak<CgroupFs>,
+}
+
+impl Common {
+    pub fn new(metadata: Metadata, fs: Weak<CgroupFs>) -> Self {
+        Self {
+            metadata: RwLock::new(metadata),
+            fs,
+        }
+    }
+
+    pub fn fs(&self) -> Arc<dyn FileSystem> {
+        self.fs.upgrade().unwrap()
+    }
+
+    pub fn metadata(&self) -> Metadata {
+        *self.metadata.read()
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.metadata.read().ino
+    }
+
+    pub fn size(&self) -> usize {
+        self.metadata.read().size
+    }
+
+    pub fn atime(&self) -> Duration {
+        self.metadata.read().atime
+    }
+
+    pub fn set_atime(&self, time: Duration) {
+        self.metadata.write().atime = time;
+    }
+
+    pub fn mtime(&self) -> Duration {
+        self.metadata.read().mtime
+    }
+
+    pub fn set_mtime(&self, time: Duration) {
+        self.metadata.write().mtime = time;
+    }
+
+    pub fn ctime(&self) -> Duration {
+        self.metadata.read().ctime
+    }
+
+    pub fn set_ctime(&self, time: Duration) {
+        self.metadata.write().ctime = time;
+    }
+
+    pub fn mode(&self) -> Result<InodeMode> {
+        Ok(self.metadata.read().mode)
+    }
+
+    pub fn set_mode(&self, mode: InodeMode) -> Result<()> {
+        self.metadata.write().mode = mode;
+        Ok(())
+    }
+
+    pub fn owner(&self) -> Result<Uid> {
+        Ok(self.metadata.read().uid)
+    }
+
+    pub fn set_owner(&self, uid: Uid) -> Result<()> {
+        self.metadata.write().uid = uid;
+        Ok(())
+    }
+
+    pub fn group(&self) -> Result<Gid> {
+        Ok(self.metadata.read().gid)
+    }
+
+    pub fn set_group(&self, gid: Gid) -> Result<()> {
+        self.metadata.write().gid = gid;
+        Ok(())
+    }
+}
@@ -46,6 +46,7 @@ impl InodeHandle<Rights> {
             access_mode,
             status_flags: AtomicU32::new(status_flags.bits()),
         });
+        inner.dentry.notify_fs_events(FsEvents::OPEN);
         Ok(Self(inner, Rights::from(access_mode)))
     }
 
@@ -17,6 +17,7 @@ use crate::{
     fs::{
         device::Device,
         file_handle::FileLike,
+        notify::FsEvents,
         path::Dentry,
         utils::{
             AccessMode, DirentVisitor, FallocMode, FileRange, FlockItem, FlockList, InodeMode,
@@ -86,11 +87,15 @@ impl InodeHandle_ {
             todo!("support read_at for FileIo");
         }
 
-        if self.status_flags().contains(StatusFlags::O_DIRECT) {
-            self.dentry.inode().read_direct_at(offset, writer)
+        let read_len = if self.status_flags().contains(StatusFlags::O_DIRECT) {
+            self.dentry.inode().read_direct_at(offset, writer)?
         } else {
-            self.dentry.inode().read_at(offset, writer)
+            self.dentry.inode().read_at(offset, writer)?
+        };
+        if read_len > 0 {
+            self.dentry.notify_fs_events(FsEvents::ACCESS);
         }
+        Ok(read_len)
     }
 
     pub fn write_at(&self, mut offset: usize, reader: &mut VmReader) -> Result<usize> {
@@ -104,11 +109,15 @@ impl InodeHandle_ {
             offset = self.dentry.size();
         }
 
-        if status_flags.contains(StatusFlags::O_DIRECT) {
-            self.dentry.inode().write_direct_at(offset, reader)
+        let write_len = if status_flags.contains(StatusFlags::O_DIRECT) {
+            self.dentry.inode().write_direct_at(offset, reader)?
         } else {
-            self.dentry.inode().write_at(offset, reader)
+            self.dentry.inode().write_at(offset, reader)?
+        };
+        if write_len > 0 {
+            self.dentry.notify_fs_events(FsEvents::MODIFY);
         }
+        Ok(write_len)
     }
 
     pub fn read_to_end(&self, buf: &mut Vec<u8>) -> Result<usize> {
@@ -341,6 +350,17 @@ impl InodeHandle_ {
     pub fn set_group(&self, gid: Gid) -> Result<()>;
 }
 
+impl Drop for InodeHandle_ {
+    fn drop(&mut self) {
+        let events = if self.access_mode.is_writable() {
+            FsEvents::CLOSE_WRITE
+        } else {
+            FsEvents::CLOSE_NOWRITE
+        };
+        self.dentry.notify_fs_events(events);
+    }
+}
+
 impl Debug for InodeHandle_ {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("InodeHandle_")
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
+pub mod cgroupfs;
 pub mod device;
 pub mod devpts;
 pub mod epoll;
@@ -9,6 +10,7 @@ pub mod file_table;
 pub mod fs_resolver;
 pub mod inode_handle;
 pub mod named_pipe;
+pub mod notify;
 pub mod path;
 pub mod pipe;
 pub mod procfs;
@@ -0,0 +1,458 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The inotify API.
+//!
+//! An inotify instance ([`InotifyFile`]) maintains a list of watches and a queue of events.
+//! Each watch subscribes to the [`FsEventPublisher`] of an inode. When an interesting event
+//! happens on the watched inode, the event is appended to the event queue, from which the user
+//! can read the events in the format of Linux's `struct inotify_event`.
+//!
+//! For more detailed information, refer to the man 7 inotify documentation.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use super::{FsEventPublisher, FsEventSubscriber, FsEvents};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{
+        file_handle::FileLike,
+        utils::{Inode, InodeMode, InodeType, IoctlCmd, Metadata, StatusFlags},
+    },
+    get_current_userspace,
+    prelude::*,
+    process::{
+        signal::{Pollable, Pollee, Poller},
+        Gid, Uid,
+    },
+    time::clocks::RealTimeCoarseClock,
+};
+
+/// The maximum number of events that can be queued in an inotify instance.
+///
+/// This is the default value of `/proc/sys/fs/inotify/max_queued_events` in Linux.
+const MAX_QUEUED_EVENTS: usize = 16384;
+
+/// The maximum number of watches that can be added to an inotify instance.
+///
+/// This is the default value of `/proc/sys/fs/inotify/max_user_watches` in Linux.
+const MAX_WATCHES: usize = 8192;
+
+bitflags! {
+    /// The flags that control how a watch is added.
+    pub struct InotifyWatchFlags: u32 {
+        /// Only watch the path if it is a directory.
+        const IN_ONLYDIR     = 0x01000000;
+        /// Do not follow a symbolic link.
+        const IN_DONT_FOLLOW = 0x02000000;
+        /// Exclude events on unlinked objects.
+        const IN_EXCL_UNLINK = 0x04000000;
+        /// Only create watches.
+        const IN_MASK_CREATE = 0x10000000;
+        /// Add to the mask of an already existing watch.
+        const IN_MASK_ADD    = 0x20000000;
+        /// Only send the event once.
+        const IN_ONESHOT     = 0x80000000;
+    }
+}
+
+/// A file-like object that provides the inotify API.
+pub struct InotifyFile {
+    // All watches, indexed by the watch descriptors.
+    watches: Mutex<InotifyWatches>,
+    // Events that have not been read yet.
+    event_queue: Mutex<VecDeque<InotifyEvent>>,
+    // The inotify file is readable when the event queue is not empty.
+    pollee: Pollee,
+    is_nonblocking: AtomicBool,
+    // Any `InotifyFile` is wrapped with `Arc` when created.
+    weak_self: Weak<Self>,
+}
+
+struct InotifyWatches {
+    map: BTreeMap<i32, Arc<InotifyWatch>>,
+    next_wd: i32,
+}
+
+impl InotifyFile {
+    /// Creates a new inotify file.
+    pub fn new(is_nonblocking: bool) -> Arc<Self> {
+        Arc::new_cyclic(|weak_self| Self {
+            watches: Mutex::new(InotifyWatches {
+                map: BTreeMap::new(),
+                next_wd: 1,
+            }),
+            event_queue: Mutex::new(VecDeque::new()),
+            pollee: Pollee::new(IoEvents::empty()),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+            weak_self: weak_self.clone(),
+        })
+    }
+
+    /// Adds a watch on the `inode`, or modifies the existing watch on it.
+    ///
+    /// Returns the watch descriptor.
+    pub fn add_watch(
+        &self,
+        inode: Arc<dyn Inode>,
+        events: FsEvents,
+        flags: InotifyWatchFlags,
+    ) -> Result<i32> {
+        let events = events & FsEvents::ALL_EVENTS;
+        if events.is_empty() {
+            return_errno_with_message!(Errno::EINVAL, "no events are specified");
+        }
+        if flags.contains(InotifyWatchFlags::IN_MASK_ADD | InotifyWatchFlags::IN_MASK_CREATE) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "IN_MASK_ADD and IN_MASK_CREATE cannot be specified together"
+            );
+        }
+
+        let Some(extension) = inode.extension() else {
+            return_errno_with_message!(
+                Errno::EOPNOTSUPP,
+                "the file system does not support inotify"
+            );
+        };
+
+        let mut watches = self.watches.lock();
+
+        if let Some(watch) = watches.map.values().find(|watch| watch.is_watching(&inode)) {
+            if flags.contains(InotifyWatchFlags::IN_MASK_CREATE) {
+                return_errno_with_message!(Errno::EEXIST, "the inode is already watched");
+            }
+            watch.update(events, flags);
+            return Ok(watch.wd);
+        }
+
+        if watches.map.len() >= MAX_WATCHES {
+            return_errno_with_message!(Errno::ENOSPC, "too many watches");
+        }
+
+        let wd = watches.next_wd;
+        watches.next_wd = watches.next_wd.checked_add(1).unwrap_or(1);
+
+        let watch = Arc::new(InotifyWatch {
+            wd,
+            mask: AtomicU32::new(0),
+            inode,
+            owner: self.weak_self.clone(),
+        });
+        watch.update(events, flags);
+
+        extension
+            .get_or_put_default::<FsEventPublisher>()
+            .add_subscriber(watch.clone());
+        watches.map.insert(wd, watch);
+
+        Ok(wd)
+    }
+
+    /// Removes the watch specified by the watch descriptor `wd`.
+    ///
+    /// An `IN_IGNORED` event will be generated for the removed watch.
+    pub fn remove_watch(&self, wd: i32) -> Result<()> {
+        let Some(watch) = self.watches.lock().map.remove(&wd) else {
+            return_errno_with_message!(Errno::EINVAL, "the watch descriptor is not valid");
+        };
+
+        watch.unsubscribe();
+        self.push_event(InotifyEvent::new(wd, FsEvents::IGNORED, 0, None));
+
+        Ok(())
+    }
+
+    fn push_event(&self, event: InotifyEvent) {
+        let mut event_queue = self.event_queue.lock();
+
+        // Coalesce the event with the last one if they are identical.
+        if event_queue.back() == Some(&event) {
+            return;
+        }
+
+        if event_queue.len() >= MAX_QUEUED_EVENTS {
+            let overflow = InotifyEvent::new(-1, FsEvents::Q_OVERFLOW, 0, None);
+            if event_queue.back() != Some(&overflow) {
+                event_queue.push_back(overflow);
+            }
+        } else {
+            event_queue.push_back(event);
+        }
+
+        self.pollee.add_events(IoEvents::IN);
+    }
+
+    fn try_read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let mut event_queue = self.event_queue.lock();
+        if event_queue.is_empty() {
+            return_errno_with_message!(Errno::EAGAIN, "no events are available");
+        }
+
+        let mut read_len = 0;
+        while let Some(event) = event_queue.front() {
+            let event_len = event.len();
+            if writer.avail() < event_len {
+                if read_len == 0 {
+                    return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+                }
+                break;
+            }
+
+            event.write_to(writer)?;
+            read_len += event_len;
+            event_queue.pop_front();
+        }
+
+        if event_queue.is_empty() {
+            self.pollee.del_events(IoEvents::IN);
+        }
+
+        Ok(read_len)
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for InotifyFile {
+    fn drop(&mut self) {
+        let watches = core::mem::take(&mut self.watches.lock().map);
+        for watch in watches.values() {
+            watch.unsubscribe();
+        }
+    }
+}
+
+impl Pollable for InotifyFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl FileLike for InotifyFile {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        if self.is_nonblocking() {
+            self.try_read(writer)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_read(writer))
+        }
+    }
+
+    fn write(&self, _reader: &mut VmReader) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "inotify files do not support write");
+    }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
+        match cmd {
+            IoctlCmd::FIONREAD => {
+                let len: usize = self.event_queue.lock().iter().map(InotifyEvent::len).sum();
+                get_current_userspace!().write_val(arg, &(len as i32))?;
+                Ok(0)
+            }
+            _ => return_errno_with_message!(Errno::EINVAL, "the ioctl command is not supported"),
+        }
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+
+    fn metadata(&self) -> Metadata {
+        let now = RealTimeCoarseClock::get().read_time();
+        Metadata {
+            dev: 0,
+            ino: 0,
+            size: 0,
+            blk_size: 0,
+            blocks: 0,
+            atime: now,
+            mtime: now,
+            ctime: now,
+            type_: InodeType::File,
+            mode: InodeMode::from_bits_truncate(0o600),
+            nlinks: 1,
+            uid: Uid::new_root(),
+            gid: Gid::new_root(),
+            rdev: 0,
+        }
+    }
+}
+
+/// A watch of an inotify instance.
+struct InotifyWatch {
+    wd: i32,
+    // The bits of `FsEvents` and `InotifyWatchFlags`.
+    mask: AtomicU32,
+    // The watched inode is pinned until the watch is removed.
+    inode: Arc<dyn Inode>,
+    owner: Weak<InotifyFile>,
+}
+
+impl InotifyWatch {
+    fn is_watching(&self, inode: &Arc<dyn Inode>) -> bool {
+        Arc::as_ptr(&self.inode) as *const () == Arc::as_ptr(inode) as *const ()
+    }
+
+    fn update(&self, events: FsEvents, flags: InotifyWatchFlags) {
+        let new_mask = events.bits()
+            | (flags & (InotifyWatchFlags::IN_ONESHOT | InotifyWatchFlags::IN_EXCL_UNLINK)).bits();
+        if flags.contains(InotifyWatchFlags::IN_MASK_ADD) {
+            self.mask.fetch_or(new_mask, Ordering::Relaxed);
+        } else {
+            self.mask.store(new_mask, Ordering::Relaxed);
+        }
+    }
+
+    fn events(&self) -> FsEvents {
+        FsEvents::from_bits_truncate(self.mask.load(Ordering::Relaxed))
+    }
+
+    fn flags(&self) -> InotifyWatchFlags {
+        InotifyWatchFlags::from_bits_truncate(self.mask.load(Ordering::Relaxed))
+    }
+
+    fn is_oneshot(&self) -> bool {
+        self.flags().contains(InotifyWatchFlags::IN_ONESHOT)
+    }
+
+    fn unsubscribe(self: &Arc<Self>) {
+        let Some(extension) = self.inode.extension() else {
+            return;
+        };
+        if let Some(publisher) = extension.get::<FsEventPublisher>() {
+            let subscriber: Arc<dyn FsEventSubscriber> = self.clone();
+            publisher.remove_subscriber(&subscriber);
+        }
+    }
+}
+
+impl FsEventSubscriber for InotifyWatch {
+    fn deliver_event(&self, events: FsEvents, name: Option<&str>, cookie: u32) {
+        let Some(owner) = self.owner.upgrade() else {
+            return;
+        };
+
+        // Events on the entries that have been unlinked from the watched directory
+        // are dropped if `IN_EXCL_UNLINK` is specified.
+        if events.contains(FsEvents::ON_UNLINKED_CHILD)
+            && self.flags().contains(InotifyWatchFlags::IN_EXCL_UNLINK)
+        {
+            return;
+        }
+
+        let reported_events = events & self.events();
+        if !reported_events.is_empty() {
+            let events = reported_events | (events & FsEvents::ISDIR);
+            owner.push_event(InotifyEvent::new(self.wd, events, cookie, name));
+        }
+        if events.contains(FsEvents::UNMOUNT) {
+            owner.push_event(InotifyEvent::new(self.wd, FsEvents::UNMOUNT, 0, None));
+        }
+
+        // The watch is removed automatically if the watched inode is gone,
+        // or if it is an one-shot watch and an event has been reported.
+        if events.intersects(FsEvents::DELETE_SELF | FsEvents::UNMOUNT)
+            || (self.is_oneshot() && !reported_events.is_empty())
+        {
+            let _ = owner.remove_watch(self.wd);
+        }
+    }
+
+    fn interesting_events(&self) -> FsEvents {
+        self.events()
+    }
+}
+
+/// An event in the event queue of an inotify instance.
+#[derive(PartialEq, Eq)]
+struct InotifyEvent {
+    wd: i32,
+    events: FsEvents,
+    cookie: u32,
+    name: Option<String>,
+}
+
+/// The header of an inotify event, i.e., Linux's `struct inotify_event` without the name.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CInotifyEventHeader {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    len: u32,
+}
+
+impl InotifyEvent {
+    fn new(wd: i32, events: FsEvents, cookie: u32, name: Option<&str>) -> Self {
+        Self {
+            wd,
+            events,
+            cookie,
+            name: name.map(String::from),
+        }
+    }
+
+    /// Returns the length of the name field, including the null terminator and paddings.
+    fn name_len(&self) -> usize {
+        // Linux pads the name so that the next event is properly aligned.
+        const ALIGN: usize = core::mem::size_of::<CInotifyEventHeader>();
+
+        match self.name.as_ref() {
+            Some(name) => (name.len() + 1).next_multiple_of(ALIGN),
+            None => 0,
+        }
+    }
+
+    /// Returns the total length of the event.
+    fn len(&self) -> usize {
+        core::mem::size_of::<CInotifyEventHeader>() + self.name_len()
+    }
+
+    fn write_to(&self, writer: &mut VmWriter) -> Result<()> {
+        let header = CInotifyEventHeader {
+            wd: self.wd,
+            mask: self.events.bits(),
+            cookie: self.cookie,
+            len: self.name_len() as u32,
+        };
+
+        let mut buf = Vec::with_capacity(self.len());
+        buf.extend_from_slice(header.as_bytes());
+        if let Some(name) = self.name.as_ref() {
+            buf.extend_from_slice(name.as_bytes());
+        }
+        buf.resize(self.len(), 0);
+
+        writer.write_fallible(&mut buf.as_slice().into())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! File system event notification.
+//!
+//! File system events (e.g., a file is modified or a directory entry is created)
+//! are published on inodes through their [`FsEventPublisher`]s.
+//! An inode keeps its publisher in its [`Extension`],
+//! so all file systems whose inodes have extensions (e.g., ramfs, ext2 and exfat)
+//! can emit events without any file system specific code.
+//!
+//! The events are generated by the VFS layer, i.e., [`Dentry`] and [`InodeHandle`],
+//! and are consumed by [`FsEventSubscriber`]s, such as the watches of an inotify instance.
+//!
+//! [`Extension`]: crate::fs::utils::Extension
+//! [`Dentry`]: crate::fs::path::Dentry
+//! [`InodeHandle`]: crate::fs::inode_handle::InodeHandle
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{fs::utils::Inode, prelude::*};
+
+pub mod inotify;
+
+bitflags! {
+    /// File system events.
+    ///
+    /// The values are compatible with those of Linux's inotify events.
+    pub struct FsEvents: u32 {
+        /// File was accessed.
+        const ACCESS        = 0x00000001;
+        /// File was modified.
+        const MODIFY        = 0x00000002;
+        /// Metadata changed.
+        const ATTRIB        = 0x00000004;
+        /// Writable file was closed.
+        const CLOSE_WRITE   = 0x00000008;
+        /// Unwritable file was closed.
+        const CLOSE_NOWRITE = 0x00000010;
+        /// File was opened.
+        const OPEN          = 0x00000020;
+        /// File was moved from X.
+        const MOVED_FROM    = 0x00000040;
+        /// File was moved to Y.
+        const MOVED_TO      = 0x00000080;
+        /// Subfile was created.
+        const CREATE        = 0x00000100;
+        /// Subfile was deleted.
+        const DELETE        = 0x00000200;
+        /// Self was deleted.
+        const DELETE_SELF   = 0x00000400;
+        /// Self was moved.
+        const MOVE_SELF     = 0x00000800;
+        /// Backing file system was unmounted.
+        const UNMOUNT       = 0x00002000;
+        /// Event queue overflowed.
+        const Q_OVERFLOW    = 0x00004000;
+        /// The watch was removed.
+        const IGNORED       = 0x00008000;
+        /// The event occurred against an unlinked entry of the watched directory.
+        ///
+        /// This is an internal bit and is never reported to the user.
+        const ON_UNLINKED_CHILD = 0x08000000;
+        /// The event occurred against a directory.
+        const ISDIR         = 0x40000000;
+
+        const CLOSE = Self::CLOSE_WRITE.bits | Self::CLOSE_NOWRITE.bits;
+        const MOVE = Self::MOVED_FROM.bits | Self::MOVED_TO.bits;
+        /// All events that can be watched.
+        const ALL_EVENTS = Self::ACCESS.bits | Self::MODIFY.bits | Self::ATTRIB.bits
+            | Self::CLOSE.bits | Self::OPEN.bits | Self::MOVE.bits | Self::CREATE.bits
+            | Self::DELETE.bits | Self::DELETE_SELF.bits | Self::MOVE_SELF.bits;
+    }
+}
+
+/// A subscriber of file system events.
+pub trait FsEventSubscriber: Send + Sync {
+    /// Delivers the `events` to the subscriber.
+    ///
+    /// If the events occur on an entry of a watched directory, `name` is the name of the entry.
+    /// Events related to the same rename operation share the same nonzero `cookie`.
+    fn deliver_event(&self, events: FsEvents, name: Option<&str>, cookie: u32);
+
+    /// Returns the events that the subscriber is interested in.
+    fn interesting_events(&self) -> FsEvents;
+}
+
+/// A publisher of file system events, which is attached to an inode.
+pub struct FsEventPublisher {
+    subscribers: RwLock<Vec<Arc<dyn FsEventSubscriber>>>,
+}
+
+impl FsEventPublisher {
+    /// Creates a new publisher without any subscribers.
+    pub fn new() -> Self {
+        Self {
+            subscribers: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Adds a subscriber.
+    pub fn add_subscriber(&self, subscriber: Arc<dyn FsEventSubscriber>) {
+        self.subscribers.write().push(subscriber);
+    }
+
+    /// Removes a subscriber.
+    ///
+    /// Returns whether the subscriber was found.
+    pub fn remove_subscriber(&self, subscriber: &Arc<dyn FsEventSubscriber>) -> bool {
+        let mut subscribers = self.subscribers.write();
+        let len = subscribers.len();
+        subscribers.retain(|s| !is_same_subscriber(s, subscriber));
+        subscribers.len() != len
+    }
+
+    /// Publishes the `events` to all the interested subscribers.
+    pub fn publish_event(&self, events: FsEvents, name: Option<&str>, cookie: u32) {
+        let interested_subscribers: Vec<_> = self
+            .subscribers
+            .read()
+            .iter()
+            .filter(|subscriber| {
+                subscriber
+                    .interesting_events()
+                    .intersects(events | FsEvents::DELETE_SELF | FsEvents::UNMOUNT)
+            })
+            .cloned()
+            .collect();
+
+        // Subscribers may remove themselves when receiving events,
+        // so the lock should not be held during the delivery.
+        for subscriber in interested_subscribers {
+            subscriber.deliver_event(events, name, cookie);
+        }
+    }
+}
+
+impl Default for FsEventPublisher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_same_subscriber(lhs: &Arc<dyn FsEventSubscriber>, rhs: &Arc<dyn FsEventSubscriber>) -> bool {
+    // Compare the data pointers only, since the vtable pointers of the same
+    // type are not guaranteed to be unique.
+    Arc::as_ptr(lhs) as *const () == Arc::as_ptr(rhs) as *const ()
+}
+
+/// Publishes the `events` on the `inode`.
+///
+/// This is a no-op if the inode does not support file system events
+/// or nobody is watching it.
+pub fn publish_event(inode: &dyn Inode, events: FsEvents, name: Option<&str>, cookie: u32) {
+    let Some(extension) = inode.extension() else {
+        return;
+    };
+    let Some(publisher) = extension.get::<FsEventPublisher>() else {
+        return;
+    };
+    publisher.publish_event(events, name, cookie);
+}
+
+/// Allocates a new cookie to connect the events of a rename operation.
+pub fn alloc_cookie() -> u32 {
+    static NEXT_COOKIE: AtomicU32 = AtomicU32::new(1);
+
+    loop {
+        let cookie = NEXT_COOKIE.fetch_add(1, Ordering::Relaxed);
+        // Zero means that the events are not related to a rename operation.
+        if cookie != 0 {
+            return cookie;
+        }
+    }
+}
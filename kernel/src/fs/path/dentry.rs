@@ -14,6 +14,7 @@ use ostd::sync::RwMutexWriteGuard;
 
 use crate::{
     fs::{
+        notify::{self, FsEvents},
         path::mount::MountNode,
         utils::{FileSystem, Inode, InodeMode, InodeType, Metadata, MknodType, NAME_MAX},
     },
@@ -36,7 +37,11 @@ pub struct Dentry_ {
     name_and_parent: RwMutex<Option<(String, Arc<Dentry_>)>>,
     this: Weak<Dentry_>,
     children: RwMutex<Children>,
-    flags: AtomicU32,
+    /// The number of the mounts on the `Dentry_`.
+    ///
+    /// The mount trees of different mount namespaces share the `Dentry_`s,
+    /// so the `Dentry_` is a mountpoint as long as any of the trees has a mount on it.
+    mount_count: AtomicU32,
 }
 
 impl Dentry_ {
@@ -51,7 +56,7 @@ impl Dentry_ {
     fn new(inode: Arc<dyn Inode>, options: DentryOptions) -> Arc<Self> {
         Arc::new_cyclic(|weak_self| Self {
             inode,
-            flags: AtomicU32::new(DentryFlags::empty().bits()),
+            mount_count: AtomicU32::new(0),
             name_and_parent: match options {
                 DentryOptions::Leaf(name_and_parent) => RwMutex::new(Some(name_and_parent)),
                 _ => RwMutex::new(None),
@@ -100,11 +105,6 @@ impl Dentry_ {
         &self.inode
     }
 
-    fn flags(&self) -> DentryFlags {
-        let flags = self.flags.load(Ordering::Relaxed);
-        DentryFlags::from_bits(flags).unwrap()
-    }
-
     /// Checks if this dentry is a descendant (child, grandchild, or
     /// great-grandchild, etc.) of another dentry.
     pub fn is_descendant_of(&self, ancestor: &Arc<Self>) -> bool {
@@ -119,17 +119,16 @@ impl Dentry_ {
     }
 
     pub fn is_mountpoint(&self) -> bool {
-        self.flags().contains(DentryFlags::MOUNTED)
+        self.mount_count.load(Ordering::Acquire) > 0
     }
 
-    pub fn set_mountpoint_dentry(&self) {
-        self.flags
-            .fetch_or(DentryFlags::MOUNTED.bits(), Ordering::Release);
+    pub(super) fn inc_mount_count(&self) {
+        self.mount_count.fetch_add(1, Ordering::Release);
     }
 
-    pub fn clear_mountpoint(&self) {
-        self.flags
-            .fetch_and(!(DentryFlags::MOUNTED.bits()), Ordering::Release);
+    pub(super) fn dec_mount_count(&self) {
+        let old_count = self.mount_count.fetch_sub(1, Ordering::Release);
+        debug_assert!(old_count > 0);
     }
 
     /// Currently, the root `Dentry_` of a fs is the root of a mount.
@@ -159,6 +158,7 @@ impl Dentry_ {
             children.insert_dentry(&dentry);
             dentry
         };
+        self.notify_dir_events(FsEvents::CREATE, name, type_, 0);
         Ok(child)
     }
 
@@ -198,6 +198,7 @@ impl Dentry_ {
             return_errno!(Errno::EEXIST);
         }
 
+        let inode_type = type_.inode_type();
         let child = {
             let inode = self.inode.mknod(name, mode, type_)?;
             let dentry = Self::new(
@@ -209,6 +210,7 @@ impl Dentry_ {
             children.insert_dentry(&dentry);
             dentry
         };
+        self.notify_dir_events(FsEvents::CREATE, name, inode_type, 0);
         Ok(child)
     }
 
@@ -232,6 +234,10 @@ impl Dentry_ {
 
         let mut children = children.upgrade();
         children.insert_dentry(&dentry);
+        drop(children);
+
+        notify::publish_event(old_inode.as_ref(), FsEvents::ATTRIB, None, 0);
+        self.notify_dir_events(FsEvents::CREATE, name, old_inode.type_(), 0);
         Ok(())
     }
 
@@ -242,11 +248,23 @@ impl Dentry_ {
         }
 
         let children = self.children.upread();
-        let _ = children.find_dentry_with_checking_mountpoint(name)?;
+        let child = children.find_dentry_with_checking_mountpoint(name)?;
+        let child_inode = self.child_inode(child.as_ref(), name);
         self.inode.unlink(name)?;
 
         let mut children = children.upgrade();
         children.delete_dentry(name);
+        drop(children);
+
+        if let Some(child_inode) = child_inode {
+            let events = if child_inode.metadata().nlinks == 0 {
+                FsEvents::DELETE_SELF
+            } else {
+                FsEvents::ATTRIB
+            };
+            notify::publish_event(child_inode.as_ref(), events, None, 0);
+            self.notify_dir_events(FsEvents::DELETE, name, child_inode.type_(), 0);
+        }
         Ok(())
     }
 
@@ -257,11 +275,23 @@ impl Dentry_ {
         }
 
         let children = self.children.upread();
-        let _ = children.find_dentry_with_checking_mountpoint(name)?;
+        let child = children.find_dentry_with_checking_mountpoint(name)?;
+        let child_inode = self.child_inode(child.as_ref(), name);
         self.inode.rmdir(name)?;
 
         let mut children = children.upgrade();
         children.delete_dentry(name);
+        drop(children);
+
+        if let Some(child_inode) = child_inode {
+            notify::publish_event(
+                child_inode.as_ref(),
+                FsEvents::DELETE_SELF | FsEvents::ISDIR,
+                None,
+                0,
+            );
+        }
+        self.notify_dir_events(FsEvents::DELETE, name, InodeType::Dir, 0);
         Ok(())
     }
 
@@ -275,7 +305,7 @@ impl Dentry_ {
         }
 
         // The two are the same dentry, we just modify the name
-        if Arc::ptr_eq(&self.this(), new_dir) {
+        let moved_inode = if Arc::ptr_eq(&self.this(), new_dir) {
             if old_name == new_name {
                 return Ok(());
             }
@@ -283,6 +313,7 @@ impl Dentry_ {
             let children = self.children.upread();
             let old_dentry = children.find_dentry_with_checking_mountpoint(old_name)?;
             let _ = children.find_dentry_with_checking_mountpoint(new_name)?;
+            let moved_inode = self.child_inode(old_dentry.as_ref(), old_name);
             self.inode.rename(old_name, &self.inode, new_name)?;
 
             let mut children = children.upgrade();
@@ -296,6 +327,7 @@ impl Dentry_ {
                     children.delete_dentry(new_name);
                 }
             }
+            moved_inode
         } else {
             // The two are different dentries
             let (mut self_children, mut new_dir_children) =
@@ -303,6 +335,7 @@ impl Dentry_ {
             let old_dentry = self_children.find_dentry_with_checking_mountpoint(old_name)?;
             let _ = new_dir_children.find_dentry_with_checking_mountpoint(new_name)?;
 
+            let moved_inode = self.child_inode(old_dentry.as_ref(), old_name);
             self.inode.rename(old_name, &new_dir.inode, new_name)?;
             match old_dentry.as_ref() {
                 Some(dentry) => {
@@ -314,9 +347,87 @@ impl Dentry_ {
                     new_dir_children.delete_dentry(new_name);
                 }
             }
+            moved_inode
+        };
+
+        if let Some(moved_inode) = moved_inode {
+            let type_ = moved_inode.type_();
+            let cookie = notify::alloc_cookie();
+            self.notify_dir_events(FsEvents::MOVED_FROM, old_name, type_, cookie);
+            new_dir.notify_dir_events(FsEvents::MOVED_TO, new_name, type_, cookie);
+            notify::publish_event(moved_inode.as_ref(), FsEvents::MOVE_SELF, None, 0);
         }
         Ok(())
     }
+
+    /// Sets the mode of the inner inode.
+    pub fn set_mode(&self, mode: InodeMode) -> Result<()> {
+        self.inode.set_mode(mode)?;
+        self.notify_fs_events(FsEvents::ATTRIB);
+        Ok(())
+    }
+
+    /// Sets the owner of the inner inode.
+    pub fn set_owner(&self, uid: Uid) -> Result<()> {
+        self.inode.set_owner(uid)?;
+        self.notify_fs_events(FsEvents::ATTRIB);
+        Ok(())
+    }
+
+    /// Sets the group of the inner inode.
+    pub fn set_group(&self, gid: Gid) -> Result<()> {
+        self.inode.set_group(gid)?;
+        self.notify_fs_events(FsEvents::ATTRIB);
+        Ok(())
+    }
+
+    /// Resizes the inner inode.
+    pub fn resize(&self, size: usize) -> Result<()> {
+        self.inode.resize(size)?;
+        self.notify_fs_events(FsEvents::MODIFY);
+        Ok(())
+    }
+
+    /// Publishes the file system `events` on this `Dentry_`.
+    ///
+    /// The events are delivered to the watchers of the inner inode, as well as
+    /// to the watchers of the parent directory with the name of this `Dentry_`.
+    pub fn notify_fs_events(&self, events: FsEvents) {
+        let events = if self.inode.type_() == InodeType::Dir {
+            events | FsEvents::ISDIR
+        } else {
+            events
+        };
+
+        notify::publish_event(self.inode.as_ref(), events, None, 0);
+        if let Some(parent) = self.parent() {
+            let events = if self.inode.metadata().nlinks == 0 {
+                events | FsEvents::ON_UNLINKED_CHILD
+            } else {
+                events
+            };
+            notify::publish_event(parent.inode.as_ref(), events, Some(&self.name()), 0);
+        }
+    }
+
+    /// Publishes the file system `events` that happen on the child named `name`
+    /// to the watchers of this directory.
+    fn notify_dir_events(&self, events: FsEvents, name: &str, child_type: InodeType, cookie: u32) {
+        let events = if child_type == InodeType::Dir {
+            events | FsEvents::ISDIR
+        } else {
+            events
+        };
+        notify::publish_event(self.inode.as_ref(), events, Some(name), cookie);
+    }
+
+    /// Gets the inode of the child named `name`, from the cached `child` if possible.
+    fn child_inode(&self, child: Option<&Arc<Self>>, name: &str) -> Option<Arc<dyn Inode>> {
+        match child {
+            Some(child) => Some(child.inode.clone()),
+            None => self.inode.lookup(name).ok(),
+        }
+    }
 }
 
 #[inherit_methods(from = "self.inode")]
@@ -327,13 +438,9 @@ impl Dentry_ {
     pub fn metadata(&self) -> Metadata;
     pub fn type_(&self) -> InodeType;
     pub fn mode(&self) -> Result<InodeMode>;
-    pub fn set_mode(&self, mode: InodeMode) -> Result<()>;
     pub fn size(&self) -> usize;
-    pub fn resize(&self, size: usize) -> Result<()>;
     pub fn owner(&self) -> Result<Uid>;
-    pub fn set_owner(&self, uid: Uid) -> Result<()>;
     pub fn group(&self) -> Result<Gid>;
-    pub fn set_group(&self, gid: Gid) -> Result<()>;
     pub fn atime(&self) -> Duration;
     pub fn set_atime(&self, time: Duration);
     pub fn mtime(&self) -> Duration;
@@ -346,7 +453,7 @@ impl Debug for Dentry_ {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("Dentry_")
             .field("inode", &self.inode)
-            .field("flags", &self.flags())
+            .field("mount_count", &self.mount_count.load(Ordering::Relaxed))
             .finish()
     }
 }
@@ -375,12 +482,6 @@ impl DentryKey {
     }
 }
 
-bitflags! {
-    struct DentryFlags: u32 {
-        const MOUNTED = 1 << 0;
-    }
-}
-
 enum DentryOptions {
     Root,
     Leaf((String, Arc<Dentry_>)),
@@ -579,13 +680,6 @@ impl Dentry {
         }
     }
 
-    /// Makes current `Dentry` to be a mountpoint,
-    /// sets it as the mountpoint of the child mount.
-    pub(super) fn set_mountpoint(&self, child_mount: Arc<MountNode>) {
-        child_mount.set_mountpoint_dentry(&self.inner);
-        self.inner.set_mountpoint_dentry();
-    }
-
     /// Mounts the fs on current `Dentry` as a mountpoint.
     ///
     /// If the given mountpoint has already been mounted,
@@ -602,7 +696,6 @@ impl Dentry {
         }
 
         let child_mount = self.mount_node().mount(fs, &self.this())?;
-        self.set_mountpoint(child_mount.clone());
         Ok(child_mount)
     }
 
@@ -623,7 +716,6 @@ impl Dentry {
         let mountpoint = Self::new(mountpoint_mount_node.clone(), mountpoint_dentry.clone());
 
         let child_mount = mountpoint_mount_node.unmount(&mountpoint)?;
-        mountpoint_dentry.clear_mountpoint();
         Ok(child_mount)
     }
 
@@ -672,6 +764,15 @@ impl Dentry {
         Ok(())
     }
 
+    /// Finds the `Dentry` corresponding to this one in the mount tree rooted at `new_root`,
+    /// which is copied from the tree containing this `Dentry`.
+    ///
+    /// Returns `None` if the mount of this `Dentry` is not in the tree.
+    pub fn find_corresponding_dentry(&self, new_root: &Arc<MountNode>) -> Option<Arc<Self>> {
+        let mount_node = self.mount_node.find_corresponding_mount(new_root)?;
+        Some(Self::new(mount_node, self.inner.clone()))
+    }
+
     fn this(&self) -> Arc<Self> {
         self.this.upgrade().unwrap()
     }
@@ -680,6 +781,11 @@ impl Dentry {
     pub fn mount_node(&self) -> &Arc<MountNode> {
         &self.mount_node
     }
+
+    /// Gets the inner `Dentry_` of current `Dentry`.
+    pub(super) fn inner(&self) -> &Arc<Dentry_> {
+        &self.inner
+    }
 }
 
 #[inherit_methods(from = "self.inner")]
@@ -707,4 +813,5 @@ impl Dentry {
     pub fn inode(&self) -> &Arc<dyn Inode>;
     pub fn is_root_of_mount(&self) -> bool;
     pub fn is_mountpoint(&self) -> bool;
+    pub fn notify_fs_events(&self, events: FsEvents);
 }
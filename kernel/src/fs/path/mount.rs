@@ -77,9 +77,8 @@ impl MountNode {
             return_errno!(Errno::ENOTDIR);
         }
 
-        let key = mountpoint.key();
         let child_mount = Self::new(fs, Some(Arc::downgrade(mountpoint.mount_node())));
-        self.children.write().insert(key, child_mount.clone());
+        self.insert_child(mountpoint.inner(), child_mount.clone());
         Ok(child_mount)
     }
 
@@ -92,9 +91,7 @@ impl MountNode {
         }
 
         let child_mount = self
-            .children
-            .write()
-            .remove(&mountpoint.key())
+            .remove_child(&mountpoint.key())
             .ok_or_else(|| Error::with_message(Errno::ENOENT, "can not find child mount"))?;
         Ok(child_mount)
     }
@@ -144,14 +141,8 @@ impl MountNode {
                 }
                 let new_child_mount =
                     old_child_mount.clone_mount_node(old_child_mount.root_dentry());
-                let key = mountpoint_dentry.key();
-                new_parent_mount
-                    .children
-                    .write()
-                    .insert(key, new_child_mount.clone());
+                new_parent_mount.insert_child(&mountpoint_dentry, new_child_mount.clone());
                 new_child_mount.set_parent(&new_parent_mount);
-                new_child_mount
-                    .set_mountpoint_dentry(&old_child_mount.mountpoint_dentry().unwrap());
                 stack.push(old_child_mount.clone());
                 new_stack.push(new_child_mount.clone());
             }
@@ -159,27 +150,66 @@ impl MountNode {
         new_root_mount.clone()
     }
 
+    /// Copies the whole mount tree rooted at this mount node.
+    pub fn copy_mount_node_tree(&self) -> Arc<Self> {
+        self.clone_mount_node_tree(&self.root_dentry, true)
+    }
+
+    /// Finds the mount node corresponding to this one in the mount tree rooted at `new_root`,
+    /// which is copied from the tree containing this mount node.
+    ///
+    /// Returns `None` if there is no such mount node in the tree.
+    pub(super) fn find_corresponding_mount(&self, new_root: &Arc<Self>) -> Option<Arc<Self>> {
+        let mut mountpoint_keys = Vec::new();
+        let mut mount = self.this();
+        while let Some(parent) = mount.parent() {
+            mountpoint_keys.push(mount.mountpoint_dentry()?.key());
+            mount = parent.upgrade()?;
+        }
+
+        let mut new_mount = new_root.clone();
+        for key in mountpoint_keys.iter().rev() {
+            let new_child_mount = new_mount.children.read().get(key).cloned()?;
+            new_mount = new_child_mount;
+        }
+        Some(new_mount)
+    }
+
     /// Detaches the mount node from the parent mount node.
     fn detach_mount_node(&self) {
         if let Some(parent) = self.parent() {
             let parent = parent.upgrade().unwrap();
-            parent
-                .children
-                .write()
-                .remove(&self.mountpoint_dentry().unwrap().key());
+            parent.remove_child(&self.mountpoint_dentry().unwrap().key());
         }
     }
 
     /// Attaches the mount node to the mountpoint.
     fn attach_mount_node(&self, mountpoint: &Arc<Dentry>) {
-        let key = mountpoint.key();
         mountpoint
             .mount_node()
-            .children
-            .write()
-            .insert(key, self.this());
+            .insert_child(mountpoint.inner(), self.this());
         self.set_parent(mountpoint.mount_node());
-        mountpoint.set_mountpoint(self.this());
+    }
+
+    /// Inserts a child mount node that is mounted on the `mountpoint`.
+    ///
+    /// The child mount node that was mounted on the `mountpoint` (if any) is replaced.
+    fn insert_child(&self, mountpoint: &Arc<Dentry_>, child_mount: Arc<Self>) {
+        child_mount.set_mountpoint_dentry(mountpoint);
+        mountpoint.inc_mount_count();
+        if let Some(old_child_mount) = self.children.write().insert(mountpoint.key(), child_mount) {
+            old_child_mount
+                .mountpoint_dentry()
+                .unwrap()
+                .dec_mount_count();
+        }
+    }
+
+    /// Removes the child mount node that is mounted on the mountpoint of the `key`.
+    fn remove_child(&self, key: &DentryKey) -> Option<Arc<Self>> {
+        let child_mount = self.children.write().remove(key)?;
+        child_mount.mountpoint_dentry().unwrap().dec_mount_count();
+        Some(child_mount)
     }
 
     /// Grafts the mount node tree to the mountpoint.
@@ -255,6 +285,16 @@ impl MountNode {
     }
 }
 
+impl Drop for MountNode {
+    fn drop(&mut self) {
+        // The child mount nodes are no longer mounted in this mount tree, though they may
+        // still be alive.
+        for child_mount in self.children.read().values() {
+            child_mount.mountpoint_dentry().unwrap().dec_mount_count();
+        }
+    }
+}
+
 impl Debug for MountNode {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("MountNode")
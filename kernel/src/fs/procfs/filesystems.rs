@@ -39,6 +39,7 @@ lazy_static! {
             FileSystemType::new("proc", true),
             FileSystemType::new("ramfs", true),
             FileSystemType::new("devpts", true),
+            FileSystemType::new("cgroup2", true),
             FileSystemType::new("ext2", false),
             FileSystemType::new("exfat", false),
         ]
@@ -4,8 +4,10 @@ use core::sync::atomic::{AtomicU64, Ordering};
 
 use sys::SysDirOps;
 
+pub use self::pid::get_ns_of_inode;
 use self::{
     meminfo::MemInfoFileOps,
+    net::NetDirOps,
     pid::PidDirOps,
     self_::SelfSymOps,
     template::{DirOps, ProcDir, ProcDirBuilder, ProcSymBuilder, SymOps},
@@ -17,11 +19,12 @@ use crate::{
         utils::{DirEntryVecExt, FileSystem, FsFlags, Inode, SuperBlock, NAME_MAX},
     },
     prelude::*,
-    process::{process_table, process_table::PidEvent, Pid},
+    process::{namespace::PidNamespace, process_table, process_table::PidEvent, Pid},
 };
 
 mod filesystems;
 mod meminfo;
+mod net;
 mod pid;
 mod self_;
 mod sys;
@@ -34,6 +37,10 @@ const PROC_ROOT_INO: u64 = 1;
 /// Block size.
 const BLOCK_SIZE: usize = 1024;
 
+/// The proc filesystem.
+///
+/// Like Linux, an instance of the filesystem shows the processes in the PID namespace
+/// that it is created for, with the PIDs in that namespace.
 pub struct ProcFS {
     sb: SuperBlock,
     root: Arc<dyn Inode>,
@@ -41,10 +48,10 @@ pub struct ProcFS {
 }
 
 impl ProcFS {
-    pub fn new() -> Arc<Self> {
+    pub fn new(pid_ns: Arc<PidNamespace>) -> Arc<Self> {
         Arc::new_cyclic(|weak_fs| Self {
             sb: SuperBlock::new(PROC_MAGIC, BLOCK_SIZE, NAME_MAX),
-            root: RootDirOps::new_inode(weak_fs.clone()),
+            root: RootDirOps::new_inode(pid_ns, weak_fs.clone()),
             inode_allocator: AtomicU64::new(PROC_ROOT_INO + 1),
         })
     }
@@ -73,11 +80,13 @@ impl FileSystem for ProcFS {
 }
 
 /// Represents the inode at `/proc`.
-struct RootDirOps;
+struct RootDirOps {
+    pid_ns: Arc<PidNamespace>,
+}
 
 impl RootDirOps {
-    pub fn new_inode(fs: Weak<ProcFS>) -> Arc<dyn Inode> {
-        let root_inode = ProcDirBuilder::new(Self)
+    pub fn new_inode(pid_ns: Arc<PidNamespace>, fs: Weak<ProcFS>) -> Arc<dyn Inode> {
+        let root_inode = ProcDirBuilder::new(Self { pid_ns })
             .fs(fs)
             .ino(PROC_ROOT_INO)
             .build()
@@ -99,16 +108,20 @@ impl Observer<PidEvent> for ProcDir<RootDirOps> {
 impl DirOps for RootDirOps {
     fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
         let child = if name == "self" {
-            SelfSymOps::new_inode(this_ptr.clone())
+            SelfSymOps::new_inode(self.pid_ns.clone(), this_ptr.clone())
         } else if name == "sys" {
             SysDirOps::new_inode(this_ptr.clone())
         } else if name == "filesystems" {
             FileSystemsFileOps::new_inode(this_ptr.clone())
         } else if name == "meminfo" {
             MemInfoFileOps::new_inode(this_ptr.clone())
+        } else if name == "net" {
+            NetDirOps::new_inode(this_ptr.clone())
         } else if let Ok(pid) = name.parse::<Pid>() {
-            let process_ref =
-                process_table::get_process(pid).ok_or_else(|| Error::new(Errno::ENOENT))?;
+            let process_ref = self
+                .pid_ns
+                .get_process(pid)
+                .ok_or_else(|| Error::new(Errno::ENOENT))?;
             PidDirOps::new_inode(process_ref, this_ptr.clone())
         } else {
             return_errno!(Errno::ENOENT);
@@ -122,18 +135,20 @@ impl DirOps for RootDirOps {
             this.downcast_ref::<ProcDir<RootDirOps>>().unwrap().this()
         };
         let mut cached_children = this.cached_children().write();
-        cached_children.put_entry_if_not_found("self", || SelfSymOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("self", || {
+            SelfSymOps::new_inode(self.pid_ns.clone(), this_ptr.clone())
+        });
         cached_children.put_entry_if_not_found("sys", || SysDirOps::new_inode(this_ptr.clone()));
         cached_children.put_entry_if_not_found("filesystems", || {
             FileSystemsFileOps::new_inode(this_ptr.clone())
         });
         cached_children
             .put_entry_if_not_found("meminfo", || MemInfoFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("net", || NetDirOps::new_inode(this_ptr.clone()));
 
-        for process in process_table::process_table().iter() {
-            let pid = process.pid().to_string();
-            cached_children.put_entry_if_not_found(&pid, || {
-                PidDirOps::new_inode(process.clone(), this_ptr.clone())
+        for (pid, process) in self.pid_ns.processes() {
+            cached_children.put_entry_if_not_found(&pid.to_string(), || {
+                PidDirOps::new_inode(process, this_ptr.clone())
             });
         }
     }
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::iface::{iter_ifaces, IfaceEx},
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/dev`.
+pub struct DevFileOps;
+
+impl DevFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for DevFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut result = String::from(concat!(
+            "Inter-|   Receive                                                |  Transmit\n",
+            " face |bytes    packets errs drop fifo frame compressed multicast|",
+            "bytes    packets errs drop fifo colls carrier compressed\n",
+        ));
+
+        for (_, iface) in iter_ifaces() {
+            let stats = iface.stats();
+
+            // TODO: Count the errors and the dropped packets.
+            result.push_str(&format!(
+                "{:>6}: {:7} {:7} {:4} {:4} {:4} {:5} {:10} {:9} {:8} {:7} {:4} {:4} {:4} {:5} {:7} {:10}\n",
+                iface.name(),
+                stats.rx_bytes.get(),
+                stats.rx_packets.get(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                stats.tx_bytes.get(),
+                stats.tx_packets.get(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ));
+        }
+
+        Ok(result.into_bytes())
+    }
+}
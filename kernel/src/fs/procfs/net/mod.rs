@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers the `/proc/net` directory, which exposes the socket tables and the network
+//! statistics in the formats that tools like `netstat` and `ss` understand.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man5/proc_net.5.html>
+
+use aster_bigtcp::wire::{IpAddress, IpEndpoint};
+
+use self::{
+    dev::DevFileOps, pnp::PnpFileOps, snmp::SnmpFileOps, tcp::TcpFileOps, udp::UdpFileOps,
+    unix::UnixFileOps,
+};
+use crate::{
+    fs::{
+        procfs::template::{DirOps, ProcDir, ProcDirBuilder},
+        utils::{DirEntryVecExt, Inode},
+    },
+    net::iface::dhcp_lease,
+    prelude::*,
+};
+
+mod dev;
+mod pnp;
+mod snmp;
+mod tcp;
+mod udp;
+mod unix;
+
+/// Represents the inode at `/proc/net`.
+pub struct NetDirOps;
+
+impl NetDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for NetDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let inode = match name {
+            "dev" => DevFileOps::new_inode(this_ptr.clone()),
+            "pnp" if dhcp_lease().is_some() => PnpFileOps::new_inode(this_ptr.clone()),
+            "snmp" => SnmpFileOps::new_inode(this_ptr.clone()),
+            "tcp" => TcpFileOps::new_inode(this_ptr.clone()),
+            "udp" => UdpFileOps::new_inode(this_ptr.clone()),
+            "unix" => UnixFileOps::new_inode(this_ptr.clone()),
+            _ => return_errno!(Errno::ENOENT),
+        };
+        Ok(inode)
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<NetDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        cached_children.put_entry_if_not_found("dev", || DevFileOps::new_inode(this_ptr.clone()));
+        if dhcp_lease().is_some() {
+            cached_children
+                .put_entry_if_not_found("pnp", || PnpFileOps::new_inode(this_ptr.clone()));
+        }
+        cached_children.put_entry_if_not_found("snmp", || SnmpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("tcp", || TcpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("udp", || UdpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("unix", || UnixFileOps::new_inode(this_ptr.clone()));
+    }
+}
+
+/// Formats an IPv4 endpoint in the way of `/proc/net/tcp` and `/proc/net/udp`.
+///
+/// Linux prints the address as an integer in the native byte order, so the bytes of the address
+/// appear reversed on little-endian machines. An unspecified endpoint is printed as zeros.
+fn format_ipv4_endpoint(endpoint: Option<IpEndpoint>) -> String {
+    let Some(IpEndpoint {
+        addr: IpAddress::Ipv4(addr),
+        port,
+    }) = endpoint
+    else {
+        return String::from("00000000:0000");
+    };
+
+    format!("{:08X}:{:04X}", u32::from_ne_bytes(addr.0), port)
+}
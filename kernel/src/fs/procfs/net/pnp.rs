@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::iface::dhcp_lease,
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/pnp`.
+///
+/// The file exists only if the iface is configured by the DHCP client during boot. Its content
+/// is in the format of `/etc/resolv.conf`.
+pub struct PnpFileOps;
+
+impl PnpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for PnpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let Some(lease) = dhcp_lease() else {
+            return_errno_with_message!(Errno::ENOENT, "the iface is not configured by DHCP");
+        };
+
+        let mut result = String::from("#PROTO: DHCP\n");
+        for dns_server in lease.dns_servers.iter() {
+            result.push_str(&format!("nameserver {}\n", dns_server));
+        }
+        result.push_str(&format!("bootserver {}\n", lease.server));
+
+        Ok(result.into_bytes())
+    }
+}
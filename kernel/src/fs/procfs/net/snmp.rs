@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_bigtcp::{
+    iface::{Counter, IfaceStats},
+    socket::RawTcpState,
+};
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::iface::iter_ifaces,
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/snmp`.
+pub struct SnmpFileOps;
+
+impl SnmpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for SnmpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let curr_estab = iter_ifaces()
+            .flat_map(|(_, iface)| iface.tcp_socket_infos())
+            .filter(|info| {
+                matches!(
+                    info.state,
+                    RawTcpState::Established | RawTcpState::CloseWait
+                )
+            })
+            .count();
+
+        // The counters that are not tracked are reported as zeros.
+        let mut result = String::new();
+        push_counters(
+            &mut result,
+            "Ip",
+            &[
+                // Forwarding is disabled.
+                ("Forwarding", 2),
+                ("DefaultTTL", 64),
+                ("InReceives", sum_counters(|stats| &stats.ip_in_receives)),
+                ("InHdrErrors", 0),
+                (
+                    "InAddrErrors",
+                    sum_counters(|stats| &stats.ip_in_addr_errors),
+                ),
+                ("ForwDatagrams", 0),
+                ("InUnknownProtos", 0),
+                ("InDiscards", 0),
+                ("InDelivers", sum_counters(|stats| &stats.ip_in_delivers)),
+                ("OutRequests", sum_counters(|stats| &stats.ip_out_requests)),
+                ("OutDiscards", 0),
+                ("OutNoRoutes", 0),
+                ("ReasmTimeout", 0),
+                ("ReasmReqds", 0),
+                ("ReasmOKs", 0),
+                ("ReasmFails", 0),
+                ("FragOKs", 0),
+                ("FragFails", 0),
+                ("FragCreates", 0),
+            ],
+        );
+        push_counters(
+            &mut result,
+            "Icmp",
+            &[
+                ("InMsgs", sum_counters(|stats| &stats.icmp_in_msgs)),
+                ("InErrors", 0),
+                ("InCsumErrors", 0),
+                ("OutMsgs", sum_counters(|stats| &stats.icmp_out_msgs)),
+                ("OutErrors", 0),
+            ],
+        );
+        push_counters(
+            &mut result,
+            "Tcp",
+            &[
+                // The RTO algorithm defined in RFC 6298 is classified as "other".
+                ("RtoAlgorithm", 1),
+                ("RtoMin", 200),
+                ("RtoMax", 120000),
+                // There is no limit on the number of connections.
+                ("MaxConn", -1),
+                ("ActiveOpens", 0),
+                ("PassiveOpens", 0),
+                ("AttemptFails", 0),
+                ("EstabResets", 0),
+                ("CurrEstab", curr_estab as i64),
+                ("InSegs", sum_counters(|stats| &stats.tcp_in_segs)),
+                ("OutSegs", sum_counters(|stats| &stats.tcp_out_segs)),
+                ("RetransSegs", 0),
+                ("InErrs", 0),
+                ("OutRsts", sum_counters(|stats| &stats.tcp_out_rsts)),
+                ("InCsumErrors", 0),
+            ],
+        );
+        push_counters(
+            &mut result,
+            "Udp",
+            &[
+                ("InDatagrams", sum_counters(|stats| &stats.udp_in_datagrams)),
+                ("NoPorts", sum_counters(|stats| &stats.udp_no_ports)),
+                ("InErrors", 0),
+                (
+                    "OutDatagrams",
+                    sum_counters(|stats| &stats.udp_out_datagrams),
+                ),
+                ("RcvbufErrors", 0),
+                ("SndbufErrors", 0),
+                ("InCsumErrors", 0),
+                ("IgnoredMulti", 0),
+                ("MemErrors", 0),
+            ],
+        );
+
+        Ok(result.into_bytes())
+    }
+}
+
+/// Sums up the counter of all the ifaces.
+fn sum_counters(counter: impl Fn(&IfaceStats) -> &Counter) -> i64 {
+    iter_ifaces()
+        .map(|(_, iface)| counter(iface.stats()).get())
+        .sum::<u64>() as i64
+}
+
+/// Pushes a line of counter names and a line of counter values for `protocol`.
+fn push_counters(result: &mut String, protocol: &str, counters: &[(&str, i64)]) {
+    result.push_str(protocol);
+    result.push(':');
+    for (name, _) in counters {
+        result.push(' ');
+        result.push_str(name);
+    }
+    result.push('\n');
+
+    result.push_str(protocol);
+    result.push(':');
+    for (_, value) in counters {
+        result.push_str(&format!(" {}", value));
+    }
+    result.push('\n');
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_bigtcp::wire::IpAddress;
+
+use super::format_ipv4_endpoint;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::{iface::iter_ifaces, socket::ip::stream::CTcpState},
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/tcp`.
+pub struct TcpFileOps;
+
+impl TcpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for TcpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut result = String::from(
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n",
+        );
+
+        let infos = iter_ifaces()
+            .flat_map(|(_, iface)| iface.tcp_socket_infos())
+            .filter(|info| matches!(info.local_endpoint.addr, IpAddress::Ipv4(_)));
+        for (slot, info) in infos.enumerate() {
+            // TODO: Report the timers, the owners, and the inode numbers of the sockets.
+            result.push_str(&format!(
+                "{:4}: {} {} {:02X} {:08X}:{:08X} 00:00000000 00000000 {:5} {:8} {}\n",
+                slot,
+                format_ipv4_endpoint(Some(info.local_endpoint)),
+                format_ipv4_endpoint(info.remote_endpoint),
+                CTcpState::from(info.state) as u8,
+                info.send_queue,
+                info.recv_queue,
+                0,
+                0,
+                0,
+            ));
+        }
+
+        Ok(result.into_bytes())
+    }
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_bigtcp::wire::IpAddress;
+
+use super::format_ipv4_endpoint;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::{iface::iter_ifaces, socket::ip::stream::CTcpState},
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/udp`.
+pub struct UdpFileOps;
+
+impl UdpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for UdpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut result = String::from(
+            "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops\n",
+        );
+
+        let infos = iter_ifaces()
+            .flat_map(|(_, iface)| iface.udp_socket_infos())
+            .filter(|info| matches!(info.local_endpoint.addr, IpAddress::Ipv4(_)));
+        for (slot, info) in infos.enumerate() {
+            // The remote endpoints of connected UDP sockets are not tracked by the iface, so all
+            // the sockets are reported as unconnected, which Linux shows in the `TCP_CLOSE` state.
+            //
+            // TODO: Report the owners, the inode numbers, and the drop counts of the sockets.
+            result.push_str(&format!(
+                "{:5}: {} {} {:02X} {:08X}:{:08X} 00:00000000 00000000 {:5} {:8} {} 0 0000000000000000 0\n",
+                slot,
+                format_ipv4_endpoint(Some(info.local_endpoint)),
+                format_ipv4_endpoint(None),
+                CTcpState::Close as u8,
+                info.send_queue,
+                info.recv_queue,
+                0,
+                0,
+                0,
+            ));
+        }
+
+        Ok(result.into_bytes())
+    }
+}
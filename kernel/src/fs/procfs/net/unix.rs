@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    net::socket::unix::{bound_socket_infos, UnixSocketAddr},
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/unix`.
+pub struct UnixFileOps;
+
+impl UnixFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+/// The flag of listening sockets.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/net.h#L64>.
+const SO_ACCEPTCON: u32 = 1 << 16;
+
+/// The state of unconnected sockets.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/net.h#L48>.
+const SS_UNCONNECTED: u8 = 1;
+
+impl FileOps for UnixFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut result = String::from("Num       RefCount Protocol Flags    Type St Inode Path\n");
+
+        for info in bound_socket_infos() {
+            let flags = if info.is_listening { SO_ACCEPTCON } else { 0 };
+
+            // TODO: Report the reference counts and the inode numbers of the sockets.
+            result.push_str(&format!(
+                "{:016X}: {:08X} {:08X} {:08X} {:04X} {:02X} {:5}",
+                0, 0, 0, flags, info.type_ as i32, SS_UNCONNECTED, 0,
+            ));
+            match info.addr {
+                UnixSocketAddr::Unnamed => (),
+                UnixSocketAddr::Path(path) => {
+                    result.push(' ');
+                    result.push_str(&path);
+                }
+                UnixSocketAddr::Abstract(name) => {
+                    // Like Linux, the abstract name starts with `@`, and the null bytes in the
+                    // name are also shown as `@`.
+                    result.push_str(" @");
+                    result.extend(name.iter().map(|&byte| match byte {
+                        0 => '@',
+                        byte => byte as char,
+                    }));
+                }
+            }
+            result.push('\n');
+        }
+
+        Ok(result.into_bytes())
+    }
+}
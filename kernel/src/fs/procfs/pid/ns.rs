@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::{
+        procfs::template::{DirOps, FileOps, ProcDir, ProcDirBuilder, ProcFile, ProcFileBuilder},
+        utils::{DirEntryVecExt, Inode},
+    },
+    prelude::*,
+    process::namespace::{NsRef, NsType},
+    Process,
+};
+
+/// Represents the inode at `/proc/[pid]/ns`.
+pub struct NsDirOps(Arc<Process>);
+
+impl NsDirOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl DirOps for NsDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let ns_type = NsType::ALL
+            .into_iter()
+            .find(|ns_type| ns_type.name() == name)
+            .ok_or_else(|| Error::new(Errno::ENOENT))?;
+        Ok(NsFileOps::new_inode(&self.0, ns_type, this_ptr))
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<NsDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        for ns_type in NsType::ALL {
+            let is_stale = cached_children
+                .iter()
+                .find(|(child_name, _)| child_name == ns_type.name())
+                .is_some_and(|(_, child)| !self.validate_child(child.as_ref()));
+            if is_stale {
+                cached_children.remove_entry_by_name(ns_type.name());
+            }
+            cached_children.put_entry_if_not_found(ns_type.name(), || {
+                NsFileOps::new_inode(&self.0, ns_type, this_ptr.clone())
+            });
+        }
+    }
+
+    fn validate_child(&self, child: &dyn Inode) -> bool {
+        // The process may have moved to other namespaces since the child was created.
+        let ns_file_ops = child.downcast_ref::<ProcFile<NsFileOps>>().unwrap().inner();
+        let current_ns = NsRef::of_process(ns_file_ops.0.ns_type(), &self.0);
+        ns_file_ops.0.ptr_eq(&current_ns)
+    }
+}
+
+/// Represents the inode at `/proc/[pid]/ns/[type]`.
+///
+/// The file refers to the namespace that the process was in when the file was looked up.
+/// It can be opened and passed to `setns` to enter the namespace.
+///
+/// TODO: Like Linux, the file should be a symbolic link that reads as `[type]:[[inode]]`,
+/// so that whether two processes are in the same namespace can be told from the links.
+pub struct NsFileOps(NsRef);
+
+impl NsFileOps {
+    pub fn new_inode(
+        process: &Process,
+        ns_type: NsType,
+        parent: Weak<dyn Inode>,
+    ) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(NsRef::of_process(ns_type, process)))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for NsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        return_errno_with_message!(Errno::EINVAL, "namespace files cannot be read");
+    }
+}
+
+/// Returns the namespace that the `inode` refers to
+/// if it is the inode at `/proc/[pid]/ns/[type]`.
+pub fn get_ns_of_inode(inode: &Arc<dyn Inode>) -> Option<NsRef> {
+    let ns_file = inode.downcast_ref::<ProcFile<NsFileOps>>()?;
+    Some(ns_file.inner().0.clone())
+}
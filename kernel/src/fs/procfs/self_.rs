@@ -6,19 +6,31 @@ use crate::{
         utils::Inode,
     },
     prelude::*,
+    process::namespace::PidNamespace,
 };
 
 /// Represents the inode at `/proc/self`.
-pub struct SelfSymOps;
+pub struct SelfSymOps {
+    pid_ns: Arc<PidNamespace>,
+}
 
 impl SelfSymOps {
-    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
-        ProcSymBuilder::new(Self).parent(parent).build().unwrap()
+    pub fn new_inode(pid_ns: Arc<PidNamespace>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcSymBuilder::new(Self { pid_ns })
+            .parent(parent)
+            .build()
+            .unwrap()
     }
 }
 
 impl SymOps for SelfSymOps {
     fn read_link(&self) -> Result<String> {
-        Ok(current!().pid().to_string())
+        let pid = self.pid_ns.pid_of(current!().pid()).ok_or_else(|| {
+            Error::with_message(
+                Errno::ENOENT,
+                "the current process is not in the PID namespace of the proc filesystem",
+            )
+        })?;
+        Ok(pid.to_string())
     }
 }
@@ -157,11 +157,15 @@ impl<D: DirOps + 'static> Inode for ProcDir<D> {
             ".." => self.parent().unwrap_or(self.this()),
             name => {
                 let mut cached_children = self.cached_children.write();
-                if let Some((_, inode)) = cached_children
-                    .iter()
-                    .find(|(child_name, inode)| child_name.as_str() == name)
+                if let Some((idx, inode)) = cached_children
+                    .idxes_and_items()
+                    .find(|(_, (child_name, _))| child_name.as_str() == name)
+                    .map(|(idx, (_, inode))| (idx, inode.clone()))
                 {
-                    return Ok(inode.clone());
+                    if self.inner.validate_child(inode.as_ref()) {
+                        return Ok(inode);
+                    }
+                    cached_children.remove(idx);
                 }
                 let inode = self.inner.lookup_child(self.this.clone(), name)?;
                 cached_children.put((String::from(name), inode.clone()));
@@ -186,4 +190,11 @@ pub trait DirOps: Sync + Send {
     }
 
     fn populate_children(&self, this_ptr: Weak<dyn Inode>) {}
+
+    /// Returns whether the cached `child` is still valid.
+    ///
+    /// An invalid child is removed from the cache and looked up again.
+    fn validate_child(&self, child: &dyn Inode) -> bool {
+        true
+    }
 }
@@ -5,7 +5,7 @@ use core::time::Duration;
 pub use self::{
     builder::{ProcDirBuilder, ProcFileBuilder, ProcSymBuilder},
     dir::{DirOps, ProcDir},
-    file::FileOps,
+    file::{FileOps, ProcFile},
     sym::SymOps,
 };
 use super::{ProcFS, BLOCK_SIZE};
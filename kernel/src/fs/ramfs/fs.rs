@@ -23,8 +23,9 @@ use crate::{
         file_handle::FileLike,
         named_pipe::NamedPipe,
         utils::{
-            CStr256, DirentVisitor, Extension, FallocMode, FileSystem, FsFlags, Inode, InodeMode,
-            InodeType, IoctlCmd, Metadata, MknodType, PageCache, PageCacheBackend, SuperBlock,
+            CStr256, DirentVisitor, Extension, FallocMode, FileSeals, FileSystem, FsFlags, Inode,
+            InodeMode, InodeType, IoctlCmd, Metadata, MknodType, PageCache, PageCacheBackend,
+            SuperBlock,
         },
     },
     prelude::*,
@@ -41,10 +42,22 @@ pub struct RamFS {
     root: Arc<RamInode>,
     /// An inode allocator
     inode_allocator: AtomicU64,
+    /// The initial seals of the regular files
+    file_seals: FileSeals,
 }
 
 impl RamFS {
     pub fn new() -> Arc<Self> {
+        // Like Linux tmpfs, the regular files cannot be sealed by default.
+        Self::new_with_file_seals(FileSeals::F_SEAL_SEAL)
+    }
+
+    /// Creates a `RamFS` whose regular files can be sealed, which is used by `memfd_create`.
+    pub fn new_sealable() -> Arc<Self> {
+        Self::new_with_file_seals(FileSeals::empty())
+    }
+
+    fn new_with_file_seals(file_seals: FileSeals) -> Arc<Self> {
         Arc::new_cyclic(|weak_fs| Self {
             sb: SuperBlock::new(RAMFS_MAGIC, BLOCK_SIZE, NAME_MAX),
             root: Arc::new_cyclic(|weak_root| RamInode {
@@ -62,6 +75,7 @@ impl RamFS {
                 extension: Extension::new(),
             }),
             inode_allocator: AtomicU64::new(ROOT_INO + 1),
+            file_seals,
         })
     }
 
@@ -107,6 +121,7 @@ struct RamInode {
 struct Node {
     inner: Inner,
     metadata: InodeMeta,
+    seals: FileSeals,
 }
 
 impl Node {
@@ -120,13 +135,21 @@ impl Node {
         Self {
             inner: Inner::Dir(DirEntry::new(this, parent)),
             metadata: InodeMeta::new_dir(mode, uid, gid),
+            seals: FileSeals::empty(),
         }
     }
 
-    pub fn new_file(mode: InodeMode, uid: Uid, gid: Gid, this: Weak<RamInode>) -> Self {
+    pub fn new_file(
+        mode: InodeMode,
+        uid: Uid,
+        gid: Gid,
+        seals: FileSeals,
+        this: Weak<RamInode>,
+    ) -> Self {
         Self {
             inner: Inner::File(PageCache::new(this).unwrap()),
             metadata: InodeMeta::new(mode, uid, gid),
+            seals,
         }
     }
 
@@ -134,6 +157,7 @@ impl Node {
         Self {
             inner: Inner::SymLink(String::from("")),
             metadata: InodeMeta::new(mode, uid, gid),
+            seals: FileSeals::empty(),
         }
     }
 
@@ -141,6 +165,7 @@ impl Node {
         Self {
             inner: Inner::Socket,
             metadata: InodeMeta::new(mode, uid, gid),
+            seals: FileSeals::empty(),
         }
     }
 
@@ -148,6 +173,7 @@ impl Node {
         Self {
             inner: Inner::Device(device),
             metadata: InodeMeta::new(mode, uid, gid),
+            seals: FileSeals::empty(),
         }
     }
 
@@ -155,9 +181,33 @@ impl Node {
         Self {
             inner: Inner::NamedPipe(NamedPipe::new().unwrap()),
             metadata: InodeMeta::new(mode, uid, gid),
+            seals: FileSeals::empty(),
         }
     }
 
+    /// Checks whether the seals allow writing to the file,
+    /// which may expand the file if `should_expand_size` is true.
+    fn check_seals_for_write(&self, should_expand_size: bool) -> Result<()> {
+        if self.seals.is_write_sealed() {
+            return_errno_with_message!(Errno::EPERM, "the file is sealed for writing");
+        }
+        if should_expand_size && self.seals.contains(FileSeals::F_SEAL_GROW) {
+            return_errno_with_message!(Errno::EPERM, "the file is sealed for growing");
+        }
+        Ok(())
+    }
+
+    /// Checks whether the seals allow resizing the file from `old_size` to `new_size`.
+    fn check_seals_for_resize(&self, old_size: usize, new_size: usize) -> Result<()> {
+        if new_size < old_size && self.seals.contains(FileSeals::F_SEAL_SHRINK) {
+            return_errno_with_message!(Errno::EPERM, "the file is sealed for shrinking");
+        }
+        if new_size > old_size && self.seals.contains(FileSeals::F_SEAL_GROW) {
+            return_errno_with_message!(Errno::EPERM, "the file is sealed for growing");
+        }
+        Ok(())
+    }
+
     pub fn inc_size(&mut self) {
         self.metadata.size += 1;
         self.metadata.blocks = (self.metadata.size + BLOCK_SIZE - 1) / BLOCK_SIZE;
@@ -461,7 +511,13 @@ impl RamInode {
 
     fn new_file(fs: &Arc<RamFS>, mode: InodeMode, uid: Uid, gid: Gid) -> Arc<Self> {
         Arc::new_cyclic(|weak_self| RamInode {
-            node: RwMutex::new(Node::new_file(mode, uid, gid, weak_self.clone())),
+            node: RwMutex::new(Node::new_file(
+                mode,
+                uid,
+                gid,
+                fs.file_seals,
+                weak_self.clone(),
+            )),
             ino: fs.alloc_id(),
             typ: InodeType::File,
             this: weak_self.clone(),
@@ -610,6 +666,7 @@ impl Inode for RamInode {
                 let write_len = reader.remain();
                 let new_size = offset + write_len;
                 let should_expand_size = new_size > file_size;
+                self_inode.check_seals_for_write(should_expand_size)?;
                 if should_expand_size {
                     page_cache.resize(new_size.align_up(BLOCK_SIZE))?;
                 }
@@ -659,6 +716,7 @@ impl Inode for RamInode {
         if file_size == new_size {
             return Ok(());
         }
+        self_inode.check_seals_for_resize(file_size, new_size)?;
 
         let mut self_inode = self_inode.upgrade();
         self_inode.resize(new_size);
@@ -1209,6 +1267,7 @@ impl Inode for RamInode {
             }
             FallocMode::PunchHoleKeepSize => {
                 let node = self.node.read();
+                node.check_seals_for_write(false)?;
                 let file_size = node.metadata.size;
                 if offset >= file_size {
                     return Ok(());
@@ -1226,6 +1285,37 @@ impl Inode for RamInode {
         }
     }
 
+    fn add_seals(&self, seals: FileSeals) -> Result<()> {
+        if self.typ != InodeType::File {
+            return_errno_with_message!(Errno::EINVAL, "not regular file");
+        }
+
+        let mut node = self.node.write();
+        if node.seals.contains(FileSeals::F_SEAL_SEAL) {
+            return_errno_with_message!(Errno::EPERM, "the seals are sealed");
+        }
+        if seals.contains(FileSeals::F_SEAL_WRITE) && !node.seals.contains(FileSeals::F_SEAL_WRITE)
+        {
+            // Fail with `EBUSY` if there are writable shared mappings of the file.
+            // Otherwise, new writable shared mappings will be denied.
+            node.inner
+                .as_file()
+                .unwrap()
+                .pages()
+                .deny_writable_mappings()?;
+        }
+        node.seals.insert(seals);
+        Ok(())
+    }
+
+    fn get_seals(&self) -> Result<FileSeals> {
+        if self.typ != InodeType::File {
+            return_errno_with_message!(Errno::EINVAL, "not regular file");
+        }
+
+        Ok(self.node.read().seals)
+    }
+
     fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
         if let Some(device) = self.node.read().inner.as_device() {
             return device.ioctl(cmd, arg);
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Anonymous memory-backed files created by `memfd_create`.
+//!
+//! A memfd is a regular file on an internal [`RamFS`], which is unlinked right after being
+//! created. So it behaves like a regular file (e.g., it can be read, written, truncated and
+//! mapped), but it lives only as long as there are references to it.
+
+use alloc::format;
+
+use aster_rights::ReadOp;
+use spin::Once;
+
+use super::RamFS;
+use crate::{
+    fs::{
+        inode_handle::InodeHandle,
+        path::{Dentry, MountNode},
+        utils::{AccessMode, FileSeals, InodeMode, InodeType, StatusFlags},
+    },
+    prelude::*,
+    process::Credentials,
+};
+
+/// The maximum length of the name of a memfd, excluding the terminating null byte.
+pub const MFD_NAME_MAX_LEN: usize = 249;
+
+/// Creates a memfd with the `name`.
+///
+/// If `is_sealable` is false, the file will be sealed with `F_SEAL_SEAL`,
+/// so that no more seals can be added.
+pub fn create_memfd(
+    name: &str,
+    is_sealable: bool,
+    credentials: &Credentials<ReadOp>,
+) -> Result<InodeHandle> {
+    let dentry = {
+        let root = MEMFD_ROOT
+            .call_once(|| {
+                Mutex::new(Dentry::new_fs_root(MountNode::new_root(
+                    RamFS::new_sealable(),
+                )))
+            })
+            .lock();
+
+        // Like Linux, the name is shown as the target of the symbolic links in `/proc/self/fd`.
+        let name = format!("memfd:{}", name);
+        let dentry =
+            root.new_fs_child(&name, InodeType::File, InodeMode::from_bits_truncate(0o777))?;
+        root.unlink(&name)?;
+        dentry
+    };
+    dentry.set_owner(credentials.euid())?;
+    dentry.set_group(credentials.egid())?;
+
+    if !is_sealable {
+        dentry.inode().add_seals(FileSeals::F_SEAL_SEAL)?;
+    }
+
+    InodeHandle::new_unchecked_access(dentry, AccessMode::O_RDWR, StatusFlags::empty())
+}
+
+/// The root directory of the internal file system that holds the memfds.
+///
+/// The lock serializes the creation of the files, so that there will be no name conflicts.
+static MEMFD_ROOT: Once<Mutex<Arc<Dentry>>> = Once::new();
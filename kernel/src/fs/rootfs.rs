@@ -12,7 +12,7 @@ use super::{
     ramfs::RamFS,
     utils::{FileSystem, InodeMode, InodeType},
 };
-use crate::prelude::*;
+use crate::{prelude::*, process::namespace::NsProxy};
 
 /// Unpack and prepare the rootfs from the initramfs CPIO buffer.
 pub fn init(initramfs_buf: &[u8]) -> Result<()> {
@@ -77,7 +77,7 @@ pub fn init(initramfs_buf: &[u8]) -> Result<()> {
     }
     // Mount ProcFS
     let proc_dentry = fs.lookup(&FsPath::try_from("/proc")?)?;
-    proc_dentry.mount(ProcFS::new())?;
+    proc_dentry.mount(ProcFS::new(NsProxy::init().pid_ns_for_children().clone()))?;
     // Mount DevFS
     let dev_dentry = fs.lookup(&FsPath::try_from("/dev")?)?;
     dev_dentry.mount(RamFS::new())?;
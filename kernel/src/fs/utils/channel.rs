@@ -136,6 +136,11 @@ impl<T> Producer<T> {
         drop(rb);
     }
 
+    /// Returns the number of items that can be written to the channel without blocking.
+    pub fn free_len(&self) -> usize {
+        self.this_end().rb().free_len()
+    }
+
     impl_common_methods_for_channel!();
 }
 
@@ -252,6 +257,28 @@ impl Consumer<u8> {
             return_errno_with_message!(Errno::EAGAIN, "the channel is empty");
         }
     }
+
+    /// Tries to read `buf` from the channel without consuming the data.
+    ///
+    /// The return values are the same as those of [`Self::try_read`].
+    pub fn try_peek(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        if writer.is_empty() {
+            return Ok(0);
+        }
+
+        // This must be recorded before the actual operation to avoid race conditions.
+        let is_shutdown = self.is_shutdown();
+
+        let read_len = self.0.peek(writer)?;
+
+        if read_len > 0 {
+            Ok(read_len)
+        } else if is_shutdown {
+            Ok(0)
+        } else {
+            return_errno_with_message!(Errno::EAGAIN, "the channel is empty");
+        }
+    }
 }
 
 impl<T: Pod> Consumer<T> {
@@ -275,6 +302,22 @@ impl<T: Pod> Consumer<T> {
             return_errno_with_message!(Errno::EAGAIN, "the channel is empty")
         }
     }
+
+    /// Tries to read an item from the channel without popping it.
+    ///
+    /// The return values are the same as those of [`Self::try_pop`].
+    pub fn try_peek_item(&self) -> Result<Option<T>> {
+        // This must be recorded before the actual operation to avoid race conditions.
+        let is_shutdown = self.is_shutdown();
+
+        if let Some(item) = self.0.peek_item() {
+            Ok(Some(item))
+        } else if is_shutdown {
+            Ok(None)
+        } else {
+            return_errno_with_message!(Errno::EAGAIN, "the channel is empty")
+        }
+    }
 }
 
 impl<T> Drop for Consumer<T> {
@@ -304,6 +347,12 @@ impl<R: TRights> Fifo<u8, R> {
         rb.read_fallible(writer)
     }
 
+    #[require(R > Read)]
+    pub fn peek(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        let rb = self.common.consumer.rb();
+        rb.peek_fallible(writer)
+    }
+
     #[require(R > Write)]
     pub fn write(&self, reader: &mut dyn MultiRead) -> Result<usize> {
         let mut rb = self.common.producer.rb();
@@ -327,6 +376,13 @@ impl<T: Pod, R: TRights> Fifo<T, R> {
         let mut rb = self.common.consumer.rb();
         rb.pop()
     }
+
+    /// Peeks an item from the endpoint without popping it.
+    #[require(R > Read)]
+    pub fn peek_item(&self) -> Option<T> {
+        let rb = self.common.consumer.rb();
+        rb.peek()
+    }
 }
 
 struct Common<T> {
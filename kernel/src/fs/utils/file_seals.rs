@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// The seals that restrict the operations on a file.
+    ///
+    /// Seals can only be added to a file but never removed.
+    /// See the "File Sealing" section of the man page of `fcntl`.
+    pub struct FileSeals: u32 {
+        /// Prevent further seals from being set
+        const F_SEAL_SEAL = 0x0001;
+        /// Prevent the file from shrinking
+        const F_SEAL_SHRINK = 0x0002;
+        /// Prevent the file from growing
+        const F_SEAL_GROW = 0x0004;
+        /// Prevent writes to the file
+        const F_SEAL_WRITE = 0x0008;
+        /// Prevent future writes while allowing the existing writable mappings
+        const F_SEAL_FUTURE_WRITE = 0x0010;
+    }
+}
+
+impl FileSeals {
+    /// Returns whether writing to the file is prohibited.
+    pub fn is_write_sealed(&self) -> bool {
+        self.intersects(Self::F_SEAL_WRITE | Self::F_SEAL_FUTURE_WRITE)
+    }
+}
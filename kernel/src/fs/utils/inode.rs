@@ -4,15 +4,15 @@
 
 use core::{any::TypeId, time::Duration};
 
-use aster_rights::Full;
+use aster_rights::{Full, ReadOp};
 use core2::io::{Error as IoError, ErrorKind as IoErrorKind, Result as IoResult, Write};
 
-use super::{DirentVisitor, FallocMode, FileSystem, IoctlCmd};
+use super::{DirentVisitor, FallocMode, FileSeals, FileSystem, IoctlCmd};
 use crate::{
     events::IoEvents,
     fs::device::{Device, DeviceType},
     prelude::*,
-    process::{signal::Poller, Gid, Uid},
+    process::{credentials::capabilities::CapSet, signal::Poller, Credentials, Gid, Uid},
     time::clocks::RealTimeCoarseClock,
     vm::vmo::Vmo,
 };
@@ -135,6 +135,15 @@ impl InodeMode {
     }
 }
 
+bitflags! {
+    /// The permissions to access an inode.
+    pub struct Permission: u16 {
+        const MAY_EXEC = 0o1;
+        const MAY_WRITE = 0o2;
+        const MAY_READ = 0o4;
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Metadata {
     pub dev: u64,
@@ -398,6 +407,18 @@ pub trait Inode: Any + Sync + Send {
         return_errno!(Errno::EOPNOTSUPP);
     }
 
+    /// Adds the `seals` to the file.
+    ///
+    /// The file system that does not support file sealing should return `EINVAL`.
+    fn add_seals(&self, seals: FileSeals) -> Result<()> {
+        return_errno_with_message!(Errno::EINVAL, "the file does not support sealing");
+    }
+
+    /// Gets the seals of the file.
+    fn get_seals(&self) -> Result<FileSeals> {
+        return_errno_with_message!(Errno::EINVAL, "the file does not support sealing");
+    }
+
     fn poll(&self, mask: IoEvents, _poller: Option<&mut Poller>) -> IoEvents {
         let events = IoEvents::IN | IoEvents::OUT;
         events & mask
@@ -441,6 +462,56 @@ impl dyn Inode {
         (self as &dyn Any).downcast_ref::<T>()
     }
 
+    /// Checks whether the user described by `credentials` has the permissions to access the
+    /// inode.
+    ///
+    /// The owner, group, or other permission bits are checked according to the file system user
+    /// and group IDs. `CAP_DAC_OVERRIDE` and `CAP_DAC_READ_SEARCH` bypass the check like Linux.
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/fs/namei.c>
+    pub fn check_permission(
+        &self,
+        perm: Permission,
+        credentials: &Credentials<ReadOp>,
+    ) -> Result<()> {
+        let mode = self.mode()?;
+        let is_dir = self.type_() == InodeType::Dir;
+
+        let granted = if credentials.fsuid() == self.owner()? {
+            mode.bits() >> 6
+        } else if credentials.fsgid() == self.group()?
+            || credentials.groups().contains(&self.group()?)
+        {
+            mode.bits() >> 3
+        } else {
+            mode.bits()
+        };
+        if perm.bits() & !granted & 0o7 == 0 {
+            return Ok(());
+        }
+
+        let capset = credentials.effective_capset();
+        // Files can be executed only if some of their execute bits are set, even with
+        // `CAP_DAC_OVERRIDE`.
+        let can_override_exec =
+            is_dir || mode.intersects(InodeMode::S_IXUSR | InodeMode::S_IXGRP | InodeMode::S_IXOTH);
+        if capset.contains(CapSet::DAC_OVERRIDE)
+            && (!perm.contains(Permission::MAY_EXEC) || can_override_exec)
+        {
+            return Ok(());
+        }
+        let can_read_search = if is_dir {
+            Permission::MAY_READ | Permission::MAY_EXEC
+        } else {
+            Permission::MAY_READ
+        };
+        if capset.contains(CapSet::DAC_READ_SEARCH) && can_read_search.contains(perm) {
+            return Ok(());
+        }
+
+        return_errno_with_message!(Errno::EACCES, "the permission is denied");
+    }
+
     pub fn read_to_end(&self, buf: &mut Vec<u8>) -> Result<usize> {
         if !self.type_().support_read() {
             return_errno!(Errno::EISDIR);
@@ -35,6 +35,28 @@ pub enum IoctlCmd {
     TIOCSPTLCK = 0x40045431,
     /// Safely open the slave
     TIOCGPTPEER = 0x40045441,
+    /// Get the name of the network interface with the given index
+    SIOCGIFNAME = 0x8910,
+    /// Get the list of network interface addresses
+    SIOCGIFCONF = 0x8912,
+    /// Get the flags of a network interface
+    SIOCGIFFLAGS = 0x8913,
+    /// Set the flags of a network interface
+    SIOCSIFFLAGS = 0x8914,
+    /// Get the address of a network interface
+    SIOCGIFADDR = 0x8915,
+    /// Set the address of a network interface
+    SIOCSIFADDR = 0x8916,
+    /// Get the network mask of a network interface
+    SIOCGIFNETMASK = 0x891b,
+    /// Set the network mask of a network interface
+    SIOCSIFNETMASK = 0x891c,
+    /// Get the MTU of a network interface
+    SIOCGIFMTU = 0x8921,
+    /// Get the hardware address of a network interface
+    SIOCGIFHWADDR = 0x8927,
+    /// Get the index of a network interface
+    SIOCGIFINDEX = 0x8933,
     /// Get tdx report using TDCALL
     TDXGETREPORT = 0xc4405401,
 }
@@ -9,9 +9,10 @@ pub use dirent_visitor::DirentVisitor;
 pub use direntry_vec::DirEntryVecExt;
 pub use falloc_mode::FallocMode;
 pub use file_creation_mask::FileCreationMask;
+pub use file_seals::FileSeals;
 pub use flock::{FlockItem, FlockList, FlockType};
 pub use fs::{FileSystem, FsFlags, SuperBlock};
-pub use inode::{Extension, Inode, InodeMode, InodeType, Metadata, MknodType};
+pub use inode::{Extension, Inode, InodeMode, InodeType, Metadata, MknodType, Permission};
 pub use ioctl::IoctlCmd;
 pub use page_cache::{PageCache, PageCacheBackend};
 pub use random_test::{generate_random_operation, new_fs_in_memory};
@@ -27,6 +28,7 @@ mod dirent_visitor;
 mod direntry_vec;
 mod falloc_mode;
 mod file_creation_mask;
+mod file_seals;
 mod flock;
 mod fs;
 mod inode;
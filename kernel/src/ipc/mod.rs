@@ -1,11 +1,16 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use aster_rights::ReadOp;
+
 use crate::{
     prelude::*,
-    process::{Gid, Uid},
+    process::{credentials::capabilities::CapSet, Credentials, Gid, Uid},
 };
 
+pub mod mqueue;
+pub mod msg;
 pub mod semaphore;
+pub mod shm;
 
 #[allow(non_camel_case_types)]
 pub type key_t = i32;
@@ -86,7 +91,34 @@ impl IpcPermission {
         self.mode
     }
 
-    pub(self) fn new_sem_perm(key: key_t, uid: Uid, gid: Gid, mode: u16) -> Self {
+    /// Checks whether the `credentials` are granted the `requested` access.
+    ///
+    /// The `requested` access is a combination of `0o4` (read), `0o2` (write) and `0o1` (execute).
+    pub fn check_access(&self, credentials: &Credentials<ReadOp>, requested: u16) -> Result<()> {
+        let euid = credentials.euid();
+        let granted = if euid == self.uid || euid == self.cuid {
+            self.mode >> 6
+        } else {
+            let egid = credentials.egid();
+            let groups = credentials.groups();
+            let is_in_group = |gid: &Gid| egid == *gid || groups.contains(gid);
+            if is_in_group(&self.gid) || is_in_group(&self.cguid) {
+                self.mode >> 3
+            } else {
+                self.mode
+            }
+        };
+
+        if requested & !granted & 0o7 != 0
+            && !credentials.effective_capset().contains(CapSet::IPC_OWNER)
+        {
+            return_errno_with_message!(Errno::EACCES, "the IPC object is not accessible");
+        }
+
+        Ok(())
+    }
+
+    pub(self) fn new(key: key_t, uid: Uid, gid: Gid, mode: u16) -> Self {
         Self {
             key,
             uid,
@@ -98,6 +130,33 @@ impl IpcPermission {
     }
 }
 
-pub(super) fn init() {
-    semaphore::init();
+/// The `ipc64_perm` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+pub struct IpcPerm {
+    pub key: key_t,
+    pub uid: u32,
+    pub gid: u32,
+    pub cuid: u32,
+    pub cgid: u32,
+    pub mode: u32,
+    pub seq: u16,
+    __pad2: u16,
+    __pad3: u32,
+    __unused1: u64,
+    __unused2: u64,
+}
+
+impl From<&IpcPermission> for IpcPerm {
+    fn from(permission: &IpcPermission) -> Self {
+        Self {
+            key: permission.key(),
+            uid: permission.uid().into(),
+            gid: permission.gid().into(),
+            cuid: permission.cuid().into(),
+            cgid: permission.cguid().into(),
+            mode: permission.mode() as u32,
+            ..Default::default()
+        }
+    }
 }
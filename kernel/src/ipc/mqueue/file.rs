@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{MessageQueue, MqAttr};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{
+        file_handle::FileLike,
+        path::Dentry,
+        utils::{AccessMode, InodeMode, Metadata, StatusFlags},
+    },
+    prelude::*,
+    process::{
+        signal::{Pollable, Poller},
+        Gid, Uid,
+    },
+};
+
+/// An open description of a POSIX message queue.
+pub struct MqueueFile {
+    queue: Arc<MessageQueue>,
+    dentry: Arc<Dentry>,
+    access_mode: AccessMode,
+    is_nonblocking: AtomicBool,
+}
+
+impl MqueueFile {
+    pub(super) fn new(
+        queue: Arc<MessageQueue>,
+        dentry: Arc<Dentry>,
+        access_mode: AccessMode,
+        is_nonblocking: bool,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            queue,
+            dentry,
+            access_mode,
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+        })
+    }
+
+    pub fn queue(&self) -> &Arc<MessageQueue> {
+        &self.queue
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    /// Returns the attributes of the queue and this open description.
+    pub fn attr(&self) -> MqAttr {
+        MqAttr {
+            mq_flags: self.status_flags().bits() as i64,
+            mq_maxmsg: self.queue.max_msgs() as i64,
+            mq_msgsize: self.queue.msg_size() as i64,
+            mq_curmsgs: self.queue.num_msgs() as i64,
+            ..Default::default()
+        }
+    }
+}
+
+impl Pollable for MqueueFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.queue.pollee().poll(mask, poller)
+    }
+}
+
+impl FileLike for MqueueFile {
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+
+        // TODO: deal with other flags
+
+        Ok(())
+    }
+
+    fn access_mode(&self) -> AccessMode {
+        self.access_mode
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.queue.pollee().register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.queue.pollee().unregister_observer(observer)
+    }
+
+    fn metadata(&self) -> Metadata {
+        self.dentry.metadata()
+    }
+
+    fn mode(&self) -> Result<InodeMode> {
+        self.dentry.mode()
+    }
+
+    fn set_mode(&self, mode: InodeMode) -> Result<()> {
+        self.dentry.set_mode(mode)
+    }
+
+    fn owner(&self) -> Result<Uid> {
+        self.dentry.owner()
+    }
+
+    fn set_owner(&self, uid: Uid) -> Result<()> {
+        self.dentry.set_owner(uid)
+    }
+
+    fn group(&self) -> Result<Gid> {
+        self.dentry.group()
+    }
+
+    fn set_group(&self, gid: Gid) -> Result<()> {
+        self.dentry.set_group(gid)
+    }
+}
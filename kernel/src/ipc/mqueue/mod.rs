@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! POSIX message queues.
+//!
+//! The queues are named inodes on an internal mqueue file system,
+//! which provides the namespace, the ownership and the permission of the queues.
+//! A [`MessageQueue`] is kept in the extension of its inode and is accessed
+//! through [`MqueueFile`]s. Since `MqueueFile`s are pollable,
+//! they can be monitored by `select`, `poll` and `epoll` like other files.
+
+mod file;
+mod queue;
+
+use aster_rights::ReadOp;
+pub use file::MqueueFile;
+pub use queue::MessageQueue;
+
+use crate::{
+    fs::{
+        path::{Dentry, MountNode},
+        ramfs::RamFS,
+        utils::{AccessMode, CreationFlags, InodeMode, InodeType},
+    },
+    prelude::*,
+    process::{credentials::capabilities::CapSet, Credentials},
+};
+
+// The following constant values are derived from the default values in Linux.
+
+/// The number of message priorities.
+pub const MQ_PRIO_MAX: u32 = 32768;
+/// Default maximum number of messages in a queue.
+pub const DFLT_MSGMAX: usize = 10;
+/// Default maximum size in bytes of a message.
+pub const DFLT_MSGSIZEMAX: usize = 8192;
+/// Upper limit of the maximum number of messages for unprivileged users.
+pub const MSG_MAX: usize = 10;
+/// Upper limit of the maximum size of a message for unprivileged users.
+pub const MSGSIZE_MAX: usize = 8192;
+/// Upper limit of the maximum number of messages.
+pub const HARD_MSGMAX: usize = 65536;
+/// Upper limit of the maximum size of a message.
+pub const HARD_MSGSIZEMAX: usize = 16 * 1024 * 1024;
+
+/// The `mq_attr` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+pub struct MqAttr {
+    /// Flags of the queue description (`O_NONBLOCK` or 0)
+    pub mq_flags: i64,
+    /// Maximum number of messages in the queue
+    pub mq_maxmsg: i64,
+    /// Maximum size in bytes of a message
+    pub mq_msgsize: i64,
+    /// Number of messages in the queue
+    pub mq_curmsgs: i64,
+    __reserved: [i64; 4],
+}
+
+/// The mqueue file system of an IPC namespace.
+pub struct MqueueFs {
+    /// The root directory.
+    ///
+    /// The lock serializes the creation and removal of the queues.
+    root: Mutex<Arc<Dentry>>,
+}
+
+impl MqueueFs {
+    pub(crate) fn new() -> Self {
+        let mount_node = MountNode::new_root(RamFS::new());
+        let root = Dentry::new_fs_root(mount_node);
+        // Like Linux, everyone can create queues, but only the owners can remove them.
+        root.set_mode(InodeMode::from_bits_truncate(0o1777))
+            .unwrap();
+
+        Self {
+            root: Mutex::new(root),
+        }
+    }
+
+    /// Opens the message queue with the `name`, and creates it if necessary.
+    ///
+    /// If `attr` is `None` when creating the queue, the default attributes are used.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_mqueue(
+        &self,
+        name: &str,
+        access_mode: AccessMode,
+        creation_flags: CreationFlags,
+        mode: u16,
+        attr: Option<MqAttr>,
+        is_nonblocking: bool,
+        credentials: &Credentials<ReadOp>,
+    ) -> Result<Arc<MqueueFile>> {
+        check_name(name)?;
+
+        let root = self.root.lock();
+        let dentry = match root.lookup(name) {
+            Ok(dentry) => {
+                if creation_flags.contains(CreationFlags::O_CREAT | CreationFlags::O_EXCL) {
+                    return_errno_with_message!(Errno::EEXIST, "the message queue already exists");
+                }
+
+                let mut requested = 0;
+                if access_mode.is_readable() {
+                    requested |= 0o4;
+                }
+                if access_mode.is_writable() {
+                    requested |= 0o2;
+                }
+                check_permission(&dentry, credentials, requested)?;
+                dentry
+            }
+            Err(err) if err.error() == Errno::ENOENT => {
+                if !creation_flags.contains(CreationFlags::O_CREAT) {
+                    return Err(err);
+                }
+
+                let (max_msgs, msg_size) = match attr {
+                    Some(attr) => check_attr(&attr, credentials)?,
+                    None => (DFLT_MSGMAX, DFLT_MSGSIZEMAX),
+                };
+                let dentry = root.new_fs_child(
+                    name,
+                    InodeType::File,
+                    InodeMode::from_bits_truncate(mode & 0o777),
+                )?;
+                dentry.set_owner(credentials.euid())?;
+                dentry.set_group(credentials.egid())?;
+                dentry
+                    .inode()
+                    .extension()
+                    .unwrap()
+                    .put(Arc::new(MessageQueue::new(max_msgs, msg_size)));
+                dentry
+            }
+            Err(err) => return Err(err),
+        };
+
+        let queue = dentry
+            .inode()
+            .extension()
+            .unwrap()
+            .get::<MessageQueue>()
+            .unwrap();
+        Ok(MqueueFile::new(queue, dentry, access_mode, is_nonblocking))
+    }
+
+    /// Removes the message queue with the `name`.
+    ///
+    /// The queue is destroyed once all the descriptors referring to it are closed.
+    pub fn unlink_mqueue(&self, name: &str, credentials: &Credentials<ReadOp>) -> Result<()> {
+        check_name(name)?;
+
+        let root = self.root.lock();
+        let dentry = root.lookup(name)?;
+        // The root directory is sticky, so only the owner can remove the queue.
+        if dentry.owner()? != credentials.euid()
+            && !credentials.effective_capset().contains(CapSet::FOWNER)
+        {
+            return_errno_with_message!(Errno::EACCES, "the message queue is not owned");
+        }
+
+        root.unlink(name)
+    }
+}
+
+fn check_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return_errno_with_message!(Errno::ENOENT, "the name is empty");
+    }
+    if name.contains('/') || name == "." || name == ".." {
+        return_errno_with_message!(Errno::EACCES, "the name is invalid");
+    }
+    Ok(())
+}
+
+/// Checks the attributes of a new queue.
+///
+/// Returns the maximum number of messages and the maximum size of a message.
+fn check_attr(attr: &MqAttr, credentials: &Credentials<ReadOp>) -> Result<(usize, usize)> {
+    if attr.mq_maxmsg <= 0 || attr.mq_msgsize <= 0 {
+        return_errno_with_message!(Errno::EINVAL, "the attributes are invalid");
+    }
+
+    let (max_msgs, msg_size) = (attr.mq_maxmsg as usize, attr.mq_msgsize as usize);
+    let (max_msgs_limit, msg_size_limit) = if credentials
+        .effective_capset()
+        .contains(CapSet::SYS_RESOURCE)
+    {
+        (HARD_MSGMAX, HARD_MSGSIZEMAX)
+    } else {
+        (MSG_MAX, MSGSIZE_MAX)
+    };
+    if max_msgs > max_msgs_limit || msg_size > msg_size_limit {
+        return_errno_with_message!(Errno::EINVAL, "the attributes exceed the limits");
+    }
+
+    Ok((max_msgs, msg_size))
+}
+
+/// Checks whether the `credentials` are granted the `requested` access to the queue.
+///
+/// The `requested` access is a combination of `0o4` (read) and `0o2` (write).
+fn check_permission(
+    dentry: &Dentry,
+    credentials: &Credentials<ReadOp>,
+    requested: u16,
+) -> Result<()> {
+    let mode = dentry.mode()?.bits();
+    let granted = if credentials.euid() == dentry.owner()? {
+        mode >> 6
+    } else if credentials.egid() == dentry.group()?
+        || credentials.groups().contains(&dentry.group()?)
+    {
+        mode >> 3
+    } else {
+        mode
+    };
+
+    if requested & !granted & 0o7 != 0
+        && !credentials
+            .effective_capset()
+            .contains(CapSet::DAC_OVERRIDE)
+    {
+        return_errno_with_message!(Errno::EACCES, "the message queue is not accessible");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::{btree_map::BTreeMap, VecDeque};
+
+use ostd::sync::WaitQueue;
+
+use crate::{
+    events::IoEvents,
+    prelude::*,
+    process::{
+        signal::{signals::kernel::KernelSignal, Pause, Pollee},
+        Pid, Process,
+    },
+    time::wait::TimerBuilder,
+};
+
+/// A POSIX message queue.
+///
+/// Messages are received in the descending order of their priorities,
+/// and messages of the same priority are received in the order of sending.
+pub struct MessageQueue {
+    /// Maximum number of messages in the queue
+    max_msgs: usize,
+    /// Maximum size in bytes of a message
+    msg_size: usize,
+    inner: Mutex<MqueueInner>,
+    pollee: Pollee,
+    /// The threads waiting for sending or receiving messages
+    wait_queue: WaitQueue,
+}
+
+struct MqueueInner {
+    /// Messages grouped by their priorities
+    messages: BTreeMap<u32, VecDeque<Vec<u8>>>,
+    /// Number of messages in the queue
+    num_msgs: usize,
+    /// Number of bytes of all the messages in the queue
+    num_bytes: usize,
+    /// Number of threads blocking in receiving messages
+    num_receivers: usize,
+    /// The registered notification
+    notification: Option<Notification>,
+}
+
+/// The notification registered by `mq_notify`.
+struct Notification {
+    /// The registering process
+    process: Weak<Process>,
+    pid: Pid,
+    /// The signal to send, or `None` if no signal is sent (i.e., `SIGEV_NONE`)
+    signal: Option<KernelSignal>,
+}
+
+impl MessageQueue {
+    pub(super) fn new(max_msgs: usize, msg_size: usize) -> Self {
+        Self {
+            max_msgs,
+            msg_size,
+            inner: Mutex::new(MqueueInner {
+                messages: BTreeMap::new(),
+                num_msgs: 0,
+                num_bytes: 0,
+                num_receivers: 0,
+                notification: None,
+            }),
+            pollee: Pollee::new(IoEvents::OUT),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    pub fn max_msgs(&self) -> usize {
+        self.max_msgs
+    }
+
+    pub fn msg_size(&self) -> usize {
+        self.msg_size
+    }
+
+    /// Returns the number of messages in the queue.
+    pub fn num_msgs(&self) -> usize {
+        self.inner.lock().num_msgs
+    }
+
+    /// Sends the message of the `prio` priority to the queue.
+    ///
+    /// If the queue is full, this method will wait until there is room for the message
+    /// unless `is_nonblocking` is true, in which case `EAGAIN` is returned.
+    /// If the `timer_builder` is set, the waiting ends with `ETIMEDOUT` once it expires.
+    pub fn send(
+        &self,
+        msg: Vec<u8>,
+        prio: u32,
+        is_nonblocking: bool,
+        timer_builder: Option<&TimerBuilder>,
+    ) -> Result<()> {
+        if msg.len() > self.msg_size {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too long");
+        }
+
+        let mut msg = Some(msg);
+        let mut try_send = || {
+            let mut inner = self.inner.lock();
+            if inner.num_msgs >= self.max_msgs {
+                return None;
+            }
+
+            let msg = msg.take().unwrap();
+            inner.num_bytes += msg.len();
+            inner.num_msgs += 1;
+            inner.messages.entry(prio).or_default().push_back(msg);
+
+            // The notification is only triggered when a message arrives at an empty queue
+            // and no thread is waiting to receive it.
+            let notification = if inner.num_msgs == 1 && inner.num_receivers == 0 {
+                inner.notification.take()
+            } else {
+                None
+            };
+            self.update_pollee(&inner);
+            Some(notification)
+        };
+
+        let notification = if is_nonblocking {
+            try_send().ok_or_else(|| Error::with_message(Errno::EAGAIN, "the queue is full"))?
+        } else {
+            self.wait_queue
+                .pause_until_or_timer_timeout_opt(try_send, timer_builder)
+                .map_err(convert_timeout_error)?
+        };
+        self.wait_queue.wake_all();
+
+        if let Some(notification) = notification {
+            notification.notify();
+        }
+        Ok(())
+    }
+
+    /// Receives the oldest message of the highest priority from the queue.
+    ///
+    /// Returns the message and its priority.
+    ///
+    /// If the queue is empty, this method will wait until a message arrives
+    /// unless `is_nonblocking` is true, in which case `EAGAIN` is returned.
+    /// If the `timer_builder` is set, the waiting ends with `ETIMEDOUT` once it expires.
+    pub fn receive(
+        &self,
+        is_nonblocking: bool,
+        timer_builder: Option<&TimerBuilder>,
+    ) -> Result<(Vec<u8>, u32)> {
+        let try_receive = || {
+            let mut inner = self.inner.lock();
+            let (msg, prio) = {
+                let mut entry = inner.messages.last_entry()?;
+                let prio = *entry.key();
+                let msg = entry.get_mut().pop_front().unwrap();
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                (msg, prio)
+            };
+
+            inner.num_bytes -= msg.len();
+            inner.num_msgs -= 1;
+            self.update_pollee(&inner);
+            Some((msg, prio))
+        };
+
+        let res = if is_nonblocking {
+            try_receive().ok_or_else(|| Error::with_message(Errno::EAGAIN, "the queue is empty"))
+        } else {
+            self.inner.lock().num_receivers += 1;
+            let res = self
+                .wait_queue
+                .pause_until_or_timer_timeout_opt(try_receive, timer_builder)
+                .map_err(convert_timeout_error);
+            self.inner.lock().num_receivers -= 1;
+            res
+        };
+
+        if res.is_ok() {
+            self.wait_queue.wake_all();
+        }
+        res
+    }
+
+    /// Registers a notification for the `process`,
+    /// which will be sent the `signal` when a message arrives at the empty queue.
+    ///
+    /// Only one process can be registered at a time.
+    pub fn register_notification(
+        &self,
+        process: &Arc<Process>,
+        signal: Option<KernelSignal>,
+    ) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if inner
+            .notification
+            .as_ref()
+            .is_some_and(|notification| notification.is_alive())
+        {
+            return_errno_with_message!(Errno::EBUSY, "a notification has been registered");
+        }
+
+        inner.notification = Some(Notification {
+            process: Arc::downgrade(process),
+            pid: process.pid(),
+            signal,
+        });
+        Ok(())
+    }
+
+    /// Removes the notification registered by the process of `pid`.
+    ///
+    /// Nothing happens if the notification is registered by another process.
+    pub fn unregister_notification(&self, pid: Pid) {
+        let mut inner = self.inner.lock();
+        if inner
+            .notification
+            .as_ref()
+            .is_some_and(|notification| notification.pid == pid)
+        {
+            inner.notification = None;
+        }
+    }
+
+    pub fn pollee(&self) -> &Pollee {
+        &self.pollee
+    }
+
+    fn update_pollee(&self, inner: &MqueueInner) {
+        if inner.num_msgs > 0 {
+            self.pollee.add_events(IoEvents::IN);
+        } else {
+            self.pollee.del_events(IoEvents::IN);
+        }
+
+        if inner.num_msgs < self.max_msgs {
+            self.pollee.add_events(IoEvents::OUT);
+        } else {
+            self.pollee.del_events(IoEvents::OUT);
+        }
+    }
+}
+
+impl Notification {
+    fn is_alive(&self) -> bool {
+        self.process.strong_count() > 0
+    }
+
+    fn notify(self) {
+        let (Some(process), Some(signal)) = (self.process.upgrade(), self.signal) else {
+            return;
+        };
+        // TODO: Deliver the signal with `SI_MESGQ` and the user-specified `sigev_value`.
+        process.enqueue_signal(signal);
+    }
+}
+
+/// Converts the `ETIME` error of the timeout to the `ETIMEDOUT` error reported by `mq_timed*`.
+fn convert_timeout_error(err: Error) -> Error {
+    if err.error() == Errno::ETIME {
+        Error::with_message(Errno::ETIMEDOUT, "the time limit is reached")
+    } else {
+        err
+    }
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! System V message queues.
+//!
+//! A message queue holds a list of typed messages.
+//! Senders append messages to the tail of the queue, while receivers
+//! pick messages by their types. Both of them may block if the queue is
+//! full or if no suitable message is available.
+
+pub mod msg_queue;
+
+use crate::prelude::*;
+
+bitflags! {
+    pub struct MsgFlags: u32 {
+        /// Truncate the message if it is longer than the buffer.
+        const MSG_NOERROR = 0o10000;
+        /// Receive the first message whose type differs from the requested one.
+        const MSG_EXCEPT  = 0o20000;
+        /// Copy the message at the requested position without removing it.
+        const MSG_COPY    = 0o40000;
+    }
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+pub enum MsgControlCmd {
+    IPC_RMID = 0,
+    IPC_SET = 1,
+    IPC_STAT = 2,
+    IPC_INFO = 3,
+
+    MSG_STAT = 11,
+    MSG_INFO = 12,
+}
@@ -0,0 +1,425 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::{btree_map::BTreeMap, VecDeque};
+use core::time::Duration;
+
+use aster_rights::ReadOp;
+use id_alloc::IdAlloc;
+use ostd::sync::WaitQueue;
+
+use super::MsgFlags;
+use crate::{
+    ipc::{key_t, IpcPermission},
+    prelude::*,
+    process::{signal::Pause, Credentials, Pid},
+    time::clocks::RealTimeCoarseClock,
+};
+
+// The following constant values are derived from the default values in Linux.
+
+/// Maximum number of message queues.
+pub const MSGMNI: usize = 32000;
+/// Maximum size in bytes of a message.
+pub const MSGMAX: usize = 8192;
+/// Default maximum size in bytes of a message queue.
+pub const MSGMNB: usize = 16384;
+
+/// The key that always creates a new message queue.
+pub const IPC_PRIVATE: key_t = 0;
+
+/// A message in a System V message queue.
+#[derive(Debug, Clone)]
+pub struct Message {
+    mtype: i64,
+    data: Vec<u8>,
+}
+
+impl Message {
+    pub fn new(mtype: i64, data: Vec<u8>) -> Self {
+        Self { mtype, data }
+    }
+
+    pub fn mtype(&self) -> i64 {
+        self.mtype
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+pub struct MessageQueue {
+    /// The identifier of the queue
+    id: key_t,
+    /// Queue permission
+    permission: IpcPermission,
+    /// Inner
+    inner: Mutex<MsgQueueInner>,
+    /// The threads waiting for sending or receiving messages
+    wait_queue: WaitQueue,
+    /// The table that the queue belongs to
+    table: Weak<MsgQueueTable>,
+}
+
+struct MsgQueueInner {
+    /// Messages in the order of sending
+    messages: VecDeque<Message>,
+    /// Number of bytes of all the messages in the queue
+    cbytes: usize,
+    /// Maximum number of bytes allowed in the queue
+    qbytes: usize,
+    /// PID of the last `msgsnd`
+    lspid: Pid,
+    /// PID of the last `msgrcv`
+    lrpid: Pid,
+    /// Last send time
+    stime: Duration,
+    /// Last receive time
+    rtime: Duration,
+    /// Creation time or last modification via `msgctl`
+    ctime: Duration,
+    /// Whether the queue has been removed
+    is_removed: bool,
+}
+
+impl MsgQueueInner {
+    fn has_room_for(&self, len: usize) -> bool {
+        // Like Linux, the number of messages is also limited by `qbytes`,
+        // so that zero-length messages cannot fill up the memory.
+        self.cbytes + len <= self.qbytes && self.messages.len() < self.qbytes
+    }
+
+    /// Finds the position of the message to receive.
+    fn select(&self, msgtyp: i64, flags: MsgFlags) -> Option<usize> {
+        if flags.contains(MsgFlags::MSG_COPY) {
+            let index = usize::try_from(msgtyp).ok()?;
+            return (index < self.messages.len()).then_some(index);
+        }
+
+        let mut messages = self.messages.iter().enumerate();
+        match msgtyp {
+            0 => messages.next().map(|(index, _)| index),
+            msgtyp if msgtyp > 0 => {
+                let is_except = flags.contains(MsgFlags::MSG_EXCEPT);
+                messages
+                    .find(|(_, message)| (message.mtype == msgtyp) != is_except)
+                    .map(|(index, _)| index)
+            }
+            // Select the first message with the lowest type
+            // that is less than or equal to the absolute value of `msgtyp`.
+            msgtyp => messages
+                .filter(|(_, message)| message.mtype <= msgtyp.saturating_neg())
+                .min_by_key(|(index, message)| (message.mtype, *index))
+                .map(|(index, _)| index),
+        }
+    }
+}
+
+impl MessageQueue {
+    pub fn id(&self) -> key_t {
+        self.id
+    }
+
+    pub fn permission(&self) -> &IpcPermission {
+        &self.permission
+    }
+
+    /// Returns the number of messages in the queue.
+    pub fn qnum(&self) -> usize {
+        self.inner.lock().messages.len()
+    }
+
+    pub fn cbytes(&self) -> usize {
+        self.inner.lock().cbytes
+    }
+
+    pub fn qbytes(&self) -> usize {
+        self.inner.lock().qbytes
+    }
+
+    pub fn lspid(&self) -> Pid {
+        self.inner.lock().lspid
+    }
+
+    pub fn lrpid(&self) -> Pid {
+        self.inner.lock().lrpid
+    }
+
+    pub fn stime(&self) -> Duration {
+        self.inner.lock().stime
+    }
+
+    pub fn rtime(&self) -> Duration {
+        self.inner.lock().rtime
+    }
+
+    pub fn ctime(&self) -> Duration {
+        self.inner.lock().ctime
+    }
+
+    /// Sets the maximum number of bytes allowed in the queue.
+    pub fn set_qbytes(&self, qbytes: usize) {
+        let mut inner = self.inner.lock();
+        inner.qbytes = qbytes;
+        inner.ctime = RealTimeCoarseClock::get().read_time();
+        drop(inner);
+
+        // The senders may be able to proceed with a larger limit
+        self.wait_queue.wake_all();
+    }
+
+    /// Sends the `message` to the queue on behalf of the process of `pid`.
+    ///
+    /// If the queue is full, this method will wait until there is enough room
+    /// unless `is_nonblocking` is true, in which case `EAGAIN` is returned.
+    pub fn send(&self, message: Message, is_nonblocking: bool, pid: Pid) -> Result<()> {
+        let len = message.data.len();
+        let mut message = Some(message);
+        let mut try_send = || {
+            let mut inner = self.inner.lock();
+            if inner.is_removed {
+                return Some(Err(Error::with_message(
+                    Errno::EIDRM,
+                    "the queue has been removed",
+                )));
+            }
+            if !inner.has_room_for(len) {
+                return None;
+            }
+
+            inner.messages.push_back(message.take().unwrap());
+            inner.cbytes += len;
+            inner.lspid = pid;
+            inner.stime = RealTimeCoarseClock::get().read_time();
+            Some(Ok(()))
+        };
+
+        if is_nonblocking {
+            try_send()
+                .unwrap_or_else(|| Err(Error::with_message(Errno::EAGAIN, "the queue is full")))?;
+        } else {
+            self.wait_queue.pause_until(try_send)??;
+        }
+
+        self.wait_queue.wake_all();
+        Ok(())
+    }
+
+    /// Receives a message from the queue on behalf of the process of `pid`.
+    ///
+    /// The message is selected according to `msgtyp` and `flags` in the same way as `msgrcv`.
+    /// If the selected message is longer than `max_len`, it is truncated if `MSG_NOERROR` is
+    /// specified, or `E2BIG` is returned otherwise.
+    ///
+    /// If no message is available, this method will wait until one arrives
+    /// unless `is_nonblocking` is true, in which case `ENOMSG` is returned.
+    pub fn receive(
+        &self,
+        msgtyp: i64,
+        max_len: usize,
+        flags: MsgFlags,
+        is_nonblocking: bool,
+        pid: Pid,
+    ) -> Result<Message> {
+        let try_receive = || {
+            let mut inner = self.inner.lock();
+            if inner.is_removed {
+                return Some(Err(Error::with_message(
+                    Errno::EIDRM,
+                    "the queue has been removed",
+                )));
+            }
+            let index = inner.select(msgtyp, flags)?;
+
+            if inner.messages[index].data.len() > max_len && !flags.contains(MsgFlags::MSG_NOERROR)
+            {
+                return Some(Err(Error::with_message(
+                    Errno::E2BIG,
+                    "the message is longer than the buffer",
+                )));
+            }
+
+            let mut message = if flags.contains(MsgFlags::MSG_COPY) {
+                inner.messages[index].clone()
+            } else {
+                let message = inner.messages.remove(index).unwrap();
+                inner.cbytes -= message.data.len();
+                inner.lrpid = pid;
+                inner.rtime = RealTimeCoarseClock::get().read_time();
+                message
+            };
+            message.data.truncate(max_len);
+            Some(Ok(message))
+        };
+
+        let message = if is_nonblocking {
+            try_receive().unwrap_or_else(|| {
+                Err(Error::with_message(
+                    Errno::ENOMSG,
+                    "no message of the desired type",
+                ))
+            })?
+        } else {
+            self.wait_queue.pause_until(try_receive)??
+        };
+
+        if !flags.contains(MsgFlags::MSG_COPY) {
+            self.wait_queue.wake_all();
+        }
+        Ok(message)
+    }
+
+    fn new(
+        id: key_t,
+        key: key_t,
+        mode: u16,
+        credentials: Credentials<ReadOp>,
+        table: Weak<MsgQueueTable>,
+    ) -> Self {
+        let permission = IpcPermission::new(key, credentials.euid(), credentials.egid(), mode);
+
+        Self {
+            id,
+            permission,
+            inner: Mutex::new(MsgQueueInner {
+                messages: VecDeque::new(),
+                cbytes: 0,
+                qbytes: MSGMNB,
+                lspid: 0,
+                lrpid: 0,
+                stime: Duration::ZERO,
+                rtime: Duration::ZERO,
+                ctime: RealTimeCoarseClock::get().read_time(),
+                is_removed: false,
+            }),
+            wait_queue: WaitQueue::new(),
+            table,
+        }
+    }
+
+    /// Marks the queue as removed and wakes up all the waiting threads,
+    /// which will fail with `EIDRM`.
+    fn mark_removed(&self) {
+        let mut inner = self.inner.lock();
+        inner.is_removed = true;
+        inner.messages.clear();
+        inner.cbytes = 0;
+        drop(inner);
+
+        self.wait_queue.wake_all();
+    }
+}
+
+impl Drop for MessageQueue {
+    fn drop(&mut self) {
+        if let Some(table) = self.table.upgrade() {
+            table.id_allocator.lock().free(self.id as usize);
+        }
+    }
+}
+
+/// The message queues in an IPC namespace.
+pub struct MsgQueueTable {
+    id_allocator: SpinLock<IdAlloc>,
+    msg_queues: RwLock<BTreeMap<key_t, Arc<MessageQueue>>>,
+}
+
+impl MsgQueueTable {
+    pub(crate) fn new() -> Arc<Self> {
+        let mut id_allocator = IdAlloc::with_capacity(MSGMNI + 1);
+        // Remove the first index 0
+        id_allocator.alloc();
+
+        Arc::new(Self {
+            id_allocator: SpinLock::new(id_allocator),
+            msg_queues: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    /// Creates a new message queue with the `key`.
+    ///
+    /// Returns the identifier of the new queue.
+    /// If the key is not `IPC_PRIVATE` and a queue with the key exists,
+    /// this function will fail with `EEXIST`.
+    pub fn create_msg_queue(
+        self: &Arc<Self>,
+        key: key_t,
+        mode: u16,
+        credentials: Credentials<ReadOp>,
+    ) -> Result<key_t> {
+        let id = self
+            .id_allocator
+            .lock()
+            .alloc()
+            .ok_or(Error::new(Errno::ENOSPC))? as key_t;
+        // The identifier will be freed when `msg_queue` is dropped
+        let msg_queue = MessageQueue::new(id, key, mode, credentials, Arc::downgrade(self));
+
+        let mut msg_queues = self.msg_queues.write();
+        if key != IPC_PRIVATE
+            && msg_queues
+                .values()
+                .any(|msg_queue| msg_queue.permission().key() == key)
+        {
+            return_errno_with_message!(Errno::EEXIST, "the key is already used");
+        }
+        msg_queues.insert(id, Arc::new(msg_queue));
+
+        Ok(id)
+    }
+
+    /// Finds the message queue that has the `key`.
+    pub fn find_msg_queue_by_key(&self, key: key_t) -> Option<Arc<MessageQueue>> {
+        debug_assert!(key != IPC_PRIVATE);
+
+        self.msg_queues
+            .read()
+            .values()
+            .find(|msg_queue| msg_queue.permission().key() == key)
+            .cloned()
+    }
+
+    /// Gets the message queue with the identifier `id`.
+    pub fn get_msg_queue(&self, id: key_t) -> Result<Arc<MessageQueue>> {
+        self.msg_queues
+            .read()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the message queue does not exist"))
+    }
+
+    /// Removes the message queue with the identifier `id` immediately.
+    pub fn remove_msg_queue(&self, id: key_t) -> Result<()> {
+        let msg_queue = self.msg_queues.write().remove(&id).ok_or_else(|| {
+            Error::with_message(Errno::EINVAL, "the message queue does not exist")
+        })?;
+        msg_queue.mark_removed();
+        Ok(())
+    }
+
+    /// Returns the identifier of the queue with the largest one in use.
+    pub fn max_msg_queue_id(&self) -> key_t {
+        self.msg_queues
+            .read()
+            .keys()
+            .next_back()
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of queues, the number of messages,
+    /// and the number of bytes of the messages in the namespace.
+    pub fn msg_queue_stats(&self) -> (usize, usize, usize) {
+        let msg_queues: Vec<_> = self.msg_queues.read().values().cloned().collect();
+        msg_queues
+            .iter()
+            .fold((0, 0, 0), |(nqueues, nmsgs, nbytes), msg_queue| {
+                let inner = msg_queue.inner.lock();
+                (
+                    nqueues + 1,
+                    nmsgs + inner.messages.len(),
+                    nbytes + inner.cbytes,
+                )
+            })
+    }
+}
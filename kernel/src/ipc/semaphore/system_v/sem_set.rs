@@ -9,7 +9,6 @@ use core::{
 use aster_rights::ReadOp;
 use id_alloc::IdAlloc;
 use ostd::sync::{PreemptDisabled, RwLockReadGuard, RwLockWriteGuard};
-use spin::Once;
 
 use super::{
     sem::{update_pending_alter, wake_const_ops, PendingOp, Status},
@@ -49,6 +48,8 @@ pub struct SemaphoreSet {
     sem_ctime: AtomicU64,
     /// Last semop time.
     sem_otime: AtomicU64,
+    /// The table that the semaphore set belongs to
+    table: Weak<SemSetTable>,
 }
 
 #[derive(Debug)]
@@ -173,7 +174,13 @@ impl SemaphoreSet {
         self.inner.lock()
     }
 
-    fn new(key: key_t, nsems: usize, mode: u16, credentials: Credentials<ReadOp>) -> Result<Self> {
+    fn new(
+        key: key_t,
+        nsems: usize,
+        mode: u16,
+        credentials: Credentials<ReadOp>,
+        table: Weak<SemSetTable>,
+    ) -> Result<Self> {
         debug_assert!(nsems <= SEMMSL);
 
         let mut sems = Vec::with_capacity(nsems);
@@ -181,8 +188,7 @@ impl SemaphoreSet {
             sems.push(Semaphore::new(0));
         }
 
-        let permission =
-            IpcPermission::new_sem_perm(key, credentials.euid(), credentials.egid(), mode);
+        let permission = IpcPermission::new(key, credentials.euid(), credentials.egid(), mode);
 
         Ok(Self {
             nsems,
@@ -194,6 +200,7 @@ impl SemaphoreSet {
                 pending_alter: LinkedList::new(),
                 pending_const: LinkedList::new(),
             }),
+            table,
         })
     }
 }
@@ -219,96 +226,115 @@ impl Drop for SemaphoreSet {
         }
         pending_const.clear();
 
-        ID_ALLOCATOR
-            .get()
-            .unwrap()
-            .lock()
-            .free(self.permission.key() as usize);
+        if let Some(table) = self.table.upgrade() {
+            table
+                .id_allocator
+                .lock()
+                .free(self.permission.key() as usize);
+        }
     }
 }
 
-pub fn create_sem_set_with_id(
-    id: key_t,
-    nsems: usize,
-    mode: u16,
-    credentials: Credentials<ReadOp>,
-) -> Result<()> {
-    debug_assert!(nsems <= SEMMSL);
-    debug_assert!(id > 0);
-    if id as usize > SEMMNI {
-        return_errno_with_message!(Errno::ENOENT, "id larger than SEMMNI");
-    }
-
-    ID_ALLOCATOR
-        .get()
-        .unwrap()
-        .lock()
-        .alloc_specific(id as usize)
-        .ok_or(Error::new(Errno::EEXIST))?;
-
-    let mut sem_sets = SEMAPHORE_SETS.write();
-    sem_sets.insert(id, SemaphoreSet::new(id, nsems, mode, credentials)?);
-
-    Ok(())
+/// The semaphore sets in an IPC namespace.
+pub struct SemSetTable {
+    id_allocator: SpinLock<IdAlloc>,
+    sem_sets: RwLock<BTreeMap<key_t, SemaphoreSet>>,
 }
 
-/// Checks the semaphore. Return Ok if the semaphore exists and pass the check.
-pub fn check_sem(id: key_t, nsems: Option<usize>, required_perm: PermissionMode) -> Result<()> {
-    debug_assert!(id > 0);
+impl SemSetTable {
+    pub(crate) fn new() -> Arc<Self> {
+        let mut id_allocator = IdAlloc::with_capacity(SEMMNI + 1);
+        // Remove the first index 0
+        id_allocator.alloc();
 
-    let sem_sets = SEMAPHORE_SETS.read();
-    let sem_set = sem_sets.get(&id).ok_or(Error::new(Errno::ENOENT))?;
+        Arc::new(Self {
+            id_allocator: SpinLock::new(id_allocator),
+            sem_sets: RwLock::new(BTreeMap::new()),
+        })
+    }
 
-    if let Some(nsems) = nsems {
+    pub fn create_sem_set_with_id(
+        self: &Arc<Self>,
+        id: key_t,
+        nsems: usize,
+        mode: u16,
+        credentials: Credentials<ReadOp>,
+    ) -> Result<()> {
         debug_assert!(nsems <= SEMMSL);
-        if nsems > sem_set.nsems() {
-            return_errno!(Errno::EINVAL);
+        debug_assert!(id > 0);
+        if id as usize > SEMMNI {
+            return_errno_with_message!(Errno::ENOENT, "id larger than SEMMNI");
         }
-    }
 
-    if !required_perm.is_empty() {
-        // TODO: Support permission check
-        warn!("Semaphore doesn't support permission check now");
-    }
+        self.id_allocator
+            .lock()
+            .alloc_specific(id as usize)
+            .ok_or(Error::new(Errno::EEXIST))?;
 
-    Ok(())
-}
+        let mut sem_sets = self.sem_sets.write();
+        sem_sets.insert(
+            id,
+            SemaphoreSet::new(id, nsems, mode, credentials, Arc::downgrade(self))?,
+        );
 
-pub fn create_sem_set(nsems: usize, mode: u16, credentials: Credentials<ReadOp>) -> Result<key_t> {
-    debug_assert!(nsems <= SEMMSL);
+        Ok(())
+    }
 
-    let id = ID_ALLOCATOR
-        .get()
-        .unwrap()
-        .lock()
-        .alloc()
-        .ok_or(Error::new(Errno::ENOSPC))? as i32;
+    /// Checks the semaphore. Return Ok if the semaphore exists and pass the check.
+    pub fn check_sem(
+        &self,
+        id: key_t,
+        nsems: Option<usize>,
+        required_perm: PermissionMode,
+    ) -> Result<()> {
+        debug_assert!(id > 0);
+
+        let sem_sets = self.sem_sets.read();
+        let sem_set = sem_sets.get(&id).ok_or(Error::new(Errno::ENOENT))?;
+
+        if let Some(nsems) = nsems {
+            debug_assert!(nsems <= SEMMSL);
+            if nsems > sem_set.nsems() {
+                return_errno!(Errno::EINVAL);
+            }
+        }
 
-    let mut sem_sets = SEMAPHORE_SETS.write();
-    sem_sets.insert(id, SemaphoreSet::new(id, nsems, mode, credentials)?);
+        if !required_perm.is_empty() {
+            // TODO: Support permission check
+            warn!("Semaphore doesn't support permission check now");
+        }
 
-    Ok(id)
-}
+        Ok(())
+    }
 
-pub fn sem_sets<'a>() -> RwLockReadGuard<'a, BTreeMap<key_t, SemaphoreSet>> {
-    SEMAPHORE_SETS.read()
-}
+    pub fn create_sem_set(
+        self: &Arc<Self>,
+        nsems: usize,
+        mode: u16,
+        credentials: Credentials<ReadOp>,
+    ) -> Result<key_t> {
+        debug_assert!(nsems <= SEMMSL);
 
-pub fn sem_sets_mut<'a>() -> RwLockWriteGuard<'a, BTreeMap<key_t, SemaphoreSet>> {
-    SEMAPHORE_SETS.write()
-}
+        let id = self
+            .id_allocator
+            .lock()
+            .alloc()
+            .ok_or(Error::new(Errno::ENOSPC))? as i32;
 
-static ID_ALLOCATOR: Once<SpinLock<IdAlloc>> = Once::new();
+        let mut sem_sets = self.sem_sets.write();
+        sem_sets.insert(
+            id,
+            SemaphoreSet::new(id, nsems, mode, credentials, Arc::downgrade(self))?,
+        );
 
-/// Semaphore sets in system
-static SEMAPHORE_SETS: RwLock<BTreeMap<key_t, SemaphoreSet>> = RwLock::new(BTreeMap::new());
+        Ok(id)
+    }
 
-pub(super) fn init() {
-    ID_ALLOCATOR.call_once(|| {
-        let mut id_alloc = IdAlloc::with_capacity(SEMMNI + 1);
-        // Remove the first index 0
-        id_alloc.alloc();
+    pub fn sem_sets(&self) -> RwLockReadGuard<BTreeMap<key_t, SemaphoreSet>> {
+        self.sem_sets.read()
+    }
 
-        SpinLock::new(id_alloc)
-    });
+    pub fn sem_sets_mut(&self) -> RwLockWriteGuard<BTreeMap<key_t, SemaphoreSet>> {
+        self.sem_sets.write()
+    }
 }
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::shared_memory::SharedMemory;
+use crate::{
+    prelude::*,
+    process::{posix_thread::PosixThreadExt, Pid},
+    thread::Thread,
+};
+
+/// An attachment of a shared memory segment, which is held by a mapping of the segment.
+///
+/// Like the `open` and `close` operations of the mappings of System V shared memory in Linux,
+/// an attachment is counted for every mapping of the segment. Splitting a mapping or forking
+/// the address space counts new attachments, and dropping a mapping for any reason (e.g.,
+/// `shmdt`, `munmap`, being overwritten by `MAP_FIXED`, exec or exit) detaches the segment.
+pub struct ShmAttachment {
+    shm: Arc<SharedMemory>,
+}
+
+impl ShmAttachment {
+    /// Attaches the `shm` for a new mapping.
+    pub fn new(shm: Arc<SharedMemory>) -> Self {
+        shm.attach(current_pid());
+        Self { shm }
+    }
+
+    /// Returns the attached segment.
+    pub fn shm(&self) -> &Arc<SharedMemory> {
+        &self.shm
+    }
+}
+
+impl Clone for ShmAttachment {
+    fn clone(&self) -> Self {
+        Self::new(self.shm.clone())
+    }
+}
+
+impl Drop for ShmAttachment {
+    fn drop(&mut self) {
+        self.shm.detach(current_pid());
+    }
+}
+
+/// Returns the PID of the current process, if the current thread belongs to a process.
+fn current_pid() -> Option<Pid> {
+    let thread = Thread::current()?;
+    let process = thread.as_posix_thread()?.weak_process().upgrade()?;
+    Some(process.pid())
+}
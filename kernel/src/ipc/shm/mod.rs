@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! System V shared memory.
+//!
+//! A shared memory segment is backed by an anonymous VMO,
+//! which is mapped into the address spaces of the attaching processes.
+//! Each mapping of a segment holds a [`ShmAttachment`],
+//! so that the segment is detached whenever the mapping goes away.
+
+mod attachment;
+pub mod shared_memory;
+
+pub use attachment::ShmAttachment;
+
+use crate::prelude::*;
+
+bitflags! {
+    pub struct ShmFlags: u32 {
+        /// Attach the segment for read-only access.
+        const SHM_RDONLY = 0o10000;
+        /// Round the attach address down to a multiple of `SHMLBA`.
+        const SHM_RND    = 0o20000;
+        /// Take over the existing mappings in the attach range.
+        const SHM_REMAP  = 0o40000;
+        /// Allow the contents of the segment to be executed.
+        const SHM_EXEC   = 0o100000;
+    }
+}
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+pub enum ShmControlCmd {
+    IPC_RMID = 0,
+    IPC_SET = 1,
+    IPC_STAT = 2,
+    IPC_INFO = 3,
+
+    SHM_LOCK = 11,
+    SHM_UNLOCK = 12,
+    SHM_STAT = 13,
+    SHM_INFO = 14,
+}
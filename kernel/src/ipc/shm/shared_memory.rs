@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::btree_map::BTreeMap;
+use core::time::Duration;
+
+use align_ext::AlignExt;
+use aster_rights::{ReadOp, Rights};
+use id_alloc::IdAlloc;
+
+use crate::{
+    ipc::{key_t, IpcPermission},
+    prelude::*,
+    process::{Credentials, Pid},
+    time::clocks::RealTimeCoarseClock,
+    vm::vmo::{Vmo, VmoOptions},
+};
+
+// The following constant values are derived from the default values in Linux.
+
+/// Maximum number of shared memory segments.
+pub const SHMMNI: usize = 4096;
+/// Minimum size in bytes of a shared memory segment.
+pub const SHMMIN: usize = 1;
+/// Maximum size in bytes of a shared memory segment.
+pub const SHMMAX: usize = usize::MAX - (1 << 24);
+/// Maximum number of pages of all shared memory segments.
+pub const SHMALL: usize = usize::MAX - (1 << 24);
+/// Maximum number of segments that a process can attach.
+pub const SHMSEG: usize = SHMMNI;
+/// The alignment of the attach addresses.
+pub const SHMLBA: usize = PAGE_SIZE;
+
+/// The key that always creates a new shared memory segment.
+pub const IPC_PRIVATE: key_t = 0;
+
+pub struct SharedMemory {
+    /// The identifier of the segment
+    id: key_t,
+    /// Size in bytes
+    size: usize,
+    /// The pages of the segment
+    vmo: Vmo<Rights>,
+    /// Segment permission
+    permission: IpcPermission,
+    /// PID of the creator
+    cpid: Pid,
+    /// Inner
+    inner: SpinLock<ShmInner>,
+    /// The table that the segment belongs to
+    table: Weak<ShmTable>,
+}
+
+struct ShmInner {
+    /// Number of current attaches
+    nattch: usize,
+    /// PID of the last `shmat` or `shmdt`
+    lpid: Pid,
+    /// Last attach time
+    atime: Duration,
+    /// Last detach time
+    dtime: Duration,
+    /// Creation time or last modification via `shmctl`
+    ctime: Duration,
+    /// Whether the segment is marked to be destroyed
+    is_removed: bool,
+}
+
+impl SharedMemory {
+    pub fn id(&self) -> key_t {
+        self.id
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn vmo(&self) -> &Vmo<Rights> {
+        &self.vmo
+    }
+
+    pub fn permission(&self) -> &IpcPermission {
+        &self.permission
+    }
+
+    pub fn cpid(&self) -> Pid {
+        self.cpid
+    }
+
+    pub fn lpid(&self) -> Pid {
+        self.inner.lock().lpid
+    }
+
+    pub fn nattch(&self) -> usize {
+        self.inner.lock().nattch
+    }
+
+    pub fn atime(&self) -> Duration {
+        self.inner.lock().atime
+    }
+
+    pub fn dtime(&self) -> Duration {
+        self.inner.lock().dtime
+    }
+
+    pub fn ctime(&self) -> Duration {
+        self.inner.lock().ctime
+    }
+
+    /// Returns whether the segment is marked to be destroyed.
+    pub fn is_removed(&self) -> bool {
+        self.inner.lock().is_removed
+    }
+
+    /// Records an attach of the segment.
+    ///
+    /// `pid` is the PID of the process that causes the attach, if any.
+    pub(super) fn attach(&self, pid: Option<Pid>) {
+        let mut inner = self.inner.lock();
+        inner.nattch += 1;
+        if let Some(pid) = pid {
+            inner.lpid = pid;
+        }
+        inner.atime = RealTimeCoarseClock::get().read_time();
+    }
+
+    /// Records a detach of the segment.
+    ///
+    /// `pid` is the PID of the process that causes the detach, if any.
+    /// The segment will be destroyed if it is marked to be destroyed and
+    /// this is the last detach.
+    pub(super) fn detach(&self, pid: Option<Pid>) {
+        let should_destroy = {
+            let mut inner = self.inner.lock();
+            debug_assert!(inner.nattch > 0);
+            inner.nattch -= 1;
+            if let Some(pid) = pid {
+                inner.lpid = pid;
+            }
+            inner.dtime = RealTimeCoarseClock::get().read_time();
+            inner.is_removed && inner.nattch == 0
+        };
+
+        if should_destroy && let Some(table) = self.table.upgrade() {
+            table.remove(self.id);
+        }
+    }
+
+    /// Marks the segment to be destroyed.
+    ///
+    /// The segment is destroyed once it is no longer attached by any process.
+    /// After being marked, the segment cannot be found by its key any more.
+    pub fn mark_removed(&self) {
+        let should_destroy = {
+            let mut inner = self.inner.lock();
+            inner.is_removed = true;
+            inner.ctime = RealTimeCoarseClock::get().read_time();
+            inner.nattch == 0
+        };
+
+        if should_destroy && let Some(table) = self.table.upgrade() {
+            table.remove(self.id);
+        }
+    }
+
+    fn new(
+        id: key_t,
+        key: key_t,
+        size: usize,
+        mode: u16,
+        pid: Pid,
+        credentials: Credentials<ReadOp>,
+        table: Weak<ShmTable>,
+    ) -> Result<Self> {
+        let vmo = VmoOptions::<Rights>::new(size.align_up(PAGE_SIZE)).alloc()?;
+        let permission = IpcPermission::new(key, credentials.euid(), credentials.egid(), mode);
+
+        Ok(Self {
+            id,
+            size,
+            vmo,
+            permission,
+            cpid: pid,
+            inner: SpinLock::new(ShmInner {
+                nattch: 0,
+                lpid: 0,
+                atime: Duration::ZERO,
+                dtime: Duration::ZERO,
+                ctime: RealTimeCoarseClock::get().read_time(),
+                is_removed: false,
+            }),
+            table,
+        })
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        if let Some(table) = self.table.upgrade() {
+            table.id_allocator.lock().free(self.id as usize);
+        }
+    }
+}
+
+/// The shared memory segments in an IPC namespace.
+pub struct ShmTable {
+    id_allocator: SpinLock<IdAlloc>,
+    segments: RwLock<BTreeMap<key_t, Arc<SharedMemory>>>,
+}
+
+impl ShmTable {
+    pub(crate) fn new() -> Arc<Self> {
+        let mut id_allocator = IdAlloc::with_capacity(SHMMNI + 1);
+        // Remove the first index 0
+        id_allocator.alloc();
+
+        Arc::new(Self {
+            id_allocator: SpinLock::new(id_allocator),
+            segments: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    /// Creates a new shared memory segment with the `key`.
+    ///
+    /// Returns the identifier of the new segment.
+    /// If the key is not `IPC_PRIVATE` and a segment with the key exists,
+    /// this function will fail with `EEXIST`.
+    pub fn create_shm(
+        self: &Arc<Self>,
+        key: key_t,
+        size: usize,
+        mode: u16,
+        pid: Pid,
+        credentials: Credentials<ReadOp>,
+    ) -> Result<key_t> {
+        if !(SHMMIN..=SHMMAX).contains(&size) {
+            return_errno_with_message!(Errno::EINVAL, "the size of the segment is invalid");
+        }
+
+        let id = self
+            .id_allocator
+            .lock()
+            .alloc()
+            .ok_or(Error::new(Errno::ENOSPC))? as key_t;
+        // The identifier will be freed when `shm` is dropped
+        let shm = SharedMemory::new(id, key, size, mode, pid, credentials, Arc::downgrade(self))?;
+
+        let mut segments = self.segments.write();
+        if key != IPC_PRIVATE
+            && segments
+                .values()
+                .any(|shm| shm.permission().key() == key && !shm.is_removed())
+        {
+            return_errno_with_message!(Errno::EEXIST, "the key is already used");
+        }
+        segments.insert(id, Arc::new(shm));
+
+        Ok(id)
+    }
+
+    /// Finds the shared memory segment that has the `key` and is not marked to be destroyed.
+    pub fn find_shm_by_key(&self, key: key_t) -> Option<Arc<SharedMemory>> {
+        debug_assert!(key != IPC_PRIVATE);
+
+        self.segments
+            .read()
+            .values()
+            .find(|shm| shm.permission().key() == key && !shm.is_removed())
+            .cloned()
+    }
+
+    /// Gets the shared memory segment with the identifier `id`.
+    pub fn get_shm(&self, id: key_t) -> Result<Arc<SharedMemory>> {
+        self.segments
+            .read()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the segment does not exist"))
+    }
+
+    /// Returns the identifier of the segment with the largest one in use.
+    pub fn max_shm_id(&self) -> key_t {
+        self.segments
+            .read()
+            .keys()
+            .next_back()
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    fn remove(&self, id: key_t) {
+        self.segments.write().remove(&id);
+    }
+}
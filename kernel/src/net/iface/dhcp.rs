@@ -0,0 +1,684 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! A DHCPv4 client that configures the first Ethernet iface during boot.
+//!
+//! The client is enabled by the `net.ip=dhcp` kernel command-line option, which is modeled after
+//! the `ip=dhcp` option of Linux. It runs before the init process is spawned and configures the
+//! address, the subnet mask, and the default gateway of `eth0`. Like Linux, the DNS servers are
+//! exposed to user space at `/proc/net/pnp`, which can be linked to `/etc/resolv.conf`.
+//!
+//! The iface cannot send or receive UDP packets before it has an address, so the client builds
+//! the frames by itself, injects them into the iface, and captures the replies with a
+//! [`PacketTap`].
+//!
+//! After the iface is configured, a kernel thread renews the lease at T1 and T2, as suggested by
+//! RFC 2131. If the lease cannot be renewed before it expires, the thread obtains a new lease
+//! through the full exchange. The address is kept in the meantime, since there is no way to leave
+//! an iface without an address.
+//!
+//! See <https://datatracker.ietf.org/doc/html/rfc2131>.
+
+use core::time::Duration;
+
+use aster_bigtcp::{
+    iface::{PacketTap, TapDirection},
+    wire::{EthernetAddress, HardwareAddress, IpAddress, IpCidr, Ipv4Address, Ipv4Cidr},
+};
+use ostd::{
+    boot::{kcmdline::ModuleArg, kernel_cmdline},
+    sync::{LocalIrqDisabled, WaitQueue},
+    timer::Jiffies,
+};
+use spin::Once;
+
+use super::{add_route, find_iface_by_name, set_iface_cidr, Iface, Route};
+use crate::{
+    prelude::*,
+    thread::{
+        kernel_thread::{KernelThreadExt, ThreadOptions},
+        Thread,
+    },
+    util::random::getrandom,
+    WaitTimeout,
+};
+
+/// The lease obtained from the DHCP server.
+#[derive(Debug, Clone)]
+pub struct DhcpLease {
+    /// The address and the subnet of the iface.
+    pub cidr: Ipv4Cidr,
+    /// The default gateway.
+    pub router: Option<Ipv4Address>,
+    /// The DNS servers.
+    pub dns_servers: Vec<Ipv4Address>,
+    /// The address of the DHCP server.
+    pub server: Ipv4Address,
+    /// The times of the lease, or `None` if the lease is infinite.
+    pub times: Option<LeaseTimes>,
+}
+
+/// The times of a lease, which are relative to the time when the lease is obtained.
+#[derive(Debug, Clone, Copy)]
+pub struct LeaseTimes {
+    /// The time when the client starts to renew the lease (T1).
+    pub renewal: Duration,
+    /// The time when the client starts to rebind the lease (T2).
+    pub rebinding: Duration,
+    /// The time when the lease expires.
+    pub expiry: Duration,
+}
+
+static LEASE: Once<DhcpLease> = Once::new();
+
+/// Returns the lease obtained during boot, if DHCP is enabled and has succeeded.
+///
+/// Like Linux, the lease is not updated when it is renewed or replaced later.
+pub fn dhcp_lease() -> Option<&'static DhcpLease> {
+    LEASE.get()
+}
+
+/// Returns whether DHCP is enabled by the kernel command line.
+pub(super) fn is_enabled() -> bool {
+    let Some(module_args) = kernel_cmdline().get_module_args("net") else {
+        return false;
+    };
+
+    module_args.iter().any(|arg| match arg {
+        ModuleArg::KeyVal(name, value) => name.as_bytes() == b"ip" && value.as_bytes() == b"dhcp",
+        ModuleArg::Arg(_) => false,
+    })
+}
+
+/// The number of times that a DHCP message is sent before the client gives up.
+const MAX_ATTEMPTS: u32 = 4;
+/// The time to wait for the reply to the first attempt.
+///
+/// The time is doubled after each attempt, as suggested by RFC 2131.
+const INITIAL_TIMEOUT: Duration = Duration::from_secs(1);
+/// The time to wait before the client tries again to obtain a new lease after it fails.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Configures `eth0` via DHCP, if DHCP is enabled.
+///
+/// If the client fails to obtain a lease, the iface is left unconfigured.
+pub(super) fn configure() {
+    if !is_enabled() {
+        return;
+    }
+
+    let Some((index, iface)) = find_iface_by_name("eth0") else {
+        warn!("DHCP is enabled, but there is no Ethernet iface");
+        return;
+    };
+
+    let (client, lease) = match Client::new(iface.clone()).and_then(|client| {
+        let lease = client.request_lease()?;
+        Ok((client, lease))
+    }) {
+        Ok(client_and_lease) => client_and_lease,
+        Err(err) => {
+            warn!("DHCP failed on {}: {:?}", iface.name(), err);
+            return;
+        }
+    };
+
+    if let Err(err) = apply_lease(index, &lease) {
+        warn!(
+            "DHCP cannot configure {} with {}: {:?}",
+            iface.name(),
+            lease.cidr,
+            err
+        );
+        return;
+    }
+
+    println!(
+        "[kernel] DHCP: {} is configured with {} (gateway: {:?}, DNS: {:?})",
+        iface.name(),
+        lease.cidr,
+        lease.router,
+        lease.dns_servers
+    );
+
+    if lease.times.is_some() {
+        let boot_lease = lease.clone();
+        let task_fn = move || maintain_lease(&client, index, boot_lease.clone());
+        Thread::spawn_kernel_thread(ThreadOptions::new(task_fn));
+    }
+    LEASE.call_once(|| lease);
+}
+
+/// Sets the address of the iface and the default route according to the lease.
+fn apply_lease(index: u32, lease: &DhcpLease) -> Result<()> {
+    set_iface_cidr(index, IpCidr::Ipv4(lease.cidr))?;
+
+    if let Some(router) = lease.router {
+        let default_cidr = IpCidr::Ipv4(Ipv4Cidr::new(Ipv4Address::UNSPECIFIED, 0));
+        let route = Route::new(default_cidr, Some(IpAddress::Ipv4(router)), index);
+        if let Err(err) = add_route(route, true) {
+            warn!(
+                "DHCP cannot add the default route via {}: {:?}",
+                router, err
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the iface configured by renewing the lease or obtaining a new one.
+///
+/// This method returns only if the lease becomes infinite.
+fn maintain_lease(client: &Client, index: u32, mut lease: DhcpLease) {
+    while let Some(times) = lease.times {
+        let obtained_at = Jiffies::elapsed().as_duration();
+
+        let new_lease = match client.extend_lease(&lease, &times, obtained_at) {
+            Some(new_lease) => new_lease,
+            None => {
+                warn!(
+                    "DHCP lease of {} on {} is lost",
+                    lease.cidr,
+                    client.iface.name()
+                );
+                client.request_lease_until_success()
+            }
+        };
+
+        if (new_lease.cidr, new_lease.router) != (lease.cidr, lease.router) {
+            if let Err(err) = apply_lease(index, &new_lease) {
+                warn!(
+                    "DHCP cannot configure {} with {}: {:?}",
+                    client.iface.name(),
+                    new_lease.cidr,
+                    err
+                );
+            }
+        }
+        lease = new_lease;
+    }
+}
+
+struct Client {
+    iface: Arc<Iface>,
+    ether_addr: EthernetAddress,
+    xid: u32,
+    /// The tap that captures the replies.
+    ///
+    /// The iface only holds a weak reference to the tap, so the tap is removed when the client is
+    /// dropped.
+    tap: Arc<ReplyTap>,
+}
+
+impl Client {
+    fn new(iface: Arc<Iface>) -> Result<Self> {
+        let HardwareAddress::Ethernet(ether_addr) = iface.hardware_addr() else {
+            return_errno_with_message!(Errno::EOPNOTSUPP, "the iface is not an Ethernet iface");
+        };
+
+        let mut xid = [0; 4];
+        getrandom(&mut xid)?;
+        let xid = u32::from_ne_bytes(xid);
+
+        let tap = Arc::new(ReplyTap {
+            xid,
+            ether_addr,
+            replies: SpinLock::new(VecDeque::new()),
+            wait_queue: WaitQueue::new(),
+        });
+        if !iface.add_tap(Arc::downgrade(&tap) as _) {
+            return_errno_with_message!(Errno::EOPNOTSUPP, "the iface does not support taps");
+        }
+
+        Ok(Self {
+            iface,
+            ether_addr,
+            xid,
+            tap,
+        })
+    }
+
+    /// Obtains a lease through the DISCOVER-OFFER-REQUEST-ACK exchange.
+    fn request_lease(&self) -> Result<DhcpLease> {
+        let discover = self.build_message(MessageType::Discover, Ipv4Address::UNSPECIFIED, None);
+        let offer = self.exchange(&discover, |reply| {
+            reply.message_type == MessageType::Offer && reply.server.is_some()
+        })?;
+
+        let request = self.build_message(
+            MessageType::Request,
+            Ipv4Address::UNSPECIFIED,
+            Some((offer.your_addr, offer.server.unwrap())),
+        );
+        let ack = self.exchange(&request, |reply| {
+            matches!(reply.message_type, MessageType::Ack | MessageType::Nak)
+                && reply.server == offer.server
+        })?;
+        if ack.message_type == MessageType::Nak {
+            return_errno_with_message!(Errno::ECONNREFUSED, "the DHCP request is rejected");
+        }
+
+        Ok(lease_from_ack(ack))
+    }
+
+    /// Obtains a lease, retrying after failures until it succeeds.
+    fn request_lease_until_success(&self) -> DhcpLease {
+        loop {
+            match self.request_lease() {
+                Ok(lease) => return lease,
+                Err(err) => warn!("DHCP failed on {}: {:?}", self.iface.name(), err),
+            }
+            self.sleep_until(Jiffies::elapsed().as_duration() + RETRY_INTERVAL);
+        }
+    }
+
+    /// Extends the lease at T1 and, if it fails, at T2.
+    ///
+    /// This method returns `None` after the lease expires or the server refuses to extend it.
+    fn extend_lease(
+        &self,
+        lease: &DhcpLease,
+        times: &LeaseTimes,
+        obtained_at: Duration,
+    ) -> Option<DhcpLease> {
+        for deadline in [times.renewal, times.rebinding] {
+            self.sleep_until(obtained_at + deadline);
+
+            match self.renew_lease(lease) {
+                Ok(new_lease) => return Some(new_lease),
+                Err(err) if err.error() == Errno::ECONNREFUSED => return None,
+                Err(err) => warn!("DHCP cannot renew the lease of {}: {:?}", lease.cidr, err),
+            }
+        }
+
+        self.sleep_until(obtained_at + times.expiry);
+        None
+    }
+
+    /// Renews the lease through the REQUEST-ACK exchange.
+    ///
+    /// The request is broadcast even at T1, so the server handles it as a request in the
+    /// REBINDING state, which extends the lease all the same.
+    fn renew_lease(&self, lease: &DhcpLease) -> Result<DhcpLease> {
+        let request = self.build_message(MessageType::Request, lease.cidr.address(), None);
+        let ack = self.exchange(&request, |reply| match reply.message_type {
+            MessageType::Ack => reply.server.is_some(),
+            MessageType::Nak => true,
+            _ => false,
+        })?;
+        if ack.message_type == MessageType::Nak {
+            return_errno_with_message!(Errno::ECONNREFUSED, "the DHCP renewal is rejected");
+        }
+
+        Ok(lease_from_ack(ack))
+    }
+
+    /// Sleeps until `deadline`, which is relative to the boot time.
+    fn sleep_until(&self, deadline: Duration) {
+        let now = Jiffies::elapsed().as_duration();
+        if deadline <= now {
+            return;
+        }
+
+        // No reply is wanted, so this only returns after the timeout.
+        let _ = self
+            .tap
+            .wait_queue
+            .wait_until_or_timeout(|| None::<()>, &(deadline - now));
+    }
+
+    /// Sends the message and waits for the reply that satisfies `is_wanted`.
+    ///
+    /// The message is sent again if there is no such reply after a timeout.
+    fn exchange(&self, message: &[u8], is_wanted: impl Fn(&Reply) -> bool) -> Result<Reply> {
+        let mut timeout = INITIAL_TIMEOUT;
+
+        for _ in 0..MAX_ATTEMPTS {
+            self.tap.replies.lock().clear();
+            if let Err(err) = self.iface.inject_frame(message) {
+                warn!("DHCP cannot send the message: {:?}", err);
+            }
+
+            let reply = self.tap.wait_queue.wait_until_or_timeout(
+                || {
+                    let mut replies = self.tap.replies.lock();
+                    while let Some(reply) = replies.pop_front() {
+                        if is_wanted(&reply) {
+                            return Some(reply);
+                        }
+                    }
+                    None
+                },
+                &timeout,
+            );
+            if let Ok(reply) = reply {
+                return Ok(reply);
+            }
+
+            timeout *= 2;
+        }
+
+        return_errno_with_message!(Errno::ETIMEDOUT, "the DHCP server does not reply")
+    }
+
+    /// Builds an Ethernet frame that contains a broadcast DHCP message.
+    ///
+    /// `client_addr` is the address of the client when it renews the lease, or unspecified
+    /// otherwise. For `DHCPREQUEST` messages that select an offer, `selected` contains the offered
+    /// address and the server.
+    fn build_message(
+        &self,
+        message_type: MessageType,
+        client_addr: Ipv4Address,
+        selected: Option<(Ipv4Address, Ipv4Address)>,
+    ) -> Vec<u8> {
+        let mut dhcp = Vec::with_capacity(MIN_BOOTP_LEN);
+        dhcp.extend_from_slice(&[
+            BOOTREQUEST,
+            HTYPE_ETHERNET,
+            ETHER_ADDR_LEN as u8,
+            0, // hops
+        ]);
+        dhcp.extend_from_slice(&self.xid.to_be_bytes());
+        dhcp.extend_from_slice(&0u16.to_be_bytes()); // secs
+        dhcp.extend_from_slice(&BOOTP_FLAG_BROADCAST.to_be_bytes());
+        dhcp.extend_from_slice(client_addr.as_bytes());
+        // The your, server, and relay agent addresses are all unspecified.
+        dhcp.extend_from_slice(&[0; 12]);
+        let mut chaddr = [0; 16];
+        chaddr[..ETHER_ADDR_LEN].copy_from_slice(self.ether_addr.as_bytes());
+        dhcp.extend_from_slice(&chaddr);
+        // The server host name and the boot file name are unused.
+        dhcp.extend_from_slice(&[0; 64 + 128]);
+        dhcp.extend_from_slice(&DHCP_MAGIC_COOKIE);
+
+        dhcp.extend_from_slice(&[OPT_MESSAGE_TYPE, 1, message_type as u8]);
+        if let Some((addr, server)) = selected {
+            dhcp.extend_from_slice(&[OPT_REQUESTED_ADDR, 4]);
+            dhcp.extend_from_slice(addr.as_bytes());
+            dhcp.extend_from_slice(&[OPT_SERVER_ID, 4]);
+            dhcp.extend_from_slice(server.as_bytes());
+        }
+        dhcp.extend_from_slice(&[
+            OPT_PARAMETER_REQUEST_LIST,
+            6,
+            OPT_SUBNET_MASK,
+            OPT_ROUTER,
+            OPT_DNS_SERVER,
+            OPT_LEASE_TIME,
+            OPT_RENEWAL_TIME,
+            OPT_REBINDING_TIME,
+        ]);
+        dhcp.push(OPT_END);
+        // Some servers ignore the messages that are shorter than the BOOTP messages.
+        dhcp.resize(dhcp.len().max(MIN_BOOTP_LEN), 0);
+
+        let udp_len = UDP_HEADER_LEN + dhcp.len();
+        let ip_len = IPV4_HEADER_LEN + udp_len;
+
+        let mut frame = Vec::with_capacity(ETHER_HEADER_LEN + ip_len);
+        frame.extend_from_slice(EthernetAddress::BROADCAST.as_bytes());
+        frame.extend_from_slice(self.ether_addr.as_bytes());
+        frame.extend_from_slice(&ETHER_TYPE_IPV4.to_be_bytes());
+
+        let mut ip_header = [0; IPV4_HEADER_LEN];
+        ip_header[0] = 0x45; // Version 4 without options
+        ip_header[2..4].copy_from_slice(&(ip_len as u16).to_be_bytes());
+        ip_header[8] = 64; // TTL
+        ip_header[9] = IPPROTO_UDP;
+        ip_header[12..16].copy_from_slice(client_addr.as_bytes());
+        ip_header[16..20].copy_from_slice(Ipv4Address::BROADCAST.as_bytes());
+        let checksum = ipv4_header_checksum(&ip_header);
+        ip_header[10..12].copy_from_slice(&checksum.to_be_bytes());
+        frame.extend_from_slice(&ip_header);
+
+        // The UDP checksum is optional for IPv4, so it is left as zero.
+        frame.extend_from_slice(&DHCP_CLIENT_PORT.to_be_bytes());
+        frame.extend_from_slice(&DHCP_SERVER_PORT.to_be_bytes());
+        frame.extend_from_slice(&(udp_len as u16).to_be_bytes());
+        frame.extend_from_slice(&0u16.to_be_bytes());
+
+        frame.extend_from_slice(&dhcp);
+        frame
+    }
+}
+
+/// A packet tap that captures the DHCP replies to the client.
+struct ReplyTap {
+    xid: u32,
+    ether_addr: EthernetAddress,
+    replies: SpinLock<VecDeque<Reply>, LocalIrqDisabled>,
+    wait_queue: WaitQueue,
+}
+
+impl PacketTap for ReplyTap {
+    fn on_frame(&self, frame: &[u8], direction: TapDirection) {
+        if direction != TapDirection::Incoming {
+            return;
+        }
+        let Some(reply) = parse_reply(frame, self.xid, self.ether_addr) else {
+            return;
+        };
+
+        self.replies.lock().push_back(reply);
+        self.wait_queue.wake_all();
+    }
+}
+
+/// A parsed DHCP reply.
+struct Reply {
+    message_type: MessageType,
+    your_addr: Ipv4Address,
+    server: Option<Ipv4Address>,
+    subnet_mask: Option<Ipv4Address>,
+    router: Option<Ipv4Address>,
+    dns_servers: Vec<Ipv4Address>,
+    lease_time: Option<u32>,
+    renewal_time: Option<u32>,
+    rebinding_time: Option<u32>,
+}
+
+/// Parses the DHCP reply in an Ethernet frame.
+///
+/// This method returns `None` if the frame does not contain a DHCP reply to the client with
+/// `xid` and `ether_addr`.
+fn parse_reply(frame: &[u8], xid: u32, ether_addr: EthernetAddress) -> Option<Reply> {
+    let ether_type = u16::from_be_bytes(frame.get(12..ETHER_HEADER_LEN)?.try_into().unwrap());
+    if ether_type != ETHER_TYPE_IPV4 {
+        return None;
+    }
+
+    let ip = &frame[ETHER_HEADER_LEN..];
+    let ip_header_len = (*ip.first()? & 0x0f) as usize * 4;
+    if ip.len() < ip_header_len.max(IPV4_HEADER_LEN) || ip[9] != IPPROTO_UDP {
+        return None;
+    }
+
+    let udp = &ip[ip_header_len..];
+    if udp.len() < UDP_HEADER_LEN
+        || u16::from_be_bytes([udp[2], udp[3]]) != DHCP_CLIENT_PORT
+        || u16::from_be_bytes([udp[0], udp[1]]) != DHCP_SERVER_PORT
+    {
+        return None;
+    }
+
+    let dhcp = &udp[UDP_HEADER_LEN..];
+    if dhcp.len() < BOOTP_HEADER_LEN + DHCP_MAGIC_COOKIE.len()
+        || dhcp[0] != BOOTREPLY
+        || dhcp[4..8] != xid.to_be_bytes()
+        || dhcp[28..28 + ETHER_ADDR_LEN] != *ether_addr.as_bytes()
+        || dhcp[BOOTP_HEADER_LEN..BOOTP_HEADER_LEN + 4] != DHCP_MAGIC_COOKIE
+    {
+        return None;
+    }
+
+    let mut message_type = None;
+    let mut server = None;
+    let mut subnet_mask = None;
+    let mut router = None;
+    let mut dns_servers = Vec::new();
+    let mut lease_time = None;
+    let mut renewal_time = None;
+    let mut rebinding_time = None;
+
+    let mut options = &dhcp[BOOTP_HEADER_LEN + 4..];
+    while let Some((&code, rest)) = options.split_first() {
+        match code {
+            OPT_PAD => {
+                options = rest;
+                continue;
+            }
+            OPT_END => break,
+            _ => (),
+        }
+
+        let (&len, rest) = rest.split_first()?;
+        let value = rest.get(..len as usize)?;
+        options = &rest[len as usize..];
+
+        let first_addr = value.get(..4).map(Ipv4Address::from_bytes);
+        let first_u32 = value
+            .get(..4)
+            .map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()));
+        match code {
+            OPT_MESSAGE_TYPE => {
+                message_type = value
+                    .first()
+                    .and_then(|&type_| MessageType::try_from(type_).ok())
+            }
+            OPT_SERVER_ID => server = first_addr,
+            OPT_SUBNET_MASK => subnet_mask = first_addr,
+            OPT_ROUTER => router = first_addr,
+            OPT_DNS_SERVER => {
+                dns_servers = value.chunks_exact(4).map(Ipv4Address::from_bytes).collect()
+            }
+            OPT_LEASE_TIME => lease_time = first_u32,
+            OPT_RENEWAL_TIME => renewal_time = first_u32,
+            OPT_REBINDING_TIME => rebinding_time = first_u32,
+            _ => (),
+        }
+    }
+
+    Some(Reply {
+        message_type: message_type?,
+        your_addr: Ipv4Address::from_bytes(&dhcp[16..20]),
+        server,
+        subnet_mask,
+        router,
+        dns_servers,
+        lease_time,
+        renewal_time,
+        rebinding_time,
+    })
+}
+
+/// Builds the lease from a `DHCPACK` reply, which must contain the server identifier.
+fn lease_from_ack(ack: Reply) -> DhcpLease {
+    let subnet_mask = ack
+        .subnet_mask
+        .unwrap_or_else(|| classful_subnet_mask(ack.your_addr));
+    let prefix_len = u32::from_be_bytes(subnet_mask.0).leading_ones() as u8;
+
+    // The server must provide the lease time, so the lease is treated as infinite if it doesn't.
+    let times = match ack.lease_time {
+        None | Some(INFINITE_LEASE_TIME) => None,
+        Some(lease_time) => {
+            let expiry = Duration::from_secs(lease_time as u64);
+            // The default values are suggested by RFC 2131.
+            let rebinding = ack
+                .rebinding_time
+                .map_or(expiry * 7 / 8, |secs| Duration::from_secs(secs as u64))
+                .min(expiry);
+            let renewal = ack
+                .renewal_time
+                .map_or(expiry / 2, |secs| Duration::from_secs(secs as u64))
+                .min(rebinding);
+            Some(LeaseTimes {
+                renewal,
+                rebinding,
+                expiry,
+            })
+        }
+    };
+
+    DhcpLease {
+        cidr: Ipv4Cidr::new(ack.your_addr, prefix_len),
+        router: ack.router,
+        dns_servers: ack.dns_servers,
+        server: ack.server.unwrap(),
+        times,
+    }
+}
+
+/// Returns the subnet mask of the address class, which is used if the server does not provide
+/// the subnet mask.
+fn classful_subnet_mask(addr: Ipv4Address) -> Ipv4Address {
+    match addr.0[0] {
+        0..=127 => Ipv4Address::new(255, 0, 0, 0),
+        128..=191 => Ipv4Address::new(255, 255, 0, 0),
+        _ => Ipv4Address::new(255, 255, 255, 0),
+    }
+}
+
+fn ipv4_header_checksum(header: &[u8]) -> u16 {
+    let mut sum = header
+        .chunks_exact(2)
+        .map(|word| u16::from_be_bytes([word[0], word[1]]) as u32)
+        .sum::<u32>();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+const ETHER_ADDR_LEN: usize = 6;
+const ETHER_HEADER_LEN: usize = 14;
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const IPV4_HEADER_LEN: usize = 20;
+const IPPROTO_UDP: u8 = 17;
+const UDP_HEADER_LEN: usize = 8;
+
+const DHCP_SERVER_PORT: u16 = 67;
+const DHCP_CLIENT_PORT: u16 = 68;
+
+/// The length of the fixed part of BOOTP messages, which is followed by the magic cookie and the
+/// options.
+const BOOTP_HEADER_LEN: usize = 236;
+/// The minimum length of BOOTP messages.
+const MIN_BOOTP_LEN: usize = 300;
+const BOOTREQUEST: u8 = 1;
+const BOOTREPLY: u8 = 2;
+const HTYPE_ETHERNET: u8 = 1;
+/// The flag that asks the server to broadcast the replies, since the client cannot receive
+/// unicast IP packets before it has an address.
+const BOOTP_FLAG_BROADCAST: u16 = 0x8000;
+const DHCP_MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+/// The lease time that means an infinite lease.
+const INFINITE_LEASE_TIME: u32 = u32::MAX;
+
+// DHCP options.
+//
+// See <https://datatracker.ietf.org/doc/html/rfc2132>.
+const OPT_PAD: u8 = 0;
+const OPT_SUBNET_MASK: u8 = 1;
+const OPT_ROUTER: u8 = 3;
+const OPT_DNS_SERVER: u8 = 6;
+const OPT_REQUESTED_ADDR: u8 = 50;
+const OPT_LEASE_TIME: u8 = 51;
+const OPT_MESSAGE_TYPE: u8 = 53;
+const OPT_SERVER_ID: u8 = 54;
+const OPT_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPT_RENEWAL_TIME: u8 = 58;
+const OPT_REBINDING_TIME: u8 = 59;
+const OPT_END: u8 = 255;
+
+/// DHCP message types.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+enum MessageType {
+    Discover = 1,
+    Offer = 2,
+    Request = 3,
+    Ack = 5,
+    Nak = 6,
+}
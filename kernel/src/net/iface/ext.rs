@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::string::String;
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use ostd::sync::WaitQueue;
 
@@ -16,6 +16,8 @@ pub struct IfaceExt {
     next_poll_at_ms: AtomicU64,
     /// The wait queue that the background polling thread will sleep on.
     polling_wait_queue: WaitQueue,
+    /// Whether the iface has been removed, after which the background polling thread exits.
+    is_removed: AtomicBool,
 }
 
 impl IfaceExt {
@@ -24,6 +26,7 @@ impl IfaceExt {
             name,
             next_poll_at_ms: AtomicU64::new(0),
             polling_wait_queue: WaitQueue::new(),
+            is_removed: AtomicBool::new(false),
         }
     }
 
@@ -40,6 +43,16 @@ impl IfaceExt {
         &self.polling_wait_queue
     }
 
+    pub(super) fn is_removed(&self) -> bool {
+        self.is_removed.load(Ordering::Relaxed)
+    }
+
+    /// Marks the iface as removed and wakes up the background polling thread to exit.
+    pub(super) fn remove(&self) {
+        self.is_removed.store(true, Ordering::Relaxed);
+        self.polling_wait_queue.wake_all();
+    }
+
     fn schedule_next_poll(&self, poll_at: Option<u64>) {
         let Some(new_instant) = poll_at else {
             self.next_poll_at_ms.store(0, Ordering::Relaxed);
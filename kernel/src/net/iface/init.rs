@@ -2,53 +2,98 @@
 
 use alloc::{borrow::ToOwned, sync::Arc};
 
-use aster_bigtcp::device::WithDevice;
+use aster_bigtcp::{
+    device::WithDevice,
+    wire::{IpAddress, IpCidr, Ipv4Address, Ipv4Cidr, Ipv6Address, Ipv6Cidr},
+};
+use aster_network::AnyNetworkDevice;
 use ostd::sync::LocalIrqDisabled;
-use spin::Once;
 
-use super::{poll_ifaces, Iface};
+use super::{
+    dhcp, poll_ifaces,
+    route::{add_route, Route},
+    Iface, IfaceSet,
+};
 use crate::{
     net::iface::ext::{IfaceEx, IfaceExt},
     prelude::*,
 };
 
-pub static IFACES: Once<Vec<Arc<Iface>>> = Once::new();
-
 pub fn init() {
-    IFACES.call_once(|| {
-        let iface_virtio = new_virtio();
-        let iface_loopback = new_loopback();
-        vec![iface_virtio, iface_loopback]
-    });
-
-    for (name, _) in aster_network::all_devices() {
-        let callback = || {
+    let devices = aster_network::all_devices();
+
+    let mut ifaces = devices
+        .iter()
+        .enumerate()
+        .map(|(unit, (_, device))| new_ether(unit, device.clone()))
+        .collect::<Vec<_>>();
+    ifaces.push(new_loopback());
+    IfaceSet::init(ifaces);
+
+    // Each network device has its own iface, which is at the same position in the iface set of
+    // the initial network namespace.
+    for (pos, (name, _)) in devices.iter().enumerate() {
+        let callback = move || {
             // TODO: further check that the irq num is the same as iface's irq num
-            let iface_virtio = &IFACES.get().unwrap()[0];
-            iface_virtio.poll();
+            let iface = &IfaceSet::init_set().ifaces()[pos];
+            iface.poll();
         };
-        aster_network::register_recv_callback(&name, callback);
-        aster_network::register_send_callback(&name, callback);
+        aster_network::register_recv_callback(name, callback);
+        aster_network::register_send_callback(name, callback);
     }
 
+    init_default_routes();
+
     poll_ifaces();
 }
 
-fn new_virtio() -> Arc<Iface> {
-    use aster_bigtcp::{
-        iface::EtherIface,
-        wire::{EthernetAddress, Ipv4Address, Ipv4Cidr},
-    };
-    use aster_network::AnyNetworkDevice;
-    use aster_virtio::device::network::DEVICE_NAME;
+/// Returns an iterator over all ifaces in the network namespace of the current process and
+/// their indexes.
+///
+/// The index of an iface is its position in the [`IfaceSet`] plus one, since zero is not a
+/// valid iface index.
+pub fn iter_ifaces() -> impl Iterator<Item = (u32, Arc<Iface>)> {
+    IfaceSet::current()
+        .ifaces()
+        .to_vec()
+        .into_iter()
+        .enumerate()
+        .map(|(pos, iface)| (pos as u32 + 1, iface))
+}
 
-    const VIRTIO_ADDRESS: Ipv4Address = Ipv4Address::new(10, 0, 2, 15);
-    const VIRTIO_ADDRESS_PREFIX_LEN: u8 = 24; // mask: 255.255.255.0
-    const VIRTIO_GATEWAY: Ipv4Address = Ipv4Address::new(10, 0, 2, 2);
+/// Finds the iface with the given index in the network namespace of the current process.
+pub fn find_iface_by_index(index: u32) -> Option<Arc<Iface>> {
+    IfaceSet::current().iface(index).cloned()
+}
 
-    let virtio_net = aster_network::get_device(DEVICE_NAME).unwrap();
+/// Finds the iface with the given name in the network namespace of the current process, and
+/// returns its index along with the iface.
+pub fn find_iface_by_name(name: &str) -> Option<(u32, Arc<Iface>)> {
+    iter_ifaces().find(|(_, iface)| iface.name() == name)
+}
 
-    let ether_addr = virtio_net.lock().mac_addr().0;
+// These are the default settings of the QEMU user networking (i.e., SLIRP), which are used by the
+// first Ethernet iface.
+const SLIRP_ADDRESS: Ipv4Address = Ipv4Address::new(10, 0, 2, 15);
+const SLIRP_ADDRESS_PREFIX_LEN: u8 = 24; // mask: 255.255.255.0
+const SLIRP_GATEWAY: Ipv4Address = Ipv4Address::new(10, 0, 2, 2);
+const SLIRP_IPV6_ADDRESS: Ipv6Address = Ipv6Address::new(0xfec0, 0, 0, 0, 0, 0, 0, 0x15);
+const SLIRP_IPV6_ADDRESS_PREFIX_LEN: u8 = 64;
+const SLIRP_IPV6_GATEWAY: Ipv6Address = Ipv6Address::new(0xfec0, 0, 0, 0, 0, 0, 0, 0x2);
+
+/// Creates an Ethernet iface for the network device.
+///
+/// The iface is named after the unit number, as in Linux (e.g., `eth0`). Only the first Ethernet
+/// iface is configured with the SLIRP settings, except that its IPv4 settings are left to the
+/// DHCP client if DHCP is enabled. Other ifaces should be configured in user space (e.g., via
+/// netlink).
+fn new_ether(
+    unit: usize,
+    device: Arc<SpinLock<dyn AnyNetworkDevice, LocalIrqDisabled>>,
+) -> Arc<Iface> {
+    use aster_bigtcp::{iface::EtherIface, wire::EthernetAddress};
+
+    let ether_addr = device.lock().mac_addr().0;
 
     struct Wrapper(Arc<SpinLock<dyn AnyNetworkDevice, LocalIrqDisabled>>);
 
@@ -64,24 +109,38 @@ fn new_virtio() -> Arc<Iface> {
         }
     }
 
-    EtherIface::new(
-        Wrapper(virtio_net),
+    let iface = EtherIface::new(
+        Wrapper(device),
         EthernetAddress(ether_addr),
-        Ipv4Cidr::new(VIRTIO_ADDRESS, VIRTIO_ADDRESS_PREFIX_LEN),
-        VIRTIO_GATEWAY,
-        IfaceExt::new("virtio".to_owned()),
-    )
+        IfaceExt::new(format!("eth{}", unit)),
+    ) as Arc<Iface>;
+
+    if unit == 0 {
+        if !dhcp::is_enabled() {
+            iface.set_ip_cidr(IpCidr::Ipv4(Ipv4Cidr::new(
+                SLIRP_ADDRESS,
+                SLIRP_ADDRESS_PREFIX_LEN,
+            )));
+        }
+        iface.set_ip_cidr(IpCidr::Ipv6(Ipv6Cidr::new(
+            SLIRP_IPV6_ADDRESS,
+            SLIRP_IPV6_ADDRESS_PREFIX_LEN,
+        )));
+    }
+
+    iface
 }
 
-fn new_loopback() -> Arc<Iface> {
+pub(super) fn new_loopback() -> Arc<Iface> {
     use aster_bigtcp::{
         device::{Loopback, Medium},
         iface::IpIface,
-        wire::{Ipv4Address, Ipv4Cidr},
     };
 
     const LOOPBACK_ADDRESS: Ipv4Address = Ipv4Address::new(127, 0, 0, 1);
     const LOOPBACK_ADDRESS_PREFIX_LEN: u8 = 8; // mask: 255.0.0.0
+    const LOOPBACK_IPV6_ADDRESS: Ipv6Address = Ipv6Address::LOOPBACK;
+    const LOOPBACK_IPV6_ADDRESS_PREFIX_LEN: u8 = 128;
 
     struct Wrapper(Mutex<Loopback>);
 
@@ -100,6 +159,30 @@ fn new_loopback() -> Arc<Iface> {
     IpIface::new(
         Wrapper(Mutex::new(Loopback::new(Medium::Ip))),
         Ipv4Cidr::new(LOOPBACK_ADDRESS, LOOPBACK_ADDRESS_PREFIX_LEN),
+        Ipv6Cidr::new(LOOPBACK_IPV6_ADDRESS, LOOPBACK_IPV6_ADDRESS_PREFIX_LEN),
         IfaceExt::new("lo".to_owned()),
     ) as _
 }
+
+/// Adds the default routes via the SLIRP gateways.
+///
+/// The routes to the subnets of the ifaces are added when the [`IfaceSet`] is created.
+fn init_default_routes() {
+    if let Some((index, _)) = find_iface_by_name("eth0") {
+        let mut default_routes = Vec::new();
+        // If DHCP is enabled, the IPv4 default route will be added by the DHCP client.
+        if !dhcp::is_enabled() {
+            default_routes.push((
+                IpCidr::Ipv4(Ipv4Cidr::new(Ipv4Address::UNSPECIFIED, 0)),
+                IpAddress::Ipv4(SLIRP_GATEWAY),
+            ));
+        }
+        default_routes.push((
+            IpCidr::Ipv6(Ipv6Cidr::new(Ipv6Address::UNSPECIFIED, 0)),
+            IpAddress::Ipv6(SLIRP_IPV6_GATEWAY),
+        ));
+        for (cidr, gateway) in default_routes {
+            add_route(Route::new(cidr, Some(gateway), index), false).unwrap();
+        }
+    }
+}
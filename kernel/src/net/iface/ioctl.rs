@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The iface-related ioctls on sockets.
+//!
+//! These are the classic `SIOC*` ioctls that tools like `ifconfig` use to query and configure
+//! ifaces. Only IPv4 addresses are supported by these ioctls, as in Linux.
+
+use aster_bigtcp::wire::{HardwareAddress, IpCidr, Ipv4Address, Ipv4Cidr};
+
+use super::{
+    find_iface_by_index, find_iface_by_name, iter_ifaces, set_iface_cidr, IfaceEx, IfaceFlags,
+    LinkType,
+};
+use crate::{
+    fs::utils::IoctlCmd, prelude::*, process::credentials::capabilities::CapSet,
+    util::net::CSocketAddrFamily,
+};
+
+/// The maximum length of an iface name, including the trailing NUL.
+const IFNAMSIZ: usize = 16;
+
+/// The request of the iface-related ioctls.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if.h#L234>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CIfReq {
+    ifr_name: [u8; IFNAMSIZ],
+    /// The request-specific data, which is a union in C.
+    ifr_data: [u8; 24],
+}
+
+/// The generic socket address (i.e., `struct sockaddr`) in [`CIfReq`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CSockAddr {
+    sa_family: u16,
+    sa_data: [u8; 14],
+}
+
+/// The request of `SIOCGIFCONF`.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if.h#L285>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CIfConf {
+    ifc_len: i32,
+    _pad: u32,
+    /// The user-space buffer of [`CIfReq`]s.
+    ifc_buf: u64,
+}
+
+impl CIfReq {
+    fn name(&self) -> Result<&str> {
+        // Linux always treats the last byte as the trailing NUL.
+        let name = &self.ifr_name[..IFNAMSIZ - 1];
+        let len = name.iter().position(|&ch| ch == 0).unwrap_or(name.len());
+        core::str::from_utf8(&name[..len])
+            .map_err(|_| Error::with_message(Errno::ENODEV, "the iface name is not valid"))
+    }
+
+    fn set_name(&mut self, name: &str) {
+        let len = name.len().min(IFNAMSIZ - 1);
+        self.ifr_name = [0; IFNAMSIZ];
+        self.ifr_name[..len].copy_from_slice(&name.as_bytes()[..len]);
+    }
+
+    fn sock_addr(&self) -> CSockAddr {
+        CSockAddr::from_bytes(&self.ifr_data)
+    }
+
+    fn set_sock_addr(&mut self, sock_addr: &CSockAddr) {
+        self.ifr_data = [0; 24];
+        self.ifr_data[..size_of::<CSockAddr>()].copy_from_slice(sock_addr.as_bytes());
+    }
+
+    fn ipv4_addr(&self) -> Result<Ipv4Address> {
+        let sock_addr = self.sock_addr();
+        if sock_addr.sa_family != CSocketAddrFamily::AF_INET as u16 {
+            return_errno_with_message!(Errno::EINVAL, "the address is not an IPv4 address");
+        }
+        // The layout of `sa_data` is the same as `struct sockaddr_in` without `sin_family`:
+        // a 2-byte port number followed by a 4-byte IPv4 address.
+        Ok(Ipv4Address::from_bytes(&sock_addr.sa_data[2..6]))
+    }
+
+    fn set_ipv4_addr(&mut self, addr: Ipv4Address) {
+        let mut sock_addr = CSockAddr {
+            sa_family: CSocketAddrFamily::AF_INET as u16,
+            sa_data: [0; 14],
+        };
+        sock_addr.sa_data[2..6].copy_from_slice(addr.as_bytes());
+        self.set_sock_addr(&sock_addr);
+    }
+
+    fn int_data(&self) -> i32 {
+        i32::from_ne_bytes(self.ifr_data[..4].try_into().unwrap())
+    }
+
+    fn set_int_data(&mut self, value: i32) {
+        self.ifr_data = [0; 24];
+        self.ifr_data[..4].copy_from_slice(&value.to_ne_bytes());
+    }
+
+    fn short_data(&self) -> i16 {
+        i16::from_ne_bytes(self.ifr_data[..2].try_into().unwrap())
+    }
+
+    fn set_short_data(&mut self, value: i16) {
+        self.ifr_data = [0; 24];
+        self.ifr_data[..2].copy_from_slice(&value.to_ne_bytes());
+    }
+}
+
+/// Handles the iface-related ioctls.
+///
+/// In Linux, these ioctls are available for all kinds of sockets, so the caller should check that
+/// the file is a socket before calling this method.
+pub fn handle_iface_ioctl(cmd: IoctlCmd, arg: Vaddr, ctx: &Context) -> Result<i32> {
+    let user_space = ctx.get_user_space();
+
+    if let IoctlCmd::SIOCGIFCONF = cmd {
+        let mut ifconf = user_space.read_val::<CIfConf>(arg)?;
+        ifconf.ifc_len = get_iface_conf(&ifconf, ctx)? as i32;
+        user_space.write_val(arg, &ifconf)?;
+        return Ok(0);
+    }
+
+    let mut ifreq = user_space.read_val::<CIfReq>(arg)?;
+
+    if let IoctlCmd::SIOCGIFNAME = cmd {
+        let iface = u32::try_from(ifreq.int_data())
+            .ok()
+            .and_then(find_iface_by_index)
+            .ok_or_else(|| Error::with_message(Errno::ENODEV, "the iface does not exist"))?;
+        ifreq.set_name(iface.name());
+        user_space.write_val(arg, &ifreq)?;
+        return Ok(0);
+    }
+
+    let (index, iface) = find_iface_by_name(ifreq.name()?)
+        .ok_or_else(|| Error::with_message(Errno::ENODEV, "the iface does not exist"))?;
+
+    match cmd {
+        IoctlCmd::SIOCGIFINDEX => ifreq.set_int_data(index as i32),
+        // The flags are truncated to 16 bits, as in Linux.
+        IoctlCmd::SIOCGIFFLAGS => ifreq.set_short_data(IfaceFlags::of(&iface).bits() as i16),
+        IoctlCmd::SIOCGIFMTU => ifreq.set_int_data(iface.mtu() as i32),
+        IoctlCmd::SIOCGIFHWADDR => {
+            let mut sock_addr = CSockAddr {
+                sa_family: LinkType::of(&iface) as u16,
+                sa_data: [0; 14],
+            };
+            if let HardwareAddress::Ethernet(ether_addr) = iface.hardware_addr() {
+                sock_addr.sa_data[..6].copy_from_slice(ether_addr.as_bytes());
+            }
+            ifreq.set_sock_addr(&sock_addr);
+        }
+        IoctlCmd::SIOCGIFADDR => {
+            let cidr = iface.ipv4_cidr().ok_or_else(|| {
+                Error::with_message(Errno::EADDRNOTAVAIL, "the iface has no IPv4 address")
+            })?;
+            ifreq.set_ipv4_addr(cidr.address());
+        }
+        IoctlCmd::SIOCGIFNETMASK => {
+            let cidr = iface.ipv4_cidr().ok_or_else(|| {
+                Error::with_message(Errno::EADDRNOTAVAIL, "the iface has no IPv4 address")
+            })?;
+            ifreq.set_ipv4_addr(cidr.netmask());
+        }
+        IoctlCmd::SIOCSIFFLAGS => {
+            check_net_admin(ctx)?;
+            let flags = IfaceFlags::from_bits_truncate(ifreq.short_data() as u16 as u32);
+            if !flags.contains(IfaceFlags::UP) {
+                return_errno_with_message!(
+                    Errno::EOPNOTSUPP,
+                    "bringing an iface down is not supported"
+                );
+            }
+            // TODO: Support changing other flags (e.g., `IFF_PROMISC`).
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFADDR => {
+            check_net_admin(ctx)?;
+            let addr = ifreq.ipv4_addr()?;
+            // Linux resets the subnet mask according to the address class.
+            let prefix_len = classful_prefix_len(addr)?;
+            set_iface_cidr(index, IpCidr::Ipv4(Ipv4Cidr::new(addr, prefix_len)))?;
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFNETMASK => {
+            check_net_admin(ctx)?;
+            let netmask = ifreq.ipv4_addr()?;
+            let cidr = iface.ipv4_cidr().ok_or_else(|| {
+                Error::with_message(Errno::EADDRNOTAVAIL, "the iface has no IPv4 address")
+            })?;
+            let new_cidr = Ipv4Cidr::from_netmask(cidr.address(), netmask)
+                .map_err(|_| Error::with_message(Errno::EINVAL, "the netmask is not valid"))?;
+            set_iface_cidr(index, IpCidr::Ipv4(new_cidr))?;
+            return Ok(0);
+        }
+        _ => unreachable!("{:?} is not an iface-related ioctl", cmd),
+    }
+
+    user_space.write_val(arg, &ifreq)?;
+    Ok(0)
+}
+
+/// Writes the IPv4 addresses of all ifaces to the buffer specified by `ifconf`.
+///
+/// If the buffer is null, nothing is written. This method returns the length in bytes of the
+/// entries written, or the length that is required to hold all entries if the buffer is null.
+fn get_iface_conf(ifconf: &CIfConf, ctx: &Context) -> Result<usize> {
+    let ifreqs = iter_ifaces()
+        .filter_map(|(_, iface)| {
+            let cidr = iface.ipv4_cidr()?;
+            let mut ifreq = CIfReq::new_zeroed();
+            ifreq.set_name(iface.name());
+            ifreq.set_ipv4_addr(cidr.address());
+            Some(ifreq)
+        })
+        .collect::<Vec<_>>();
+
+    if ifconf.ifc_buf == 0 {
+        return Ok(ifreqs.len() * size_of::<CIfReq>());
+    }
+
+    let max_entries = ifconf.ifc_len.max(0) as usize / size_of::<CIfReq>();
+    let user_space = ctx.get_user_space();
+    let mut written_len = 0;
+    for ifreq in ifreqs.iter().take(max_entries) {
+        user_space.write_val(ifconf.ifc_buf as Vaddr + written_len, ifreq)?;
+        written_len += size_of::<CIfReq>();
+    }
+
+    Ok(written_len)
+}
+
+/// Returns the prefix length of the classful network that the IPv4 address belongs to.
+///
+/// See <https://en.wikipedia.org/wiki/Classful_network>.
+fn classful_prefix_len(addr: Ipv4Address) -> Result<u8> {
+    if addr.is_unspecified() {
+        return Ok(0);
+    }
+
+    match addr.as_bytes()[0] {
+        // Class A
+        0..=127 => Ok(8),
+        // Class B
+        128..=191 => Ok(16),
+        // Class C
+        192..=223 => Ok(24),
+        // Class D (multicast) or class E (reserved)
+        _ => return_errno_with_message!(Errno::EINVAL, "the address is not a unicast address"),
+    }
+}
+
+fn check_net_admin(ctx: &Context) -> Result<()> {
+    if !ctx
+        .posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::NET_ADMIN)
+    {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "CAP_NET_ADMIN is required to configure ifaces"
+        );
+    }
+    Ok(())
+}
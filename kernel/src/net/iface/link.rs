@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Link-layer properties of ifaces.
+
+use aster_bigtcp::wire::HardwareAddress;
+
+use super::Iface;
+use crate::prelude::*;
+
+bitflags! {
+    /// Iface flags.
+    ///
+    /// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if.h#L82>.
+    pub struct IfaceFlags: u32 {
+        /// Interface is up.
+        const UP = 1 << 0;
+        /// Broadcast address valid.
+        const BROADCAST = 1 << 1;
+        /// Turn on debugging.
+        const DEBUG = 1 << 2;
+        /// Is a loopback net.
+        const LOOPBACK = 1 << 3;
+        /// Interface has a point-to-point link.
+        const POINTOPOINT = 1 << 4;
+        /// Avoid use of trailers.
+        const NOTRAILERS = 1 << 5;
+        /// Interface RFC2863 OPER_UP.
+        const RUNNING = 1 << 6;
+        /// No ARP protocol.
+        const NOARP = 1 << 7;
+        /// Receive all packets.
+        const PROMISC = 1 << 8;
+        /// Receive all multicast packets.
+        const ALLMULTI = 1 << 9;
+        /// Master of a load balancer.
+        const MASTER = 1 << 10;
+        /// Slave of a load balancer.
+        const SLAVE = 1 << 11;
+        /// Supports multicast.
+        const MULTICAST = 1 << 12;
+        /// Can set media type.
+        const PORTSEL = 1 << 13;
+        /// Auto media select active.
+        const AUTOMEDIA = 1 << 14;
+        /// Dialup device with changing addresses.
+        const DYNAMIC = 1 << 15;
+        /// Driver signals L1 up.
+        const LOWER_UP = 1 << 16;
+        /// Driver signals dormant.
+        const DORMANT = 1 << 17;
+        /// Echo sent packets.
+        const ECHO = 1 << 18;
+    }
+}
+
+/// Link types (i.e., ARP protocol hardware identifiers).
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if_arp.h#L30>.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum LinkType {
+    /// Ethernet 10Mbps.
+    ETHER = 1,
+    /// Loopback device.
+    LOOPBACK = 772,
+}
+
+impl IfaceFlags {
+    /// Returns the flags of the iface.
+    ///
+    /// FIXME: All ifaces are always up and running, since we do not support bringing an iface
+    /// down yet.
+    pub fn of(iface: &Iface) -> Self {
+        match iface.hardware_addr() {
+            HardwareAddress::Ethernet(_) => {
+                Self::UP | Self::BROADCAST | Self::RUNNING | Self::MULTICAST | Self::LOWER_UP
+            }
+            HardwareAddress::Ip => Self::UP | Self::LOOPBACK | Self::RUNNING | Self::LOWER_UP,
+        }
+    }
+}
+
+impl LinkType {
+    /// Returns the link type of the iface.
+    ///
+    /// Currently, the only iface without a hardware address is the loopback iface.
+    pub fn of(iface: &Iface) -> Self {
+        match iface.hardware_addr() {
+            HardwareAddress::Ethernet(_) => Self::ETHER,
+            HardwareAddress::Ip => Self::LOOPBACK,
+        }
+    }
+}
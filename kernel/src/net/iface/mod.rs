@@ -1,12 +1,25 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod dhcp;
 mod ext;
 mod init;
+mod ioctl;
+mod link;
 mod poll;
+mod route;
+mod set;
 
-pub use init::{init, IFACES};
+pub use dhcp::{dhcp_lease, DhcpLease};
+pub use ext::IfaceEx;
+pub use init::{find_iface_by_index, find_iface_by_name, init, iter_ifaces};
+pub use ioctl::handle_iface_ioctl;
+pub use link::{IfaceFlags, LinkType};
 pub use poll::{lazy_init, poll_ifaces};
+pub use route::{add_route, all_routes, lookup_route, remove_route, set_iface_cidr, Route};
+pub use set::IfaceSet;
 
 pub type Iface = dyn aster_bigtcp::iface::Iface<ext::IfaceExt>;
 pub type BoundTcpSocket = aster_bigtcp::socket::BoundTcpSocket<ext::IfaceExt>;
 pub type BoundUdpSocket = aster_bigtcp::socket::BoundUdpSocket<ext::IfaceExt>;
+pub type BoundPingSocket = aster_bigtcp::socket::BoundPingSocket<ext::IfaceExt>;
+pub type BoundRawIpSocket = aster_bigtcp::socket::BoundRawIpSocket<ext::IfaceExt>;
@@ -6,7 +6,7 @@ use core::time::Duration;
 use log::trace;
 use ostd::timer::Jiffies;
 
-use super::{ext::IfaceEx, Iface, IFACES};
+use super::{dhcp, ext::IfaceEx, Iface, IfaceSet};
 use crate::{
     sched::priority::{Priority, PriorityRange},
     thread::{
@@ -17,20 +17,24 @@ use crate::{
 };
 
 pub fn lazy_init() {
-    for iface in IFACES.get().unwrap() {
+    for iface in IfaceSet::init_set().ifaces() {
         spawn_background_poll_thread(iface.clone());
     }
+
+    // This may block for a while, so it is done here instead of in `init`.
+    dhcp::configure();
 }
 
+/// Polls the ifaces in the network namespace of the current process.
 pub fn poll_ifaces() {
-    let ifaces = IFACES.get().unwrap();
+    let iface_set = IfaceSet::current();
 
-    for iface in ifaces.iter() {
+    for iface in iface_set.ifaces() {
         iface.poll();
     }
 }
 
-fn spawn_background_poll_thread(iface: Arc<Iface>) {
+pub(super) fn spawn_background_poll_thread(iface: Arc<Iface>) {
     let task_fn = move || {
         trace!("spawn background poll thread for {}", iface.name());
 
@@ -38,10 +42,24 @@ fn spawn_background_poll_thread(iface: Arc<Iface>) {
         let wait_queue = iface_ext.polling_wait_queue();
 
         loop {
+            if iface_ext.is_removed() {
+                break;
+            }
+
             let next_poll_at_ms = if let Some(next_poll_at_ms) = iface_ext.next_poll_at_ms() {
                 next_poll_at_ms
             } else {
-                wait_queue.wait_until(|| iface_ext.next_poll_at_ms())
+                let next_poll_at_ms = wait_queue.wait_until(|| {
+                    if iface_ext.is_removed() {
+                        Some(None)
+                    } else {
+                        iface_ext.next_poll_at_ms().map(Some)
+                    }
+                });
+                let Some(next_poll_at_ms) = next_poll_at_ms else {
+                    break;
+                };
+                next_poll_at_ms
             };
 
             let now_as_ms = Jiffies::elapsed().as_duration().as_millis() as u64;
@@ -61,9 +79,15 @@ fn spawn_background_poll_thread(iface: Arc<Iface>) {
 
             let duration = Duration::from_millis(next_poll_at_ms - now_as_ms);
             let _ = wait_queue.wait_until_or_timeout(
-                // If `iface_ext.next_poll_at_ms()` changes to an earlier time, we will end the
-                // waiting.
-                || (iface_ext.next_poll_at_ms()? < next_poll_at_ms).then_some(()),
+                // If `iface_ext.next_poll_at_ms()` changes to an earlier time, or the iface is
+                // removed, we will end the waiting.
+                || {
+                    (iface_ext.is_removed()
+                        || iface_ext
+                            .next_poll_at_ms()
+                            .is_some_and(|poll_at_ms| poll_at_ms < next_poll_at_ms))
+                    .then_some(())
+                },
                 &duration,
             );
         }
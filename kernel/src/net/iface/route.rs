@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The routing table.
+//!
+//! Each network namespace has its own routing table, which is kept in its [`IfaceSet`]. The
+//! functions in this module operate on the routing table of the current process.
+//!
+//! The routing table determines the iface through which the packets to a destination should be
+//! sent. The route whose destination subnet has the longest prefix wins. Ties are broken in favor
+//! of the route that was added first.
+//!
+//! If a route has a gateway, the route is also added to the output iface, so that the iface knows
+//! the next hop of the packets.
+
+use aster_bigtcp::wire::{IpAddress, IpCidr, Ipv6Address, Ipv6Cidr};
+
+use super::{Iface, IfaceSet};
+use crate::prelude::*;
+
+/// A route in the routing table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+    cidr: IpCidr,
+    gateway: Option<IpAddress>,
+    iface_index: u32,
+}
+
+impl Route {
+    /// Creates a route to the destinations in `cidr`.
+    ///
+    /// If `gateway` is `None`, the destinations are directly reachable from the iface.
+    pub fn new(cidr: IpCidr, gateway: Option<IpAddress>, iface_index: u32) -> Self {
+        Self {
+            cidr,
+            gateway,
+            iface_index,
+        }
+    }
+
+    /// Returns the destination subnet of the route.
+    pub fn cidr(&self) -> IpCidr {
+        self.cidr
+    }
+
+    /// Returns the gateway of the route, if any.
+    pub fn gateway(&self) -> Option<IpAddress> {
+        self.gateway
+    }
+
+    /// Returns the index of the output iface.
+    pub fn iface_index(&self) -> u32 {
+        self.iface_index
+    }
+
+    /// Returns the output iface in the network namespace of the current process.
+    pub fn iface(&self) -> Arc<Iface> {
+        self.iface_in(&IfaceSet::current()).clone()
+    }
+
+    fn iface_in<'a>(&self, iface_set: &'a IfaceSet) -> &'a Arc<Iface> {
+        // Ifaces are never removed from an iface set, so the iface must exist.
+        iface_set.iface(self.iface_index).unwrap()
+    }
+}
+
+/// Adds a route to the routing table.
+///
+/// If there is already a route to the same destinations, the old route will be replaced if
+/// `replace` is true. Otherwise, this method fails with [`Errno::EEXIST`].
+pub fn add_route(route: Route, replace: bool) -> Result<()> {
+    if route.gateway.is_some_and(|gateway| {
+        gateway.version() != route.cidr.address().version() || gateway.is_unspecified()
+    }) {
+        return_errno_with_message!(Errno::EINVAL, "the gateway is not valid");
+    }
+    let iface_set = IfaceSet::current();
+    let Some(iface) = iface_set.iface(route.iface_index) else {
+        return_errno_with_message!(Errno::ENODEV, "the iface does not exist");
+    };
+
+    let mut routes = iface_set.routes().write();
+
+    let old_pos = routes
+        .iter()
+        .position(|old_route| old_route.cidr == route.cidr);
+    if old_pos.is_some() && !replace {
+        return_errno_with_message!(Errno::EEXIST, "the route already exists");
+    }
+
+    if let Some(gateway) = route.gateway {
+        if iface.add_route(route.cidr, gateway).is_err() {
+            return_errno_with_message!(Errno::ENOBUFS, "too many routes via the iface");
+        }
+    }
+
+    if let Some(old_pos) = old_pos {
+        let old_route = core::mem::replace(&mut routes[old_pos], route);
+        if old_route.gateway.is_some()
+            && (route.gateway.is_none() || old_route.iface_index != route.iface_index)
+        {
+            old_route.iface_in(&iface_set).remove_route(&old_route.cidr);
+        }
+    } else {
+        routes.push(route);
+    }
+
+    Ok(())
+}
+
+/// Removes the route to the destinations in `cidr` from the routing table.
+///
+/// If `iface_index` is not `None`, only the route via the specified iface will be removed. This
+/// method returns the removed route, or fails with [`Errno::ESRCH`] if no route matches.
+pub fn remove_route(cidr: &IpCidr, iface_index: Option<u32>) -> Result<Route> {
+    let iface_set = IfaceSet::current();
+    let mut routes = iface_set.routes().write();
+
+    let Some(pos) = routes.iter().position(|route| {
+        route.cidr == *cidr && iface_index.map_or(true, |index| index == route.iface_index)
+    }) else {
+        return_errno_with_message!(Errno::ESRCH, "the route does not exist");
+    };
+
+    let route = routes.remove(pos);
+    if route.gateway.is_some() {
+        route.iface_in(&iface_set).remove_route(&route.cidr);
+    }
+
+    Ok(route)
+}
+
+/// Sets the IP address of the iface and updates the route to its subnet accordingly.
+pub fn set_iface_cidr(iface_index: u32, cidr: IpCidr) -> Result<()> {
+    let iface_set = IfaceSet::current();
+    let Some(iface) = iface_set.iface(iface_index) else {
+        return_errno_with_message!(Errno::ENODEV, "the iface does not exist");
+    };
+
+    let old_cidr = match cidr {
+        IpCidr::Ipv4(_) => iface.ipv4_cidr().map(IpCidr::Ipv4),
+        IpCidr::Ipv6(_) => iface.ipv6_cidr().map(IpCidr::Ipv6),
+    };
+    iface.set_ip_cidr(cidr);
+    update_subnet_route(&mut iface_set.routes().write(), iface_index, old_cidr, cidr);
+
+    Ok(())
+}
+
+/// Updates the route to the subnet of an iface after the address of the iface is changed.
+///
+/// The route to `old_cidr`, if any, will be replaced by the route to `new_cidr`.
+pub(super) fn update_subnet_route(
+    routes: &mut Vec<Route>,
+    iface_index: u32,
+    old_cidr: Option<IpCidr>,
+    new_cidr: IpCidr,
+) {
+    let new_route = Route::new(subnet_of(&new_cidr), None, iface_index);
+
+    let old_pos = old_cidr.and_then(|old_cidr| {
+        let old_route = Route::new(subnet_of(&old_cidr), None, iface_index);
+        routes.iter().position(|route| *route == old_route)
+    });
+    if let Some(old_pos) = old_pos {
+        routes.remove(old_pos);
+    }
+
+    // The subnet route is inserted at the front, so it takes precedence over the routes that
+    // have the same prefix length and are via other ifaces.
+    if !routes.contains(&new_route) {
+        routes.insert(0, new_route);
+    }
+}
+
+/// Looks up the route to the destination address.
+pub fn lookup_route(dst_addr: &IpAddress) -> Option<Route> {
+    IfaceSet::current()
+        .routes()
+        .read()
+        .iter()
+        .filter(|route| route.cidr.contains_addr(dst_addr))
+        // `max_by_key` returns the last maximum element, so the iterator is reversed to prefer
+        // the route that was added first.
+        .rev()
+        .max_by_key(|route| route.cidr.prefix_len())
+        .copied()
+}
+
+/// Returns all the routes in the routing table.
+pub fn all_routes() -> Vec<Route> {
+    IfaceSet::current().routes().read().clone()
+}
+
+/// Returns the subnet that the CIDR belongs to, i.e., the CIDR with the host bits cleared.
+fn subnet_of(cidr: &IpCidr) -> IpCidr {
+    match cidr {
+        IpCidr::Ipv4(cidr) => IpCidr::Ipv4(cidr.network()),
+        IpCidr::Ipv6(cidr) => {
+            let prefix_len = cidr.prefix_len() as usize;
+            let mut bytes = [0u8; 16];
+            for (i, byte) in cidr.address().as_bytes().iter().enumerate() {
+                let prefix_bits = prefix_len.saturating_sub(i * 8).min(8) as u32;
+                let mask = 0xffu8.checked_shl(8 - prefix_bits).unwrap_or(0);
+                bytes[i] = byte & mask;
+            }
+            IpCidr::Ipv6(Ipv6Cidr::new(
+                Ipv6Address::from_bytes(&bytes),
+                cidr.prefix_len(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The sets of ifaces.
+//!
+//! Each network namespace has its own set of ifaces, along with its own routing table. The
+//! iface set of the initial namespace contains the ifaces of the network devices, which are
+//! created at boot time. The iface set of a new namespace only contains a loopback iface.
+//!
+//! The index of an iface is its position in the set plus one, since zero is not a valid iface
+//! index. So the indexes are only meaningful in the set that the iface belongs to.
+
+use aster_bigtcp::wire::IpCidr;
+use spin::Once;
+
+use super::{
+    init::new_loopback, poll::spawn_background_poll_thread, route::update_subnet_route, Iface,
+    Route,
+};
+use crate::{prelude::*, process::Process};
+
+/// A set of ifaces and the routing table among them.
+pub struct IfaceSet {
+    ifaces: Vec<Arc<Iface>>,
+    routes: RwLock<Vec<Route>>,
+}
+
+static INIT_IFACE_SET: Once<Arc<IfaceSet>> = Once::new();
+
+impl IfaceSet {
+    fn new(ifaces: Vec<Arc<Iface>>) -> Arc<Self> {
+        let mut routes = Vec::new();
+        for (pos, iface) in ifaces.iter().enumerate() {
+            let ipv4_cidr = iface.ipv4_cidr().map(IpCidr::Ipv4);
+            let ipv6_cidr = iface.ipv6_cidr().map(IpCidr::Ipv6);
+            for cidr in ipv4_cidr.into_iter().chain(ipv6_cidr) {
+                update_subnet_route(&mut routes, pos as u32 + 1, None, cidr);
+            }
+        }
+
+        Arc::new(Self {
+            ifaces,
+            routes: RwLock::new(routes),
+        })
+    }
+
+    /// Initializes the iface set of the initial network namespace.
+    pub(super) fn init(ifaces: Vec<Arc<Iface>>) -> &'static Arc<Self> {
+        INIT_IFACE_SET.call_once(|| Self::new(ifaces))
+    }
+
+    /// Returns the iface set of the initial network namespace.
+    pub fn init_set() -> &'static Arc<Self> {
+        INIT_IFACE_SET.get().unwrap()
+    }
+
+    /// Creates the iface set of a new network namespace, which only contains a loopback iface.
+    pub fn new_isolated() -> Arc<Self> {
+        let loopback = new_loopback();
+        spawn_background_poll_thread(loopback.clone());
+        Self::new(vec![loopback])
+    }
+
+    /// Returns the iface set of the network namespace of the current process.
+    ///
+    /// The kernel threads, which are not associated with a process, use the iface set of the
+    /// initial namespace.
+    pub fn current() -> Arc<Self> {
+        match Process::current() {
+            Some(process) => process.nsproxy().net_ns().iface_set().clone(),
+            None => Self::init_set().clone(),
+        }
+    }
+
+    /// Returns the ifaces in the set.
+    pub fn ifaces(&self) -> &[Arc<Iface>] {
+        &self.ifaces
+    }
+
+    /// Finds the iface with the given index.
+    pub fn iface(&self, index: u32) -> Option<&Arc<Iface>> {
+        let pos = index.checked_sub(1)?;
+        self.ifaces.get(pos as usize)
+    }
+
+    pub(super) fn routes(&self) -> &RwLock<Vec<Route>> {
+        &self.routes
+    }
+}
+
+impl Drop for IfaceSet {
+    fn drop(&mut self) {
+        // Stop the background polling threads. The ifaces may still be used by the sockets
+        // that are bound to them, but they will only be polled when the sockets are used.
+        for iface in self.ifaces.iter() {
+            iface.ext().remove();
+        }
+    }
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use aster_bigtcp::wire::{IpAddress, IpEndpoint, Ipv4Address};
+use aster_bigtcp::wire::{IpAddress, IpEndpoint, Ipv4Address, Ipv6Address};
 
 use crate::{net::socket::SocketAddr, prelude::*, return_errno_with_message};
 
@@ -10,6 +10,7 @@ impl TryFrom<SocketAddr> for IpEndpoint {
     fn try_from(value: SocketAddr) -> Result<Self> {
         match value {
             SocketAddr::IPv4(addr, port) => Ok(IpEndpoint::new(addr.into_address(), port)),
+            SocketAddr::IPv6(addr, port) => Ok(IpEndpoint::new(addr.into_address(), port)),
             _ => return_errno_with_message!(
                 Errno::EAFNOSUPPORT,
                 "the address is in an unsupported address family"
@@ -23,15 +24,82 @@ impl From<IpEndpoint> for SocketAddr {
         let port = endpoint.port;
         match endpoint.addr {
             IpAddress::Ipv4(addr) => SocketAddr::IPv4(addr, port),
-            // TODO: support IPv6
+            IpAddress::Ipv6(addr) => SocketAddr::IPv6(addr, port),
         }
     }
 }
 
-/// A local endpoint, which indicates that the local endpoint is unspecified.
+/// The address family of an IP socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFamily {
+    /// `AF_INET` sockets.
+    Ipv4,
+    /// `AF_INET6` sockets.
+    Ipv6,
+}
+
+impl IpFamily {
+    /// Converts a socket address specified by the user to an IP endpoint.
+    ///
+    /// An IPv6 socket can talk with IPv4 peers via IPv4-mapped IPv6 addresses (i.e.,
+    /// `::ffff:a.b.c.d`), which are converted to IPv4 endpoints here. This is disallowed if
+    /// `v6_only` is true (i.e., the `IPV6_V6ONLY` option is set).
+    pub(super) fn endpoint_from(
+        self,
+        socket_addr: SocketAddr,
+        v6_only: bool,
+    ) -> Result<IpEndpoint> {
+        match (self, socket_addr) {
+            (Self::Ipv4, SocketAddr::IPv4(addr, port)) => {
+                Ok(IpEndpoint::new(IpAddress::Ipv4(addr), port))
+            }
+            (Self::Ipv6, SocketAddr::IPv6(addr, port)) => match addr.as_ipv4() {
+                Some(_) if v6_only => return_errno_with_message!(
+                    Errno::ENETUNREACH,
+                    "IPv4-mapped addresses are not allowed for IPv6-only sockets"
+                ),
+                Some(ipv4_addr) => Ok(IpEndpoint::new(IpAddress::Ipv4(ipv4_addr), port)),
+                None => Ok(IpEndpoint::new(IpAddress::Ipv6(addr), port)),
+            },
+            _ => return_errno_with_message!(
+                Errno::EAFNOSUPPORT,
+                "the address is in a different address family from the socket"
+            ),
+        }
+    }
+
+    /// Converts an IP endpoint to a socket address reported to the user.
+    ///
+    /// This is the reverse of [`Self::endpoint_from`], so IPv4 endpoints are reported as
+    /// IPv4-mapped IPv6 addresses to IPv6 sockets.
+    pub(super) fn socket_addr_from(self, endpoint: IpEndpoint) -> SocketAddr {
+        match (self, endpoint.addr) {
+            (Self::Ipv6, IpAddress::Ipv4(addr)) => {
+                SocketAddr::IPv6(ipv4_mapped_addr(addr), endpoint.port)
+            }
+            _ => endpoint.into(),
+        }
+    }
+
+    /// Returns a local endpoint, which indicates that the local endpoint is unspecified.
+    ///
+    /// According to the Linux man pages and the Linux implementation, `getsockname()` will _not_
+    /// fail even if the socket is unbound. Instead, it will return an unspecified socket address.
+    /// This unspecified endpoint helps with that.
+    pub(super) const fn unspecified_local_endpoint(self) -> IpEndpoint {
+        match self {
+            Self::Ipv4 => IpEndpoint::new(IpAddress::Ipv4(Ipv4Address::UNSPECIFIED), 0),
+            Self::Ipv6 => IpEndpoint::new(IpAddress::Ipv6(Ipv6Address::UNSPECIFIED), 0),
+        }
+    }
+}
+
+/// Converts an IPv4 address to the IPv4-mapped IPv6 address.
 ///
-/// According to the Linux man pages and the Linux implementation, `getsockname()` will _not_ fail
-/// even if the socket is unbound. Instead, it will return an unspecified socket address. This
-/// unspecified endpoint helps with that.
-pub(super) const UNSPECIFIED_LOCAL_ENDPOINT: IpEndpoint =
-    IpEndpoint::new(IpAddress::Ipv4(Ipv4Address::UNSPECIFIED), 0);
+/// See <https://datatracker.ietf.org/doc/html/rfc4291#section-2.5.5.2>.
+fn ipv4_mapped_addr(addr: Ipv4Address) -> Ipv6Address {
+    let mut bytes = [0; 16];
+    bytes[10..12].copy_from_slice(&[0xff, 0xff]);
+    bytes[12..].copy_from_slice(&addr.0);
+    Ipv6Address(bytes)
+}
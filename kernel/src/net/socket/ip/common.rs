@@ -7,42 +7,40 @@ use aster_bigtcp::{
 };
 
 use crate::{
-    net::iface::{Iface, IFACES},
+    net::iface::{iter_ifaces, lookup_route, Iface},
     prelude::*,
 };
 
 pub(super) fn get_iface_to_bind(ip_addr: &IpAddress) -> Option<Arc<Iface>> {
-    let ifaces = IFACES.get().unwrap();
-    let IpAddress::Ipv4(ipv4_addr) = ip_addr;
-    ifaces
-        .iter()
-        .find(|iface| {
-            if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
-                iface_ipv4_addr == *ipv4_addr
-            } else {
-                false
-            }
-        })
-        .map(Clone::clone)
+    iter_ifaces()
+        .map(|(_, iface)| iface)
+        .find(|iface| iface_has_addr(iface, ip_addr))
 }
 
 /// Get a suitable iface to deal with sendto/connect request if the socket is not bound to an iface.
 /// If the remote address is the same as that of some iface, we will use the iface.
-/// Otherwise, we will use a default interface.
-fn get_ephemeral_iface(remote_ip_addr: &IpAddress) -> Arc<Iface> {
-    let ifaces = IFACES.get().unwrap();
-    let IpAddress::Ipv4(remote_ipv4_addr) = remote_ip_addr;
-    if let Some(iface) = ifaces.iter().find(|iface| {
-        if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
-            iface_ipv4_addr == *remote_ipv4_addr
-        } else {
-            false
-        }
-    }) {
-        return iface.clone();
+/// Otherwise, we will look up the routing table to find the iface.
+fn get_ephemeral_iface(remote_ip_addr: &IpAddress) -> Result<Arc<Iface>> {
+    if let Some(iface) = get_iface_to_bind(remote_ip_addr) {
+        return Ok(iface);
+    }
+
+    let Some(route) = lookup_route(remote_ip_addr) else {
+        return_errno_with_message!(Errno::ENETUNREACH, "no route to the remote address");
+    };
+    Ok(route.iface())
+}
+
+/// Returns whether the IP address is the address of the iface.
+fn iface_has_addr(iface: &Iface, ip_addr: &IpAddress) -> bool {
+    match ip_addr {
+        IpAddress::Ipv4(ipv4_addr) => iface
+            .ipv4_addr()
+            .is_some_and(|iface_ipv4_addr| iface_ipv4_addr == *ipv4_addr),
+        IpAddress::Ipv6(ipv6_addr) => iface
+            .ipv6_addr()
+            .is_some_and(|iface_ipv6_addr| iface_ipv6_addr == *ipv6_addr),
     }
-    // FIXME: use the virtio-net as the default interface
-    ifaces[0].clone()
 }
 
 pub(super) fn bind_socket<S, T>(
@@ -52,6 +50,7 @@ pub(super) fn bind_socket<S, T>(
     bind: impl FnOnce(
         Arc<Iface>,
         Box<S>,
+        IpAddress,
         BindPortConfig,
     ) -> core::result::Result<T, (BindError, Box<S>)>,
 ) -> core::result::Result<T, (Error, Box<S>)> {
@@ -68,7 +67,8 @@ pub(super) fn bind_socket<S, T>(
 
     let bind_port_config = BindPortConfig::new(endpoint.port, can_reuse);
 
-    bind(iface, unbound_socket, bind_port_config).map_err(|(err, unbound)| (err.into(), unbound))
+    bind(iface, unbound_socket, endpoint.addr, bind_port_config)
+        .map_err(|(err, unbound)| (err.into(), unbound))
 }
 
 impl From<BindError> for Error {
@@ -84,8 +84,34 @@ impl From<BindError> for Error {
     }
 }
 
-pub(super) fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> IpEndpoint {
-    let iface = get_ephemeral_iface(&remote_endpoint.addr);
-    let ip_addr = iface.ipv4_addr().unwrap();
-    IpEndpoint::new(IpAddress::Ipv4(ip_addr), 0)
+pub(super) fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> Result<IpEndpoint> {
+    let iface = get_ephemeral_iface(&remote_endpoint.addr)?;
+    let ip_addr = match remote_endpoint.addr {
+        IpAddress::Ipv4(_) => iface.ipv4_addr().map(IpAddress::Ipv4),
+        IpAddress::Ipv6(_) => iface.ipv6_addr().map(IpAddress::Ipv6),
+    };
+    let Some(ip_addr) = ip_addr else {
+        return_errno_with_message!(
+            Errno::EADDRNOTAVAIL,
+            "the iface to the remote address has no address"
+        );
+    };
+    Ok(IpEndpoint::new(ip_addr, 0))
+}
+
+/// Checks whether the local endpoint and the remote endpoint are in the same address family.
+///
+/// They can be in different address families if an IPv6 socket bound to an IPv6 address tries
+/// to talk with an IPv4 peer via an IPv4-mapped IPv6 address, or vice versa.
+pub(super) fn check_address_family(
+    local_endpoint: &IpEndpoint,
+    remote_endpoint: &IpEndpoint,
+) -> Result<()> {
+    if local_endpoint.addr.version() != remote_endpoint.addr.version() {
+        return_errno_with_message!(
+            Errno::EAFNOSUPPORT,
+            "the local and remote addresses are in different address families"
+        );
+    }
+    Ok(())
 }
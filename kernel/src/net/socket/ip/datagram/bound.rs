@@ -7,7 +7,13 @@ use aster_bigtcp::{
 
 use crate::{
     events::IoEvents,
-    net::{iface::BoundUdpSocket, socket::util::send_recv_flags::SendRecvFlags},
+    net::{
+        iface::BoundUdpSocket,
+        socket::{
+            ip::{common::check_address_family, datagram_like::BoundDatagramLike},
+            util::send_recv_flags::SendRecvFlags,
+        },
+    },
     prelude::*,
     process::signal::Pollee,
     util::{MultiRead, MultiWrite},
@@ -15,43 +21,46 @@ use crate::{
 
 pub struct BoundDatagram {
     bound_socket: BoundUdpSocket,
-    remote_endpoint: Option<IpEndpoint>,
 }
 
 impl BoundDatagram {
     pub fn new(bound_socket: BoundUdpSocket) -> Self {
-        Self {
-            bound_socket,
-            remote_endpoint: None,
-        }
-    }
-
-    pub fn local_endpoint(&self) -> IpEndpoint {
-        self.bound_socket.local_endpoint().unwrap()
-    }
-
-    pub fn remote_endpoint(&self) -> Option<IpEndpoint> {
-        self.remote_endpoint
+        Self { bound_socket }
     }
+}
 
-    pub fn set_remote_endpoint(&mut self, endpoint: &IpEndpoint) {
-        self.remote_endpoint = Some(*endpoint)
+impl BoundDatagramLike for BoundDatagram {
+    fn local_endpoint(&self) -> IpEndpoint {
+        self.bound_socket.local_endpoint()
     }
 
-    pub fn try_recv(
+    fn try_recv(
         &self,
         writer: &mut dyn MultiWrite,
-        _flags: SendRecvFlags,
+        flags: SendRecvFlags,
     ) -> Result<(usize, IpEndpoint)> {
-        let result = self.bound_socket.recv(|packet, udp_metadata| {
+        let recv_packet = |packet: &[u8], udp_metadata| {
+            // The part of the packet that does not fit in the buffer is discarded.
             let copied_res = writer.write(&mut VmReader::from(packet));
-            let endpoint = udp_metadata.endpoint;
-            (copied_res, endpoint)
-        });
+            (copied_res, packet.len(), udp_metadata)
+        };
+        let result = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.bound_socket.peek(recv_packet)
+        } else {
+            self.bound_socket.recv(recv_packet)
+        };
 
         match result {
-            Ok((Ok(res), endpoint)) => Ok((res, endpoint)),
-            Ok((Err(e), _)) => Err(e),
+            Ok((Ok(copied_len), packet_len, udp_metadata)) => {
+                // With `MSG_TRUNC`, the real length of the packet is returned even if it is
+                // truncated.
+                if flags.contains(SendRecvFlags::MSG_TRUNC) {
+                    Ok((packet_len, udp_metadata.endpoint))
+                } else {
+                    Ok((copied_len, udp_metadata.endpoint))
+                }
+            }
+            Ok((Err(e), _, _)) => Err(e),
             Err(RecvError::Exhausted) => {
                 return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
             }
@@ -61,12 +70,14 @@ impl BoundDatagram {
         }
     }
 
-    pub fn try_send(
+    fn try_send(
         &self,
         reader: &mut dyn MultiRead,
         remote: &IpEndpoint,
         _flags: SendRecvFlags,
     ) -> Result<usize> {
+        check_address_family(&self.local_endpoint(), remote)?;
+
         let result = self
             .bound_socket
             .send(reader.sum_lens(), *remote, |socket_buffer| {
@@ -94,12 +105,28 @@ impl BoundDatagram {
         }
     }
 
-    pub(super) fn init_pollee(&self, pollee: &Pollee) {
-        pollee.reset_events();
-        self.update_io_events(pollee)
+    /// Takes the error reported by ICMP messages, if any.
+    ///
+    /// Like Linux, unless `IP_RECVERR` is enabled, the error is only reported if the socket is
+    /// connected and the ICMP message is about the remote endpoint that the socket is connected
+    /// to. The extended errors that Linux queues for `MSG_ERRQUEUE` are not supported.
+    fn take_icmp_error(
+        &self,
+        remote_endpoint: Option<&IpEndpoint>,
+        recv_err: bool,
+    ) -> Option<Error> {
+        let unreachable_endpoint = self.bound_socket.take_unreachable()?;
+        if !recv_err && remote_endpoint != Some(&unreachable_endpoint) {
+            return None;
+        }
+
+        Some(Error::with_message(
+            Errno::ECONNREFUSED,
+            "the remote port is unreachable",
+        ))
     }
 
-    pub(super) fn update_io_events(&self, pollee: &Pollee) {
+    fn update_io_events(&self, pollee: &Pollee) {
         self.bound_socket.raw_with(|socket| {
             if socket.can_recv() {
                 pollee.add_events(IoEvents::IN);
@@ -9,7 +9,8 @@ use aster_bigtcp::{
 
 use super::bound::BoundDatagram;
 use crate::{
-    events::IoEvents, net::socket::ip::common::bind_socket, prelude::*, process::signal::Pollee,
+    net::socket::ip::{common::bind_socket, datagram_like::UnboundDatagramLike},
+    prelude::*,
 };
 
 pub struct UnboundDatagram {
@@ -22,8 +23,12 @@ impl UnboundDatagram {
             unbound_socket: Box::new(UnboundUdpSocket::new(observer)),
         }
     }
+}
+
+impl UnboundDatagramLike for UnboundDatagram {
+    type Bound = BoundDatagram;
 
-    pub fn bind(
+    fn bind(
         self,
         endpoint: &IpEndpoint,
         can_reuse: bool,
@@ -32,20 +37,15 @@ impl UnboundDatagram {
             self.unbound_socket,
             endpoint,
             can_reuse,
-            |iface, socket, config| iface.bind_udp(socket, config),
+            |iface, socket, addr, config| iface.bind_udp(socket, addr, config),
         ) {
             Ok(bound_socket) => bound_socket,
             Err((err, unbound_socket)) => return Err((err, Self { unbound_socket })),
         };
 
-        let bound_endpoint = bound_socket.local_endpoint().unwrap();
+        let bound_endpoint = bound_socket.local_endpoint();
         bound_socket.bind(bound_endpoint).unwrap();
 
         Ok(BoundDatagram::new(bound_socket))
     }
-
-    pub(super) fn init_pollee(&self, pollee: &Pollee) {
-        pollee.reset_events();
-        pollee.add_events(IoEvents::OUT);
-    }
 }
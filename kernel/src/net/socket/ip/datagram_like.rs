@@ -0,0 +1,590 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The common parts of the datagram-like IP sockets.
+//!
+//! The UDP sockets, the ping sockets and the raw IP sockets are all connectionless and
+//! message-oriented. They only differ in how they are bound and how the messages are sent and
+//! received, which are provided by the protocol-specific [`UnboundDatagramLike`] and
+//! [`BoundDatagramLike`] sockets.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use aster_bigtcp::{socket::SocketEventObserver, wire::IpEndpoint};
+use takeable::Takeable;
+
+use super::{
+    common::{check_address_family, get_ephemeral_endpoint},
+    options::IpOptionSet,
+    IpFamily,
+};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut,
+    net::{
+        iface::poll_ifaces,
+        socket::{
+            options::{Error as SocketError, SocketOption},
+            util::{
+                options::SocketOptionSet, send_recv_flags::SendRecvFlags, socket_addr::SocketAddr,
+                MessageHeader,
+            },
+            Socket,
+        },
+    },
+    prelude::*,
+    process::signal::{Pollable, Pollee, Poller},
+    util::{MultiRead, MultiWrite},
+};
+
+/// An unbound socket of a datagram-like protocol.
+pub trait UnboundDatagramLike: Sized + Send + Sync + 'static {
+    type Bound: BoundDatagramLike;
+
+    /// Binds the socket to the endpoint.
+    fn bind(
+        self,
+        endpoint: &IpEndpoint,
+        can_reuse: bool,
+    ) -> core::result::Result<Self::Bound, (Error, Self)>;
+
+    /// Returns the local endpoint of the socket before it is bound.
+    fn local_endpoint(&self, family: IpFamily) -> IpEndpoint {
+        family.unspecified_local_endpoint()
+    }
+
+    /// Gets the protocol-specific options.
+    fn get_option(&self, _option: &mut dyn SocketOption) -> Result<()> {
+        return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+    }
+
+    /// Sets the protocol-specific options.
+    fn set_option(&mut self, _option: &dyn SocketOption) -> Result<()> {
+        return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+    }
+}
+
+/// A bound socket of a datagram-like protocol.
+pub trait BoundDatagramLike: Send + Sync + 'static {
+    fn local_endpoint(&self) -> IpEndpoint;
+
+    /// Receives a message and returns its length and the endpoint of its sender.
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, IpEndpoint)>;
+
+    /// Sends a message to `remote` and returns its length.
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: &IpEndpoint,
+        flags: SendRecvFlags,
+    ) -> Result<usize>;
+
+    fn update_io_events(&self, pollee: &Pollee);
+
+    /// Gets the protocol-specific options.
+    fn get_option(&self, _option: &mut dyn SocketOption) -> Result<()> {
+        return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+    }
+
+    /// Sets the protocol-specific options.
+    fn set_option(&self, _option: &dyn SocketOption) -> Result<()> {
+        return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+    }
+
+    /// Takes the error reported by ICMP messages, if any.
+    ///
+    /// `remote_endpoint` is the endpoint that the socket is connected to. `recv_err` indicates
+    /// whether the `IP_RECVERR` option is enabled.
+    fn take_icmp_error(
+        &self,
+        _remote_endpoint: Option<&IpEndpoint>,
+        _recv_err: bool,
+    ) -> Option<Error> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OptionSet {
+    socket: SocketOptionSet,
+    ip: IpOptionSet,
+}
+
+impl OptionSet {
+    fn new() -> Self {
+        let socket = SocketOptionSet::new_udp();
+        let ip = IpOptionSet::new();
+        OptionSet { socket, ip }
+    }
+}
+
+/// A datagram-like IP socket whose protocol is determined by `U`.
+pub struct DatagramLikeSocket<U: UnboundDatagramLike> {
+    family: IpFamily,
+    options: RwLock<OptionSet>,
+    inner: RwLock<Takeable<Inner<U>>>,
+    nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+enum Inner<U: UnboundDatagramLike> {
+    Unbound(U),
+    Bound(BoundState<U::Bound>),
+}
+
+struct BoundState<B> {
+    socket: B,
+    remote_endpoint: Option<IpEndpoint>,
+}
+
+impl<U: UnboundDatagramLike> Inner<U> {
+    fn bind(
+        self,
+        endpoint: &IpEndpoint,
+        can_reuse: bool,
+    ) -> core::result::Result<BoundState<U::Bound>, (Error, Self)> {
+        let unbound = match self {
+            Inner::Unbound(unbound) => unbound,
+            Inner::Bound(bound) => {
+                return Err((
+                    Error::with_message(Errno::EINVAL, "the socket is already bound to an address"),
+                    Inner::Bound(bound),
+                ));
+            }
+        };
+
+        let socket = match unbound.bind(endpoint, can_reuse) {
+            Ok(socket) => socket,
+            Err((err, unbound)) => return Err((err, Inner::Unbound(unbound))),
+        };
+        Ok(BoundState {
+            socket,
+            remote_endpoint: None,
+        })
+    }
+
+    fn bind_to_ephemeral_endpoint(
+        self,
+        remote_endpoint: &IpEndpoint,
+    ) -> core::result::Result<BoundState<U::Bound>, (Error, Self)> {
+        if let Inner::Bound(bound) = self {
+            return Ok(bound);
+        }
+
+        let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err((err, self)),
+        };
+        self.bind(&endpoint, false)
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match self {
+            Inner::Unbound(unbound) => unbound.get_option(option),
+            Inner::Bound(bound) => bound.socket.get_option(option),
+        }
+    }
+
+    fn set_option(&mut self, option: &dyn SocketOption) -> Result<()> {
+        match self {
+            Inner::Unbound(unbound) => unbound.set_option(option),
+            Inner::Bound(bound) => bound.socket.set_option(option),
+        }
+    }
+}
+
+impl<B: BoundDatagramLike> BoundState<B> {
+    fn set_remote_endpoint(&mut self, endpoint: &IpEndpoint) -> Result<()> {
+        check_address_family(&self.socket.local_endpoint(), endpoint)?;
+        self.remote_endpoint = Some(*endpoint);
+        Ok(())
+    }
+
+    fn init_pollee(&self, pollee: &Pollee) {
+        pollee.reset_events();
+        self.socket.update_io_events(pollee);
+    }
+}
+
+impl<U: UnboundDatagramLike> DatagramLikeSocket<U> {
+    /// Creates a socket with the unbound socket returned by `new_unbound`.
+    ///
+    /// The argument of `new_unbound` is the observer of the socket events.
+    pub(super) fn new_with<F>(family: IpFamily, nonblocking: bool, new_unbound: F) -> Arc<Self>
+    where
+        F: FnOnce(Weak<dyn SocketEventObserver>) -> U,
+    {
+        Arc::new_cyclic(|me| Self {
+            family,
+            inner: RwLock::new(Takeable::new(Inner::Unbound(new_unbound(me.clone() as _)))),
+            nonblocking: AtomicBool::new(nonblocking),
+            // An unbound socket can always be written, since it will be bound automatically.
+            pollee: Pollee::new(IoEvents::OUT),
+            options: RwLock::new(OptionSet::new()),
+        })
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::SeqCst)
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::SeqCst);
+    }
+
+    fn endpoint_from(&self, socket_addr: SocketAddr) -> Result<IpEndpoint> {
+        let v6_only = self.options.read().ip.v6_only();
+        self.family.endpoint_from(socket_addr, v6_only)
+    }
+
+    fn remote_endpoint(&self) -> Option<IpEndpoint> {
+        let inner = self.inner.read();
+
+        match inner.as_ref() {
+            Inner::Bound(bound) => bound.remote_endpoint,
+            Inner::Unbound(_) => None,
+        }
+    }
+
+    fn try_bind_ephemeral(&self, remote_endpoint: &IpEndpoint) -> Result<()> {
+        // Fast path
+        if let Inner::Bound(_) = self.inner.read().as_ref() {
+            return Ok(());
+        }
+
+        // Slow path
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound = match owned_inner.bind_to_ephemeral_endpoint(remote_endpoint) {
+                Ok(bound) => bound,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            bound.init_pollee(&self.pollee);
+            (Inner::Bound(bound), Ok(()))
+        })
+    }
+
+    /// Returns and clears the pending socket error, if any.
+    fn test_and_clear_error(&self) -> Result<()> {
+        let mut options = self.options.write();
+
+        let Some(error) = options.socket.sock_errors() else {
+            return Ok(());
+        };
+        options.socket.set_sock_errors(None);
+        self.pollee.del_events(IoEvents::ERR);
+
+        Err(error)
+    }
+
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, SocketAddr)> {
+        self.test_and_clear_error()?;
+
+        let inner = self.inner.read();
+
+        let Inner::Bound(bound) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound");
+        };
+
+        let received = bound
+            .socket
+            .try_recv(writer, flags)
+            .map(|(recv_bytes, remote_endpoint)| {
+                (recv_bytes, self.family.socket_addr_from(remote_endpoint))
+            });
+
+        drop(inner);
+        poll_ifaces();
+
+        received
+    }
+
+    fn recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, SocketAddr)> {
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            self.try_recv(writer, flags)
+        } else {
+            let timeout = self.options.read().socket.recv_timeout();
+            self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || {
+                self.try_recv(writer, flags)
+            })
+        }
+    }
+
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: &IpEndpoint,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        self.test_and_clear_error()?;
+
+        let inner = self.inner.read();
+
+        let Inner::Bound(bound) = inner.as_ref() else {
+            return_errno_with_message!(Errno::EAGAIN, "the socket is not bound")
+        };
+
+        let sent_bytes = bound.socket.try_send(reader, remote, flags);
+
+        drop(inner);
+        poll_ifaces();
+
+        sent_bytes
+    }
+
+    fn update_io_events(&self) {
+        // Read the option before locking `inner` to follow the locking order in `set_option`.
+        let recv_err = self.options.read().ip.recv_err();
+
+        let inner = self.inner.read();
+        let Inner::Bound(bound) = inner.as_ref() else {
+            return;
+        };
+        bound.socket.update_io_events(&self.pollee);
+
+        let Some(error) = bound
+            .socket
+            .take_icmp_error(bound.remote_endpoint.as_ref(), recv_err)
+        else {
+            return;
+        };
+        drop(inner);
+
+        self.options.write().socket.set_sock_errors(Some(error));
+        self.pollee.add_events(IoEvents::ERR);
+    }
+}
+
+impl<U: UnboundDatagramLike> Pollable for DatagramLikeSocket<U> {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl<U: UnboundDatagramLike> FileLike for DatagramLikeSocket<U> {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        // TODO: set correct flags
+        let flags = SendRecvFlags::empty();
+        let read_len = self.recv(writer, flags).map(|(len, _)| len)?;
+        Ok(read_len)
+    }
+
+    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+        let remote = self.remote_endpoint().ok_or_else(|| {
+            Error::with_message(
+                Errno::EDESTADDRREQ,
+                "the destination address is not specified",
+            )
+        })?;
+
+        // TODO: Set correct flags
+        let flags = SendRecvFlags::empty();
+
+        // TODO: Block if send buffer is full
+        self.try_send(reader, &remote, flags)
+    }
+
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        // TODO: when we fully support O_ASYNC, return the flag
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        if new_flags.contains(StatusFlags::O_NONBLOCK) {
+            self.set_nonblocking(true);
+        } else {
+            self.set_nonblocking(false);
+        }
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+}
+
+impl<U: UnboundDatagramLike> Socket for DatagramLikeSocket<U> {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let endpoint = self.endpoint_from(socket_addr)?;
+
+        let can_reuse = self.options.read().socket.reuse_addr();
+        let mut inner = self.inner.write();
+        inner.borrow_result(|owned_inner| {
+            let bound = match owned_inner.bind(&endpoint, can_reuse) {
+                Ok(bound) => bound,
+                Err((err, err_inner)) => {
+                    return (err_inner, Err(err));
+                }
+            };
+            bound.init_pollee(&self.pollee);
+            (Inner::Bound(bound), Ok(()))
+        })
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let endpoint = self.endpoint_from(socket_addr)?;
+
+        self.try_bind_ephemeral(&endpoint)?;
+
+        let mut inner = self.inner.write();
+        let Inner::Bound(bound) = inner.as_mut() else {
+            return_errno_with_message!(Errno::EINVAL, "the socket is not bound")
+        };
+        bound.set_remote_endpoint(&endpoint)
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let inner = self.inner.read();
+        let local_endpoint = match inner.as_ref() {
+            Inner::Unbound(unbound) => unbound.local_endpoint(self.family),
+            Inner::Bound(bound) => bound.socket.local_endpoint(),
+        };
+        Ok(self.family.socket_addr_from(local_endpoint))
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        self.remote_endpoint()
+            .map(|endpoint| self.family.socket_addr_from(endpoint))
+            .ok_or_else(|| Error::with_message(Errno::ENOTCONN, "the socket is not connected"))
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let MessageHeader {
+            addr,
+            control_message,
+        } = message_header;
+
+        let remote_endpoint = match addr {
+            Some(remote_addr) => {
+                let endpoint = self.endpoint_from(remote_addr)?;
+                self.try_bind_ephemeral(&endpoint)?;
+                endpoint
+            }
+            None => self.remote_endpoint().ok_or_else(|| {
+                Error::with_message(
+                    Errno::EDESTADDRREQ,
+                    "the destination address is not specified",
+                )
+            })?,
+        };
+
+        if control_message.is_some() {
+            // TODO: Support sending control message
+            warn!("sending control message is not supported");
+        }
+
+        // TODO: Block if the send buffer is full
+        self.try_send(reader, &remote_endpoint, flags)
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let (received_bytes, peer_addr) = self.recv(writer, flags)?;
+
+        // TODO: Receive control message
+
+        let message_header = MessageHeader::new(Some(peer_addr), None);
+
+        Ok((received_bytes, message_header))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                self.options.write().socket.get_and_clear_sock_errors(socket_errors);
+                self.pollee.del_events(IoEvents::ERR);
+                return Ok(());
+            },
+            _ => ()
+        });
+
+        let options = self.options.read();
+
+        match options.socket.get_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        match options.ip.get_option(self.family, option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        self.inner.read().as_ref().get_option(option)
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        let mut options = self.options.write();
+
+        match options.socket.set_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        let mut inner = self.inner.write();
+
+        let is_bound = matches!(inner.as_ref(), Inner::Bound(_));
+        match options.ip.set_option(self.family, option, is_bound) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        inner.as_mut().set_option(option)
+    }
+}
+
+impl<U: UnboundDatagramLike> SocketEventObserver for DatagramLikeSocket<U> {
+    fn on_events(&self) {
+        self.update_io_events();
+    }
+}
@@ -3,8 +3,14 @@
 mod addr;
 mod common;
 mod datagram;
+mod datagram_like;
+pub mod options;
+mod ping;
+pub mod raw;
 pub mod stream;
 
-use addr::UNSPECIFIED_LOCAL_ENDPOINT;
+pub use addr::IpFamily;
 pub use datagram::DatagramSocket;
+pub use ping::PingSocket;
+pub use raw::RawSocket;
 pub use stream::StreamSocket;
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::IpFamily;
+use crate::{
+    impl_socket_options, match_sock_option_mut, match_sock_option_ref,
+    net::socket::options::SocketOption, prelude::*,
+};
+
+impl_socket_options!(
+    pub struct V6Only(bool);
+    pub struct RecvErr(bool);
+);
+
+/// IP-level options (i.e., the options at the `SOL_IP` or `SOL_IPV6` level).
+#[derive(Debug, Clone, Copy, CopyGetters, Setters)]
+#[get_copy = "pub"]
+#[set = "pub"]
+pub struct IpOptionSet {
+    v6_only: bool,
+    recv_err: bool,
+}
+
+impl IpOptionSet {
+    pub fn new() -> Self {
+        Self {
+            v6_only: false,
+            recv_err: false,
+        }
+    }
+
+    /// Gets IP-level options.
+    pub fn get_option(&self, family: IpFamily, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            ipv6_v6_only: V6Only => {
+                check_ipv6(family)?;
+                let v6_only = self.v6_only();
+                ipv6_v6_only.set(v6_only);
+            },
+            ip_recv_err: RecvErr => {
+                let recv_err = self.recv_err();
+                ip_recv_err.set(recv_err);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+        Ok(())
+    }
+
+    /// Sets IP-level options.
+    ///
+    /// Some options cannot be changed after the socket is bound, as indicated by `is_bound`.
+    pub fn set_option(
+        &mut self,
+        family: IpFamily,
+        option: &dyn SocketOption,
+        is_bound: bool,
+    ) -> Result<()> {
+        match_sock_option_ref!(option, {
+            ipv6_v6_only: V6Only => {
+                check_ipv6(family)?;
+                if is_bound {
+                    return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+                }
+                let v6_only = ipv6_v6_only.get().unwrap();
+                self.set_v6_only(*v6_only);
+            },
+            ip_recv_err: RecvErr => {
+                let recv_err = ip_recv_err.get().unwrap();
+                self.set_recv_err(*recv_err);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+        Ok(())
+    }
+}
+
+impl Default for IpOptionSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_ipv6(family: IpFamily) -> Result<()> {
+    if family != IpFamily::Ipv6 {
+        return_errno_with_message!(
+            Errno::ENOPROTOOPT,
+            "the socket option is only available for IPv6 sockets"
+        );
+    }
+    Ok(())
+}
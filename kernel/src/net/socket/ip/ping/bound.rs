@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{
+    errors::ping::{RecvError, SendError},
+    wire::{IpAddress, IpEndpoint},
+};
+
+use crate::{
+    events::IoEvents,
+    net::{
+        iface::BoundPingSocket,
+        socket::{
+            ip::{common::check_address_family, datagram_like::BoundDatagramLike},
+            util::send_recv_flags::SendRecvFlags,
+        },
+    },
+    prelude::*,
+    process::signal::Pollee,
+    util::{MultiRead, MultiWrite},
+};
+
+pub struct BoundPing {
+    bound_socket: BoundPingSocket,
+}
+
+impl BoundPing {
+    pub fn new(bound_socket: BoundPingSocket) -> Self {
+        Self { bound_socket }
+    }
+}
+
+impl BoundDatagramLike for BoundPing {
+    fn local_endpoint(&self) -> IpEndpoint {
+        self.bound_socket.local_endpoint()
+    }
+
+    /// Receives an ICMP echo reply.
+    ///
+    /// The ICMP header is written to `writer` along with the data. The port of the returned
+    /// endpoint is always zero.
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, IpEndpoint)> {
+        let recv_message = |message: &[u8], remote_addr: IpAddress| {
+            // The part of the message that does not fit in the buffer is discarded.
+            let copied_res = writer.write(&mut VmReader::from(message));
+            (copied_res, message.len(), IpEndpoint::new(remote_addr, 0))
+        };
+        let result = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.bound_socket.peek(recv_message)
+        } else {
+            self.bound_socket.recv(recv_message)
+        };
+
+        match result {
+            Ok((Ok(copied_len), message_len, endpoint)) => {
+                // With `MSG_TRUNC`, the real length of the message is returned even if it is
+                // truncated.
+                if flags.contains(SendRecvFlags::MSG_TRUNC) {
+                    Ok((message_len, endpoint))
+                } else {
+                    Ok((copied_len, endpoint))
+                }
+            }
+            Ok((Err(e), _, _)) => Err(e),
+            Err(RecvError::Exhausted) => {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
+            }
+        }
+    }
+
+    /// Sends an ICMP echo request.
+    ///
+    /// The message read from `reader` should start with the ICMP header. The port of `remote` is
+    /// ignored.
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: &IpEndpoint,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
+        check_address_family(&self.local_endpoint(), remote)?;
+
+        let mut message = vec![0u8; reader.sum_lens()];
+        reader.read(&mut VmWriter::from(message.as_mut_slice()))?;
+
+        match self.bound_socket.send(remote.addr, &message) {
+            Ok(()) => Ok(message.len()),
+            Err(SendError::TooLarge) => {
+                return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+            }
+            Err(SendError::Unaddressable) => {
+                return_errno_with_message!(Errno::EINVAL, "the destination address is invalid");
+            }
+            Err(SendError::BufferFull) => {
+                return_errno_with_message!(Errno::EAGAIN, "the send buffer is full");
+            }
+            Err(SendError::InvalidMessage) => {
+                return_errno_with_message!(
+                    Errno::EINVAL,
+                    "the message is not an ICMP echo request"
+                );
+            }
+        }
+    }
+
+    fn update_io_events(&self, pollee: &Pollee) {
+        self.bound_socket.raw_with(|socket| {
+            if socket.can_recv() {
+                pollee.add_events(IoEvents::IN);
+            } else {
+                pollee.del_events(IoEvents::IN);
+            }
+
+            if socket.can_send() {
+                pollee.add_events(IoEvents::OUT);
+            } else {
+                pollee.del_events(IoEvents::OUT);
+            }
+        });
+    }
+}
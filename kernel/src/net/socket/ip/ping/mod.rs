@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use self::unbound::UnboundPing;
+use super::{datagram_like::DatagramLikeSocket, IpFamily};
+use crate::prelude::*;
+
+mod bound;
+mod unbound;
+
+/// A ping socket (i.e., a `SOCK_DGRAM` socket with `IPPROTO_ICMP` or `IPPROTO_ICMPV6`).
+///
+/// It can send ICMP echo requests and receive ICMP echo replies without any privileges.
+pub type PingSocket = DatagramLikeSocket<UnboundPing>;
+
+impl PingSocket {
+    pub fn new(family: IpFamily, nonblocking: bool) -> Arc<Self> {
+        Self::new_with(family, nonblocking, UnboundPing::new)
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::sync::Weak;
+
+use aster_bigtcp::{
+    socket::{SocketEventObserver, UnboundPingSocket},
+    wire::IpEndpoint,
+};
+
+use super::bound::BoundPing;
+use crate::{
+    net::socket::ip::{common::bind_socket, datagram_like::UnboundDatagramLike},
+    prelude::*,
+};
+
+pub struct UnboundPing {
+    unbound_socket: Box<UnboundPingSocket>,
+}
+
+impl UnboundPing {
+    pub fn new(observer: Weak<dyn SocketEventObserver>) -> Self {
+        Self {
+            unbound_socket: Box::new(UnboundPingSocket::new(observer)),
+        }
+    }
+}
+
+impl UnboundDatagramLike for UnboundPing {
+    type Bound = BoundPing;
+
+    /// Binds the socket to the endpoint.
+    ///
+    /// The port of the endpoint is used as the identifier of the ICMP echo requests.
+    fn bind(
+        self,
+        endpoint: &IpEndpoint,
+        can_reuse: bool,
+    ) -> core::result::Result<BoundPing, (Error, Self)> {
+        let bound_socket = match bind_socket(
+            self.unbound_socket,
+            endpoint,
+            can_reuse,
+            |iface, socket, addr, config| iface.bind_ping(socket, addr, config),
+        ) {
+            Ok(bound_socket) => bound_socket,
+            Err((err, unbound_socket)) => return Err((err, Self { unbound_socket })),
+        };
+
+        Ok(BoundPing::new(bound_socket))
+    }
+}
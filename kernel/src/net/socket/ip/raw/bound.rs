@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{
+    errors::raw::{RecvError, SendError},
+    wire::{IpAddress, IpEndpoint},
+};
+
+use super::options::RawOptionSet;
+use crate::{
+    events::IoEvents,
+    net::{
+        iface::BoundRawIpSocket,
+        socket::{
+            ip::{common::check_address_family, datagram_like::BoundDatagramLike},
+            options::SocketOption,
+            util::send_recv_flags::SendRecvFlags,
+        },
+    },
+    prelude::*,
+    process::signal::Pollee,
+    util::{MultiRead, MultiWrite},
+};
+
+pub struct BoundRaw {
+    bound_socket: BoundRawIpSocket,
+    protocol: u8,
+    options: RwLock<RawOptionSet>,
+}
+
+impl BoundRaw {
+    pub fn new(bound_socket: BoundRawIpSocket, protocol: u8, options: RawOptionSet) -> Self {
+        Self {
+            bound_socket,
+            protocol,
+            options: RwLock::new(options),
+        }
+    }
+}
+
+impl BoundDatagramLike for BoundRaw {
+    fn local_endpoint(&self) -> IpEndpoint {
+        self.bound_socket.local_endpoint()
+    }
+
+    /// Receives an IP packet.
+    ///
+    /// The IP header is written to `writer` along with the IP payload. The port of the returned
+    /// endpoint is always zero.
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, IpEndpoint)> {
+        let recv_packet = |packet: &[u8], remote_addr: IpAddress| {
+            // The part of the packet that does not fit in the buffer is discarded.
+            let copied_res = writer.write(&mut VmReader::from(packet));
+            (copied_res, packet.len(), IpEndpoint::new(remote_addr, 0))
+        };
+        let result = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.bound_socket.peek(recv_packet)
+        } else {
+            self.bound_socket.recv(recv_packet)
+        };
+
+        match result {
+            Ok((Ok(copied_len), packet_len, endpoint)) => {
+                // With `MSG_TRUNC`, the real length of the packet is returned even if it is
+                // truncated.
+                if flags.contains(SendRecvFlags::MSG_TRUNC) {
+                    Ok((packet_len, endpoint))
+                } else {
+                    Ok((copied_len, endpoint))
+                }
+            }
+            Ok((Err(e), _, _)) => Err(e),
+            Err(RecvError::Exhausted) => {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
+            }
+        }
+    }
+
+    /// Sends an IP packet.
+    ///
+    /// The data read from `reader` is the IP payload, and the IP header is generated by the
+    /// kernel. If `IP_HDRINCL` is set, the data starts with the IP header instead, and the packet
+    /// is sent to the destination address in the header. The port of `remote` is ignored.
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: &IpEndpoint,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
+        check_address_family(&self.local_endpoint(), remote)?;
+
+        let mut data = vec![0u8; reader.sum_lens()];
+        reader.read(&mut VmWriter::from(data.as_mut_slice()))?;
+
+        let result = if self.options.read().hdr_incl() {
+            self.bound_socket.send_with_header(&data)
+        } else {
+            self.bound_socket.send(remote.addr, &data)
+        };
+
+        match result {
+            Ok(()) => Ok(data.len()),
+            Err(SendError::TooLarge) => {
+                return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+            }
+            Err(SendError::Unaddressable) => {
+                return_errno_with_message!(Errno::EINVAL, "the destination address is invalid");
+            }
+            Err(SendError::BufferFull) => {
+                return_errno_with_message!(Errno::EAGAIN, "the send buffer is full");
+            }
+            Err(SendError::InvalidHeader) => {
+                return_errno_with_message!(Errno::EINVAL, "the IP header is invalid");
+            }
+        }
+    }
+
+    fn update_io_events(&self, pollee: &Pollee) {
+        self.bound_socket.raw_with(|socket| {
+            if socket.can_recv() {
+                pollee.add_events(IoEvents::IN);
+            } else {
+                pollee.del_events(IoEvents::IN);
+            }
+
+            if socket.can_send() {
+                pollee.add_events(IoEvents::OUT);
+            } else {
+                pollee.del_events(IoEvents::OUT);
+            }
+        });
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        self.options.read().get_option(self.protocol, option)
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        let mut options = self.options.write();
+        options.set_option(self.protocol, option)?;
+        self.bound_socket.set_icmp_filter(options.icmp_filter());
+        Ok(())
+    }
+}
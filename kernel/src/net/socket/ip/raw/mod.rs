@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use self::unbound::UnboundRaw;
+use super::{datagram_like::DatagramLikeSocket, IpFamily};
+use crate::{
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::PosixThreadExt},
+    util::net::Protocol,
+};
+
+mod bound;
+pub mod options;
+mod unbound;
+
+/// A raw IP socket (i.e., a `SOCK_RAW` socket in the `AF_INET` domain).
+///
+/// It sends and receives the IP packets of a specific protocol. The IP header is generated by the
+/// kernel when sending packets (unless `IP_HDRINCL` is set), but it is included in the received
+/// packets. Creating raw IP sockets requires `CAP_NET_RAW`.
+///
+/// Currently, only IPv4 is supported.
+pub type RawSocket = DatagramLikeSocket<UnboundRaw>;
+
+impl RawSocket {
+    pub fn new(protocol: Protocol, nonblocking: bool) -> Result<Arc<Self>> {
+        let protocol = match protocol {
+            Protocol::IPPROTO_IP => {
+                return_errno_with_message!(Errno::EPROTONOSUPPORT, "the protocol must be specified")
+            }
+            // TODO: Support `IPPROTO_RAW`, which implies `IP_HDRINCL`.
+            Protocol::IPPROTO_RAW | Protocol::IPPROTO_MPTCP => {
+                return_errno_with_message!(
+                    Errno::EPROTONOSUPPORT,
+                    "the protocol is not supported by raw sockets"
+                )
+            }
+            protocol => protocol as u8,
+        };
+
+        let credentials = current_thread!().as_posix_thread().unwrap().credentials();
+        if !credentials.effective_capset().contains(CapSet::NET_RAW) {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "CAP_NET_RAW is required to create raw sockets"
+            );
+        }
+
+        Ok(Self::new_with(IpFamily::Ipv4, nonblocking, |observer| {
+            UnboundRaw::new(observer, protocol)
+        }))
+    }
+}
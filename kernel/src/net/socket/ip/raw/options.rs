@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    impl_socket_options, match_sock_option_mut, match_sock_option_ref,
+    net::socket::options::SocketOption, prelude::*, util::net::Protocol,
+};
+
+impl_socket_options!(
+    pub struct HdrIncl(bool);
+    pub struct IcmpFilter(u32);
+);
+
+/// Options of raw IP sockets (i.e., `IP_HDRINCL` and the options at the `SOL_RAW` level).
+#[derive(Debug, Clone, Copy, CopyGetters, Setters)]
+#[get_copy = "pub"]
+#[set = "pub"]
+pub struct RawOptionSet {
+    hdr_incl: bool,
+    icmp_filter: u32,
+}
+
+impl RawOptionSet {
+    pub fn new() -> Self {
+        Self {
+            hdr_incl: false,
+            icmp_filter: 0,
+        }
+    }
+
+    /// Gets raw-socket options.
+    ///
+    /// `protocol` is the protocol of the raw socket.
+    pub fn get_option(&self, protocol: u8, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            ip_hdr_incl: HdrIncl => {
+                let hdr_incl = self.hdr_incl();
+                ip_hdr_incl.set(hdr_incl);
+            },
+            raw_icmp_filter: IcmpFilter => {
+                check_icmp(protocol)?;
+                let icmp_filter = self.icmp_filter();
+                raw_icmp_filter.set(icmp_filter);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+        Ok(())
+    }
+
+    /// Sets raw-socket options.
+    ///
+    /// `protocol` is the protocol of the raw socket.
+    pub fn set_option(&mut self, protocol: u8, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            ip_hdr_incl: HdrIncl => {
+                let hdr_incl = ip_hdr_incl.get().unwrap();
+                self.set_hdr_incl(*hdr_incl);
+            },
+            raw_icmp_filter: IcmpFilter => {
+                check_icmp(protocol)?;
+                let icmp_filter = raw_icmp_filter.get().unwrap();
+                self.set_icmp_filter(*icmp_filter);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+        Ok(())
+    }
+}
+
+impl Default for RawOptionSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_icmp(protocol: u8) -> Result<()> {
+    if protocol != Protocol::IPPROTO_ICMP as u8 {
+        return_errno_with_message!(
+            Errno::EOPNOTSUPP,
+            "the socket option is only available for ICMP raw sockets"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::sync::Weak;
+
+use aster_bigtcp::{
+    socket::{SocketEventObserver, UnboundRawIpSocket},
+    wire::{IpAddress, IpEndpoint, Ipv4Address},
+};
+
+use super::{bound::BoundRaw, options::RawOptionSet};
+use crate::{
+    net::socket::{
+        ip::{common::get_iface_to_bind, datagram_like::UnboundDatagramLike, IpFamily},
+        options::SocketOption,
+    },
+    prelude::*,
+};
+
+pub struct UnboundRaw {
+    unbound_socket: Box<UnboundRawIpSocket>,
+    protocol: u8,
+    options: RawOptionSet,
+}
+
+impl UnboundRaw {
+    pub fn new(observer: Weak<dyn SocketEventObserver>, protocol: u8) -> Self {
+        Self {
+            unbound_socket: Box::new(UnboundRawIpSocket::new(observer)),
+            protocol,
+            options: RawOptionSet::new(),
+        }
+    }
+}
+
+impl UnboundDatagramLike for UnboundRaw {
+    type Bound = BoundRaw;
+
+    /// Binds the socket to the endpoint.
+    ///
+    /// The port of the endpoint is ignored, since raw sockets have no ports. Instead, the socket
+    /// is bound to its protocol. Raw sockets can always share the same address, so `can_reuse`
+    /// is ignored as well.
+    fn bind(
+        self,
+        endpoint: &IpEndpoint,
+        _can_reuse: bool,
+    ) -> core::result::Result<BoundRaw, (Error, Self)> {
+        let Some(iface) = get_iface_to_bind(&endpoint.addr) else {
+            let err = Error::with_message(
+                Errno::EADDRNOTAVAIL,
+                "the address is not available from the local machine",
+            );
+            return Err((err, self));
+        };
+
+        let bound_socket = iface.bind_raw_ip(self.unbound_socket, endpoint.addr, self.protocol);
+
+        Ok(BoundRaw::new(bound_socket, self.protocol, self.options))
+    }
+
+    /// The port of a raw socket is its protocol.
+    fn local_endpoint(&self, _family: IpFamily) -> IpEndpoint {
+        IpEndpoint::new(
+            IpAddress::Ipv4(Ipv4Address::UNSPECIFIED),
+            self.protocol as u16,
+        )
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        self.options.get_option(self.protocol, option)
+    }
+
+    fn set_option(&mut self, option: &dyn SocketOption) -> Result<()> {
+        self.options.set_option(self.protocol, option)?;
+        self.unbound_socket
+            .set_icmp_filter(self.options.icmp_filter());
+        Ok(())
+    }
+}
@@ -1,13 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::sync::Weak;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use aster_bigtcp::{
     errors::tcp::{RecvError, SendError},
-    socket::SocketEventObserver,
+    socket::{RawTcpOption, SocketEventObserver},
     wire::IpEndpoint,
 };
 
+use super::util::TcpInfo;
 use crate::{
     events::IoEvents,
     net::{
@@ -33,6 +35,11 @@ pub struct ConnectedStream {
     /// connection is established asynchronously will succeed and any subsequent `connect()` will
     /// fail.
     is_new_connection: bool,
+    /// Indicates whether the socket is shut down for writing.
+    ///
+    /// Sending data after the socket is shut down for writing fails with `EPIPE` instead of
+    /// `ECONNRESET`.
+    is_write_shutdown: AtomicBool,
 }
 
 impl ConnectedStream {
@@ -45,22 +52,31 @@ impl ConnectedStream {
             bound_socket,
             remote_endpoint,
             is_new_connection,
+            is_write_shutdown: AtomicBool::new(false),
         }
     }
 
-    pub fn shutdown(&self, _cmd: SockShutdownCmd) -> Result<()> {
-        // TODO: deal with cmd
-        self.bound_socket.close();
+    pub fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
+        // TODO: Deal with `SHUT_RD`
+        if cmd.shut_write() {
+            self.is_write_shutdown.store(true, Ordering::Relaxed);
+            self.bound_socket.close();
+        }
         Ok(())
     }
 
-    pub fn try_recv(&self, writer: &mut dyn MultiWrite, _flags: SendRecvFlags) -> Result<usize> {
-        let result = self.bound_socket.recv(|socket_buffer| {
-            match writer.write(&mut VmReader::from(&*socket_buffer)) {
-                Ok(len) => (len, Ok(len)),
-                Err(e) => (0, Err(e)),
-            }
-        });
+    pub fn try_recv(&self, writer: &mut dyn MultiWrite, flags: SendRecvFlags) -> Result<usize> {
+        let result = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.bound_socket
+                .peek(|socket_buffer| writer.write(&mut VmReader::from(socket_buffer)))
+        } else {
+            self.bound_socket.recv(|socket_buffer| {
+                match writer.write(&mut VmReader::from(&*socket_buffer)) {
+                    Ok(len) => (len, Ok(len)),
+                    Err(e) => (0, Err(e)),
+                }
+            })
+        };
 
         match result {
             Ok(Ok(0)) => return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty"),
@@ -85,17 +101,17 @@ impl ConnectedStream {
             Ok(Ok(0)) => return_errno_with_message!(Errno::EAGAIN, "the send buffer is full"),
             Ok(Ok(sent_bytes)) => Ok(sent_bytes),
             Ok(Err(e)) => Err(e),
+            Err(SendError::InvalidState) if self.is_write_shutdown.load(Ordering::Relaxed) => {
+                return_errno_with_message!(Errno::EPIPE, "the socket is shut down for writing");
+            }
             Err(SendError::InvalidState) => {
-                // FIXME: `EPIPE` is another possibility, which means that the socket is shut down
-                // for writing. In that case, we should also trigger a `SIGPIPE` if `MSG_NOSIGNAL`
-                // is not specified.
                 return_errno_with_message!(Errno::ECONNRESET, "the connection is reset");
             }
         }
     }
 
     pub fn local_endpoint(&self) -> IpEndpoint {
-        self.bound_socket.local_endpoint().unwrap()
+        self.bound_socket.local_endpoint()
     }
 
     pub fn remote_endpoint(&self) -> IpEndpoint {
@@ -117,22 +133,30 @@ impl ConnectedStream {
     }
 
     pub(super) fn update_io_events(&self, pollee: &Pollee) {
-        self.bound_socket.raw_with(|socket| {
-            if socket.can_recv() {
-                pollee.add_events(IoEvents::IN);
-            } else {
-                pollee.del_events(IoEvents::IN);
-            }
+        if self.bound_socket.raw_with(|socket| socket.can_recv()) {
+            pollee.add_events(IoEvents::IN);
+        } else {
+            pollee.del_events(IoEvents::IN);
+        }
 
-            if socket.can_send() {
-                pollee.add_events(IoEvents::OUT);
-            } else {
-                pollee.del_events(IoEvents::OUT);
-            }
-        });
+        // The data held back by `TCP_CORK` also occupies the send buffer.
+        if self.bound_socket.can_send() {
+            pollee.add_events(IoEvents::OUT);
+        } else {
+            pollee.del_events(IoEvents::OUT);
+        }
     }
 
     pub(super) fn set_observer(&self, observer: Weak<dyn SocketEventObserver>) {
         self.bound_socket.set_observer(observer)
     }
+
+    pub(super) fn set_raw_option(&self, raw_option: &RawTcpOption) {
+        self.bound_socket.set_raw_option(raw_option)
+    }
+
+    pub(super) fn tcp_info(&self, mss: u32) -> TcpInfo {
+        self.bound_socket
+            .raw_with(|socket| TcpInfo::from_raw(socket, mss))
+    }
 }
@@ -1,9 +1,13 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use aster_bigtcp::wire::IpEndpoint;
+use aster_bigtcp::{socket::RawTcpOption, wire::IpEndpoint};
 
-use super::{connected::ConnectedStream, init::InitStream};
-use crate::{net::iface::BoundTcpSocket, prelude::*, process::signal::Pollee};
+use super::{connected::ConnectedStream, init::InitStream, util::TcpInfo};
+use crate::{
+    net::{iface::BoundTcpSocket, socket::ip::common::check_address_family},
+    prelude::*,
+    process::signal::Pollee,
+};
 
 pub struct ConnectingStream {
     bound_socket: BoundTcpSocket,
@@ -27,7 +31,11 @@ impl ConnectingStream {
         bound_socket: BoundTcpSocket,
         remote_endpoint: IpEndpoint,
     ) -> core::result::Result<Self, (Error, BoundTcpSocket)> {
-        // The only reason this method might fail is because we're trying to connect to an
+        if let Err(err) = check_address_family(&bound_socket.local_endpoint(), &remote_endpoint) {
+            return Err((err, bound_socket));
+        }
+
+        // Except for the check above, the only reason this method might fail is because we're trying to connect to an
         // unspecified address (i.e. 0.0.0.0). We currently have no support for binding to,
         // listening on, or connecting to the unspecified address.
         //
@@ -69,7 +77,7 @@ impl ConnectingStream {
     }
 
     pub fn local_endpoint(&self) -> IpEndpoint {
-        self.bound_socket.local_endpoint().unwrap()
+        self.bound_socket.local_endpoint()
     }
 
     pub fn remote_endpoint(&self) -> IpEndpoint {
@@ -80,6 +88,15 @@ impl ConnectingStream {
         pollee.reset_events();
     }
 
+    pub(super) fn set_raw_option(&self, raw_option: &RawTcpOption) {
+        self.bound_socket.set_raw_option(raw_option)
+    }
+
+    pub(super) fn tcp_info(&self, mss: u32) -> TcpInfo {
+        self.bound_socket
+            .raw_with(|socket| TcpInfo::from_raw(socket, mss))
+    }
+
     /// Returns `true` when `conn_result` becomes ready, which indicates that the caller should
     /// invoke the `into_result()` method as soon as possible.
     ///
@@ -7,7 +7,7 @@ use aster_bigtcp::{
     wire::IpEndpoint,
 };
 
-use super::{connecting::ConnectingStream, listen::ListenStream};
+use super::{connecting::ConnectingStream, listen::ListenStream, OptionSet};
 use crate::{
     events::IoEvents,
     net::{
@@ -50,7 +50,7 @@ impl InitStream {
             unbound_socket,
             endpoint,
             can_reuse,
-            |iface, socket, config| iface.bind_tcp(socket, config),
+            |iface, socket, addr, config| iface.bind_tcp(socket, addr, config),
         ) {
             Ok(bound_socket) => bound_socket,
             Err((err, unbound_socket)) => return Err((err, InitStream::Unbound(unbound_socket))),
@@ -62,7 +62,10 @@ impl InitStream {
         self,
         remote_endpoint: &IpEndpoint,
     ) -> core::result::Result<BoundTcpSocket, (Error, Self)> {
-        let endpoint = get_ephemeral_endpoint(remote_endpoint);
+        let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err((err, self)),
+        };
         self.bind(&endpoint, false)
     }
 
@@ -79,7 +82,11 @@ impl InitStream {
             .map_err(|(err, bound_socket)| (err, InitStream::Bound(bound_socket)))
     }
 
-    pub fn listen(self, backlog: usize) -> core::result::Result<ListenStream, (Error, Self)> {
+    pub fn listen(
+        self,
+        backlog: usize,
+        options: &OptionSet,
+    ) -> core::result::Result<ListenStream, (Error, Self)> {
         let InitStream::Bound(bound_socket) = self else {
             // FIXME: The socket should be bound to INADDR_ANY (i.e., 0.0.0.0) with an ephemeral
             // port. However, INADDR_ANY is not yet supported, so we need to return an error first.
@@ -90,14 +97,14 @@ impl InitStream {
             ));
         };
 
-        ListenStream::new(bound_socket, backlog)
+        ListenStream::new(bound_socket, backlog, options)
             .map_err(|(err, bound_socket)| (err, InitStream::Bound(bound_socket)))
     }
 
     pub fn local_endpoint(&self) -> Option<IpEndpoint> {
         match self {
             InitStream::Unbound(_) => None,
-            InitStream::Bound(bound_socket) => Some(bound_socket.local_endpoint().unwrap()),
+            InitStream::Bound(bound_socket) => Some(bound_socket.local_endpoint()),
         }
     }
 
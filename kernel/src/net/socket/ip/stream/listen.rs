@@ -1,10 +1,13 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use aster_bigtcp::{
-    errors::tcp::ListenError, iface::BindPortConfig, socket::UnboundTcpSocket, wire::IpEndpoint,
+    errors::tcp::ListenError,
+    iface::BindPortConfig,
+    socket::{RawTcpOption, UnboundTcpSocket},
+    wire::IpEndpoint,
 };
 
-use super::connected::ConnectedStream;
+use super::{connected::ConnectedStream, OptionSet};
 use crate::{events::IoEvents, net::iface::BoundTcpSocket, prelude::*, process::signal::Pollee};
 
 pub struct ListenStream {
@@ -19,6 +22,7 @@ impl ListenStream {
     pub fn new(
         bound_socket: BoundTcpSocket,
         backlog: usize,
+        options: &OptionSet,
     ) -> core::result::Result<Self, (Error, BoundTcpSocket)> {
         const SOMAXCONN: usize = 4096;
         let somaxconn = SOMAXCONN.min(backlog);
@@ -28,14 +32,14 @@ impl ListenStream {
             bound_socket,
             backlog_sockets: RwLock::new(Vec::new()),
         };
-        if let Err(err) = listen_stream.fill_backlog_sockets() {
+        if let Err(err) = listen_stream.fill_backlog_sockets(options) {
             return Err((err, listen_stream.bound_socket));
         }
         Ok(listen_stream)
     }
 
     /// Append sockets listening at LocalEndPoint to support backlog
-    fn fill_backlog_sockets(&self) -> Result<()> {
+    fn fill_backlog_sockets(&self, options: &OptionSet) -> Result<()> {
         let mut backlog_sockets = self.backlog_sockets.write();
 
         let backlog = self.backlog;
@@ -46,14 +50,14 @@ impl ListenStream {
         }
 
         for _ in current_backlog_len..backlog {
-            let backlog_socket = BacklogSocket::new(&self.bound_socket)?;
+            let backlog_socket = BacklogSocket::new(&self.bound_socket, options)?;
             backlog_sockets.push(backlog_socket);
         }
 
         Ok(())
     }
 
-    pub fn try_accept(&self) -> Result<ConnectedStream> {
+    pub fn try_accept(&self, options: &OptionSet) -> Result<ConnectedStream> {
         let mut backlog_sockets = self.backlog_sockets.write();
 
         let index = backlog_sockets
@@ -64,7 +68,7 @@ impl ListenStream {
             })?;
         let active_backlog_socket = backlog_sockets.remove(index);
 
-        if let Ok(backlog_socket) = BacklogSocket::new(&self.bound_socket) {
+        if let Ok(backlog_socket) = BacklogSocket::new(&self.bound_socket, options) {
             backlog_sockets.push(backlog_socket);
         }
 
@@ -77,7 +81,7 @@ impl ListenStream {
     }
 
     pub fn local_endpoint(&self) -> IpEndpoint {
-        self.bound_socket.local_endpoint().unwrap()
+        self.bound_socket.local_endpoint()
     }
 
     pub(super) fn init_pollee(&self, pollee: &Pollee) {
@@ -85,6 +89,22 @@ impl ListenStream {
         self.update_io_events(pollee);
     }
 
+    /// Sets the TCP options of the backlog sockets.
+    ///
+    /// Connections that have already been established will not be affected if they are accepted
+    /// later. This is consistent with the Linux behavior, since they are already separate sockets
+    /// at that point.
+    pub(super) fn set_raw_option(&self, raw_option: &RawTcpOption) {
+        let backlog_sockets = self.backlog_sockets.read();
+
+        for backlog_socket in backlog_sockets
+            .iter()
+            .filter(|backlog_socket| !backlog_socket.is_active())
+        {
+            backlog_socket.bound_socket.set_raw_option(raw_option);
+        }
+    }
+
     pub(super) fn update_io_events(&self, pollee: &Pollee) {
         // The lock should be held to avoid data races
         let backlog_sockets = self.backlog_sockets.read();
@@ -105,18 +125,23 @@ struct BacklogSocket {
 impl BacklogSocket {
     // FIXME: All of the error codes below seem to have no Linux equivalents, and I see no reason
     // why the error may occur. Perhaps it is better to call `unwrap()` directly?
-    fn new(bound_socket: &BoundTcpSocket) -> Result<Self> {
-        let local_endpoint = bound_socket.local_endpoint().ok_or(Error::with_message(
-            Errno::EINVAL,
-            "the socket is not bound",
-        ))?;
-
-        let unbound_socket = Box::new(UnboundTcpSocket::new(bound_socket.observer()));
+    fn new(bound_socket: &BoundTcpSocket, options: &OptionSet) -> Result<Self> {
+        let local_endpoint = bound_socket.local_endpoint();
+
+        let unbound_socket = {
+            let mut unbound_socket = UnboundTcpSocket::new_with_buf_len(
+                bound_socket.observer(),
+                options.recv_buf_len(),
+                options.send_buf_len(),
+            );
+            unbound_socket.set_raw_option(&options.raw_tcp_option());
+            Box::new(unbound_socket)
+        };
         let bound_socket = {
             let iface = bound_socket.iface();
             let bind_port_config = BindPortConfig::new(local_endpoint.port, true);
             iface
-                .bind_tcp(unbound_socket, bind_port_config)
+                .bind_tcp(unbound_socket, local_endpoint.addr, bind_port_config)
                 .map_err(|(err, _)| err)?
         };
 
@@ -1,17 +1,25 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
-use aster_bigtcp::{socket::SocketEventObserver, wire::IpEndpoint};
+use aster_bigtcp::{
+    socket::{KeepAlive, RawTcpOption, SocketEventObserver},
+    wire::IpEndpoint,
+};
 use connected::ConnectedStream;
 use connecting::ConnectingStream;
 use init::InitStream;
 use listen::ListenStream;
-use options::{Congestion, MaxSegment, NoDelay, WindowClamp};
+use options::{
+    Congestion, Cork, Info, KeepCount, KeepIdle, KeepInterval, MaxSegment, NoDelay, WindowClamp,
+};
 use takeable::Takeable;
-use util::{TcpOptionSet, DEFAULT_MAXSEG};
+use util::{TcpOptionSet, DEFAULT_MAXSEG, MAX_KEEPCNT, MAX_KEEPIDLE, MAX_KEEPINTVL};
 
-use super::UNSPECIFIED_LOCAL_ENDPOINT;
+use super::{options::IpOptionSet, IpFamily};
 use crate::{
     events::{IoEvents, Observer},
     fs::{file_handle::FileLike, utils::StatusFlags},
@@ -40,9 +48,10 @@ pub mod options;
 mod util;
 
 use self::connecting::NonConnectedStream;
-pub use self::util::CongestionControl;
+pub use self::util::{CTcpState, CongestionControl, TcpInfo};
 
 pub struct StreamSocket {
+    family: IpFamily,
     options: RwLock<OptionSet>,
     state: RwLock<Takeable<State>>,
     is_nonblocking: AtomicBool,
@@ -63,24 +72,49 @@ enum State {
 #[derive(Debug, Clone)]
 struct OptionSet {
     socket: SocketOptionSet,
+    ip: IpOptionSet,
     tcp: TcpOptionSet,
 }
 
 impl OptionSet {
     fn new() -> Self {
         let socket = SocketOptionSet::new_tcp();
+        let ip = IpOptionSet::new();
         let tcp = TcpOptionSet::new();
-        OptionSet { socket, tcp }
+        OptionSet { socket, ip, tcp }
+    }
+
+    fn recv_buf_len(&self) -> usize {
+        self.socket.recv_buf() as usize
+    }
+
+    fn send_buf_len(&self) -> usize {
+        self.socket.send_buf() as usize
+    }
+
+    fn raw_tcp_option(&self) -> RawTcpOption {
+        let keep_alive = self.socket.keep_alive().then(|| KeepAlive {
+            idle: Duration::from_secs(self.tcp.keep_idle() as u64),
+            interval: Duration::from_secs(self.tcp.keep_intvl() as u64),
+            count: self.tcp.keep_cnt(),
+        });
+
+        RawTcpOption {
+            is_nagle_enabled: !self.tcp.no_delay(),
+            is_corked: self.tcp.cork(),
+            keep_alive,
+        }
     }
 }
 
 impl StreamSocket {
-    pub fn new(nonblocking: bool) -> Arc<Self> {
+    pub fn new(family: IpFamily, nonblocking: bool) -> Arc<Self> {
         Arc::new_cyclic(|me| {
             let init_stream = InitStream::new(me.clone() as _);
             let pollee = Pollee::new(IoEvents::empty());
             init_stream.init_pollee(&pollee);
             Self {
+                family,
                 options: RwLock::new(OptionSet::new()),
                 state: RwLock::new(Takeable::new(State::Init(init_stream))),
                 is_nonblocking: AtomicBool::new(nonblocking),
@@ -89,13 +123,18 @@ impl StreamSocket {
         })
     }
 
-    fn new_connected(connected_stream: ConnectedStream) -> Arc<Self> {
+    fn new_connected(
+        family: IpFamily,
+        connected_stream: ConnectedStream,
+        options: OptionSet,
+    ) -> Arc<Self> {
         Arc::new_cyclic(move |me| {
             let pollee = Pollee::new(IoEvents::empty());
             connected_stream.set_observer(me.clone() as _);
             connected_stream.init_pollee(&pollee);
             Self {
-                options: RwLock::new(OptionSet::new()),
+                family,
+                options: RwLock::new(options),
                 state: RwLock::new(Takeable::new(State::Connected(connected_stream))),
                 is_nonblocking: AtomicBool::new(false),
                 pollee,
@@ -103,6 +142,11 @@ impl StreamSocket {
         })
     }
 
+    fn endpoint_from(&self, socket_addr: SocketAddr) -> Result<IpEndpoint> {
+        let v6_only = self.options.read().ip.v6_only();
+        self.family.endpoint_from(socket_addr, v6_only)
+    }
+
     fn is_nonblocking(&self) -> bool {
         self.is_nonblocking.load(Ordering::Relaxed)
     }
@@ -224,18 +268,27 @@ impl StreamSocket {
     }
 
     fn try_accept(&self) -> Result<(Arc<dyn FileLike>, SocketAddr)> {
+        // Hold the lock in advance to avoid deadlocks.
+        let options = self.options.read();
         let state = self.state.read();
 
         let State::Listen(listen_stream) = state.as_ref() else {
             return_errno_with_message!(Errno::EINVAL, "the socket is not listening");
         };
 
-        listen_stream.try_accept().map(|connected_stream| {
+        listen_stream.try_accept(&options).map(|connected_stream| {
             listen_stream.update_io_events(&self.pollee);
 
+            // The accepted socket inherits the options of the listening socket.
+            let mut options = options.clone();
+            options.socket.set_sock_errors(None);
+
             let remote_endpoint = connected_stream.remote_endpoint();
-            let accepted_socket = Self::new_connected(connected_stream);
-            (accepted_socket as _, remote_endpoint.into())
+            let accepted_socket = Self::new_connected(self.family, connected_stream, options);
+            (
+                accepted_socket as _,
+                self.family.socket_addr_from(remote_endpoint),
+            )
         })
     }
 
@@ -258,7 +311,7 @@ impl StreamSocket {
 
         let received = connected_stream.try_recv(writer, flags).map(|recv_bytes| {
             let remote_endpoint = connected_stream.remote_endpoint();
-            (recv_bytes, remote_endpoint.into())
+            (recv_bytes, self.family.socket_addr_from(remote_endpoint))
         });
 
         drop(state);
@@ -272,11 +325,32 @@ impl StreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, SocketAddr)> {
-        if self.is_nonblocking() {
-            self.try_recv(writer, flags)
-        } else {
-            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            return self.try_recv(writer, flags);
         }
+
+        let timeout = self.options.read().socket.recv_timeout();
+        let (mut recv_bytes, remote_addr) =
+            self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || {
+                self.try_recv(writer, flags)
+            })?;
+
+        if !flags.contains(SendRecvFlags::MSG_WAITALL) || flags.contains(SendRecvFlags::MSG_PEEK) {
+            return Ok((recv_bytes, remote_addr));
+        }
+
+        // With `MSG_WAITALL`, keep receiving until the buffer is full, the connection is closed,
+        // or an error occurs. The error is not reported since some data has been received.
+        while recv_bytes > 0 && !writer.is_empty() {
+            match self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || {
+                self.try_recv(writer, flags)
+            }) {
+                Ok((0, _)) | Err(_) => break,
+                Ok((new_bytes, _)) => recv_bytes += new_bytes,
+            }
+        }
+
+        Ok((recv_bytes, remote_addr))
     }
 
     fn try_send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
@@ -285,7 +359,6 @@ impl StreamSocket {
         let connected_stream = match state.as_ref() {
             State::Connected(connected_stream) => connected_stream,
             State::Init(_) | State::Listen(_) => {
-                // TODO: Trigger `SIGPIPE` if `MSG_NOSIGNAL` is not specified
                 return_errno_with_message!(Errno::EPIPE, "the socket is not connected");
             }
             State::Connecting(_) => {
@@ -304,10 +377,110 @@ impl StreamSocket {
     }
 
     fn send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
-        if self.is_nonblocking() {
+        let result = if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
             self.try_send(reader, flags)
         } else {
-            self.wait_events(IoEvents::OUT, || self.try_send(reader, flags))
+            let timeout = self.options.read().socket.send_timeout();
+            self.wait_events_timeout(IoEvents::OUT, timeout.as_ref(), || {
+                self.try_send(reader, flags)
+            })
+        };
+
+        flags.check_sigpipe(result)
+    }
+
+    /// Updates the option values in the option set.
+    fn update_options(&self, options: &mut OptionSet, option: &dyn SocketOption) -> Result<()> {
+        match options.socket.set_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        let is_bound = !matches!(
+            self.state.read().as_ref(),
+            State::Init(InitStream::Unbound(_))
+        );
+        match options.ip.set_option(self.family, option, is_bound) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        match_sock_option_ref!(option, {
+            tcp_no_delay: NoDelay => {
+                let no_delay = tcp_no_delay.get().unwrap();
+                options.tcp.set_no_delay(*no_delay);
+            },
+            tcp_congestion: Congestion => {
+                let congestion = tcp_congestion.get().unwrap();
+                options.tcp.set_congestion(*congestion);
+            },
+            tcp_maxseg: MaxSegment => {
+                const MIN_MAXSEG: u32 = 536;
+                const MAX_MAXSEG: u32 = 65535;
+
+                let maxseg = tcp_maxseg.get().unwrap();
+                if *maxseg < MIN_MAXSEG || *maxseg > MAX_MAXSEG {
+                    return_errno_with_message!(Errno::EINVAL, "the maximum segment size is out of bounds");
+                }
+                options.tcp.set_maxseg(*maxseg);
+            },
+            tcp_window_clamp: WindowClamp => {
+                let window_clamp = tcp_window_clamp.get().unwrap();
+                let half_recv_buf = (options.socket.recv_buf()) / 2;
+                if *window_clamp <= half_recv_buf {
+                    options.tcp.set_window_clamp(half_recv_buf);
+                } else {
+                    options.tcp.set_window_clamp(*window_clamp);
+                }
+            },
+            tcp_cork: Cork => {
+                let cork = tcp_cork.get().unwrap();
+                options.tcp.set_cork(*cork);
+            },
+            tcp_keep_idle: KeepIdle => {
+                let keep_idle = tcp_keep_idle.get().unwrap();
+                if *keep_idle < 1 || *keep_idle > MAX_KEEPIDLE {
+                    return_errno_with_message!(Errno::EINVAL, "the keep-alive idle time is out of bounds");
+                }
+                options.tcp.set_keep_idle(*keep_idle);
+            },
+            tcp_keep_interval: KeepInterval => {
+                let keep_intvl = tcp_keep_interval.get().unwrap();
+                if *keep_intvl < 1 || *keep_intvl > MAX_KEEPINTVL {
+                    return_errno_with_message!(Errno::EINVAL, "the keep-alive interval is out of bounds");
+                }
+                options.tcp.set_keep_intvl(*keep_intvl);
+            },
+            tcp_keep_count: KeepCount => {
+                let keep_cnt = tcp_keep_count.get().unwrap();
+                if *keep_cnt < 1 || *keep_cnt > MAX_KEEPCNT {
+                    return_errno_with_message!(Errno::EINVAL, "the keep-alive probe count is out of bounds");
+                }
+                options.tcp.set_keep_cnt(*keep_cnt);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
+
+    /// Makes the options take effect on the underlying TCP socket.
+    fn apply_options(&self, options: &OptionSet) {
+        let raw_option = options.raw_tcp_option();
+
+        let mut state = self.state.write();
+        match state.as_mut() {
+            State::Init(InitStream::Unbound(unbound_socket)) => {
+                // The buffer lengths can only be changed before the socket is bound.
+                unbound_socket.set_buf_len(options.recv_buf_len(), options.send_buf_len());
+                unbound_socket.set_raw_option(&raw_option);
+            }
+            State::Init(InitStream::Bound(bound_socket)) => {
+                bound_socket.set_raw_option(&raw_option)
+            }
+            State::Connecting(connecting_stream) => connecting_stream.set_raw_option(&raw_option),
+            State::Connected(connected_stream) => connected_stream.set_raw_option(&raw_option),
+            State::Listen(listen_stream) => listen_stream.set_raw_option(&raw_option),
         }
     }
 
@@ -389,7 +562,7 @@ impl FileLike for StreamSocket {
 
 impl Socket for StreamSocket {
     fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
-        let endpoint = socket_addr.try_into()?;
+        let endpoint = self.endpoint_from(socket_addr)?;
 
         let can_reuse = self.options.read().socket.reuse_addr();
         let mut state = self.state.write();
@@ -417,16 +590,26 @@ impl Socket for StreamSocket {
     }
 
     fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
-        let remote_endpoint = socket_addr.try_into()?;
+        let remote_endpoint = self.endpoint_from(socket_addr)?;
 
         if let Some(result) = self.start_connect(&remote_endpoint) {
             return result;
         }
 
-        self.wait_events(IoEvents::OUT, || self.check_connect())
+        let timeout = self.options.read().socket.send_timeout();
+        self.wait_events_timeout(IoEvents::OUT, timeout.as_ref(), || self.check_connect())
+            .map_err(|err| {
+                if err.error() == Errno::EAGAIN {
+                    Error::with_message(Errno::EINPROGRESS, "the socket is connecting")
+                } else {
+                    err
+                }
+            })
     }
 
     fn listen(&self, backlog: usize) -> Result<()> {
+        // Hold the lock in advance to avoid deadlocks.
+        let options = self.options.read();
         let mut state = self.state.write();
 
         state.borrow_result(|owned_state| {
@@ -446,7 +629,7 @@ impl Socket for StreamSocket {
                 }
             };
 
-            let listen_stream = match init_stream.listen(backlog) {
+            let listen_stream = match init_stream.listen(backlog, &options) {
                 Ok(listen_stream) => listen_stream,
                 Err((err, init_stream)) => {
                     return (State::Init(init_stream), Err(err));
@@ -462,7 +645,8 @@ impl Socket for StreamSocket {
         if self.is_nonblocking() {
             self.try_accept()
         } else {
-            self.wait_events(IoEvents::IN, || self.try_accept())
+            let timeout = self.options.read().socket.recv_timeout();
+            self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || self.try_accept())
         }
     }
 
@@ -480,12 +664,12 @@ impl Socket for StreamSocket {
         let local_endpoint = match state.as_ref() {
             State::Init(init_stream) => init_stream
                 .local_endpoint()
-                .unwrap_or(UNSPECIFIED_LOCAL_ENDPOINT),
+                .unwrap_or(self.family.unspecified_local_endpoint()),
             State::Connecting(connecting_stream) => connecting_stream.local_endpoint(),
             State::Listen(listen_stream) => listen_stream.local_endpoint(),
             State::Connected(connected_stream) => connected_stream.local_endpoint(),
         };
-        Ok(local_endpoint.into())
+        Ok(self.family.socket_addr_from(local_endpoint))
     }
 
     fn peer_addr(&self) -> Result<SocketAddr> {
@@ -497,7 +681,7 @@ impl Socket for StreamSocket {
             State::Connecting(connecting_stream) => connecting_stream.remote_endpoint(),
             State::Connected(connected_stream) => connected_stream.remote_endpoint(),
         };
-        Ok(remote_endpoint.into())
+        Ok(self.family.socket_addr_from(remote_endpoint))
     }
 
     fn sendmsg(
@@ -506,8 +690,9 @@ impl Socket for StreamSocket {
         message_header: MessageHeader,
         flags: SendRecvFlags,
     ) -> Result<usize> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
         let MessageHeader {
             control_message, ..
@@ -530,8 +715,9 @@ impl Socket for StreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, MessageHeader)> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
         let (received_bytes, _) = self.recv(writer, flags)?;
 
@@ -560,6 +746,11 @@ impl Socket for StreamSocket {
             res => return res.map(|_| ()),
         }
 
+        match options.ip.get_option(self.family, option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
         match_sock_option_mut!(option, {
             tcp_no_delay: NoDelay => {
                 let no_delay = options.tcp.no_delay();
@@ -584,6 +775,31 @@ impl Socket for StreamSocket {
                 let window_clamp = options.tcp.window_clamp();
                 tcp_window_clamp.set(window_clamp);
             },
+            tcp_cork: Cork => {
+                let cork = options.tcp.cork();
+                tcp_cork.set(cork);
+            },
+            tcp_keep_idle: KeepIdle => {
+                let keep_idle = options.tcp.keep_idle();
+                tcp_keep_idle.set(keep_idle);
+            },
+            tcp_keep_interval: KeepInterval => {
+                let keep_intvl = options.tcp.keep_intvl();
+                tcp_keep_interval.set(keep_intvl);
+            },
+            tcp_keep_count: KeepCount => {
+                let keep_cnt = options.tcp.keep_cnt();
+                tcp_keep_count.set(keep_cnt);
+            },
+            tcp_info: Info => {
+                let info = match self.state.read().as_ref() {
+                    State::Init(_) => TcpInfo::new(CTcpState::Close),
+                    State::Listen(_) => TcpInfo::new(CTcpState::Listen),
+                    State::Connecting(connecting_stream) => connecting_stream.tcp_info(DEFAULT_MAXSEG),
+                    State::Connected(connected_stream) => connected_stream.tcp_info(options.tcp.maxseg()),
+                };
+                tcp_info.set(info);
+            },
             _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
         });
 
@@ -593,43 +809,8 @@ impl Socket for StreamSocket {
     fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
         let mut options = self.options.write();
 
-        match options.socket.set_option(option) {
-            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
-            res => return res,
-        }
-
-        // FIXME: here we have only set the value of the option, without actually
-        // making any real modifications.
-        match_sock_option_ref!(option, {
-            tcp_no_delay: NoDelay => {
-                let no_delay = tcp_no_delay.get().unwrap();
-                options.tcp.set_no_delay(*no_delay);
-            },
-            tcp_congestion: Congestion => {
-                let congestion = tcp_congestion.get().unwrap();
-                options.tcp.set_congestion(*congestion);
-            },
-            tcp_maxseg: MaxSegment => {
-                const MIN_MAXSEG: u32 = 536;
-                const MAX_MAXSEG: u32 = 65535;
-
-                let maxseg = tcp_maxseg.get().unwrap();
-                if *maxseg < MIN_MAXSEG || *maxseg > MAX_MAXSEG {
-                    return_errno_with_message!(Errno::EINVAL, "the maximum segment size is out of bounds");
-                }
-                options.tcp.set_maxseg(*maxseg);
-            },
-            tcp_window_clamp: WindowClamp => {
-                let window_clamp = tcp_window_clamp.get().unwrap();
-                let half_recv_buf = (options.socket.recv_buf()) / 2;
-                if *window_clamp <= half_recv_buf {
-                    options.tcp.set_window_clamp(half_recv_buf);
-                } else {
-                    options.tcp.set_window_clamp(*window_clamp);
-                }
-            },
-            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
-        });
+        self.update_options(&mut options, option)?;
+        self.apply_options(&options);
 
         Ok(())
     }
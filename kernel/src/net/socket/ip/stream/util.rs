@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use aster_bigtcp::socket::{RawTcpSocket, RawTcpState};
+
 use crate::prelude::*;
 
 #[derive(Debug, Clone, Copy, CopyGetters, Setters)]
@@ -7,21 +9,37 @@ use crate::prelude::*;
 #[set = "pub"]
 pub struct TcpOptionSet {
     no_delay: bool,
+    cork: bool,
     congestion: CongestionControl,
     maxseg: u32,
     window_clamp: u32,
+    keep_idle: u32,
+    keep_intvl: u32,
+    keep_cnt: u32,
 }
 
 pub const DEFAULT_MAXSEG: u32 = 536;
 pub const DEFAULT_WINDOW_CLAMP: u32 = 0x8000_0000;
 
+// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/net/tcp.h#L146>
+pub const DEFAULT_KEEPIDLE: u32 = 7200;
+pub const DEFAULT_KEEPINTVL: u32 = 75;
+pub const DEFAULT_KEEPCNT: u32 = 9;
+pub const MAX_KEEPIDLE: u32 = 32767;
+pub const MAX_KEEPINTVL: u32 = 32767;
+pub const MAX_KEEPCNT: u32 = 127;
+
 impl TcpOptionSet {
     pub fn new() -> Self {
         Self {
             no_delay: false,
+            cork: false,
             congestion: CongestionControl::Reno,
             maxseg: DEFAULT_MAXSEG,
             window_clamp: DEFAULT_WINDOW_CLAMP,
+            keep_idle: DEFAULT_KEEPIDLE,
+            keep_intvl: DEFAULT_KEEPINTVL,
+            keep_cnt: DEFAULT_KEEPCNT,
         }
     }
 }
@@ -59,3 +77,139 @@ impl CongestionControl {
         }
     }
 }
+
+/// TCP connection information.
+///
+/// This is the kernel counterpart of `struct tcp_info` in Linux. Currently, only a subset of the
+/// fields is filled in, and the rest are left as zeros.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/tcp.h#L214>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct TcpInfo {
+    pub state: u8,
+    pub ca_state: u8,
+    pub retransmits: u8,
+    pub probes: u8,
+    pub backoff: u8,
+    pub options: u8,
+    /// The `snd_wscale` (the lower 4 bits) and `rcv_wscale` (the higher 4 bits) fields.
+    pub wscale: u8,
+    /// The `delivery_rate_app_limited` (the lowest bit) and `fastopen_client_fail` (the next 2
+    /// bits) fields.
+    pub app_limited_and_fastopen_fail: u8,
+
+    pub rto: u32,
+    pub ato: u32,
+    pub snd_mss: u32,
+    pub rcv_mss: u32,
+
+    pub unacked: u32,
+    pub sacked: u32,
+    pub lost: u32,
+    pub retrans: u32,
+    pub fackets: u32,
+
+    pub last_data_sent: u32,
+    pub last_ack_sent: u32,
+    pub last_data_recv: u32,
+    pub last_ack_recv: u32,
+
+    pub pmtu: u32,
+    pub rcv_ssthresh: u32,
+    pub rtt: u32,
+    pub rttvar: u32,
+    pub snd_ssthresh: u32,
+    pub snd_cwnd: u32,
+    pub advmss: u32,
+    pub reordering: u32,
+
+    pub rcv_rtt: u32,
+    pub rcv_space: u32,
+
+    pub total_retrans: u32,
+
+    pub pacing_rate: u64,
+    pub max_pacing_rate: u64,
+    pub bytes_acked: u64,
+    pub bytes_received: u64,
+    pub segs_out: u32,
+    pub segs_in: u32,
+
+    pub notsent_bytes: u32,
+    pub min_rtt: u32,
+    pub data_segs_in: u32,
+    pub data_segs_out: u32,
+
+    pub delivery_rate: u64,
+
+    pub busy_time: u64,
+    pub rwnd_limited: u64,
+    pub sndbuf_limited: u64,
+
+    pub delivered: u32,
+    pub delivered_ce: u32,
+
+    pub bytes_sent: u64,
+    pub bytes_retrans: u64,
+    pub dsack_dups: u32,
+    pub reord_seen: u32,
+
+    pub rcv_ooopack: u32,
+
+    pub snd_wnd: u32,
+}
+
+impl TcpInfo {
+    pub(super) fn new(state: CTcpState) -> Self {
+        let mut info = Self::new_zeroed();
+        info.state = state as u8;
+        info
+    }
+
+    pub(super) fn from_raw(socket: &RawTcpSocket, mss: u32) -> Self {
+        let mut info = Self::new(CTcpState::from(socket.state()));
+        info.snd_mss = mss;
+        info.rcv_mss = mss;
+        info.advmss = mss;
+        info.rcv_space = socket.recv_capacity() as u32;
+        info
+    }
+}
+
+/// TCP states as seen by the user space.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/net/tcp_states.h#L12>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy)]
+pub enum CTcpState {
+    Established = 1,
+    SynSent = 2,
+    SynRecv = 3,
+    FinWait1 = 4,
+    FinWait2 = 5,
+    TimeWait = 6,
+    Close = 7,
+    CloseWait = 8,
+    LastAck = 9,
+    Listen = 10,
+    Closing = 11,
+}
+
+impl From<RawTcpState> for CTcpState {
+    fn from(state: RawTcpState) -> Self {
+        match state {
+            RawTcpState::Closed => Self::Close,
+            RawTcpState::Listen => Self::Listen,
+            RawTcpState::SynSent => Self::SynSent,
+            RawTcpState::SynReceived => Self::SynRecv,
+            RawTcpState::Established => Self::Established,
+            RawTcpState::FinWait1 => Self::FinWait1,
+            RawTcpState::FinWait2 => Self::FinWait2,
+            RawTcpState::CloseWait => Self::CloseWait,
+            RawTcpState::Closing => Self::Closing,
+            RawTcpState::LastAck => Self::LastAck,
+            RawTcpState::TimeWait => Self::TimeWait,
+        }
+    }
+}
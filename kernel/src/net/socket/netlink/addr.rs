@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{net::socket::SocketAddr, prelude::*};
+
+/// The socket address of a netlink socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetlinkSocketAddr {
+    /// The port number, which is zero for the kernel.
+    port: u32,
+    /// The bitmask of the multicast groups.
+    groups: u32,
+}
+
+impl NetlinkSocketAddr {
+    /// The address of the kernel.
+    pub const KERNEL: Self = Self::new(0, 0);
+
+    pub const fn new(port: u32, groups: u32) -> Self {
+        Self { port, groups }
+    }
+
+    pub const fn port(&self) -> u32 {
+        self.port
+    }
+
+    pub const fn groups(&self) -> u32 {
+        self.groups
+    }
+}
+
+impl TryFrom<SocketAddr> for NetlinkSocketAddr {
+    type Error = Error;
+
+    fn try_from(value: SocketAddr) -> Result<Self> {
+        let SocketAddr::Netlink(netlink_addr) = value else {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the socket address is not a netlink address"
+            );
+        };
+        Ok(netlink_addr)
+    }
+}
+
+impl From<NetlinkSocketAddr> for SocketAddr {
+    fn from(value: NetlinkSocketAddr) -> Self {
+        SocketAddr::Netlink(value)
+    }
+}
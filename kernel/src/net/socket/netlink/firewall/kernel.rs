@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The kernel side of `NETLINK_FIREWALL`, which handles the requests from user space.
+
+use aster_bigtcp::{
+    iface::{FilterHook, FilterRule, FilterVerdict},
+    wire::IpProtocol,
+};
+
+use super::message::{CFwRuleMsg, FwAttrType, FwHook, FwMessageType, FwVerdict};
+use crate::{
+    net::{
+        iface::{find_iface_by_index, iter_ifaces, Iface},
+        socket::netlink::{
+            kernel::{check_net_admin, family_of, parse_cidr},
+            message::{parse_attrs, parse_struct, CMessageHeader, MessageFlags, MessageWriter},
+        },
+    },
+    prelude::*,
+    util::net::CSocketAddrFamily,
+};
+
+/// Handles a request from the socket bound to `port`.
+///
+/// The reply, if any, is written to `writer`.
+pub(super) fn handle_request(
+    header: &CMessageHeader,
+    payload: &[u8],
+    port: u32,
+    writer: &mut MessageWriter,
+) -> Result<()> {
+    let Ok(type_) = FwMessageType::try_from(header.type_) else {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "the message type is not supported");
+    };
+    let is_dump = header.flags().contains(MessageFlags::DUMP);
+
+    match type_ {
+        FwMessageType::FWM_GETRULE if is_dump => {
+            for (index, iface) in iter_ifaces() {
+                for rule in iface.filter().rules() {
+                    write_rule(writer, header, port, MessageFlags::MULTI, index, &rule);
+                }
+            }
+            writer.write_done(header, port);
+        }
+        FwMessageType::FWM_NEWRULE => {
+            check_net_admin()?;
+            new_rule(header, payload)?;
+        }
+        FwMessageType::FWM_DELRULE => {
+            check_net_admin()?;
+            del_rule(payload)?;
+        }
+        _ => return_errno_with_message!(Errno::EOPNOTSUPP, "the message type is not supported"),
+    }
+
+    Ok(())
+}
+
+fn write_rule(
+    writer: &mut MessageWriter,
+    request: &CMessageHeader,
+    port: u32,
+    flags: MessageFlags,
+    index: u32,
+    rule: &FilterRule,
+) {
+    writer.begin_message(FwMessageType::FWM_NEWRULE as u16, flags, request.seq, port);
+
+    let hook = match rule.hook {
+        FilterHook::Ingress => FwHook::FW_HOOK_INGRESS,
+        FilterHook::Egress => FwHook::FW_HOOK_EGRESS,
+    };
+    let verdict = match rule.verdict {
+        FilterVerdict::Accept => FwVerdict::FW_ACCEPT,
+        FilterVerdict::Drop => FwVerdict::FW_DROP,
+        FilterVerdict::Reject => FwVerdict::FW_REJECT,
+    };
+    let family = rule
+        .src_cidr
+        .or(rule.dst_cidr)
+        .map_or(CSocketAddrFamily::AF_UNSPEC as u8, |cidr| {
+            family_of(&cidr.address())
+        });
+    writer.push_struct(&CFwRuleMsg {
+        family,
+        hook: hook as u8,
+        verdict: verdict as u8,
+        protocol: rule.protocol.map_or(0, u8::from),
+        src_len: rule.src_cidr.map_or(0, |cidr| cidr.prefix_len()),
+        dst_len: rule.dst_cidr.map_or(0, |cidr| cidr.prefix_len()),
+        _pad: 0,
+        index,
+    });
+
+    if let Some(cidr) = rule.src_cidr {
+        writer.push_attr(FwAttrType::FWA_SRC as u16, cidr.address().as_bytes());
+    }
+    if let Some(cidr) = rule.dst_cidr {
+        writer.push_attr(FwAttrType::FWA_DST as u16, cidr.address().as_bytes());
+    }
+    if let Some(src_port) = rule.src_port {
+        writer.push_attr(FwAttrType::FWA_SPORT as u16, &src_port.to_be_bytes());
+    }
+    if let Some(dst_port) = rule.dst_port {
+        writer.push_attr(FwAttrType::FWA_DPORT as u16, &dst_port.to_be_bytes());
+    }
+
+    writer.end_message();
+}
+
+/// Parses the rule in a `FWM_NEWRULE` or `FWM_DELRULE` request.
+///
+/// This method returns the ifaces specified by the request along with the rule.
+fn parse_rule(payload: &[u8]) -> Result<(Vec<Arc<Iface>>, FilterRule)> {
+    if payload.len() < size_of::<CFwRuleMsg>() {
+        return_errno_with_message!(Errno::EINVAL, "the message is too short");
+    }
+    let (rulemsg, attrs) = parse_struct::<CFwRuleMsg>(payload);
+
+    let ifaces = if rulemsg.index == 0 {
+        iter_ifaces().map(|(_, iface)| iface).collect()
+    } else {
+        let iface = find_iface_by_index(rulemsg.index)
+            .ok_or_else(|| Error::with_message(Errno::ENODEV, "the iface does not exist"))?;
+        vec![iface]
+    };
+
+    let hook = match FwHook::try_from(rulemsg.hook) {
+        Ok(FwHook::FW_HOOK_INGRESS) => FilterHook::Ingress,
+        Ok(FwHook::FW_HOOK_EGRESS) => FilterHook::Egress,
+        Err(_) => return_errno_with_message!(Errno::EINVAL, "the hook is not valid"),
+    };
+    let verdict = match FwVerdict::try_from(rulemsg.verdict) {
+        Ok(FwVerdict::FW_ACCEPT) => FilterVerdict::Accept,
+        Ok(FwVerdict::FW_DROP) => FilterVerdict::Drop,
+        Ok(FwVerdict::FW_REJECT) => FilterVerdict::Reject,
+        Err(_) => return_errno_with_message!(Errno::EINVAL, "the verdict is not valid"),
+    };
+
+    let mut src_cidr = None;
+    let mut dst_cidr = None;
+    let mut src_port = None;
+    let mut dst_port = None;
+    for (type_, value) in parse_attrs(attrs) {
+        match FwAttrType::try_from(type_) {
+            Ok(FwAttrType::FWA_SRC) => {
+                src_cidr = Some(parse_cidr(rulemsg.family, value, rulemsg.src_len)?)
+            }
+            Ok(FwAttrType::FWA_DST) => {
+                dst_cidr = Some(parse_cidr(rulemsg.family, value, rulemsg.dst_len)?)
+            }
+            Ok(FwAttrType::FWA_SPORT) if value.len() >= size_of::<u16>() => {
+                src_port = Some(u16::from_be_bytes([value[0], value[1]]))
+            }
+            Ok(FwAttrType::FWA_DPORT) if value.len() >= size_of::<u16>() => {
+                dst_port = Some(u16::from_be_bytes([value[0], value[1]]))
+            }
+            _ => (),
+        }
+    }
+
+    let protocol = (rulemsg.protocol != 0).then(|| IpProtocol::from(rulemsg.protocol));
+    if (src_port.is_some() || dst_port.is_some())
+        && !matches!(protocol, Some(IpProtocol::Tcp | IpProtocol::Udp))
+    {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "the ports can only be specified for TCP or UDP rules"
+        );
+    }
+
+    let rule = FilterRule {
+        hook,
+        protocol,
+        src_cidr,
+        dst_cidr,
+        src_port,
+        dst_port,
+        verdict,
+    };
+
+    Ok((ifaces, rule))
+}
+
+/// Handles a `FWM_NEWRULE` request.
+///
+/// Like `iptables -A` and `iptables -I`, the rule is appended to the chain if `NLM_F_APPEND` is
+/// set, and is inserted at the beginning of the chain otherwise.
+fn new_rule(header: &CMessageHeader, payload: &[u8]) -> Result<()> {
+    let (ifaces, rule) = parse_rule(payload)?;
+
+    if header.flags().contains(MessageFlags::EXCL)
+        && ifaces
+            .iter()
+            .any(|iface| iface.filter().rules().contains(&rule))
+    {
+        return_errno_with_message!(Errno::EEXIST, "the rule already exists");
+    }
+
+    let append = header.flags().contains(MessageFlags::APPEND);
+    for iface in ifaces {
+        if append {
+            iface.filter().append_rule(rule.clone());
+        } else {
+            iface.filter().prepend_rule(rule.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles a `FWM_DELRULE` request.
+///
+/// The first rule that is equal to the specified rule is removed from each specified iface.
+fn del_rule(payload: &[u8]) -> Result<()> {
+    let (ifaces, rule) = parse_rule(payload)?;
+
+    let mut removed = false;
+    for iface in ifaces {
+        removed |= iface.filter().remove_rule(&rule);
+    }
+    if !removed {
+        return_errno_with_message!(Errno::ENOENT, "the rule does not exist");
+    }
+
+    Ok(())
+}
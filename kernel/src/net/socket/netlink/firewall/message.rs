@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The message types and structures of `NETLINK_FIREWALL`.
+
+use crate::prelude::*;
+
+/// Firewall message types.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum FwMessageType {
+    FWM_NEWRULE = 16,
+    FWM_DELRULE = 17,
+    FWM_GETRULE = 18,
+}
+
+/// The header of rule messages.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CFwRuleMsg {
+    /// Address family of the source and destination addresses.
+    pub(super) family: u8,
+    /// The hook in [`FwHook`].
+    pub(super) hook: u8,
+    /// The verdict in [`FwVerdict`].
+    pub(super) verdict: u8,
+    /// The protocol of the IP payload, or zero for any protocol.
+    pub(super) protocol: u8,
+    /// Length of the source prefix.
+    pub(super) src_len: u8,
+    /// Length of the destination prefix.
+    pub(super) dst_len: u8,
+    pub(super) _pad: u16,
+    /// Interface index, or zero for all ifaces.
+    pub(super) index: u32,
+}
+
+/// Filter hooks.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum FwHook {
+    FW_HOOK_INGRESS = 0,
+    FW_HOOK_EGRESS = 1,
+}
+
+/// Filter verdicts.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum FwVerdict {
+    FW_ACCEPT = 0,
+    FW_DROP = 1,
+    FW_REJECT = 2,
+}
+
+/// Rule attribute types.
+///
+/// The ports are 16-bit integers in network byte order.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum FwAttrType {
+    FWA_UNSPEC = 0,
+    FWA_SRC = 1,
+    FWA_DST = 2,
+    FWA_SPORT = 3,
+    FWA_DPORT = 4,
+}
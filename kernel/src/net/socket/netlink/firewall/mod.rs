@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The `NETLINK_FIREWALL` protocol, which allows user-space programs to configure the packet
+//! filters of the ifaces.
+//!
+//! Linux no longer uses this protocol number, so the messages are specific to Asterinas. Each
+//! rule message consists of a `CFwRuleMsg` header followed by the rule attributes.
+
+use super::kernel::NetlinkKernel;
+
+mod kernel;
+mod message;
+
+pub(super) static FIREWALL_KERNEL: NetlinkKernel = NetlinkKernel::new(kernel::handle_request);
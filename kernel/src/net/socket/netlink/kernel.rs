@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The kernel side of netlink protocols, which handles the requests from user space.
+
+use aster_bigtcp::wire::{IpAddress, IpCidr, Ipv4Address, Ipv4Cidr, Ipv6Address, Ipv6Cidr};
+
+use super::{
+    message::{parse_messages, CMessageHeader, MessageFlags, MessageWriter, NLMSG_MIN_TYPE},
+    table::PortTable,
+};
+use crate::{
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::PosixThreadExt},
+    util::net::CSocketAddrFamily,
+};
+
+/// The function that handles a request from the socket bound to a port.
+///
+/// The arguments are the header of the request, the payload of the request, the port, and the
+/// writer of the reply.
+type RequestHandler = fn(&CMessageHeader, &[u8], u32, &mut MessageWriter) -> Result<()>;
+
+/// The kernel side of a netlink protocol.
+pub(super) struct NetlinkKernel {
+    /// The table of the ports bound by the sockets of the protocol.
+    port_table: PortTable,
+    handle_request: RequestHandler,
+}
+
+impl NetlinkKernel {
+    pub(super) const fn new(handle_request: RequestHandler) -> Self {
+        Self {
+            port_table: PortTable::new(),
+            handle_request,
+        }
+    }
+
+    pub(super) fn port_table(&self) -> &PortTable {
+        &self.port_table
+    }
+
+    /// Handles the requests in the buffer sent by the socket bound to `port`.
+    ///
+    /// This method returns the replies, one for each request that needs a reply. Each reply may
+    /// contain multiple netlink messages (e.g., for a dump request).
+    pub(super) fn handle_requests(&self, buf: &[u8], port: u32) -> Vec<Vec<u8>> {
+        let mut replies = Vec::new();
+
+        for (header, payload) in parse_messages(buf) {
+            // Only requests are handled by the kernel.
+            if !header.flags().contains(MessageFlags::REQUEST) {
+                continue;
+            }
+            // Control messages (e.g., `NLMSG_NOOP`) are ignored.
+            if header.type_ < NLMSG_MIN_TYPE {
+                continue;
+            }
+
+            let mut writer = MessageWriter::new();
+            match (self.handle_request)(&header, payload, port, &mut writer) {
+                Ok(()) => {
+                    if header.flags().contains(MessageFlags::ACK) {
+                        writer.write_error(&header, port, None);
+                    }
+                }
+                Err(err) => {
+                    // Discard the partial reply, if any.
+                    writer = MessageWriter::new();
+                    writer.write_error(&header, port, Some(err.error()));
+                }
+            }
+
+            if !writer.is_empty() {
+                replies.push(writer.into_bytes());
+            }
+        }
+
+        replies
+    }
+}
+
+/// Checks whether the current thread has the `CAP_NET_ADMIN` capability, which is required to
+/// change the network configuration.
+pub(super) fn check_net_admin() -> Result<()> {
+    let credentials = current_thread!().as_posix_thread().unwrap().credentials();
+    if !credentials.effective_capset().contains(CapSet::NET_ADMIN) {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "CAP_NET_ADMIN is required to change the network configuration"
+        );
+    }
+    Ok(())
+}
+
+/// Parses an IP address and its prefix length in the specified address family.
+pub(super) fn parse_cidr(family: u8, addr_bytes: &[u8], prefix_len: u8) -> Result<IpCidr> {
+    let cidr = match CSocketAddrFamily::try_from(family as i32) {
+        Ok(CSocketAddrFamily::AF_INET) if addr_bytes.len() == 4 && prefix_len <= 32 => {
+            IpCidr::Ipv4(Ipv4Cidr::new(
+                Ipv4Address::from_bytes(addr_bytes),
+                prefix_len,
+            ))
+        }
+        Ok(CSocketAddrFamily::AF_INET6) if addr_bytes.len() == 16 && prefix_len <= 128 => {
+            IpCidr::Ipv6(Ipv6Cidr::new(
+                Ipv6Address::from_bytes(addr_bytes),
+                prefix_len,
+            ))
+        }
+        Ok(CSocketAddrFamily::AF_INET | CSocketAddrFamily::AF_INET6) => {
+            return_errno_with_message!(Errno::EINVAL, "the address is not valid")
+        }
+        _ => return_errno_with_message!(Errno::EAFNOSUPPORT, "the address family is not supported"),
+    };
+
+    Ok(cidr)
+}
+
+/// Returns the address family of `addr`.
+pub(super) fn family_of(addr: &IpAddress) -> u8 {
+    let family = match addr {
+        IpAddress::Ipv4(_) => CSocketAddrFamily::AF_INET,
+        IpAddress::Ipv6(_) => CSocketAddrFamily::AF_INET6,
+    };
+    family as u8
+}
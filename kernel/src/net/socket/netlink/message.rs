@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Netlink messages.
+//!
+//! A netlink message consists of a [`CMessageHeader`] followed by the payload. Multiple messages
+//! can be packed into a single buffer, with each message aligned to [`MESSAGE_ALIGN`] bytes.
+//!
+//! For more details, see <https://docs.kernel.org/userspace-api/netlink/intro.html>.
+
+use crate::prelude::*;
+
+/// The alignment of netlink messages and attributes.
+pub(super) const MESSAGE_ALIGN: usize = 4;
+
+/// Rounds up the length to the alignment of netlink messages and attributes.
+pub(super) const fn align_up(len: usize) -> usize {
+    (len + MESSAGE_ALIGN - 1) & !(MESSAGE_ALIGN - 1)
+}
+
+/// The header of netlink messages.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L52>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CMessageHeader {
+    /// Length of message including header.
+    pub(super) len: u32,
+    /// Message content type.
+    pub(super) type_: u16,
+    /// Additional flags.
+    pub(super) flags: u16,
+    /// Sequence number.
+    pub(super) seq: u32,
+    /// Sending process port ID.
+    pub(super) pid: u32,
+}
+
+impl CMessageHeader {
+    pub(super) fn flags(&self) -> MessageFlags {
+        MessageFlags::from_bits_truncate(self.flags)
+    }
+}
+
+bitflags! {
+    /// Flags of netlink messages.
+    ///
+    /// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L54>.
+    pub(super) struct MessageFlags: u16 {
+        /// It is request message.
+        const REQUEST = 0x01;
+        /// Multipart message, terminated by `NLMSG_DONE`.
+        const MULTI = 0x02;
+        /// Reply with ack, with zero or error code.
+        const ACK = 0x04;
+        /// Echo this request.
+        const ECHO = 0x08;
+        /// Dump was inconsistent due to sequence change.
+        const DUMP_INTR = 0x10;
+        /// Dump was filtered as requested.
+        const DUMP_FILTERED = 0x20;
+
+        // Modifiers to GET requests.
+
+        /// Specify tree root.
+        const ROOT = 0x100;
+        /// Return all matching.
+        const MATCH = 0x200;
+        /// Atomic GET.
+        const ATOMIC = 0x400;
+        const DUMP = Self::ROOT.bits | Self::MATCH.bits;
+
+        // Modifiers to NEW requests.
+
+        /// Override existing.
+        const REPLACE = 0x100;
+        /// Do not touch, if it exists.
+        const EXCL = 0x200;
+        /// Create, if it does not exist.
+        const CREATE = 0x400;
+        /// Add to end of list.
+        const APPEND = 0x800;
+    }
+}
+
+/// The standard message types.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L112>.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+#[allow(dead_code)]
+pub(super) enum StandardMessageType {
+    /// Nothing.
+    NLMSG_NOOP = 1,
+    /// Error.
+    NLMSG_ERROR = 2,
+    /// End of a dump.
+    NLMSG_DONE = 3,
+    /// Data lost.
+    NLMSG_OVERRUN = 4,
+}
+
+/// The message types below this value are reserved for control messages.
+pub(super) const NLMSG_MIN_TYPE: u16 = 0x10;
+
+/// The payload of `NLMSG_ERROR` messages.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L119>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CErrorMessage {
+    /// Negative errno or zero for acknowledgements.
+    error: i32,
+    /// The header of the message that caused the error.
+    msg: CMessageHeader,
+}
+
+/// The header of netlink attributes.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L229>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CAttrHeader {
+    len: u16,
+    type_: u16,
+}
+
+/// Parses the netlink messages in the buffer.
+///
+/// This method returns an iterator over the headers of the messages and their payloads. Like
+/// Linux, the iteration stops silently at the first malformed message.
+pub(super) fn parse_messages(mut buf: &[u8]) -> impl Iterator<Item = (CMessageHeader, &[u8])> {
+    core::iter::from_fn(move || {
+        if buf.len() < size_of::<CMessageHeader>() {
+            return None;
+        }
+
+        let header = CMessageHeader::from_bytes(buf);
+        let len = header.len as usize;
+        if len < size_of::<CMessageHeader>() || len > buf.len() {
+            return None;
+        }
+
+        let payload = &buf[size_of::<CMessageHeader>()..len];
+        buf = &buf[align_up(len).min(buf.len())..];
+
+        Some((header, payload))
+    })
+}
+
+/// Parses a fixed-size structure at the beginning of the payload.
+///
+/// This method returns the structure and the remaining bytes. If the payload is too short, the
+/// missing bytes of the structure are filled with zeros.
+pub(super) fn parse_struct<T: Pod>(payload: &[u8]) -> (T, &[u8]) {
+    let mut value = T::new_zeroed();
+
+    let len = payload.len().min(size_of::<T>());
+    value.as_bytes_mut()[..len].copy_from_slice(&payload[..len]);
+
+    let rest = &payload[align_up(size_of::<T>()).min(payload.len())..];
+    (value, rest)
+}
+
+/// Parses the netlink attributes in the buffer.
+///
+/// This method returns an iterator over the types of the attributes and their payloads. The
+/// iteration stops silently at the first malformed attribute.
+pub(super) fn parse_attrs(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    core::iter::from_fn(move || {
+        if buf.len() < size_of::<CAttrHeader>() {
+            return None;
+        }
+
+        let header = CAttrHeader::from_bytes(buf);
+        let len = header.len as usize;
+        if len < size_of::<CAttrHeader>() || len > buf.len() {
+            return None;
+        }
+
+        let payload = &buf[size_of::<CAttrHeader>()..len];
+        buf = &buf[align_up(len).min(buf.len())..];
+
+        // The upper two bits are the `NLA_F_NESTED` and `NLA_F_NET_BYTEORDER` flags.
+        Some((header.type_ & 0x3fff, payload))
+    })
+}
+
+/// A writer that builds netlink messages in a buffer.
+pub(super) struct MessageWriter {
+    buf: Vec<u8>,
+    /// The start position of the message that is being built.
+    msg_start: usize,
+}
+
+impl MessageWriter {
+    pub(super) fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            msg_start: 0,
+        }
+    }
+
+    /// Starts a new message.
+    ///
+    /// The length of the message will be filled in by [`Self::end_message`].
+    pub(super) fn begin_message(&mut self, type_: u16, flags: MessageFlags, seq: u32, pid: u32) {
+        self.msg_start = self.buf.len();
+
+        let header = CMessageHeader {
+            len: 0,
+            type_,
+            flags: flags.bits(),
+            seq,
+            pid,
+        };
+        self.push_struct(&header);
+    }
+
+    /// Ends the message that is being built.
+    pub(super) fn end_message(&mut self) {
+        let len = (self.buf.len() - self.msg_start) as u32;
+        self.buf[self.msg_start..self.msg_start + size_of::<u32>()]
+            .copy_from_slice(&len.to_ne_bytes());
+    }
+
+    /// Appends a fixed-size structure to the message that is being built.
+    pub(super) fn push_struct<T: Pod>(&mut self, value: &T) {
+        self.push_bytes(value.as_bytes());
+    }
+
+    /// Appends an attribute to the message that is being built.
+    pub(super) fn push_attr(&mut self, type_: u16, payload: &[u8]) {
+        let header = CAttrHeader {
+            len: (size_of::<CAttrHeader>() + payload.len()) as u16,
+            type_,
+        };
+        self.push_struct(&header);
+        self.push_bytes(payload);
+    }
+
+    /// Appends a string attribute, which includes the trailing NUL, to the message that is being
+    /// built.
+    pub(super) fn push_str_attr(&mut self, type_: u16, payload: &str) {
+        let mut bytes = Vec::with_capacity(payload.len() + 1);
+        bytes.extend_from_slice(payload.as_bytes());
+        bytes.push(0);
+        self.push_attr(type_, &bytes);
+    }
+
+    /// Writes a complete `NLMSG_DONE` message, which terminates a dump.
+    pub(super) fn write_done(&mut self, request: &CMessageHeader, pid: u32) {
+        self.begin_message(
+            StandardMessageType::NLMSG_DONE as u16,
+            MessageFlags::MULTI,
+            request.seq,
+            pid,
+        );
+        self.push_struct(&0i32);
+        self.end_message();
+    }
+
+    /// Writes a complete `NLMSG_ERROR` message, which reports an error or acknowledges the
+    /// request if `error` is `None`.
+    pub(super) fn write_error(&mut self, request: &CMessageHeader, pid: u32, error: Option<Errno>) {
+        self.begin_message(
+            StandardMessageType::NLMSG_ERROR as u16,
+            MessageFlags::empty(),
+            request.seq,
+            pid,
+        );
+        self.push_struct(&CErrorMessage {
+            error: error.map_or(0, |errno| -(errno as i32)),
+            msg: *request,
+        });
+        self.end_message();
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub(super) fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+        self.buf.resize(align_up(self.buf.len()), 0);
+    }
+}
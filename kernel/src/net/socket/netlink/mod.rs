@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Netlink sockets.
+//!
+//! Netlink is used to transfer information between the kernel and user-space processes.
+//! Currently, the following protocols are supported:
+//!  - `NETLINK_ROUTE`, which allows user-space programs (e.g., `ip`) to query and configure
+//!    ifaces;
+//!  - `NETLINK_FIREWALL`, which allows user-space programs to configure the packet filters of
+//!    the ifaces.
+//!
+//! See <https://man7.org/linux/man-pages/man7/netlink.7.html>.
+
+mod addr;
+mod firewall;
+mod kernel;
+mod message;
+mod route;
+mod socket;
+mod table;
+
+pub use addr::NetlinkSocketAddr;
+pub use socket::NetlinkSocket;
+
+/// Netlink protocols.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/netlink.h#L9>.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum NetlinkProtocol {
+    /// Routing/device hook
+    NETLINK_ROUTE = 0,
+    /// Unused number
+    NETLINK_UNUSED = 1,
+    /// Reserved for user mode socket protocols
+    NETLINK_USERSOCK = 2,
+    /// Unused number, formerly ip_queue
+    NETLINK_FIREWALL = 3,
+    /// socket monitoring
+    NETLINK_SOCK_DIAG = 4,
+    /// netfilter/iptables ULOG
+    NETLINK_NFLOG = 5,
+    /// ipsec
+    NETLINK_XFRM = 6,
+    /// SELinux event notifications
+    NETLINK_SELINUX = 7,
+    /// Open-iSCSI
+    NETLINK_ISCSI = 8,
+    /// auditing
+    NETLINK_AUDIT = 9,
+    NETLINK_FIB_LOOKUP = 10,
+    NETLINK_CONNECTOR = 11,
+    /// netfilter subsystem
+    NETLINK_NETFILTER = 12,
+    NETLINK_IP6_FW = 13,
+    /// DECnet routing messages
+    NETLINK_DNRTMSG = 14,
+    /// Kernel messages to userspace
+    NETLINK_KOBJECT_UEVENT = 15,
+    NETLINK_GENERIC = 16,
+    /// SCSI Transports
+    NETLINK_SCSITRANSPORT = 18,
+    NETLINK_ECRYPTFS = 19,
+    NETLINK_RDMA = 20,
+    /// Crypto layer
+    NETLINK_CRYPTO = 21,
+    /// SMC monitoring
+    NETLINK_SMC = 22,
+}
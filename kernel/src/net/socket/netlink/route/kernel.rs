@@ -0,0 +1,400 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The kernel side of `NETLINK_ROUTE`, which handles the requests from user space.
+
+use aster_bigtcp::wire::{HardwareAddress, IpAddress, IpCidr};
+
+use super::message::{
+    AddrAttrType, CIfaddrMsg, CIfinfoMsg, CRtMsg, LinkAttrType, RouteAttrType, RouteMessageType,
+    RouteProtocol, Scope, IFA_F_PERMANENT, RTN_UNICAST, RT_TABLE_MAIN,
+};
+use crate::{
+    net::{
+        iface::{
+            add_route, all_routes, find_iface_by_index, find_iface_by_name, iter_ifaces,
+            lookup_route, remove_route, set_iface_cidr, Iface, IfaceEx, IfaceFlags, LinkType,
+            Route,
+        },
+        socket::netlink::{
+            kernel::{check_net_admin, family_of, parse_cidr},
+            message::{parse_attrs, parse_struct, CMessageHeader, MessageFlags, MessageWriter},
+        },
+    },
+    prelude::*,
+    util::net::CSocketAddrFamily,
+};
+
+/// Handles a request from the socket bound to `port`.
+///
+/// The reply, if any, is written to `writer`.
+pub(super) fn handle_request(
+    header: &CMessageHeader,
+    payload: &[u8],
+    port: u32,
+    writer: &mut MessageWriter,
+) -> Result<()> {
+    let Ok(type_) = RouteMessageType::try_from(header.type_) else {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "the message type is not supported");
+    };
+    let is_dump = header.flags().contains(MessageFlags::DUMP);
+
+    match type_ {
+        RouteMessageType::RTM_GETLINK if is_dump => {
+            for (index, iface) in iter_ifaces() {
+                let flags = MessageFlags::MULTI;
+                write_link(writer, header, port, flags, index, &iface);
+            }
+            writer.write_done(header, port);
+        }
+        RouteMessageType::RTM_GETLINK => {
+            let (index, iface) = find_link(payload)?;
+            write_link(writer, header, port, MessageFlags::empty(), index, &iface);
+        }
+        RouteMessageType::RTM_GETADDR if is_dump => {
+            let (ifaddr, _) = parse_struct::<CIfaddrMsg>(payload);
+            for (index, iface) in iter_ifaces() {
+                let flags = MessageFlags::MULTI;
+                for cidr in iface_cidrs(&iface) {
+                    if ifaddr.family == CSocketAddrFamily::AF_UNSPEC as u8
+                        || ifaddr.family == family_of(&cidr.address())
+                    {
+                        write_addr(writer, header, port, flags, index, &iface, &cidr);
+                    }
+                }
+            }
+            writer.write_done(header, port);
+        }
+        RouteMessageType::RTM_NEWADDR => {
+            check_net_admin()?;
+            new_addr(header, payload)?;
+        }
+        RouteMessageType::RTM_GETROUTE if is_dump => {
+            let (rtmsg, _) = parse_struct::<CRtMsg>(payload);
+            for route in all_routes() {
+                if rtmsg.family == CSocketAddrFamily::AF_UNSPEC as u8
+                    || rtmsg.family == family_of(&route.cidr().address())
+                {
+                    write_route(writer, header, port, MessageFlags::MULTI, &route);
+                }
+            }
+            writer.write_done(header, port);
+        }
+        RouteMessageType::RTM_GETROUTE => {
+            let RouteAttrs { cidr, .. } = parse_route(payload)?;
+            let route = lookup_route(&cidr.address()).ok_or_else(|| {
+                Error::with_message(Errno::ENETUNREACH, "the destination is not reachable")
+            })?;
+            write_route(writer, header, port, MessageFlags::empty(), &route);
+        }
+        RouteMessageType::RTM_NEWROUTE => {
+            check_net_admin()?;
+            new_route(header, payload)?;
+        }
+        RouteMessageType::RTM_DELROUTE => {
+            check_net_admin()?;
+            del_route(payload)?;
+        }
+        // TODO: Support other message types.
+        _ => return_errno_with_message!(Errno::EOPNOTSUPP, "the message type is not supported"),
+    }
+
+    Ok(())
+}
+
+/// Finds the iface specified by the index or the name in a `RTM_GETLINK` request.
+fn find_link(payload: &[u8]) -> Result<(u32, Arc<Iface>)> {
+    let (ifinfo, attrs) = parse_struct::<CIfinfoMsg>(payload);
+
+    if ifinfo.index > 0 {
+        let index = ifinfo.index as u32;
+        if let Some(iface) = find_iface_by_index(index) {
+            return Ok((index, iface));
+        }
+        return_errno_with_message!(Errno::ENODEV, "the iface does not exist");
+    }
+
+    for (type_, value) in parse_attrs(attrs) {
+        if type_ != LinkAttrType::IFLA_IFNAME as u16 {
+            continue;
+        }
+        let name = value.split(|&ch| ch == 0).next().unwrap();
+        if let Some(found) = core::str::from_utf8(name).ok().and_then(find_iface_by_name) {
+            return Ok(found);
+        }
+        return_errno_with_message!(Errno::ENODEV, "the iface does not exist");
+    }
+
+    return_errno_with_message!(Errno::EINVAL, "the iface is not specified");
+}
+
+fn write_link(
+    writer: &mut MessageWriter,
+    request: &CMessageHeader,
+    port: u32,
+    flags: MessageFlags,
+    index: u32,
+    iface: &Iface,
+) {
+    writer.begin_message(
+        RouteMessageType::RTM_NEWLINK as u16,
+        flags,
+        request.seq,
+        port,
+    );
+
+    writer.push_struct(&CIfinfoMsg {
+        family: CSocketAddrFamily::AF_UNSPEC as u8,
+        _pad: 0,
+        type_: LinkType::of(iface) as u16,
+        index: index as i32,
+        flags: IfaceFlags::of(iface).bits(),
+        change: 0,
+    });
+
+    writer.push_str_attr(LinkAttrType::IFLA_IFNAME as u16, iface.name());
+    writer.push_attr(
+        LinkAttrType::IFLA_MTU as u16,
+        &(iface.mtu() as u32).to_ne_bytes(),
+    );
+    if let HardwareAddress::Ethernet(ether_addr) = iface.hardware_addr() {
+        writer.push_attr(LinkAttrType::IFLA_ADDRESS as u16, ether_addr.as_bytes());
+        writer.push_attr(LinkAttrType::IFLA_BROADCAST as u16, &[0xff; 6]);
+    }
+
+    writer.end_message();
+}
+
+fn write_addr(
+    writer: &mut MessageWriter,
+    request: &CMessageHeader,
+    port: u32,
+    flags: MessageFlags,
+    index: u32,
+    iface: &Iface,
+    cidr: &IpCidr,
+) {
+    writer.begin_message(
+        RouteMessageType::RTM_NEWADDR as u16,
+        flags,
+        request.seq,
+        port,
+    );
+
+    let addr = cidr.address();
+    let scope = if addr.is_loopback() {
+        Scope::RT_SCOPE_HOST
+    } else {
+        Scope::RT_SCOPE_UNIVERSE
+    };
+    writer.push_struct(&CIfaddrMsg {
+        family: family_of(&addr),
+        prefix_len: cidr.prefix_len(),
+        flags: IFA_F_PERMANENT,
+        scope: scope as u8,
+        index,
+    });
+
+    writer.push_attr(AddrAttrType::IFA_ADDRESS as u16, addr.as_bytes());
+    if let IpAddress::Ipv4(_) = addr {
+        writer.push_attr(AddrAttrType::IFA_LOCAL as u16, addr.as_bytes());
+        writer.push_str_attr(AddrAttrType::IFA_LABEL as u16, iface.name());
+    }
+
+    writer.end_message();
+}
+
+/// Handles a `RTM_NEWADDR` request.
+///
+/// Since an iface can have at most one address in each address family, the new address will
+/// replace the existing one in the same address family.
+fn new_addr(header: &CMessageHeader, payload: &[u8]) -> Result<()> {
+    if payload.len() < size_of::<CIfaddrMsg>() {
+        return_errno_with_message!(Errno::EINVAL, "the message is too short");
+    }
+    let (ifaddr, attrs) = parse_struct::<CIfaddrMsg>(payload);
+
+    let iface = find_iface_by_index(ifaddr.index)
+        .ok_or_else(|| Error::with_message(Errno::ENODEV, "the iface does not exist"))?;
+
+    // `IFA_LOCAL` is the address of the iface, while `IFA_ADDRESS` is the address of the peer
+    // for point-to-point links. They are the same for other links, and either of them may be
+    // omitted.
+    let mut addr_bytes = None;
+    for (type_, value) in parse_attrs(attrs) {
+        match AddrAttrType::try_from(type_) {
+            Ok(AddrAttrType::IFA_LOCAL) => addr_bytes = Some(value),
+            Ok(AddrAttrType::IFA_ADDRESS) => addr_bytes = addr_bytes.or(Some(value)),
+            _ => (),
+        }
+    }
+    let addr_bytes = addr_bytes
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the address is not specified"))?;
+
+    let cidr = parse_cidr(ifaddr.family, addr_bytes, ifaddr.prefix_len)?;
+
+    if header.flags().contains(MessageFlags::EXCL)
+        && iface_cidrs(&iface).any(|old_cidr| old_cidr.address() == cidr.address())
+    {
+        return_errno_with_message!(Errno::EEXIST, "the address already exists");
+    }
+
+    set_iface_cidr(ifaddr.index, cidr)
+}
+
+fn write_route(
+    writer: &mut MessageWriter,
+    request: &CMessageHeader,
+    port: u32,
+    flags: MessageFlags,
+    route: &Route,
+) {
+    writer.begin_message(
+        RouteMessageType::RTM_NEWROUTE as u16,
+        flags,
+        request.seq,
+        port,
+    );
+
+    let cidr = route.cidr();
+    // Routes without gateways are the routes to the subnets of the ifaces.
+    let (protocol, scope) = if route.gateway().is_some() {
+        (RouteProtocol::RTPROT_BOOT, Scope::RT_SCOPE_UNIVERSE)
+    } else {
+        (RouteProtocol::RTPROT_KERNEL, Scope::RT_SCOPE_LINK)
+    };
+    writer.push_struct(&CRtMsg {
+        family: family_of(&cidr.address()),
+        dst_len: cidr.prefix_len(),
+        src_len: 0,
+        tos: 0,
+        table: RT_TABLE_MAIN,
+        protocol: protocol as u8,
+        scope: scope as u8,
+        type_: RTN_UNICAST,
+        flags: 0,
+    });
+
+    writer.push_attr(
+        RouteAttrType::RTA_TABLE as u16,
+        &(RT_TABLE_MAIN as u32).to_ne_bytes(),
+    );
+    if cidr.prefix_len() > 0 {
+        writer.push_attr(RouteAttrType::RTA_DST as u16, cidr.address().as_bytes());
+    }
+    if let Some(gateway) = route.gateway() {
+        writer.push_attr(RouteAttrType::RTA_GATEWAY as u16, gateway.as_bytes());
+    }
+    writer.push_attr(
+        RouteAttrType::RTA_OIF as u16,
+        &route.iface_index().to_ne_bytes(),
+    );
+
+    writer.end_message();
+}
+
+/// The attributes of a route in `RTM_NEWROUTE` and `RTM_DELROUTE` requests.
+struct RouteAttrs {
+    cidr: IpCidr,
+    gateway: Option<IpAddress>,
+    out_index: Option<u32>,
+}
+
+fn parse_route(payload: &[u8]) -> Result<RouteAttrs> {
+    if payload.len() < size_of::<CRtMsg>() {
+        return_errno_with_message!(Errno::EINVAL, "the message is too short");
+    }
+    let (rtmsg, attrs) = parse_struct::<CRtMsg>(payload);
+
+    if rtmsg.table != RT_TABLE_MAIN && rtmsg.table != 0 {
+        return_errno_with_message!(
+            Errno::EOPNOTSUPP,
+            "only the main routing table is supported"
+        );
+    }
+
+    let mut dst_bytes = None;
+    let mut gateway_bytes = None;
+    let mut out_index = None;
+    for (type_, value) in parse_attrs(attrs) {
+        match RouteAttrType::try_from(type_) {
+            Ok(RouteAttrType::RTA_DST) => dst_bytes = Some(value),
+            Ok(RouteAttrType::RTA_GATEWAY) => gateway_bytes = Some(value),
+            Ok(RouteAttrType::RTA_OIF) if value.len() >= size_of::<u32>() => {
+                out_index = Some(u32::from_ne_bytes(value[..4].try_into().unwrap()))
+            }
+            _ => (),
+        }
+    }
+
+    // The destination can be omitted for default routes.
+    const UNSPECIFIED: [u8; 16] = [0; 16];
+    let dst_bytes = match dst_bytes {
+        Some(dst_bytes) => dst_bytes,
+        None if rtmsg.dst_len == 0 && rtmsg.family == CSocketAddrFamily::AF_INET as u8 => {
+            &UNSPECIFIED[..4]
+        }
+        None if rtmsg.dst_len == 0 => &UNSPECIFIED,
+        None => return_errno_with_message!(Errno::EINVAL, "the destination is not specified"),
+    };
+    let cidr = parse_cidr(rtmsg.family, dst_bytes, rtmsg.dst_len)?;
+
+    let gateway = gateway_bytes
+        .map(|gateway_bytes| {
+            let max_prefix_len = cidr.address().as_bytes().len() as u8 * 8;
+            parse_cidr(rtmsg.family, gateway_bytes, max_prefix_len)
+        })
+        .transpose()?
+        .map(|cidr| cidr.address());
+
+    Ok(RouteAttrs {
+        cidr,
+        gateway,
+        out_index,
+    })
+}
+
+/// Handles a `RTM_NEWROUTE` request.
+fn new_route(header: &CMessageHeader, payload: &[u8]) -> Result<()> {
+    let RouteAttrs {
+        cidr,
+        gateway,
+        out_index,
+    } = parse_route(payload)?;
+
+    let index = match (out_index, gateway) {
+        (Some(index), _) => index,
+        // Like Linux, pick up the iface whose subnet contains the gateway.
+        (None, Some(gateway)) => {
+            let Some(route) = lookup_route(&gateway).filter(|route| route.gateway().is_none())
+            else {
+                return_errno_with_message!(Errno::ENETUNREACH, "the gateway is not reachable");
+            };
+            route.iface_index()
+        }
+        (None, None) => {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "neither the iface nor the gateway is specified"
+            )
+        }
+    };
+
+    let replace = header.flags().contains(MessageFlags::REPLACE);
+    add_route(Route::new(cidr, gateway, index), replace)
+}
+
+/// Handles a `RTM_DELROUTE` request.
+fn del_route(payload: &[u8]) -> Result<()> {
+    let RouteAttrs {
+        cidr, out_index, ..
+    } = parse_route(payload)?;
+
+    remove_route(&cidr, out_index)?;
+    Ok(())
+}
+
+fn iface_cidrs(iface: &Iface) -> impl Iterator<Item = IpCidr> {
+    let ipv4_cidr = iface.ipv4_cidr().map(IpCidr::Ipv4);
+    let ipv6_cidr = iface.ipv6_cidr().map(IpCidr::Ipv6);
+    ipv4_cidr.into_iter().chain(ipv6_cidr)
+}
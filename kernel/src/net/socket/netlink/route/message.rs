@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The message types and structures of `NETLINK_ROUTE`.
+//!
+//! The definitions are from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/rtnetlink.h>,
+//! <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if_link.h>, and
+//! <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if_addr.h>.
+
+use crate::prelude::*;
+
+/// Route message types.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum RouteMessageType {
+    RTM_NEWLINK = 16,
+    RTM_DELLINK = 17,
+    RTM_GETLINK = 18,
+    RTM_SETLINK = 19,
+    RTM_NEWADDR = 20,
+    RTM_DELADDR = 21,
+    RTM_GETADDR = 22,
+    RTM_NEWROUTE = 24,
+    RTM_DELROUTE = 25,
+    RTM_GETROUTE = 26,
+}
+
+/// The header of link messages (i.e., `struct ifinfomsg`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CIfinfoMsg {
+    pub(super) family: u8,
+    pub(super) _pad: u8,
+    /// Device type.
+    pub(super) type_: u16,
+    /// Interface index.
+    pub(super) index: i32,
+    /// Device flags.
+    pub(super) flags: u32,
+    /// Change mask.
+    pub(super) change: u32,
+}
+
+/// Link attribute types.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum LinkAttrType {
+    IFLA_UNSPEC = 0,
+    IFLA_ADDRESS = 1,
+    IFLA_BROADCAST = 2,
+    IFLA_IFNAME = 3,
+    IFLA_MTU = 4,
+}
+
+/// The header of address messages (i.e., `struct ifaddrmsg`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CIfaddrMsg {
+    /// Address family.
+    pub(super) family: u8,
+    /// The prefix length.
+    pub(super) prefix_len: u8,
+    /// Address flags.
+    pub(super) flags: u8,
+    /// Address scope.
+    pub(super) scope: u8,
+    /// Interface index.
+    pub(super) index: u32,
+}
+
+/// Address attribute types.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum AddrAttrType {
+    IFA_UNSPEC = 0,
+    IFA_ADDRESS = 1,
+    IFA_LOCAL = 2,
+    IFA_LABEL = 3,
+}
+
+/// The permanent address flag (i.e., `IFA_F_PERMANENT`).
+pub(super) const IFA_F_PERMANENT: u8 = 0x80;
+
+/// The header of route messages (i.e., `struct rtmsg`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CRtMsg {
+    /// Address family.
+    pub(super) family: u8,
+    /// Length of the destination prefix.
+    pub(super) dst_len: u8,
+    /// Length of the source prefix.
+    pub(super) src_len: u8,
+    /// Type of service.
+    pub(super) tos: u8,
+    /// Routing table ID.
+    pub(super) table: u8,
+    /// Routing protocol.
+    pub(super) protocol: u8,
+    /// Distance to the destination.
+    pub(super) scope: u8,
+    /// Route type.
+    pub(super) type_: u8,
+    /// Route flags.
+    pub(super) flags: u32,
+}
+
+/// Route attribute types.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum RouteAttrType {
+    RTA_UNSPEC = 0,
+    RTA_DST = 1,
+    RTA_SRC = 2,
+    RTA_IIF = 3,
+    RTA_OIF = 4,
+    RTA_GATEWAY = 5,
+    RTA_PRIORITY = 6,
+    RTA_PREFSRC = 7,
+    RTA_METRICS = 8,
+    RTA_MULTIPATH = 9,
+    RTA_PROTOINFO = 10,
+    RTA_FLOW = 11,
+    RTA_CACHEINFO = 12,
+    RTA_SESSION = 13,
+    RTA_MP_ALGO = 14,
+    RTA_TABLE = 15,
+}
+
+/// The main routing table (i.e., `RT_TABLE_MAIN`).
+pub(super) const RT_TABLE_MAIN: u8 = 254;
+
+/// Route origins.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum RouteProtocol {
+    /// The route is installed by the kernel (e.g., the route to the subnet of an iface).
+    RTPROT_KERNEL = 2,
+    /// The route is installed during boot.
+    RTPROT_BOOT = 3,
+}
+
+/// Unicast routes (i.e., `RTN_UNICAST`), which are the only supported route type.
+pub(super) const RTN_UNICAST: u8 = 1;
+
+/// Address and route scopes.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(super) enum Scope {
+    RT_SCOPE_UNIVERSE = 0,
+    RT_SCOPE_LINK = 253,
+    RT_SCOPE_HOST = 254,
+}
@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The `NETLINK_ROUTE` protocol (i.e., rtnetlink).
+//!
+//! See <https://man7.org/linux/man-pages/man7/rtnetlink.7.html>.
+
+use super::kernel::NetlinkKernel;
+
+mod kernel;
+mod message;
+
+pub(super) static ROUTE_KERNEL: NetlinkKernel = NetlinkKernel::new(kernel::handle_request);
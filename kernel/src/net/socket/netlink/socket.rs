@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{
+    firewall::FIREWALL_KERNEL, kernel::NetlinkKernel, route::ROUTE_KERNEL, table::BoundPort,
+    NetlinkProtocol, NetlinkSocketAddr,
+};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut,
+    net::socket::{
+        options::{Error as SocketError, SocketOption},
+        util::{
+            options::SocketOptionSet, send_recv_flags::SendRecvFlags, socket_addr::SocketAddr,
+            MessageHeader,
+        },
+        Socket,
+    },
+    prelude::*,
+    process::signal::{Pollable, Pollee, Poller},
+    util::{MultiRead, MultiWrite},
+};
+
+/// The maximum length of the messages that can be sent at once.
+const SEND_BUF_LEN: usize = 65536;
+
+pub struct NetlinkSocket {
+    kernel: &'static NetlinkKernel,
+    inner: Mutex<Inner>,
+    /// The replies from the kernel that have not been received.
+    receive_queue: Mutex<VecDeque<Vec<u8>>>,
+    options: RwLock<SocketOptionSet>,
+    nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+struct Inner {
+    kernel: &'static NetlinkKernel,
+    bound_port: Option<BoundPort>,
+    /// The multicast groups that the socket has joined.
+    //
+    // TODO: Send notifications to the multicast groups.
+    groups: u32,
+    remote_addr: NetlinkSocketAddr,
+}
+
+impl Inner {
+    fn bind(&mut self, addr: &NetlinkSocketAddr) -> Result<()> {
+        if let Some(bound_port) = self.bound_port.as_ref() {
+            // Linux allows rebinding to the same port, which updates the multicast groups.
+            if addr.port() != 0 && addr.port() != bound_port.port() {
+                return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+            }
+        } else {
+            self.bound_port = Some(self.kernel.port_table().bind(addr.port())?);
+        }
+
+        self.groups = addr.groups();
+        Ok(())
+    }
+
+    fn bind_ephemeral(&mut self) -> Result<u32> {
+        if self.bound_port.is_none() {
+            self.bound_port = Some(self.kernel.port_table().bind(0)?);
+        }
+
+        Ok(self.bound_port.as_ref().unwrap().port())
+    }
+
+    fn local_addr(&self) -> NetlinkSocketAddr {
+        let port = self
+            .bound_port
+            .as_ref()
+            .map_or(0, |bound_port| bound_port.port());
+        NetlinkSocketAddr::new(port, self.groups)
+    }
+}
+
+impl NetlinkSocket {
+    /// Creates a netlink socket of `protocol`.
+    ///
+    /// Currently, only `NETLINK_ROUTE` and `NETLINK_FIREWALL` are supported.
+    pub fn new(protocol: NetlinkProtocol, nonblocking: bool) -> Result<Arc<Self>> {
+        let kernel = match protocol {
+            NetlinkProtocol::NETLINK_ROUTE => &ROUTE_KERNEL,
+            NetlinkProtocol::NETLINK_FIREWALL => &FIREWALL_KERNEL,
+            _ => return_errno_with_message!(
+                Errno::EPROTONOSUPPORT,
+                "the netlink protocol is not supported"
+            ),
+        };
+
+        let inner = Inner {
+            kernel,
+            bound_port: None,
+            groups: 0,
+            remote_addr: NetlinkSocketAddr::KERNEL,
+        };
+
+        Ok(Arc::new(Self {
+            kernel,
+            inner: Mutex::new(inner),
+            receive_queue: Mutex::new(VecDeque::new()),
+            options: RwLock::new(SocketOptionSet::new_udp()),
+            nonblocking: AtomicBool::new(nonblocking),
+            pollee: Pollee::new(IoEvents::OUT),
+        }))
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::Relaxed);
+    }
+
+    fn send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote_addr: Option<NetlinkSocketAddr>,
+    ) -> Result<usize> {
+        let mut inner = self.inner.lock();
+
+        let remote_addr = remote_addr.unwrap_or(inner.remote_addr);
+        if remote_addr.port() != 0 || remote_addr.groups() != 0 {
+            // TODO: Support sending messages to user-space sockets and multicast groups.
+            return_errno_with_message!(
+                Errno::ECONNREFUSED,
+                "sending messages to user-space sockets is not supported"
+            );
+        }
+
+        let len = reader.sum_lens();
+        if len > SEND_BUF_LEN {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        let mut buf = vec![0u8; len];
+        reader.read(&mut VmWriter::from(buf.as_mut_slice()))?;
+
+        let port = inner.bind_ephemeral()?;
+        drop(inner);
+
+        let replies = self.kernel.handle_requests(&buf, port);
+        if !replies.is_empty() {
+            self.receive_queue.lock().extend(replies);
+            self.pollee.add_events(IoEvents::IN);
+        }
+
+        Ok(len)
+    }
+
+    fn try_recv(&self, writer: &mut dyn MultiWrite, flags: SendRecvFlags) -> Result<usize> {
+        let mut receive_queue = self.receive_queue.lock();
+
+        let Some(reply) = receive_queue.front() else {
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is empty");
+        };
+
+        // Like other datagram sockets, the part of the reply that does not fit in the buffer is
+        // discarded.
+        let copied_len = writer.write(&mut VmReader::from(reply.as_slice()))?;
+        let reply_len = reply.len();
+
+        if !flags.contains(SendRecvFlags::MSG_PEEK) {
+            receive_queue.pop_front();
+            if receive_queue.is_empty() {
+                self.pollee.del_events(IoEvents::IN);
+            }
+        }
+
+        // With `MSG_TRUNC`, the real length of the reply is returned even if it is truncated.
+        if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            Ok(reply_len)
+        } else {
+            Ok(copied_len)
+        }
+    }
+
+    fn recv(&self, writer: &mut dyn MultiWrite, flags: SendRecvFlags) -> Result<usize> {
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            self.try_recv(writer, flags)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))
+        }
+    }
+}
+
+impl Pollable for NetlinkSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl FileLike for NetlinkSocket {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        self.recv(writer, SendRecvFlags::empty())
+    }
+
+    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+        self.send(reader, None)
+    }
+
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        // TODO: when we fully support O_ASYNC, return the flag
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.set_nonblocking(new_flags.contains(StatusFlags::O_NONBLOCK));
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+}
+
+impl Socket for NetlinkSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = NetlinkSocketAddr::try_from(socket_addr)?;
+        self.inner.lock().bind(&addr)
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = NetlinkSocketAddr::try_from(socket_addr)?;
+
+        let mut inner = self.inner.lock();
+        inner.bind_ephemeral()?;
+        inner.remote_addr = addr;
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        Ok(self.inner.lock().local_addr().into())
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        Ok(self.inner.lock().remote_addr.into())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let MessageHeader {
+            addr,
+            control_message,
+        } = message_header;
+
+        let remote_addr = addr.map(NetlinkSocketAddr::try_from).transpose()?;
+
+        if control_message.is_some() {
+            // TODO: Support sending control message
+            warn!("sending control message is not supported");
+        }
+
+        self.send(reader, remote_addr)
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        let received_len = self.recv(writer, flags)?;
+
+        // All the messages are sent by the kernel.
+        let message_header = MessageHeader::new(Some(NetlinkSocketAddr::KERNEL.into()), None);
+
+        Ok((received_len, message_header))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                self.options.write().get_and_clear_sock_errors(socket_errors);
+                return Ok(());
+            },
+            _ => ()
+        });
+
+        self.options.read().get_option(option)
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        self.options.write().set_option(option)
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::prelude::*;
+
+/// A port bound by a netlink socket.
+///
+/// The port will be released when this handle is dropped.
+#[derive(Debug)]
+pub(super) struct BoundPort {
+    table: &'static PortTable,
+    port: u32,
+}
+
+impl BoundPort {
+    pub(super) fn port(&self) -> u32 {
+        self.port
+    }
+}
+
+impl Drop for BoundPort {
+    fn drop(&mut self) {
+        self.table.ports.write().remove(&self.port);
+    }
+}
+
+/// The table of the ports that are bound by the netlink sockets of a protocol.
+#[derive(Debug)]
+pub(super) struct PortTable {
+    ports: RwLock<BTreeSet<u32>>,
+}
+
+/// The first port that is tried when allocating ephemeral ports, if the port of the process ID is
+/// in use.
+///
+/// Linux uses negative numbers (starting from -4096) for ephemeral ports, so they will not
+/// conflict with the process IDs.
+const EPHEMERAL_PORT_START: u32 = -4096i32 as u32;
+
+impl PortTable {
+    pub(super) const fn new() -> Self {
+        Self {
+            ports: RwLock::new(BTreeSet::new()),
+        }
+    }
+
+    /// Binds to the specified port.
+    ///
+    /// Zero is not a valid port for user-space sockets, since it is the port of the kernel. If
+    /// the port is zero, an ephemeral port will be allocated instead.
+    pub(super) fn bind(&'static self, port: u32) -> Result<BoundPort> {
+        let mut ports = self.ports.write();
+
+        if port != 0 {
+            if !ports.insert(port) {
+                return_errno_with_message!(Errno::EADDRINUSE, "the netlink port is already in use");
+            }
+            return Ok(BoundPort { table: self, port });
+        }
+
+        // See "nl_pid" in the man pages:
+        // <https://man7.org/linux/man-pages/man7/netlink.7.html>.
+        let pid = current!().pid();
+        let port = core::iter::once(pid)
+            .chain((1..=EPHEMERAL_PORT_START).rev())
+            .find(|port| !ports.contains(port))
+            .ok_or_else(|| {
+                Error::with_message(
+                    Errno::EADDRNOTAVAIL,
+                    "no ephemeral netlink port is available",
+                )
+            })?;
+        ports.insert(port);
+
+        Ok(BoundPort { table: self, port })
+    }
+}
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::time::Duration;
+
 use crate::{impl_socket_options, prelude::*};
 mod macros;
 
@@ -20,4 +22,7 @@ impl_socket_options!(
     pub struct Error(Option<crate::error::Error>);
     pub struct Linger(LingerOption);
     pub struct KeepAlive(bool);
+    pub struct PassCred(bool);
+    pub struct RecvTimeout(Option<Duration>);
+    pub struct SendTimeout(Option<Duration>);
 );
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::wire::EthernetAddress;
+
+use crate::{net::socket::SocketAddr, prelude::*};
+
+/// The socket address of a packet socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketSocketAddr {
+    /// The index of the iface, where zero means any iface.
+    pub ifindex: u32,
+    /// The link-layer protocol (i.e., the EtherType) in host byte order.
+    pub protocol: u16,
+    /// The link type of the iface (i.e., the ARP protocol hardware identifier).
+    pub hatype: u16,
+    /// The type of the packet.
+    pub pkttype: PacketType,
+    /// The link-layer address, which is the source address for received packets.
+    pub hwaddr: Option<EthernetAddress>,
+}
+
+/// Packet types.
+///
+/// The definition is from <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/if_packet.h#L26>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum PacketType {
+    /// To us.
+    PACKET_HOST = 0,
+    /// To all.
+    PACKET_BROADCAST = 1,
+    /// To group.
+    PACKET_MULTICAST = 2,
+    /// To someone else.
+    PACKET_OTHERHOST = 3,
+    /// Outgoing of any type.
+    PACKET_OUTGOING = 4,
+}
+
+impl TryFrom<SocketAddr> for PacketSocketAddr {
+    type Error = Error;
+
+    fn try_from(value: SocketAddr) -> Result<Self> {
+        let SocketAddr::Packet(packet_addr) = value else {
+            return_errno_with_message!(Errno::EINVAL, "the socket address is not a packet address");
+        };
+        Ok(packet_addr)
+    }
+}
+
+impl From<PacketSocketAddr> for SocketAddr {
+    fn from(value: PacketSocketAddr) -> Self {
+        SocketAddr::Packet(value)
+    }
+}
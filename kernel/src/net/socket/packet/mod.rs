@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Packet sockets (i.e., `AF_PACKET` sockets).
+//!
+//! Packet sockets send and receive raw link-layer frames, bypassing the network stack. They are
+//! mainly used for network diagnostics (e.g., `tcpdump`). Currently, only `SOCK_RAW` packet
+//! sockets over Ethernet ifaces are supported.
+//!
+//! See <https://man7.org/linux/man-pages/man7/packet.7.html>.
+
+use core::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+
+use aster_bigtcp::{
+    errors::InjectError,
+    iface::{PacketTap, TapDirection},
+    wire::{EthernetAddress, HardwareAddress},
+};
+use ostd::sync::LocalIrqDisabled;
+
+pub use self::addr::{PacketSocketAddr, PacketType};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut,
+    net::{
+        iface::{find_iface_by_index, iter_ifaces, Iface, LinkType},
+        socket::{
+            options::{Error as SocketError, SocketOption},
+            util::{
+                options::SocketOptionSet, send_recv_flags::SendRecvFlags, socket_addr::SocketAddr,
+                MessageHeader,
+            },
+            Socket,
+        },
+    },
+    prelude::*,
+    process::{
+        credentials::capabilities::CapSet,
+        posix_thread::PosixThreadExt,
+        signal::{Pollable, Pollee, Poller},
+    },
+    util::{MultiRead, MultiWrite},
+};
+
+mod addr;
+
+/// The protocol that matches every frame.
+pub const ETH_P_ALL: u16 = 0x0003;
+
+/// The length of the Ethernet header.
+const ETHER_HEADER_LEN: usize = 14;
+
+/// The maximum total length of the frames that have not been received.
+///
+/// This is the default value of `net.core.rmem_default` in Linux.
+const RECV_BUF_LEN: usize = 212992;
+
+pub struct PacketSocket {
+    inner: Mutex<Inner>,
+    /// The link-layer protocol in host byte order.
+    ///
+    /// This is read by the packet taps, so it's kept outside of `inner`.
+    protocol: AtomicU16,
+    /// The frames that have not been received.
+    receive_queue: SpinLock<ReceiveQueue, LocalIrqDisabled>,
+    options: RwLock<SocketOptionSet>,
+    nonblocking: AtomicBool,
+    pollee: Pollee,
+    weak_self: Weak<Self>,
+}
+
+struct Inner {
+    /// The index of the bound iface, where zero means all ifaces.
+    ifindex: u32,
+    /// The taps registered on the ifaces.
+    ///
+    /// The ifaces only hold weak references to the taps, so dropping them unregisters the taps.
+    taps: Vec<Arc<IfaceTap>>,
+}
+
+struct ReceiveQueue {
+    frames: VecDeque<ReceivedFrame>,
+    len: usize,
+}
+
+struct ReceivedFrame {
+    ifindex: u32,
+    pkttype: PacketType,
+    data: Vec<u8>,
+}
+
+/// A packet tap that delivers the frames of an iface to a packet socket.
+struct IfaceTap {
+    ifindex: u32,
+    ether_addr: EthernetAddress,
+    socket: Weak<PacketSocket>,
+}
+
+impl PacketTap for IfaceTap {
+    fn on_frame(&self, frame: &[u8], direction: TapDirection) {
+        let Some(socket) = self.socket.upgrade() else {
+            return;
+        };
+        socket.on_frame(self, frame, direction);
+    }
+}
+
+impl PacketSocket {
+    pub fn new(protocol: u16, nonblocking: bool) -> Result<Arc<Self>> {
+        let credentials = current_thread!().as_posix_thread().unwrap().credentials();
+        if !credentials.effective_capset().contains(CapSet::NET_RAW) {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "CAP_NET_RAW is required to create packet sockets"
+            );
+        }
+
+        let socket = Arc::new_cyclic(|weak_self| Self {
+            inner: Mutex::new(Inner {
+                ifindex: 0,
+                taps: Vec::new(),
+            }),
+            protocol: AtomicU16::new(protocol),
+            receive_queue: SpinLock::new(ReceiveQueue {
+                frames: VecDeque::new(),
+                len: 0,
+            }),
+            options: RwLock::new(SocketOptionSet::new_udp()),
+            nonblocking: AtomicBool::new(nonblocking),
+            pollee: Pollee::new(IoEvents::OUT),
+            weak_self: weak_self.clone(),
+        });
+        socket.inner.lock().taps = socket.add_taps(0);
+
+        Ok(socket)
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Ordering::Relaxed);
+    }
+
+    /// Adds taps to the iface at `ifindex`, or all ifaces if `ifindex` is zero.
+    fn add_taps(&self, ifindex: u32) -> Vec<Arc<IfaceTap>> {
+        iter_ifaces()
+            .filter(|(index, _)| ifindex == 0 || *index == ifindex)
+            .filter_map(|(index, iface)| {
+                let HardwareAddress::Ethernet(ether_addr) = iface.hardware_addr() else {
+                    return None;
+                };
+                let tap = Arc::new(IfaceTap {
+                    ifindex: index,
+                    ether_addr,
+                    socket: self.weak_self.clone(),
+                });
+                iface.add_tap(Arc::downgrade(&tap) as _).then_some(tap)
+            })
+            .collect()
+    }
+
+    fn on_frame(&self, tap: &IfaceTap, frame: &[u8], direction: TapDirection) {
+        if frame.len() < ETHER_HEADER_LEN {
+            return;
+        }
+
+        let protocol = self.protocol.load(Ordering::Relaxed);
+        let ether_type = u16::from_be_bytes([frame[12], frame[13]]);
+        // Only `ETH_P_ALL` sockets see the outgoing frames.
+        let is_wanted = match direction {
+            TapDirection::Incoming => protocol == ETH_P_ALL || protocol == ether_type,
+            TapDirection::Outgoing => protocol == ETH_P_ALL,
+        };
+        if !is_wanted {
+            return;
+        }
+
+        let dst_addr = EthernetAddress::from_bytes(&frame[..6]);
+        let pkttype = match direction {
+            TapDirection::Outgoing => PacketType::PACKET_OUTGOING,
+            TapDirection::Incoming if dst_addr == tap.ether_addr => PacketType::PACKET_HOST,
+            TapDirection::Incoming if dst_addr.is_broadcast() => PacketType::PACKET_BROADCAST,
+            TapDirection::Incoming if dst_addr.is_multicast() => PacketType::PACKET_MULTICAST,
+            TapDirection::Incoming => PacketType::PACKET_OTHERHOST,
+        };
+
+        let mut receive_queue = self.receive_queue.lock();
+        // The frame is dropped if the receive buffer is full.
+        if receive_queue.len + frame.len() > RECV_BUF_LEN {
+            return;
+        }
+        receive_queue.len += frame.len();
+        receive_queue.frames.push_back(ReceivedFrame {
+            ifindex: tap.ifindex,
+            pkttype,
+            data: frame.to_vec(),
+        });
+        drop(receive_queue);
+
+        self.pollee.add_events(IoEvents::IN);
+    }
+
+    fn send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote_addr: Option<PacketSocketAddr>,
+    ) -> Result<usize> {
+        let ifindex = match remote_addr {
+            Some(remote_addr) => remote_addr.ifindex,
+            None => self.inner.lock().ifindex,
+        };
+        if ifindex == 0 {
+            return_errno_with_message!(Errno::ENXIO, "the iface is not specified");
+        }
+        let Some(iface) = find_iface_by_index(ifindex) else {
+            return_errno_with_message!(Errno::ENXIO, "the iface does not exist");
+        };
+
+        let len = reader.sum_lens();
+        if len < ETHER_HEADER_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the frame is too short");
+        }
+
+        let mut frame = vec![0u8; len];
+        reader.read(&mut VmWriter::from(frame.as_mut_slice()))?;
+
+        match iface.inject_frame(&frame) {
+            Ok(()) => Ok(len),
+            Err(InjectError::Unsupported) => {
+                return_errno_with_message!(
+                    Errno::EOPNOTSUPP,
+                    "the iface does not support packet sockets"
+                );
+            }
+            Err(InjectError::TooLarge) => {
+                return_errno_with_message!(Errno::EMSGSIZE, "the frame is too large");
+            }
+            Err(InjectError::BufferFull) => {
+                return_errno_with_message!(Errno::ENOBUFS, "the send buffer of the iface is full");
+            }
+        }
+    }
+
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, PacketSocketAddr)> {
+        let mut receive_queue = self.receive_queue.lock();
+
+        let Some(frame) = receive_queue.frames.front() else {
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is empty");
+        };
+
+        // The part of the frame that does not fit in the buffer is discarded.
+        let copied_len = writer.write(&mut VmReader::from(frame.data.as_slice()))?;
+        let frame_len = frame.data.len();
+        let remote_addr = PacketSocketAddr {
+            ifindex: frame.ifindex,
+            protocol: u16::from_be_bytes([frame.data[12], frame.data[13]]),
+            hatype: LinkType::ETHER as u16,
+            pkttype: frame.pkttype,
+            hwaddr: Some(EthernetAddress::from_bytes(&frame.data[6..12])),
+        };
+
+        if !flags.contains(SendRecvFlags::MSG_PEEK) {
+            receive_queue.frames.pop_front();
+            receive_queue.len -= frame_len;
+            if receive_queue.frames.is_empty() {
+                self.pollee.del_events(IoEvents::IN);
+            }
+        }
+
+        // With `MSG_TRUNC`, the real length of the frame is returned even if it is truncated.
+        if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            Ok((frame_len, remote_addr))
+        } else {
+            Ok((copied_len, remote_addr))
+        }
+    }
+
+    fn recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, PacketSocketAddr)> {
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            self.try_recv(writer, flags)
+        } else {
+            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))
+        }
+    }
+}
+
+impl Pollable for PacketSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl FileLike for PacketSocket {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        self.recv(writer, SendRecvFlags::empty())
+            .map(|(len, _)| len)
+    }
+
+    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+        self.send(reader, None)
+    }
+
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        // TODO: when we fully support O_ASYNC, return the flag
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.set_nonblocking(new_flags.contains(StatusFlags::O_NONBLOCK));
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+}
+
+impl Socket for PacketSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = PacketSocketAddr::try_from(socket_addr)?;
+
+        if addr.ifindex != 0 && find_iface_by_index(addr.ifindex).is_none() {
+            return_errno_with_message!(Errno::ENODEV, "the iface does not exist");
+        }
+
+        let mut inner = self.inner.lock();
+
+        // A zero protocol keeps the current protocol unchanged.
+        if addr.protocol != 0 {
+            self.protocol.store(addr.protocol, Ordering::Relaxed);
+        }
+
+        if addr.ifindex != inner.ifindex {
+            inner.taps = self.add_taps(addr.ifindex);
+            inner.ifindex = addr.ifindex;
+        }
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let ifindex = self.inner.lock().ifindex;
+        let iface = find_iface_by_index(ifindex);
+
+        let addr = PacketSocketAddr {
+            ifindex,
+            protocol: self.protocol.load(Ordering::Relaxed),
+            hatype: iface.as_ref().map_or(0, |iface| LinkType::of(iface) as u16),
+            pkttype: PacketType::PACKET_HOST,
+            hwaddr: iface.as_ref().and_then(|iface| ether_addr_of(iface)),
+        };
+        Ok(addr.into())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let MessageHeader {
+            addr,
+            control_message,
+        } = message_header;
+
+        let remote_addr = addr.map(PacketSocketAddr::try_from).transpose()?;
+
+        if control_message.is_some() {
+            // TODO: Support sending control message
+            warn!("sending control message is not supported");
+        }
+
+        self.send(reader, remote_addr)
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        let (received_len, remote_addr) = self.recv(writer, flags)?;
+
+        let message_header = MessageHeader::new(Some(remote_addr.into()), None);
+
+        Ok((received_len, message_header))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                self.options.write().get_and_clear_sock_errors(socket_errors);
+                return Ok(());
+            },
+            _ => ()
+        });
+
+        self.options.read().get_option(option)
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        self.options.write().set_option(option)
+    }
+}
+
+fn ether_addr_of(iface: &Iface) -> Option<EthernetAddress> {
+    match iface.hardware_addr() {
+        HardwareAddress::Ethernet(ether_addr) => Some(ether_addr),
+        HardwareAddress::Ip => None,
+    }
+}
@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use ostd::sync::WaitQueue;
+
+use crate::{
+    events::{IoEvents, Observer},
+    fs::file_handle::FileLike,
+    net::socket::{
+        unix::{
+            addr::{UnixSocketAddrBound, UnixSocketAddrKey},
+            gc::InflightFiles,
+            UnixSocketInfo,
+        },
+        util::send_recv_flags::SendRecvFlags,
+        CUcred, ControlMessage,
+    },
+    prelude::*,
+    process::signal::{Pollee, Poller},
+    util::{net::SockType, MultiWrite},
+};
+
+/// The maximum total length of the messages that can be queued in a socket.
+///
+/// This is also the maximum length of a single message.
+pub(super) const MAX_QUEUED_LEN: usize = 212992;
+
+/// A message sent via a datagram socket.
+pub(super) struct Message {
+    bytes: Vec<u8>,
+    src_addr: Option<UnixSocketAddrBound>,
+    /// The files passed via `SCM_RIGHTS`.
+    files: InflightFiles,
+    /// The credentials of the sender, which can be specified via `SCM_CREDENTIALS`.
+    credentials: CUcred,
+}
+
+impl Message {
+    pub(super) fn new(
+        bytes: Vec<u8>,
+        src_addr: Option<UnixSocketAddrBound>,
+        control_message: ControlMessage,
+    ) -> Result<Self> {
+        // The credentials are always recorded in case the receiver enables `SO_PASSCRED`.
+        let credentials = control_message
+            .credentials()
+            .copied()
+            .unwrap_or_else(CUcred::current);
+
+        Ok(Self {
+            bytes,
+            src_addr,
+            files: InflightFiles::new(control_message.files())?,
+            credentials,
+        })
+    }
+}
+
+/// The receive queue of a datagram socket.
+///
+/// The queue also records the address of its socket, so that the address can be seen by the
+/// sockets that are connected to it.
+pub(super) struct MessageQueue {
+    addr: SpinLock<Option<UnixSocketAddrBound>>,
+    inner: Mutex<Inner>,
+    pollee: Pollee,
+    /// The wait queue for the senders that are waiting for free space.
+    wait_queue: WaitQueue,
+}
+
+struct Inner {
+    messages: VecDeque<Message>,
+    total_len: usize,
+    is_shutdown: bool,
+    is_closed: bool,
+}
+
+impl MessageQueue {
+    pub(super) fn new() -> Self {
+        let inner = Inner {
+            messages: VecDeque::new(),
+            total_len: 0,
+            is_shutdown: false,
+            is_closed: false,
+        };
+
+        Self {
+            addr: SpinLock::new(None),
+            inner: Mutex::new(inner),
+            pollee: Pollee::new(IoEvents::OUT),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    pub(super) fn addr(&self) -> Option<UnixSocketAddrBound> {
+        self.addr.lock().clone()
+    }
+
+    /// Binds the socket to `addr` and registers the queue so that it can be found by others.
+    pub(super) fn bind(self: &Arc<Self>, addr: UnixSocketAddrBound) -> Result<()> {
+        let mut locked_addr = self.addr.lock();
+
+        if locked_addr.is_some() {
+            return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
+        }
+
+        QUEUE_TABLE.add_queue(addr.to_key(), Arc::downgrade(self));
+        *locked_addr = Some(addr);
+
+        Ok(())
+    }
+
+    /// Tries to push a message to the queue.
+    ///
+    /// The message is returned on failure, so it can be dropped after the lock is released (see
+    /// [`InflightFiles`]).
+    ///
+    /// - Returns `Err(ECONNREFUSED)` if the socket of the queue is closed.
+    /// - Returns `Err(EPIPE)` if the socket of the queue is shut down for reading.
+    /// - Returns `Err(EAGAIN)` if the queue is full.
+    pub(super) fn try_push(&self, message: Message) -> core::result::Result<(), (Error, Message)> {
+        let mut inner = self.inner.lock();
+
+        if inner.is_closed {
+            let err = Error::with_message(Errno::ECONNREFUSED, "the receiving socket is closed");
+            return Err((err, message));
+        }
+        if inner.is_shutdown {
+            let err = Error::with_message(Errno::EPIPE, "the receiving socket is shut down");
+            return Err((err, message));
+        }
+        if inner.total_len + message.bytes.len() > MAX_QUEUED_LEN && !inner.messages.is_empty() {
+            let err = Error::with_message(Errno::EAGAIN, "the receive queue is full");
+            return Err((err, message));
+        }
+
+        inner.total_len += message.bytes.len();
+        inner.messages.push_back(message);
+        self.pollee.add_events(IoEvents::IN);
+
+        Ok(())
+    }
+
+    /// Pushes a message to the queue, waiting for free space if `is_nonblocking` is false.
+    pub(super) fn push(&self, message: Message, is_nonblocking: bool) -> Result<()> {
+        if is_nonblocking {
+            return self.try_push(message).map_err(|(err, _)| err);
+        }
+
+        let mut message = Some(message);
+        self.wait_queue
+            .pause_until(|| match self.try_push(message.take().unwrap()) {
+                Ok(()) => Some(Ok(())),
+                Err((err, returned)) if err.error() == Errno::EAGAIN => {
+                    message = Some(returned);
+                    None
+                }
+                Err((err, _)) => Some(Err(err)),
+            })?
+    }
+
+    /// Tries to pop a message from the queue and write its bytes to `writer`.
+    ///
+    /// Returns the number of bytes written (or the length of the message if `MSG_TRUNC` is
+    /// specified), the address of the sender, and the control messages. Returns `Ok((0, None,
+    /// _))` if the queue is shut down and there is no message left.
+    pub(super) fn try_pop(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, Option<UnixSocketAddrBound>, ControlMessage)> {
+        let mut inner = self.inner.lock();
+
+        let Some(message) = inner.messages.front() else {
+            if inner.is_shutdown {
+                return Ok((0, None, ControlMessage::default()));
+            }
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is empty");
+        };
+
+        // The part of the message that does not fit in the buffer is discarded.
+        let copied_len = writer.write(&mut VmReader::from(message.bytes.as_slice()))?;
+        let message_len = message.bytes.len();
+
+        let (src_addr, control_message) = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            // The files are duplicated if the message is peeked, just like Linux.
+            let files = message.files.files().to_vec();
+            (
+                message.src_addr.clone(),
+                ControlMessage::new(files, Some(message.credentials)),
+            )
+        } else {
+            let message = inner.messages.pop_front().unwrap();
+            inner.total_len -= message_len;
+            if inner.messages.is_empty() && !inner.is_shutdown {
+                self.pollee.del_events(IoEvents::IN);
+            }
+            drop(inner);
+            self.wait_queue.wake_all();
+
+            let files = message.files.receive();
+            (
+                message.src_addr,
+                ControlMessage::new(files, Some(message.credentials)),
+            )
+        };
+
+        // With `MSG_TRUNC`, the real length of the message is returned even if it is truncated.
+        if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            Ok((message_len, src_addr, control_message))
+        } else {
+            Ok((copied_len, src_addr, control_message))
+        }
+    }
+
+    /// Shuts down the queue so that no more messages can be pushed.
+    pub(super) fn shutdown(&self) {
+        let mut inner = self.inner.lock();
+
+        inner.is_shutdown = true;
+        self.pollee.add_events(IoEvents::IN | IoEvents::RDHUP);
+
+        drop(inner);
+
+        self.wait_queue.wake_all();
+    }
+
+    /// Closes the queue and unregisters it, after which no one can find the queue.
+    pub(super) fn close(&self) {
+        if let Some(addr) = self.addr.lock().as_ref() {
+            QUEUE_TABLE.remove_queue(&addr.to_key());
+        }
+
+        let mut inner = self.inner.lock();
+        inner.is_closed = true;
+        let messages = core::mem::take(&mut inner.messages);
+        drop(inner);
+
+        // The messages must be dropped after the lock is released (see `InflightFiles`).
+        drop(messages);
+
+        self.wait_queue.wake_all();
+    }
+
+    /// Visits the in-flight files of the queued messages.
+    pub(super) fn for_each_queued_file(&self, f: &mut dyn FnMut(&Arc<dyn FileLike>)) {
+        self.inner
+            .lock()
+            .messages
+            .iter()
+            .flat_map(|message| message.files.files())
+            .for_each(f);
+    }
+
+    /// Takes out the in-flight files of the queued messages.
+    pub(super) fn take_queued_files(&self) -> Vec<InflightFiles> {
+        self.inner
+            .lock()
+            .messages
+            .iter_mut()
+            .map(|message| core::mem::take(&mut message.files))
+            .filter(|files| !files.is_empty())
+            .collect()
+    }
+
+    pub(super) fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+
+    pub(super) fn register_observer(&self, observer: Weak<dyn Observer<IoEvents>>, mask: IoEvents) {
+        self.pollee.register_observer(observer, mask);
+    }
+
+    pub(super) fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+}
+
+static QUEUE_TABLE: QueueTable = QueueTable::new();
+
+/// The table of the receive queues of the bound datagram sockets.
+struct QueueTable {
+    queues: RwLock<BTreeMap<UnixSocketAddrKey, Weak<MessageQueue>>>,
+}
+
+impl QueueTable {
+    const fn new() -> Self {
+        Self {
+            queues: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    fn add_queue(&self, addr_key: UnixSocketAddrKey, queue: Weak<MessageQueue>) {
+        // The address is exclusively owned by the socket once bound, so there cannot be an
+        // existing queue with the same key.
+        self.queues.write().insert(addr_key, queue);
+    }
+
+    fn get_queue(&self, addr_key: &UnixSocketAddrKey) -> Option<Arc<MessageQueue>> {
+        self.queues.read().get(addr_key).and_then(Weak::upgrade)
+    }
+
+    fn remove_queue(&self, addr_key: &UnixSocketAddrKey) {
+        self.queues.write().remove(addr_key);
+    }
+}
+
+/// Gets the receive queue of the datagram socket bound to the address.
+pub(super) fn get_queue(addr_key: &UnixSocketAddrKey) -> Result<Arc<MessageQueue>> {
+    QUEUE_TABLE.get_queue(addr_key).ok_or_else(|| {
+        Error::with_message(
+            Errno::ECONNREFUSED,
+            "no datagram socket is bound to the remote address",
+        )
+    })
+}
+
+/// Gets the information of all the bound datagram sockets.
+pub(in crate::net::socket::unix) fn bound_socket_infos() -> Vec<UnixSocketInfo> {
+    QUEUE_TABLE
+        .queues
+        .read()
+        .values()
+        .filter_map(Weak::upgrade)
+        .filter_map(|queue| queue.addr())
+        .map(|addr| UnixSocketInfo {
+            addr: addr.into(),
+            type_: SockType::SOCK_DGRAM,
+            is_listening: false,
+        })
+        .collect()
+}
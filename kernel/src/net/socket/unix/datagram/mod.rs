@@ -0,0 +1,7 @@
+// SPDX-License-Identifier: MPL-2.0
+
+mod message;
+mod socket;
+
+pub(super) use message::bound_socket_infos;
+pub use socket::UnixDatagramSocket;
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::message::{get_queue, Message, MessageQueue, MAX_QUEUED_LEN};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut, match_sock_option_ref,
+    net::socket::{
+        options::{PassCred, SocketOption},
+        unix::{
+            gc::{collect_garbage, InflightFiles},
+            UnixSocketAddr,
+        },
+        util::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr, MessageHeader},
+        ControlMessage, SockShutdownCmd, Socket,
+    },
+    prelude::*,
+    process::signal::{Pollable, Poller},
+    util::{MultiRead, MultiWrite},
+};
+
+pub struct UnixDatagramSocket {
+    /// The queue of the messages sent to this socket.
+    receive_queue: Arc<MessageQueue>,
+    /// The queue of the peer socket, if the socket is connected.
+    peer: RwLock<Option<Weak<MessageQueue>>>,
+    is_write_shutdown: AtomicBool,
+    is_nonblocking: AtomicBool,
+    /// Whether the credentials of the senders should be received (i.e., `SO_PASSCRED`).
+    is_pass_cred: AtomicBool,
+}
+
+impl UnixDatagramSocket {
+    pub fn new(is_nonblocking: bool) -> Arc<Self> {
+        Arc::new(Self::new_with_peer(None, is_nonblocking))
+    }
+
+    pub fn new_pair(is_nonblocking: bool) -> (Arc<Self>, Arc<Self>) {
+        let socket_a = Self::new_with_peer(None, is_nonblocking);
+        let socket_b = Self::new_with_peer(
+            Some(Arc::downgrade(&socket_a.receive_queue)),
+            is_nonblocking,
+        );
+        *socket_a.peer.write() = Some(Arc::downgrade(&socket_b.receive_queue));
+
+        (Arc::new(socket_a), Arc::new(socket_b))
+    }
+
+    fn new_with_peer(peer: Option<Weak<MessageQueue>>, is_nonblocking: bool) -> Self {
+        Self {
+            receive_queue: Arc::new(MessageQueue::new()),
+            peer: RwLock::new(peer),
+            is_write_shutdown: AtomicBool::new(false),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+            is_pass_cred: AtomicBool::new(false),
+        }
+    }
+
+    fn send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote_addr: Option<UnixSocketAddr>,
+        control_message: ControlMessage,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let remote_queue = if let Some(remote_addr) = remote_addr {
+            get_queue(&remote_addr.connect()?)?
+        } else {
+            let Some(peer) = self.peer.read().clone() else {
+                return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected");
+            };
+            peer.upgrade().ok_or_else(|| {
+                Error::with_message(Errno::ECONNREFUSED, "the peer socket is closed")
+            })?
+        };
+
+        if self.is_write_shutdown.load(Ordering::Relaxed) {
+            return_errno_with_message!(Errno::EPIPE, "the socket is shut down for writing");
+        }
+
+        let len = reader.sum_lens();
+        if len > MAX_QUEUED_LEN {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        let mut bytes = vec![0u8; len];
+        reader.read(&mut VmWriter::from(bytes.as_mut_slice()))?;
+
+        // Unlike the other datagram sockets, an unbound socket is not bound automatically. Its
+        // messages are received from an unnamed address.
+        let message = Message::new(bytes, self.receive_queue.addr(), control_message)?;
+        remote_queue.push(
+            message,
+            self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT),
+        )?;
+
+        Ok(len)
+    }
+
+    fn recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, UnixSocketAddr, ControlMessage)> {
+        let (len, src_addr, control_message) =
+            if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+                self.receive_queue.try_pop(writer, flags)?
+            } else {
+                self.wait_events(IoEvents::IN, || self.receive_queue.try_pop(writer, flags))?
+            };
+
+        Ok((len, src_addr.into(), control_message))
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        self.is_nonblocking.store(nonblocking, Ordering::Relaxed);
+    }
+}
+
+impl UnixDatagramSocket {
+    /// Visits the in-flight files in the receive queue.
+    pub(in crate::net::socket::unix) fn for_each_queued_file(
+        &self,
+        f: &mut dyn FnMut(&Arc<dyn FileLike>),
+    ) {
+        self.receive_queue.for_each_queued_file(f);
+    }
+
+    /// Takes out the in-flight files in the receive queue.
+    pub(in crate::net::socket::unix) fn take_queued_files(&self) -> Vec<InflightFiles> {
+        self.receive_queue.take_queued_files()
+    }
+}
+
+impl Drop for UnixDatagramSocket {
+    fn drop(&mut self) {
+        self.receive_queue.close();
+        collect_garbage();
+    }
+}
+
+impl Pollable for UnixDatagramSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.receive_queue.poll(mask, poller)
+    }
+}
+
+impl FileLike for UnixDatagramSocket {
+    fn as_socket(self: Arc<Self>) -> Option<Arc<dyn Socket>> {
+        Some(self)
+    }
+
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let (read_len, _, _) = self.recv(writer, SendRecvFlags::empty())?;
+        Ok(read_len)
+    }
+
+    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+        self.send(
+            reader,
+            None,
+            ControlMessage::default(),
+            SendRecvFlags::empty(),
+        )
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.set_nonblocking(new_flags.contains(StatusFlags::O_NONBLOCK));
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.receive_queue.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.receive_queue.unregister_observer(observer)
+    }
+}
+
+impl Socket for UnixDatagramSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = UnixSocketAddr::try_from(socket_addr)?;
+
+        if self.receive_queue.addr().is_some() {
+            return addr.bind_unnamed();
+        }
+
+        self.receive_queue.bind(addr.bind()?)
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let remote_addr = UnixSocketAddr::try_from(socket_addr)?.connect()?;
+        let remote_queue = get_queue(&remote_addr)?;
+
+        *self.peer.write() = Some(Arc::downgrade(&remote_queue));
+
+        Ok(())
+    }
+
+    fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
+        if cmd.shut_read() {
+            self.receive_queue.shutdown();
+        }
+
+        if cmd.shut_write() {
+            self.is_write_shutdown.store(true, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        Ok(self.receive_queue.addr().into())
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        let Some(peer) = self.peer.read().clone() else {
+            return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected");
+        };
+
+        // If the peer socket is closed, its address is no longer meaningful.
+        let peer_addr = peer.upgrade().and_then(|peer| peer.addr());
+
+        Ok(peer_addr.into())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let MessageHeader {
+            addr,
+            control_message,
+        } = message_header;
+
+        let remote_addr = addr.map(UnixSocketAddr::try_from).transpose()?;
+
+        self.send(
+            reader,
+            remote_addr,
+            control_message.unwrap_or_default(),
+            flags,
+        )
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        let (received_len, src_addr, mut control_message) = self.recv(writer, flags)?;
+
+        if !self.is_pass_cred.load(Ordering::Relaxed) {
+            control_message.clear_credentials();
+        }
+        let control_message = (!control_message.is_empty()).then_some(control_message);
+
+        let message_header = MessageHeader::new(Some(src_addr.into()), control_message);
+
+        Ok((received_len, message_header))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                socket_pass_cred.set(self.is_pass_cred.load(Ordering::Relaxed));
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*is_pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Accounting and garbage collection of the files passed via `SCM_RIGHTS`.
+//!
+//! A file that has been sent but not yet received is in flight. In-flight UNIX sockets can form
+//! reference cycles. For example, a socket can be sent to itself and then closed, after which it
+//! is only referenced by its own receive queue. Such sockets can no longer be accessed by anyone,
+//! so the garbage collector breaks the cycles by discarding the files in their receive queues.
+//!
+//! The number of the in-flight files sent by each user is also limited by `RLIMIT_NOFILE`.
+//!
+//! See <https://elixir.bootlin.com/linux/v6.0/source/net/unix/garbage.c>.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{UnixDatagramSocket, UnixStreamSocket};
+use crate::{
+    fs::file_handle::FileLike,
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::PosixThreadExt, ResourceType},
+};
+
+/// The files passed via `SCM_RIGHTS` that are in flight.
+///
+/// Once the files are received, they should be taken out by [`Self::receive`]. Otherwise, they
+/// are discarded when this object is dropped.
+///
+/// Note that the locks of the receive queues must not be held when calling [`Self::new`] or
+/// [`Self::receive`], or when dropping this object, since the garbage collector locks the
+/// receive queues with the global lock of the in-flight files held.
+#[derive(Default)]
+pub(super) struct InflightFiles {
+    files: Vec<Arc<dyn FileLike>>,
+    /// The real UID of the sender.
+    uid: u32,
+}
+
+impl InflightFiles {
+    /// Records the files as in flight.
+    ///
+    /// This method fails with `ETOOMANYREFS` if the current user has too many in-flight files
+    /// and is not privileged.
+    pub(super) fn new(files: &[Arc<dyn FileLike>]) -> Result<Self> {
+        if files.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let current_thread = current_thread!();
+        let credentials = current_thread.as_posix_thread().unwrap().credentials();
+        let uid = u32::from(credentials.ruid());
+        let is_privileged = credentials
+            .effective_capset()
+            .intersects(CapSet::SYS_RESOURCE | CapSet::SYS_ADMIN);
+        let max_files = current!()
+            .resource_limits()
+            .lock()
+            .get_rlimit(ResourceType::RLIMIT_NOFILE)
+            .get_cur();
+
+        let is_over_limit =
+            || INFLIGHT.lock().user_files.get(&uid).copied().unwrap_or(0) as u64 > max_files;
+        if !is_privileged && is_over_limit() {
+            // Some of the in-flight files may be garbage.
+            collect_garbage();
+            if is_over_limit() {
+                return_errno_with_message!(Errno::ETOOMANYREFS, "too many files are in flight");
+            }
+        }
+
+        let mut inflight = INFLIGHT.lock();
+        *inflight.user_files.entry(uid).or_insert(0) += files.len();
+        for file in files.iter().filter(|file| is_unix_socket(file)) {
+            inflight
+                .sockets
+                .entry(file_key(file))
+                .or_insert_with(|| InflightSocket {
+                    socket: Arc::downgrade(file),
+                    num_refs: 0,
+                })
+                .num_refs += 1;
+        }
+        drop(inflight);
+
+        Ok(Self {
+            files: files.to_vec(),
+            uid,
+        })
+    }
+
+    /// Returns the in-flight files.
+    pub(super) fn files(&self) -> &[Arc<dyn FileLike>] {
+        &self.files
+    }
+
+    /// Returns whether there are no in-flight files.
+    pub(super) fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Takes the files out because they are received.
+    pub(super) fn receive(mut self) -> Vec<Arc<dyn FileLike>> {
+        self.unregister();
+        core::mem::take(&mut self.files)
+    }
+
+    fn unregister(&self) {
+        if self.files.is_empty() {
+            return;
+        }
+
+        let mut inflight = INFLIGHT.lock();
+
+        let user_files = inflight.user_files.get_mut(&self.uid).unwrap();
+        *user_files -= self.files.len();
+        if *user_files == 0 {
+            inflight.user_files.remove(&self.uid);
+        }
+
+        for file in self.files.iter().filter(|file| is_unix_socket(file)) {
+            let key = file_key(file);
+            let socket = inflight.sockets.get_mut(&key).unwrap();
+            socket.num_refs -= 1;
+            if socket.num_refs == 0 {
+                inflight.sockets.remove(&key);
+            }
+        }
+    }
+}
+
+impl Drop for InflightFiles {
+    fn drop(&mut self) {
+        // The lock is released before the files are dropped, since dropping a UNIX socket may
+        // trigger the garbage collector.
+        self.unregister();
+    }
+}
+
+static INFLIGHT: Mutex<Inflight> = Mutex::new(Inflight {
+    user_files: BTreeMap::new(),
+    sockets: BTreeMap::new(),
+});
+
+/// Whether the garbage collector is running.
+static IS_COLLECTING: AtomicBool = AtomicBool::new(false);
+
+struct Inflight {
+    /// The number of the in-flight files sent by each user, keyed by the real UID.
+    user_files: BTreeMap<u32, usize>,
+    /// The in-flight UNIX sockets, keyed by their addresses.
+    sockets: BTreeMap<usize, InflightSocket>,
+}
+
+struct InflightSocket {
+    socket: Weak<dyn FileLike>,
+    /// The number of the in-flight references to the socket.
+    num_refs: usize,
+}
+
+fn file_key(file: &Arc<dyn FileLike>) -> usize {
+    Arc::as_ptr(file) as *const () as usize
+}
+
+fn is_unix_socket(file: &Arc<dyn FileLike>) -> bool {
+    file.downcast_ref::<UnixStreamSocket>().is_some()
+        || file.downcast_ref::<UnixDatagramSocket>().is_some()
+}
+
+/// Visits the in-flight files in the receive queue of the UNIX socket.
+///
+/// The files in the backlog of a listening socket are not visited. So such files are always
+/// considered reachable, which may leak them but never discards reachable files.
+fn for_each_queued_file(socket: &Arc<dyn FileLike>, f: &mut dyn FnMut(&Arc<dyn FileLike>)) {
+    if let Some(stream) = socket.downcast_ref::<UnixStreamSocket>() {
+        stream.for_each_queued_file(f);
+    } else if let Some(datagram) = socket.downcast_ref::<UnixDatagramSocket>() {
+        datagram.for_each_queued_file(f);
+    }
+}
+
+/// Takes out the in-flight files in the receive queue of the UNIX socket.
+fn take_queued_files(socket: &Arc<dyn FileLike>) -> Vec<InflightFiles> {
+    if let Some(stream) = socket.downcast_ref::<UnixStreamSocket>() {
+        stream.take_queued_files()
+    } else if let Some(datagram) = socket.downcast_ref::<UnixDatagramSocket>() {
+        datagram.take_queued_files()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Discards the in-flight files in the receive queues of the unreachable UNIX sockets.
+///
+/// This should be called when a UNIX socket is released, since the socket may be the last one
+/// that can reach some in-flight sockets.
+pub(super) fn collect_garbage() {
+    if IS_COLLECTING.swap(true, Ordering::Acquire) {
+        return;
+    }
+
+    // The garbage and the candidates are dropped after the lock is released.
+    let mut garbage = Vec::new();
+    let mut candidates = BTreeMap::new();
+
+    let inflight = INFLIGHT.lock();
+
+    // The candidates are the in-flight sockets that are only referenced by in-flight files. The
+    // references are counted with the lock held, so they cannot change, except that new
+    // references may be added by peeking the files in the receive queues of reachable sockets.
+    let mut num_outer_refs = BTreeMap::new();
+    for (key, socket) in inflight.sockets.iter() {
+        if socket.socket.strong_count() != socket.num_refs {
+            continue;
+        }
+        let Some(file) = socket.socket.upgrade() else {
+            continue;
+        };
+        candidates.insert(*key, file);
+        num_outer_refs.insert(*key, socket.num_refs);
+    }
+
+    // Exclude the references from the receive queues of the candidates.
+    for file in candidates.values() {
+        for_each_queued_file(file, &mut |child| {
+            if let Some(num_refs) = num_outer_refs.get_mut(&file_key(child)) {
+                *num_refs -= 1;
+            }
+        });
+    }
+
+    // The candidates that are still referenced from outside are reachable, and so are the
+    // candidates that can be reached from them.
+    let mut stack: Vec<usize> = num_outer_refs
+        .iter()
+        .filter(|(_, num_refs)| **num_refs > 0)
+        .map(|(key, _)| *key)
+        .collect();
+    let mut reachable: BTreeSet<usize> = stack.iter().copied().collect();
+    while let Some(key) = stack.pop() {
+        for_each_queued_file(&candidates[&key], &mut |child| {
+            let child_key = file_key(child);
+            if candidates.contains_key(&child_key) && reachable.insert(child_key) {
+                stack.push(child_key);
+            }
+        });
+    }
+
+    for (key, file) in candidates.iter() {
+        if !reachable.contains(key) {
+            garbage.extend(take_queued_files(file));
+        }
+    }
+
+    drop(inflight);
+    drop(garbage);
+    drop(candidates);
+
+    IS_COLLECTING.store(false, Ordering::Release);
+}
@@ -1,8 +1,30 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod addr;
+mod datagram;
+mod gc;
 mod ns;
 mod stream;
 
 pub use addr::UnixSocketAddr;
+pub use datagram::UnixDatagramSocket;
 pub use stream::UnixStreamSocket;
+
+use crate::{prelude::*, util::net::SockType};
+
+/// The information of a UNIX socket that can be found by its address.
+#[derive(Debug, Clone)]
+pub struct UnixSocketInfo {
+    pub addr: UnixSocketAddr,
+    pub type_: SockType,
+    pub is_listening: bool,
+}
+
+/// Gets the information of the listening stream sockets and the bound datagram sockets.
+///
+/// Other UNIX sockets cannot be found by their addresses, so they are not included.
+pub fn bound_socket_infos() -> Vec<UnixSocketInfo> {
+    let mut infos = stream::listening_socket_infos();
+    infos.extend(datagram::bound_socket_infos());
+    infos
+}
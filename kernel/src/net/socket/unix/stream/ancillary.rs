@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::file_handle::FileLike,
+    net::socket::{unix::gc::InflightFiles, CUcred, ControlMessage},
+    prelude::*,
+};
+
+/// The ancillary data sent along with the bytes in one direction of a connection.
+///
+/// The ancillary data is attached to the position of the first byte sent with it. A read never
+/// goes across the position where new ancillary data starts, so the ancillary data is received
+/// exactly with the bytes that it is sent with.
+pub(super) struct AncillaryQueue {
+    /// The number of bytes that have been written.
+    written_len: usize,
+    /// The number of bytes that have been read.
+    read_len: usize,
+    items: VecDeque<AncillaryItem>,
+    /// The credentials of the last written item.
+    written_credentials: Option<CUcred>,
+    /// The credentials of the last read item.
+    read_credentials: Option<CUcred>,
+}
+
+struct AncillaryItem {
+    /// The position of the first byte that the item is attached to.
+    pos: usize,
+    files: InflightFiles,
+    credentials: CUcred,
+}
+
+/// The ancillary data of the bytes that have been read.
+pub(super) struct ReadAncillary {
+    files: InflightFiles,
+    credentials: Option<CUcred>,
+}
+
+impl ReadAncillary {
+    /// Receives the files and returns the control messages.
+    ///
+    /// The lock of the [`AncillaryQueue`] must not be held (see [`InflightFiles`]).
+    pub(super) fn receive(self) -> ControlMessage {
+        ControlMessage::new(self.files.receive(), self.credentials)
+    }
+}
+
+impl AncillaryQueue {
+    pub(super) fn new() -> Self {
+        Self {
+            written_len: 0,
+            read_len: 0,
+            items: VecDeque::new(),
+            written_credentials: None,
+            read_credentials: None,
+        }
+    }
+
+    /// Records the ancillary data of the `len` bytes that have been written.
+    ///
+    /// For `SOCK_STREAM` sockets, the ancillary data is recorded only if there are files or the
+    /// credentials change, so that adjacent writes can be read at once. For `SOCK_SEQPACKET`
+    /// sockets (i.e., if `is_record` is true), the ancillary data is always recorded because
+    /// each message is read separately.
+    pub(super) fn push_written(
+        &mut self,
+        len: usize,
+        control_message: &ControlMessage,
+        files: &mut InflightFiles,
+        is_record: bool,
+    ) {
+        let pos = self.written_len;
+        self.written_len += len;
+
+        if len == 0 && !is_record {
+            return;
+        }
+
+        let credentials = control_message
+            .credentials()
+            .copied()
+            .unwrap_or_else(CUcred::current);
+
+        if is_record || !files.is_empty() || self.written_credentials != Some(credentials) {
+            self.items.push_back(AncillaryItem {
+                pos,
+                files: core::mem::take(files),
+                credentials,
+            });
+            self.written_credentials = Some(credentials);
+        }
+    }
+
+    /// Returns whether the bytes to read start with new ancillary data.
+    pub(super) fn has_unread_item(&self) -> bool {
+        self.items
+            .front()
+            .is_some_and(|item| item.pos == self.read_len)
+    }
+
+    /// Returns the maximum number of bytes that can be read at once.
+    pub(super) fn max_read_len(&self) -> usize {
+        self.items
+            .iter()
+            .find(|item| item.pos > self.read_len)
+            .map_or(usize::MAX, |item| item.pos - self.read_len)
+    }
+
+    /// Records that `len` bytes have been read and returns their ancillary data.
+    pub(super) fn pop_read(&mut self, len: usize) -> ReadAncillary {
+        let pos = self.read_len;
+        self.read_len += len;
+
+        let mut files = InflightFiles::default();
+        if self.items.front().is_some_and(|item| item.pos == pos) {
+            let item = self.items.pop_front().unwrap();
+            files = item.files;
+            self.read_credentials = Some(item.credentials);
+        }
+
+        ReadAncillary {
+            files,
+            credentials: self.read_credentials,
+        }
+    }
+
+    /// Returns the ancillary data of the bytes to read without recording that they have been
+    /// read.
+    ///
+    /// The files are duplicated if the bytes are peeked, just like Linux.
+    pub(super) fn peek_read(&self) -> ControlMessage {
+        match self.items.front() {
+            Some(item) if item.pos == self.read_len => {
+                ControlMessage::new(item.files.files().to_vec(), Some(item.credentials))
+            }
+            _ => ControlMessage::new(Vec::new(), self.read_credentials),
+        }
+    }
+
+    /// Visits the in-flight files of the bytes to read.
+    pub(super) fn for_each_file(&self, f: &mut dyn FnMut(&Arc<dyn FileLike>)) {
+        self.items
+            .iter()
+            .flat_map(|item| item.files.files())
+            .for_each(f);
+    }
+
+    /// Takes out the in-flight files of the bytes to read.
+    pub(super) fn take_files(&mut self) -> Vec<InflightFiles> {
+        self.items
+            .iter_mut()
+            .map(|item| core::mem::take(&mut item.files))
+            .filter(|files| !files.is_empty())
+            .collect()
+    }
+}
@@ -4,12 +4,17 @@ use core::ops::Deref;
 
 use ostd::sync::PreemptDisabled;
 
+use super::ancillary::AncillaryQueue;
 use crate::{
     events::{IoEvents, Observer},
-    fs::utils::{Channel, Consumer, Producer},
+    fs::{
+        file_handle::FileLike,
+        utils::{Channel, Consumer, Producer},
+    },
     net::socket::{
-        unix::{addr::UnixSocketAddrBound, UnixSocketAddr},
-        SockShutdownCmd,
+        unix::{addr::UnixSocketAddrBound, gc::InflightFiles, UnixSocketAddr},
+        util::send_recv_flags::SendRecvFlags,
+        ControlMessage, SockShutdownCmd,
     },
     prelude::*,
     process::signal::{Pollee, Poller},
@@ -20,6 +25,16 @@ pub(super) struct Connected {
     addr: AddrView,
     reader: Consumer<u8>,
     writer: Producer<u8>,
+    /// The message boundaries, which only exist for `SOCK_SEQPACKET` sockets.
+    records: Option<Records>,
+    /// The ancillary data of the bytes to read.
+    ///
+    /// The lock also ensures that the bytes and their ancillary data are read atomically.
+    reader_ancillary: Arc<Mutex<AncillaryQueue>>,
+    /// The ancillary data of the bytes to write.
+    ///
+    /// The lock also ensures that the bytes and their ancillary data are written atomically.
+    writer_ancillary: Arc<Mutex<AncillaryQueue>>,
 }
 
 impl Connected {
@@ -28,23 +43,70 @@ impl Connected {
         peer_addr: Option<UnixSocketAddrBound>,
         reader_pollee: Option<Pollee>,
         writer_pollee: Option<Pollee>,
+        is_seqpacket: bool,
     ) -> (Connected, Connected) {
-        let (writer_peer, reader_this) =
-            Channel::with_capacity_and_pollees(DEFAULT_BUF_SIZE, None, reader_pollee).split();
+        let (addr_this, addr_peer) = AddrView::new_pair(addr, peer_addr);
+        let ancillary_this = Arc::new(Mutex::new(AncillaryQueue::new()));
+        let ancillary_peer = Arc::new(Mutex::new(AncillaryQueue::new()));
+
+        if !is_seqpacket {
+            let (writer_peer, reader_this) =
+                Channel::with_capacity_and_pollees(DEFAULT_BUF_SIZE, None, reader_pollee).split();
+            let (writer_this, reader_peer) =
+                Channel::with_capacity_and_pollees(DEFAULT_BUF_SIZE, writer_pollee, None).split();
+
+            let this = Connected {
+                addr: addr_this,
+                reader: reader_this,
+                writer: writer_this,
+                records: None,
+                reader_ancillary: ancillary_this.clone(),
+                writer_ancillary: ancillary_peer.clone(),
+            };
+            let peer = Connected {
+                addr: addr_peer,
+                reader: reader_peer,
+                writer: writer_peer,
+                records: None,
+                reader_ancillary: ancillary_peer,
+                writer_ancillary: ancillary_this,
+            };
+
+            return (this, peer);
+        }
+
+        // For `SOCK_SEQPACKET` sockets, a message becomes readable only after its length is
+        // pushed to the record channel. So the readable events come from the record channel,
+        // while the writable events still come from the byte channel.
+        let (writer_peer, reader_this) = Channel::with_capacity(DEFAULT_BUF_SIZE).split();
         let (writer_this, reader_peer) =
             Channel::with_capacity_and_pollees(DEFAULT_BUF_SIZE, writer_pollee, None).split();
-
-        let (addr_this, addr_peer) = AddrView::new_pair(addr, peer_addr);
+        let (records_writer_peer, records_reader_this) =
+            Channel::with_capacity_and_pollees(MAX_RECORDS, None, reader_pollee).split();
+        let (records_writer_this, records_reader_peer) =
+            Channel::with_capacity(MAX_RECORDS).split();
 
         let this = Connected {
             addr: addr_this,
             reader: reader_this,
             writer: writer_this,
+            records: Some(Records {
+                reader: records_reader_this,
+                writer: records_writer_this,
+            }),
+            reader_ancillary: ancillary_this.clone(),
+            writer_ancillary: ancillary_peer.clone(),
         };
         let peer = Connected {
             addr: addr_peer,
             reader: reader_peer,
             writer: writer_peer,
+            records: Some(Records {
+                reader: records_reader_peer,
+                writer: records_writer_peer,
+            }),
+            reader_ancillary: ancillary_peer,
+            writer_ancillary: ancillary_this,
         };
 
         (this, peer)
@@ -71,27 +133,195 @@ impl Connected {
         Ok(())
     }
 
-    pub(super) fn try_read(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
-        self.reader.try_read(writer)
+    pub(super) fn try_read(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, ControlMessage)> {
+        let is_peek = flags.contains(SendRecvFlags::MSG_PEEK);
+
+        let mut ancillary = self.reader_ancillary.lock();
+
+        let Some(records) = self.records.as_ref() else {
+            let read_len = self.read_bytes(writer, ancillary.max_read_len(), is_peek)?;
+            let control_message = if is_peek {
+                ancillary.peek_read()
+            } else {
+                let read_ancillary = ancillary.pop_read(read_len);
+                drop(ancillary);
+                read_ancillary.receive()
+            };
+            return Ok((read_len, control_message));
+        };
+
+        let record = if is_peek {
+            records.reader.try_peek_item()?
+        } else {
+            records.reader.try_pop()?
+        };
+        let Some(record_len) = record else {
+            // The peer has shut down for writing and all the messages have been read.
+            return Ok((0, ControlMessage::default()));
+        };
+
+        // The bytes of a message are always written before its length is pushed, so the whole
+        // message must be available now.
+        let mut buf = vec![0u8; record_len as usize];
+        if !buf.is_empty() {
+            let mut buf_writer = VmWriter::from(buf.as_mut_slice()).to_fallible();
+            if is_peek {
+                self.reader.try_peek(&mut buf_writer)?;
+            } else {
+                self.reader.try_read(&mut buf_writer)?;
+            }
+        }
+        let control_message = if is_peek {
+            ancillary.peek_read()
+        } else {
+            let read_ancillary = ancillary.pop_read(buf.len());
+            drop(ancillary);
+            read_ancillary.receive()
+        };
+
+        // The part of the message that does not fit in the buffer is discarded.
+        let copied_len = writer.write(&mut VmReader::from(buf.as_slice()))?;
+
+        // With `MSG_TRUNC`, the real length of the message is returned even if it is truncated.
+        if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            Ok((buf.len(), control_message))
+        } else {
+            Ok((copied_len, control_message))
+        }
     }
 
-    pub(super) fn try_write(&self, reader: &mut dyn MultiRead) -> Result<usize> {
-        self.writer.try_write(reader)
+    /// Tries to read more bytes after some bytes have been read with `MSG_WAITALL`.
+    ///
+    /// Like Linux, the bytes are not read if they come with new ancillary data, because the
+    /// ancillary data cannot be received along with the bytes that have been read. `MSG_WAITALL`
+    /// also has no effect on `SOCK_SEQPACKET` sockets. This method returns `Ok(0)` in both cases.
+    pub(super) fn try_read_more(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        if self.records.is_some() {
+            return Ok(0);
+        }
+
+        let mut ancillary = self.reader_ancillary.lock();
+        if ancillary.has_unread_item() {
+            return Ok(0);
+        }
+
+        let read_len = self.read_bytes(writer, ancillary.max_read_len(), false)?;
+        // There are no files to receive, since the bytes do not start with new ancillary data.
+        ancillary.pop_read(read_len);
+
+        Ok(read_len)
+    }
+
+    /// Reads at most `max_len` bytes from the byte channel of a `SOCK_STREAM` socket.
+    fn read_bytes(
+        &self,
+        writer: &mut dyn MultiWrite,
+        max_len: usize,
+        is_peek: bool,
+    ) -> Result<usize> {
+        if max_len >= writer.sum_lens() {
+            return if is_peek {
+                self.reader.try_peek(writer)
+            } else {
+                self.reader.try_read(writer)
+            };
+        }
+
+        let mut buf = vec![0u8; max_len];
+        let mut buf_writer = VmWriter::from(buf.as_mut_slice()).to_fallible();
+        let read_len = if is_peek {
+            self.reader.try_peek(&mut buf_writer)?
+        } else {
+            self.reader.try_read(&mut buf_writer)?
+        };
+        writer.write(&mut VmReader::from(&buf[..read_len]))
+    }
+
+    pub(super) fn try_write(
+        &self,
+        reader: &mut dyn MultiRead,
+        control_message: &ControlMessage,
+    ) -> Result<usize> {
+        // The files must be dropped after the lock is released (see `InflightFiles`).
+        let mut files = InflightFiles::new(control_message.files())?;
+        let mut ancillary = self.writer_ancillary.lock();
+
+        let Some(records) = self.records.as_ref() else {
+            let written_len = self.writer.try_write(reader)?;
+            ancillary.push_written(written_len, control_message, &mut files, false);
+            return Ok(written_len);
+        };
+
+        let record_len = reader.sum_lens();
+        if record_len > DEFAULT_BUF_SIZE {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        if self.writer.is_shutdown() {
+            return_errno_with_message!(Errno::EPIPE, "the channel is shut down");
+        }
+        if self.writer.free_len() < record_len || records.writer.free_len() == 0 {
+            return_errno_with_message!(Errno::EAGAIN, "the channel is full");
+        }
+
+        // Since the free space can only grow when the lock is held, the message is written as a
+        // whole.
+        let mut buf = vec![0u8; record_len];
+        reader.read(&mut VmWriter::from(buf.as_mut_slice()))?;
+        if !buf.is_empty() {
+            self.writer
+                .try_write(&mut VmReader::from(buf.as_slice()).to_fallible())?;
+        }
+        ancillary.push_written(record_len, control_message, &mut files, true);
+
+        records
+            .writer
+            .try_push(record_len as u32)
+            .map_err(|(err, _)| err)?;
+
+        Ok(record_len)
     }
 
     pub(super) fn shutdown(&self, cmd: SockShutdownCmd) {
         if cmd.shut_read() {
             self.reader.shutdown();
+            if let Some(records) = self.records.as_ref() {
+                records.reader.shutdown();
+            }
         }
 
         if cmd.shut_write() {
             self.writer.shutdown();
+            if let Some(records) = self.records.as_ref() {
+                records.writer.shutdown();
+            }
         }
     }
 
+    /// Visits the in-flight files of the bytes to read.
+    pub(super) fn for_each_queued_file(&self, f: &mut dyn FnMut(&Arc<dyn FileLike>)) {
+        self.reader_ancillary.lock().for_each_file(f);
+    }
+
+    /// Takes out the in-flight files of the bytes to read.
+    pub(super) fn take_queued_files(&self) -> Vec<InflightFiles> {
+        self.reader_ancillary.lock().take_files()
+    }
+
+    pub(super) fn is_seqpacket(&self) -> bool {
+        self.records.is_some()
+    }
+
     pub(super) fn poll(&self, mask: IoEvents, mut poller: Option<&mut Poller>) -> IoEvents {
         // Note that `mask | IoEvents::ALWAYS_POLL` contains all the events we care about.
-        let reader_events = self.reader.poll(mask, poller.as_deref_mut());
+        let reader_events = match self.records.as_ref() {
+            Some(records) => records.reader.poll(mask, poller.as_deref_mut()),
+            None => self.reader.poll(mask, poller.as_deref_mut()),
+        };
         let writer_events = self.writer.poll(mask, poller);
 
         combine_io_events(mask, reader_events, writer_events)
@@ -102,7 +332,10 @@ impl Connected {
         observer: Weak<dyn Observer<IoEvents>>,
         mask: IoEvents,
     ) -> Result<()> {
-        self.reader.register_observer(observer.clone(), mask)?;
+        match self.records.as_ref() {
+            Some(records) => records.reader.register_observer(observer.clone(), mask)?,
+            None => self.reader.register_observer(observer.clone(), mask)?,
+        }
         self.writer.register_observer(observer, mask)?;
         Ok(())
     }
@@ -111,12 +344,21 @@ impl Connected {
         &self,
         observer: &Weak<dyn Observer<IoEvents>>,
     ) -> Option<Weak<dyn Observer<IoEvents>>> {
-        let reader_observer = self.reader.unregister_observer(observer);
+        let reader_observer = match self.records.as_ref() {
+            Some(records) => records.reader.unregister_observer(observer),
+            None => self.reader.unregister_observer(observer),
+        };
         let writer_observer = self.writer.unregister_observer(observer);
         reader_observer.or(writer_observer)
     }
 }
 
+/// The lengths of the messages that are sent and received via a `SOCK_SEQPACKET` socket.
+struct Records {
+    reader: Consumer<u32>,
+    writer: Producer<u32>,
+}
+
 pub(super) fn combine_io_events(
     mask: IoEvents,
     reader_events: IoEvents,
@@ -176,3 +418,6 @@ impl AddrView {
 }
 
 const DEFAULT_BUF_SIZE: usize = 65536;
+
+/// The maximum number of messages that can be buffered in a `SOCK_SEQPACKET` socket.
+const MAX_RECORDS: usize = 1024;
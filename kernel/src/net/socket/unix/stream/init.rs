@@ -22,16 +22,18 @@ pub(super) struct Init {
     writer_pollee: Pollee,
     is_read_shutdown: AtomicBool,
     is_write_shutdown: AtomicBool,
+    is_seqpacket: bool,
 }
 
 impl Init {
-    pub(super) fn new() -> Self {
+    pub(super) fn new(is_seqpacket: bool) -> Self {
         Self {
             addr: None,
             reader_pollee: Pollee::new(IoEvents::empty()),
             writer_pollee: Pollee::new(IoEvents::OUT),
             is_read_shutdown: AtomicBool::new(false),
             is_write_shutdown: AtomicBool::new(false),
+            is_seqpacket,
         }
     }
 
@@ -53,6 +55,7 @@ impl Init {
             writer_pollee,
             is_read_shutdown,
             is_write_shutdown,
+            is_seqpacket,
         } = self;
 
         let (this_conn, peer_conn) = Connected::new_pair(
@@ -60,6 +63,7 @@ impl Init {
             Some(peer_addr),
             Some(reader_pollee),
             Some(writer_pollee),
+            is_seqpacket,
         );
 
         if is_read_shutdown.into_inner() {
@@ -87,6 +91,7 @@ impl Init {
             self.writer_pollee,
             backlog,
             self.is_read_shutdown.into_inner(),
+            self.is_seqpacket,
         ))
     }
 
@@ -112,6 +117,10 @@ impl Init {
         self.addr.as_ref()
     }
 
+    pub(super) fn is_seqpacket(&self) -> bool {
+        self.is_seqpacket
+    }
+
     pub(super) fn poll(&self, mask: IoEvents, mut poller: Option<&mut Poller>) -> IoEvents {
         // To avoid loss of events, this must be compatible with
         // `Connected::poll`/`Listener::poll`.
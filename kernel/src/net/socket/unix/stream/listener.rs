@@ -13,11 +13,15 @@ use crate::{
     events::{IoEvents, Observer},
     fs::file_handle::FileLike,
     net::socket::{
-        unix::addr::{UnixSocketAddrBound, UnixSocketAddrKey},
+        unix::{
+            addr::{UnixSocketAddrBound, UnixSocketAddrKey},
+            UnixSocketInfo,
+        },
         SockShutdownCmd, SocketAddr,
     },
     prelude::*,
     process::signal::{Pollee, Poller},
+    util::net::SockType,
 };
 
 pub(super) struct Listener {
@@ -32,11 +36,12 @@ impl Listener {
         writer_pollee: Pollee,
         backlog: usize,
         is_shutdown: bool,
+        is_seqpacket: bool,
     ) -> Self {
         // Note that the I/O events can be correctly inherited from `Init`. There is no need to
         // explicitly call `Pollee::reset_io_events`.
         let backlog = BACKLOG_TABLE
-            .add_backlog(addr, reader_pollee, backlog, is_shutdown)
+            .add_backlog(addr, reader_pollee, backlog, is_shutdown, is_seqpacket)
             .unwrap();
         writer_pollee.del_events(IoEvents::OUT);
 
@@ -132,6 +137,7 @@ impl BacklogTable {
         pollee: Pollee,
         backlog: usize,
         is_shutdown: bool,
+        is_seqpacket: bool,
     ) -> Option<Arc<Backlog>> {
         let addr_key = addr.to_key();
 
@@ -141,7 +147,13 @@ impl BacklogTable {
             return None;
         }
 
-        let new_backlog = Arc::new(Backlog::new(addr, pollee, backlog, is_shutdown));
+        let new_backlog = Arc::new(Backlog::new(
+            addr,
+            pollee,
+            backlog,
+            is_shutdown,
+            is_seqpacket,
+        ));
         backlog_sockets.insert(addr_key, new_backlog.clone());
 
         Some(new_backlog)
@@ -162,10 +174,17 @@ pub(super) struct Backlog {
     backlog: AtomicUsize,
     incoming_conns: SpinLock<Option<VecDeque<Connected>>>,
     wait_queue: WaitQueue,
+    is_seqpacket: bool,
 }
 
 impl Backlog {
-    fn new(addr: UnixSocketAddrBound, pollee: Pollee, backlog: usize, is_shutdown: bool) -> Self {
+    fn new(
+        addr: UnixSocketAddrBound,
+        pollee: Pollee,
+        backlog: usize,
+        is_shutdown: bool,
+        is_seqpacket: bool,
+    ) -> Self {
         let incoming_sockets = if is_shutdown {
             None
         } else {
@@ -178,6 +197,7 @@ impl Backlog {
             backlog: AtomicUsize::new(backlog),
             incoming_conns: SpinLock::new(incoming_sockets),
             wait_queue: WaitQueue::new(),
+            is_seqpacket,
         }
     }
 
@@ -252,6 +272,16 @@ impl Backlog {
         &self,
         init: Init,
     ) -> core::result::Result<Connected, (Error, Init)> {
+        if init.is_seqpacket() != self.is_seqpacket {
+            return Err((
+                Error::with_message(
+                    Errno::EPROTOTYPE,
+                    "the listening socket has a different socket type",
+                ),
+                init,
+            ));
+        }
+
         let mut locked_incoming_conns = self.incoming_conns.lock();
 
         let Some(incoming_conns) = &mut *locked_incoming_conns else {
@@ -297,6 +327,24 @@ fn unregister_backlog(addr: &UnixSocketAddrKey) {
     BACKLOG_TABLE.remove_backlog(addr);
 }
 
+/// Gets the information of all the listening sockets.
+pub(in crate::net::socket::unix) fn listening_socket_infos() -> Vec<UnixSocketInfo> {
+    BACKLOG_TABLE
+        .backlog_sockets
+        .read()
+        .values()
+        .map(|backlog| UnixSocketInfo {
+            addr: backlog.addr().clone().into(),
+            type_: if backlog.is_seqpacket {
+                SockType::SOCK_SEQPACKET
+            } else {
+                SockType::SOCK_STREAM
+            },
+            is_listening: true,
+        })
+        .collect()
+}
+
 pub(super) fn get_backlog(server_key: &UnixSocketAddrKey) -> Result<Arc<Backlog>> {
     BACKLOG_TABLE.get_backlog(server_key).ok_or_else(|| {
         Error::with_message(
@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod ancillary;
 mod connected;
 mod init;
 mod listener;
 mod socket;
 
+pub(super) use listener::listening_socket_infos;
 pub use socket::UnixStreamSocket;
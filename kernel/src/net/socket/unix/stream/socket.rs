@@ -12,10 +12,15 @@ use super::{
 use crate::{
     events::{IoEvents, Observer},
     fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut, match_sock_option_ref,
     net::socket::{
-        unix::UnixSocketAddr,
+        options::{PassCred, SocketOption},
+        unix::{
+            gc::{collect_garbage, InflightFiles},
+            UnixSocketAddr,
+        },
         util::{send_recv_flags::SendRecvFlags, socket_addr::SocketAddr, MessageHeader},
-        SockShutdownCmd, Socket,
+        ControlMessage, SockShutdownCmd, Socket,
     },
     prelude::*,
     process::signal::{Pollable, Poller},
@@ -25,6 +30,8 @@ use crate::{
 pub struct UnixStreamSocket {
     state: RwMutex<Takeable<State>>,
     is_nonblocking: AtomicBool,
+    /// Whether the credentials of the peer should be received (i.e., `SO_PASSCRED`).
+    is_pass_cred: AtomicBool,
 }
 
 impl UnixStreamSocket {
@@ -32,6 +39,7 @@ impl UnixStreamSocket {
         Arc::new(Self {
             state: RwMutex::new(Takeable::new(State::Init(init))),
             is_nonblocking: AtomicBool::new(is_nonblocking),
+            is_pass_cred: AtomicBool::new(false),
         })
     }
 
@@ -39,6 +47,7 @@ impl UnixStreamSocket {
         Arc::new(Self {
             state: RwMutex::new(Takeable::new(State::Connected(connected))),
             is_nonblocking: AtomicBool::new(is_nonblocking),
+            is_pass_cred: AtomicBool::new(false),
         })
     }
 }
@@ -50,46 +59,102 @@ enum State {
 }
 
 impl UnixStreamSocket {
-    pub fn new(is_nonblocking: bool) -> Arc<Self> {
-        Self::new_init(Init::new(), is_nonblocking)
+    /// Creates a new socket.
+    ///
+    /// If `is_seqpacket` is true, the socket is a `SOCK_SEQPACKET` socket, which preserves
+    /// message boundaries. Otherwise, it is a `SOCK_STREAM` socket.
+    pub fn new(is_nonblocking: bool, is_seqpacket: bool) -> Arc<Self> {
+        Self::new_init(Init::new(is_seqpacket), is_nonblocking)
     }
 
-    pub fn new_pair(is_nonblocking: bool) -> (Arc<Self>, Arc<Self>) {
-        let (conn_a, conn_b) = Connected::new_pair(None, None, None, None);
+    pub fn new_pair(is_nonblocking: bool, is_seqpacket: bool) -> (Arc<Self>, Arc<Self>) {
+        let (conn_a, conn_b) = Connected::new_pair(None, None, None, None, is_seqpacket);
         (
             Self::new_connected(conn_a, is_nonblocking),
             Self::new_connected(conn_b, is_nonblocking),
         )
     }
 
-    fn send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
-        if self.is_nonblocking() {
-            self.try_send(reader, flags)
+    fn send(
+        &self,
+        reader: &mut dyn MultiRead,
+        control_message: &ControlMessage,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            self.try_send(reader, control_message, flags)
         } else {
-            self.wait_events(IoEvents::OUT, || self.try_send(reader, flags))
+            self.wait_events(IoEvents::OUT, || {
+                self.try_send(reader, control_message, flags)
+            })
         }
     }
 
-    fn try_send(&self, buf: &mut dyn MultiRead, _flags: SendRecvFlags) -> Result<usize> {
+    fn try_send(
+        &self,
+        buf: &mut dyn MultiRead,
+        control_message: &ControlMessage,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
         match self.state.read().as_ref() {
-            State::Connected(connected) => connected.try_write(buf),
+            // In Linux, `SOCK_SEQPACKET` sockets never raise `SIGPIPE`.
+            State::Connected(connected) if connected.is_seqpacket() => {
+                connected.try_write(buf, control_message)
+            }
+            State::Connected(connected) => {
+                flags.check_sigpipe(connected.try_write(buf, control_message))
+            }
             State::Init(_) | State::Listen(_) => {
                 return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected")
             }
         }
     }
 
-    fn recv(&self, writer: &mut dyn MultiWrite, flags: SendRecvFlags) -> Result<usize> {
-        if self.is_nonblocking() {
-            self.try_recv(writer, flags)
-        } else {
-            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))
+    fn recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, ControlMessage)> {
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            return self.try_recv(writer, flags);
         }
+
+        let (mut recv_bytes, control_message) =
+            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))?;
+
+        if !flags.contains(SendRecvFlags::MSG_WAITALL) || flags.contains(SendRecvFlags::MSG_PEEK) {
+            return Ok((recv_bytes, control_message));
+        }
+
+        // With `MSG_WAITALL`, keep receiving until the buffer is full, the connection is closed,
+        // or an error occurs. The error is not reported since some data has been received. Like
+        // Linux, the receive operation also stops after files are received.
+        while recv_bytes > 0 && !writer.is_empty() && control_message.files().is_empty() {
+            match self.wait_events(IoEvents::IN, || self.try_recv_more(writer)) {
+                Ok(0) | Err(_) => break,
+                Ok(new_bytes) => recv_bytes += new_bytes,
+            }
+        }
+
+        Ok((recv_bytes, control_message))
     }
 
-    fn try_recv(&self, buf: &mut dyn MultiWrite, _flags: SendRecvFlags) -> Result<usize> {
+    fn try_recv(
+        &self,
+        buf: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, ControlMessage)> {
         match self.state.read().as_ref() {
-            State::Connected(connected) => connected.try_read(buf),
+            State::Connected(connected) => connected.try_read(buf, flags),
+            State::Init(_) | State::Listen(_) => {
+                return_errno_with_message!(Errno::EINVAL, "the socket is not connected")
+            }
+        }
+    }
+
+    fn try_recv_more(&self, buf: &mut dyn MultiWrite) -> Result<usize> {
+        match self.state.read().as_ref() {
+            State::Connected(connected) => connected.try_read_more(buf),
             State::Init(_) | State::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not connected")
             }
@@ -149,6 +214,34 @@ impl UnixStreamSocket {
     }
 }
 
+impl UnixStreamSocket {
+    /// Visits the in-flight files in the receive queue.
+    pub(in crate::net::socket::unix) fn for_each_queued_file(
+        &self,
+        f: &mut dyn FnMut(&Arc<dyn FileLike>),
+    ) {
+        if let State::Connected(connected) = self.state.read().as_ref() {
+            connected.for_each_queued_file(f);
+        }
+    }
+
+    /// Takes out the in-flight files in the receive queue.
+    pub(in crate::net::socket::unix) fn take_queued_files(&self) -> Vec<InflightFiles> {
+        match self.state.read().as_ref() {
+            State::Connected(connected) => connected.take_queued_files(),
+            State::Init(_) | State::Listen(_) => Vec::new(),
+        }
+    }
+}
+
+impl Drop for UnixStreamSocket {
+    fn drop(&mut self) {
+        // The files that are not received are discarded with the socket.
+        drop(self.take_queued_files());
+        collect_garbage();
+    }
+}
+
 impl Pollable for UnixStreamSocket {
     fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
         let inner = self.state.read();
@@ -168,14 +261,14 @@ impl FileLike for UnixStreamSocket {
     fn read(&self, writer: &mut VmWriter) -> Result<usize> {
         // TODO: Set correct flags
         let flags = SendRecvFlags::empty();
-        let read_len = self.recv(writer, flags)?;
+        let (read_len, _) = self.recv(writer, flags)?;
         Ok(read_len)
     }
 
     fn write(&self, reader: &mut VmReader) -> Result<usize> {
         // TODO: Set correct flags
         let flags = SendRecvFlags::empty();
-        self.send(reader, flags)
+        self.send(reader, &ControlMessage::default(), flags)
     }
 
     fn status_flags(&self) -> StatusFlags {
@@ -324,19 +417,15 @@ impl Socket for UnixStreamSocket {
         message_header: MessageHeader,
         flags: SendRecvFlags,
     ) -> Result<usize> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
         let MessageHeader {
             control_message, ..
         } = message_header;
 
-        if control_message.is_some() {
-            // TODO: Support sending control message
-            warn!("sending control message is not supported");
-        }
-
-        self.send(reader, flags)
+        self.send(reader, &control_message.unwrap_or_default(), flags)
     }
 
     fn recvmsg(
@@ -344,15 +433,42 @@ impl Socket for UnixStreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, MessageHeader)> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
-        let received_bytes = self.recv(writer, flags)?;
+        let (received_bytes, mut control_message) = self.recv(writer, flags)?;
 
-        // TODO: Receive control message
+        if !self.is_pass_cred.load(Ordering::Relaxed) {
+            control_message.clear_credentials();
+        }
+        let control_message = (!control_message.is_empty()).then_some(control_message);
 
-        let message_header = MessageHeader::new(None, None);
+        let message_header = MessageHeader::new(None, control_message);
 
         Ok((received_bytes, message_header))
     }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                socket_pass_cred.set(self.is_pass_cred.load(Ordering::Relaxed));
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let is_pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*is_pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
 }
@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Control messages (i.e., ancillary data) of `sendmsg`/`recvmsg`.
+//!
+//! Currently, only the `SOL_SOCKET`-level control messages are supported, i.e., `SCM_RIGHTS` for
+//! passing files and `SCM_CREDENTIALS` for passing process credentials.
+//!
+//! See <https://man7.org/linux/man-pages/man3/cmsg.3.html> and
+//! <https://man7.org/linux/man-pages/man7/unix.7.html>.
+
+use align_ext::AlignExt;
+
+use crate::{
+    fs::{file_handle::FileLike, file_table::FdFlags},
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::PosixThreadExt},
+};
+
+/// Control messages carried by [`MessageHeader`].
+///
+/// [`MessageHeader`]: super::MessageHeader
+#[derive(Clone, Default)]
+pub struct ControlMessage {
+    /// The files passed via `SCM_RIGHTS`.
+    files: Vec<Arc<dyn FileLike>>,
+    /// The credentials passed via `SCM_CREDENTIALS`.
+    credentials: Option<CUcred>,
+}
+
+impl Debug for ControlMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ControlMessage")
+            .field("num_files", &self.files.len())
+            .field("credentials", &self.credentials)
+            .finish()
+    }
+}
+
+/// The control message header (i.e., `struct cmsghdr`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CControlMessageHeader {
+    /// The length of the header and the data.
+    len: usize,
+    level: i32,
+    type_: i32,
+}
+
+/// The credentials of a process (i.e., `struct ucred`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, PartialEq, Eq)]
+pub struct CUcred {
+    pub pid: u32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl CUcred {
+    /// Returns the credentials of the current process.
+    pub fn current() -> Self {
+        let credentials = current_thread!().as_posix_thread().unwrap().credentials();
+
+        Self {
+            pid: current!().pid(),
+            uid: credentials.euid().into(),
+            gid: credentials.egid().into(),
+        }
+    }
+
+    /// Checks whether the current process is allowed to send the credentials.
+    ///
+    /// A process can only send its own PID and user/group IDs, unless it has the corresponding
+    /// capabilities.
+    fn check_current(&self) -> Result<()> {
+        let credentials = current_thread!().as_posix_thread().unwrap().credentials();
+        let capset = credentials.effective_capset();
+
+        let is_pid_valid = self.pid == current!().pid() || capset.contains(CapSet::SYS_ADMIN);
+        let is_uid_valid = [credentials.ruid(), credentials.euid(), credentials.suid()]
+            .into_iter()
+            .any(|uid| u32::from(uid) == self.uid)
+            || capset.contains(CapSet::SETUID);
+        let is_gid_valid = [credentials.rgid(), credentials.egid(), credentials.sgid()]
+            .into_iter()
+            .any(|gid| u32::from(gid) == self.gid)
+            || capset.contains(CapSet::SETGID);
+
+        if !is_pid_valid || !is_uid_valid || !is_gid_valid {
+            return_errno_with_message!(Errno::EPERM, "the credentials cannot be sent");
+        }
+
+        Ok(())
+    }
+}
+
+/// The socket-level control message types.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+enum ControlMessageType {
+    SCM_RIGHTS = 1,
+    SCM_CREDENTIALS = 2,
+}
+
+/// The level of socket-level control messages (i.e., `SOL_SOCKET`).
+const SOL_SOCKET: i32 = 1;
+
+/// The maximum number of files that can be passed in one message (i.e., `SCM_MAX_FD`).
+const MAX_FILES: usize = 253;
+
+/// The maximum length of the control messages (i.e., the default value of `optmem_max`).
+const MAX_CONTROL_LEN: usize = 20480;
+
+const HEADER_LEN: usize = size_of::<CControlMessageHeader>();
+
+impl ControlMessage {
+    /// Creates control messages with the files and credentials.
+    pub fn new(files: Vec<Arc<dyn FileLike>>, credentials: Option<CUcred>) -> Self {
+        Self { files, credentials }
+    }
+
+    /// Returns the files passed via `SCM_RIGHTS`.
+    pub fn files(&self) -> &[Arc<dyn FileLike>] {
+        &self.files
+    }
+
+    /// Returns the credentials passed via `SCM_CREDENTIALS`.
+    pub fn credentials(&self) -> Option<&CUcred> {
+        self.credentials.as_ref()
+    }
+
+    /// Removes the credentials.
+    ///
+    /// The credentials should only be received if `SO_PASSCRED` is enabled.
+    pub fn clear_credentials(&mut self) {
+        self.credentials = None;
+    }
+
+    /// Returns whether there are no control messages.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.credentials.is_none()
+    }
+
+    /// Reads the control messages from the user space.
+    ///
+    /// This method returns `None` if there are no supported control messages.
+    pub fn read_from_user(ctx: &Context, addr: Vaddr, len: usize) -> Result<Option<Self>> {
+        if addr == 0 || len < HEADER_LEN {
+            return Ok(None);
+        }
+        if len > MAX_CONTROL_LEN {
+            return_errno_with_message!(Errno::ENOBUFS, "the control messages are too long");
+        }
+
+        let mut buf = vec![0u8; len];
+        ctx.get_user_space()
+            .read_bytes(addr, &mut VmWriter::from(buf.as_mut_slice()))?;
+
+        let mut control_message = Self::default();
+
+        let mut rest = buf.as_slice();
+        while rest.len() >= HEADER_LEN {
+            let header = CControlMessageHeader::from_bytes(rest);
+            if header.len < HEADER_LEN || header.len > rest.len() {
+                return_errno_with_message!(Errno::EINVAL, "the control message is malformed");
+            }
+
+            let data = &rest[HEADER_LEN..header.len];
+            rest = &rest[header.len.align_up(size_of::<usize>()).min(rest.len())..];
+
+            if header.level != SOL_SOCKET {
+                warn!(
+                    "control messages of level {} are not supported",
+                    header.level
+                );
+                continue;
+            }
+
+            match ControlMessageType::try_from(header.type_)? {
+                ControlMessageType::SCM_RIGHTS => control_message.read_files(ctx, data)?,
+                ControlMessageType::SCM_CREDENTIALS => {
+                    if data.len() != size_of::<CUcred>() {
+                        return_errno_with_message!(Errno::EINVAL, "the credentials are invalid");
+                    }
+                    let credentials = CUcred::from_bytes(data);
+                    credentials.check_current()?;
+                    control_message.credentials = Some(credentials);
+                }
+            }
+        }
+
+        if control_message.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(control_message))
+        }
+    }
+
+    fn read_files(&mut self, ctx: &Context, data: &[u8]) -> Result<()> {
+        let num_fds = data.len() / size_of::<i32>();
+        if num_fds == 0 || self.files.len() + num_fds > MAX_FILES {
+            return_errno_with_message!(Errno::EINVAL, "the number of files is invalid");
+        }
+
+        let file_table = ctx.process.file_table().lock();
+        for fd_bytes in data.chunks_exact(size_of::<i32>()) {
+            let fd = i32::from_bytes(fd_bytes);
+            self.files.push(file_table.get_file(fd)?.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Writes the control messages to the user space.
+    ///
+    /// The passed files are installed to the file table of the current process. The files and
+    /// credentials that do not fit in the buffer are discarded.
+    ///
+    /// This method returns the number of bytes written and whether the control messages are
+    /// truncated (i.e., whether `MSG_CTRUNC` should be reported).
+    pub fn write_to_user(
+        self,
+        ctx: &Context,
+        addr: Vaddr,
+        max_len: usize,
+        is_cloexec: bool,
+    ) -> Result<(usize, bool)> {
+        let mut buf = Vec::new();
+        let mut is_truncated = false;
+
+        if let Some(credentials) = self.credentials {
+            if buf.len() + HEADER_LEN + size_of::<CUcred>() <= max_len {
+                push_message(
+                    &mut buf,
+                    ControlMessageType::SCM_CREDENTIALS,
+                    credentials.as_bytes(),
+                );
+            } else {
+                is_truncated = true;
+            }
+        }
+
+        if !self.files.is_empty() {
+            let max_fds = (max_len.saturating_sub(buf.len() + HEADER_LEN)) / size_of::<i32>();
+            let num_fds = self.files.len().min(max_fds);
+            if num_fds < self.files.len() {
+                is_truncated = true;
+            }
+
+            if num_fds > 0 {
+                let fd_flags = if is_cloexec {
+                    FdFlags::CLOEXEC
+                } else {
+                    FdFlags::empty()
+                };
+
+                let mut file_table = ctx.process.file_table().lock();
+                let fds: Vec<u8> = self
+                    .files
+                    .into_iter()
+                    .take(num_fds)
+                    .flat_map(|file| file_table.insert(file, fd_flags).to_ne_bytes())
+                    .collect();
+                drop(file_table);
+
+                push_message(&mut buf, ControlMessageType::SCM_RIGHTS, &fds);
+            }
+        }
+
+        // The last message does not need to be padded if there is no space.
+        let written_len = buf.len().min(max_len);
+        ctx.get_user_space()
+            .write_bytes(addr, &mut VmReader::from(&buf[..written_len]))?;
+
+        Ok((written_len, is_truncated))
+    }
+}
+
+fn push_message(buf: &mut Vec<u8>, type_: ControlMessageType, data: &[u8]) {
+    let header = CControlMessageHeader {
+        len: HEADER_LEN + data.len(),
+        level: SOL_SOCKET,
+        type_: type_ as i32,
+    };
+
+    buf.extend_from_slice(header.as_bytes());
+    buf.extend_from_slice(data);
+    buf.resize(buf.len().align_up(size_of::<usize>()), 0);
+}
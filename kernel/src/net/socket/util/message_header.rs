@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::socket_addr::SocketAddr;
+use super::{control_message::ControlMessage, socket_addr::SocketAddr};
 use crate::prelude::*;
 
 /// Message header used for sendmsg/recvmsg.
@@ -23,10 +23,9 @@ impl MessageHeader {
     pub fn addr(&self) -> Option<&SocketAddr> {
         self.addr.as_ref()
     }
-}
 
-/// Control message carried by MessageHeader.
-///
-/// TODO: Implement the struct. The struct is empty now.
-#[derive(Debug)]
-pub struct ControlMessage;
+    /// Takes the control message out of the header.
+    pub fn take_control_message(&mut self) -> Option<ControlMessage> {
+        self.control_message.take()
+    }
+}
@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod control_message;
 mod message_header;
 pub mod options;
 pub mod send_recv_flags;
 pub mod shutdown_cmd;
 pub mod socket_addr;
 
+pub use control_message::{CUcred, ControlMessage};
 pub use message_header::MessageHeader;
@@ -9,7 +9,8 @@ use aster_bigtcp::socket::{
 use crate::{
     match_sock_option_mut, match_sock_option_ref,
     net::socket::options::{
-        Error as SocketError, Linger, RecvBuf, ReuseAddr, ReusePort, SendBuf, SocketOption,
+        Error as SocketError, KeepAlive, Linger, RecvBuf, RecvTimeout, ReuseAddr, ReusePort,
+        SendBuf, SendTimeout, SocketOption,
     },
     prelude::*,
 };
@@ -24,6 +25,9 @@ pub struct SocketOptionSet {
     send_buf: u32,
     recv_buf: u32,
     linger: LingerOption,
+    keep_alive: bool,
+    recv_timeout: Option<Duration>,
+    send_timeout: Option<Duration>,
 }
 
 impl SocketOptionSet {
@@ -36,6 +40,9 @@ impl SocketOptionSet {
             send_buf: TCP_SEND_BUF_LEN as u32,
             recv_buf: TCP_RECV_BUF_LEN as u32,
             linger: LingerOption::default(),
+            keep_alive: false,
+            recv_timeout: None,
+            send_timeout: None,
         }
     }
 
@@ -48,6 +55,9 @@ impl SocketOptionSet {
             send_buf: UDP_SEND_PAYLOAD_LEN as u32,
             recv_buf: UDP_RECV_PAYLOAD_LEN as u32,
             linger: LingerOption::default(),
+            keep_alive: false,
+            recv_timeout: None,
+            send_timeout: None,
         }
     }
 
@@ -87,6 +97,18 @@ impl SocketOptionSet {
                 let linger = self.linger();
                 socket_linger.set(linger);
             },
+            socket_keep_alive: KeepAlive => {
+                let keep_alive = self.keep_alive();
+                socket_keep_alive.set(keep_alive);
+            },
+            socket_recv_timeout: RecvTimeout => {
+                let recv_timeout = self.recv_timeout();
+                socket_recv_timeout.set(recv_timeout);
+            },
+            socket_send_timeout: SendTimeout => {
+                let send_timeout = self.send_timeout();
+                socket_send_timeout.set(send_timeout);
+            },
             _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
         });
         Ok(())
@@ -96,20 +118,16 @@ impl SocketOptionSet {
     pub fn set_option(&mut self, option: &dyn SocketOption) -> Result<()> {
         match_sock_option_ref!(option, {
             socket_recv_buf: RecvBuf => {
+                // Linux doubles the value to allow space for bookkeeping overhead. See
+                // <https://elixir.bootlin.com/linux/v6.0.9/source/net/core/sock.c#L1219>.
                 let recv_buf = socket_recv_buf.get().unwrap();
-                if *recv_buf <= MIN_RECVBUF {
-                    self.set_recv_buf(MIN_RECVBUF);
-                } else {
-                    self.set_recv_buf(*recv_buf);
-                }
+                let recv_buf = (*recv_buf).min(MAX_BUF) * 2;
+                self.set_recv_buf(recv_buf.max(MIN_RECVBUF));
             },
             socket_send_buf: SendBuf => {
                 let send_buf = socket_send_buf.get().unwrap();
-                if *send_buf <= MIN_SENDBUF {
-                    self.set_send_buf(MIN_SENDBUF);
-                } else {
-                    self.set_send_buf(*send_buf);
-                }
+                let send_buf = (*send_buf).min(MAX_BUF) * 2;
+                self.set_send_buf(send_buf.max(MIN_SENDBUF));
             },
             socket_reuse_addr: ReuseAddr => {
                 let reuse_addr = socket_reuse_addr.get().unwrap();
@@ -123,6 +141,18 @@ impl SocketOptionSet {
                 let linger = socket_linger.get().unwrap();
                 self.set_linger(*linger);
             },
+            socket_keep_alive: KeepAlive => {
+                let keep_alive = socket_keep_alive.get().unwrap();
+                self.set_keep_alive(*keep_alive);
+            },
+            socket_recv_timeout: RecvTimeout => {
+                let recv_timeout = socket_recv_timeout.get().unwrap();
+                self.set_recv_timeout(*recv_timeout);
+            },
+            socket_send_timeout: SendTimeout => {
+                let send_timeout = socket_send_timeout.get().unwrap();
+                self.set_send_timeout(*send_timeout);
+            },
             _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
         });
 
@@ -133,6 +163,11 @@ impl SocketOptionSet {
 pub const MIN_SENDBUF: u32 = 2304;
 pub const MIN_RECVBUF: u32 = 2304;
 
+/// The maximum value that can be set for `SO_SNDBUF` and `SO_RCVBUF`, before doubling.
+///
+/// This corresponds to the default value of `net.core.wmem_max` and `net.core.rmem_max` in Linux.
+const MAX_BUF: u32 = 212992;
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct LingerOption {
     is_on: bool,
@@ -1,6 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        signal::{constants::SIGPIPE, signals::kernel::KernelSignal},
+    },
+};
 
 bitflags! {
     /// Flags used for send/recv.
@@ -32,16 +38,41 @@ bitflags! {
         // const MSG_EOF         MSG_FIN
         const MSG_NO_SHARED_FRAGS = 0x80000; /* sendpage() internal : page frags are not shared */
         const MSG_SENDPAGE_DECRYPTED	= 0x100000; /* sendpage() internal : page may carry plain text and require encryption */
+        const MSG_CMSG_CLOEXEC = 0x40000000;	/* Set close_on_exec for file descriptor received through SCM_RIGHTS */
     }
 }
 
 impl SendRecvFlags {
     fn supported_flags() -> Self {
-        SendRecvFlags::empty()
+        SendRecvFlags::MSG_PEEK
+            | SendRecvFlags::MSG_TRUNC
+            | SendRecvFlags::MSG_DONTWAIT
+            | SendRecvFlags::MSG_WAITALL
+            | SendRecvFlags::MSG_NOSIGNAL
+            // `MSG_CMSG_CLOEXEC` is handled when the control messages are written to the user
+            // space.
+            | SendRecvFlags::MSG_CMSG_CLOEXEC
     }
 
     pub fn is_all_supported(&self) -> bool {
         let supported_flags = Self::supported_flags();
         supported_flags.contains(*self)
     }
+
+    /// Raises `SIGPIPE` for the current thread if `result` is an `EPIPE` error, unless
+    /// `MSG_NOSIGNAL` is specified.
+    ///
+    /// Connection-oriented sockets should call this method with the result of sending data. In
+    /// Linux, datagram sockets never raise `SIGPIPE`.
+    pub fn check_sigpipe<T>(&self, result: Result<T>) -> Result<T> {
+        let is_broken_pipe = matches!(&result, Err(err) if err.error() == Errno::EPIPE);
+
+        if is_broken_pipe && !self.contains(SendRecvFlags::MSG_NOSIGNAL) {
+            let thread = current_thread!();
+            let posix_thread = thread.as_posix_thread().unwrap();
+            posix_thread.enqueue_signal(Box::new(KernelSignal::new(SIGPIPE)));
+        }
+
+        result
+    }
 }
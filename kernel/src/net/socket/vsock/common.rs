@@ -6,6 +6,7 @@ use aster_virtio::device::socket::{
     connect::{ConnectionInfo, VsockEvent, VsockEventType},
     device::SocketDevice,
     error::SocketError,
+    header::{SeqPacketFlags, VsockType},
 };
 
 use super::{
@@ -16,7 +17,7 @@ use super::{
         listen::Listen,
     },
 };
-use crate::{events::IoEvents, prelude::*, return_errno_with_message, util::MultiRead};
+use crate::{prelude::*, return_errno_with_message, util::MultiRead};
 
 /// Manage all active sockets
 pub struct VsockSpace {
@@ -189,17 +190,66 @@ impl VsockSpace {
             .map_err(|_| Error::with_message(Errno::EIO, "cannot send credit update packet"))
     }
 
-    /// Send a data packet
-    pub fn send(&self, reader: &mut dyn MultiRead, info: &mut ConnectionInfo) -> Result<()> {
+    /// Send data packets
+    ///
+    /// For stream sockets, as much data as the peer has room for is sent. For seqpacket sockets,
+    /// the data is sent as one message only if the peer has room for the whole message. If
+    /// nothing can be sent, a credit request is sent to the peer and this method fails with
+    /// `EAGAIN` without consuming the data.
+    ///
+    /// Returns the number of bytes sent.
+    pub fn send(&self, reader: &mut dyn MultiRead, info: &mut ConnectionInfo) -> Result<usize> {
+        let total_len = reader.sum_lens();
+        let peer_free = info.peer_free() as usize;
+
+        let (len, has_room, flags) = match info.socket_type {
+            VsockType::Stream => {
+                if total_len == 0 {
+                    return Ok(0);
+                }
+                (
+                    total_len.min(peer_free),
+                    peer_free > 0,
+                    SeqPacketFlags::empty(),
+                )
+            }
+            VsockType::SeqPacket => {
+                if total_len > info.peer_buf_alloc() as usize {
+                    return_errno_with_message!(
+                        Errno::EMSGSIZE,
+                        "the message is larger than the receive buffer of the peer"
+                    );
+                }
+                (
+                    total_len,
+                    peer_free >= total_len,
+                    SeqPacketFlags::VIRTIO_VSOCK_SEQ_EOM,
+                )
+            }
+        };
+
+        let mut driver = self.driver.disable_irq().lock();
+
+        if !has_room {
+            // Request an update of the cached peer credit, if we haven't already done so.
+            if !info.has_pending_credit_request {
+                driver.credit_request(info).map_err(|_| {
+                    Error::with_message(Errno::EIO, "cannot send credit request packet")
+                })?;
+                info.has_pending_credit_request = true;
+            }
+            return_errno_with_message!(Errno::EAGAIN, "the peer does not have enough buffer space");
+        }
+
         // FIXME: Creating this buffer should be avoided
         // if the underlying driver can accept reader.
-        let mut buffer = vec![0u8; reader.sum_lens()];
+        let mut buffer = vec![0u8; len];
         reader.read(&mut VmWriter::from(buffer.as_mut_slice()))?;
 
-        let mut driver = self.driver.disable_irq().lock();
         driver
-            .send(&buffer, info)
-            .map_err(|_| Error::with_message(Errno::EIO, "cannot send data packet"))
+            .send(&buffer, info, flags)
+            .map_err(|_| Error::with_message(Errno::EIO, "cannot send data packet"))?;
+        Ok(len)
     }
 
     /// Poll for each event from the driver
@@ -220,6 +270,7 @@ impl VsockSpace {
                 .get(&event.into())
             {
                 connected.update_info(&event);
+                connected.update_io_events();
             }
 
             // Response to the event
@@ -234,14 +285,28 @@ impl VsockSpace {
                         );
                     };
                     let peer = event.source;
-                    let connected = Arc::new(Connected::new(peer.into(), listen.addr()));
+                    let connected = Arc::new(Connected::new(
+                        peer.into(),
+                        listen.addr(),
+                        listen.socket_type(),
+                    ));
                     connected.update_info(&event);
-                    listen.push_incoming(connected).unwrap();
+
+                    // Refuse the connection if the socket types mismatch or the backlog is full.
+                    if event.socket_type != listen.socket_type()
+                        || listen.push_incoming(connected.clone()).is_err()
+                    {
+                        driver.reset(&connected.get_info()).map_err(|_| {
+                            Error::with_message(Errno::EIO, "cannot send reset packet")
+                        })?;
+                        continue;
+                    }
                     listen.update_io_events();
                 }
                 VsockEventType::ConnectionResponse => {
-                    let connecting_sockets = self.connecting_sockets.disable_irq().lock();
-                    let Some(connecting) = connecting_sockets.get(&event.destination.into()) else {
+                    let mut connecting_sockets = self.connecting_sockets.disable_irq().lock();
+                    let Some(connecting) = connecting_sockets.remove(&event.destination.into())
+                    else {
                         return_errno_with_message!(
                             Errno::EINVAL,
                             "connected event can only be handled by connecting socket"
@@ -253,14 +318,32 @@ impl VsockSpace {
                         connecting.local_addr()
                     );
                     connecting.update_info(&event);
-                    connecting.add_events(IoEvents::IN);
+
+                    // Move the connection to the connected sockets right now, so the data that the
+                    // peer sends before the socket is used again will not be lost.
+                    let connected = Arc::new(Connected::from_connecting(connecting.clone()));
+                    connected.update_io_events();
+                    self.insert_connected_socket(connected.id(), connected.clone());
+                    connecting.set_result(Ok(connected));
                 }
                 VsockEventType::Disconnected { .. } => {
+                    // The peer refuses the connection.
+                    if let Some(connecting) =
+                        self.remove_connecting_socket(&event.destination.into())
+                    {
+                        connecting.set_result(Err(Error::with_message(
+                            Errno::ECONNRESET,
+                            "the connection is refused by the peer",
+                        )));
+                        continue;
+                    }
+
                     let connected_sockets = self.connected_sockets.read_irq_disabled();
                     let Some(connected) = connected_sockets.get(&event.into()) else {
                         return_errno_with_message!(Errno::ENOTCONN, "the socket hasn't connected");
                     };
                     connected.set_peer_requested_shutdown();
+                    connected.update_io_events();
                 }
                 VsockEventType::Received { .. } => {}
                 VsockEventType::CreditRequest => {
@@ -288,12 +371,15 @@ impl VsockSpace {
         driver
             .poll(|event, body| {
                 // Deal with Received before the buffer are recycled.
-                if let VsockEventType::Received { .. } = event.event_type {
+                if let VsockEventType::Received { flags, .. } = event.event_type {
                     // Only consider the connected socket and copy body to buffer
                     let connected_sockets = self.connected_sockets.read_irq_disabled();
-                    let connected = connected_sockets.get(&event.into()).unwrap();
+                    let Some(connected) = connected_sockets.get(&event.into()) else {
+                        debug!("ignore data for an unconnected socket");
+                        return Ok(Some(event));
+                    };
                     debug!("Rw matches a connection with id {:?}", connected.id());
-                    if !connected.add_connection_buffer(body) {
+                    if !connected.add_connection_buffer(body, flags) {
                         return Err(SocketError::BufferTooShort);
                     }
                     connected.update_io_events();
@@ -8,6 +8,7 @@ use spin::Once;
 
 pub mod addr;
 pub mod common;
+pub mod options;
 pub mod stream;
 pub use addr::VsockSocketAddr;
 pub use stream::VsockStreamSocket;
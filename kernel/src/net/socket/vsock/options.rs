@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use crate::{
+    impl_socket_options, match_sock_option_mut, match_sock_option_ref,
+    net::socket::options::SocketOption, prelude::*,
+};
+
+impl_socket_options!(
+    pub struct ConnectTimeout(Duration);
+);
+
+/// The time limit for connecting if `SO_VM_SOCKETS_CONNECT_TIMEOUT` is not set, which is the same
+/// as Linux.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Vsock-level options (i.e., the options at the `AF_VSOCK` level).
+#[derive(Debug, Clone, Copy, CopyGetters, Setters)]
+#[get_copy = "pub"]
+#[set = "pub"]
+pub struct VsockOptionSet {
+    connect_timeout: Duration,
+}
+
+impl VsockOptionSet {
+    pub fn new() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        }
+    }
+
+    /// Gets vsock-level options.
+    pub fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            vsock_connect_timeout: ConnectTimeout => {
+                let connect_timeout = self.connect_timeout();
+                vsock_connect_timeout.set(connect_timeout);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+        Ok(())
+    }
+
+    /// Sets vsock-level options.
+    pub fn set_option(&mut self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            vsock_connect_timeout: ConnectTimeout => {
+                let connect_timeout = vsock_connect_timeout.get().unwrap();
+                // Like Linux, a zero timeout restores the default one.
+                // Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/net/vmw_vsock/af_vsock.c>
+                if connect_timeout.is_zero() {
+                    self.set_connect_timeout(DEFAULT_CONNECT_TIMEOUT);
+                } else {
+                    self.set_connect_timeout(*connect_timeout);
+                }
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+        Ok(())
+    }
+}
+
+impl Default for VsockOptionSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use aster_virtio::device::socket::connect::{ConnectionInfo, VsockEvent};
+use aster_virtio::device::socket::{
+    connect::{ConnectionInfo, VsockEvent},
+    header::{SeqPacketFlags, VsockType},
+};
 
 use super::connecting::Connecting;
 use crate::{
@@ -14,7 +17,7 @@ use crate::{
     util::{ring_buffer::RingBuffer, MultiRead, MultiWrite},
 };
 
-const PER_CONNECTION_BUFFER_CAPACITY: usize = 4096;
+pub(super) const PER_CONNECTION_BUFFER_CAPACITY: usize = 4096;
 
 pub struct Connected {
     connection: SpinLock<Connection>,
@@ -23,9 +26,13 @@ pub struct Connected {
 }
 
 impl Connected {
-    pub fn new(peer_addr: VsockSocketAddr, local_addr: VsockSocketAddr) -> Self {
+    pub fn new(
+        peer_addr: VsockSocketAddr,
+        local_addr: VsockSocketAddr,
+        socket_type: VsockType,
+    ) -> Self {
         Self {
-            connection: SpinLock::new(Connection::new(peer_addr, local_addr.port)),
+            connection: SpinLock::new(Connection::new(peer_addr, local_addr.port, socket_type)),
             id: ConnectionID::new(local_addr, peer_addr),
             pollee: Pollee::new(IoEvents::empty()),
         }
@@ -38,6 +45,7 @@ impl Connected {
             pollee: Pollee::new(IoEvents::empty()),
         }
     }
+
     pub fn peer_addr(&self) -> VsockSocketAddr {
         self.id.peer_addr
     }
@@ -50,33 +58,59 @@ impl Connected {
         self.id
     }
 
-    pub fn try_recv(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+    pub fn try_recv(&self, writer: &mut dyn MultiWrite, flags: SendRecvFlags) -> Result<usize> {
         let mut connection = self.connection.disable_irq().lock();
-        let bytes_read = connection.buffer.read_fallible(writer)?;
-        connection.info.done_forwarding(bytes_read);
-
-        match bytes_read {
-            0 => {
-                if !connection.is_peer_requested_shutdown() {
-                    return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty");
-                } else {
-                    return_errno_with_message!(Errno::ECONNRESET, "the connection is reset");
-                }
+
+        let bytes_read = if connection.records.is_some() {
+            connection.recv_message(writer, flags)?
+        } else {
+            connection.recv_bytes(writer, flags)?
+        };
+
+        let Some(bytes_read) = bytes_read else {
+            if !connection.is_peer_requested_shutdown() {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty");
+            } else {
+                return_errno_with_message!(Errno::ECONNRESET, "the connection is reset");
             }
-            bytes_read => Ok(bytes_read),
+        };
+
+        if connection.info.needs_credit_update() && !connection.is_local_shutdown() {
+            VSOCK_GLOBAL
+                .get()
+                .unwrap()
+                .update_credit(&connection.info)?;
+            connection.info.done_reporting_credit();
         }
+
+        Ok(bytes_read)
     }
 
     pub fn send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
         let mut connection = self.connection.disable_irq().lock();
-        debug_assert!(flags.is_all_supported());
-        let buf_len = reader.sum_lens();
-        VSOCK_GLOBAL
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+        if connection.is_local_shutdown() || connection.is_peer_requested_shutdown() {
+            return_errno_with_message!(Errno::EPIPE, "the connection is shut down");
+        }
+
+        let result = VSOCK_GLOBAL
             .get()
             .unwrap()
-            .send(reader, &mut connection.info)?;
+            .send(reader, &mut connection.info);
+
+        // A message is only sent if the peer has room for the whole message, so the socket should
+        // not become writable until then.
+        let is_seqpacket = connection.records.is_some();
+        connection.pending_send_len = match &result {
+            Err(err) if err.error() == Errno::EAGAIN && is_seqpacket => reader.sum_lens(),
+            _ => 0,
+        };
+        drop(connection);
+        self.update_io_events();
 
-        Ok(buf_len)
+        result
     }
 
     pub fn should_close(&self) -> bool {
@@ -114,9 +148,9 @@ impl Connected {
         connection.info.clone()
     }
 
-    pub fn add_connection_buffer(&self, bytes: &[u8]) -> bool {
+    pub fn add_connection_buffer(&self, bytes: &[u8], flags: SeqPacketFlags) -> bool {
         let mut connection = self.connection.disable_irq().lock();
-        connection.add(bytes)
+        connection.add(bytes, flags)
     }
 
     pub fn set_peer_requested_shutdown(&self) {
@@ -133,17 +167,27 @@ impl Connected {
     pub fn update_io_events(&self) {
         let connection = self.connection.disable_irq().lock();
         // receive
-        if !connection.buffer.is_empty() {
+        if connection.has_data_to_recv() || connection.is_peer_requested_shutdown() {
             self.pollee.add_events(IoEvents::IN);
         } else {
             self.pollee.del_events(IoEvents::IN);
         }
+        // send
+        if connection.can_send() {
+            self.pollee.add_events(IoEvents::OUT);
+        } else {
+            self.pollee.del_events(IoEvents::OUT);
+        }
     }
 }
 
 struct Connection {
     info: ConnectionInfo,
     buffer: RingBuffer<u8>,
+    /// The message boundaries, which only exist for `SOCK_SEQPACKET` sockets.
+    records: Option<Records>,
+    /// The length of the data that failed to be sent because the peer did not have enough room.
+    pending_send_len: usize,
     /// The peer sent a SHUTDOWN request, but we haven't yet responded with a RST because there is
     /// still data in the buffer.
     peer_requested_shutdown: bool,
@@ -151,15 +195,10 @@ struct Connection {
 }
 
 impl Connection {
-    fn new(peer: VsockSocketAddr, local_port: u32) -> Self {
+    fn new(peer: VsockSocketAddr, local_port: u32, socket_type: VsockType) -> Self {
         let mut info = ConnectionInfo::new(peer.into(), local_port);
-        info.buf_alloc = PER_CONNECTION_BUFFER_CAPACITY.try_into().unwrap();
-        Self {
-            info,
-            buffer: RingBuffer::new(PER_CONNECTION_BUFFER_CAPACITY),
-            peer_requested_shutdown: false,
-            local_shutdown: false,
-        }
+        info.socket_type = socket_type;
+        Self::new_from_info(info)
     }
 
     fn is_peer_requested_shutdown(&self) -> bool {
@@ -180,9 +219,15 @@ impl Connection {
 
     fn new_from_info(mut info: ConnectionInfo) -> Self {
         info.buf_alloc = PER_CONNECTION_BUFFER_CAPACITY.try_into().unwrap();
+        let records = match info.socket_type {
+            VsockType::Stream => None,
+            VsockType::SeqPacket => Some(Records::default()),
+        };
         Self {
             info,
             buffer: RingBuffer::new(PER_CONNECTION_BUFFER_CAPACITY),
+            records,
+            pending_send_len: 0,
             peer_requested_shutdown: false,
             local_shutdown: false,
         }
@@ -192,13 +237,99 @@ impl Connection {
         self.info.update_for_event(event)
     }
 
-    fn add(&mut self, bytes: &[u8]) -> bool {
+    fn add(&mut self, bytes: &[u8], flags: SeqPacketFlags) -> bool {
         if bytes.len() > self.buffer.capacity() - self.buffer.len() {
             return false;
         }
         self.buffer.push_slice(bytes).unwrap();
+
+        if let Some(records) = self.records.as_mut() {
+            records.partial_len += bytes.len();
+            if flags.contains(SeqPacketFlags::VIRTIO_VSOCK_SEQ_EOM) {
+                records.lens.push_back(records.partial_len);
+                records.partial_len = 0;
+            }
+        }
         true
     }
+
+    /// Receives bytes from a `SOCK_STREAM` connection.
+    ///
+    /// This method returns `None` if there is no data to receive.
+    fn recv_bytes(
+        &mut self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<Option<usize>> {
+        let bytes_read = if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.buffer.peek_fallible(writer)?
+        } else {
+            let bytes_read = self.buffer.read_fallible(writer)?;
+            self.info.done_forwarding(bytes_read);
+            bytes_read
+        };
+
+        Ok(Some(bytes_read).filter(|bytes_read| *bytes_read != 0))
+    }
+
+    /// Receives a message from a `SOCK_SEQPACKET` connection.
+    ///
+    /// The part of the message that does not fit in the `writer` is discarded. This method returns
+    /// `None` if there is no complete message to receive.
+    fn recv_message(
+        &mut self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<Option<usize>> {
+        let records = self.records.as_mut().unwrap();
+        let Some(&message_len) = records.lens.front() else {
+            return Ok(None);
+        };
+
+        let mut message = vec![0u8; message_len];
+        if flags.contains(SendRecvFlags::MSG_PEEK) {
+            self.buffer
+                .peek_fallible(&mut VmWriter::from(message.as_mut_slice()).to_fallible())?;
+        } else {
+            self.buffer.pop_slice(&mut message).unwrap();
+            records.lens.pop_front();
+            self.info.done_forwarding(message_len);
+        }
+
+        let copied_len = writer.write(&mut VmReader::from(message.as_slice()))?;
+
+        // With `MSG_TRUNC`, the real length of the message is returned even if it is truncated.
+        if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            Ok(Some(message_len))
+        } else {
+            Ok(Some(copied_len))
+        }
+    }
+
+    fn has_data_to_recv(&self) -> bool {
+        match self.records.as_ref() {
+            None => !self.buffer.is_empty(),
+            Some(records) => !records.lens.is_empty(),
+        }
+    }
+
+    fn can_send(&self) -> bool {
+        if self.is_local_shutdown() || self.is_peer_requested_shutdown() {
+            return false;
+        }
+
+        let peer_free = self.info.peer_free() as usize;
+        peer_free > 0 && peer_free >= self.pending_send_len
+    }
+}
+
+/// The lengths of the messages received via a `SOCK_SEQPACKET` connection.
+#[derive(Default)]
+struct Records {
+    /// The lengths of the complete messages in the buffer.
+    lens: VecDeque<usize>,
+    /// The length of the incomplete message at the end of the buffer.
+    partial_len: usize,
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
@@ -1,11 +1,14 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use aster_virtio::device::socket::connect::{ConnectionInfo, VsockEvent};
+use aster_virtio::device::socket::{
+    connect::{ConnectionInfo, VsockEvent},
+    header::VsockType,
+};
 
-use super::connected::ConnectionID;
+use super::connected::{Connected, ConnectionID, PER_CONNECTION_BUFFER_CAPACITY};
 use crate::{
     events::IoEvents,
-    net::socket::vsock::{addr::VsockSocketAddr, VSOCK_GLOBAL},
+    net::socket::vsock::addr::VsockSocketAddr,
     prelude::*,
     process::signal::{Pollee, Poller},
 };
@@ -13,14 +16,26 @@ use crate::{
 pub struct Connecting {
     id: ConnectionID,
     info: SpinLock<ConnectionInfo>,
+    /// The result of the connection, which is known after the peer responds.
+    result: SpinLock<Option<Result<Arc<Connected>>>>,
     pollee: Pollee,
 }
 
 impl Connecting {
-    pub fn new(peer_addr: VsockSocketAddr, local_addr: VsockSocketAddr) -> Self {
+    pub fn new(
+        peer_addr: VsockSocketAddr,
+        local_addr: VsockSocketAddr,
+        socket_type: VsockType,
+    ) -> Self {
+        let mut info = ConnectionInfo::new(peer_addr.into(), local_addr.port);
+        info.socket_type = socket_type;
+        // The request packet tells the peer how much data we can receive.
+        info.buf_alloc = PER_CONNECTION_BUFFER_CAPACITY.try_into().unwrap();
+
         Self {
-            info: SpinLock::new(ConnectionInfo::new(peer_addr.into(), local_addr.port)),
+            info: SpinLock::new(info),
             id: ConnectionID::new(local_addr, peer_addr),
+            result: SpinLock::new(None),
             pollee: Pollee::new(IoEvents::empty()),
         }
     }
@@ -45,19 +60,24 @@ impl Connecting {
         self.info.disable_irq().lock().update_for_event(event)
     }
 
-    pub fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
-        self.pollee.poll(mask, poller)
+    /// Returns the result of the connection, or `None` if the peer has not responded.
+    pub fn result(&self) -> Option<Result<Arc<Connected>>> {
+        self.result.disable_irq().lock().clone()
     }
 
-    pub fn add_events(&self, events: IoEvents) {
-        self.pollee.add_events(events)
+    /// Sets the result of the connection after the peer responds.
+    pub fn set_result(&self, result: Result<Arc<Connected>>) {
+        let events = if result.is_ok() {
+            IoEvents::OUT
+        } else {
+            IoEvents::OUT | IoEvents::ERR
+        };
+
+        *self.result.disable_irq().lock() = Some(result);
+        self.pollee.add_events(events);
     }
-}
 
-impl Drop for Connecting {
-    fn drop(&mut self) {
-        let vsockspace = VSOCK_GLOBAL.get().unwrap();
-        vsockspace.recycle_port(&self.local_addr().port);
-        vsockspace.remove_connecting_socket(&self.local_addr());
+    pub fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
     }
 }
@@ -23,6 +23,17 @@ impl Init {
         }
     }
 
+    /// Creates an `Init` that is already bound to `addr`.
+    ///
+    /// This is used when a connection attempt fails and the socket goes back to the initial
+    /// state, keeping the address it was bound to.
+    pub fn new_bound(addr: VsockSocketAddr) -> Self {
+        Self {
+            bound_addr: Mutex::new(Some(addr)),
+            pollee: Pollee::new(IoEvents::empty()),
+        }
+    }
+
     pub fn bind(&self, addr: VsockSocketAddr) -> Result<()> {
         if self.bound_addr.lock().is_some() {
             return_errno_with_message!(Errno::EINVAL, "the socket is already bound");
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use aster_virtio::device::socket::header::VsockType;
+
 use super::connected::Connected;
 use crate::{
     events::IoEvents,
@@ -9,15 +11,17 @@ use crate::{
 };
 pub struct Listen {
     addr: VsockSocketAddr,
+    socket_type: VsockType,
     backlog: usize,
     incoming_connection: SpinLock<VecDeque<Arc<Connected>>>,
     pollee: Pollee,
 }
 
 impl Listen {
-    pub fn new(addr: VsockSocketAddr, backlog: usize) -> Self {
+    pub fn new(addr: VsockSocketAddr, backlog: usize, socket_type: VsockType) -> Self {
         Self {
             addr,
+            socket_type,
             pollee: Pollee::new(IoEvents::empty()),
             backlog,
             incoming_connection: SpinLock::new(VecDeque::with_capacity(backlog)),
@@ -28,6 +32,10 @@ impl Listen {
         self.addr
     }
 
+    pub fn socket_type(&self) -> VsockType {
+        self.socket_type
+    }
+
     pub fn push_incoming(&self, connect: Arc<Connected>) -> Result<()> {
         let mut incoming_connections = self.incoming_connection.disable_irq().lock();
         if incoming_connections.len() >= self.backlog {
@@ -2,12 +2,17 @@
 
 use core::sync::atomic::{AtomicBool, Ordering};
 
+use aster_virtio::device::socket::header::VsockType;
+
 use super::{connected::Connected, connecting::Connecting, init::Init, listen::Listen};
 use crate::{
     events::IoEvents,
     fs::{file_handle::FileLike, utils::StatusFlags},
+    match_sock_option_mut,
     net::socket::{
-        vsock::{addr::VsockSocketAddr, VSOCK_GLOBAL},
+        options::{Error as SocketError, SocketOption},
+        util::options::SocketOptionSet,
+        vsock::{addr::VsockSocketAddr, options::VsockOptionSet, VSOCK_GLOBAL},
         MessageHeader, SendRecvFlags, SockShutdownCmd, Socket, SocketAddr,
     },
     prelude::*,
@@ -17,27 +22,57 @@ use crate::{
 
 pub struct VsockStreamSocket {
     status: RwLock<Status>,
+    socket_type: VsockType,
+    options: RwLock<OptionSet>,
     is_nonblocking: AtomicBool,
 }
 
+#[derive(Debug, Clone)]
+struct OptionSet {
+    socket: SocketOptionSet,
+    vsock: VsockOptionSet,
+}
+
+impl OptionSet {
+    fn new() -> Self {
+        let socket = SocketOptionSet::new_tcp();
+        let vsock = VsockOptionSet::new();
+        OptionSet { socket, vsock }
+    }
+}
+
 pub enum Status {
     Init(Arc<Init>),
+    Connecting(Arc<Connecting>),
     Listen(Arc<Listen>),
     Connected(Arc<Connected>),
 }
 
 impl VsockStreamSocket {
-    pub fn new(nonblocking: bool) -> Self {
+    /// Creates a new vsock socket.
+    ///
+    /// If `is_seqpacket` is true, the socket is a `SOCK_SEQPACKET` socket, which preserves
+    /// message boundaries. Otherwise, the socket is a `SOCK_STREAM` socket.
+    pub fn new(nonblocking: bool, is_seqpacket: bool) -> Self {
         let init = Arc::new(Init::new());
+        let socket_type = if is_seqpacket {
+            VsockType::SeqPacket
+        } else {
+            VsockType::Stream
+        };
         Self {
             status: RwLock::new(Status::Init(init)),
+            socket_type,
+            options: RwLock::new(OptionSet::new()),
             is_nonblocking: AtomicBool::new(nonblocking),
         }
     }
 
-    pub(super) fn new_from_connected(connected: Arc<Connected>) -> Self {
+    pub(super) fn new_from_connected(connected: Arc<Connected>, socket_type: VsockType) -> Self {
         Self {
             status: RwLock::new(Status::Connected(connected)),
+            socket_type,
+            options: RwLock::new(OptionSet::new()),
             is_nonblocking: AtomicBool::new(false),
         }
     }
@@ -50,10 +85,122 @@ impl VsockStreamSocket {
         self.is_nonblocking.store(nonblocking, Ordering::Relaxed);
     }
 
+    fn start_connect(&self, remote_addr: VsockSocketAddr) -> Result<()> {
+        self.finish_connect();
+
+        let mut status = self.status.write();
+        let init = match &*status {
+            Status::Init(init) => init.clone(),
+            Status::Connecting(_) => {
+                return_errno_with_message!(Errno::EALREADY, "the socket is connecting");
+            }
+            Status::Listen(_) => {
+                return_errno_with_message!(Errno::EINVAL, "the socket is listened");
+            }
+            Status::Connected(_) => {
+                return_errno_with_message!(Errno::EISCONN, "the socket is connected");
+            }
+        };
+
+        let local_addr = init.bound_addr();
+        if let Some(addr) = local_addr {
+            if addr == remote_addr {
+                return_errno_with_message!(Errno::EINVAL, "try to connect to self is invalid");
+            }
+        } else {
+            init.bind(VsockSocketAddr::any_addr())?;
+        }
+
+        let connecting = Arc::new(Connecting::new(
+            remote_addr,
+            init.bound_addr().unwrap(),
+            self.socket_type,
+        ));
+        let vsockspace = VSOCK_GLOBAL.get().unwrap();
+        vsockspace.insert_connecting_socket(connecting.local_addr(), connecting.clone());
+
+        // Send request
+        if let Err(err) = vsockspace.request(&connecting.info()) {
+            vsockspace.remove_connecting_socket(&connecting.local_addr());
+            return Err(err);
+        }
+
+        *status = Status::Connecting(connecting);
+        Ok(())
+    }
+
+    /// Moves the socket out of the connecting state if the peer has responded.
+    ///
+    /// If the peer has refused the connection, the error will be reported by the next call to
+    /// [`Self::check_connect`] or by `SO_ERROR`.
+    fn finish_connect(&self) {
+        let mut status = self.status.write();
+        let Status::Connecting(connecting) = &*status else {
+            return;
+        };
+        let Some(result) = connecting.result() else {
+            return;
+        };
+
+        match result {
+            Ok(connected) => *status = Status::Connected(connected),
+            Err(err) => {
+                let init = Arc::new(Init::new_bound(connecting.local_addr()));
+                *status = Status::Init(init);
+                self.options.write().socket.set_sock_errors(Some(err));
+            }
+        }
+    }
+
+    fn check_connect(&self) -> Result<()> {
+        self.finish_connect();
+
+        match &*self.status.read() {
+            Status::Connecting(_) => {
+                return_errno_with_message!(Errno::EAGAIN, "the connection is pending")
+            }
+            Status::Connected(_) => Ok(()),
+            Status::Init(_) | Status::Listen(_) => {
+                let mut options = self.options.write();
+                let sock_errors = options.socket.sock_errors();
+                options.socket.set_sock_errors(None);
+                sock_errors.map(Err).unwrap_or(Ok(()))
+            }
+        }
+    }
+
+    /// Gives up the pending connection, making the connection fail with `error`.
+    fn cancel_connect(&self, error: Error) -> Result<()> {
+        let mut status = self.status.write();
+        let Status::Connecting(connecting) = &*status else {
+            drop(status);
+            return self.check_connect();
+        };
+
+        let vsockspace = VSOCK_GLOBAL.get().unwrap();
+        if vsockspace
+            .remove_connecting_socket(&connecting.local_addr())
+            .is_none()
+        {
+            // The peer has responded in the meantime.
+            drop(status);
+            return self.check_connect();
+        }
+
+        let info = connecting.info();
+        let init = Arc::new(Init::new_bound(connecting.local_addr()));
+        *status = Status::Init(init);
+        drop(status);
+
+        // Tell the peer to drop the connection in case it responds later.
+        vsockspace.reset(&info)?;
+        Err(error)
+    }
+
     fn try_accept(&self) -> Result<(Arc<dyn FileLike>, SocketAddr)> {
         let listen = match &*self.status.read() {
             Status::Listen(listen) => listen.clone(),
-            Status::Init(_) | Status::Connected(_) => {
+            Status::Init(_) | Status::Connecting(_) | Status::Connected(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not listening");
             }
         };
@@ -73,34 +220,57 @@ impl VsockStreamSocket {
             .unwrap()
             .response(&connected.get_info())
             .unwrap();
+        connected.update_io_events();
 
-        let socket = Arc::new(VsockStreamSocket::new_from_connected(connected));
+        let socket = Arc::new(VsockStreamSocket::new_from_connected(
+            connected,
+            self.socket_type,
+        ));
         Ok((socket, peer_addr.into()))
     }
 
-    fn send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
-        let inner = self.status.read();
-        match &*inner {
+    fn try_send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
+        self.finish_connect();
+
+        match &*self.status.read() {
             Status::Connected(connected) => connected.send(reader, flags),
+            Status::Connecting(_) => {
+                return_errno_with_message!(Errno::EAGAIN, "the socket is connecting");
+            }
             Status::Init(_) | Status::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not connected");
             }
         }
     }
 
+    fn send(&self, reader: &mut dyn MultiRead, flags: SendRecvFlags) -> Result<usize> {
+        let result = if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            self.try_send(reader, flags)
+        } else {
+            let timeout = self.options.read().socket.send_timeout();
+            self.wait_events_timeout(IoEvents::OUT, timeout.as_ref(), || {
+                self.try_send(reader, flags)
+            })
+        };
+
+        flags.check_sigpipe(result)
+    }
+
     fn try_recv(
         &self,
         writer: &mut dyn MultiWrite,
-        _flags: SendRecvFlags,
+        flags: SendRecvFlags,
     ) -> Result<(usize, SocketAddr)> {
+        self.finish_connect();
+
         let connected = match &*self.status.read() {
             Status::Connected(connected) => connected.clone(),
-            Status::Init(_) | Status::Listen(_) => {
+            Status::Init(_) | Status::Connecting(_) | Status::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not connected");
             }
         };
 
-        let read_size = connected.try_recv(writer)?;
+        let read_size = connected.try_recv(writer, flags)?;
         connected.update_io_events();
 
         let peer_addr = self.peer_addr()?;
@@ -119,11 +289,35 @@ impl VsockStreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, SocketAddr)> {
-        if self.is_nonblocking() {
-            self.try_recv(writer, flags)
-        } else {
-            self.wait_events(IoEvents::IN, || self.try_recv(writer, flags))
+        if self.is_nonblocking() || flags.contains(SendRecvFlags::MSG_DONTWAIT) {
+            return self.try_recv(writer, flags);
+        }
+
+        let timeout = self.options.read().socket.recv_timeout();
+        let (mut recv_bytes, peer_addr) =
+            self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || {
+                self.try_recv(writer, flags)
+            })?;
+
+        if !flags.contains(SendRecvFlags::MSG_WAITALL)
+            || flags.contains(SendRecvFlags::MSG_PEEK)
+            || self.socket_type == VsockType::SeqPacket
+        {
+            return Ok((recv_bytes, peer_addr));
+        }
+
+        // With `MSG_WAITALL`, keep receiving until the buffer is full, the connection is closed,
+        // or an error occurs. The error is not reported since some data has been received.
+        while !writer.is_empty() {
+            match self.wait_events_timeout(IoEvents::IN, timeout.as_ref(), || {
+                self.try_recv(writer, flags)
+            }) {
+                Ok((0, _)) | Err(_) => break,
+                Ok((new_bytes, _)) => recv_bytes += new_bytes,
+            }
         }
+
+        Ok((recv_bytes, peer_addr))
     }
 }
 
@@ -131,6 +325,7 @@ impl Pollable for VsockStreamSocket {
     fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
         match &*self.status.read() {
             Status::Init(init) => init.poll(mask, poller),
+            Status::Connecting(connecting) => connecting.poll(mask, poller),
             Status::Listen(listen) => listen.poll(mask, poller),
             Status::Connected(connected) => connected.poll(mask, poller),
         }
@@ -179,7 +374,7 @@ impl Socket for VsockStreamSocket {
         let inner = self.status.read();
         match &*inner {
             Status::Init(init) => init.bind(addr),
-            Status::Listen(_) | Status::Connected(_) => {
+            Status::Connecting(_) | Status::Listen(_) | Status::Connected(_) => {
                 return_errno_with_message!(
                     Errno::EINVAL,
                     "cannot bind a listening or connected socket"
@@ -188,63 +383,32 @@ impl Socket for VsockStreamSocket {
         }
     }
 
-    // Since blocking mode is supported, there is no need to store the connecting status.
-    // TODO: Refactor when nonblocking mode is supported.
     fn connect(&self, sockaddr: SocketAddr) -> Result<()> {
-        let init = match &*self.status.read() {
-            Status::Init(init) => init.clone(),
-            Status::Listen(_) => {
-                return_errno_with_message!(Errno::EINVAL, "the socket is listened");
-            }
-            Status::Connected(_) => {
-                return_errno_with_message!(Errno::EINVAL, "the socket is connected");
-            }
-        };
         let remote_addr = VsockSocketAddr::try_from(sockaddr)?;
-        let local_addr = init.bound_addr();
-        if let Some(addr) = local_addr {
-            if addr == remote_addr {
-                return_errno_with_message!(Errno::EINVAL, "try to connect to self is invalid");
-            }
-        } else {
-            init.bind(VsockSocketAddr::any_addr())?;
-        }
+        self.start_connect(remote_addr)?;
 
-        let connecting = Arc::new(Connecting::new(remote_addr, init.bound_addr().unwrap()));
-        let vsockspace = VSOCK_GLOBAL.get().unwrap();
-        vsockspace.insert_connecting_socket(connecting.local_addr(), connecting.clone());
+        if self.is_nonblocking() {
+            return_errno_with_message!(Errno::EINPROGRESS, "the socket is connecting");
+        }
 
-        // Send request
-        vsockspace.request(&connecting.info()).unwrap();
         // wait for response from driver
-        // TODO: Add timeout
-        let mut poller = Poller::new();
-        if !connecting
-            .poll(IoEvents::IN, Some(&mut poller))
-            .contains(IoEvents::IN)
-        {
-            if let Err(e) = poller.wait() {
-                vsockspace
-                    .remove_connecting_socket(&connecting.local_addr())
-                    .unwrap();
-                return Err(e);
-            }
+        let timeout = self.options.read().vsock.connect_timeout();
+        match self.wait_events_timeout(IoEvents::OUT, Some(&timeout), || self.check_connect()) {
+            Err(err) if err.error() == Errno::EAGAIN => self.cancel_connect(Error::with_message(
+                Errno::ETIMEDOUT,
+                "the connection timed out",
+            )),
+            Err(err) if err.error() == Errno::EINTR => self.cancel_connect(err),
+            result => result,
         }
-
-        vsockspace
-            .remove_connecting_socket(&connecting.local_addr())
-            .unwrap();
-        let connected = Arc::new(Connected::from_connecting(connecting));
-        *self.status.write() = Status::Connected(connected.clone());
-        // move connecting socket map to connected sockmap
-        vsockspace.insert_connected_socket(connected.id(), connected);
-
-        Ok(())
     }
 
     fn listen(&self, backlog: usize) -> Result<()> {
         let init = match &*self.status.read() {
             Status::Init(init) => init.clone(),
+            Status::Connecting(_) => {
+                return_errno_with_message!(Errno::EINVAL, "the socket is connecting");
+            }
             Status::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is already listened");
             }
@@ -256,7 +420,7 @@ impl Socket for VsockStreamSocket {
             Errno::EINVAL,
             "the socket is not bound",
         ))?;
-        let listen = Arc::new(Listen::new(addr, backlog));
+        let listen = Arc::new(Listen::new(addr, backlog, self.socket_type));
         *self.status.write() = Status::Listen(listen.clone());
 
         // push listen socket into vsockspace
@@ -279,7 +443,7 @@ impl Socket for VsockStreamSocket {
     fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
         match &*self.status.read() {
             Status::Connected(connected) => connected.shutdown(cmd),
-            Status::Init(_) | Status::Listen(_) => {
+            Status::Init(_) | Status::Connecting(_) | Status::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not connected");
             }
         }
@@ -291,8 +455,9 @@ impl Socket for VsockStreamSocket {
         message_header: MessageHeader,
         flags: SendRecvFlags,
     ) -> Result<usize> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
         let MessageHeader {
             control_message, ..
@@ -311,8 +476,9 @@ impl Socket for VsockStreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, MessageHeader)> {
-        // TODO: Deal with flags
-        debug_assert!(flags.is_all_supported());
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
 
         let (received_bytes, _) = self.recv(writer, flags)?;
 
@@ -327,6 +493,7 @@ impl Socket for VsockStreamSocket {
         let inner = self.status.read();
         let addr = match &*inner {
             Status::Init(init) => init.bound_addr(),
+            Status::Connecting(connecting) => Some(connecting.local_addr()),
             Status::Listen(listen) => Some(listen.addr()),
             Status::Connected(connected) => Some(connected.local_addr()),
         };
@@ -345,10 +512,44 @@ impl Socket for VsockStreamSocket {
             return_errno_with_message!(Errno::EINVAL, "the socket is not connected");
         }
     }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                // The result of a nonblocking connection is reported here.
+                self.finish_connect();
+                self.options.write().socket.get_and_clear_sock_errors(socket_errors);
+                return Ok(());
+            },
+            _ => ()
+        });
+
+        let options = self.options.read();
+
+        match options.socket.get_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        options.vsock.get_option(option)
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        let mut options = self.options.write();
+
+        match options.socket.set_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        options.vsock.set_option(option)
+    }
 }
 
 impl Drop for VsockStreamSocket {
     fn drop(&mut self) {
+        self.finish_connect();
+
         let vsockspace = VSOCK_GLOBAL.get().unwrap();
         let inner = self.status.read();
         match &*inner {
@@ -357,6 +558,19 @@ impl Drop for VsockStreamSocket {
                     vsockspace.recycle_port(&addr.port);
                 }
             }
+            Status::Connecting(connecting) => {
+                // Cancel the pending connection, unless the peer has responded in the meantime.
+                if vsockspace
+                    .remove_connecting_socket(&connecting.local_addr())
+                    .is_some()
+                {
+                    vsockspace.reset(&connecting.info()).unwrap();
+                } else if let Some(Ok(connected)) = connecting.result() {
+                    vsockspace.reset(&connected.get_info()).unwrap();
+                    vsockspace.remove_connected_socket(&connected.id());
+                }
+                vsockspace.recycle_port(&connecting.local_addr().port);
+            }
             Status::Listen(listen) => {
                 vsockspace.recycle_port(&listen.addr().port);
                 vsockspace.remove_listen_socket(&listen.addr());
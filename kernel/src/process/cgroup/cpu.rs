@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use crate::prelude::*;
+
+/// The default value of `cpu.weight`.
+pub const DEFAULT_CPU_WEIGHT: u32 = 100;
+
+/// The CPU controller.
+///
+/// It limits the CPU time that the tasks in a cgroup and its descendants can consume
+/// in each period (i.e., the CPU bandwidth), and distributes the CPU time among the
+/// cgroups by their weights. Both of them only apply to the tasks that are not real-time.
+pub struct CpuController {
+    /// The weight in the range of [1, 10000]
+    weight: AtomicU32,
+    bandwidth: SpinLock<CpuBandwidth>,
+}
+
+struct CpuBandwidth {
+    /// The CPU time that can be consumed in each period, or `None` if there is no limit
+    quota: Option<Duration>,
+    period: Duration,
+    /// The start time of the current period
+    period_start: Duration,
+    /// The CPU time that has been consumed in the current period
+    runtime: Duration,
+}
+
+impl CpuController {
+    const MIN_WEIGHT: u32 = 1;
+    const MAX_WEIGHT: u32 = 10000;
+    const DEFAULT_PERIOD: Duration = Duration::from_millis(100);
+    const MIN_QUOTA: Duration = Duration::from_millis(1);
+    const MIN_PERIOD: Duration = Duration::from_millis(1);
+    const MAX_PERIOD: Duration = Duration::from_secs(1);
+
+    pub(super) const fn new() -> Self {
+        Self {
+            weight: AtomicU32::new(DEFAULT_CPU_WEIGHT),
+            bandwidth: SpinLock::new(CpuBandwidth {
+                quota: None,
+                period: Self::DEFAULT_PERIOD,
+                period_start: Duration::ZERO,
+                runtime: Duration::ZERO,
+            }),
+        }
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight.load(Ordering::Relaxed)
+    }
+
+    pub fn set_weight(&self, weight: u32) -> Result<()> {
+        if !(Self::MIN_WEIGHT..=Self::MAX_WEIGHT).contains(&weight) {
+            return_errno_with_message!(Errno::EINVAL, "the CPU weight is out of range");
+        }
+        self.weight.store(weight, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the quota and the period of the CPU bandwidth.
+    ///
+    /// A `None` quota means there is no limit.
+    pub fn max(&self) -> (Option<Duration>, Duration) {
+        let bandwidth = self.bandwidth.disable_irq().lock();
+        (bandwidth.quota, bandwidth.period)
+    }
+
+    /// Sets the quota and the period of the CPU bandwidth.
+    ///
+    /// A `None` quota means there is no limit.
+    pub fn set_max(&self, quota: Option<Duration>, period: Duration) -> Result<()> {
+        if !(Self::MIN_PERIOD..=Self::MAX_PERIOD).contains(&period) {
+            return_errno_with_message!(Errno::EINVAL, "the CPU period is out of range");
+        }
+        if quota.is_some_and(|quota| quota < Self::MIN_QUOTA) {
+            return_errno_with_message!(Errno::EINVAL, "the CPU quota is too small");
+        }
+
+        let mut bandwidth = self.bandwidth.disable_irq().lock();
+        bandwidth.quota = quota;
+        bandwidth.period = period;
+        bandwidth.runtime = Duration::ZERO;
+        Ok(())
+    }
+
+    pub(super) fn account(&self, now: Duration, cpu_time: Duration) {
+        let mut bandwidth = self.bandwidth.disable_irq().lock();
+        bandwidth.refresh(now);
+        bandwidth.runtime += cpu_time;
+    }
+
+    pub(super) fn is_throttled(&self, now: Duration) -> bool {
+        let mut bandwidth = self.bandwidth.disable_irq().lock();
+        bandwidth.refresh(now);
+        bandwidth
+            .quota
+            .is_some_and(|quota| bandwidth.runtime >= quota)
+    }
+}
+
+impl CpuBandwidth {
+    /// Starts a new period if the current one has ended.
+    fn refresh(&mut self, now: Duration) {
+        let Some(elapsed) = now.checked_sub(self.period_start) else {
+            return;
+        };
+        if elapsed < self.period {
+            return;
+        }
+
+        let elapsed_in_period = elapsed.as_nanos() % self.period.as_nanos();
+        self.period_start = now - Duration::from_nanos(elapsed_in_period as u64);
+        self.runtime = Duration::ZERO;
+    }
+}
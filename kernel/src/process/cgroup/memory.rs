@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The memory controller.
+///
+/// It accounts and limits the memory committed to the anonymous VMOs, i.e., the VMOs
+/// without pagers, created by the processes in a cgroup and its descendants. These include
+/// the VMOs of the anonymous memory mappings, the shared memory segments, and the heap.
+///
+/// Unlike Linux, the pages committed through a pager, i.e., the page cache and the pages of
+/// the file mappings, are not accounted, so `memory.current` only reports the anonymous
+/// memory and `memory.max` does not limit the page cache. As in Linux, the memory stays
+/// charged to the cgroup that the creator belonged to, even if the creator moves to another
+/// cgroup.
+///
+/// TODO: Account the page cache and the private pages of the memory mappings,
+/// which are not committed to the anonymous VMOs.
+pub struct MemoryController {
+    /// The maximum memory usage in bytes, or `usize::MAX` if there is no limit
+    max: AtomicUsize,
+    /// The current memory usage in bytes
+    current: AtomicUsize,
+}
+
+impl MemoryController {
+    pub(super) const fn new() -> Self {
+        Self {
+            max: AtomicUsize::new(usize::MAX),
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the maximum memory usage in bytes, or `None` if there is no limit.
+    pub fn max(&self) -> Option<usize> {
+        let max = self.max.load(Ordering::Relaxed);
+        (max != usize::MAX).then_some(max)
+    }
+
+    /// Sets the maximum memory usage in bytes. `None` means there is no limit.
+    ///
+    /// Since the memory cannot be reclaimed, lowering the limit below the current
+    /// usage only fails the subsequent charges.
+    pub fn set_max(&self, max: Option<usize>) {
+        self.max.store(max.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// Returns the current memory usage in bytes.
+    ///
+    /// Only the memory committed to the anonymous VMOs is counted.
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn try_charge(&self, size: usize) -> bool {
+        let max = self.max.load(Ordering::Relaxed);
+        self.current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                current.checked_add(size).filter(|new| *new <= max)
+            })
+            .is_ok()
+    }
+
+    pub(super) fn force_charge(&self, size: usize) {
+        self.current.fetch_add(size, Ordering::Relaxed);
+    }
+
+    pub(super) fn uncharge(&self, size: usize) {
+        let old = self.current.fetch_sub(size, Ordering::Relaxed);
+        debug_assert!(old >= size);
+    }
+}
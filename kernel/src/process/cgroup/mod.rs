@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Control groups (cgroup v2).
+//!
+//! Cgroups organize processes into a hierarchy, along which system resources are
+//! limited and distributed by controllers. The supported controllers are:
+//! - The CPU controller, which limits the CPU bandwidth (`cpu.max`) and distributes
+//!   CPU time by weight (`cpu.weight`);
+//! - The memory controller, which accounts and limits the memory committed to
+//!   anonymous VMOs (`memory.max` and `memory.current`). Unlike Linux, the page cache
+//!   and the other pages backed by files are not accounted;
+//! - The PIDs controller, which limits the number of tasks (`pids.max` and `pids.current`).
+//!
+//! All the controllers are always enabled for all the cgroups.
+//! The hierarchy is exposed to the user space by the cgroup2 file system.
+
+mod cpu;
+mod memory;
+mod pids;
+
+use alloc::format;
+use core::time::Duration;
+
+pub use cpu::{CpuController, DEFAULT_CPU_WEIGHT};
+pub use memory::MemoryController;
+pub use pids::PidsController;
+use spin::Once;
+
+use super::{Pid, Process};
+use crate::{prelude::*, thread::Thread};
+
+/// A control group.
+pub struct Cgroup {
+    name: String,
+    parent: Option<Arc<Cgroup>>,
+    /// The processes that belong to the cgroup
+    processes: Mutex<BTreeMap<Pid, Weak<Process>>>,
+    cpu: CpuController,
+    memory: MemoryController,
+    pids: PidsController,
+}
+
+static ROOT_CGROUP: Once<Arc<Cgroup>> = Once::new();
+
+impl Cgroup {
+    /// Returns the root cgroup.
+    pub fn root() -> &'static Arc<Cgroup> {
+        ROOT_CGROUP.call_once(|| Arc::new(Self::new(String::new(), None)))
+    }
+
+    fn new(name: String, parent: Option<Arc<Cgroup>>) -> Self {
+        Self {
+            name,
+            parent,
+            processes: Mutex::new(BTreeMap::new()),
+            cpu: CpuController::new(),
+            memory: MemoryController::new(),
+            pids: PidsController::new(),
+        }
+    }
+
+    /// Creates a child cgroup with the `name`.
+    pub fn new_child(self: &Arc<Self>, name: &str) -> Arc<Self> {
+        Arc::new(Self::new(name.to_string(), Some(self.clone())))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<&Arc<Cgroup>> {
+        self.parent.as_ref()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    /// Returns the path of the cgroup relative to the root of the hierarchy.
+    pub fn path(&self) -> String {
+        let mut names: Vec<&str> = self
+            .ancestors()
+            .filter(|cgroup| !cgroup.is_root())
+            .map(|cgroup| cgroup.name())
+            .collect();
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Returns an iterator over the cgroup itself and its ancestors, from bottom to top.
+    fn ancestors(&self) -> impl Iterator<Item = &Cgroup> {
+        core::iter::successors(Some(self), |cgroup| cgroup.parent.as_deref())
+    }
+
+    pub fn cpu(&self) -> &CpuController {
+        &self.cpu
+    }
+
+    pub fn memory(&self) -> &MemoryController {
+        &self.memory
+    }
+
+    pub fn pids(&self) -> &PidsController {
+        &self.pids
+    }
+
+    // ************** Processes **************
+
+    /// Returns the live processes that belong to the cgroup.
+    pub fn processes(&self) -> Vec<Arc<Process>> {
+        self.processes
+            .lock()
+            .values()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// Returns whether any process belongs to the cgroup.
+    pub fn has_processes(&self) -> bool {
+        !self.processes.lock().is_empty()
+    }
+
+    pub(super) fn add_process(&self, process: &Arc<Process>) {
+        self.processes
+            .lock()
+            .insert(process.pid(), Arc::downgrade(process));
+    }
+
+    pub(super) fn remove_process(&self, pid: Pid) {
+        self.processes.lock().remove(&pid);
+    }
+
+    // ************** Resource accounting **************
+
+    /// Charges the cgroup and its ancestors for the CPU time that has been consumed.
+    pub fn account_cpu_time(&self, now: Duration, cpu_time: Duration) {
+        for cgroup in self.ancestors() {
+            cgroup.cpu.account(now, cpu_time);
+        }
+    }
+
+    /// Returns whether the cgroup or any of its ancestors has run out of its CPU bandwidth.
+    pub fn is_cpu_throttled(&self, now: Duration) -> bool {
+        self.ancestors().any(|cgroup| cgroup.cpu.is_throttled(now))
+    }
+
+    /// Charges the cgroup and its ancestors for `size` bytes of memory.
+    ///
+    /// If the memory limit of any of them would be exceeded, nothing is charged
+    /// and `ENOMEM` is returned.
+    pub fn try_charge_memory(&self, size: usize) -> Result<()> {
+        let is_charged = self.try_charge(
+            |cgroup| cgroup.memory.try_charge(size),
+            |cgroup| cgroup.memory.uncharge(size),
+        );
+        if !is_charged {
+            return_errno_with_message!(Errno::ENOMEM, "the memory limit of the cgroup is reached");
+        }
+        Ok(())
+    }
+
+    /// Charges the cgroup and its ancestors for `size` bytes of memory regardless of the limits.
+    pub fn force_charge_memory(&self, size: usize) {
+        for cgroup in self.ancestors() {
+            cgroup.memory.force_charge(size);
+        }
+    }
+
+    /// Uncharges `size` bytes of memory from the cgroup and its ancestors.
+    pub fn uncharge_memory(&self, size: usize) {
+        for cgroup in self.ancestors() {
+            cgroup.memory.uncharge(size);
+        }
+    }
+
+    /// Charges the cgroup and its ancestors for a new task.
+    ///
+    /// If the task limit of any of them would be exceeded, nothing is charged
+    /// and `EAGAIN` is returned.
+    pub fn try_charge_task(&self) -> Result<()> {
+        let is_charged = self.try_charge(
+            |cgroup| cgroup.pids.try_charge(1),
+            |cgroup| cgroup.pids.uncharge(1),
+        );
+        if !is_charged {
+            return_errno_with_message!(Errno::EAGAIN, "the pids limit of the cgroup is reached");
+        }
+        Ok(())
+    }
+
+    /// Uncharges a task from the cgroup and its ancestors.
+    pub fn uncharge_task(&self) {
+        for cgroup in self.ancestors() {
+            cgroup.pids.uncharge(1);
+        }
+    }
+
+    /// Moves the charges of `nr_tasks` tasks from the cgroup to the `target` cgroup.
+    ///
+    /// Like Linux, the limits are only enforced when new tasks are created,
+    /// so the move always succeeds.
+    fn move_tasks_to(&self, target: &Cgroup, nr_tasks: usize) {
+        for cgroup in target.ancestors() {
+            cgroup.pids.force_charge(nr_tasks);
+        }
+        for cgroup in self.ancestors() {
+            cgroup.pids.uncharge(nr_tasks);
+        }
+    }
+
+    /// Charges the cgroup and its ancestors with `try_charge` from bottom to top.
+    ///
+    /// If any of the charges fails, the successful ones are reverted with `uncharge`.
+    fn try_charge<F, G>(&self, try_charge: F, uncharge: G) -> bool
+    where
+        F: Fn(&Cgroup) -> bool,
+        G: Fn(&Cgroup),
+    {
+        for (nr_charged, cgroup) in self.ancestors().enumerate() {
+            if !try_charge(cgroup) {
+                self.ancestors().take(nr_charged).for_each(&uncharge);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Process {
+    /// Moves the process and all its threads to the `cgroup`.
+    pub fn move_to_cgroup(self: &Arc<Self>, cgroup: Arc<Cgroup>) -> Result<()> {
+        // The threads are charged and uncharged with the lock held in `clone` and `exit`,
+        // so the number of the live threads cannot change until the charges are moved.
+        let tasks = self.tasks().lock();
+
+        if self.is_zombie() {
+            return_errno_with_message!(Errno::ESRCH, "the process has exited");
+        }
+
+        let old_cgroup = self.replace_cgroup(cgroup.clone());
+        if Arc::ptr_eq(&old_cgroup, &cgroup) {
+            return Ok(());
+        }
+
+        old_cgroup.remove_process(self.pid());
+        cgroup.add_process(self);
+
+        let nr_live_threads = tasks
+            .iter()
+            .filter(|task| !Thread::borrow_from_task(task).status().is_exited())
+            .count();
+        old_cgroup.move_tasks_to(&cgroup, nr_live_threads);
+        Ok(())
+    }
+}
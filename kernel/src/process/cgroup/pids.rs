@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The PIDs controller.
+///
+/// It limits the number of tasks (i.e., threads) in a cgroup and its descendants.
+pub struct PidsController {
+    /// The maximum number of tasks, or `usize::MAX` if there is no limit
+    max: AtomicUsize,
+    /// The current number of tasks
+    current: AtomicUsize,
+}
+
+impl PidsController {
+    pub(super) const fn new() -> Self {
+        Self {
+            max: AtomicUsize::new(usize::MAX),
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the maximum number of tasks, or `None` if there is no limit.
+    pub fn max(&self) -> Option<usize> {
+        let max = self.max.load(Ordering::Relaxed);
+        (max != usize::MAX).then_some(max)
+    }
+
+    /// Sets the maximum number of tasks. `None` means there is no limit.
+    pub fn set_max(&self, max: Option<usize>) {
+        self.max.store(max.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// Returns the current number of tasks.
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn try_charge(&self, nr_tasks: usize) -> bool {
+        let max = self.max.load(Ordering::Relaxed);
+        self.current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                current.checked_add(nr_tasks).filter(|new| *new <= max)
+            })
+            .is_ok()
+    }
+
+    pub(super) fn force_charge(&self, nr_tasks: usize) {
+        self.current.fetch_add(nr_tasks, Ordering::Relaxed);
+    }
+
+    pub(super) fn uncharge(&self, nr_tasks: usize) {
+        let old = self.current.fetch_sub(nr_tasks, Ordering::Relaxed);
+        debug_assert!(old >= nr_tasks);
+    }
+}
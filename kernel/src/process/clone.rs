@@ -9,6 +9,8 @@ use ostd::{
 };
 
 use super::{
+    cgroup::Cgroup,
+    namespace::{check_ns_permission, NsProxy, NS_CLONE_FLAGS},
     posix_thread::{thread_table, PosixThread, PosixThreadBuilder, PosixThreadExt, ThreadName},
     process_table,
     process_vm::ProcessVm,
@@ -17,7 +19,13 @@ use super::{
 };
 use crate::{
     cpu::LinuxAbi,
-    fs::{file_table::FileTable, fs_resolver::FsResolver, utils::FileCreationMask},
+    fs::{
+        cgroupfs::get_cgroup_of_inode,
+        file_table::{FileDesc, FileTable},
+        fs_resolver::FsResolver,
+        inode_handle::InodeHandle,
+        utils::FileCreationMask,
+    },
     get_current_userspace,
     prelude::*,
     process::posix_thread::allocate_posix_tid,
@@ -90,7 +98,7 @@ pub struct CloneArgs {
     pub tls: u64,
     pub _set_tid: Option<u64>,
     pub _set_tid_size: Option<u64>,
-    pub _cgroup: Option<u64>,
+    pub cgroup: Option<FileDesc>,
 }
 
 impl CloneArgs {
@@ -162,13 +170,41 @@ impl CloneFlags {
             | CloneFlags::CLONE_SETTLS
             | CloneFlags::CLONE_PARENT_SETTID
             | CloneFlags::CLONE_CHILD_SETTID
-            | CloneFlags::CLONE_CHILD_CLEARTID;
+            | CloneFlags::CLONE_CHILD_CLEARTID
+            | NS_CLONE_FLAGS;
         let unsupported_flags = *self - supported_flags;
         if !unsupported_flags.is_empty() {
             panic!("contains unsupported clone flags: {:?}", unsupported_flags);
         }
         Ok(())
     }
+
+    fn check_ns_flags(&self, ctx: &Context) -> Result<()> {
+        if !self.intersects(NS_CLONE_FLAGS) {
+            return Ok(());
+        }
+
+        if self.contains(CloneFlags::CLONE_NEWNS | CloneFlags::CLONE_FS) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "CLONE_NEWNS cannot be specified with CLONE_FS"
+            );
+        }
+        if self.contains(CloneFlags::CLONE_NEWIPC | CloneFlags::CLONE_SYSVSEM) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "CLONE_NEWIPC cannot be specified with CLONE_SYSVSEM"
+            );
+        }
+        if self.contains(CloneFlags::CLONE_THREAD) {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "new namespaces cannot be created for threads"
+            );
+        }
+
+        check_ns_permission(ctx)
+    }
 }
 
 /// Clone a child thread or child process.
@@ -181,18 +217,25 @@ pub fn clone_child(
     clone_args: CloneArgs,
 ) -> Result<Tid> {
     clone_args.flags.check_unsupported_flags()?;
+    clone_args.flags.check_ns_flags(ctx)?;
     if clone_args.flags.contains(CloneFlags::CLONE_THREAD) {
+        if clone_args.cgroup.is_some() {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "a thread cannot be placed in a different cgroup"
+            );
+        }
         let child_task = clone_child_task(ctx, parent_context, clone_args)?;
         let child_thread = Thread::borrow_from_task(&child_task);
         child_thread.run();
 
-        let child_tid = child_thread.tid();
+        let child_tid = ctx.process.pid_ns().pid_or_zero(child_thread.tid());
         Ok(child_tid)
     } else {
         let child_process = clone_child_process(ctx, parent_context, clone_args)?;
         child_process.run();
 
-        let child_pid = child_process.pid();
+        let child_pid = ctx.process.pid_ns().pid_or_zero(child_process.pid());
         Ok(child_pid)
     }
 }
@@ -213,6 +256,16 @@ fn clone_child_task(
     debug_assert!(clone_flags.contains(CloneFlags::CLONE_VM));
     debug_assert!(clone_flags.contains(CloneFlags::CLONE_FILES));
     debug_assert!(clone_flags.contains(CloneFlags::CLONE_SIGHAND));
+
+    // The threads of a process must be in the same PID namespace.
+    let pid_ns = process.pid_ns();
+    if !Arc::ptr_eq(pid_ns, process.nsproxy().pid_ns_for_children()) {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "a thread cannot be created after the PID namespace for children is changed"
+        );
+    }
+
     let child_root_vmar = process.root_vmar();
 
     let child_user_space = {
@@ -231,7 +284,15 @@ fn clone_child_task(
     // Inherit sigmask from current thread
     let sig_mask = posix_thread.sig_mask().load(Ordering::Relaxed).into();
 
+    // See `Process::move_to_cgroup` for why the lock is held until the child task is added.
+    let mut tasks = process.tasks().lock();
+    let cgroup = process.cgroup();
+    cgroup.try_charge_task()?;
+
     let child_tid = allocate_posix_tid();
+    pid_ns
+        .alloc_pids(child_tid)
+        .inspect_err(|_| cgroup.uncharge_task())?;
     let child_task = {
         let credentials = {
             let credentials = ctx.posix_thread.credentials();
@@ -244,12 +305,24 @@ fn clone_child_task(
         thread_builder.build()
     };
 
-    process.tasks().lock().push(child_task.clone());
+    tasks.push(child_task.clone());
+    drop(tasks);
+    clone_sched_attrs(ctx.thread, Thread::borrow_from_task(&child_task));
 
     let child_posix_thread = child_task.as_posix_thread().unwrap();
-    clone_parent_settid(child_tid, clone_args.parent_tid, clone_flags)?;
-    clone_child_cleartid(child_posix_thread, clone_args.child_tid, clone_flags)?;
-    clone_child_settid(child_posix_thread, clone_args.child_tid, clone_flags)?;
+    // The child task never runs if the following steps fail, so it is never uncharged on exit.
+    let child_ns_tid = pid_ns.pid_or_zero(child_tid);
+    clone_parent_settid(child_ns_tid, clone_args.parent_tid, clone_flags)
+        .and_then(|_| clone_child_cleartid(child_posix_thread, clone_args.child_tid, clone_flags))
+        .and_then(|_| clone_child_settid(child_posix_thread, clone_args.child_tid, clone_flags))
+        .inspect_err(|_| {
+            let mut tasks = process.tasks().lock();
+            tasks.retain(|task| !Arc::ptr_eq(task, &child_task));
+            process.cgroup().uncharge_task();
+            drop(tasks);
+            thread_table::remove_thread(child_tid);
+            pid_ns.free_pids(child_tid);
+        })?;
     Ok(child_task)
 }
 
@@ -292,8 +365,17 @@ fn clone_child_process(
     // clone file table
     let child_file_table = clone_files(process.file_table(), clone_flags);
 
+    // clone namespaces
+    let child_nsproxy = clone_nsproxy(process, clone_flags)?;
+
+    // clone cgroup
+    let child_cgroup = clone_cgroup(ctx, clone_args.cgroup)?;
+
     // clone fs
     let child_fs = clone_fs(process.fs(), clone_flags);
+    if clone_flags.contains(CloneFlags::CLONE_NEWNS) {
+        child_nsproxy.mnt_ns().translate_fs(&mut child_fs.write());
+    }
 
     // clone umask
     let child_umask = {
@@ -314,6 +396,8 @@ fn clone_child_process(
     let child_nice = process.nice().load(Ordering::Relaxed);
 
     let child_tid = allocate_posix_tid();
+    let child_pid_ns = child_nsproxy.pid_ns_for_children().clone();
+    child_pid_ns.alloc_pids(child_tid)?;
 
     let child = {
         let child_elf_path = process.executable_path();
@@ -340,19 +424,30 @@ fn clone_child_process(
             .fs(child_fs)
             .umask(child_umask)
             .sig_dispositions(child_sig_dispositions)
-            .nice(child_nice);
+            .nice(child_nice)
+            .pid_ns(child_pid_ns.clone())
+            .nsproxy(child_nsproxy)
+            .cgroup(child_cgroup);
 
-        process_builder.build()?
+        process_builder
+            .build()
+            .inspect_err(|_| child_pid_ns.free_pids(child_tid))?
     };
 
     if let Some(sig) = clone_args.exit_signal {
         child.set_exit_signal(sig);
     };
+    child.set_dumpable(process.is_dumpable());
 
     // Deals with clone flags
     let child_thread = thread_table::get_thread(child_tid).unwrap();
+    clone_sched_attrs(ctx.thread, &child_thread);
     let child_posix_thread = child_thread.as_posix_thread().unwrap();
-    clone_parent_settid(child_tid, clone_args.parent_tid, clone_flags)?;
+    clone_parent_settid(
+        process.pid_ns().pid_or_zero(child_tid),
+        clone_args.parent_tid,
+        clone_flags,
+    )?;
     clone_child_cleartid(child_posix_thread, clone_args.child_tid, clone_flags)?;
     clone_child_settid(child_posix_thread, clone_args.child_tid, clone_flags)?;
 
@@ -362,6 +457,12 @@ fn clone_child_process(
     Ok(child)
 }
 
+/// Inherits the scheduling policy, the priority and the CPU affinity of the parent thread.
+fn clone_sched_attrs(parent_thread: &Thread, child_thread: &Thread) {
+    child_thread.set_sched_policy(parent_thread.sched_policy(), parent_thread.priority());
+    child_thread.set_cpu_affinity(parent_thread.lock_cpu_affinity().clone());
+}
+
 fn clone_child_cleartid(
     child_posix_thread: &PosixThread,
     child_tidptr: Vaddr,
@@ -439,6 +540,33 @@ fn clone_cpu_context(
     child_context
 }
 
+/// Clones the namespaces of the child process. The parent and the child
+/// share the same namespaces unless new namespaces are requested.
+fn clone_nsproxy(parent: &Process, clone_flags: CloneFlags) -> Result<Arc<NsProxy>> {
+    let parent_nsproxy = parent.nsproxy();
+    if !clone_flags.intersects(NS_CLONE_FLAGS) {
+        return Ok(parent_nsproxy);
+    }
+
+    Ok(Arc::new(
+        parent_nsproxy.copy_with_flags(clone_flags, parent.pid_ns())?,
+    ))
+}
+
+/// Returns the cgroup of the child process, which is either specified by
+/// `CLONE_INTO_CGROUP` or inherited from the parent.
+fn clone_cgroup(ctx: &Context, cgroup_fd: Option<FileDesc>) -> Result<Arc<Cgroup>> {
+    let Some(cgroup_fd) = cgroup_fd else {
+        return Ok(ctx.process.cgroup());
+    };
+
+    let file_table = ctx.process.file_table().lock();
+    let file = file_table.get_file(cgroup_fd)?;
+    file.downcast_ref::<InodeHandle>()
+        .and_then(|inode_handle| get_cgroup_of_inode(inode_handle.dentry().inode()))
+        .ok_or_else(|| Error::with_message(Errno::EBADF, "the file is not a cgroup directory"))
+}
+
 fn clone_fs(
     parent_fs: &Arc<RwMutex<FsResolver>>,
     clone_flags: CloneFlags,
@@ -1,11 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::{process_table, Pid, Process, TermStatus};
+use super::{namespace::INIT_PROCESS_PID, Process, TermStatus};
 use crate::{
     prelude::*,
     process::{
         posix_thread::{do_exit, PosixThreadExt},
-        signal::signals::kernel::KernelSignal,
+        ptrace::detach_all_tracees,
+        signal::{constants::SIGKILL, signals::kernel::KernelSignal},
     },
     thread::Thread,
 };
@@ -18,6 +19,13 @@ pub fn do_exit_group(term_status: TermStatus) {
     }
     current.set_zombie(term_status);
 
+    // A cgroup with only zombie processes is considered empty
+    {
+        // See `Process::move_to_cgroup` for why the lock is held.
+        let _tasks = current.tasks().lock();
+        current.cgroup().remove_process(current.pid());
+    }
+
     // Exit all threads
     let tasks = current.tasks().lock().clone();
     for task in tasks {
@@ -28,6 +36,9 @@ pub fn do_exit_group(term_status: TermStatus) {
         }
     }
 
+    // Resume all threads traced by the process
+    detach_all_tracees(&current);
+
     // Sends parent-death signal
     // FIXME: according to linux spec, the signal should be sent when a posix thread which
     // creates child process exits, not when the whole process exits group.
@@ -46,14 +57,12 @@ pub fn do_exit_group(term_status: TermStatus) {
     drop(files);
 
     // Move children to the init process
-    if !is_init_process(&current) {
-        if let Some(init_process) = get_init_process() {
-            let mut init_children = init_process.children().lock();
-            for (_, child_process) in current.children().lock().extract_if(|_, _| true) {
-                let mut parent = child_process.parent.lock();
-                init_children.insert(child_process.pid(), child_process.clone());
-                parent.set_process(&init_process);
-            }
+    if let Some(reaper) = find_child_reaper(&current) {
+        let mut reaper_children = reaper.children().lock();
+        for (_, child_process) in current.children().lock().extract_if(|_, _| true) {
+            let mut parent = child_process.parent.lock();
+            reaper_children.insert(child_process.pid(), child_process.clone());
+            parent.set_process(&reaper);
         }
     }
 
@@ -67,13 +76,44 @@ pub fn do_exit_group(term_status: TermStatus) {
     };
 }
 
-const INIT_PROCESS_PID: Pid = 1;
+/// Finds the process that adopts the children of the exiting `process`.
+///
+/// The children are adopted by the init process of the PID namespace of the exiting process.
+/// If the exiting process is the init process of a PID namespace other than the initial one,
+/// the namespace is torn down, and the children are adopted by the init process of the parent
+/// namespace. This method returns `None` if the exiting process is the init process of the
+/// initial namespace.
+fn find_child_reaper(process: &Process) -> Option<Arc<Process>> {
+    let mut pid_ns = process.pid_ns();
+    if pid_ns.pid_of(process.pid()) == Some(INIT_PROCESS_PID) {
+        let parent_ns = pid_ns.parent()?;
+        zap_pid_ns_processes(process);
+        pid_ns = parent_ns;
+    }
 
-/// Gets the init process
-fn get_init_process() -> Option<Arc<Process>> {
-    process_table::get_process(INIT_PROCESS_PID)
+    // The init process of a namespace may have exited while the processes in the namespace
+    // are being killed.
+    loop {
+        if let Some(init_process) = pid_ns.init_process()
+            && !init_process.is_zombie()
+        {
+            return Some(init_process);
+        }
+        pid_ns = pid_ns.parent()?;
+    }
 }
 
-fn is_init_process(process: &Process) -> bool {
-    process.pid() == INIT_PROCESS_PID
+/// Kills all the processes in the PID namespace of the exiting `init_process`.
+///
+/// FIXME: Linux waits for all the processes in the namespace to be reaped before the init
+/// process of the namespace becomes a zombie. We only kill them here.
+fn zap_pid_ns_processes(init_process: &Process) {
+    let pid_ns = init_process.pid_ns();
+    pid_ns.set_dead();
+
+    for (_, process) in pid_ns.processes() {
+        if !core::ptr::eq(process.as_ref(), init_process) {
+            process.enqueue_signal(KernelSignal::new(SIGKILL));
+        }
+    }
 }
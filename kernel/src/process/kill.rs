@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use super::{
+    namespace::INIT_PROCESS_PID,
     posix_thread::{thread_table, PosixThreadExt},
     process_table,
     signal::{
@@ -101,16 +102,18 @@ pub fn tgkill(tid: Tid, tgid: Pid, signal: Option<UserSignal>, ctx: &Context) ->
 /// Sends a signal to all processes except current process and init process, using
 /// the current process as the sender.
 ///
+/// Only the processes in the PID namespace of the current process and its descendant
+/// namespaces are signaled, and the init process is the one of the namespace.
+///
 /// The credentials of the current process will be checked to determine
 /// if it is authorized to send the signal to the target group.
 pub fn kill_all(signal: Option<UserSignal>, ctx: &Context) -> Result<()> {
-    let current = current!();
-    for process in process_table::process_table().iter() {
-        if Arc::ptr_eq(&current, process) || process.is_init_process() {
+    for (pid, process) in ctx.process.pid_ns().processes() {
+        if core::ptr::eq(ctx.process, process.as_ref()) || pid == INIT_PROCESS_PID {
             continue;
         }
 
-        kill_process(process, signal, ctx)?;
+        kill_process(&process, signal, ctx)?;
     }
 
     Ok(())
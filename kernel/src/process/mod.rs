@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub mod cgroup;
 mod clone;
 pub mod credentials;
 mod exit;
 mod kill;
+pub mod namespace;
 pub mod posix_thread;
 #[allow(clippy::module_inception)]
 mod process;
@@ -11,6 +13,7 @@ mod process_filter;
 pub mod process_table;
 mod process_vm;
 mod program_loader;
+pub mod ptrace;
 mod rlimit;
 pub mod signal;
 mod status;
@@ -30,7 +33,7 @@ pub use process_vm::{MAX_ARGV_NUMBER, MAX_ARG_LEN, MAX_ENVP_NUMBER, MAX_ENV_LEN}
 pub use program_loader::{check_executable_file, load_program_to_vm};
 pub use rlimit::ResourceType;
 pub use term_status::TermStatus;
-pub use wait::{wait_child_exit, WaitOptions};
+pub use wait::{wait_child_exit, WaitOptions, WaitedChild};
 
 pub(super) fn init() {
     process::init();
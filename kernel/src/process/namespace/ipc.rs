@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    ipc::{
+        mqueue::MqueueFs, msg::msg_queue::MsgQueueTable, semaphore::system_v::sem_set::SemSetTable,
+        shm::shared_memory::ShmTable,
+    },
+    prelude::*,
+};
+
+/// An IPC namespace, which isolates the System V IPC objects and the POSIX message queues.
+pub struct IpcNamespace {
+    shm_table: Arc<ShmTable>,
+    msg_queue_table: Arc<MsgQueueTable>,
+    sem_set_table: Arc<SemSetTable>,
+    mqueue_fs: MqueueFs,
+}
+
+impl IpcNamespace {
+    /// Creates a new namespace without any IPC objects.
+    pub(super) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            shm_table: ShmTable::new(),
+            msg_queue_table: MsgQueueTable::new(),
+            sem_set_table: SemSetTable::new(),
+            mqueue_fs: MqueueFs::new(),
+        })
+    }
+
+    /// Returns the System V shared memory segments.
+    pub fn shm_table(&self) -> &Arc<ShmTable> {
+        &self.shm_table
+    }
+
+    /// Returns the System V message queues.
+    pub fn msg_queue_table(&self) -> &Arc<MsgQueueTable> {
+        &self.msg_queue_table
+    }
+
+    /// Returns the System V semaphore sets.
+    pub fn sem_set_table(&self) -> &Arc<SemSetTable> {
+        &self.sem_set_table
+    }
+
+    /// Returns the POSIX message queues.
+    pub fn mqueue_fs(&self) -> &MqueueFs {
+        &self.mqueue_fs
+    }
+}
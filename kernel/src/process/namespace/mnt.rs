@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::{
+        fs_resolver::FsResolver,
+        path::{Dentry, MountNode},
+        rootfs::root_mount,
+    },
+    prelude::*,
+};
+
+/// A mount namespace, which isolates the mount tree.
+///
+/// The dentries are shared by the mount trees of all the namespaces, but whether a dentry
+/// is a mountpoint is decided by the mount tree that the dentry is looked up in.
+pub struct MntNamespace {
+    root: Arc<MountNode>,
+}
+
+impl MntNamespace {
+    pub(super) fn new_init() -> Arc<Self> {
+        Arc::new(Self {
+            root: root_mount().clone(),
+        })
+    }
+
+    /// Creates a new namespace with a copy of the mount tree of this one.
+    pub(super) fn new_copy(&self) -> Arc<Self> {
+        Arc::new(Self {
+            root: self.root.copy_mount_node_tree(),
+        })
+    }
+
+    /// Returns the root of the mount tree.
+    pub fn root(&self) -> &Arc<MountNode> {
+        &self.root
+    }
+
+    /// Moves the root and the working directory of the `fs` to the corresponding
+    /// directories in this namespace, which is copied from the namespace of the `fs`.
+    ///
+    /// If a directory has no corresponding one, it is set to the root of this namespace.
+    pub fn translate_fs(&self, fs: &mut FsResolver) {
+        let new_root = fs
+            .root()
+            .find_corresponding_dentry(&self.root)
+            .unwrap_or_else(|| Dentry::new_fs_root(self.root.clone()));
+        let new_cwd = fs
+            .cwd()
+            .find_corresponding_dentry(&self.root)
+            .unwrap_or_else(|| Dentry::new_fs_root(self.root.clone()));
+        fs.set_root(new_root);
+        fs.set_cwd(new_cwd);
+    }
+
+    /// Sets both the root and the working directory of the `fs` to the root of this namespace.
+    pub fn reset_fs(&self, fs: &mut FsResolver) {
+        fs.set_root(Dentry::new_fs_root(self.root.clone()));
+        fs.set_cwd(Dentry::new_fs_root(self.root.clone()));
+    }
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Namespaces.
+//!
+//! A namespace wraps a global resource so that the processes in the namespace
+//! appear to have their own isolated instance of the resource.
+//! Except for the PID namespace, which is fixed for the lifetime of a process,
+//! the namespaces of a process are held by its [`NsProxy`]. An `NsProxy` is shared
+//! with the child processes until `clone`, `unshare` or `setns` replaces some of the namespaces.
+//! Instead of its own PID namespace, the `NsProxy` of a process holds the PID namespace
+//! that its child processes will be created in.
+
+mod ipc;
+mod mnt;
+mod net;
+mod pid;
+mod uts;
+
+pub use ipc::IpcNamespace;
+pub use mnt::MntNamespace;
+pub use net::NetNamespace;
+pub use pid::{PidNamespace, INIT_PROCESS_PID};
+use spin::Once;
+pub use uts::{UtsName, UtsNamespace, UTS_NAME_LEN};
+
+use super::{credentials::capabilities::CapSet, CloneFlags, Process};
+use crate::prelude::*;
+
+/// The types of the namespaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NsType {
+    Mnt,
+    Pid,
+    Uts,
+    Ipc,
+    Net,
+}
+
+impl NsType {
+    pub const ALL: [Self; 5] = [Self::Mnt, Self::Pid, Self::Uts, Self::Ipc, Self::Net];
+
+    /// Returns the name of the namespace type, which is used as the file name in `/proc/[pid]/ns`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mnt => "mnt",
+            Self::Pid => "pid",
+            Self::Uts => "uts",
+            Self::Ipc => "ipc",
+            Self::Net => "net",
+        }
+    }
+
+    /// Returns the clone flag that creates a new namespace of the type.
+    pub fn clone_flag(&self) -> CloneFlags {
+        match self {
+            Self::Mnt => CloneFlags::CLONE_NEWNS,
+            Self::Pid => CloneFlags::CLONE_NEWPID,
+            Self::Uts => CloneFlags::CLONE_NEWUTS,
+            Self::Ipc => CloneFlags::CLONE_NEWIPC,
+            Self::Net => CloneFlags::CLONE_NEWNET,
+        }
+    }
+}
+
+/// The clone flags that create new namespaces.
+pub const NS_CLONE_FLAGS: CloneFlags = CloneFlags::CLONE_NEWNS
+    .union(CloneFlags::CLONE_NEWUTS)
+    .union(CloneFlags::CLONE_NEWIPC)
+    .union(CloneFlags::CLONE_NEWPID)
+    .union(CloneFlags::CLONE_NEWNET);
+
+/// The namespaces of a process.
+#[derive(Clone)]
+pub struct NsProxy {
+    mnt_ns: Arc<MntNamespace>,
+    uts_ns: Arc<UtsNamespace>,
+    ipc_ns: Arc<IpcNamespace>,
+    net_ns: Arc<NetNamespace>,
+    /// The PID namespace of the child processes.
+    pid_ns_for_children: Arc<PidNamespace>,
+}
+
+static INIT_NSPROXY: Once<Arc<NsProxy>> = Once::new();
+
+impl NsProxy {
+    /// Returns the namespaces of the init process.
+    pub fn init() -> &'static Arc<NsProxy> {
+        INIT_NSPROXY.call_once(|| {
+            Arc::new(Self {
+                mnt_ns: MntNamespace::new_init(),
+                uts_ns: UtsNamespace::new_init(),
+                ipc_ns: IpcNamespace::new(),
+                net_ns: NetNamespace::new_init(),
+                pid_ns_for_children: PidNamespace::new_init(),
+            })
+        })
+    }
+
+    pub fn mnt_ns(&self) -> &Arc<MntNamespace> {
+        &self.mnt_ns
+    }
+
+    pub fn uts_ns(&self) -> &Arc<UtsNamespace> {
+        &self.uts_ns
+    }
+
+    pub fn ipc_ns(&self) -> &Arc<IpcNamespace> {
+        &self.ipc_ns
+    }
+
+    pub fn net_ns(&self) -> &Arc<NetNamespace> {
+        &self.net_ns
+    }
+
+    pub fn pid_ns_for_children(&self) -> &Arc<PidNamespace> {
+        &self.pid_ns_for_children
+    }
+
+    /// Creates a copy of the namespaces, in which the namespaces specified by the `flags`
+    /// are newly created and the others are shared with this one.
+    ///
+    /// The `pid_ns` is the PID namespace of the process that owns this `NsProxy`. A new PID
+    /// namespace is created as its child, and only if the PID namespace for the child
+    /// processes has not been changed.
+    ///
+    /// If a new mount namespace is created, the caller should move the root and the working
+    /// directory of the process into it with [`MntNamespace::translate_fs`].
+    pub fn copy_with_flags(&self, flags: CloneFlags, pid_ns: &Arc<PidNamespace>) -> Result<Self> {
+        let mut new_nsproxy = self.clone();
+        if flags.contains(CloneFlags::CLONE_NEWPID) {
+            if !Arc::ptr_eq(&self.pid_ns_for_children, pid_ns) {
+                return_errno_with_message!(
+                    Errno::EINVAL,
+                    "the PID namespace for children has been changed"
+                );
+            }
+            new_nsproxy.pid_ns_for_children = pid_ns.new_child()?;
+        }
+        if flags.contains(CloneFlags::CLONE_NEWNET) {
+            new_nsproxy.net_ns = NetNamespace::new();
+        }
+        if flags.contains(CloneFlags::CLONE_NEWNS) {
+            new_nsproxy.mnt_ns = self.mnt_ns.new_copy();
+        }
+        if flags.contains(CloneFlags::CLONE_NEWUTS) {
+            new_nsproxy.uts_ns = self.uts_ns.new_copy();
+        }
+        if flags.contains(CloneFlags::CLONE_NEWIPC) {
+            new_nsproxy.ipc_ns = IpcNamespace::new();
+        }
+        Ok(new_nsproxy)
+    }
+
+    /// Creates a copy of the namespaces, in which the namespace of the same type as `ns`
+    /// is replaced by `ns`.
+    ///
+    /// If the mount namespace is replaced, the caller should reset the root and the working
+    /// directory of the process with [`MntNamespace::reset_fs`].
+    pub fn copy_with_ns(&self, ns: &NsRef) -> Self {
+        let mut new_nsproxy = self.clone();
+        match ns {
+            NsRef::Mnt(mnt_ns) => new_nsproxy.mnt_ns = mnt_ns.clone(),
+            NsRef::Uts(uts_ns) => new_nsproxy.uts_ns = uts_ns.clone(),
+            NsRef::Ipc(ipc_ns) => new_nsproxy.ipc_ns = ipc_ns.clone(),
+            NsRef::Net(net_ns) => new_nsproxy.net_ns = net_ns.clone(),
+            NsRef::Pid(pid_ns) => new_nsproxy.pid_ns_for_children = pid_ns.clone(),
+        }
+        new_nsproxy
+    }
+}
+
+/// A reference to a namespace of any type.
+#[derive(Clone)]
+pub enum NsRef {
+    Mnt(Arc<MntNamespace>),
+    Pid(Arc<PidNamespace>),
+    Uts(Arc<UtsNamespace>),
+    Ipc(Arc<IpcNamespace>),
+    Net(Arc<NetNamespace>),
+}
+
+impl NsRef {
+    /// Returns the namespace of the `ns_type` that the `process` is currently in.
+    pub fn of_process(ns_type: NsType, process: &Process) -> Self {
+        let nsproxy = process.nsproxy();
+        match ns_type {
+            NsType::Mnt => Self::Mnt(nsproxy.mnt_ns.clone()),
+            NsType::Pid => Self::Pid(process.pid_ns().clone()),
+            NsType::Uts => Self::Uts(nsproxy.uts_ns.clone()),
+            NsType::Ipc => Self::Ipc(nsproxy.ipc_ns.clone()),
+            NsType::Net => Self::Net(nsproxy.net_ns.clone()),
+        }
+    }
+
+    pub fn ns_type(&self) -> NsType {
+        match self {
+            Self::Mnt(_) => NsType::Mnt,
+            Self::Pid(_) => NsType::Pid,
+            Self::Uts(_) => NsType::Uts,
+            Self::Ipc(_) => NsType::Ipc,
+            Self::Net(_) => NsType::Net,
+        }
+    }
+
+    /// Returns whether the two references refer to the same namespace.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Mnt(this), Self::Mnt(other)) => Arc::ptr_eq(this, other),
+            (Self::Pid(this), Self::Pid(other)) => Arc::ptr_eq(this, other),
+            (Self::Uts(this), Self::Uts(other)) => Arc::ptr_eq(this, other),
+            (Self::Ipc(this), Self::Ipc(other)) => Arc::ptr_eq(this, other),
+            (Self::Net(this), Self::Net(other)) => Arc::ptr_eq(this, other),
+            _ => false,
+        }
+    }
+}
+
+/// Checks whether the credentials of the current thread allow creating or entering namespaces.
+pub fn check_ns_permission(ctx: &Context) -> Result<()> {
+    let credentials = ctx.posix_thread.credentials();
+    if !credentials.effective_capset().contains(CapSet::SYS_ADMIN) {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "CAP_SYS_ADMIN is required to create or enter namespaces"
+        );
+    }
+    Ok(())
+}
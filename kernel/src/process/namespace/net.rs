@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{net::iface::IfaceSet, prelude::*};
+
+/// A network namespace.
+///
+/// Each network namespace has its own ifaces and its own routing table, which are kept in an
+/// [`IfaceSet`]. A new namespace only has a loopback iface.
+///
+/// A socket is bound to an iface of the namespace of the process that binds it, and keeps
+/// using the iface even if it is passed to a process in another namespace, as in Linux.
+pub struct NetNamespace {
+    /// The ifaces of the namespace, or `None` for the initial namespace, which uses the
+    /// ifaces created at boot time.
+    iface_set: Option<Arc<IfaceSet>>,
+}
+
+impl NetNamespace {
+    pub(super) fn new_init() -> Arc<Self> {
+        Arc::new(Self { iface_set: None })
+    }
+
+    /// Creates a new network namespace with a loopback iface.
+    pub(super) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            iface_set: Some(IfaceSet::new_isolated()),
+        })
+    }
+
+    /// Returns the ifaces of the namespace.
+    pub fn iface_set(&self) -> &Arc<IfaceSet> {
+        match self.iface_set.as_ref() {
+            Some(iface_set) => iface_set,
+            None => IfaceSet::init_set(),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    prelude::*,
+    process::{posix_thread::thread_table, process_table, Pid, Process},
+    thread::{Thread, Tid},
+};
+
+/// The maximum nesting depth of the PID namespaces.
+const MAX_PID_NS_LEVEL: u32 = 32;
+
+/// The maximum PID in a PID namespace, which is the default value of
+/// `/proc/sys/kernel/pid_max` in Linux.
+const PID_MAX: Pid = 1 << 22;
+
+/// The PID of the init process of a PID namespace.
+pub const INIT_PROCESS_PID: Pid = 1;
+
+/// A PID namespace.
+///
+/// The processes and the threads are numbered separately in every PID namespace.
+/// A process in a PID namespace can only see the processes in the same namespace and in the
+/// descendant namespaces, and sees them with the PIDs allocated in its own namespace.
+///
+/// The processes and the threads are kept in the global tables (see [`process_table`] and
+/// [`thread_table`]), which are indexed by the PIDs in the initial namespace, i.e., the
+/// global PIDs. So a namespace other than the initial one maintains a table that translates
+/// between its own PIDs and the global PIDs of the processes and the threads in it.
+/// The process groups and the sessions are identified by the PIDs of their leaders, so their
+/// IDs are translated in the same way as long as their leaders have not been reaped.
+pub struct PidNamespace {
+    parent: Option<Arc<PidNamespace>>,
+    level: u32,
+    table: Mutex<PidTable>,
+}
+
+/// The table of the PIDs allocated in a PID namespace.
+#[derive(Default)]
+struct PidTable {
+    /// The PID that was allocated most recently.
+    last_pid: Pid,
+    /// The global PIDs indexed by the PIDs in the namespace.
+    global_pids: BTreeMap<Pid, Pid>,
+    /// The PIDs in the namespace indexed by the global PIDs.
+    local_pids: BTreeMap<Pid, Pid>,
+    /// Whether the init process of the namespace has exited.
+    ///
+    /// No more processes can be created in the namespace after its init process exits.
+    is_dead: bool,
+}
+
+impl PidNamespace {
+    pub(super) fn new_init() -> Arc<Self> {
+        Arc::new(Self {
+            parent: None,
+            level: 0,
+            table: Mutex::new(PidTable::default()),
+        })
+    }
+
+    /// Creates a new child namespace.
+    pub(super) fn new_child(self: &Arc<Self>) -> Result<Arc<Self>> {
+        if self.level >= MAX_PID_NS_LEVEL {
+            return_errno_with_message!(Errno::ENOSPC, "too many nested PID namespaces");
+        }
+
+        Ok(Arc::new(Self {
+            parent: Some(self.clone()),
+            level: self.level + 1,
+            table: Mutex::new(PidTable::default()),
+        }))
+    }
+
+    /// Returns the nesting depth of the namespace, where the initial namespace is at level 0.
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Returns the parent namespace, or `None` if this is the initial namespace.
+    pub fn parent(&self) -> Option<&Arc<PidNamespace>> {
+        self.parent.as_ref()
+    }
+
+    /// Returns whether the processes in the `other` namespace are visible in this one,
+    /// i.e., whether this namespace is `other` or one of its ancestors.
+    pub fn is_ancestor_of(&self, other: &PidNamespace) -> bool {
+        let mut ns = other;
+        loop {
+            if core::ptr::eq(ns, self) {
+                return true;
+            }
+            match ns.parent.as_ref() {
+                Some(parent) if parent.level >= self.level => ns = parent,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Allocates the PIDs for a new process or thread in this namespace and in all
+    /// the ancestor namespaces.
+    ///
+    /// The `global_pid` is the PID of the process or the thread in the initial namespace,
+    /// which has already been allocated.
+    pub(in crate::process) fn alloc_pids(&self, global_pid: Pid) -> Result<()> {
+        let mut ns = self;
+        while let Some(parent) = ns.parent.as_ref() {
+            if let Err(err) = ns.table.lock().alloc(global_pid) {
+                self.free_pids_until(global_pid, ns);
+                return Err(err);
+            }
+            ns = parent;
+        }
+        Ok(())
+    }
+
+    /// Frees the PIDs of a process or thread in this namespace and in all the ancestor
+    /// namespaces.
+    pub(in crate::process) fn free_pids(&self, global_pid: Pid) {
+        let mut ns = self;
+        while let Some(parent) = ns.parent.as_ref() {
+            ns.table.lock().free(global_pid);
+            ns = parent;
+        }
+    }
+
+    /// Frees the PIDs in the namespaces from this one up to, but excluding, `end`.
+    fn free_pids_until(&self, global_pid: Pid, end: &PidNamespace) {
+        let mut ns = self;
+        while !core::ptr::eq(ns, end) {
+            ns.table.lock().free(global_pid);
+            ns = ns.parent.as_ref().unwrap();
+        }
+    }
+
+    /// Translates a global PID to the PID in this namespace.
+    ///
+    /// This method returns `None` if the process or the thread is not visible in the namespace.
+    pub fn pid_of(&self, global_pid: Pid) -> Option<Pid> {
+        if self.parent.is_none() {
+            return Some(global_pid);
+        }
+        self.table.lock().local_pids.get(&global_pid).copied()
+    }
+
+    /// Translates a PID in this namespace to the global PID.
+    ///
+    /// This method returns `None` if no process or thread has the PID in the namespace.
+    pub fn global_pid_of(&self, pid: Pid) -> Option<Pid> {
+        if self.parent.is_none() {
+            return Some(pid);
+        }
+        self.table.lock().global_pids.get(&pid).copied()
+    }
+
+    /// Translates a global PID to the PID in this namespace, or to 0 if the process or the
+    /// thread is not visible in the namespace.
+    ///
+    /// This is the value that Linux reports for an invisible process, e.g., for the parent
+    /// of the init process of a namespace.
+    pub fn pid_or_zero(&self, global_pid: Pid) -> Pid {
+        self.pid_of(global_pid).unwrap_or(0)
+    }
+
+    /// Gets the process with the `pid` in this namespace.
+    pub fn get_process(&self, pid: Pid) -> Option<Arc<Process>> {
+        process_table::get_process(self.global_pid_of(pid)?)
+    }
+
+    /// Gets the thread with the `tid` in this namespace.
+    pub fn get_thread(&self, tid: Tid) -> Option<Arc<Thread>> {
+        thread_table::get_thread(self.global_pid_of(tid)?)
+    }
+
+    /// Returns the processes that are visible in this namespace, in the order of their PIDs
+    /// in this namespace.
+    pub fn processes(&self) -> Vec<(Pid, Arc<Process>)> {
+        if self.parent.is_none() {
+            return process_table::process_table()
+                .iter()
+                .map(|process| (process.pid(), process.clone()))
+                .collect();
+        }
+
+        let global_pids: Vec<(Pid, Pid)> = self
+            .table
+            .lock()
+            .global_pids
+            .iter()
+            .map(|(pid, global_pid)| (*pid, *global_pid))
+            .collect();
+        global_pids
+            .into_iter()
+            .filter_map(|(pid, global_pid)| Some((pid, process_table::get_process(global_pid)?)))
+            .collect()
+    }
+
+    /// Returns the init process of the namespace, which adopts the orphaned processes
+    /// in the namespace.
+    pub fn init_process(&self) -> Option<Arc<Process>> {
+        self.get_process(INIT_PROCESS_PID)
+    }
+
+    /// Marks that the init process of the namespace has exited.
+    ///
+    /// Creating new processes in the namespace fails afterwards.
+    pub(in crate::process) fn set_dead(&self) {
+        self.table.lock().is_dead = true;
+    }
+}
+
+impl PidTable {
+    fn alloc(&mut self, global_pid: Pid) -> Result<()> {
+        if self.is_dead {
+            return_errno_with_message!(
+                Errno::ENOMEM,
+                "the init process of the PID namespace has exited"
+            );
+        }
+        if self.global_pids.len() >= (PID_MAX - 1) as usize {
+            return_errno_with_message!(Errno::EAGAIN, "no PID is available in the namespace");
+        }
+
+        // Like Linux, allocate the PIDs cyclically, so that a freed PID is not reused soon.
+        let mut pid = self.last_pid;
+        loop {
+            pid = if pid + 1 >= PID_MAX { 1 } else { pid + 1 };
+            if !self.global_pids.contains_key(&pid) {
+                break;
+            }
+        }
+
+        self.last_pid = pid;
+        self.global_pids.insert(pid, global_pid);
+        self.local_pids.insert(global_pid, pid);
+        Ok(())
+    }
+
+    fn free(&mut self, global_pid: Pid) {
+        if let Some(pid) = self.local_pids.remove(&global_pid) {
+            self.global_pids.remove(&pid);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::prelude::*;
+
+// We don't use the real name and version of our os here. Instead, we pick up fake values witch is the same as the ones of linux.
+// The values are used to fool glibc since glibc will check the version and os name.
+lazy_static! {
+    /// used to fool glibc
+    static ref SYS_NAME: CString = CString::new("Linux").unwrap();
+    static ref NODE_NAME: CString = CString::new("WHITLEY").unwrap();
+    static ref RELEASE: CString = CString::new("5.13.0").unwrap();
+    static ref VERSION: CString = CString::new("5.13.0").unwrap();
+    static ref MACHINE: CString = CString::new("x86_64").unwrap();
+    static ref DOMAIN_NAME: CString = CString::new("").unwrap();
+}
+
+/// The maximum length of the fields in [`UtsName`], excluding the trailing null byte.
+pub const UTS_NAME_LEN: usize = 64;
+
+const UTS_FIELD_LEN: usize = UTS_NAME_LEN + 1;
+
+/// The `utsname` structure in Linux.
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+pub struct UtsName {
+    sysname: [u8; UTS_FIELD_LEN],
+    nodename: [u8; UTS_FIELD_LEN],
+    release: [u8; UTS_FIELD_LEN],
+    version: [u8; UTS_FIELD_LEN],
+    machine: [u8; UTS_FIELD_LEN],
+    domainname: [u8; UTS_FIELD_LEN],
+}
+
+impl UtsName {
+    const fn new() -> Self {
+        UtsName {
+            sysname: [0; UTS_FIELD_LEN],
+            nodename: [0; UTS_FIELD_LEN],
+            release: [0; UTS_FIELD_LEN],
+            version: [0; UTS_FIELD_LEN],
+            machine: [0; UTS_FIELD_LEN],
+            domainname: [0; UTS_FIELD_LEN],
+        }
+    }
+}
+
+/// A UTS namespace, which isolates the host name and the domain name.
+pub struct UtsNamespace {
+    uts_name: RwLock<UtsName>,
+}
+
+impl UtsNamespace {
+    pub(super) fn new_init() -> Arc<Self> {
+        let mut uts_name = UtsName::new();
+        copy_bytes_to_field(SYS_NAME.as_bytes(), &mut uts_name.sysname);
+        copy_bytes_to_field(NODE_NAME.as_bytes(), &mut uts_name.nodename);
+        copy_bytes_to_field(RELEASE.as_bytes(), &mut uts_name.release);
+        copy_bytes_to_field(VERSION.as_bytes(), &mut uts_name.version);
+        copy_bytes_to_field(MACHINE.as_bytes(), &mut uts_name.machine);
+        copy_bytes_to_field(DOMAIN_NAME.as_bytes(), &mut uts_name.domainname);
+
+        Arc::new(Self {
+            uts_name: RwLock::new(uts_name),
+        })
+    }
+
+    /// Creates a new namespace that starts with the names of this one.
+    pub(super) fn new_copy(&self) -> Arc<Self> {
+        Arc::new(Self {
+            uts_name: RwLock::new(*self.uts_name.read()),
+        })
+    }
+
+    /// Returns the names of the namespace.
+    pub fn uts_name(&self) -> UtsName {
+        *self.uts_name.read()
+    }
+
+    /// Sets the host name.
+    pub fn set_hostname(&self, hostname: &[u8]) -> Result<()> {
+        if hostname.len() > UTS_NAME_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the host name is too long");
+        }
+        copy_bytes_to_field(hostname, &mut self.uts_name.write().nodename);
+        Ok(())
+    }
+
+    /// Sets the domain name.
+    pub fn set_domainname(&self, domainname: &[u8]) -> Result<()> {
+        if domainname.len() > UTS_NAME_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the domain name is too long");
+        }
+        copy_bytes_to_field(domainname, &mut self.uts_name.write().domainname);
+        Ok(())
+    }
+}
+
+/// Copies the bytes to the field and pads the rest of the field with null bytes.
+fn copy_bytes_to_field(src: &[u8], field: &mut [u8; UTS_FIELD_LEN]) {
+    debug_assert!(src.len() <= UTS_NAME_LEN);
+    field.fill(0);
+    field[..src.len()].copy_from_slice(src);
+}
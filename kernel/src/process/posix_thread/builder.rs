@@ -2,6 +2,8 @@
 
 #![allow(dead_code)]
 
+use core::sync::atomic::Ordering;
+
 use ostd::{cpu::CpuSet, task::Task, user::UserSpace};
 
 use super::{thread_table, PosixThread};
@@ -9,6 +11,7 @@ use crate::{
     prelude::*,
     process::{
         posix_thread::name::ThreadName,
+        ptrace::PtraceState,
         signal::{sig_mask::AtomicSigMask, sig_queues::SigQueues},
         Credentials, Process,
     },
@@ -86,6 +89,11 @@ impl PosixThreadBuilder {
             sig_queues,
         } = self;
 
+        // The new thread inherits the nice value of its process.
+        let priority = process.upgrade().map_or_else(Priority::default, |process| {
+            process.nice().load(Ordering::Relaxed).into()
+        });
+
         Arc::new_cyclic(|weak_task| {
             let posix_thread = {
                 let prof_clock = ProfClock::new();
@@ -108,11 +116,11 @@ impl PosixThreadBuilder {
                     prof_clock,
                     virtual_timer_manager,
                     prof_timer_manager,
+                    ptrace: PtraceState::new(),
                 }
             };
 
             let status = ThreadStatus::Init;
-            let priority = Priority::default();
             let cpu_affinity = CpuSet::new_full();
             let thread = Arc::new(Thread::new(
                 weak_task.clone(),
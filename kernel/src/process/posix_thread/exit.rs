@@ -4,7 +4,7 @@ use super::{futex::futex_wake, robust_list::wake_robust_futex, thread_table, Pos
 use crate::{
     get_current_userspace,
     prelude::*,
-    process::{do_exit_group, TermStatus},
+    process::{do_exit_group, ptrace::untrace_exiting_thread, TermStatus},
     thread::{Thread, Tid},
 };
 
@@ -17,7 +17,15 @@ pub fn do_exit(thread: &Thread, posix_thread: &PosixThread, term_status: TermSta
     if thread.status().is_exited() {
         return Ok(());
     }
-    thread.exit();
+    let process = posix_thread.process();
+    {
+        // See `Process::move_to_cgroup` for why the lock is held.
+        let _tasks = process.tasks().lock();
+        thread.exit();
+        process.cgroup().uncharge_task();
+    }
+
+    untrace_exiting_thread(posix_thread);
 
     let tid = posix_thread.tid;
 
@@ -31,13 +39,15 @@ pub fn do_exit(thread: &Thread, posix_thread: &PosixThread, term_status: TermSta
             .unwrap();
         *clear_ctid = 0;
     }
-    // exit the robust list: walk the robust list; mark futex words as dead and do futex wake
-    wake_robust_list(posix_thread, tid);
+    // exit the robust list: walk the robust list; mark futex words as dead and do futex wake.
+    // The futex words hold the TIDs in the PID namespace of the process.
+    wake_robust_list(posix_thread, process.pid_ns().pid_or_zero(tid));
 
-    if tid != posix_thread.process().pid() {
+    if tid != process.pid() {
         // We don't remove main thread.
         // The main thread is removed when the process is reaped.
         thread_table::remove_thread(tid);
+        process.pid_ns().free_pids(tid);
     }
 
     if posix_thread.is_main_thread(tid) || posix_thread.is_last_thread() {
@@ -45,7 +55,7 @@ pub fn do_exit(thread: &Thread, posix_thread: &PosixThread, term_status: TermSta
         do_exit_group(term_status);
     }
 
-    futex_wake(Arc::as_ptr(&posix_thread.process()) as Vaddr, 1, None)?;
+    futex_wake(Arc::as_ptr(&process) as Vaddr, 1, None)?;
     Ok(())
 }
 
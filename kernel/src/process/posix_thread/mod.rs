@@ -9,6 +9,7 @@ use ostd::sync::Waker;
 
 use super::{
     kill::SignalSenderIds,
+    ptrace::PtraceState,
     signal::{
         sig_mask::{AtomicSigMask, SigMask, SigSet},
         sig_num::SigNum,
@@ -21,7 +22,7 @@ use super::{
 use crate::{
     events::Observer,
     prelude::*,
-    process::signal::constants::SIGCONT,
+    process::signal::constants::{SIGCONT, SIGKILL},
     thread::{Thread, Tid},
     time::{clocks::ProfClock, Timer, TimerManager},
 };
@@ -79,6 +80,9 @@ pub struct PosixThread {
 
     /// A manager that manages timers based on the profiling clock of the current thread.
     prof_timer_manager: Arc<TimerManager>,
+
+    /// The state of being traced by `ptrace`.
+    ptrace: PtraceState,
 }
 
 impl PosixThread {
@@ -116,15 +120,16 @@ impl PosixThread {
         &self.sig_mask
     }
 
+    /// Returns the signals that are pending on the thread or on its process.
     pub fn sig_pending(&self) -> SigSet {
-        self.sig_queues.sig_pending()
+        self.sig_queues.sig_pending() | self.process().sig_queues().sig_pending()
     }
 
     /// Returns whether the thread has some pending signals
     /// that are not blocked.
     pub fn has_pending(&self) -> bool {
         let blocked = self.sig_mask().load(Ordering::Relaxed);
-        self.sig_queues.has_pending(blocked)
+        self.sig_queues.has_pending(blocked) || self.process().sig_queues().has_pending(blocked)
     }
 
     /// Returns whether the signal is blocked by the thread.
@@ -205,10 +210,20 @@ impl PosixThread {
     /// Enqueues a thread-directed signal. This method should only be used for enqueue kernel
     /// signal and fault signal.
     pub fn enqueue_signal(&self, signal: Box<dyn Signal>) {
+        let sig_num = signal.num();
         self.sig_queues.enqueue(signal);
+        self.wake_up_for_signal(sig_num);
+    }
+
+    /// Wakes up the thread to handle the newly pending signal `sig_num`.
+    pub(in crate::process) fn wake_up_for_signal(&self, sig_num: SigNum) {
         if let Some(waker) = &*self.signalled_waker.lock() {
             waker.wake_up();
         }
+        // A thread stopped by `ptrace` must be woken up to be killed.
+        if sig_num == SIGKILL {
+            self.ptrace.wake_up();
+        }
     }
 
     /// Returns a reference to the profiling clock of the current thread.
@@ -238,8 +253,13 @@ impl PosixThread {
         self.prof_timer_manager.process_expired_timers();
     }
 
+    /// Dequeues a signal that is not blocked by the `mask`.
+    ///
+    /// The thread-directed signals are dequeued before the process-directed ones.
     pub fn dequeue_signal(&self, mask: &SigMask) -> Option<Box<dyn Signal>> {
-        self.sig_queues.dequeue(mask)
+        self.sig_queues
+            .dequeue(mask)
+            .or_else(|| self.process().sig_queues().dequeue(mask))
     }
 
     pub fn register_sigqueue_observer(
@@ -266,6 +286,11 @@ impl PosixThread {
         &self.robust_list
     }
 
+    /// Returns the state of being traced by `ptrace`.
+    pub fn ptrace(&self) -> &PtraceState {
+        &self.ptrace
+    }
+
     fn is_main_thread(&self, tid: Tid) -> bool {
         let process = self.process();
         let pid = process.pid();
@@ -7,6 +7,8 @@ use crate::{
     fs::{file_table::FileTable, fs_resolver::FsResolver, utils::FileCreationMask},
     prelude::*,
     process::{
+        cgroup::Cgroup,
+        namespace::{NsProxy, PidNamespace},
         posix_thread::{create_posix_task_from_executable, PosixThreadBuilder},
         process_vm::ProcessVm,
         rlimit::ResourceLimits,
@@ -34,6 +36,9 @@ pub struct ProcessBuilder<'a> {
     sig_dispositions: Option<Arc<Mutex<SigDispositions>>>,
     credentials: Option<Credentials>,
     nice: Option<Nice>,
+    nsproxy: Option<Arc<NsProxy>>,
+    pid_ns: Option<Arc<PidNamespace>>,
+    cgroup: Option<Arc<Cgroup>>,
 }
 
 impl<'a> ProcessBuilder<'a> {
@@ -53,6 +58,9 @@ impl<'a> ProcessBuilder<'a> {
             sig_dispositions: None,
             credentials: None,
             nice: None,
+            nsproxy: None,
+            pid_ns: None,
+            cgroup: None,
         }
     }
 
@@ -111,6 +119,21 @@ impl<'a> ProcessBuilder<'a> {
         self
     }
 
+    pub fn nsproxy(&mut self, nsproxy: Arc<NsProxy>) -> &mut Self {
+        self.nsproxy = Some(nsproxy);
+        self
+    }
+
+    pub fn pid_ns(&mut self, pid_ns: Arc<PidNamespace>) -> &mut Self {
+        self.pid_ns = Some(pid_ns);
+        self
+    }
+
+    pub fn cgroup(&mut self, cgroup: Arc<Cgroup>) -> &mut Self {
+        self.cgroup = Some(cgroup);
+        self
+    }
+
     fn check_build(&self) -> Result<()> {
         if self.main_thread_builder.is_some() {
             debug_assert!(self.parent.upgrade().is_some());
@@ -146,6 +169,9 @@ impl<'a> ProcessBuilder<'a> {
             sig_dispositions,
             credentials,
             nice,
+            nsproxy,
+            pid_ns,
+            cgroup,
         } = self;
 
         let process_vm = process_vm.or_else(|| Some(ProcessVm::alloc())).unwrap();
@@ -172,6 +198,14 @@ impl<'a> ProcessBuilder<'a> {
 
         let nice = nice.or_else(|| Some(Nice::default())).unwrap();
 
+        let nsproxy = nsproxy.unwrap_or_else(|| NsProxy::init().clone());
+
+        let pid_ns = pid_ns.unwrap_or_else(|| nsproxy.pid_ns_for_children().clone());
+
+        let cgroup = cgroup.unwrap_or_else(|| Cgroup::root().clone());
+        // Charge the cgroup for the main thread
+        cgroup.try_charge_task()?;
+
         let process = {
             let threads = Vec::new();
             Process::new(
@@ -186,6 +220,9 @@ impl<'a> ProcessBuilder<'a> {
                 resource_limits,
                 nice,
                 sig_dispositions,
+                nsproxy,
+                pid_ns,
+                cgroup.clone(),
             )
         };
 
@@ -202,9 +239,12 @@ impl<'a> ProcessBuilder<'a> {
                 Arc::downgrade(&process),
                 argv.unwrap(),
                 envp.unwrap(),
-            )?
+            )
+            .inspect_err(|_| cgroup.uncharge_task())?
         };
 
+        cgroup.add_process(&process);
+
         process.tasks().lock().push(task);
 
         process.set_runnable();
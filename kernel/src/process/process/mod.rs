@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use self::timer_manager::PosixTimerManager;
 use super::{
+    cgroup::Cgroup,
+    namespace::{NsProxy, PidNamespace},
     posix_thread::{allocate_posix_tid, PosixThreadExt},
     process_table,
     process_vm::{Heap, InitStackReader, ProcessVm},
@@ -11,6 +13,7 @@ use super::{
     signal::{
         sig_disposition::SigDispositions,
         sig_num::{AtomicSigNum, SigNum},
+        sig_queues::SigQueues,
         signals::Signal,
     },
     status::ProcessStatus,
@@ -21,7 +24,7 @@ use crate::{
     fs::{file_table::FileTable, fs_resolver::FsResolver, utils::FileCreationMask},
     prelude::*,
     sched::priority::{AtomicNice, Nice},
-    thread::Thread,
+    thread::{Thread, Tid},
     time::clocks::ProfClock,
     vm::vmar::Vmar,
 };
@@ -79,6 +82,8 @@ pub struct Process {
     pub(super) parent: ParentProcess,
     /// Children processes
     children: Mutex<BTreeMap<Pid, Arc<Process>>>,
+    /// Threads traced by the process
+    pub(super) tracees: Mutex<BTreeMap<Tid, Arc<Thread>>>,
     /// Process group
     pub(super) process_group: Mutex<Weak<ProcessGroup>>,
     /// File table
@@ -89,6 +94,16 @@ pub struct Process {
     umask: Arc<RwLock<FileCreationMask>>,
     /// resource limits
     resource_limits: Mutex<ResourceLimits>,
+    /// The namespaces of the process, except for the PID namespace
+    nsproxy: Mutex<Arc<NsProxy>>,
+    /// The PID namespace of the process
+    pid_ns: Arc<PidNamespace>,
+    /// The cgroup that the process belongs to
+    ///
+    /// The lock of `tasks` is held when the cgroup is charged or uncharged for a thread,
+    /// and when the process is moved to another cgroup, so that the charges for the threads
+    /// are always in the cgroup that the process belongs to.
+    cgroup: SpinLock<Arc<Cgroup>>,
     /// Scheduling priority nice value
     /// According to POSIX.1, the nice value is a per-process attribute,
     /// the threads in a process should share a nice value.
@@ -97,12 +112,19 @@ pub struct Process {
     // Signal
     /// Sig dispositions
     sig_dispositions: Arc<Mutex<SigDispositions>>,
+    /// The pending process-directed signals, which can be handled by any thread.
+    sig_queues: SigQueues,
     /// The signal that the process should receive when parent process exits.
     parent_death_signal: AtomicSigNum,
 
     /// The signal that should be sent to the parent when this process exits.
     exit_signal: AtomicSigNum,
 
+    /// Whether the process is dumpable.
+    ///
+    /// A process that is not dumpable cannot be traced by unprivileged users.
+    is_dumpable: AtomicBool,
+
     /// A profiling clock measures the user CPU time and kernel CPU time of the current process.
     prof_clock: Arc<ProfClock>,
 
@@ -198,6 +220,9 @@ impl Process {
         resource_limits: ResourceLimits,
         nice: Nice,
         sig_dispositions: Arc<Mutex<SigDispositions>>,
+        nsproxy: Arc<NsProxy>,
+        pid_ns: Arc<PidNamespace>,
+        cgroup: Arc<Cgroup>,
     ) -> Arc<Self> {
         // SIGCHID does not interrupt pauser. Child process will
         // resume paused parent when doing exit.
@@ -214,15 +239,21 @@ impl Process {
             status: ProcessStatus::new_uninit(),
             parent: ParentProcess::new(parent),
             children: Mutex::new(BTreeMap::new()),
+            tracees: Mutex::new(BTreeMap::new()),
             process_group: Mutex::new(Weak::new()),
             file_table,
             fs,
             umask,
             sig_dispositions,
+            sig_queues: SigQueues::new(),
             parent_death_signal: AtomicSigNum::new_empty(),
             exit_signal: AtomicSigNum::new_empty(),
+            is_dumpable: AtomicBool::new(true),
             resource_limits: Mutex::new(resource_limits),
             nice: AtomicNice::new(nice),
+            nsproxy: Mutex::new(nsproxy),
+            pid_ns,
+            cgroup: SpinLock::new(cgroup),
             timer_manager: PosixTimerManager::new(&prof_clock, process_ref),
             prof_clock,
         })
@@ -298,6 +329,10 @@ impl Process {
 
     // *********** Basic structures ***********
 
+    /// Returns the PID of the process in the initial PID namespace.
+    ///
+    /// The PID that the process sees in its own namespace can be got with
+    /// [`PidNamespace::pid_of`].
     pub fn pid(&self) -> Pid {
         self.pid
     }
@@ -638,6 +673,38 @@ impl Process {
         &self.umask
     }
 
+    // ************** Namespaces **************
+
+    /// Returns the namespaces of the process, except for the PID namespace.
+    pub fn nsproxy(&self) -> Arc<NsProxy> {
+        self.nsproxy.lock().clone()
+    }
+
+    /// Replaces the namespaces of the process.
+    pub fn set_nsproxy(&self, nsproxy: Arc<NsProxy>) {
+        *self.nsproxy.lock() = nsproxy;
+    }
+
+    /// Returns the PID namespace of the process.
+    pub fn pid_ns(&self) -> &Arc<PidNamespace> {
+        &self.pid_ns
+    }
+
+    // ************** Cgroups **************
+
+    /// Returns the cgroup that the process belongs to.
+    pub fn cgroup(&self) -> Arc<Cgroup> {
+        self.cgroup.disable_irq().lock().clone()
+    }
+
+    /// Replaces the cgroup of the process and returns the old one.
+    ///
+    /// The caller should hold the lock of the tasks and update the processes and the charges
+    /// of the cgroups.
+    pub(super) fn replace_cgroup(&self, cgroup: Arc<Cgroup>) -> Arc<Cgroup> {
+        core::mem::replace(&mut *self.cgroup.disable_irq().lock(), cgroup)
+    }
+
     // ****************** Signal ******************
 
     pub fn sig_dispositions(&self) -> &Arc<Mutex<SigDispositions>> {
@@ -659,20 +726,28 @@ impl Process {
 
         // TODO: check that the signal is not user signal
 
-        // Enqueue signal to the first thread that does not block the signal
+        let sig_num = signal.num();
+        self.sig_queues.enqueue(Box::new(signal));
+
+        // Wake up the first thread that does not block the signal
         let threads = self.tasks.lock();
         for thread in threads.iter() {
             let posix_thread = thread.as_posix_thread().unwrap();
-            if !posix_thread.has_signal_blocked(signal.num()) {
-                posix_thread.enqueue_signal(Box::new(signal));
+            if !posix_thread.has_signal_blocked(sig_num) {
+                posix_thread.wake_up_for_signal(sig_num);
                 return;
             }
         }
 
-        // If all threads block the signal, enqueue signal to the first thread
+        // If all threads block the signal, wake up the first thread
         let thread = threads.iter().next().unwrap();
         let posix_thread = thread.as_posix_thread().unwrap();
-        posix_thread.enqueue_signal(Box::new(signal));
+        posix_thread.wake_up_for_signal(sig_num);
+    }
+
+    /// Returns the queues of the pending process-directed signals.
+    pub fn sig_queues(&self) -> &SigQueues {
+        &self.sig_queues
     }
 
     /// Clears the parent death signal.
@@ -693,6 +768,16 @@ impl Process {
         self.parent_death_signal.as_sig_num()
     }
 
+    /// Returns whether the process is dumpable.
+    pub fn is_dumpable(&self) -> bool {
+        self.is_dumpable.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the process is dumpable.
+    pub fn set_dumpable(&self, is_dumpable: bool) {
+        self.is_dumpable.store(is_dumpable, Ordering::Relaxed);
+    }
+
     pub fn set_exit_signal(&self, sig_num: SigNum) {
         self.exit_signal.set(sig_num);
     }
@@ -752,6 +837,9 @@ mod test {
             ResourceLimits::default(),
             Nice::default(),
             Arc::new(Mutex::new(SigDispositions::default())),
+            NsProxy::init().clone(),
+            NsProxy::init().pid_ns_for_children().clone(),
+            Cgroup::root().clone(),
         )
     }
 
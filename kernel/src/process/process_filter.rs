@@ -2,9 +2,14 @@
 
 #![allow(dead_code)]
 
-use super::{Pgid, Pid};
+use super::{namespace::PidNamespace, Pgid, Pid};
 use crate::prelude::*;
 
+/// A filter of the processes to wait for or to send signals to.
+///
+/// The PIDs and the PGIDs in the filter are the global ones, i.e., the ones in the initial
+/// PID namespace. The constructors translate the IDs given by the user from the PID namespace
+/// `pid_ns`, and return `None` if no process or process group has the IDs in the namespace.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProcessFilter {
     Any,
@@ -14,35 +19,39 @@ pub enum ProcessFilter {
 
 impl ProcessFilter {
     // used for waitid
-    pub fn from_which_and_id(which: u64, id: u64) -> Result<Self> {
+    pub fn from_which_and_id(which: u64, id: u64, pid_ns: &PidNamespace) -> Result<Option<Self>> {
         // Does not support PID_FD now(which = 3)
         // https://elixir.bootlin.com/linux/latest/source/include/uapi/linux/wait.h#L20
         match which {
-            0 => Ok(ProcessFilter::Any),
-            1 => Ok(ProcessFilter::WithPid(id as Pid)),
-            2 => Ok(ProcessFilter::WithPgid(id as Pgid)),
+            0 => Ok(Some(ProcessFilter::Any)),
+            1 => Ok(pid_ns.global_pid_of(id as Pid).map(ProcessFilter::WithPid)),
+            2 => Ok(pid_ns
+                .global_pid_of(id as Pgid)
+                .map(ProcessFilter::WithPgid)),
             3 => todo!(),
             _ => return_errno_with_message!(Errno::EINVAL, "invalid which"),
         }
     }
 
     // used for wait4 and kill
-    pub fn from_id(wait_pid: i32) -> Self {
+    pub fn from_id(wait_pid: i32, pid_ns: &PidNamespace) -> Option<Self> {
         // https://man7.org/linux/man-pages/man2/waitpid.2.html
         // https://man7.org/linux/man-pages/man2/kill.2.html
         if wait_pid < -1 {
             // process group ID is equal to the absolute value of pid.
-            ProcessFilter::WithPgid((-wait_pid) as Pgid)
+            let pgid = pid_ns.global_pid_of((-wait_pid) as Pgid)?;
+            Some(ProcessFilter::WithPgid(pgid))
         } else if wait_pid == -1 {
             // wait for any child process
-            ProcessFilter::Any
+            Some(ProcessFilter::Any)
         } else if wait_pid == 0 {
             // wait for any child process with same process group ID
             let pgid = current!().pgid();
-            ProcessFilter::WithPgid(pgid)
+            Some(ProcessFilter::WithPgid(pgid))
         } else {
             // pid > 0. wait for the child whose process ID is equal to the value of pid.
-            ProcessFilter::WithPid(wait_pid as Pid)
+            let pid = pid_ns.global_pid_of(wait_pid as Pid)?;
+            Some(ProcessFilter::WithPid(pid))
         }
     }
 
@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Process tracing.
+//!
+//! A thread (the tracee) can be traced by a process (the tracer), either by calling
+//! `ptrace(PTRACE_TRACEME)` to be traced by its parent, or by being attached by the tracer via
+//! `ptrace(PTRACE_ATTACH)`.
+//!
+//! A tracee stops before a signal is delivered to it and, if it is resumed by
+//! `ptrace(PTRACE_SYSCALL)`, at the entry and the exit of the next syscall. The stops are
+//! reported to the tracer by `wait4` and `waitid`. While the tracee is stopped, the tracer can
+//! inspect and modify its registers and memory, and then resume it.
+
+use ostd::{cpu::UserContext, sync::WaitQueue};
+
+use super::{
+    posix_thread::{PosixThread, PosixThreadExt},
+    process_filter::ProcessFilter,
+    signal::{
+        constants::{SIGCHLD, SIGKILL, SIGSTOP, SIGTRAP},
+        sig_num::SigNum,
+        signals::{kernel::KernelSignal, Signal},
+    },
+    Process,
+};
+use crate::{
+    arch::ptrace::{set_single_step, UserRegs},
+    prelude::*,
+    process::credentials::capabilities::CapSet,
+    thread::{Thread, Tid},
+};
+
+bitflags! {
+    /// The options set by `ptrace(PTRACE_SETOPTIONS)`.
+    pub struct PtraceOptions: u32 {
+        /// Sets bit 7 of the signal number in the wait status of syscall stops.
+        const PTRACE_O_TRACESYSGOOD = 1 << 0;
+    }
+}
+
+/// The way that a tracee is resumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeMode {
+    /// Runs until the next signal-delivery stop.
+    Continue,
+    /// Additionally stops at the entry and the exit of the next syscall.
+    Syscall,
+    /// Additionally stops after executing one instruction.
+    SingleStep,
+}
+
+/// The ptrace state of a POSIX thread.
+pub struct PtraceState {
+    inner: Mutex<PtraceInner>,
+    /// The wait queue where the stopped tracee waits to be resumed
+    wait_queue: WaitQueue,
+}
+
+struct PtraceInner {
+    /// The tracer, which is dangling if the thread is not traced
+    tracer: Weak<Process>,
+    options: PtraceOptions,
+    resume_mode: ResumeMode,
+    /// The number of the syscall that the tracee is executing, if any
+    syscall_num: Option<usize>,
+    /// The current stop, if the tracee is stopped
+    stop: Option<PtraceStop>,
+}
+
+struct PtraceStop {
+    /// The status reported by `wait4` and `waitid`
+    status: u32,
+    /// Whether the stop has been reported to the tracer
+    is_reported: bool,
+    /// The saved user context, which can be inspected and modified by the tracer
+    user_ctx: UserContext,
+    /// The signal to deliver after the tracee is resumed
+    signal: Option<SigNum>,
+    /// Whether the tracee has been resumed
+    is_resumed: bool,
+}
+
+impl PtraceState {
+    pub(super) fn new() -> Self {
+        Self {
+            inner: Mutex::new(PtraceInner {
+                tracer: Weak::new(),
+                options: PtraceOptions::empty(),
+                resume_mode: ResumeMode::Continue,
+                syscall_num: None,
+                stop: None,
+            }),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    /// Returns the tracer, if the thread is traced.
+    pub fn tracer(&self) -> Option<Arc<Process>> {
+        self.inner.lock().tracer.upgrade()
+    }
+
+    /// Returns whether the thread is traced.
+    pub fn is_traced(&self) -> bool {
+        self.inner.lock().tracer.strong_count() > 0
+    }
+
+    /// Returns whether the thread should stop at the entry and the exit of syscalls.
+    pub fn is_syscall_traced(&self) -> bool {
+        let inner = self.inner.lock();
+        inner.tracer.strong_count() > 0 && inner.resume_mode == ResumeMode::Syscall
+    }
+
+    /// Stops the current thread and waits for the tracer to resume it.
+    ///
+    /// Returns the signal that should be delivered after being resumed. If the thread is
+    /// killed by `SIGKILL` or its process exits while being stopped, the thread is woken up
+    /// without being resumed and no signal is returned.
+    fn stop(
+        &self,
+        status: u32,
+        signal: Option<SigNum>,
+        user_ctx: &mut UserContext,
+        ctx: &Context,
+    ) -> Option<SigNum> {
+        let tracer = {
+            let mut inner = self.inner.lock();
+            let Some(tracer) = inner.tracer.upgrade() else {
+                return signal;
+            };
+            inner.stop = Some(PtraceStop {
+                status,
+                is_reported: false,
+                user_ctx: user_ctx.clone(),
+                signal,
+                is_resumed: false,
+            });
+            tracer
+        };
+
+        tracer.enqueue_signal(KernelSignal::new(SIGCHLD));
+        tracer.children_wait_queue().wake_all();
+
+        self.wait_queue.wait_until(|| {
+            if ctx.posix_thread.sig_pending().contains(SIGKILL) || ctx.process.is_zombie() {
+                return Some(());
+            }
+
+            let inner = self.inner.lock();
+            match &inner.stop {
+                Some(stop) if !stop.is_resumed => None,
+                _ => Some(()),
+            }
+        });
+
+        let stop = self.inner.lock().stop.take()?;
+        if !stop.is_resumed {
+            return None;
+        }
+        *user_ctx = stop.user_ctx;
+        stop.signal
+    }
+
+    /// Wakes up the thread if it is stopped, so that it can check whether it is killed.
+    pub(super) fn wake_up(&self) {
+        self.wait_queue.wake_all();
+    }
+
+    fn stop_at_syscall(&self, user_ctx: &mut UserContext, ctx: &Context) -> Option<SigNum> {
+        let trap_num = {
+            let inner = self.inner.lock();
+            let sysgood_bit = if inner.options.contains(PtraceOptions::PTRACE_O_TRACESYSGOOD) {
+                0x80
+            } else {
+                0
+            };
+            SIGTRAP.as_u8() | sysgood_bit
+        };
+        self.stop(stopped_status(trap_num), None, user_ctx, ctx)
+    }
+
+    /// Makes the `tracer` trace the thread.
+    fn attach(&self, tracer: &Arc<Process>) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if inner.tracer.strong_count() > 0 {
+            return_errno_with_message!(Errno::EPERM, "the thread is already traced");
+        }
+
+        inner.tracer = Arc::downgrade(tracer);
+        inner.options = PtraceOptions::empty();
+        inner.resume_mode = ResumeMode::Continue;
+        Ok(())
+    }
+
+    /// Stops tracing the thread and resumes it if it is stopped.
+    fn detach(&self, signal: Option<SigNum>) {
+        let mut inner = self.inner.lock();
+        inner.tracer = Weak::new();
+        inner.resume_mode = ResumeMode::Continue;
+        if let Some(stop) = inner.stop.as_mut() {
+            let _ = set_single_step(&mut stop.user_ctx, false);
+            stop.signal = signal;
+            stop.is_resumed = true;
+        }
+        drop(inner);
+
+        self.wait_queue.wake_all();
+    }
+
+    /// Locks the state and checks that the thread is traced by the `tracer` and stopped.
+    fn lock_stopped(&self, tracer: &Process) -> Result<MutexGuard<'_, PtraceInner>> {
+        let inner = self.inner.lock();
+        if !core::ptr::eq(inner.tracer.as_ptr(), tracer) {
+            return_errno_with_message!(Errno::ESRCH, "the thread is not traced by the process");
+        }
+        if !inner.stop.as_ref().is_some_and(|stop| !stop.is_resumed) {
+            return_errno_with_message!(Errno::ESRCH, "the thread is not stopped");
+        }
+
+        Ok(inner)
+    }
+
+    /// Resumes the stopped thread.
+    pub fn resume(&self, tracer: &Process, mode: ResumeMode, signal: Option<SigNum>) -> Result<()> {
+        let mut inner = self.lock_stopped(tracer)?;
+        let stop = inner.stop.as_mut().unwrap();
+        set_single_step(&mut stop.user_ctx, mode == ResumeMode::SingleStep)?;
+        stop.signal = signal;
+        stop.is_resumed = true;
+        inner.resume_mode = mode;
+        drop(inner);
+
+        self.wait_queue.wake_all();
+        Ok(())
+    }
+
+    /// Checks that the thread is traced by the `tracer` and stopped.
+    pub fn check_stopped(&self, tracer: &Process) -> Result<()> {
+        self.lock_stopped(tracer)?;
+        Ok(())
+    }
+
+    /// Gets the registers of the stopped thread.
+    pub fn regs(&self, tracer: &Process) -> Result<UserRegs> {
+        let inner = self.lock_stopped(tracer)?;
+        let stop = inner.stop.as_ref().unwrap();
+        Ok(UserRegs::from_context(&stop.user_ctx, inner.syscall_num))
+    }
+
+    /// Sets the registers of the stopped thread.
+    pub fn set_regs(&self, tracer: &Process, regs: &UserRegs) -> Result<()> {
+        let mut inner = self.lock_stopped(tracer)?;
+        let syscall_num = regs.copy_to_context(&mut inner.stop.as_mut().unwrap().user_ctx)?;
+        if inner.syscall_num.is_some() {
+            inner.syscall_num = Some(syscall_num);
+        }
+        Ok(())
+    }
+
+    /// Sets the options of the stopped thread.
+    pub fn set_options(&self, tracer: &Process, options: PtraceOptions) -> Result<()> {
+        let mut inner = self.lock_stopped(tracer)?;
+        inner.options = options;
+        Ok(())
+    }
+
+    /// Returns the status of the stop that has not been reported to the `tracer`.
+    ///
+    /// If `should_consume` is true, the stop is marked as reported.
+    fn unreported_stop_status(&self, tracer: &Process, should_consume: bool) -> Option<u32> {
+        let mut inner = self.inner.lock();
+        if !core::ptr::eq(inner.tracer.as_ptr(), tracer) {
+            return None;
+        }
+
+        let stop = inner.stop.as_mut()?;
+        if stop.is_reported || stop.is_resumed {
+            return None;
+        }
+        if should_consume {
+            stop.is_reported = true;
+        }
+        Some(stop.status)
+    }
+}
+
+/// Encodes the wait status of a thread that is stopped by the signal.
+fn stopped_status(sig_num: u8) -> u32 {
+    ((sig_num as u32) << 8) | 0x7f
+}
+
+/// Stops the current thread before the signal is delivered, if the thread is traced.
+///
+/// Returns the signal to deliver, which may be replaced or discarded by the tracer.
+pub fn stop_for_signal(
+    signal: Box<dyn Signal>,
+    user_ctx: &mut UserContext,
+    ctx: &Context,
+) -> Option<Box<dyn Signal>> {
+    let sig_num = signal.num();
+    let ptrace = ctx.posix_thread.ptrace();
+    if sig_num == SIGKILL || !ptrace.is_traced() {
+        return Some(signal);
+    }
+
+    let new_sig_num = ptrace.stop(
+        stopped_status(sig_num.as_u8()),
+        Some(sig_num),
+        user_ctx,
+        ctx,
+    )?;
+    if new_sig_num == sig_num {
+        Some(signal)
+    } else {
+        Some(Box::new(KernelSignal::new(new_sig_num)))
+    }
+}
+
+/// Stops the current thread at the entry of a syscall.
+///
+/// Returns the number of the syscall to execute, which may be changed by the tracer.
+pub fn stop_at_syscall_entry(
+    syscall_num: usize,
+    user_ctx: &mut UserContext,
+    ctx: &Context,
+) -> usize {
+    let ptrace = ctx.posix_thread.ptrace();
+    ptrace.inner.lock().syscall_num = Some(syscall_num);
+
+    if let Some(sig_num) = ptrace.stop_at_syscall(user_ctx, ctx) {
+        ctx.posix_thread
+            .enqueue_signal(Box::new(KernelSignal::new(sig_num)));
+    }
+
+    ptrace.inner.lock().syscall_num.unwrap_or(syscall_num)
+}
+
+/// Stops the current thread at the exit of a syscall, if the thread is still resumed by
+/// `ptrace(PTRACE_SYSCALL)`.
+///
+/// This should be called after [`stop_at_syscall_entry`] to finish the traced syscall.
+pub fn stop_at_syscall_exit(user_ctx: &mut UserContext, ctx: &Context) {
+    let ptrace = ctx.posix_thread.ptrace();
+    if ptrace.is_syscall_traced()
+        && let Some(sig_num) = ptrace.stop_at_syscall(user_ctx, ctx)
+    {
+        ctx.posix_thread
+            .enqueue_signal(Box::new(KernelSignal::new(sig_num)));
+    }
+
+    ptrace.inner.lock().syscall_num = None;
+}
+
+/// Makes the current thread traced by its parent.
+pub fn ptrace_traceme(ctx: &Context) -> Result<()> {
+    let Some(parent) = ctx.process.parent().lock().process().upgrade() else {
+        return_errno_with_message!(Errno::EPERM, "the process has no parent");
+    };
+
+    ctx.posix_thread.ptrace().attach(&parent)?;
+    parent
+        .tracees
+        .lock()
+        .insert(ctx.posix_thread.tid(), current_thread!());
+    Ok(())
+}
+
+/// Attaches the current process to the `tracee` as its tracer.
+///
+/// The tracee will be sent a `SIGSTOP`.
+pub fn ptrace_attach(tracee: Arc<Thread>, ctx: &Context) -> Result<()> {
+    let current = current!();
+    let tracee_thread = tracee.as_posix_thread().unwrap();
+    if Arc::ptr_eq(&tracee_thread.process(), &current) {
+        return_errno_with_message!(Errno::EPERM, "a process cannot trace itself");
+    }
+
+    let credentials = ctx.posix_thread.credentials();
+    if !credentials.effective_capset().contains(CapSet::SYS_PTRACE) {
+        let tracee_credentials = tracee_thread.credentials();
+        let uid = credentials.ruid();
+        if uid != tracee_credentials.ruid()
+            || uid != tracee_credentials.euid()
+            || uid != tracee_credentials.suid()
+        {
+            return_errno_with_message!(Errno::EPERM, "the thread cannot be traced");
+        }
+        if !tracee_thread.process().is_dumpable() {
+            return_errno_with_message!(Errno::EPERM, "the process is not dumpable");
+        }
+    }
+
+    tracee_thread.ptrace().attach(&current)?;
+    current.tracees.lock().insert(tracee.tid(), tracee.clone());
+    tracee_thread.enqueue_signal(Box::new(KernelSignal::new(SIGSTOP)));
+    Ok(())
+}
+
+/// Detaches the current process from the `tracee`, which is resumed with the `signal`.
+pub fn ptrace_detach(tracee: &Arc<Thread>, signal: Option<SigNum>, ctx: &Context) -> Result<()> {
+    let ptrace = tracee.as_posix_thread().unwrap().ptrace();
+    ptrace.check_stopped(ctx.process)?;
+
+    ctx.process.tracees.lock().remove(&tracee.tid());
+    ptrace.detach(signal);
+    Ok(())
+}
+
+/// Gets the thread with `tid` that is traced by the current process.
+pub fn get_tracee(tid: Tid, ctx: &Context) -> Result<Arc<Thread>> {
+    let tid = ctx
+        .process
+        .pid_ns()
+        .global_pid_of(tid)
+        .ok_or_else(|| Error::with_message(Errno::ESRCH, "the thread does not exist"))?;
+    ctx.process
+        .tracees
+        .lock()
+        .get(&tid)
+        .cloned()
+        .ok_or_else(|| Error::with_message(Errno::ESRCH, "the thread is not traced"))
+}
+
+/// Stops tracing the exiting thread.
+pub(super) fn untrace_exiting_thread(posix_thread: &PosixThread) {
+    let ptrace = posix_thread.ptrace();
+    let Some(tracer) = ptrace.tracer() else {
+        return;
+    };
+
+    tracer.tracees.lock().remove(&posix_thread.tid());
+    ptrace.detach(None);
+}
+
+/// Detaches the exiting `tracer` from all its tracees.
+pub(super) fn detach_all_tracees(tracer: &Process) {
+    let tracees = core::mem::take(&mut *tracer.tracees.lock());
+    for tracee in tracees.values() {
+        tracee.as_posix_thread().unwrap().ptrace().detach(None);
+    }
+}
+
+/// Finds a tracee of the `tracer` that matches the `filter` and has a stop that has not been
+/// reported.
+///
+/// Returns the tracee and its wait status.
+pub(super) fn find_unreported_stop(
+    tracer: &Process,
+    filter: &ProcessFilter,
+    should_consume: bool,
+) -> Option<(Arc<Thread>, u32)> {
+    let tracees = tracer.tracees.lock();
+    tracees
+        .iter()
+        .filter(|(tid, tracee)| match filter {
+            ProcessFilter::Any => true,
+            ProcessFilter::WithPid(pid) => **tid == *pid,
+            ProcessFilter::WithPgid(pgid) => {
+                tracee.as_posix_thread().unwrap().process().pgid() == *pgid
+            }
+        })
+        .find_map(|(tid, tracee)| {
+            let status = tracee
+                .as_posix_thread()
+                .unwrap()
+                .ptrace()
+                .unreported_stop_status(tracer, should_consume)?;
+            Some((tracee.clone(), status))
+        })
+}
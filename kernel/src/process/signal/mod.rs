@@ -30,7 +30,7 @@ use super::posix_thread::PosixThread;
 use crate::{
     get_current_userspace,
     prelude::*,
-    process::{do_exit_group, TermStatus},
+    process::{do_exit_group, ptrace::stop_for_signal, TermStatus},
     thread::status::ThreadStatus,
 };
 
@@ -54,6 +54,12 @@ pub fn handle_pending_signal(user_ctx: &mut UserContext, ctx: &Context) -> Resul
         }
     };
 
+    // A traced thread stops before the signal is delivered,
+    // after which the tracer may replace or discard the signal.
+    let Some(signal) = stop_for_signal(signal, user_ctx, ctx) else {
+        return Ok(());
+    };
+
     let sig_num = signal.num();
     trace!("sig_num = {:?}, sig_name = {}", sig_num, sig_num.sig_name());
     let current = posix_thread.process();
@@ -5,7 +5,10 @@ use core::{
     time::Duration,
 };
 
-use ostd::sync::{Waiter, Waker};
+use ostd::{
+    sync::{Waiter, Waker},
+    timer::Jiffies,
+};
 
 use crate::{
     events::{IoEvents, Observer, Subject},
@@ -259,11 +262,29 @@ pub trait Pollable {
     /// The user must ensure that a call to `cond()` does not fail with `EAGAIN` when the
     /// interesting events occur. However, it is allowed to have spurious `EAGAIN` failures due to
     /// race conditions where the events are consumed by another thread.
-    fn wait_events<F, R>(&self, mask: IoEvents, mut cond: F) -> Result<R>
+    fn wait_events<F, R>(&self, mask: IoEvents, cond: F) -> Result<R>
     where
         Self: Sized,
         F: FnMut() -> Result<R>,
     {
+        self.wait_events_timeout(mask, None, cond)
+    }
+
+    /// Waits for events and performs event-based operations, with a time limit.
+    ///
+    /// This method behaves like [`Self::wait_events`], except that if `timeout` is not `None` and
+    /// it expires before `cond()` succeeds, the method will fail with `EAGAIN`.
+    fn wait_events_timeout<F, R>(
+        &self,
+        mask: IoEvents,
+        timeout: Option<&Duration>,
+        mut cond: F,
+    ) -> Result<R>
+    where
+        Self: Sized,
+        F: FnMut() -> Result<R>,
+    {
+        let deadline = timeout.map(|timeout| Jiffies::elapsed().as_duration() + *timeout);
         let mut poller = Poller::new();
 
         loop {
@@ -277,8 +298,18 @@ pub trait Pollable {
                 continue;
             }
 
-            // TODO: Support timeout
-            poller.wait()?;
+            let Some(deadline) = deadline else {
+                poller.wait()?;
+                continue;
+            };
+
+            let remaining = deadline.saturating_sub(Jiffies::elapsed().as_duration());
+            match poller.wait_timeout(&remaining) {
+                Err(err) if err.error() == Errno::ETIME => {
+                    return_errno_with_message!(Errno::EAGAIN, "the time limit is reached")
+                }
+                result => result?,
+            }
         }
     }
 }
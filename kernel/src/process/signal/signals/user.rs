@@ -58,11 +58,14 @@ impl Signal for UserSignal {
             UserSignalKind::Sigqueue => SI_QUEUE,
         };
 
-        siginfo_t::new(self.num, code)
-        // info.set_si_pid(self.pid);
-        // info.set_si_uid(self.uid);
+        let mut info = siginfo_t::new(self.num, code);
+        // The PID of the sender is reported in the PID namespace of the receiver,
+        // which is the current process when the signal is delivered.
+        let pid = crate::current!().pid_ns().pid_or_zero(self.pid);
+        info.set_si_pid_uid(pid, self.uid);
         // if let UserSignalKind::Sigqueue(val) = self.kind {
         //     info.set_si_value(val);
         // }
+        info
     }
 }
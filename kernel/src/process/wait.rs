@@ -2,7 +2,9 @@
 
 #![allow(dead_code)]
 
-use super::{process_filter::ProcessFilter, signal::constants::SIGCHLD, ExitCode, Pid, Process};
+use super::{
+    process_filter::ProcessFilter, ptrace, signal::constants::SIGCHLD, ExitCode, Pid, Process,
+};
 use crate::{
     prelude::*,
     process::{
@@ -31,13 +33,37 @@ impl WaitOptions {
     }
 }
 
+/// A child whose status has changed, which is returned by [`wait_child_exit`].
+pub struct WaitedChild {
+    pid: Pid,
+    status: u32,
+    process: Arc<Process>,
+}
+
+impl WaitedChild {
+    /// Returns the PID of the exited child, or the TID of the stopped tracee.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// Returns the wait status, which is encoded in the same way as Linux.
+    pub fn status(&self) -> u32 {
+        self.status
+    }
+
+    /// Returns the process of the child.
+    pub fn process(&self) -> &Arc<Process> {
+        &self.process
+    }
+}
+
 pub fn wait_child_exit(
     child_filter: ProcessFilter,
     wait_options: WaitOptions,
     ctx: &Context,
-) -> Result<Option<Arc<Process>>> {
+) -> Result<Option<WaitedChild>> {
     let current = ctx.process;
-    let zombie_child = with_signal_blocked(ctx, SIGCHLD.into(), || {
+    let waited_child = with_signal_blocked(ctx, SIGCHLD.into(), || {
         current.children_wait_queue().pause_until(|| {
             let unwaited_children = current
                 .children()
@@ -51,7 +77,19 @@ pub fn wait_child_exit(
                 .cloned()
                 .collect::<Vec<_>>();
 
-            if unwaited_children.is_empty() {
+            // Return immediately if we find a stopped tracee
+            let should_consume = !wait_options.contains(WaitOptions::WNOWAIT);
+            if let Some((tracee, status)) =
+                ptrace::find_unreported_stop(current, &child_filter, should_consume)
+            {
+                return Some(Ok(Some(WaitedChild {
+                    pid: tracee.tid(),
+                    status,
+                    process: tracee.as_posix_thread().unwrap().process(),
+                })));
+            }
+
+            if unwaited_children.is_empty() && current.tracees.lock().is_empty() {
                 return Some(Err(Error::with_message(
                     Errno::ECHILD,
                     "the process has no child to wait",
@@ -63,13 +101,16 @@ pub fn wait_child_exit(
 
             if let Some(zombie_child) = zombie_child {
                 let zombie_pid = zombie_child.pid();
-                if wait_options.contains(WaitOptions::WNOWAIT) {
-                    // does not reap child, directly return
-                    return Some(Ok(Some(zombie_child.clone())));
-                } else {
+                let waited_child = WaitedChild {
+                    pid: zombie_pid,
+                    status: zombie_child.exit_code(),
+                    process: zombie_child.clone(),
+                };
+                if !wait_options.contains(WaitOptions::WNOWAIT) {
                     reap_zombie_child(current, zombie_pid);
-                    return Some(Ok(Some(zombie_child.clone())));
                 }
+                // does not reap child if `WNOWAIT` is specified
+                return Some(Ok(Some(waited_child)));
             }
 
             if wait_options.contains(WaitOptions::WNOHANG) {
@@ -81,7 +122,7 @@ pub fn wait_child_exit(
         })
     })??;
 
-    Ok(zombie_child)
+    Ok(waited_child)
 }
 
 /// Free zombie child with pid, returns the exit code of child process.
@@ -90,6 +131,7 @@ fn reap_zombie_child(process: &Process, pid: Pid) -> ExitCode {
     assert!(child_process.is_zombie());
     for task in &*child_process.tasks().lock() {
         thread_table::remove_thread(task.tid());
+        child_process.pid_ns().free_pids(task.tid());
     }
 
     // Lock order: session table -> group table -> process table -> group of process
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::AtomicU8;
+
+use atomic_integer_wrapper::define_atomic_version_of_integer_like_type;
+use int_to_c_enum::TryFromInt;
+
+use super::priority::{Priority, PriorityRange, RangedU8};
+
+/// The scheduling policy of a thread.
+///
+/// The values are the same as the `SCHED_*` constants of Linux.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, TryFromInt)]
+#[repr(u8)]
+pub enum SchedPolicy {
+    /// The default time-sharing policy (`SCHED_OTHER`).
+    Normal = 0,
+    /// The first-in, first-out real-time policy.
+    Fifo = 1,
+    /// The round-robin real-time policy.
+    RoundRobin = 2,
+    /// The time-sharing policy for CPU-intensive tasks that should not preempt others on wakeup.
+    Batch = 3,
+    /// The time-sharing policy for tasks with extremely low priority.
+    Idle = 5,
+}
+
+define_atomic_version_of_integer_like_type!(SchedPolicy, try_from = true, {
+    #[derive(Debug)]
+    pub struct AtomicSchedPolicy(AtomicU8);
+});
+
+impl SchedPolicy {
+    pub fn is_real_time(&self) -> bool {
+        matches!(self, Self::Fifo | Self::RoundRobin)
+    }
+
+    /// Returns the range of the real-time priority for the policy.
+    ///
+    /// The real-time priority is always zero for the non-real-time policies.
+    pub fn real_time_priority_range(&self) -> (u8, u8) {
+        if self.is_real_time() {
+            (RealTimePriorityRange::MIN, RealTimePriorityRange::MAX)
+        } else {
+            (0, 0)
+        }
+    }
+}
+
+impl Default for SchedPolicy {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl From<SchedPolicy> for u8 {
+    fn from(value: SchedPolicy) -> Self {
+        value as u8
+    }
+}
+
+/// The real-time priority of a thread, which is an integer in the range of [1, 99].
+///
+/// Unlike [`Priority`], a larger real-time priority is more favorable in scheduling.
+pub type RealTimePriorityRange = RangedU8<1, 99>;
+
+impl From<RealTimePriorityRange> for Priority {
+    fn from(value: RealTimePriorityRange) -> Self {
+        // Follow the Linux mapping: prio = 99 - rt_priority.
+        Self::new(PriorityRange::new(RealTimePriorityRange::MAX - value.get()))
+    }
+}
+
+impl Priority {
+    /// Returns the real-time priority if the priority is a real-time one.
+    pub fn real_time_priority(&self) -> Option<RealTimePriorityRange> {
+        RealTimePriorityRange::MAX
+            .checked_sub(self.range().get())
+            .and_then(|rt_priority| RealTimePriorityRange::try_from(rt_priority).ok())
+    }
+}
@@ -85,7 +85,7 @@ impl Priority {
 
 impl From<Nice> for Priority {
     fn from(value: Nice) -> Self {
-        Self::new(PriorityRange::new(value.range().get() as u8 + 120))
+        Self::new(PriorityRange::new((value.range().get() as i16 + 120) as u8))
     }
 }
 
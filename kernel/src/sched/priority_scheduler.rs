@@ -1,6 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::Duration,
+};
+
 use ostd::{
+    arch::timer::TIMER_FREQ,
     cpu::{num_cpus, CpuSet, PinCurrentCpu},
     sync::PreemptDisabled,
     task::{
@@ -10,29 +16,69 @@ use ostd::{
         },
         Task,
     },
+    timer::Jiffies,
     trap::disable_local,
 };
+use spin::Once;
+
+use super::{
+    policy::SchedPolicy,
+    priority::{Priority, PriorityRange},
+};
+use crate::{
+    prelude::*,
+    process::{
+        cgroup::{Cgroup, DEFAULT_CPU_WEIGHT},
+        posix_thread::PosixThreadExt,
+    },
+    thread::Thread,
+};
 
-use super::priority::{Priority, PriorityRange};
-use crate::{prelude::*, thread::Thread};
+static SCHEDULER: Once<&'static PreemptScheduler<Thread, Task>> = Once::new();
 
 pub fn init() {
     let preempt_scheduler = Box::new(PreemptScheduler::default());
-    let scheduler = Box::<PreemptScheduler<Thread, Task>>::leak(preempt_scheduler);
+    let scheduler: &'static PreemptScheduler<Thread, Task> = Box::leak(preempt_scheduler);
+    SCHEDULER.call_once(|| scheduler);
     inject_scheduler(scheduler);
 }
 
+/// Moves the task to a CPU in its affinity if the CPU that it is on is no longer allowed.
+///
+/// This should be called after the CPU affinity of the task is changed. A queued task is moved
+/// at once. A running task is preempted at the next tick of its CPU and then moved by that CPU.
+/// If the task is the current one, the caller can yield to move it immediately.
+pub fn migrate_to_allowed_cpu(task: &Arc<Task>) {
+    SCHEDULER.get().unwrap().migrate_to_allowed_cpu(task);
+}
+
 /// The preempt scheduler.
 ///
-/// Real-time tasks are placed in the `real_time_entities` queue and
-/// are always prioritized during scheduling.
-/// Normal tasks are placed in the `normal_entities` queue and are only
-/// scheduled for execution when there are no real-time tasks.
+/// Real-time tasks are placed in the `real_time_entities` queue in the order
+/// of their priorities and are always prioritized during scheduling.
+/// The ones with the `SCHED_FIFO` policy run until they block or yield,
+/// while the others are rotated when their time slices run out.
+/// Normal tasks are placed in the `fair_entities` queue (see [`FairRunQueue`]) and
+/// are only scheduled for execution when there are no real-time tasks.
+///
+/// The tasks that are not real-time are also subject to the CPU controller
+/// of their cgroups: their weights in the fair queue are scaled by the CPU weights
+/// of the cgroups, and they are not picked while the cgroups are throttled
+/// due to running out of the CPU bandwidth.
+///
+/// Each CPU has its own runqueue. The load among the runqueues is balanced by
+/// pulling tasks from the busiest runqueue, both when a CPU becomes idle and
+/// periodically (see [`PreemptScheduler::balance`]).
 struct PreemptScheduler<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> {
     rq: Vec<SpinLock<PreemptRunQueue<T, U>>>,
 }
 
 impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> PreemptScheduler<T, U> {
+    /// The interval of the periodic load balancing in ticks.
+    const BALANCE_INTERVAL: u64 = 10;
+    /// The maximum number of tasks that can be migrated in one load balancing.
+    const MAX_MIGRATIONS_PER_BALANCE: usize = 4;
+
     fn new(nr_cpus: u32) -> Self {
         let mut rq = Vec::with_capacity(nr_cpus as usize);
         for _ in 0..nr_cpus {
@@ -41,33 +87,198 @@ impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> PreemptScheduler<T,
         Self { rq }
     }
 
-    /// Selects a CPU for task to run on for the first time.
+    /// Selects a CPU for task to run on when it becomes runnable.
     fn select_cpu(&self, entity: &PreemptSchedEntity<T, U>) -> u32 {
         // If the CPU of a runnable task has been set before, keep scheduling
-        // the task to that one.
-        // TODO: Consider migrating tasks between CPUs for load balancing.
+        // the task to that one. The task may be migrated later by load balancing.
         if let Some(cpu_id) = entity.task.cpu().get() {
             return cpu_id;
         }
+        // If the task is still being switched out, it cannot run on other CPUs until
+        // its context is saved, so enqueue it there instead of waiting for that.
+        if let Some(cpu_id) = entity.task.on_cpu() {
+            return cpu_id;
+        }
 
         let irq_guard = disable_local();
-        let mut selected = irq_guard.current_cpu();
-        let mut minimum_load = usize::MAX;
-
-        for candidate in entity.thread.cpu_affinity().iter() {
-            let rq = self.rq[candidate as usize].lock();
-            // A wild guess measuring the load of a runqueue. We assume that
-            // real-time tasks are 4-times as important as normal tasks.
-            let load = rq.real_time_entities.len() * 8
-                + rq.normal_entities.len() * 2
-                + rq.lowest_entities.len();
-            if load < minimum_load {
-                selected = candidate;
-                minimum_load = load;
+        self.least_loaded_cpu(&entity.thread.cpu_affinity())
+            .unwrap_or_else(|| irq_guard.current_cpu())
+    }
+
+    /// Returns the CPU in `cpu_set` whose runqueue has the least load.
+    ///
+    /// The caller must disable the local IRQs.
+    fn least_loaded_cpu(&self, cpu_set: &CpuSet) -> Option<u32> {
+        cpu_set
+            .iter()
+            .min_by_key(|cpu_id| self.rq[*cpu_id as usize].lock().queued_load())
+    }
+
+    /// Locks the runqueues of two different CPUs in the order of CPU IDs to avoid deadlocks.
+    fn lock_two_rqs(
+        &self,
+        first_cpu: u32,
+        second_cpu: u32,
+    ) -> (
+        SpinLockGuard<PreemptRunQueue<T, U>, PreemptDisabled>,
+        SpinLockGuard<PreemptRunQueue<T, U>, PreemptDisabled>,
+    ) {
+        if first_cpu < second_cpu {
+            let first_rq = self.rq[first_cpu as usize].lock();
+            (first_rq, self.rq[second_cpu as usize].lock())
+        } else {
+            let second_rq = self.rq[second_cpu as usize].lock();
+            (self.rq[first_cpu as usize].lock(), second_rq)
+        }
+    }
+
+    /// Balances the load by pulling tasks from the busiest runqueue to the runqueue of `this_cpu`.
+    ///
+    /// The balancing is done at most once per tick if the CPU is idle, i.e., it has nothing
+    /// to run other than the lowest-priority tasks. Otherwise, it is done every
+    /// [`Self::BALANCE_INTERVAL`] ticks.
+    ///
+    /// The caller must disable the local IRQs.
+    fn balance(&self, this_cpu: u32) {
+        let now = Jiffies::elapsed().as_u64();
+        {
+            let mut this_rq = self.rq[this_cpu as usize].lock();
+            let interval = if this_rq.is_idle() {
+                1
+            } else {
+                Self::BALANCE_INTERVAL
+            };
+            if now < this_rq.last_balance + interval {
+                return;
+            }
+            this_rq.last_balance = now;
+        }
+
+        let Some(busiest_cpu) = (0..self.rq.len() as u32)
+            .filter(|cpu_id| *cpu_id != this_cpu)
+            .max_by_key(|cpu_id| self.rq[*cpu_id as usize].lock().load())
+        else {
+            return;
+        };
+
+        let (mut this_rq, mut busiest_rq) = self.lock_two_rqs(this_cpu, busiest_cpu);
+
+        for _ in 0..Self::MAX_MIGRATIONS_PER_BALANCE {
+            let imbalance = busiest_rq.load().saturating_sub(this_rq.load());
+            // Migrating a task only makes sense if it reduces the imbalance.
+            let Some(mut entity) = busiest_rq.pop_migratable(this_cpu, |load| load < imbalance)
+            else {
+                break;
+            };
+            // A queued task always has the CPU of its runqueue set.
+            let result = entity.task.cpu().set_if_is(busiest_cpu, this_cpu);
+            debug_assert!(result.is_ok());
+            // Keep the virtual runtime relative to the fair queues.
+            let lag = busiest_rq.fair_entities.lag_of(&entity);
+            this_rq.fair_entities.place(&mut entity, lag);
+            this_rq.push(entity);
+        }
+    }
+
+    /// Moves the task to a CPU in its affinity if the CPU that it is on is no longer allowed.
+    fn migrate_to_allowed_cpu(&self, task: &Arc<U>) {
+        let _irq_guard = disable_local();
+        let allowed_cpus = T::from_task(task).cpu_affinity().clone();
+
+        loop {
+            // If the task is not runnable, the new affinity takes effect when it is woken up.
+            let Some(cpu_id) = task.cpu().get() else {
+                return;
+            };
+            if allowed_cpus.contains(cpu_id) {
+                return;
+            }
+
+            {
+                let mut rq = self.rq[cpu_id as usize].lock();
+                if task.cpu().get() != Some(cpu_id) {
+                    continue;
+                }
+                if rq
+                    .current
+                    .as_ref()
+                    .is_some_and(|current| Arc::ptr_eq(&current.task, task))
+                {
+                    rq.should_migrate_current = true;
+                    return;
+                }
+                if task.on_cpu().is_some() {
+                    // The task is still being switched out, so it is left to that CPU.
+                    rq.has_disallowed = true;
+                    return;
+                }
+            }
+
+            if self.move_queued(task, cpu_id, &allowed_cpus) {
+                return;
             }
         }
+    }
+
+    /// Pushes the queued tasks that are not allowed to run on `this_cpu` to the CPUs in their
+    /// affinities.
+    ///
+    /// The caller must disable the local IRQs.
+    fn push_disallowed(&self, this_cpu: u32) {
+        let is_disallowed =
+            |entity: &PreemptSchedEntity<T, U>| !entity.thread.cpu_affinity().contains(this_cpu);
+
+        loop {
+            let (task, allowed_cpus) = {
+                let mut this_rq = self.rq[this_cpu as usize].lock();
+                if !this_rq.has_disallowed {
+                    return;
+                }
+
+                let candidate = this_rq
+                    .queued()
+                    .find(|entity| entity.task.on_cpu().is_none() && is_disallowed(*entity))
+                    .map(|entity| (entity.task.clone(), entity.thread.cpu_affinity().clone()));
+                let Some(candidate) = candidate else {
+                    // The tasks that are still being switched out are moved next time.
+                    this_rq.has_disallowed = this_rq.queued().any(is_disallowed);
+                    if this_rq.current.as_ref().is_some_and(is_disallowed) {
+                        this_rq.should_migrate_current = true;
+                    }
+                    return;
+                };
+                candidate
+            };
 
-        selected
+            self.move_queued(&task, this_cpu, &allowed_cpus);
+        }
+    }
+
+    /// Moves the queued `task` from the runqueue of `from_cpu` to the least loaded CPU in
+    /// `allowed_cpus`, which must not contain `from_cpu`.
+    ///
+    /// This method returns `false` if the task is no longer queued there.
+    ///
+    /// The caller must disable the local IRQs.
+    fn move_queued(&self, task: &Arc<U>, from_cpu: u32, allowed_cpus: &CpuSet) -> bool {
+        let Some(to_cpu) = self.least_loaded_cpu(allowed_cpus) else {
+            return true;
+        };
+
+        let (mut from_rq, mut to_rq) = self.lock_two_rqs(from_cpu, to_cpu);
+        let Some(mut entity) = from_rq.remove_queued(|entity| {
+            Arc::ptr_eq(&entity.task, task) && entity.task.on_cpu().is_none()
+        }) else {
+            return false;
+        };
+        // A queued task always has the CPU of its runqueue set.
+        let result = entity.task.cpu().set_if_is(from_cpu, to_cpu);
+        debug_assert!(result.is_ok());
+        // Keep the virtual runtime relative to the fair queues.
+        let lag = from_rq.fair_entities.lag_of(&entity);
+        to_rq.fair_entities.place(&mut entity, lag);
+        to_rq.push(entity);
+        true
     }
 }
 
@@ -75,7 +286,7 @@ impl<T: Sync + Send + PreemptSchedInfo + FromTask<U>, U: Sync + Send + CommonSch
     for PreemptScheduler<T, U>
 {
     fn enqueue(&self, task: Arc<U>, flags: EnqueueFlags) -> Option<u32> {
-        let entity = PreemptSchedEntity::new(task);
+        let mut entity = PreemptSchedEntity::new(task);
         let mut still_in_rq = false;
         let target_cpu = {
             let mut cpu_id = self.select_cpu(&entity);
@@ -92,15 +303,18 @@ impl<T: Sync + Send + PreemptSchedInfo + FromTask<U>, U: Sync + Send + CommonSch
         if still_in_rq && let Err(_) = entity.task.cpu().set_if_is_none(target_cpu) {
             return None;
         }
-        if entity.thread.is_real_time() {
-            rq.real_time_entities.push_back(entity);
-        } else if entity.thread.is_lowest() {
-            rq.lowest_entities.push_back(entity);
-        } else {
-            rq.normal_entities.push_back(entity);
-        }
-
-        Some(target_cpu)
+        // The tasks that have slept for a while get some credit, but not too much,
+        // so that they can respond quickly without starving the others.
+        let lag = entity
+            .thread
+            .vruntime_lag()
+            .load(Ordering::Relaxed)
+            .max(-FairRunQueue::<T, U>::SLEEPER_CREDIT);
+        rq.fair_entities.place(&mut entity, lag);
+        let should_preempt = rq.should_preempt_for(&entity);
+        rq.push(entity);
+
+        should_preempt.then_some(target_cpu)
     }
 
     fn local_rq_with(&self, f: &mut dyn FnMut(&dyn LocalRunQueue<U>)) {
@@ -111,8 +325,10 @@ impl<T: Sync + Send + PreemptSchedInfo + FromTask<U>, U: Sync + Send + CommonSch
 
     fn local_mut_rq_with(&self, f: &mut dyn FnMut(&mut dyn LocalRunQueue<U>)) {
         let irq_guard = disable_local();
-        let local_rq: &mut PreemptRunQueue<T, U> =
-            &mut self.rq[irq_guard.current_cpu() as usize].lock();
+        let this_cpu = irq_guard.current_cpu();
+        self.push_disallowed(this_cpu);
+        self.balance(this_cpu);
+        let local_rq: &mut PreemptRunQueue<T, U> = &mut self.rq[this_cpu as usize].lock();
         f(local_rq);
     }
 }
@@ -126,8 +342,16 @@ impl Default for PreemptScheduler<Thread, Task> {
 struct PreemptRunQueue<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> {
     current: Option<PreemptSchedEntity<T, U>>,
     real_time_entities: VecDeque<PreemptSchedEntity<T, U>>,
-    normal_entities: VecDeque<PreemptSchedEntity<T, U>>,
+    fair_entities: FairRunQueue<T, U>,
     lowest_entities: VecDeque<PreemptSchedEntity<T, U>>,
+    /// The time of the last load balancing in jiffies
+    last_balance: u64,
+    /// Whether some queued tasks may not be allowed to run on this CPU
+    /// after their CPU affinities are changed
+    has_disallowed: bool,
+    /// Whether the current task is not allowed to run on this CPU
+    /// after its CPU affinity is changed
+    should_migrate_current: bool,
 }
 
 impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> PreemptRunQueue<T, U> {
@@ -135,9 +359,129 @@ impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> PreemptRunQueue<T, U
         Self {
             current: None,
             real_time_entities: VecDeque::new(),
-            normal_entities: VecDeque::new(),
+            fair_entities: FairRunQueue::new(),
             lowest_entities: VecDeque::new(),
+            last_balance: 0,
+            has_disallowed: false,
+            should_migrate_current: false,
+        }
+    }
+
+    fn push(&mut self, entity: PreemptSchedEntity<T, U>) {
+        if entity.thread.is_real_time() {
+            // Keep the real-time entities ordered by their priorities,
+            // and in FIFO order among the ones with the same priority.
+            let priority = entity.thread.priority();
+            let idx = self
+                .real_time_entities
+                .partition_point(|queued| queued.thread.priority() <= priority);
+            self.real_time_entities.insert(idx, entity);
+        } else if entity.thread.is_lowest() {
+            self.lowest_entities.push_back(entity);
+        } else {
+            self.fair_entities.push(entity);
+        }
+    }
+
+    /// Returns whether the current task should be preempted by the newly enqueued `entity`.
+    fn should_preempt_for(&self, entity: &PreemptSchedEntity<T, U>) -> bool {
+        let Some(current) = &self.current else {
+            return true;
+        };
+
+        if current.thread.is_lowest() {
+            !entity.thread.is_lowest()
+        } else if current.thread.is_real_time() {
+            entity.thread.is_real_time() && entity.thread.priority() < current.thread.priority()
+        } else if entity.thread.is_real_time() {
+            true
+        } else if entity.thread.is_lowest() {
+            false
+        } else if matches!(
+            entity.thread.sched_policy(),
+            SchedPolicy::Batch | SchedPolicy::Idle
+        ) {
+            // Like Linux, the batch and idle tasks do not preempt others on wakeup.
+            false
+        } else {
+            // Preempt only if the current task has run long enough more than the new one,
+            // which avoids over-scheduling.
+            entity.vruntime + FairRunQueue::<T, U>::WAKEUP_GRANULARITY < current.vruntime
+        }
+    }
+
+    /// Returns the load of the queued tasks, excluding the current one.
+    fn queued_load(&self) -> usize {
+        self.real_time_entities.len() * PreemptSchedEntity::<T, U>::REAL_TIME_LOAD
+            + self.fair_entities.len() * PreemptSchedEntity::<T, U>::NORMAL_LOAD
+            + self.lowest_entities.len() * PreemptSchedEntity::<T, U>::LOWEST_LOAD
+    }
+
+    /// Returns the load of all the runnable tasks, including the current one.
+    fn load(&self) -> usize {
+        self.queued_load() + self.current.as_ref().map_or(0, |entity| entity.load())
+    }
+
+    /// Returns whether there is nothing to run other than the lowest-priority tasks.
+    fn is_idle(&self) -> bool {
+        self.real_time_entities.is_empty()
+            && self.fair_entities.is_empty()
+            && self
+                .current
+                .as_ref()
+                .map_or(true, |entity| entity.thread.is_lowest())
+    }
+
+    /// Returns an iterator over the queued tasks.
+    fn queued(&self) -> impl Iterator<Item = &PreemptSchedEntity<T, U>> {
+        self.real_time_entities
+            .iter()
+            .chain(self.fair_entities.iter())
+            .chain(self.lowest_entities.iter())
+    }
+
+    /// Removes the first queued task that satisfies `predicate`.
+    fn remove_queued<F>(&mut self, predicate: F) -> Option<PreemptSchedEntity<T, U>>
+    where
+        F: Fn(&PreemptSchedEntity<T, U>) -> bool,
+    {
+        if let Some(idx) = self.real_time_entities.iter().position(&predicate) {
+            return self.real_time_entities.remove(idx);
+        }
+        if let Some(entity) = self.fair_entities.pop_last_matching(&predicate) {
+            return Some(entity);
+        }
+        let idx = self.lowest_entities.iter().position(&predicate)?;
+        self.lowest_entities.remove(idx)
+    }
+
+    /// Pops a queued task that is allowed to run on `target_cpu` and whose load satisfies
+    /// `is_load_acceptable`.
+    ///
+    /// The lowest-priority tasks are never migrated, since they only run when the CPU is idle.
+    /// The tasks that are still running (e.g., the previous current task that is put back to
+    /// the runqueue but not yet switched out) are not migrated either, since they cannot
+    /// run on other CPUs until their contexts are saved.
+    /// The most recently queued tasks are preferred, since they are the last to run.
+    fn pop_migratable<F>(
+        &mut self,
+        target_cpu: u32,
+        is_load_acceptable: F,
+    ) -> Option<PreemptSchedEntity<T, U>>
+    where
+        F: Fn(usize) -> bool,
+    {
+        let is_migratable = |entity: &PreemptSchedEntity<T, U>| {
+            is_load_acceptable(entity.load())
+                && entity.task.on_cpu().is_none()
+                && entity.thread.cpu_affinity().contains(target_cpu)
+        };
+
+        if let Some(entity) = self.fair_entities.pop_last_matching(is_migratable) {
+            return Some(entity);
         }
+        let idx = self.real_time_entities.iter().rposition(is_migratable)?;
+        self.real_time_entities.remove(idx)
     }
 }
 
@@ -154,37 +498,56 @@ impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> LocalRunQueue<U>
                 let Some(ref mut current_entity) = self.current else {
                     return false;
                 };
-                current_entity.tick()
-                    || (!current_entity.thread.is_real_time()
-                        && !self.real_time_entities.is_empty())
+                let now = Jiffies::elapsed().as_duration();
+                let should_preempt = current_entity.tick(now, &self.fair_entities)
+                    || current_entity.is_throttled(now)
+                    || self.should_migrate_current
+                    || self.real_time_entities.front().is_some_and(|entity| {
+                        !current_entity.thread.is_real_time()
+                            || entity.thread.priority() < current_entity.thread.priority()
+                    });
+                self.fair_entities
+                    .update_min_vruntime(self.current.as_ref());
+                should_preempt
             }
             _ => true,
         }
     }
 
     fn pick_next_current(&mut self) -> Option<&Arc<U>> {
-        let next_entity = if !self.real_time_entities.is_empty() {
-            self.real_time_entities.pop_front()
-        } else if !self.normal_entities.is_empty() {
-            self.normal_entities.pop_front()
-        } else {
-            self.lowest_entities.pop_front()
-        }?;
+        let now = Jiffies::elapsed().as_duration();
+        // A real-time task can only be replaced by the ones with higher or the same priorities,
+        // so it is queued before picking the next one.
+        if let Some(prev_entity) = self
+            .current
+            .take_if(|current| current.thread.is_real_time())
+        {
+            self.push(prev_entity);
+        }
+        let mut next_entity = pop_unthrottled(&mut self.real_time_entities, now)
+            .or_else(|| self.fair_entities.pop_first_unthrottled(now))
+            .or_else(|| pop_unthrottled(&mut self.lowest_entities, now))?;
+        next_entity.ticks_on_cpu = 0;
         if let Some(prev_entity) = self.current.replace(next_entity) {
-            if prev_entity.thread.is_real_time() {
-                self.real_time_entities.push_back(prev_entity);
-            } else if prev_entity.thread.is_lowest() {
-                self.lowest_entities.push_back(prev_entity);
-            } else {
-                self.normal_entities.push_back(prev_entity);
-            }
+            self.push(prev_entity);
+        }
+        // The previous task is moved to an allowed CPU once it is switched out.
+        if core::mem::take(&mut self.should_migrate_current) {
+            self.has_disallowed = true;
         }
+        self.fair_entities
+            .update_min_vruntime(self.current.as_ref());
 
         Some(&self.current.as_ref().unwrap().task)
     }
 
     fn dequeue_current(&mut self) -> Option<Arc<U>> {
+        self.should_migrate_current = false;
         self.current.take().map(|entity| {
+            // Remember the virtual runtime for the next time the task is enqueued.
+            let lag = self.fair_entities.lag_of(&entity);
+            entity.thread.vruntime_lag().store(lag, Ordering::Relaxed);
+
             let runnable = entity.task;
             runnable.cpu().set_to_none();
 
@@ -192,25 +555,247 @@ impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> LocalRunQueue<U>
         })
     }
 }
+
+/// Pops the first entity that is not throttled from the queue.
+fn pop_unthrottled<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo>(
+    entities: &mut VecDeque<PreemptSchedEntity<T, U>>,
+    now: Duration,
+) -> Option<PreemptSchedEntity<T, U>> {
+    let idx = entities
+        .iter()
+        .position(|entity| !entity.is_throttled(now))?;
+    entities.remove(idx)
+}
+
+/// The runqueue of the fair scheduling class for normal tasks.
+///
+/// Like the CFS of Linux, each entity has a virtual runtime, which increases
+/// inversely proportional to the weight of the entity while it is running.
+/// The entity with the smallest virtual runtime is picked first, so the CPU time
+/// is distributed among the entities in proportion to their weights.
+struct FairRunQueue<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> {
+    /// The entities ordered by their virtual runtime and then their queuing order
+    entities: BTreeMap<(u64, u64), PreemptSchedEntity<T, U>>,
+    /// The monotonically increasing lower bound of the virtual runtime
+    /// of the entities in the queue and the current one
+    min_vruntime: u64,
+    /// The total weight of the entities in the queue
+    total_weight: u64,
+    next_seq: u64,
+}
+
+impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> FairRunQueue<T, U> {
+    /// The period in which every entity is expected to run once, in ticks.
+    const SCHED_LATENCY: u64 = 24;
+    /// The minimum number of ticks that an entity runs before being preempted.
+    const MIN_GRANULARITY: u64 = 3;
+    /// The virtual runtime by which a newly woken entity must be ahead of
+    /// the current one to preempt it.
+    const WAKEUP_GRANULARITY: u64 = PreemptSchedEntity::<T, U>::TICK_DURATION.as_nanos() as u64;
+    /// The maximum virtual runtime credit that a sleeping entity can get.
+    const SLEEPER_CREDIT: i64 = (PreemptSchedEntity::<T, U>::TICK_DURATION.as_nanos() as u64
+        * Self::SCHED_LATENCY
+        / 2) as i64;
+
+    fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            min_vruntime: 0,
+            total_weight: 0,
+            next_seq: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn push(&mut self, entity: PreemptSchedEntity<T, U>) {
+        self.total_weight += entity.weight;
+        self.entities
+            .insert((entity.vruntime, self.next_seq), entity);
+        self.next_seq += 1;
+    }
+
+    fn remove(&mut self, key: (u64, u64)) -> Option<PreemptSchedEntity<T, U>> {
+        let entity = self.entities.remove(&key)?;
+        self.total_weight -= entity.weight;
+        Some(entity)
+    }
+
+    /// Pops the entity with the smallest virtual runtime that is not throttled.
+    fn pop_first_unthrottled(&mut self, now: Duration) -> Option<PreemptSchedEntity<T, U>> {
+        let key = *self
+            .entities
+            .iter()
+            .find(|(_, entity)| !entity.is_throttled(now))?
+            .0;
+        self.remove(key)
+    }
+
+    /// Pops the entity with the largest virtual runtime that matches the `predicate`.
+    fn iter(&self) -> impl Iterator<Item = &PreemptSchedEntity<T, U>> {
+        self.entities.values()
+    }
+
+    fn pop_last_matching<F>(&mut self, predicate: F) -> Option<PreemptSchedEntity<T, U>>
+    where
+        F: Fn(&PreemptSchedEntity<T, U>) -> bool,
+    {
+        let key = *self
+            .entities
+            .iter()
+            .rev()
+            .find(|(_, entity)| predicate(entity))?
+            .0;
+        self.remove(key)
+    }
+
+    /// Returns the number of ticks that the `current` entity is expected to run
+    /// before yielding to the others.
+    fn ideal_slice(&self, current: &PreemptSchedEntity<T, U>) -> u64 {
+        (Self::SCHED_LATENCY * current.weight / (self.total_weight + current.weight))
+            .max(Self::MIN_GRANULARITY)
+    }
+
+    /// Returns whether the `current` entity should yield to the others.
+    fn should_preempt(&self, current: &PreemptSchedEntity<T, U>) -> bool {
+        let Some(((first_vruntime, _), _)) = self.entities.first_key_value() else {
+            return false;
+        };
+        current.ticks_on_cpu as u64 >= self.ideal_slice(current)
+            && *first_vruntime < current.vruntime
+    }
+
+    fn update_min_vruntime(&mut self, current: Option<&PreemptSchedEntity<T, U>>) {
+        let first_vruntime = self
+            .entities
+            .first_key_value()
+            .map(|((vruntime, _), _)| *vruntime);
+        let current_vruntime = current
+            .filter(|current| current.is_fair())
+            .map(|current| current.vruntime);
+        let Some(vruntime) = first_vruntime.into_iter().chain(current_vruntime).min() else {
+            return;
+        };
+        self.min_vruntime = self.min_vruntime.max(vruntime);
+    }
+
+    /// Returns the virtual runtime of the `entity` relative to the queue.
+    fn lag_of(&self, entity: &PreemptSchedEntity<T, U>) -> i64 {
+        entity.vruntime.wrapping_sub(self.min_vruntime) as i64
+    }
+
+    /// Places the `entity` at the virtual runtime of `lag` relative to the queue.
+    fn place(&self, entity: &mut PreemptSchedEntity<T, U>, lag: i64) {
+        entity.vruntime = self.min_vruntime.saturating_add_signed(lag);
+    }
+}
+
 struct PreemptSchedEntity<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> {
     task: Arc<U>,
     thread: Arc<T>,
+    /// The cgroup whose CPU controller applies to the entity,
+    /// which is refreshed when the task is enqueued and on every tick
+    cgroup: Option<Arc<Cgroup>>,
+    /// The time slice of a real-time or lowest-priority entity
     time_slice: TimeSlice,
+    /// The weight of a normal entity in the fair queue
+    weight: u64,
+    /// The virtual runtime of a normal entity in nanoseconds
+    vruntime: u64,
+    /// The number of ticks since the entity became the current one
+    ticks_on_cpu: u32,
 }
 
 impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> PreemptSchedEntity<T, U> {
+    /// The CPU time consumed in a tick.
+    const TICK_DURATION: Duration = Duration::from_nanos(1_000_000_000 / TIMER_FREQ);
+
+    // A wild guess measuring the load of a task. We assume that
+    // real-time tasks are 4-times as important as normal tasks.
+    const REAL_TIME_LOAD: usize = 8;
+    const NORMAL_LOAD: usize = 2;
+    const LOWEST_LOAD: usize = 1;
+
     fn new(task: Arc<U>) -> Self {
         let thread = T::from_task(&task);
-        let time_slice = TimeSlice::default();
-        Self {
+        let mut entity = Self {
             task,
             thread,
-            time_slice,
+            cgroup: None,
+            time_slice: TimeSlice::default(),
+            weight: NICE_0_WEIGHT,
+            vruntime: 0,
+            ticks_on_cpu: 0,
+        };
+        entity.refresh_weight();
+        entity
+    }
+
+    /// Recomputes the cgroup and the weight of the entity.
+    ///
+    /// The priority, the scheduling policy and the cgroup of the task may be changed while it is
+    /// running, so this is done on every tick of the current entity, in addition to the time when
+    /// the task is enqueued. A queued entity must not be refreshed, since the total weight of the
+    /// fair queue would become inconsistent.
+    fn refresh_weight(&mut self) {
+        self.cgroup = if self.thread.is_real_time() {
+            None
+        } else {
+            self.thread.cgroup()
+        };
+        let cgroup_weight = self
+            .cgroup
+            .as_ref()
+            .map_or(DEFAULT_CPU_WEIGHT, |cgroup| cgroup.cpu().weight());
+        let nice_weight = if self.thread.sched_policy() == SchedPolicy::Idle {
+            IDLE_WEIGHT
+        } else {
+            nice_to_weight(self.thread.priority())
+        };
+        self.weight = (nice_weight * cgroup_weight as u64 / DEFAULT_CPU_WEIGHT as u64).max(1);
+    }
+
+    /// Returns whether the entity belongs to the fair scheduling class.
+    fn is_fair(&self) -> bool {
+        !self.thread.is_real_time() && !self.thread.is_lowest()
+    }
+
+    /// Accounts a tick of CPU time to the entity and returns whether it should be preempted.
+    fn tick(&mut self, now: Duration, fair_entities: &FairRunQueue<T, U>) -> bool {
+        if let Some(cgroup) = &self.cgroup {
+            cgroup.account_cpu_time(now, Self::TICK_DURATION);
         }
+        self.refresh_weight();
+        self.ticks_on_cpu = self.ticks_on_cpu.saturating_add(1);
+
+        if !self.is_fair() {
+            // The FIFO tasks run until they block or yield, without time slices.
+            return self.thread.sched_policy() != SchedPolicy::Fifo && self.time_slice.elapse();
+        }
+        self.vruntime += Self::TICK_DURATION.as_nanos() as u64 * NICE_0_WEIGHT / self.weight;
+        fair_entities.should_preempt(self)
     }
 
-    fn tick(&mut self) -> bool {
-        self.time_slice.elapse()
+    fn load(&self) -> usize {
+        if self.thread.is_real_time() {
+            Self::REAL_TIME_LOAD
+        } else if self.thread.is_lowest() {
+            Self::LOWEST_LOAD
+        } else {
+            Self::NORMAL_LOAD
+        }
+    }
+
+    fn is_throttled(&self, now: Duration) -> bool {
+        self.cgroup
+            .as_ref()
+            .is_some_and(|cgroup| cgroup.is_cpu_throttled(now))
     }
 }
 
@@ -219,7 +804,11 @@ impl<T: PreemptSchedInfo + FromTask<U>, U: CommonSchedInfo> Clone for PreemptSch
         Self {
             task: self.task.clone(),
             thread: self.thread.clone(),
+            cgroup: self.cgroup.clone(),
             time_slice: self.time_slice,
+            weight: self.weight,
+            vruntime: self.vruntime,
+            ticks_on_cpu: self.ticks_on_cpu,
         }
     }
 }
@@ -249,6 +838,27 @@ impl Default for TimeSlice {
     }
 }
 
+/// The weight of a task with the nice value of zero.
+const NICE_0_WEIGHT: u64 = 1024;
+/// The weight of a task with the `SCHED_IDLE` policy, which is even lower than the nice value of 19.
+const IDLE_WEIGHT: u64 = 3;
+
+/// Returns the weight of a normal task in the fair queue.
+///
+/// Like Linux, each nice level changes the weight by about 25%, so that
+/// a task gets about 10% more or less CPU time than another task with
+/// the adjacent nice value.
+fn nice_to_weight(priority: Priority) -> u64 {
+    const NICE_TO_WEIGHT: [u64; 40] = [
+        88761, 71755, 56483, 46273, 36291, 29154, 23254, 18705, 14949, 11916, 9548, 7620, 6100,
+        4904, 3906, 3121, 2501, 1991, 1586, 1277, 1024, 820, 655, 526, 423, 335, 272, 215, 172,
+        137, 110, 87, 70, 56, 45, 36, 29, 23, 18, 15,
+    ];
+    // The priorities of the normal tasks are [100, 139], i.e., the nice values of [-20, 19].
+    let idx = priority.range().get().saturating_sub(100) as usize;
+    NICE_TO_WEIGHT[idx.min(NICE_TO_WEIGHT.len() - 1)]
+}
+
 impl PreemptSchedInfo for Thread {
     const REAL_TIME_TASK_PRIORITY: Priority = Priority::new(PriorityRange::new(100));
     const LOWEST_TASK_PRIORITY: Priority = Priority::new(PriorityRange::new(PriorityRange::MAX));
@@ -257,9 +867,30 @@ impl PreemptSchedInfo for Thread {
         self.priority()
     }
 
+    fn sched_policy(&self) -> SchedPolicy {
+        self.sched_policy()
+    }
+
     fn cpu_affinity(&self) -> SpinLockGuard<CpuSet, PreemptDisabled> {
         self.lock_cpu_affinity()
     }
+
+    fn cgroup(&self) -> Option<Arc<Cgroup>> {
+        let process = self.as_posix_thread()?.weak_process().upgrade()?;
+        Some(process.cgroup())
+    }
+
+    fn vruntime_lag(&self) -> &AtomicI64 {
+        self.vruntime_lag()
+    }
+
+    /// Only kernel threads (e.g., the idle threads) can be the lowest-priority tasks.
+    ///
+    /// The user threads with the lowest priority (i.e., the nice value of 19)
+    /// are still scheduled in the fair queue.
+    fn is_lowest(&self) -> bool {
+        self.priority() == Self::LOWEST_TASK_PRIORITY && self.as_posix_thread().is_none()
+    }
 }
 
 trait PreemptSchedInfo {
@@ -268,8 +899,17 @@ trait PreemptSchedInfo {
 
     fn priority(&self) -> Priority;
 
+    fn sched_policy(&self) -> SchedPolicy;
+
     fn cpu_affinity(&self) -> SpinLockGuard<CpuSet, PreemptDisabled>;
 
+    /// Returns the cgroup of the task, or `None` if the task does not belong to a process.
+    fn cgroup(&self) -> Option<Arc<Cgroup>>;
+
+    /// Returns the virtual runtime of the task relative to the fair queue
+    /// when the task was dequeued last time.
+    fn vruntime_lag(&self) -> &AtomicI64;
+
     fn is_real_time(&self) -> bool {
         self.priority() < Self::REAL_TIME_TASK_PRIORITY
     }
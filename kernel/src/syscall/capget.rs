@@ -26,7 +26,8 @@ pub fn sys_capget(
     // Capget only query current process's credential. Namely, it only allows header->pid == 0
     // or header->pid == getpid(), which are equivalent.
     // See https://linux.die.net/man/2/capget (Section. With VFS capability support) for details.
-    if header_pid != 0 && header_pid != ctx.process.pid() {
+    if header_pid != 0 && ctx.process.pid_ns().global_pid_of(header_pid) != Some(ctx.process.pid())
+    {
         return_errno_with_message!(Errno::EINVAL, "invalid pid");
     }
 
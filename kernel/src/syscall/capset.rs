@@ -29,7 +29,8 @@ pub fn sys_capset(
     // The ability to set capabilities of any other process has been deprecated.
     // See: https://elixir.bootlin.com/linux/v6.9.3/source/kernel/capability.c#L209 for more details.
     let header_pid = cap_user_header.pid;
-    if header_pid != 0 && header_pid != ctx.process.pid() {
+    if header_pid != 0 && ctx.process.pid_ns().global_pid_of(header_pid) != Some(ctx.process.pid())
+    {
         return_errno_with_message!(Errno::EINVAL, "invalid pid");
     }
 
@@ -6,6 +6,7 @@ use ostd::cpu::UserContext;
 
 use super::SyscallReturn;
 use crate::{
+    fs::file_table::FileDesc,
     prelude::*,
     process::{clone_child, signal::sig_num::SigNum, CloneArgs, CloneFlags},
 };
@@ -81,9 +82,14 @@ struct Clone3Args {
     cgroup: u64,
 }
 
+/// Places the child process in the cgroup specified by the `cgroup` field.
+///
+/// The flag does not fit in [`CloneFlags`], which only holds the lower 32 bits.
+const CLONE_INTO_CGROUP: u64 = 0x200000000;
+
 impl From<Clone3Args> for CloneArgs {
     fn from(value: Clone3Args) -> Self {
-        // TODO: deal with pidfd, set_tid, set_tid_size, cgroup
+        // TODO: deal with pidfd, set_tid, set_tid_size
         if value.pidfd != 0 {
             warn!("pidfd is not supported");
         }
@@ -92,10 +98,6 @@ impl From<Clone3Args> for CloneArgs {
             warn!("set_tid is not supported");
         }
 
-        if value.cgroup != 0 {
-            warn!("cgroup is not supported");
-        }
-
         Self {
             flags: CloneFlags::from_bits_truncate(value.flags as u32),
             _pidfd: Some(value.pidfd),
@@ -107,7 +109,7 @@ impl From<Clone3Args> for CloneArgs {
             tls: value.tls,
             _set_tid: Some(value.set_tid),
             _set_tid_size: Some(value.set_tid_size),
-            _cgroup: Some(value.cgroup),
+            cgroup: (value.flags & CLONE_INTO_CGROUP != 0).then_some(value.cgroup as FileDesc),
         }
     }
 }
@@ -13,8 +13,10 @@ use crate::{
     },
     prelude::*,
     process::{
-        check_executable_file, load_program_to_vm, posix_thread::ThreadName, Credentials, Process,
-        MAX_ARGV_NUMBER, MAX_ARG_LEN, MAX_ENVP_NUMBER, MAX_ENV_LEN,
+        check_executable_file, load_program_to_vm,
+        posix_thread::ThreadName,
+        signal::{constants::SIGTRAP, signals::kernel::KernelSignal},
+        Credentials, Process, MAX_ARGV_NUMBER, MAX_ARG_LEN, MAX_ENVP_NUMBER, MAX_ENV_LEN,
     },
 };
 
@@ -123,6 +125,9 @@ fn do_execve(
     debug!("load elf in execve succeeds");
 
     let credentials = ctx.posix_thread.credentials_mut();
+    // The process becomes dumpable again, unless it gains privileges from a set-user-ID or
+    // set-group-ID program below.
+    process.set_dumpable(true);
     set_uid_from_elf(process, &credentials, &elf_file)?;
     set_gid_from_elf(process, &credentials, &elf_file)?;
 
@@ -141,6 +146,11 @@ fn do_execve(
     // set new user stack top
     user_context.set_stack_pointer(elf_load_info.user_stack_top() as _);
     debug!("user stack top: 0x{:x}", elf_load_info.user_stack_top());
+    // A traced thread is sent a `SIGTRAP` after a successful `execve`,
+    // which gives the tracer a chance to gain control before the new program runs.
+    if posix_thread.ptrace().is_traced() {
+        posix_thread.enqueue_signal(Box::new(KernelSignal::new(SIGTRAP)));
+    }
     Ok(())
 }
 
@@ -200,6 +210,7 @@ fn set_uid_from_elf(
         credentials.set_euid(uid);
 
         current.clear_parent_death_signal();
+        current.set_dumpable(false);
     }
 
     // No matter whether the elf_file has `set_uid` bit, suid should be reset.
@@ -218,6 +229,7 @@ fn set_gid_from_elf(
         credentials.set_egid(gid);
 
         current.clear_parent_death_signal();
+        current.set_dumpable(false);
     }
 
     // No matter whether the the elf file has `set_gid` bit, sgid should be reset.
@@ -7,11 +7,12 @@ use crate::{
         file_table::{FdFlags, FileDesc},
         inode_handle::InodeHandle,
         utils::{
-            FileRange, RangeLockItem, RangeLockItemBuilder, RangeLockType, StatusFlags, OFFSET_MAX,
+            FileRange, FileSeals, RangeLockItem, RangeLockItemBuilder, RangeLockType, StatusFlags,
+            OFFSET_MAX,
         },
     },
     prelude::*,
-    process::{process_table, Pid},
+    process::Pid,
 };
 
 pub fn sys_fcntl(fd: FileDesc, cmd: i32, arg: u64, ctx: &Context) -> Result<SyscallReturn> {
@@ -29,6 +30,8 @@ pub fn sys_fcntl(fd: FileDesc, cmd: i32, arg: u64, ctx: &Context) -> Result<Sysc
         FcntlCmd::F_SETLKW => handle_setlk(fd, arg, false, ctx),
         FcntlCmd::F_GETOWN => handle_getown(fd, ctx),
         FcntlCmd::F_SETOWN => handle_setown(fd, arg, ctx),
+        FcntlCmd::F_ADD_SEALS => handle_addseals(fd, arg, ctx),
+        FcntlCmd::F_GET_SEALS => handle_getseals(fd, ctx),
     }
 }
 
@@ -137,7 +140,9 @@ fn handle_setlk(
 fn handle_getown(fd: FileDesc, ctx: &Context) -> Result<SyscallReturn> {
     let file_table = ctx.process.file_table().lock();
     let file_entry = file_table.get_entry(fd)?;
-    let pid = file_entry.owner().unwrap_or(0);
+    let pid = file_entry
+        .owner()
+        .map_or(0, |pid| ctx.process.pid_ns().pid_or_zero(pid));
     Ok(SyscallReturn::Return(pid as _))
 }
 
@@ -153,10 +158,15 @@ fn handle_setown(fd: FileDesc, arg: u64, ctx: &Context) -> Result<SyscallReturn>
     let owner_process = if pid == 0 {
         None
     } else {
-        Some(process_table::get_process(pid).ok_or(Error::with_message(
-            Errno::ESRCH,
-            "cannot set_owner with an invalid pid",
-        ))?)
+        Some(
+            ctx.process
+                .pid_ns()
+                .get_process(pid)
+                .ok_or(Error::with_message(
+                    Errno::ESRCH,
+                    "cannot set_owner with an invalid pid",
+                ))?,
+        )
     };
 
     let mut file_table = ctx.process.file_table().lock();
@@ -165,6 +175,38 @@ fn handle_setown(fd: FileDesc, arg: u64, ctx: &Context) -> Result<SyscallReturn>
     Ok(SyscallReturn::Return(0))
 }
 
+fn handle_addseals(fd: FileDesc, arg: u64, ctx: &Context) -> Result<SyscallReturn> {
+    let new_seals = FileSeals::from_bits(arg as u32)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid seals"))?;
+
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let inode_file = file
+        .downcast_ref::<InodeHandle>()
+        .ok_or(Error::with_message(Errno::EINVAL, "not inode"))?;
+    if !inode_file.access_mode().is_writable() {
+        return_errno_with_message!(Errno::EPERM, "the file is not opened for writing");
+    }
+
+    inode_file.dentry().inode().add_seals(new_seals)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+fn handle_getseals(fd: FileDesc, ctx: &Context) -> Result<SyscallReturn> {
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let inode_file = file
+        .downcast_ref::<InodeHandle>()
+        .ok_or(Error::with_message(Errno::EINVAL, "not inode"))?;
+
+    let seals = inode_file.dentry().inode().get_seals()?;
+    Ok(SyscallReturn::Return(seals.bits() as _))
+}
+
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, TryFromInt)]
 #[allow(non_camel_case_types)]
@@ -180,6 +222,8 @@ enum FcntlCmd {
     F_SETOWN = 8,
     F_GETOWN = 9,
     F_DUPFD_CLOEXEC = 1030,
+    F_ADD_SEALS = 1033,
+    F_GET_SEALS = 1034,
 }
 
 #[allow(non_camel_case_types)]
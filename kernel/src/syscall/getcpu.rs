@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use ostd::{cpu::PinCurrentCpu, task::disable_preempt};
+
+use super::SyscallReturn;
+use crate::prelude::*;
+
+pub fn sys_getcpu(cpu_ptr: Vaddr, node_ptr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("cpu_ptr = 0x{:x}, node_ptr = 0x{:x}", cpu_ptr, node_ptr);
+
+    // The result may be outdated once the thread is migrated, as in Linux.
+    let cpu_id = disable_preempt().current_cpu();
+
+    let user_space = ctx.get_user_space();
+    if cpu_ptr != 0 {
+        user_space.write_val(cpu_ptr, &cpu_id)?;
+    }
+    // NUMA is not supported, so all the CPUs are on node 0.
+    if node_ptr != 0 {
+        user_space.write_val(node_ptr, &0u32)?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
@@ -4,5 +4,8 @@ use super::SyscallReturn;
 use crate::prelude::*;
 
 pub fn sys_getppid(ctx: &Context) -> Result<SyscallReturn> {
-    Ok(SyscallReturn::Return(ctx.process.parent().pid() as _))
+    // The parent of the init process of a PID namespace is outside the namespace,
+    // so the PPID is 0 in the namespace.
+    let ppid = ctx.process.pid_ns().pid_or_zero(ctx.process.parent().pid());
+    Ok(SyscallReturn::Return(ppid as _))
 }
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    fs::{
+        file_table::{FdFlags, FileDesc},
+        fs_resolver::{FsPath, AT_FDCWD},
+        notify::{
+            inotify::{InotifyFile, InotifyWatchFlags},
+            FsEvents,
+        },
+        utils::{CreationFlags, InodeType, Permission, StatusFlags, PATH_MAX},
+    },
+    prelude::*,
+};
+
+pub fn sys_inotify_init(ctx: &Context) -> Result<SyscallReturn> {
+    sys_inotify_init1(0, ctx)
+}
+
+pub fn sys_inotify_init1(flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = Flags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    debug!("flags = {:?}", flags);
+
+    let fd_flags = if flags.contains(Flags::IN_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+    let inotify_file = InotifyFile::new(flags.contains(Flags::IN_NONBLOCK));
+
+    let fd = ctx
+        .process
+        .file_table()
+        .lock()
+        .insert(inotify_file, fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+pub fn sys_inotify_add_watch(
+    fd: FileDesc,
+    path_ptr: Vaddr,
+    mask: u32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let path = ctx.get_user_space().read_cstring(path_ptr, PATH_MAX)?;
+    let events = FsEvents::from_bits_truncate(mask);
+    let flags = InotifyWatchFlags::from_bits_truncate(mask);
+    debug!(
+        "fd = {}, path = {:?}, events = {:?}, flags = {:?}",
+        fd, path, events, flags
+    );
+
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let inotify_file = file
+        .downcast_ref::<InotifyFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not an inotify file"))?;
+
+    let dentry = {
+        let path = path.to_string_lossy();
+        if path.is_empty() {
+            return_errno_with_message!(Errno::ENOENT, "path is empty");
+        }
+        let fs_path = FsPath::new(AT_FDCWD, path.as_ref())?;
+        let fs = ctx.process.fs().read();
+        if flags.contains(InotifyWatchFlags::IN_DONT_FOLLOW) {
+            fs.lookup_no_follow(&fs_path)?
+        } else {
+            fs.lookup(&fs_path)?
+        }
+    };
+    if flags.contains(InotifyWatchFlags::IN_ONLYDIR) && dentry.type_() != InodeType::Dir {
+        return_errno_with_message!(Errno::ENOTDIR, "the path is not a directory");
+    }
+    dentry
+        .inode()
+        .check_permission(Permission::MAY_READ, &ctx.posix_thread.credentials())?;
+
+    let wd = inotify_file.add_watch(dentry.inode().clone(), events, flags)?;
+    Ok(SyscallReturn::Return(wd as _))
+}
+
+pub fn sys_inotify_rm_watch(fd: FileDesc, wd: i32, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("fd = {}, wd = {}", fd, wd);
+
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let inotify_file = file
+        .downcast_ref::<InotifyFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not an inotify file"))?;
+
+    inotify_file.remove_watch(wd)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+bitflags! {
+    struct Flags: u32 {
+        const IN_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+        const IN_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+    }
+}
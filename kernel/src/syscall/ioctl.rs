@@ -6,6 +6,7 @@ use crate::{
         file_table::FileDesc,
         utils::{IoctlCmd, StatusFlags},
     },
+    net::iface::handle_iface_ioctl,
     prelude::*,
 };
 
@@ -39,6 +40,23 @@ pub fn sys_ioctl(fd: FileDesc, cmd: u32, arg: Vaddr, ctx: &Context) -> Result<Sy
             file.set_status_flags(flags)?;
             0
         }
+        IoctlCmd::SIOCGIFNAME
+        | IoctlCmd::SIOCGIFCONF
+        | IoctlCmd::SIOCGIFFLAGS
+        | IoctlCmd::SIOCSIFFLAGS
+        | IoctlCmd::SIOCGIFADDR
+        | IoctlCmd::SIOCSIFADDR
+        | IoctlCmd::SIOCGIFNETMASK
+        | IoctlCmd::SIOCSIFNETMASK
+        | IoctlCmd::SIOCGIFMTU
+        | IoctlCmd::SIOCGIFHWADDR
+        | IoctlCmd::SIOCGIFINDEX => {
+            // The iface-related ioctls are available for all kinds of sockets.
+            if file.clone().as_socket().is_none() {
+                return_errno_with_message!(Errno::ENOTTY, "the file is not a socket");
+            }
+            handle_iface_ioctl(ioctl_cmd, arg, ctx)?
+        }
         _ => file.ioctl(ioctl_cmd, arg)?,
     };
     Ok(SyscallReturn::Return(res as _))
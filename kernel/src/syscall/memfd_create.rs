@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    fs::{
+        file_table::FdFlags,
+        ramfs::memfd::{create_memfd, MFD_NAME_MAX_LEN},
+    },
+    prelude::*,
+    syscall::constants::MAX_FILENAME_LEN,
+};
+
+pub fn sys_memfd_create(name_addr: Vaddr, flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    let name = ctx
+        .get_user_space()
+        .read_cstring(name_addr, MAX_FILENAME_LEN)?;
+    let flags = MemfdFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    debug!("name = {:?}, flags = {:?}", name, flags);
+
+    if name.as_bytes().len() > MFD_NAME_MAX_LEN {
+        return_errno_with_message!(Errno::EINVAL, "the name is too long");
+    }
+
+    if flags.contains(MemfdFlags::MFD_HUGETLB) {
+        return_errno_with_message!(Errno::EINVAL, "huge pages are not supported");
+    }
+
+    let memfd = create_memfd(
+        name.to_string_lossy().as_ref(),
+        flags.contains(MemfdFlags::MFD_ALLOW_SEALING),
+        &ctx.posix_thread.credentials(),
+    )?;
+
+    let fd_flags = if flags.contains(MemfdFlags::MFD_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+    let fd = ctx
+        .process
+        .file_table()
+        .lock()
+        .insert(Arc::new(memfd), fd_flags);
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+bitflags! {
+    struct MemfdFlags: u32 {
+        const MFD_CLOEXEC = 0x0001;
+        const MFD_ALLOW_SEALING = 0x0002;
+        const MFD_HUGETLB = 0x0004;
+    }
+}
@@ -106,7 +106,7 @@ fn do_sys_mmap(
                 options = options.vmo(shared_vmo);
             }
         } else {
-            let vmo = {
+            let (vmo, may_write) = {
                 let file_table = ctx.process.file_table().lock();
                 let file = file_table.get_file(fd)?;
                 let inode_handle = file
@@ -125,18 +125,32 @@ fn do_sys_mmap(
                 }
 
                 let inode = inode_handle.dentry().inode();
-                inode
+                let is_write_sealed = inode.get_seals().is_ok_and(|seals| seals.is_write_sealed());
+                if option.typ() == MMapType::Shared
+                    && vm_perms.contains(VmPerms::WRITE)
+                    && is_write_sealed
+                {
+                    return_errno_with_message!(Errno::EPERM, "the file is sealed for writing");
+                }
+                // The shared mapping cannot be made writable later by `mprotect`
+                // if the file is not opened for writing or is sealed for writing.
+                let may_write = option.typ() != MMapType::Shared
+                    || (access_mode.is_writable() && !is_write_sealed);
+
+                let vmo = inode
                     .page_cache()
                     .ok_or(Error::with_message(
                         Errno::EBADF,
                         "File does not have page cache",
                     ))?
-                    .to_dyn()
+                    .to_dyn();
+                (vmo, may_write)
             };
 
             options = options
                 .vmo(vmo)
                 .vmo_offset(offset)
+                .may_write(may_write)
                 .handle_page_faults_around();
         }
 
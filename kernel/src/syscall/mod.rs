@@ -5,7 +5,12 @@
 pub use clock_gettime::ClockId;
 use ostd::cpu::UserContext;
 
-use crate::{context::Context, cpu::LinuxAbi, prelude::*};
+use crate::{
+    context::Context,
+    cpu::LinuxAbi,
+    prelude::*,
+    process::ptrace::{stop_at_syscall_entry, stop_at_syscall_exit},
+};
 
 mod accept;
 mod access;
@@ -37,6 +42,7 @@ mod flock;
 mod fork;
 mod fsync;
 mod futex;
+mod getcpu;
 mod getcwd;
 mod getdents64;
 mod getegid;
@@ -57,17 +63,24 @@ mod getsockopt;
 mod gettid;
 mod gettimeofday;
 mod getuid;
+mod inotify;
 mod ioctl;
 mod kill;
 mod link;
 mod listen;
 mod lseek;
 mod madvise;
+mod memfd_create;
 mod mkdir;
 mod mknod;
 mod mmap;
 mod mount;
 mod mprotect;
+mod mqueue;
+mod msgctl;
+mod msgget;
+mod msgrcv;
+mod msgsnd;
 mod msync;
 mod munmap;
 mod nanosleep;
@@ -80,6 +93,7 @@ mod pread64;
 mod preadv;
 mod prlimit64;
 mod pselect6;
+mod ptrace;
 mod pwrite64;
 mod pwritev;
 mod read;
@@ -93,7 +107,9 @@ mod rt_sigpending;
 mod rt_sigprocmask;
 mod rt_sigreturn;
 mod rt_sigsuspend;
-mod sched_getaffinity;
+mod sched_affinity;
+mod sched_attr;
+mod sched_param;
 mod sched_yield;
 mod select;
 mod semctl;
@@ -110,6 +126,7 @@ mod setfsuid;
 mod setgid;
 mod setgroups;
 mod setitimer;
+mod setns;
 mod setpgid;
 mod setregid;
 mod setresgid;
@@ -118,8 +135,13 @@ mod setreuid;
 mod setsid;
 mod setsockopt;
 mod setuid;
+mod shmat;
+mod shmctl;
+mod shmdt;
+mod shmget;
 mod shutdown;
 mod sigaltstack;
+mod signalfd;
 mod socket;
 mod socketpair;
 mod stat;
@@ -130,11 +152,13 @@ mod tgkill;
 mod time;
 mod timer_create;
 mod timer_settime;
+mod timerfd;
 mod truncate;
 mod umask;
 mod umount;
 mod uname;
 mod unlink;
+mod unshare;
 mod utimens;
 mod wait4;
 mod waitid;
@@ -317,7 +341,25 @@ impl SyscallArgument {
 }
 
 pub fn handle_syscall(ctx: &Context, user_ctx: &mut UserContext) {
-    let syscall_frame = SyscallArgument::new_from_context(user_ctx);
+    let mut syscall_frame = SyscallArgument::new_from_context(user_ctx);
+
+    let is_syscall_traced = ctx.posix_thread.ptrace().is_syscall_traced();
+    if is_syscall_traced {
+        // The tracer may change the syscall number and the arguments at the syscall-entry stop.
+        // Setting the syscall number to -1 skips the syscall.
+        let syscall_number =
+            stop_at_syscall_entry(syscall_frame.syscall_number as usize, user_ctx, ctx);
+        syscall_frame = SyscallArgument::new_from_context(user_ctx);
+        syscall_frame.syscall_number = syscall_number as u64;
+
+        // The return value of a skipped syscall is whatever the tracer has left in the
+        // registers, so the user context should not be touched.
+        if syscall_number == usize::MAX {
+            stop_at_syscall_exit(user_ctx, ctx);
+            return;
+        }
+    }
+
     let syscall_return = arch::syscall_dispatch(
         syscall_frame.syscall_number,
         syscall_frame.args,
@@ -337,6 +379,10 @@ pub fn handle_syscall(ctx: &Context, user_ctx: &mut UserContext) {
             user_ctx.set_syscall_ret((-errno) as usize)
         }
     }
+
+    if is_syscall_traced {
+        stop_at_syscall_exit(user_ctx, ctx);
+    }
 }
 
 #[macro_export]
@@ -3,10 +3,12 @@
 use super::SyscallReturn;
 use crate::{
     fs::{
+        cgroupfs::CgroupFs,
         exfat::{ExfatFS, ExfatMountOptions},
         ext2::Ext2,
         fs_resolver::{FsPath, AT_FDCWD},
         path::Dentry,
+        procfs::ProcFS,
         utils::{FileSystem, InodeType},
     },
     prelude::*,
@@ -148,13 +150,22 @@ fn do_new_mount(
     if fs_type.is_empty() {
         return_errno_with_message!(Errno::EINVAL, "fs_type is empty");
     }
-    let fs = get_fs(fs_type, devname)?;
+    let fs = get_fs(fs_type, devname, ctx)?;
     target_dentry.mount(fs)?;
     Ok(())
 }
 
 /// Get the filesystem by fs_type and devname.
-fn get_fs(fs_type: CString, devname: CString) -> Result<Arc<dyn FileSystem>> {
+fn get_fs(fs_type: CString, devname: CString, ctx: &Context) -> Result<Arc<dyn FileSystem>> {
+    // The pseudo file systems do not need a device.
+    if fs_type.to_str() == Ok("cgroup2") {
+        return Ok(CgroupFs::singleton().clone());
+    }
+    if fs_type.to_str() == Ok("proc") {
+        // A new instance shows the processes in the PID namespace of the current process.
+        return Ok(ProcFS::new(ctx.process.pid_ns().clone()));
+    }
+
     let devname = devname.to_str().unwrap();
     let device = match aster_block::get_device(devname) {
         Some(device) => device,
@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The POSIX message queue syscalls.
+//!
+//! A message queue is opened as a file descriptor (we name it as `MqueueFile`),
+//! which can be passed to the other `mq_*` syscalls,
+//! and can be monitored by `select`, `poll` and `epoll`.
+//!
+//! For more detailed information about these syscalls,
+//! refer to the man 7 mq_overview documentation.
+//!
+
+use core::time::Duration;
+
+use super::SyscallReturn;
+use crate::{
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDesc},
+        utils::{AccessMode, CreationFlags, StatusFlags},
+    },
+    ipc::mqueue::{MqAttr, MqueueFile, MQ_PRIO_MAX},
+    prelude::*,
+    process::signal::{
+        c_types::{sigevent_t, SigNotify},
+        sig_num::SigNum,
+        signals::kernel::KernelSignal,
+    },
+    syscall::constants::MAX_FILENAME_LEN,
+    time::{clocks::RealTimeClock, timer::Timeout, timespec_t, wait::TimerBuilder},
+};
+
+pub fn sys_mq_open(
+    name_addr: Vaddr,
+    oflag: u32,
+    mode: u16,
+    attr_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let user_space = ctx.get_user_space();
+    let name = user_space.read_cstring(name_addr, MAX_FILENAME_LEN)?;
+    debug!(
+        "name = {:?}, oflag = {:#o}, mode = {:#o}, attr_addr = {:#x}",
+        name, oflag, mode, attr_addr
+    );
+
+    let access_mode = AccessMode::from_u32(oflag)?;
+    let creation_flags = CreationFlags::from_bits_truncate(oflag);
+    let is_nonblocking = StatusFlags::from_bits_truncate(oflag).contains(StatusFlags::O_NONBLOCK);
+    let attr = if attr_addr != 0 && creation_flags.contains(CreationFlags::O_CREAT) {
+        Some(user_space.read_val::<MqAttr>(attr_addr)?)
+    } else {
+        None
+    };
+
+    let current = ctx.process;
+    let mqueue_file = current.nsproxy().ipc_ns().mqueue_fs().open_mqueue(
+        name.to_string_lossy().as_ref(),
+        access_mode,
+        creation_flags,
+        mode & !current.umask().read().get(),
+        attr,
+        is_nonblocking,
+        &ctx.posix_thread.credentials(),
+    )?;
+
+    // Like Linux, the descriptors of message queues are always closed on exec.
+    let fd = current
+        .file_table()
+        .lock()
+        .insert(mqueue_file, FdFlags::CLOEXEC);
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+pub fn sys_mq_unlink(name_addr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    let name = ctx
+        .get_user_space()
+        .read_cstring(name_addr, MAX_FILENAME_LEN)?;
+    debug!("name = {:?}", name);
+
+    ctx.process.nsproxy().ipc_ns().mqueue_fs().unlink_mqueue(
+        name.to_string_lossy().as_ref(),
+        &ctx.posix_thread.credentials(),
+    )?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_mq_timedsend(
+    mqdes: FileDesc,
+    msg_ptr: Vaddr,
+    msg_len: usize,
+    msg_prio: u32,
+    abs_timeout_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "mqdes = {}, msg_ptr = {:#x}, msg_len = {}, msg_prio = {}, abs_timeout_addr = {:#x}",
+        mqdes, msg_ptr, msg_len, msg_prio, abs_timeout_addr
+    );
+
+    if msg_prio >= MQ_PRIO_MAX {
+        return_errno_with_message!(Errno::EINVAL, "the message priority is too large");
+    }
+    let timer_builder = read_timer_builder(abs_timeout_addr, ctx)?;
+
+    let file = get_mqueue_file(mqdes, ctx)?;
+    let mqueue_file = file.downcast_ref::<MqueueFile>().unwrap();
+    if !mqueue_file.access_mode().is_writable() {
+        return_errno_with_message!(Errno::EBADF, "the queue is not opened for writing");
+    }
+
+    let queue = mqueue_file.queue();
+    if msg_len > queue.msg_size() {
+        return_errno_with_message!(Errno::EMSGSIZE, "the message is too long");
+    }
+    let mut msg = vec![0u8; msg_len];
+    ctx.get_user_space()
+        .read_bytes(msg_ptr, &mut VmWriter::from(msg.as_mut_slice()))?;
+
+    queue.send(
+        msg,
+        msg_prio,
+        mqueue_file.is_nonblocking(),
+        timer_builder.as_ref(),
+    )?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_mq_timedreceive(
+    mqdes: FileDesc,
+    msg_ptr: Vaddr,
+    msg_len: usize,
+    msg_prio_addr: Vaddr,
+    abs_timeout_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "mqdes = {}, msg_ptr = {:#x}, msg_len = {}, msg_prio_addr = {:#x}, abs_timeout_addr = {:#x}",
+        mqdes, msg_ptr, msg_len, msg_prio_addr, abs_timeout_addr
+    );
+
+    let timer_builder = read_timer_builder(abs_timeout_addr, ctx)?;
+
+    let file = get_mqueue_file(mqdes, ctx)?;
+    let mqueue_file = file.downcast_ref::<MqueueFile>().unwrap();
+    if !mqueue_file.access_mode().is_readable() {
+        return_errno_with_message!(Errno::EBADF, "the queue is not opened for reading");
+    }
+
+    let queue = mqueue_file.queue();
+    if msg_len < queue.msg_size() {
+        return_errno_with_message!(
+            Errno::EMSGSIZE,
+            "the buffer is smaller than the message size"
+        );
+    }
+
+    let (msg, msg_prio) = queue.receive(mqueue_file.is_nonblocking(), timer_builder.as_ref())?;
+
+    let user_space = ctx.get_user_space();
+    user_space.write_bytes(msg_ptr, &mut VmReader::from(msg.as_slice()))?;
+    if msg_prio_addr != 0 {
+        user_space.write_val(msg_prio_addr, &msg_prio)?;
+    }
+    Ok(SyscallReturn::Return(msg.len() as _))
+}
+
+pub fn sys_mq_notify(mqdes: FileDesc, sevp_addr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("mqdes = {}, sevp_addr = {:#x}", mqdes, sevp_addr);
+
+    let file = get_mqueue_file(mqdes, ctx)?;
+    let queue = file.downcast_ref::<MqueueFile>().unwrap().queue();
+
+    // Remove the notification registered by the current process
+    if sevp_addr == 0 {
+        queue.unregister_notification(ctx.process.pid());
+        return Ok(SyscallReturn::Return(0));
+    }
+
+    let sig_event = ctx.get_user_space().read_val::<sigevent_t>(sevp_addr)?;
+    let signal = match SigNotify::try_from(sig_event.sigev_notify)? {
+        SigNotify::SIGEV_NONE => None,
+        SigNotify::SIGEV_SIGNAL => {
+            let signo = sig_event.sigev_signo;
+            Some(KernelSignal::new(SigNum::try_from(signo as u8)?))
+        }
+        // TODO: Support `SIGEV_THREAD`, which is implemented by the C library with netlink
+        // sockets.
+        SigNotify::SIGEV_THREAD | SigNotify::SIGEV_THREAD_ID => {
+            return_errno_with_message!(Errno::EINVAL, "the notification method is not supported");
+        }
+    };
+    queue.register_notification(&current!(), signal)?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_mq_getsetattr(
+    mqdes: FileDesc,
+    new_attr_addr: Vaddr,
+    old_attr_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "mqdes = {}, new_attr_addr = {:#x}, old_attr_addr = {:#x}",
+        mqdes, new_attr_addr, old_attr_addr
+    );
+
+    let user_space = ctx.get_user_space();
+    let new_attr = if new_attr_addr != 0 {
+        let new_attr = user_space.read_val::<MqAttr>(new_attr_addr)?;
+        if new_attr.mq_flags & !(StatusFlags::O_NONBLOCK.bits() as i64) != 0 {
+            return_errno_with_message!(Errno::EINVAL, "only O_NONBLOCK can be set");
+        }
+        Some(new_attr)
+    } else {
+        None
+    };
+
+    let file = get_mqueue_file(mqdes, ctx)?;
+    let mqueue_file = file.downcast_ref::<MqueueFile>().unwrap();
+
+    if old_attr_addr != 0 {
+        user_space.write_val(old_attr_addr, &mqueue_file.attr())?;
+    }
+
+    if let Some(new_attr) = new_attr {
+        mqueue_file.set_status_flags(StatusFlags::from_bits_truncate(new_attr.mq_flags as u32))?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Gets the file of the `mqdes`, which must be a message queue.
+fn get_mqueue_file(mqdes: FileDesc, ctx: &Context) -> Result<Arc<dyn FileLike>> {
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(mqdes)?.clone()
+    };
+    if file.downcast_ref::<MqueueFile>().is_none() {
+        return_errno_with_message!(Errno::EBADF, "the file is not a message queue");
+    }
+    Ok(file)
+}
+
+/// Reads the absolute timeout against `CLOCK_REALTIME`, if any.
+fn read_timer_builder(
+    abs_timeout_addr: Vaddr,
+    ctx: &Context,
+) -> Result<Option<TimerBuilder<'static>>> {
+    if abs_timeout_addr == 0 {
+        return Ok(None);
+    }
+
+    let abs_timeout = ctx
+        .get_user_space()
+        .read_val::<timespec_t>(abs_timeout_addr)?;
+    let abs_timeout = Duration::try_from(abs_timeout)?;
+    Ok(Some(TimerBuilder::new_with_timer_manager(
+        Timeout::When(abs_timeout),
+        RealTimeClock::timer_manager(),
+    )))
+}
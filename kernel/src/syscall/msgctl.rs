@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{
+        key_t,
+        msg::{
+            msg_queue::{MessageQueue, MSGMAX, MSGMNB, MSGMNI},
+            MsgControlCmd,
+        },
+        IpcPerm,
+    },
+    prelude::*,
+    process::credentials::capabilities::CapSet,
+};
+
+pub fn sys_msgctl(msqid: key_t, cmd: i32, buf: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    let cmd = MsgControlCmd::try_from(cmd)?;
+    debug!(
+        "[sys_msgctl] msqid = {}, cmd = {:?}, buf = {:#x}",
+        msqid, cmd, buf
+    );
+
+    let nsproxy = ctx.process.nsproxy();
+    let msg_queue_table = nsproxy.ipc_ns().msg_queue_table();
+
+    match cmd {
+        MsgControlCmd::IPC_INFO | MsgControlCmd::MSG_INFO => {
+            let mut msg_info = MsgInfo {
+                msgpool: (MSGMNI * MSGMNB / 1024) as i32,
+                msgmap: MSGMNB as i32,
+                msgmax: MSGMAX as i32,
+                msgmnb: MSGMNB as i32,
+                msgmni: MSGMNI as i32,
+                msgssz: MsgInfo::MSGSSZ,
+                msgtql: MSGMNB as i32,
+                msgseg: MsgInfo::MSGSEG,
+                ..Default::default()
+            };
+            // `MSG_INFO` reports the resources consumed by the queues instead
+            if matches!(cmd, MsgControlCmd::MSG_INFO) {
+                let (nqueues, nmsgs, nbytes) = msg_queue_table.msg_queue_stats();
+                msg_info.msgpool = nqueues as i32;
+                msg_info.msgmap = nmsgs as i32;
+                msg_info.msgtql = nbytes as i32;
+            }
+            ctx.get_user_space().write_val(buf, &msg_info)?;
+            return Ok(SyscallReturn::Return(
+                msg_queue_table.max_msg_queue_id() as isize
+            ));
+        }
+        MsgControlCmd::IPC_STAT | MsgControlCmd::MSG_STAT => {
+            let msg_queue = msg_queue_table.get_msg_queue(msqid)?;
+            msg_queue
+                .permission()
+                .check_access(&ctx.posix_thread.credentials(), 0o4)?;
+            ctx.get_user_space()
+                .write_val(buf, &MsqidDs::from_msg_queue(&msg_queue))?;
+
+            // `MSG_STAT` returns the identifier of the queue
+            if matches!(cmd, MsgControlCmd::MSG_STAT) {
+                return Ok(SyscallReturn::Return(msqid as isize));
+            }
+        }
+        MsgControlCmd::IPC_SET => {
+            let msg_queue = msg_queue_table.get_msg_queue(msqid)?;
+            let msqid_ds = ctx.get_user_space().read_val::<MsqidDs>(buf)?;
+
+            let credentials = ctx.posix_thread.credentials();
+            let euid = credentials.euid();
+            let permission = msg_queue.permission();
+            let can_set = (euid == permission.uid()) || (euid == permission.cuid());
+            if !can_set {
+                return_errno!(Errno::EPERM);
+            }
+
+            let qbytes = msqid_ds.msg_qbytes as usize;
+            if qbytes > MSGMNB
+                && !credentials
+                    .effective_capset()
+                    .contains(CapSet::SYS_RESOURCE)
+            {
+                return_errno_with_message!(Errno::EPERM, "cannot raise the queue size limit");
+            }
+            // TODO: Support updating the owner and the mode of the queue.
+            msg_queue.set_qbytes(qbytes);
+        }
+        MsgControlCmd::IPC_RMID => {
+            let msg_queue = msg_queue_table.get_msg_queue(msqid)?;
+
+            let euid = ctx.posix_thread.credentials().euid();
+            let permission = msg_queue.permission();
+            let can_removed = (euid == permission.uid()) || (euid == permission.cuid());
+            if !can_removed {
+                return_errno!(Errno::EPERM);
+            }
+
+            msg_queue_table.remove_msg_queue(msqid)?;
+        }
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// The `msqid64_ds` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct MsqidDs {
+    msg_perm: IpcPerm,
+    msg_stime: i64,
+    msg_rtime: i64,
+    msg_ctime: i64,
+    msg_cbytes: u64,
+    msg_qnum: u64,
+    msg_qbytes: u64,
+    msg_lspid: i32,
+    msg_lrpid: i32,
+    __unused4: u64,
+    __unused5: u64,
+}
+
+impl MsqidDs {
+    fn from_msg_queue(msg_queue: &MessageQueue) -> Self {
+        Self {
+            msg_perm: IpcPerm::from(msg_queue.permission()),
+            msg_stime: msg_queue.stime().as_secs() as i64,
+            msg_rtime: msg_queue.rtime().as_secs() as i64,
+            msg_ctime: msg_queue.ctime().as_secs() as i64,
+            msg_cbytes: msg_queue.cbytes() as u64,
+            msg_qnum: msg_queue.qnum() as u64,
+            msg_qbytes: msg_queue.qbytes() as u64,
+            msg_lspid: msg_queue.lspid() as i32,
+            msg_lrpid: msg_queue.lrpid() as i32,
+            ..Default::default()
+        }
+    }
+}
+
+/// The `msginfo` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct MsgInfo {
+    msgpool: i32,
+    msgmap: i32,
+    msgmax: i32,
+    msgmnb: i32,
+    msgmni: i32,
+    msgssz: i32,
+    msgtql: i32,
+    msgseg: u16,
+    __pad: u16,
+}
+
+impl MsgInfo {
+    /// The size of a message segment.
+    const MSGSSZ: i32 = 16;
+    /// The maximum number of message segments.
+    const MSGSEG: u16 = 0xffff;
+}
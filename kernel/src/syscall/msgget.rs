@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{key_t, msg::msg_queue::IPC_PRIVATE, IpcFlags},
+    prelude::*,
+};
+
+pub fn sys_msgget(key: key_t, msgflg: i32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = IpcFlags::from_bits_truncate(msgflg as u32);
+    let mode: u16 = (msgflg as u32 & 0x1FF) as u16;
+    let credentials = ctx.posix_thread.credentials();
+    let nsproxy = ctx.process.nsproxy();
+    let msg_queue_table = nsproxy.ipc_ns().msg_queue_table();
+
+    debug!("[sys_msgget] key = {}, flags = {:?}", key, msgflg);
+
+    // Create a new queue directly
+    if key == IPC_PRIVATE {
+        return Ok(SyscallReturn::Return(
+            msg_queue_table.create_msg_queue(key, mode, credentials)? as isize,
+        ));
+    }
+
+    // Get a queue, and create if necessary
+    let Some(msg_queue) = msg_queue_table.find_msg_queue_by_key(key) else {
+        if !flags.contains(IpcFlags::IPC_CREAT) {
+            return_errno_with_message!(Errno::ENOENT, "no message queue exists for the key");
+        }
+        return Ok(SyscallReturn::Return(
+            msg_queue_table.create_msg_queue(key, mode, credentials)? as isize,
+        ));
+    };
+
+    if flags.contains(IpcFlags::IPC_CREAT | IpcFlags::IPC_EXCL) {
+        return_errno_with_message!(
+            Errno::EEXIST,
+            "the message queue for the key already exists"
+        );
+    }
+    let requested = (mode >> 6) | (mode >> 3) | mode;
+    msg_queue
+        .permission()
+        .check_access(&credentials, requested)?;
+
+    Ok(SyscallReturn::Return(msg_queue.id() as isize))
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{key_t, msg::MsgFlags, IpcFlags},
+    prelude::*,
+};
+
+pub fn sys_msgrcv(
+    msqid: key_t,
+    msgp: Vaddr,
+    msgsz: usize,
+    msgtyp: i64,
+    msgflg: i32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let ipc_flags = IpcFlags::from_bits_truncate(msgflg as u32);
+    let msg_flags = MsgFlags::from_bits_truncate(msgflg as u32);
+    debug!(
+        "[sys_msgrcv] msqid = {}, msgp = {:#x}, msgsz = {}, msgtyp = {}, flags = {:?} {:?}",
+        msqid, msgp, msgsz, msgtyp, ipc_flags, msg_flags
+    );
+
+    if msqid < 0 || (msgsz as isize) < 0 {
+        return_errno_with_message!(Errno::EINVAL, "the queue identifier or size is invalid");
+    }
+    // `MSG_COPY` is only allowed in the non-blocking mode without `MSG_EXCEPT`
+    if msg_flags.contains(MsgFlags::MSG_COPY)
+        && (!ipc_flags.contains(IpcFlags::IPC_NOWAIT) || msg_flags.contains(MsgFlags::MSG_EXCEPT))
+    {
+        return_errno_with_message!(Errno::EINVAL, "invalid flags with MSG_COPY");
+    }
+
+    let msg_queue = ctx
+        .process
+        .nsproxy()
+        .ipc_ns()
+        .msg_queue_table()
+        .get_msg_queue(msqid)?;
+    msg_queue
+        .permission()
+        .check_access(&ctx.posix_thread.credentials(), 0o4)?;
+
+    let message = msg_queue.receive(
+        msgtyp,
+        msgsz,
+        msg_flags,
+        ipc_flags.contains(IpcFlags::IPC_NOWAIT),
+        ctx.process.pid(),
+    )?;
+
+    // The message buffer starts with the message type, followed by the message data
+    let user_space = ctx.get_user_space();
+    user_space.write_val(msgp, &message.mtype())?;
+    user_space.write_bytes(
+        msgp + core::mem::size_of::<i64>(),
+        &mut VmReader::from(message.data()),
+    )?;
+
+    Ok(SyscallReturn::Return(message.data().len() as _))
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{
+        key_t,
+        msg::msg_queue::{Message, MSGMAX},
+        IpcFlags,
+    },
+    prelude::*,
+};
+
+pub fn sys_msgsnd(
+    msqid: key_t,
+    msgp: Vaddr,
+    msgsz: usize,
+    msgflg: i32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let flags = IpcFlags::from_bits_truncate(msgflg as u32);
+    debug!(
+        "[sys_msgsnd] msqid = {}, msgp = {:#x}, msgsz = {}, flags = {:?}",
+        msqid, msgp, msgsz, flags
+    );
+
+    if msqid < 0 {
+        return_errno_with_message!(Errno::EINVAL, "the queue identifier is invalid");
+    }
+    if msgsz > MSGMAX {
+        return_errno_with_message!(Errno::EINVAL, "the message is too long");
+    }
+
+    let msg_queue = ctx
+        .process
+        .nsproxy()
+        .ipc_ns()
+        .msg_queue_table()
+        .get_msg_queue(msqid)?;
+    msg_queue
+        .permission()
+        .check_access(&ctx.posix_thread.credentials(), 0o2)?;
+
+    // The message buffer starts with the message type, followed by the message data
+    let user_space = ctx.get_user_space();
+    let mtype = user_space.read_val::<i64>(msgp)?;
+    if mtype <= 0 {
+        return_errno_with_message!(Errno::EINVAL, "the message type must be positive");
+    }
+    let mut data = vec![0u8; msgsz];
+    user_space.read_bytes(
+        msgp + core::mem::size_of::<i64>(),
+        &mut VmWriter::from(data.as_mut_slice()),
+    )?;
+
+    msg_queue.send(
+        Message::new(mtype, data),
+        flags.contains(IpcFlags::IPC_NOWAIT),
+        ctx.process.pid(),
+    )?;
+
+    Ok(SyscallReturn::Return(0))
+}
@@ -31,8 +31,12 @@ pub fn sys_prctl(
             ctx.get_user_space().write_val(write_to_addr, &write_val)?;
         }
         PrctlCmd::PR_GET_DUMPABLE => {
-            // TODO: when coredump is supported, return the actual value
-            return Ok(SyscallReturn::Return(Dumpable::Disable as _));
+            let dumpable = if ctx.process.is_dumpable() {
+                Dumpable::User
+            } else {
+                Dumpable::Disable
+            };
+            return Ok(SyscallReturn::Return(dumpable as _));
         }
         PrctlCmd::PR_SET_DUMPABLE(dumpable) => {
             if dumpable != Dumpable::Disable && dumpable != Dumpable::User {
@@ -40,6 +44,7 @@ pub fn sys_prctl(
             }
 
             // TODO: implement coredump
+            ctx.process.set_dumpable(dumpable == Dumpable::User);
         }
         PrctlCmd::PR_GET_NAME(write_to_addr) => {
             let thread_name = ctx.posix_thread.thread_name().lock();
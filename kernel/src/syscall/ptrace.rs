@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    arch::ptrace::UserRegs,
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        ptrace::{
+            get_tracee, ptrace_attach, ptrace_detach, ptrace_traceme, PtraceOptions, ResumeMode,
+        },
+        signal::sig_num::SigNum,
+    },
+    thread::Tid,
+};
+
+pub fn sys_ptrace(
+    request: u32,
+    pid: Tid,
+    addr: Vaddr,
+    data: usize,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let request = PtraceRequest::try_from(request)
+        .map_err(|_| Error::with_message(Errno::EIO, "unsupported ptrace request"))?;
+    debug!(
+        "request = {:?}, pid = {}, addr = 0x{:x}, data = 0x{:x}",
+        request, pid, addr, data
+    );
+
+    match request {
+        PtraceRequest::PTRACE_TRACEME => {
+            ptrace_traceme(ctx)?;
+            return Ok(SyscallReturn::Return(0));
+        }
+        PtraceRequest::PTRACE_ATTACH => {
+            let tracee =
+                ctx.process.pid_ns().get_thread(pid).ok_or_else(|| {
+                    Error::with_message(Errno::ESRCH, "the thread does not exist")
+                })?;
+            ptrace_attach(tracee, ctx)?;
+            return Ok(SyscallReturn::Return(0));
+        }
+        _ => (),
+    }
+
+    let tracee = get_tracee(pid, ctx)?;
+    let tracee_thread = tracee.as_posix_thread().unwrap();
+    let ptrace = tracee_thread.ptrace();
+    let user_space = ctx.get_user_space();
+
+    match request {
+        PtraceRequest::PTRACE_PEEKTEXT | PtraceRequest::PTRACE_PEEKDATA => {
+            ptrace.check_stopped(ctx.process)?;
+            let mut buf = [0u8; core::mem::size_of::<usize>()];
+            tracee_thread
+                .process()
+                .root_vmar()
+                .read_foreign(addr, &mut buf)?;
+            user_space.write_val(data, &usize::from_ne_bytes(buf))?;
+        }
+        PtraceRequest::PTRACE_POKETEXT | PtraceRequest::PTRACE_POKEDATA => {
+            ptrace.check_stopped(ctx.process)?;
+            tracee_thread
+                .process()
+                .root_vmar()
+                .write_foreign(addr, &data.to_ne_bytes())?;
+        }
+        PtraceRequest::PTRACE_GETREGS => {
+            let regs = ptrace.regs(ctx.process)?;
+            user_space.write_val(data, &regs)?;
+        }
+        PtraceRequest::PTRACE_SETREGS => {
+            let regs = user_space.read_val::<UserRegs>(data)?;
+            ptrace.set_regs(ctx.process, &regs)?;
+        }
+        PtraceRequest::PTRACE_SETOPTIONS => {
+            let options = PtraceOptions::from_bits(data as u32)
+                .ok_or_else(|| Error::with_message(Errno::EINVAL, "unsupported ptrace options"))?;
+            ptrace.set_options(ctx.process, options)?;
+        }
+        PtraceRequest::PTRACE_CONT => {
+            ptrace.resume(ctx.process, ResumeMode::Continue, parse_signal(data)?)?;
+        }
+        PtraceRequest::PTRACE_SYSCALL => {
+            ptrace.resume(ctx.process, ResumeMode::Syscall, parse_signal(data)?)?;
+        }
+        PtraceRequest::PTRACE_SINGLESTEP => {
+            ptrace.resume(ctx.process, ResumeMode::SingleStep, parse_signal(data)?)?;
+        }
+        PtraceRequest::PTRACE_DETACH => {
+            ptrace_detach(&tracee, parse_signal(data)?, ctx)?;
+        }
+        PtraceRequest::PTRACE_TRACEME | PtraceRequest::PTRACE_ATTACH => unreachable!(),
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Parses the signal to deliver when the tracee is resumed, where zero means no signal.
+fn parse_signal(data: usize) -> Result<Option<SigNum>> {
+    if data == 0 {
+        return Ok(None);
+    }
+
+    u8::try_from(data)
+        .ok()
+        .and_then(|sig_num| SigNum::try_from(sig_num).ok())
+        .map(Some)
+        .ok_or_else(|| Error::with_message(Errno::EIO, "invalid signal number"))
+}
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+enum PtraceRequest {
+    PTRACE_TRACEME = 0,
+    PTRACE_PEEKTEXT = 1,
+    PTRACE_PEEKDATA = 2,
+    PTRACE_POKETEXT = 4,
+    PTRACE_POKEDATA = 5,
+    PTRACE_CONT = 7,
+    PTRACE_SINGLESTEP = 9,
+    PTRACE_GETREGS = 12,
+    PTRACE_SETREGS = 13,
+    PTRACE_ATTACH = 16,
+    PTRACE_DETACH = 17,
+    PTRACE_SYSCALL = 24,
+    PTRACE_SETOPTIONS = 0x4200,
+}
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::mem::offset_of;
+
 use super::SyscallReturn;
 use crate::{
     fs::file_table::FileDesc,
@@ -22,7 +24,7 @@ pub fn sys_recvmsg(
         sockfd, c_user_msghdr, flags
     );
 
-    let (total_bytes, message_header) = {
+    let (total_bytes, mut message_header) = {
         let socket = get_socket_from_fd(sockfd)?;
         let mut io_vec_writer = c_user_msghdr.copy_writer_array_from_user(ctx)?;
         socket.recvmsg(&mut io_vec_writer, flags)?
@@ -32,9 +34,35 @@ pub fn sys_recvmsg(
         c_user_msghdr.write_socket_addr_to_user(addr)?;
     }
 
-    if c_user_msghdr.msg_control != 0 {
-        warn!("receiving control message is not supported");
+    let mut msg_flags = SendRecvFlags::empty();
+    let mut control_len = 0;
+    if let Some(control_message) = message_header.take_control_message() {
+        let (written_len, is_truncated) = if c_user_msghdr.msg_control != 0 {
+            control_message.write_to_user(
+                ctx,
+                c_user_msghdr.msg_control,
+                c_user_msghdr.msg_controllen,
+                flags.contains(SendRecvFlags::MSG_CMSG_CLOEXEC),
+            )?
+        } else {
+            (0, true)
+        };
+
+        control_len = written_len;
+        if is_truncated {
+            msg_flags |= SendRecvFlags::MSG_CTRUNC;
+        }
     }
 
+    let user_space = ctx.get_user_space();
+    user_space.write_val(
+        user_msghdr_ptr + offset_of!(CUserMsgHdr, msg_controllen),
+        &control_len,
+    )?;
+    user_space.write_val(
+        user_msghdr_ptr + offset_of!(CUserMsgHdr, msg_flags),
+        &msg_flags.bits(),
+    )?;
+
     Ok(SyscallReturn::Return(total_bytes as _))
 }
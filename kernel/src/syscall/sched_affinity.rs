@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::mem;
+
+use ostd::{
+    cpu::{num_cpus, CpuSet, PinCurrentCpu},
+    task::disable_preempt,
+};
+
+use super::{
+    sched_param::{check_permission, get_thread},
+    SyscallReturn,
+};
+use crate::{prelude::*, thread::Thread};
+
+pub fn sys_sched_getaffinity(
+    tid: i32,
+    cpuset_size: usize,
+    cpu_set_ptr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "tid = {}, cpuset_size = {}, cpu_set_ptr = 0x{:x}",
+        tid, cpuset_size, cpu_set_ptr
+    );
+
+    let mask_size = cpu_mask_size();
+    if cpuset_size < mask_size || cpuset_size % mem::size_of::<usize>() != 0 {
+        return_errno_with_message!(Errno::EINVAL, "invalid cpuset size");
+    }
+
+    let thread = get_thread(tid, ctx)?;
+    let mut mask = vec![0u8; mask_size];
+    for cpu_id in thread.lock_cpu_affinity().iter() {
+        mask[cpu_id as usize / 8] |= 1 << (cpu_id % 8);
+    }
+
+    ctx.get_user_space()
+        .write_bytes(cpu_set_ptr, &mut VmReader::from(mask.as_slice()))?;
+
+    // Like Linux, return the number of bytes written, and the C library
+    // will zero the rest of the buffer.
+    Ok(SyscallReturn::Return(mask_size as _))
+}
+
+pub fn sys_sched_setaffinity(
+    tid: i32,
+    cpuset_size: usize,
+    cpu_set_ptr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "tid = {}, cpuset_size = {}, cpu_set_ptr = 0x{:x}",
+        tid, cpuset_size, cpu_set_ptr
+    );
+
+    // The bits beyond the CPUs in the system are ignored.
+    let mut mask = vec![0u8; cpu_mask_size()];
+    let len = cpuset_size.min(mask.len());
+    ctx.get_user_space()
+        .read_bytes(cpu_set_ptr, &mut VmWriter::from(&mut mask[..len]))?;
+
+    let mut cpu_set = CpuSet::new_empty();
+    for cpu_id in 0..num_cpus() {
+        if mask[cpu_id as usize / 8] & (1 << (cpu_id % 8)) != 0 {
+            cpu_set.add(cpu_id);
+        }
+    }
+    if cpu_set.count() == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the CPU set contains no available CPUs");
+    }
+
+    let thread = get_thread(tid, ctx)?;
+    check_permission(&thread, ctx)?;
+
+    let is_current_cpu_allowed = cpu_set.contains(disable_preempt().current_cpu());
+    thread.set_cpu_affinity(cpu_set);
+    // A running thread is only preempted at the next tick of its CPU to be migrated.
+    // The current thread yields instead, so that it is migrated before the system call returns.
+    if core::ptr::eq(thread.as_ref(), ctx.thread) && !is_current_cpu_allowed {
+        Thread::yield_now();
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Returns the size of the CPU mask in bytes, which covers all the CPUs
+/// in the system and is rounded up to the size of `usize`.
+fn cpu_mask_size() -> usize {
+    const BITS_PER_WORD: usize = 8 * mem::size_of::<usize>();
+
+    (num_cpus() as usize).div_ceil(BITS_PER_WORD) * mem::size_of::<usize>()
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::mem;
+
+use super::{
+    sched_param::{
+        clamp_nice, get_thread, nice_of, parse_policy, real_time_priority_of, set_sched_policy,
+    },
+    SyscallReturn,
+};
+use crate::prelude::*;
+
+/// The size of the first published version of `struct sched_attr`.
+const SCHED_ATTR_SIZE_VER0: usize = 48;
+
+pub fn sys_sched_setattr(
+    tid: i32,
+    attr_addr: Vaddr,
+    flags: u32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "tid = {}, attr_addr = 0x{:x}, flags = {}",
+        tid, attr_addr, flags
+    );
+    if attr_addr == 0 || tid < 0 || flags != 0 {
+        return_errno_with_message!(Errno::EINVAL, "invalid arguments");
+    }
+
+    let attr = read_attr(attr_addr, ctx)?;
+    debug!("attr = {:?}", attr);
+
+    let sched_flags = SchedFlags::from_bits(attr.sched_flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid scheduling flags"))?;
+    if sched_flags.intersects(SchedFlags::UTIL_CLAMP_MIN | SchedFlags::UTIL_CLAMP_MAX) {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "utilization clamping is not supported");
+    }
+
+    let thread = get_thread(tid, ctx)?;
+    let policy = if sched_flags.contains(SchedFlags::KEEP_POLICY) {
+        thread.sched_policy()
+    } else {
+        let policy = i32::try_from(attr.sched_policy).unwrap_or(-1);
+        parse_policy(policy)?
+    };
+    let (rt_priority, nice) = if sched_flags.contains(SchedFlags::KEEP_PARAMS) {
+        (real_time_priority_of(&thread) as u32, nice_of(&thread))
+    } else {
+        (attr.sched_priority, clamp_nice(attr.sched_nice))
+    };
+    set_sched_policy(&thread, policy, rt_priority, Some(nice), ctx)?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getattr(
+    tid: i32,
+    attr_addr: Vaddr,
+    size: u32,
+    flags: u32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "tid = {}, attr_addr = 0x{:x}, size = {}, flags = {}",
+        tid, attr_addr, size, flags
+    );
+    let size = size as usize;
+    if attr_addr == 0
+        || tid < 0
+        || flags != 0
+        || !(SCHED_ATTR_SIZE_VER0..=PAGE_SIZE).contains(&size)
+    {
+        return_errno_with_message!(Errno::EINVAL, "invalid arguments");
+    }
+
+    let thread = get_thread(tid, ctx)?;
+    let len = size.min(mem::size_of::<SchedAttr>());
+    let mut attr = SchedAttr::new_zeroed();
+    attr.size = len as u32;
+    attr.sched_policy = u8::from(thread.sched_policy()) as u32;
+    attr.sched_nice = i8::from(nice_of(&thread)) as i32;
+    attr.sched_priority = real_time_priority_of(&thread) as u32;
+
+    // Only write the fields known by the user.
+    ctx.get_user_space()
+        .write_bytes(attr_addr, &mut VmReader::from(&attr.as_bytes()[..len]))?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Reads the `struct sched_attr` at `attr_addr`, whose size is specified by its `size` field.
+fn read_attr(attr_addr: Vaddr, ctx: &Context) -> Result<SchedAttr> {
+    let user_space = ctx.get_user_space();
+
+    let size = match user_space.read_val::<u32>(attr_addr)? as usize {
+        0 => SCHED_ATTR_SIZE_VER0,
+        size if (SCHED_ATTR_SIZE_VER0..=PAGE_SIZE).contains(&size) => size,
+        _ => {
+            // Like Linux, tell the user the size of the structure known by the kernel.
+            user_space.write_val(attr_addr, &(mem::size_of::<SchedAttr>() as u32))?;
+            return_errno_with_message!(Errno::E2BIG, "invalid size of the attributes");
+        }
+    };
+
+    // The fields unknown by the user are zero, while the ones unknown by the kernel are ignored.
+    let len = size.min(mem::size_of::<SchedAttr>());
+    let mut attr = SchedAttr::new_zeroed();
+    user_space.read_bytes(
+        attr_addr,
+        &mut VmWriter::from(&mut attr.as_bytes_mut()[..len]),
+    )?;
+
+    Ok(attr)
+}
+
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+struct SchedAttr {
+    size: u32,
+    sched_policy: u32,
+    sched_flags: u64,
+    sched_nice: i32,
+    sched_priority: u32,
+    // The attributes of `SCHED_DEADLINE`, which is not supported.
+    sched_runtime: u64,
+    sched_deadline: u64,
+    sched_period: u64,
+    // The attributes of utilization clamping, which is not supported.
+    sched_util_min: u32,
+    sched_util_max: u32,
+}
+
+bitflags! {
+    struct SchedFlags: u64 {
+        const RESET_ON_FORK = 0x01;
+        const RECLAIM = 0x02;
+        const DL_OVERRUN = 0x04;
+        const KEEP_POLICY = 0x08;
+        const KEEP_PARAMS = 0x10;
+        const UTIL_CLAMP_MIN = 0x20;
+        const UTIL_CLAMP_MAX = 0x40;
+    }
+}
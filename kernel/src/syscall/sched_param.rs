@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::Ordering;
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::PosixThreadExt},
+    sched::{
+        policy::{RealTimePriorityRange, SchedPolicy},
+        priority::{Nice, NiceRange, Priority},
+    },
+    thread::{Thread, Tid},
+};
+
+/// The flag of the policy that resets the scheduling attributes of the children on fork.
+const SCHED_RESET_ON_FORK: i32 = 0x4000_0000;
+
+pub fn sys_sched_setscheduler(
+    tid: i32,
+    policy: i32,
+    param_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    // TODO: Support resetting the scheduling attributes of the children on fork.
+    let policy = parse_policy(policy & !SCHED_RESET_ON_FORK)?;
+    let param = read_param(param_addr, ctx)?;
+    debug!("tid = {}, policy = {:?}, param = {:?}", tid, policy, param);
+
+    let thread = get_thread(tid, ctx)?;
+    set_sched_policy(&thread, policy, param.sched_priority as u32, None, ctx)?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getscheduler(tid: i32, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("tid = {}", tid);
+
+    let thread = get_thread(tid, ctx)?;
+    let policy = thread.sched_policy();
+
+    Ok(SyscallReturn::Return(u8::from(policy) as _))
+}
+
+pub fn sys_sched_setparam(tid: i32, param_addr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    let param = read_param(param_addr, ctx)?;
+    debug!("tid = {}, param = {:?}", tid, param);
+
+    let thread = get_thread(tid, ctx)?;
+    set_sched_policy(
+        &thread,
+        thread.sched_policy(),
+        param.sched_priority as u32,
+        None,
+        ctx,
+    )?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_getparam(tid: i32, param_addr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("tid = {}, param_addr = 0x{:x}", tid, param_addr);
+    if param_addr == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the parameter pointer is null");
+    }
+
+    let thread = get_thread(tid, ctx)?;
+    let param = SchedParam {
+        sched_priority: real_time_priority_of(&thread) as i32,
+    };
+    ctx.get_user_space().write_val(param_addr, &param)?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_sched_get_priority_max(policy: i32, _ctx: &Context) -> Result<SyscallReturn> {
+    let policy = parse_policy(policy)?;
+    debug!("policy = {:?}", policy);
+
+    let (_, max) = policy.real_time_priority_range();
+    Ok(SyscallReturn::Return(max as _))
+}
+
+pub fn sys_sched_get_priority_min(policy: i32, _ctx: &Context) -> Result<SyscallReturn> {
+    let policy = parse_policy(policy)?;
+    debug!("policy = {:?}", policy);
+
+    let (min, _) = policy.real_time_priority_range();
+    Ok(SyscallReturn::Return(min as _))
+}
+
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+struct SchedParam {
+    sched_priority: i32,
+}
+
+fn read_param(param_addr: Vaddr, ctx: &Context) -> Result<SchedParam> {
+    if param_addr == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the parameter pointer is null");
+    }
+    ctx.get_user_space().read_val(param_addr)
+}
+
+pub(super) fn parse_policy(policy: i32) -> Result<SchedPolicy> {
+    u8::try_from(policy)
+        .ok()
+        .and_then(|policy| SchedPolicy::try_from(policy).ok())
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the scheduling policy is invalid"))
+}
+
+/// Gets the thread specified by `tid`, which is the current thread if `tid` is zero.
+pub(super) fn get_thread(tid: i32, ctx: &Context) -> Result<Arc<Thread>> {
+    let tid = match tid {
+        0 => return Ok(current_thread!()),
+        tid if tid > 0 => tid as Tid,
+        _ => return_errno_with_message!(Errno::EINVAL, "the thread ID is negative"),
+    };
+    ctx.process
+        .pid_ns()
+        .get_thread(tid)
+        .ok_or_else(|| Error::with_message(Errno::ESRCH, "the thread does not exist"))
+}
+
+/// Checks whether the current thread can change the scheduling attributes of `thread`.
+///
+/// Like Linux, this is allowed if the effective user ID of the current thread matches
+/// the real or effective user ID of the target thread, or if the current thread has
+/// the `CAP_SYS_NICE` capability.
+pub(super) fn check_permission(thread: &Thread, ctx: &Context) -> Result<()> {
+    if has_cap_sys_nice(ctx) {
+        return Ok(());
+    }
+
+    let euid = ctx.posix_thread.credentials().euid();
+    let Some(target) = thread.as_posix_thread() else {
+        return_errno_with_message!(Errno::EPERM, "the thread is not a POSIX thread");
+    };
+    let target_credentials = target.credentials();
+    if euid != target_credentials.euid() && euid != target_credentials.ruid() {
+        return_errno_with_message!(Errno::EPERM, "the thread belongs to a different user");
+    }
+
+    Ok(())
+}
+
+fn has_cap_sys_nice(ctx: &Context) -> bool {
+    ctx.posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::SYS_NICE)
+}
+
+/// Returns the real-time priority of `thread`, or zero if it is not a real-time thread.
+pub(super) fn real_time_priority_of(thread: &Thread) -> u8 {
+    if !thread.sched_policy().is_real_time() {
+        return 0;
+    }
+    thread
+        .priority()
+        .real_time_priority()
+        .map_or(0, |rt_priority| rt_priority.get())
+}
+
+/// Returns the nice value of `thread`.
+///
+/// The nice value of a real-time thread is the one of its process.
+pub(super) fn nice_of(thread: &Thread) -> Nice {
+    if !thread.sched_policy().is_real_time() {
+        let nice = thread.priority().range().get() as i16 - 120;
+        if let Ok(nice) = Nice::try_from(nice as i8) {
+            return nice;
+        }
+    }
+    process_nice_of(thread)
+}
+
+fn process_nice_of(thread: &Thread) -> Nice {
+    thread
+        .as_posix_thread()
+        .and_then(|posix_thread| posix_thread.weak_process().upgrade())
+        .map_or_else(Nice::default, |process| {
+            process.nice().load(Ordering::Relaxed)
+        })
+}
+
+/// Sets the scheduling policy of `thread`, along with the real-time priority
+/// and, for the non-real-time policies, the nice value.
+///
+/// If `nice` is `None`, the nice value of the process is used.
+pub(super) fn set_sched_policy(
+    thread: &Thread,
+    policy: SchedPolicy,
+    rt_priority: u32,
+    nice: Option<Nice>,
+    ctx: &Context,
+) -> Result<()> {
+    let (min, max) = policy.real_time_priority_range();
+    if rt_priority < min as u32 || rt_priority > max as u32 {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "the real-time priority is invalid for the policy"
+        );
+    }
+
+    check_permission(thread, ctx)?;
+
+    let priority = if policy.is_real_time() {
+        // TODO: Allow unprivileged threads to use the real-time policies
+        // within the limit of `RLIMIT_RTPRIO`.
+        if !has_cap_sys_nice(ctx) {
+            return_errno_with_message!(Errno::EPERM, "the real-time policies require CAP_SYS_NICE");
+        }
+        Priority::from(RealTimePriorityRange::new(rt_priority as u8))
+    } else {
+        let nice = match nice {
+            Some(nice) => {
+                if nice < nice_of(thread) && !has_cap_sys_nice(ctx) {
+                    return_errno_with_message!(
+                        Errno::EPERM,
+                        "lowering the nice value requires CAP_SYS_NICE"
+                    );
+                }
+                nice
+            }
+            None => process_nice_of(thread),
+        };
+        Priority::from(nice)
+    };
+
+    thread.set_sched_policy(policy, priority);
+    Ok(())
+}
+
+/// Clamps the raw nice value into the range of [`Nice`].
+pub(super) fn clamp_nice(nice: i32) -> Nice {
+    Nice::new(NiceRange::new(
+        nice.clamp(NiceRange::MIN as i32, NiceRange::MAX as i32) as i8,
+    ))
+}
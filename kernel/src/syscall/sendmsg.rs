@@ -3,7 +3,7 @@
 use super::SyscallReturn;
 use crate::{
     fs::file_table::FileDesc,
-    net::socket::{MessageHeader, SendRecvFlags},
+    net::socket::{ControlMessage, MessageHeader, SendRecvFlags},
     prelude::*,
     util::net::{get_socket_from_fd, CUserMsgHdr},
 };
@@ -28,13 +28,11 @@ pub fn sys_sendmsg(
         let addr = c_user_msghdr.read_socket_addr_from_user()?;
         let io_vec_reader = c_user_msghdr.copy_reader_array_from_user(ctx)?;
 
-        let control_message = {
-            if c_user_msghdr.msg_control != 0 {
-                // TODO: support sending control message
-                warn!("control message is not supported now");
-            }
-            None
-        };
+        let control_message = ControlMessage::read_from_user(
+            ctx,
+            c_user_msghdr.msg_control,
+            c_user_msghdr.msg_controllen,
+        )?;
 
         (io_vec_reader, MessageHeader::new(addr, control_message))
     };
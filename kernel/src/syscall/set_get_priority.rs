@@ -7,6 +7,7 @@ use crate::{
     prelude::*,
     process::{posix_thread::PosixThreadExt, process_table, Pgid, Pid, Process, Uid},
     sched::priority::{Nice, NiceRange},
+    thread::Thread,
 };
 
 pub fn sys_set_priority(which: i32, who: u32, prio: i32, ctx: &Context) -> Result<SyscallReturn> {
@@ -21,9 +22,18 @@ pub fn sys_set_priority(which: i32, who: u32, prio: i32, ctx: &Context) -> Resul
         prio_target, new_nice
     );
 
-    let processes = get_processes(prio_target)?;
+    let processes = get_processes(prio_target, ctx)?;
     for process in processes.iter() {
         process.nice().store(new_nice, Ordering::Relaxed);
+        // Update the priorities of the threads so that the scheduler
+        // gives them the CPU share of the new nice value.
+        for task in process.tasks().lock().iter() {
+            let thread = Thread::borrow_from_task(task);
+            // The nice value does not affect the real-time threads.
+            if !thread.sched_policy().is_real_time() {
+                thread.set_priority(new_nice.into());
+            }
+        }
     }
 
     Ok(SyscallReturn::Return(0))
@@ -33,7 +43,7 @@ pub fn sys_get_priority(which: i32, who: u32, ctx: &Context) -> Result<SyscallRe
     let prio_target = PriorityTarget::new(which, who, ctx)?;
     debug!("get_priority prio_target: {:?}", prio_target);
 
-    let processes = get_processes(prio_target)?;
+    let processes = get_processes(prio_target, ctx)?;
     let highest_prio = {
         let mut nice = NiceRange::MAX;
         for process in processes.iter() {
@@ -52,7 +62,7 @@ pub fn sys_get_priority(which: i32, who: u32, ctx: &Context) -> Result<SyscallRe
     Ok(SyscallReturn::Return(highest_prio as _))
 }
 
-fn get_processes(prio_target: PriorityTarget) -> Result<Vec<Arc<Process>>> {
+fn get_processes(prio_target: PriorityTarget, ctx: &Context) -> Result<Vec<Arc<Process>>> {
     Ok(match prio_target {
         PriorityTarget::Process(pid) => {
             let process = process_table::get_process(pid).ok_or(Error::new(Errno::ESRCH))?;
@@ -69,8 +79,12 @@ fn get_processes(prio_target: PriorityTarget) -> Result<Vec<Arc<Process>>> {
         }
         PriorityTarget::User(uid) => {
             // Get the processes that are running under the specified user
-            let processes: Vec<Arc<Process>> = process_table::process_table()
-                .iter()
+            let processes: Vec<Arc<Process>> = ctx
+                .process
+                .pid_ns()
+                .processes()
+                .into_iter()
+                .map(|(_, process)| process)
                 .filter(|process| {
                     let Some(main_thread) = process.main_thread() else {
                         return false;
@@ -80,7 +94,6 @@ fn get_processes(prio_target: PriorityTarget) -> Result<Vec<Arc<Process>>> {
                     };
                     uid == posix_thread.credentials().ruid()
                 })
-                .cloned()
                 .collect();
             if processes.is_empty() {
                 return_errno!(Errno::ESRCH);
@@ -90,6 +103,7 @@ fn get_processes(prio_target: PriorityTarget) -> Result<Vec<Arc<Process>>> {
     })
 }
 
+/// The target of a priority operation, where the PIDs and the PGIDs are the global ones.
 #[derive(Debug)]
 enum PriorityTarget {
     Process(Pid),
@@ -106,7 +120,7 @@ impl PriorityTarget {
                 let pid = if who == 0 {
                     ctx.process.pid()
                 } else {
-                    who as Pid
+                    global_pid_of(who as Pid, ctx)?
                 };
                 Self::Process(pid)
             }
@@ -114,7 +128,7 @@ impl PriorityTarget {
                 let pgid = if who == 0 {
                     ctx.process.pgid()
                 } else {
-                    who as Pgid
+                    global_pid_of(who as Pgid, ctx)?
                 };
                 Self::ProcessGroup(pgid)
             }
@@ -138,3 +152,11 @@ enum Which {
     PRIO_PGRP = 1,
     PRIO_USER = 2,
 }
+
+/// Translates a PID or a PGID in the PID namespace of the current process to the global one.
+fn global_pid_of(pid: Pid, ctx: &Context) -> Result<Pid> {
+    ctx.process
+        .pid_ns()
+        .global_pid_of(pid)
+        .ok_or_else(|| Error::with_message(Errno::ESRCH, "the process does not exist"))
+}
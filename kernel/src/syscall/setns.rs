@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    fs::{file_table::FileDesc, inode_handle::InodeHandle, procfs::get_ns_of_inode},
+    prelude::*,
+    process::namespace::{check_ns_permission, NsRef, NsType},
+};
+
+pub fn sys_setns(fd: FileDesc, nstype: i32, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("fd = {}, nstype = {:#x}", fd, nstype);
+
+    let ns = {
+        let file_table = ctx.process.file_table().lock();
+        let file = file_table.get_file(fd)?;
+        file.downcast_ref::<InodeHandle>()
+            .and_then(|inode_handle| get_ns_of_inode(inode_handle.dentry().inode()))
+            .ok_or_else(|| {
+                Error::with_message(Errno::EINVAL, "the file does not refer to a namespace")
+            })?
+    };
+    let ns_type = ns.ns_type();
+    // A zero `nstype` allows any type of namespace
+    if nstype != 0 && nstype as u32 != ns_type.clone_flag().bits() {
+        return_errno_with_message!(Errno::EINVAL, "the namespace type does not match");
+    }
+    check_ns_permission(ctx)?;
+
+    let process = ctx.process;
+
+    // A process can only move its future children into its own PID namespace or
+    // a descendant one.
+    if let NsRef::Pid(pid_ns) = &ns
+        && !process.pid_ns().is_ancestor_of(pid_ns)
+    {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "the PID namespace is not a descendant of the current one"
+        );
+    }
+
+    let new_nsproxy = process.nsproxy().copy_with_ns(&ns);
+    if ns_type == NsType::Mnt {
+        new_nsproxy.mnt_ns().reset_fs(&mut process.fs().write());
+    }
+    process.set_nsproxy(Arc::new(new_nsproxy));
+
+    Ok(SyscallReturn::Return(0))
+}
@@ -8,11 +8,26 @@ use crate::{
 
 pub fn sys_setpgid(pid: Pid, pgid: Pgid, ctx: &Context) -> Result<SyscallReturn> {
     let current = ctx.process;
+    debug!("pid = {}, pgid = {}", pid, pgid);
+
+    // Translate the IDs in the PID namespace of the current process to the global ones
+    let pid_ns = current.pid_ns();
     // if pid is 0, pid should be the pid of current process
-    let pid = if pid == 0 { current.pid() } else { pid };
+    let pid = if pid == 0 {
+        current.pid()
+    } else {
+        pid_ns
+            .global_pid_of(pid)
+            .ok_or_else(|| Error::with_message(Errno::ESRCH, "process does not exist"))?
+    };
     // if pgid is 0, pgid should be pid
-    let pgid = if pgid == 0 { pid } else { pgid };
-    debug!("pid = {}, pgid = {}", pid, pgid);
+    let pgid = if pgid == 0 {
+        pid
+    } else {
+        pid_ns
+            .global_pid_of(pgid)
+            .ok_or_else(|| Error::with_message(Errno::EPERM, "process group must exist"))?
+    };
 
     if pid != current.pid() && !current.has_child(&pid) {
         return_errno_with_message!(
@@ -7,5 +7,6 @@ pub fn sys_setsid(_ctx: &Context) -> Result<SyscallReturn> {
     let current = current!();
     let session = current.to_new_session()?;
 
-    Ok(SyscallReturn::Return(session.sid() as _))
+    let sid = current.pid_ns().pid_or_zero(session.sid());
+    Ok(SyscallReturn::Return(sid as _))
 }
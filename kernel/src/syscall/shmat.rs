@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use align_ext::AlignExt;
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{
+        key_t,
+        shm::{shared_memory::SHMLBA, ShmFlags},
+    },
+    prelude::*,
+    vm::perms::VmPerms,
+};
+
+pub fn sys_shmat(
+    shmid: key_t,
+    shmaddr: Vaddr,
+    shmflg: i32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let flags = ShmFlags::from_bits_truncate(shmflg as u32);
+    debug!(
+        "[sys_shmat] shmid = {}, shmaddr = {:#x}, flags = {:?}",
+        shmid, shmaddr, flags
+    );
+
+    if shmid < 0 {
+        return_errno_with_message!(Errno::EINVAL, "invalid shmid");
+    }
+
+    let shmaddr = if shmaddr % SHMLBA == 0 {
+        shmaddr
+    } else if flags.contains(ShmFlags::SHM_RND) {
+        shmaddr.align_down(SHMLBA)
+    } else {
+        return_errno_with_message!(Errno::EINVAL, "shmaddr is not aligned");
+    };
+    if shmaddr == 0 && flags.contains(ShmFlags::SHM_REMAP) {
+        return_errno_with_message!(Errno::EINVAL, "SHM_REMAP requires a non-null shmaddr");
+    }
+
+    let (perms, requested) = {
+        let (mut perms, mut requested) = if flags.contains(ShmFlags::SHM_RDONLY) {
+            (VmPerms::READ, 0o4)
+        } else {
+            (VmPerms::READ | VmPerms::WRITE, 0o6)
+        };
+        if flags.contains(ShmFlags::SHM_EXEC) {
+            perms |= VmPerms::EXEC;
+            requested |= 0o1;
+        }
+        (perms, requested)
+    };
+
+    let shm = ctx.process.nsproxy().ipc_ns().shm_table().get_shm(shmid)?;
+    shm.permission()
+        .check_access(&ctx.posix_thread.credentials(), requested)?;
+
+    let map_addr = {
+        let size = shm.size().align_up(PAGE_SIZE);
+        let mut options = ctx
+            .process
+            .root_vmar()
+            .new_map(size, perms)?
+            .vmo(shm.vmo().dup()?)
+            .is_shared(true)
+            .shm(shm);
+        if shmaddr != 0 {
+            options = options
+                .offset(shmaddr)
+                .can_overwrite(flags.contains(ShmFlags::SHM_REMAP));
+        }
+        options.build()?
+    };
+
+    Ok(SyscallReturn::Return(map_addr as _))
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{
+        key_t,
+        shm::{
+            shared_memory::{SharedMemory, SHMALL, SHMMAX, SHMMIN, SHMMNI, SHMSEG},
+            ShmControlCmd,
+        },
+        IpcPerm,
+    },
+    prelude::*,
+};
+
+pub fn sys_shmctl(shmid: key_t, cmd: i32, buf: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    let cmd = ShmControlCmd::try_from(cmd)?;
+    debug!(
+        "[sys_shmctl] shmid = {}, cmd = {:?}, buf = {:#x}",
+        shmid, cmd, buf
+    );
+
+    let nsproxy = ctx.process.nsproxy();
+    let shm_table = nsproxy.ipc_ns().shm_table();
+
+    match cmd {
+        ShmControlCmd::IPC_INFO => {
+            let shm_info = ShmInfo {
+                shmmax: SHMMAX as u64,
+                shmmin: SHMMIN as u64,
+                shmmni: SHMMNI as u64,
+                shmseg: SHMSEG as u64,
+                shmall: SHMALL as u64,
+                ..Default::default()
+            };
+            ctx.get_user_space().write_val(buf, &shm_info)?;
+            return Ok(SyscallReturn::Return(shm_table.max_shm_id() as isize));
+        }
+        ShmControlCmd::IPC_STAT | ShmControlCmd::SHM_STAT => {
+            let shm = shm_table.get_shm(shmid)?;
+            shm.permission()
+                .check_access(&ctx.posix_thread.credentials(), 0o4)?;
+            ctx.get_user_space()
+                .write_val(buf, &ShmidDs::from_shm(&shm))?;
+
+            // `SHM_STAT` returns the identifier of the segment
+            if matches!(cmd, ShmControlCmd::SHM_STAT) {
+                return Ok(SyscallReturn::Return(shmid as isize));
+            }
+        }
+        ShmControlCmd::IPC_RMID => {
+            let shm = shm_table.get_shm(shmid)?;
+
+            let euid = ctx.posix_thread.credentials().euid();
+            let permission = shm.permission();
+            let can_removed = (euid == permission.uid()) || (euid == permission.cuid());
+            if !can_removed {
+                return_errno!(Errno::EPERM);
+            }
+
+            shm.mark_removed();
+        }
+        ShmControlCmd::SHM_LOCK | ShmControlCmd::SHM_UNLOCK => {
+            // The pages of shared memory segments are never swapped out,
+            // so locking and unlocking are no-ops.
+            let shm = shm_table.get_shm(shmid)?;
+
+            let euid = ctx.posix_thread.credentials().euid();
+            let permission = shm.permission();
+            let can_locked = (euid == permission.uid()) || (euid == permission.cuid());
+            if !can_locked {
+                return_errno!(Errno::EPERM);
+            }
+        }
+        ShmControlCmd::IPC_SET | ShmControlCmd::SHM_INFO => {
+            return_errno_with_message!(Errno::EINVAL, "the command is not supported");
+        }
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// The `shmid64_ds` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct ShmidDs {
+    shm_perm: IpcPerm,
+    shm_segsz: u64,
+    shm_atime: i64,
+    shm_dtime: i64,
+    shm_ctime: i64,
+    shm_cpid: i32,
+    shm_lpid: i32,
+    shm_nattch: u64,
+    __unused4: u64,
+    __unused5: u64,
+}
+
+impl ShmidDs {
+    /// The segment is marked to be destroyed.
+    const SHM_DEST: u32 = 0o1000;
+
+    fn from_shm(shm: &SharedMemory) -> Self {
+        let mut shm_perm = IpcPerm::from(shm.permission());
+        if shm.is_removed() {
+            shm_perm.key = 0;
+            shm_perm.mode |= Self::SHM_DEST;
+        }
+
+        Self {
+            shm_perm,
+            shm_segsz: shm.size() as u64,
+            shm_atime: shm.atime().as_secs() as i64,
+            shm_dtime: shm.dtime().as_secs() as i64,
+            shm_ctime: shm.ctime().as_secs() as i64,
+            shm_cpid: shm.cpid() as i32,
+            shm_lpid: shm.lpid() as i32,
+            shm_nattch: shm.nattch() as u64,
+            ..Default::default()
+        }
+    }
+}
+
+/// The `shminfo64` structure in Linux.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct ShmInfo {
+    shmmax: u64,
+    shmmin: u64,
+    shmmni: u64,
+    shmseg: u64,
+    shmall: u64,
+    __unused1: u64,
+    __unused2: u64,
+    __unused3: u64,
+    __unused4: u64,
+}
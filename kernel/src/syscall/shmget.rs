@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    ipc::{key_t, shm::shared_memory::IPC_PRIVATE, IpcFlags},
+    prelude::*,
+};
+
+pub fn sys_shmget(key: key_t, size: usize, shmflg: i32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = IpcFlags::from_bits_truncate(shmflg as u32);
+    let mode: u16 = (shmflg as u32 & 0x1FF) as u16;
+    let credentials = ctx.posix_thread.credentials();
+    let pid = ctx.process.pid();
+    let nsproxy = ctx.process.nsproxy();
+    let shm_table = nsproxy.ipc_ns().shm_table();
+
+    debug!(
+        "[sys_shmget] key = {}, size = {}, flags = {:?}",
+        key, size, shmflg
+    );
+
+    // Create a new segment directly
+    if key == IPC_PRIVATE {
+        return Ok(SyscallReturn::Return(
+            shm_table.create_shm(key, size, mode, pid, credentials)? as isize,
+        ));
+    }
+
+    // Get a segment, and create if necessary
+    let Some(shm) = shm_table.find_shm_by_key(key) else {
+        if !flags.contains(IpcFlags::IPC_CREAT) {
+            return_errno_with_message!(Errno::ENOENT, "no segment exists for the key");
+        }
+        return Ok(SyscallReturn::Return(
+            shm_table.create_shm(key, size, mode, pid, credentials)? as isize,
+        ));
+    };
+
+    if flags.contains(IpcFlags::IPC_CREAT | IpcFlags::IPC_EXCL) {
+        return_errno_with_message!(Errno::EEXIST, "the segment for the key already exists");
+    }
+    if size > shm.size() {
+        return_errno_with_message!(Errno::EINVAL, "the segment is smaller than size");
+    }
+    let requested = (mode >> 6) | (mode >> 3) | mode;
+    shm.permission().check_access(&credentials, requested)?;
+
+    Ok(SyscallReturn::Return(shm.id() as isize))
+}
@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! `signalfd()` creates a file descriptor (we name it as `SignalFile`)
+//! that can be used to accept signals targeted at the caller.
+//!
+//! The signals to accept are specified by a signal mask.
+//! Reading from `SignalFile` dequeues the pending signals in the mask
+//! and returns one `signalfd_siginfo` structure for each of them.
+//! The signals are supposed to be blocked by the caller,
+//! so that they will not be handled according to their default dispositions.
+//!
+//! For more detailed information about this syscall,
+//! refer to the man 2 signalfd documentation.
+//!
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::SyscallReturn;
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDesc},
+        utils::{CreationFlags, InodeMode, InodeType, Metadata, StatusFlags},
+    },
+    prelude::*,
+    process::{
+        posix_thread::PosixThreadExt,
+        signal::{
+            constants::{SIGKILL, SIGSTOP, SI_QUEUE, SI_TKILL, SI_USER},
+            sig_mask::{SigMask, SigSet},
+            signals::Signal,
+            Pollable, Pollee, Poller, SigEvents, SigEventsFilter,
+        },
+        Gid, Uid,
+    },
+    thread::Thread,
+    time::clocks::RealTimeClock,
+};
+
+pub fn sys_signalfd(
+    fd: FileDesc,
+    mask_ptr: Vaddr,
+    sizemask: usize,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    sys_signalfd4(fd, mask_ptr, sizemask, 0, ctx)
+}
+
+pub fn sys_signalfd4(
+    fd: FileDesc,
+    mask_ptr: Vaddr,
+    sizemask: usize,
+    flags: u32,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    trace!("raw flags = {}", flags);
+    let flags = Flags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    if sizemask != core::mem::size_of::<SigSet>() {
+        return_errno_with_message!(Errno::EINVAL, "invalid size of the signal mask");
+    }
+    let mask = {
+        let mut mask = ctx.get_user_space().read_val::<SigMask>(mask_ptr)?;
+        // SIGKILL and SIGSTOP cannot be accepted via signalfd
+        mask -= SIGKILL;
+        mask -= SIGSTOP;
+        mask
+    };
+    debug!("fd = {}, mask = {:x}, flags = {:?}", fd, mask, flags);
+
+    // Update the mask of an existing signalfd
+    if fd != -1 {
+        let file = {
+            let file_table = ctx.process.file_table().lock();
+            file_table.get_file(fd)?.clone()
+        };
+        let signal_file = file
+            .downcast_ref::<SignalFile>()
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a signalfd"))?;
+        signal_file.set_mask(mask);
+        return Ok(SyscallReturn::Return(fd as _));
+    }
+
+    let signal_file = SignalFile::new(mask, flags.contains(Flags::SFD_NONBLOCK));
+    let fd = {
+        let mut file_table = ctx.process.file_table().lock();
+        let fd_flags = if flags.contains(Flags::SFD_CLOEXEC) {
+            FdFlags::CLOEXEC
+        } else {
+            FdFlags::empty()
+        };
+        file_table.insert(signal_file, fd_flags)
+    };
+
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+bitflags! {
+    struct Flags: u32 {
+        const SFD_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+        const SFD_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+    }
+}
+
+struct SignalFile {
+    mask: Mutex<SigMask>,
+    pollee: Pollee,
+    is_nonblocking: AtomicBool,
+    /// The threads whose signal queues are observed.
+    ///
+    /// Reading the file dequeues the signals that are pending on the reading thread or on its
+    /// process. So the signal queues of a thread and its process are observed once the thread
+    /// creates, polls or reads the file. This allows the file to be polled in other threads or
+    /// in forked child processes.
+    observed_threads: Mutex<Vec<Weak<Thread>>>,
+    weak_self: Weak<Self>,
+}
+
+impl SignalFile {
+    fn new(mask: SigMask, is_nonblocking: bool) -> Arc<Self> {
+        let signal_file = Arc::new_cyclic(|weak_self| Self {
+            mask: Mutex::new(mask),
+            pollee: Pollee::new(IoEvents::empty()),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+            observed_threads: Mutex::new(Vec::new()),
+            weak_self: weak_self.clone(),
+        });
+        signal_file.observe_current_thread();
+        signal_file
+    }
+
+    fn set_mask(&self, mask: SigMask) {
+        let mut observed_threads = self.observed_threads.lock();
+        *self.mask.lock() = mask;
+
+        observed_threads.retain(|thread| thread.strong_count() > 0);
+        for thread in observed_threads.iter().filter_map(Weak::upgrade) {
+            self.observe_signals(&thread, mask);
+        }
+    }
+
+    /// Observes the signal queues of the current thread and its process, if they have not
+    /// been observed yet.
+    fn observe_current_thread(&self) {
+        let Some(current) = Thread::current() else {
+            return;
+        };
+
+        let mut observed_threads = self.observed_threads.lock();
+        if observed_threads
+            .iter()
+            .any(|thread| Weak::as_ptr(thread) == Arc::as_ptr(&current))
+        {
+            return;
+        }
+        observed_threads.retain(|thread| thread.strong_count() > 0);
+        observed_threads.push(Arc::downgrade(&current));
+
+        self.observe_signals(&current, *self.mask.lock());
+    }
+
+    /// Registers the file as an observer of the signals in the `mask` that are queued on the
+    /// `thread` or on its process.
+    ///
+    /// If the file has been registered, the interesting signals will be updated.
+    fn observe_signals(&self, thread: &Arc<Thread>, mask: SigMask) {
+        let posix_thread = thread.as_posix_thread().unwrap();
+        let observer = self.weak_self.clone() as Weak<dyn Observer<SigEvents>>;
+        // The filter accepts the signals that are _not_ in its mask
+        let filter = SigEventsFilter::new(SigSet::new_full() - mask);
+        posix_thread.register_sigqueue_observer(observer.clone(), filter);
+        posix_thread
+            .process()
+            .sig_queues()
+            .register_observer(observer, filter);
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    /// Dequeues a pending signal of the current thread that is in the mask.
+    fn dequeue_signal(&self) -> Option<Box<dyn Signal>> {
+        let thread = Thread::current()?;
+        let posix_thread = thread.as_posix_thread()?;
+        let blocked = SigSet::new_full() - *self.mask.lock();
+        posix_thread.dequeue_signal(&blocked)
+    }
+
+    fn update_io_state(&self) {
+        let has_pending = Thread::current()
+            .and_then(|thread| {
+                let posix_thread = thread.as_posix_thread()?;
+                let pending = posix_thread.sig_pending() & *self.mask.lock();
+                Some(!pending.is_empty())
+            })
+            .unwrap_or(false);
+
+        if has_pending {
+            self.pollee.add_events(IoEvents::IN);
+        } else {
+            self.pollee.del_events(IoEvents::IN);
+        }
+    }
+}
+
+impl Observer<SigEvents> for SignalFile {
+    fn on_events(&self, _events: &SigEvents) {
+        self.pollee.add_events(IoEvents::IN);
+    }
+}
+
+impl Pollable for SignalFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.observe_current_thread();
+        self.update_io_state();
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl FileLike for SignalFile {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let siginfo_len = core::mem::size_of::<SignalfdSiginfo>();
+        if writer.avail() < siginfo_len {
+            return_errno_with_message!(Errno::EINVAL, "buf len is less than the siginfo size");
+        }
+
+        let mut read_len = 0;
+        loop {
+            while writer.avail() >= siginfo_len {
+                let Some(signal) = self.dequeue_signal() else {
+                    break;
+                };
+                let siginfo = SignalfdSiginfo::from_signal(signal.as_ref());
+                writer.write_fallible(&mut siginfo.as_bytes().into())?;
+                read_len += siginfo_len;
+            }
+
+            if read_len > 0 {
+                break;
+            }
+
+            // Wait until some signals in the mask are pending
+            if self.is_nonblocking() {
+                return_errno_with_message!(Errno::EAGAIN, "try reading signalfd again");
+            }
+
+            let mut poller = Poller::new();
+            if self.poll(IoEvents::IN, Some(&mut poller)).is_empty() {
+                poller.wait()?;
+            }
+        }
+
+        self.update_io_state();
+        Ok(read_len)
+    }
+
+    fn write(&self, _reader: &mut VmReader) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "signalfd does not support write");
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+
+        // TODO: deal with other flags
+
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.observe_current_thread();
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+
+    fn metadata(&self) -> Metadata {
+        let now = RealTimeClock::get().read_time();
+        Metadata {
+            dev: 0,
+            ino: 0,
+            size: 0,
+            blk_size: 0,
+            blocks: 0,
+            atime: now,
+            mtime: now,
+            ctime: now,
+            type_: InodeType::NamedPipe,
+            mode: InodeMode::from_bits_truncate(0o400),
+            nlinks: 1,
+            uid: Uid::new_root(),
+            gid: Gid::new_root(),
+            rdev: 0,
+        }
+    }
+}
+
+impl Drop for SignalFile {
+    fn drop(&mut self) {
+        let observer = self.weak_self.clone() as Weak<dyn Observer<SigEvents>>;
+        let observed_threads = core::mem::take(&mut *self.observed_threads.lock());
+        for thread in observed_threads.iter().filter_map(Weak::upgrade) {
+            let posix_thread = thread.as_posix_thread().unwrap();
+            posix_thread.unregister_sigqueue_observer(&observer);
+            posix_thread
+                .process()
+                .sig_queues()
+                .unregister_observer(&observer);
+        }
+    }
+}
+
+/// The `signalfd_siginfo` structure in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/signalfd.h#L20>.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, Pod)]
+struct SignalfdSiginfo {
+    ssi_signo: u32,
+    ssi_errno: i32,
+    ssi_code: i32,
+    ssi_pid: u32,
+    ssi_uid: u32,
+    ssi_fd: i32,
+    ssi_tid: u32,
+    ssi_band: u32,
+    ssi_overrun: u32,
+    ssi_trapno: u32,
+    ssi_status: i32,
+    ssi_int: i32,
+    ssi_ptr: u64,
+    ssi_utime: u64,
+    ssi_stime: u64,
+    ssi_addr: u64,
+    ssi_addr_lsb: u16,
+    __pad2: u16,
+    ssi_syscall: i32,
+    ssi_call_addr: u64,
+    ssi_arch: u32,
+    __pad: [u8; 28],
+}
+
+impl SignalfdSiginfo {
+    fn from_signal(signal: &dyn Signal) -> Self {
+        let info = signal.to_info();
+        // Only the signals sent by users carry the sender's PID and UID.
+        let (ssi_pid, ssi_uid) = if matches!(info.si_code, SI_USER | SI_TKILL | SI_QUEUE) {
+            (info.si_pid(), info.si_uid().into())
+        } else {
+            (0, 0)
+        };
+        // TODO: Fill in the other fields once `siginfo_t` provides them.
+        Self {
+            ssi_signo: info.si_signo as u32,
+            ssi_errno: info.si_errno,
+            ssi_code: info.si_code,
+            ssi_pid,
+            ssi_uid,
+            ..Default::default()
+        }
+    }
+}
@@ -4,8 +4,10 @@ use super::SyscallReturn;
 use crate::{
     fs::{file_handle::FileLike, file_table::FdFlags},
     net::socket::{
-        ip::{DatagramSocket, StreamSocket},
-        unix::UnixStreamSocket,
+        ip::{DatagramSocket, IpFamily, PingSocket, RawSocket, StreamSocket},
+        netlink::{NetlinkProtocol, NetlinkSocket},
+        packet::PacketSocket,
+        unix::{UnixDatagramSocket, UnixStreamSocket},
         vsock::VsockStreamSocket,
     },
     prelude::*,
@@ -16,40 +18,133 @@ pub fn sys_socket(domain: i32, type_: i32, protocol: i32, ctx: &Context) -> Resu
     let domain = CSocketAddrFamily::try_from(domain)?;
     let sock_type = SockType::try_from(type_ & SOCK_TYPE_MASK)?;
     let sock_flags = SockFlags::from_bits_truncate(type_ & !SOCK_TYPE_MASK);
-    let protocol = Protocol::try_from(protocol)?;
     debug!(
-        "domain = {:?}, sock_type = {:?}, sock_flags = {:?}, protocol = {:?}",
+        "domain = {:?}, sock_type = {:?}, sock_flags = {:?}, protocol = {}",
         domain, sock_type, sock_flags, protocol
     );
     let nonblocking = sock_flags.contains(SockFlags::SOCK_NONBLOCK);
+    let file_like = if domain == CSocketAddrFamily::AF_NETLINK {
+        // Netlink sockets have their own protocol numbers.
+        new_netlink_socket(sock_type, protocol, nonblocking)?
+    } else if domain == CSocketAddrFamily::AF_PACKET {
+        // Packet sockets use link-layer protocol numbers in network byte order.
+        new_packet_socket(sock_type, u16::from_be(protocol as u16), nonblocking)?
+    } else {
+        new_socket(
+            domain,
+            sock_type,
+            Protocol::try_from(protocol)?,
+            nonblocking,
+        )?
+    };
+    let fd = {
+        let mut file_table = ctx.process.file_table().lock();
+        let fd_flags = if sock_flags.contains(SockFlags::SOCK_CLOEXEC) {
+            FdFlags::CLOEXEC
+        } else {
+            FdFlags::empty()
+        };
+        file_table.insert(file_like, fd_flags)
+    };
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+fn new_socket(
+    domain: CSocketAddrFamily,
+    sock_type: SockType,
+    protocol: Protocol,
+    nonblocking: bool,
+) -> Result<Arc<dyn FileLike>> {
     let file_like = match (domain, sock_type, protocol) {
-        // FIXME: SOCK_SEQPACKET is added to run fcntl_test, not supported yet.
-        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM | SockType::SOCK_SEQPACKET, _) => {
-            UnixStreamSocket::new(nonblocking) as Arc<dyn FileLike>
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM, _) => {
+            UnixStreamSocket::new(nonblocking, false) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_SEQPACKET, _) => {
+            UnixStreamSocket::new(nonblocking, true) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_DGRAM, _) => {
+            UnixDatagramSocket::new(nonblocking) as Arc<dyn FileLike>
         }
         (
             CSocketAddrFamily::AF_INET,
             SockType::SOCK_STREAM,
             Protocol::IPPROTO_IP | Protocol::IPPROTO_TCP,
-        ) => StreamSocket::new(nonblocking) as Arc<dyn FileLike>,
+        ) => StreamSocket::new(IpFamily::Ipv4, nonblocking) as Arc<dyn FileLike>,
+        (
+            CSocketAddrFamily::AF_INET6,
+            SockType::SOCK_STREAM,
+            Protocol::IPPROTO_IP | Protocol::IPPROTO_TCP,
+        ) => StreamSocket::new(IpFamily::Ipv6, nonblocking) as Arc<dyn FileLike>,
         (
             CSocketAddrFamily::AF_INET,
             SockType::SOCK_DGRAM,
             Protocol::IPPROTO_IP | Protocol::IPPROTO_UDP,
-        ) => DatagramSocket::new(nonblocking) as Arc<dyn FileLike>,
+        ) => DatagramSocket::new(IpFamily::Ipv4, nonblocking) as Arc<dyn FileLike>,
+        (
+            CSocketAddrFamily::AF_INET6,
+            SockType::SOCK_DGRAM,
+            Protocol::IPPROTO_IP | Protocol::IPPROTO_UDP,
+        ) => DatagramSocket::new(IpFamily::Ipv6, nonblocking) as Arc<dyn FileLike>,
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_DGRAM, Protocol::IPPROTO_ICMP) => {
+            PingSocket::new(IpFamily::Ipv4, nonblocking) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_INET6, SockType::SOCK_DGRAM, Protocol::IPPROTO_ICMPV6) => {
+            PingSocket::new(IpFamily::Ipv6, nonblocking) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_RAW, _) => {
+            RawSocket::new(protocol, nonblocking)? as Arc<dyn FileLike>
+        }
         (CSocketAddrFamily::AF_VSOCK, SockType::SOCK_STREAM, _) => {
-            Arc::new(VsockStreamSocket::new(nonblocking)) as Arc<dyn FileLike>
+            Arc::new(VsockStreamSocket::new(nonblocking, false)) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_VSOCK, SockType::SOCK_SEQPACKET, _) => {
+            Arc::new(VsockStreamSocket::new(nonblocking, true)) as Arc<dyn FileLike>
+        }
+        // Datagram vsock sockets are out of scope: the virtio-vsock device offers no datagram
+        // feature, so Linux only supports them with other transports (e.g., VMCI).
+        (CSocketAddrFamily::AF_VSOCK, SockType::SOCK_DGRAM, _) => {
+            return_errno_with_message!(
+                Errno::ESOCKTNOSUPPORT,
+                "datagram vsock sockets are not supported"
+            )
         }
         _ => return_errno_with_message!(Errno::EAFNOSUPPORT, "unsupported domain"),
     };
-    let fd = {
-        let mut file_table = ctx.process.file_table().lock();
-        let fd_flags = if sock_flags.contains(SockFlags::SOCK_CLOEXEC) {
-            FdFlags::CLOEXEC
-        } else {
-            FdFlags::empty()
-        };
-        file_table.insert(file_like, fd_flags)
+    Ok(file_like)
+}
+
+fn new_netlink_socket(
+    sock_type: SockType,
+    protocol: i32,
+    nonblocking: bool,
+) -> Result<Arc<dyn FileLike>> {
+    let Ok(protocol) = NetlinkProtocol::try_from(protocol) else {
+        return_errno_with_message!(Errno::EPROTONOSUPPORT, "the netlink protocol is invalid");
     };
-    Ok(SyscallReturn::Return(fd as _))
+    let file_like = match (sock_type, protocol) {
+        (SockType::SOCK_RAW | SockType::SOCK_DGRAM, _) => {
+            NetlinkSocket::new(protocol, nonblocking)? as Arc<dyn FileLike>
+        }
+        _ => return_errno_with_message!(
+            Errno::ESOCKTNOSUPPORT,
+            "the socket type is not supported by netlink"
+        ),
+    };
+    Ok(file_like)
+}
+
+fn new_packet_socket(
+    sock_type: SockType,
+    protocol: u16,
+    nonblocking: bool,
+) -> Result<Arc<dyn FileLike>> {
+    let file_like = match sock_type {
+        SockType::SOCK_RAW => PacketSocket::new(protocol, nonblocking)? as Arc<dyn FileLike>,
+        // TODO: Support `SOCK_DGRAM` packet sockets, which strip the link-layer headers.
+        _ => return_errno_with_message!(
+            Errno::ESOCKTNOSUPPORT,
+            "the socket type is not supported by packet sockets"
+        ),
+    };
+    Ok(file_like)
 }
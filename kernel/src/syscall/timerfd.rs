@@ -0,0 +1,384 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! `timerfd_create()` creates a timer (we name it as `TimerFile`)
+//! that delivers timer expiration notifications via a file descriptor.
+//!
+//! `TimerFile` holds a u64 integer counter,
+//! which records the number of expirations since the timer was last set or read.
+//! Reading from `TimerFile` returns the current counter value and resets it.
+//! The read operations may be blocked based on file flags.
+//!
+//! For more detailed information about this syscall,
+//! refer to the man 2 timerfd_create documentation.
+//!
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use super::{ClockId, SyscallReturn};
+use crate::{
+    events::{IoEvents, Observer},
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDesc},
+        utils::{CreationFlags, InodeMode, InodeType, Metadata, StatusFlags},
+    },
+    prelude::*,
+    process::{
+        signal::{Pollable, Pollee, Poller},
+        Gid, Uid,
+    },
+    thread::work_queue::{submit_work_item, work_item::WorkItem, WorkPriority},
+    time::{
+        clockid_t,
+        clocks::{BootTimeClock, MonotonicClock, RealTimeClock},
+        itimerspec_t,
+        timer::Timeout,
+        timespec_t, Timer, TimerManager,
+    },
+};
+
+pub fn sys_timerfd_create(clockid: clockid_t, flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    trace!("raw flags = {}", flags);
+    let flags = Flags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    let clock_id = ClockId::try_from(clockid)?;
+    debug!("clock_id = {:?}, flags = {:?}", clock_id, flags);
+
+    let timer_file = TimerFile::new(clock_id, flags.contains(Flags::TFD_NONBLOCK))?;
+    let fd = {
+        let mut file_table = ctx.process.file_table().lock();
+        let fd_flags = if flags.contains(Flags::TFD_CLOEXEC) {
+            FdFlags::CLOEXEC
+        } else {
+            FdFlags::empty()
+        };
+        file_table.insert(timer_file, fd_flags)
+    };
+
+    Ok(SyscallReturn::Return(fd as _))
+}
+
+pub fn sys_timerfd_settime(
+    fd: FileDesc,
+    flags: u32,
+    new_itimerspec_addr: Vaddr,
+    old_itimerspec_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let flags = SetTimeFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unknown flags"))?;
+    debug!(
+        "fd = {}, flags = {:?}, new_itimerspec_addr = {:#x}, old_itimerspec_addr = {:#x}",
+        fd, flags, new_itimerspec_addr, old_itimerspec_addr
+    );
+
+    let user_space = ctx.get_user_space();
+    let new_itimerspec = user_space.read_val::<itimerspec_t>(new_itimerspec_addr)?;
+    let interval = Duration::try_from(new_itimerspec.it_interval)?;
+    let expire_time = Duration::try_from(new_itimerspec.it_value)?;
+
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let timer_file = file
+        .downcast_ref::<TimerFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a timerfd"))?;
+
+    if old_itimerspec_addr != 0 {
+        user_space.write_val(old_itimerspec_addr, &timer_file.itimerspec())?;
+    }
+
+    // TODO: Support `TFD_TIMER_CANCEL_ON_SET`.
+    let timeout = if expire_time == Duration::ZERO {
+        None
+    } else if flags.contains(SetTimeFlags::TFD_TIMER_ABSTIME) {
+        Some(Timeout::When(expire_time))
+    } else {
+        Some(Timeout::After(expire_time))
+    };
+    timer_file.set_time(interval, timeout);
+
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_timerfd_gettime(
+    fd: FileDesc,
+    itimerspec_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!("fd = {}, itimerspec_addr = {:#x}", fd, itimerspec_addr);
+
+    let file = {
+        let file_table = ctx.process.file_table().lock();
+        file_table.get_file(fd)?.clone()
+    };
+    let timer_file = file
+        .downcast_ref::<TimerFile>()
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the file is not a timerfd"))?;
+
+    ctx.get_user_space()
+        .write_val(itimerspec_addr, &timer_file.itimerspec())?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+bitflags! {
+    struct Flags: u32 {
+        const TFD_CLOEXEC = CreationFlags::O_CLOEXEC.bits();
+        const TFD_NONBLOCK = StatusFlags::O_NONBLOCK.bits();
+    }
+}
+
+bitflags! {
+    struct SetTimeFlags: u32 {
+        const TFD_TIMER_ABSTIME = 1;
+        const TFD_TIMER_CANCEL_ON_SET = 2;
+    }
+}
+
+struct TimerFile {
+    timer_manager: Arc<TimerManager>,
+    /// The timer of the current setting.
+    ///
+    /// A new timer is created every time the timerfd is set, so that the callbacks of a timer
+    /// that has been reset or disarmed can be told apart by their generations.
+    timer: Mutex<Arc<Timer>>,
+    expirations: Arc<SpinLock<Expirations>>,
+    /// The work item that notifies the observers after the timer expires.
+    notify_work: Arc<WorkItem>,
+    pollee: Pollee,
+    is_nonblocking: AtomicBool,
+}
+
+struct Expirations {
+    /// The generation of the current timer setting.
+    generation: u64,
+    /// The number of expirations that have not been read.
+    ticks: u64,
+}
+
+impl TimerFile {
+    fn new(clock_id: ClockId, is_nonblocking: bool) -> Result<Arc<Self>> {
+        let timer_manager = match clock_id {
+            ClockId::CLOCK_REALTIME => RealTimeClock::timer_manager(),
+            ClockId::CLOCK_MONOTONIC => MonotonicClock::timer_manager(),
+            ClockId::CLOCK_BOOTTIME => BootTimeClock::timer_manager(),
+            _ => return_errno_with_message!(Errno::EINVAL, "unsupported clock ID"),
+        };
+
+        let timer_file = Arc::new_cyclic(|weak_self: &Weak<Self>| {
+            // The timer callback is invoked in the interrupt context,
+            // so the events are delivered in a work item to wake up the observers safely.
+            let weak_self = weak_self.clone();
+            let notify_work = Arc::new(WorkItem::new(Box::new(move || {
+                if let Some(timer_file) = weak_self.upgrade() {
+                    timer_file.on_expired();
+                }
+            })));
+            let expirations = Arc::new(SpinLock::new(Expirations {
+                generation: 0,
+                ticks: 0,
+            }));
+            let timer = Self::create_timer(timer_manager, &expirations, &notify_work, 0);
+
+            Self {
+                timer_manager: timer_manager.clone(),
+                timer: Mutex::new(timer),
+                expirations,
+                notify_work,
+                pollee: Pollee::new(IoEvents::empty()),
+                is_nonblocking: AtomicBool::new(is_nonblocking),
+            }
+        });
+
+        Ok(timer_file)
+    }
+
+    /// Creates a timer whose expirations are counted only if the timerfd has not been set
+    /// again since the `generation`.
+    fn create_timer(
+        timer_manager: &Arc<TimerManager>,
+        expirations: &Arc<SpinLock<Expirations>>,
+        notify_work: &Arc<WorkItem>,
+        generation: u64,
+    ) -> Arc<Timer> {
+        let expirations = expirations.clone();
+        let notify_work = notify_work.clone();
+        timer_manager.create_timer(move || {
+            let mut expirations = expirations.disable_irq().lock();
+            if expirations.generation != generation {
+                return;
+            }
+            expirations.ticks += 1;
+            drop(expirations);
+
+            submit_work_item(notify_work.clone(), WorkPriority::High);
+        })
+    }
+
+    fn on_expired(&self) {
+        if self.expirations.disable_irq().lock().ticks != 0 {
+            self.pollee.add_events(IoEvents::IN);
+        }
+    }
+
+    /// Arms the timer with the `timeout`, or disarms it if the `timeout` is `None`.
+    ///
+    /// The expirations that have not been read are discarded.
+    fn set_time(&self, interval: Duration, timeout: Option<Timeout>) {
+        let mut timer = self.timer.lock();
+
+        // The old timer may still be running its callback, which will be dropped
+        // since the generation has changed.
+        timer.set_interval(Duration::ZERO);
+        timer.cancel();
+
+        let generation = {
+            let mut expirations = self.expirations.disable_irq().lock();
+            expirations.generation += 1;
+            expirations.ticks = 0;
+            expirations.generation
+        };
+        self.pollee.del_events(IoEvents::IN);
+
+        *timer = Self::create_timer(
+            &self.timer_manager,
+            &self.expirations,
+            &self.notify_work,
+            generation,
+        );
+        timer.set_interval(interval);
+        if let Some(timeout) = timeout {
+            timer.set_timeout(timeout);
+        }
+    }
+
+    /// Takes the expirations that have not been read.
+    fn take_ticks(&self) -> u64 {
+        core::mem::take(&mut self.expirations.disable_irq().lock().ticks)
+    }
+
+    fn itimerspec(&self) -> itimerspec_t {
+        let timer = self.timer.lock();
+        itimerspec_t {
+            it_interval: timespec_t::from(timer.interval()),
+            it_value: timespec_t::from(timer.remain()),
+        }
+    }
+
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+}
+
+impl Pollable for TimerFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut Poller>) -> IoEvents {
+        self.pollee.poll(mask, poller)
+    }
+}
+
+impl FileLike for TimerFile {
+    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let read_len = core::mem::size_of::<u64>();
+        if writer.avail() < read_len {
+            return_errno_with_message!(Errno::EINVAL, "buf len is less than the size of u64");
+        }
+
+        loop {
+            let ticks = self.take_ticks();
+            if ticks != 0 {
+                self.pollee.del_events(IoEvents::IN);
+                // Handle the race with the timer callback that happens after the take
+                if self.expirations.disable_irq().lock().ticks != 0 {
+                    self.pollee.add_events(IoEvents::IN);
+                }
+
+                writer.write_fallible(&mut ticks.as_bytes().into())?;
+                break;
+            }
+
+            // Wait until the timer expires
+            if self.is_nonblocking() {
+                return_errno_with_message!(Errno::EAGAIN, "try reading timerfd again");
+            }
+
+            let mut poller = Poller::new();
+            if self.pollee.poll(IoEvents::IN, Some(&mut poller)).is_empty() {
+                poller.wait()?;
+            }
+        }
+
+        Ok(read_len)
+    }
+
+    fn write(&self, _reader: &mut VmReader) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "timerfd does not support write");
+    }
+
+    fn status_flags(&self) -> StatusFlags {
+        if self.is_nonblocking() {
+            StatusFlags::O_NONBLOCK
+        } else {
+            StatusFlags::empty()
+        }
+    }
+
+    fn set_status_flags(&self, new_flags: StatusFlags) -> Result<()> {
+        self.is_nonblocking.store(
+            new_flags.contains(StatusFlags::O_NONBLOCK),
+            Ordering::Relaxed,
+        );
+
+        // TODO: deal with other flags
+
+        Ok(())
+    }
+
+    fn register_observer(
+        &self,
+        observer: Weak<dyn Observer<IoEvents>>,
+        mask: IoEvents,
+    ) -> Result<()> {
+        self.pollee.register_observer(observer, mask);
+        Ok(())
+    }
+
+    fn unregister_observer(
+        &self,
+        observer: &Weak<dyn Observer<IoEvents>>,
+    ) -> Option<Weak<dyn Observer<IoEvents>>> {
+        self.pollee.unregister_observer(observer)
+    }
+
+    fn metadata(&self) -> Metadata {
+        let now = RealTimeClock::get().read_time();
+        Metadata {
+            dev: 0,
+            ino: 0,
+            size: 0,
+            blk_size: 0,
+            blocks: 0,
+            atime: now,
+            mtime: now,
+            ctime: now,
+            type_: InodeType::NamedPipe,
+            mode: InodeMode::from_bits_truncate(0o400),
+            nlinks: 1,
+            uid: Uid::new_root(),
+            gid: Gid::new_root(),
+            rdev: 0,
+        }
+    }
+}
+
+impl Drop for TimerFile {
+    fn drop(&mut self) {
+        let timer = self.timer.lock();
+        timer.set_interval(Duration::ZERO);
+        timer.cancel();
+    }
+}
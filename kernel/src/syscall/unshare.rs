@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::{
+        namespace::{check_ns_permission, NS_CLONE_FLAGS},
+        CloneFlags,
+    },
+};
+
+pub fn sys_unshare(unshare_flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = CloneFlags::from_bits(unshare_flags)
+        .filter(|flags| NS_CLONE_FLAGS.contains(*flags))
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "unsupported unshare flags"))?;
+    debug!("flags = {:?}", flags);
+
+    if flags.is_empty() {
+        return Ok(SyscallReturn::Return(0));
+    }
+    check_ns_permission(ctx)?;
+
+    let new_nsproxy = ctx
+        .process
+        .nsproxy()
+        .copy_with_flags(flags, ctx.process.pid_ns())?;
+    if flags.contains(CloneFlags::CLONE_NEWNS) {
+        // FIXME: The `FsResolver` may be shared with other processes created with `CLONE_FS`,
+        // which should be unshared first instead of being moved to the new namespace together.
+        new_nsproxy
+            .mnt_ns()
+            .translate_fs(&mut ctx.process.fs().write());
+    }
+    ctx.process.set_nsproxy(Arc::new(new_nsproxy));
+
+    Ok(SyscallReturn::Return(0))
+}
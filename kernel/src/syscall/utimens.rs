@@ -7,6 +7,7 @@ use crate::{
     fs::{
         file_table::FileDesc,
         fs_resolver::{FsPath, AT_FDCWD},
+        notify::FsEvents,
         path::Dentry,
     },
     prelude::*,
@@ -142,6 +143,7 @@ fn vfs_utimes(dentry: &Arc<Dentry>, times: Option<TimeSpecPair>) -> Result<Sysca
     dentry.set_atime(atime);
     dentry.set_mtime(mtime);
     dentry.set_ctime(ctime);
+    dentry.notify_fs_events(FsEvents::ATTRIB);
 
     Ok(SyscallReturn::Return(0))
 }
@@ -20,14 +20,16 @@ pub fn sys_wait4(
         wait_pid as i32, exit_status_ptr, wait_options
     );
     debug!("wait4 current pid = {}", ctx.process.pid());
-    let process_filter = ProcessFilter::from_id(wait_pid as _);
+    let process_filter = ProcessFilter::from_id(wait_pid as _, ctx.process.pid_ns())
+        .ok_or_else(|| Error::with_message(Errno::ECHILD, "the child does not exist"))?;
 
-    let waited_process = wait_child_exit(process_filter, wait_options, ctx)?;
-    let Some(process) = waited_process else {
+    let waited_child = wait_child_exit(process_filter, wait_options, ctx)?;
+    let Some(waited_child) = waited_child else {
         return Ok(SyscallReturn::Return(0 as _));
     };
 
-    let (return_pid, exit_code) = (process.pid(), process.exit_code());
+    let (return_pid, exit_code) = (waited_child.pid(), waited_child.status());
+    let process = waited_child.process();
     if exit_status_ptr != 0 {
         ctx.get_user_space()
             .write_val(exit_status_ptr as _, &exit_code)?;
@@ -43,5 +45,6 @@ pub fn sys_wait4(
         ctx.get_user_space().write_val(rusage_addr, &rusage)?;
     }
 
+    let return_pid = ctx.process.pid_ns().pid_or_zero(return_pid);
     Ok(SyscallReturn::Return(return_pid as _))
 }
@@ -15,10 +15,11 @@ pub fn sys_waitid(
     ctx: &Context,
 ) -> Result<SyscallReturn> {
     // FIXME: what does infoq and rusage use for?
-    let process_filter = ProcessFilter::from_which_and_id(which, upid)?;
+    let process_filter = ProcessFilter::from_which_and_id(which, upid, ctx.process.pid_ns())?
+        .ok_or_else(|| Error::with_message(Errno::ECHILD, "the child does not exist"))?;
     let wait_options = WaitOptions::from_bits(options as u32)
         .ok_or(Error::with_message(Errno::EINVAL, "invalid options"))?;
-    let waited_process = wait_child_exit(process_filter, wait_options, ctx)?;
-    let pid = waited_process.map_or(0, |process| process.pid());
+    let waited_child = wait_child_exit(process_filter, wait_options, ctx)?;
+    let pid = waited_child.map_or(0, |child| ctx.process.pid_ns().pid_or_zero(child.pid()));
     Ok(SyscallReturn::Return(pid as _))
 }
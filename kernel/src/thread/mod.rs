@@ -2,14 +2,17 @@
 
 //! Posix thread implementation
 
-use core::sync::atomic::Ordering;
+use core::sync::atomic::{AtomicI64, Ordering};
 
 use ostd::{cpu::CpuSet, sync::PreemptDisabled, task::Task};
 
 use self::status::{AtomicThreadStatus, ThreadStatus};
 use crate::{
     prelude::*,
-    sched::priority::{AtomicPriority, Priority},
+    sched::{
+        policy::{AtomicSchedPolicy, SchedPolicy},
+        priority::{AtomicPriority, Priority},
+    },
 };
 
 pub mod exception;
@@ -31,10 +34,14 @@ pub struct Thread {
     // mutable part
     /// Thread status
     status: AtomicThreadStatus,
+    /// Thread scheduling policy
+    sched_policy: AtomicSchedPolicy,
     /// Thread priority
     priority: AtomicPriority,
     /// Thread cpu affinity
     cpu_affinity: SpinLock<CpuSet>,
+    /// Virtual runtime relative to the fair runqueue when last dequeued
+    vruntime_lag: AtomicI64,
 }
 
 impl Thread {
@@ -50,8 +57,10 @@ impl Thread {
             task,
             data: Box::new(data),
             status: AtomicThreadStatus::new(status),
+            sched_policy: AtomicSchedPolicy::new(SchedPolicy::default()),
             priority: AtomicPriority::new(priority),
             cpu_affinity: SpinLock::new(cpu_affinity),
+            vruntime_lag: AtomicI64::new(0),
         }
     }
 
@@ -100,6 +109,23 @@ impl Thread {
         self.status.store(new_status, Ordering::Release);
     }
 
+    /// Returns the scheduling policy.
+    pub fn sched_policy(&self) -> SchedPolicy {
+        self.sched_policy.load(Ordering::Relaxed)
+    }
+
+    /// Updates the scheduling policy and the priority with the new values.
+    ///
+    /// The new priority must be a real-time one if and only if the new policy is real-time.
+    pub fn set_sched_policy(&self, new_policy: SchedPolicy, new_priority: Priority) {
+        debug_assert_eq!(
+            new_policy.is_real_time(),
+            new_priority.real_time_priority().is_some()
+        );
+        self.sched_policy.store(new_policy, Ordering::Relaxed);
+        self.priority.store(new_priority, Ordering::Relaxed);
+    }
+
     /// Returns the reference to the atomic priority.
     pub fn atomic_priority(&self) -> &AtomicPriority {
         &self.priority
@@ -121,8 +147,19 @@ impl Thread {
     }
 
     /// Updates the cpu affinity with the new value.
+    ///
+    /// If the thread is runnable on a CPU out of the new set, it is migrated to a CPU in the set
+    /// (see [`crate::sched::migrate_to_allowed_cpu`]).
     pub fn set_cpu_affinity(&self, new_cpu_affinity: CpuSet) {
         *self.cpu_affinity.lock() = new_cpu_affinity;
+        if let Some(task) = self.task.upgrade() {
+            crate::sched::migrate_to_allowed_cpu(&task);
+        }
+    }
+
+    /// Returns the virtual runtime lag used by the fair scheduling class.
+    pub fn vruntime_lag(&self) -> &AtomicI64 {
+        &self.vruntime_lag
     }
 
     pub fn yield_now() {
@@ -47,8 +47,11 @@ pub fn create_new_user_task(user_space: Arc<UserSpace>, thread_ref: Arc<Thread>)
         // Make sure the store operation completes before the clone call returns control to user space
         // in the child process.
         if is_userspace_vaddr(child_tid_ptr) {
+            let child_tid = current_process
+                .pid_ns()
+                .pid_or_zero(current_posix_thread.tid());
             get_current_userspace!()
-                .write_val(child_tid_ptr, &current_posix_thread.tid())
+                .write_val(child_tid_ptr, &child_tid)
                 .unwrap();
         }
 
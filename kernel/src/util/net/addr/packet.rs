@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::wire::EthernetAddress;
+
+use super::family::CSocketAddrFamily;
+use crate::{
+    net::socket::packet::{PacketSocketAddr, PacketType},
+    prelude::*,
+};
+
+/// Link-layer socket address.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub(super) struct CSocketAddrLinkLayer {
+    /// Address family (AF_PACKET).
+    sll_family: u16,
+    /// Physical-layer protocol in network byte order.
+    sll_protocol: u16,
+    /// Interface index.
+    sll_ifindex: i32,
+    /// ARP hardware type.
+    sll_hatype: u16,
+    /// Packet type.
+    sll_pkttype: u8,
+    /// Length of address.
+    sll_halen: u8,
+    /// Physical-layer address.
+    sll_addr: [u8; 8],
+}
+
+impl From<PacketSocketAddr> for CSocketAddrLinkLayer {
+    fn from(value: PacketSocketAddr) -> Self {
+        let mut sll_addr = [0; 8];
+        let sll_halen = if let Some(hwaddr) = value.hwaddr {
+            sll_addr[..hwaddr.0.len()].copy_from_slice(&hwaddr.0);
+            hwaddr.0.len() as u8
+        } else {
+            0
+        };
+
+        Self {
+            sll_family: CSocketAddrFamily::AF_PACKET as u16,
+            sll_protocol: value.protocol.to_be(),
+            sll_ifindex: value.ifindex as i32,
+            sll_hatype: value.hatype,
+            sll_pkttype: value.pkttype as u8,
+            sll_halen,
+            sll_addr,
+        }
+    }
+}
+
+impl From<CSocketAddrLinkLayer> for PacketSocketAddr {
+    fn from(value: CSocketAddrLinkLayer) -> Self {
+        // Negative iface indexes are invalid and will match no ifaces.
+        let ifindex = u32::try_from(value.sll_ifindex).unwrap_or(u32::MAX);
+
+        let hwaddr = if usize::from(value.sll_halen) == size_of::<EthernetAddress>() {
+            Some(EthernetAddress::from_bytes(
+                &value.sll_addr[..size_of::<EthernetAddress>()],
+            ))
+        } else {
+            None
+        };
+
+        Self {
+            ifindex,
+            protocol: u16::from_be(value.sll_protocol),
+            hatype: value.sll_hatype,
+            // The packet type is ignored when binding or sending packets.
+            pkttype: PacketType::try_from(value.sll_pkttype).unwrap_or(PacketType::PACKET_HOST),
+            hwaddr,
+        }
+    }
+}
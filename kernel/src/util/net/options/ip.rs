@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::RawSocketOption;
+use crate::{
+    impl_raw_socket_option,
+    net::socket::ip::{options::RecvErr, raw::options::HdrIncl},
+    prelude::*,
+    util::net::options::SocketOption,
+};
+
+/// Sock options for IPv4 socket.
+///
+/// The raw definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/in.h#L94
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum CIpOptionName {
+    HDRINCL = 3,
+    RECVERR = 11,
+}
+
+pub fn new_ip_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
+    let name = CIpOptionName::try_from(name)?;
+    match name {
+        CIpOptionName::HDRINCL => Ok(Box::new(HdrIncl::new())),
+        CIpOptionName::RECVERR => Ok(Box::new(RecvErr::new())),
+    }
+}
+
+impl_raw_socket_option!(HdrIncl);
+impl_raw_socket_option!(RecvErr);
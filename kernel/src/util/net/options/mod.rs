@@ -53,11 +53,18 @@
 
 use crate::{net::socket::options::SocketOption, prelude::*};
 
+mod ip;
+mod ipv6;
+mod raw;
 mod socket;
 mod tcp;
 mod utils;
+mod vsock;
 
-use self::{socket::new_socket_option, tcp::new_tcp_option};
+use self::{
+    ip::new_ip_option, ipv6::new_ipv6_option, raw::new_raw_option, socket::new_socket_option,
+    tcp::new_tcp_option, vsock::new_vsock_option,
+};
 
 pub trait RawSocketOption: SocketOption {
     fn read_from_user(&mut self, addr: Vaddr, max_len: u32) -> Result<()>;
@@ -132,9 +139,13 @@ pub fn new_raw_socket_option(
     name: i32,
 ) -> Result<Box<dyn RawSocketOption>> {
     match level {
+        CSocketOptionLevel::SOL_IP => new_ip_option(name),
         CSocketOptionLevel::SOL_SOCKET => new_socket_option(name),
         CSocketOptionLevel::SOL_TCP => new_tcp_option(name),
-        _ => todo!(),
+        CSocketOptionLevel::SOL_IPV6 => new_ipv6_option(name),
+        CSocketOptionLevel::SOL_RAW => new_raw_option(name),
+        CSocketOptionLevel::AF_VSOCK => new_vsock_option(name),
+        _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option level is unknown"),
     }
 }
 
@@ -147,6 +158,8 @@ pub enum CSocketOptionLevel {
     SOL_SOCKET = 1,
     SOL_TCP = 6,
     SOL_UDP = 17,
+    /// Vsock sockets use the address family as the option level.
+    AF_VSOCK = 40,
     SOL_IPV6 = 41,
     SOL_RAW = 255,
 }
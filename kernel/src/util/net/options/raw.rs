@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::RawSocketOption;
+use crate::{
+    impl_raw_socket_option, net::socket::ip::raw::options::IcmpFilter, prelude::*,
+    util::net::options::SocketOption,
+};
+
+/// Sock options for raw IP socket.
+///
+/// The raw definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/icmp.h#L124
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum CRawOptionName {
+    ICMP_FILTER = 1,
+}
+
+pub fn new_raw_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
+    let name = CRawOptionName::try_from(name)?;
+    match name {
+        CRawOptionName::ICMP_FILTER => Ok(Box::new(IcmpFilter::new())),
+    }
+}
+
+impl_raw_socket_option!(IcmpFilter);
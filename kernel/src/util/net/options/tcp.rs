@@ -2,8 +2,10 @@
 
 use super::RawSocketOption;
 use crate::{
-    impl_raw_socket_option,
-    net::socket::ip::stream::options::{Congestion, MaxSegment, NoDelay, WindowClamp},
+    impl_raw_sock_option_get_only, impl_raw_socket_option,
+    net::socket::ip::stream::options::{
+        Congestion, Cork, Info, KeepCount, KeepIdle, KeepInterval, MaxSegment, NoDelay, WindowClamp,
+    },
     prelude::*,
     util::net::options::SocketOption,
 };
@@ -20,8 +22,10 @@ pub enum CTcpOptionName {
     MAXSEG = 2,        /* Limit MSS */
     CORK = 3,          /* Never send partially complete segments */
     KEEPIDLE = 4,      /* Start keeplives after this period */
-    KEEPALIVE = 5,     /* Interval between keepalives */
+    KEEPINTVL = 5,     /* Interval between keepalives */
+    KEEPCNT = 6,       /* Number of keepalives before death */
     WINDOW_CLAMP = 10, /* Bound advertised window */
+    INFO = 11,         /* Information about this connection. */
     CONGESTION = 13,   /* Congestion control algorithm */
 }
 
@@ -32,7 +36,11 @@ pub fn new_tcp_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
         CTcpOptionName::CONGESTION => Ok(Box::new(Congestion::new())),
         CTcpOptionName::MAXSEG => Ok(Box::new(MaxSegment::new())),
         CTcpOptionName::WINDOW_CLAMP => Ok(Box::new(WindowClamp::new())),
-        _ => todo!(),
+        CTcpOptionName::CORK => Ok(Box::new(Cork::new())),
+        CTcpOptionName::KEEPIDLE => Ok(Box::new(KeepIdle::new())),
+        CTcpOptionName::KEEPINTVL => Ok(Box::new(KeepInterval::new())),
+        CTcpOptionName::KEEPCNT => Ok(Box::new(KeepCount::new())),
+        CTcpOptionName::INFO => Ok(Box::new(Info::new())),
     }
 }
 
@@ -40,3 +48,8 @@ impl_raw_socket_option!(NoDelay);
 impl_raw_socket_option!(Congestion);
 impl_raw_socket_option!(MaxSegment);
 impl_raw_socket_option!(WindowClamp);
+impl_raw_socket_option!(Cork);
+impl_raw_socket_option!(KeepIdle);
+impl_raw_socket_option!(KeepInterval);
+impl_raw_socket_option!(KeepCount);
+impl_raw_sock_option_get_only!(Info);
@@ -4,8 +4,12 @@ use core::time::Duration;
 
 use crate::{
     get_current_userspace,
-    net::socket::{ip::stream::CongestionControl, LingerOption},
+    net::socket::{
+        ip::stream::{CongestionControl, TcpInfo},
+        LingerOption,
+    },
     prelude::*,
+    time::timeval_t,
 };
 
 /// Create an object by reading its C counterpart from the user space.
@@ -162,6 +166,94 @@ impl WriteToUser for CongestionControl {
     }
 }
 
+/// A socket timeout, where `None` means that the operation never times out.
+///
+/// In the user space, the timeout is represented by a `struct timeval`, where a zero value means
+/// that the operation never times out.
+impl ReadFromUser for Option<Duration> {
+    fn read_from_user(addr: Vaddr, max_len: u32) -> Result<Self> {
+        if (max_len as usize) < core::mem::size_of::<timeval_t>() {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let timeval = get_current_userspace!().read_val::<timeval_t>(addr)?;
+
+        // Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/net/core/sock.c#L446>
+        if !(0..1_000_000).contains(&timeval.usec) {
+            return_errno_with_message!(Errno::EDOM, "the timeout is out of range");
+        }
+        if timeval.sec < 0 {
+            return Ok(Some(Duration::ZERO));
+        }
+        if timeval.sec == 0 && timeval.usec == 0 {
+            return Ok(None);
+        }
+
+        Duration::try_from(timeval).map(Some)
+    }
+}
+
+impl WriteToUser for Option<Duration> {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        let write_len = core::mem::size_of::<timeval_t>();
+
+        if (max_len as usize) < write_len {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let timeval = timeval_t::from(self.unwrap_or(Duration::ZERO));
+        get_current_userspace!().write_val(addr, &timeval)?;
+        Ok(write_len)
+    }
+}
+
+/// A timeout that is always finite, such as the connect timeout of vsock sockets.
+///
+/// In the user space, the timeout is represented by a `struct timeval`.
+impl ReadFromUser for Duration {
+    fn read_from_user(addr: Vaddr, max_len: u32) -> Result<Self> {
+        if (max_len as usize) < core::mem::size_of::<timeval_t>() {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let timeval = get_current_userspace!().read_val::<timeval_t>(addr)?;
+
+        // Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/net/vmw_vsock/af_vsock.c>
+        if timeval.sec < 0 || !(0..1_000_000).contains(&timeval.usec) {
+            return_errno_with_message!(Errno::ERANGE, "the timeout is out of range");
+        }
+
+        Duration::try_from(timeval)
+    }
+}
+
+impl WriteToUser for Duration {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        let write_len = core::mem::size_of::<timeval_t>();
+
+        if (max_len as usize) < write_len {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let timeval = timeval_t::from(*self);
+        get_current_userspace!().write_val(addr, &timeval)?;
+        Ok(write_len)
+    }
+}
+
+impl WriteToUser for TcpInfo {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        // Linux truncates the information if the buffer is too short, so that user programs
+        // compiled against older headers keep working.
+        let write_len = core::mem::size_of::<TcpInfo>().min(max_len as usize);
+
+        let bytes = &self.as_bytes()[..write_len];
+        get_current_userspace!().write_bytes(addr, &mut VmReader::from(bytes))?;
+
+        Ok(write_len)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod)]
 struct CLinger {
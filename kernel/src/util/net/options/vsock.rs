@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::RawSocketOption;
+use crate::{
+    impl_raw_socket_option, net::socket::vsock::options::ConnectTimeout, prelude::*,
+    util::net::options::SocketOption,
+};
+
+/// Sock options for vsock sockets.
+///
+/// The raw definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/vm_sockets.h
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[allow(non_camel_case_types)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum CVsockOptionName {
+    CONNECT_TIMEOUT_OLD = 6, /* Also `SO_VM_SOCKETS_CONNECT_TIMEOUT` on 64-bit platforms */
+    CONNECT_TIMEOUT_NEW = 8, /* The same as the old one, since `timeval` has 64-bit fields */
+}
+
+pub fn new_vsock_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
+    let name = CVsockOptionName::try_from(name)?;
+    match name {
+        CVsockOptionName::CONNECT_TIMEOUT_OLD | CVsockOptionName::CONNECT_TIMEOUT_NEW => {
+            Ok(Box::new(ConnectTimeout::new()))
+        }
+    }
+}
+
+impl_raw_socket_option!(ConnectTimeout);
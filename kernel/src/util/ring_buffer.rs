@@ -242,6 +242,17 @@ impl RingBuffer<u8> {
         };
         consumer.read_fallible(writer)
     }
+
+    /// Reads data from the `RingBuffer` to the `writer` without consuming the data.
+    ///
+    /// Returns the number of bytes read.
+    pub fn peek_fallible(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        let consumer = Consumer {
+            rb: self,
+            phantom: PhantomData,
+        };
+        consumer.peek_fallible(writer)
+    }
 }
 
 impl<T: Pod, R: Deref<Target = RingBuffer<T>>> Producer<T, R> {
@@ -365,6 +376,23 @@ impl<T: Pod, R: Deref<Target = RingBuffer<T>>> Consumer<T, R> {
         Some(item)
     }
 
+    /// Returns the first item in the `RingBuffer` without popping it.
+    ///
+    /// Returns `None` if the ring buffer is empty.
+    pub fn peek(&self) -> Option<T> {
+        let rb = &self.rb;
+        if rb.is_empty() {
+            return None;
+        }
+
+        let head = rb.head();
+        debug_assert!(head < rb.capacity);
+
+        let segment_offset = head * Self::T_SIZE;
+        let mut reader = rb.segment.reader().skip(segment_offset);
+        Some(reader.read_val::<T>().unwrap())
+    }
+
     /// Pops a slice of items from the `RingBuffer`.
     ///
     /// Returns `Some` on success, all items are popped from the ring buffer.
@@ -402,6 +430,17 @@ impl<R: Deref<Target = RingBuffer<u8>>> Consumer<u8, R> {
     ///
     /// Returns the number of bytes read.
     pub fn read_fallible(&mut self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        let read_len = self.peek_fallible(writer)?;
+
+        let rb = &self.rb;
+        rb.advance_head(rb.head(), read_len);
+        Ok(read_len)
+    }
+
+    /// Reads data from the `RingBuffer` to the `VmWriter` without consuming the data.
+    ///
+    /// Returns the number of bytes read.
+    pub fn peek_fallible(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
         let rb = &self.rb;
         let len = rb.len();
         if len == 0 {
@@ -423,7 +462,6 @@ impl<R: Deref<Target = RingBuffer<u8>>> Consumer<u8, R> {
             writer.write(&mut reader)?
         };
 
-        rb.advance_head(head, read_len);
         Ok(read_len)
     }
 }
@@ -518,6 +556,35 @@ mod test {
         assert_eq!(output, input);
     }
 
+    #[ktest]
+    fn test_rb_peek() {
+        let rb = RingBuffer::<u8>::new(4);
+
+        let (mut prod, mut cons) = rb.split();
+        assert!(cons.peek().is_none());
+
+        prod.push_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(cons.peek().unwrap(), 1u8);
+
+        let mut output = [0u8; 2];
+        assert_eq!(
+            cons.peek_fallible(&mut writer_from(output.as_mut_slice()))
+                .unwrap(),
+            2
+        );
+        assert_eq!(output, [1u8, 2]);
+        assert_eq!(cons.len(), 3);
+
+        let mut output = [0u8; 4];
+        assert_eq!(
+            cons.read_fallible(&mut writer_from(output.as_mut_slice()))
+                .unwrap(),
+            3
+        );
+        assert_eq!(output[..3], [1u8, 2, 3]);
+        assert!(cons.is_empty());
+    }
+
     #[ktest]
     fn test_rb_write_read_all() {
         let rb = RingBuffer::<u8>::new(4 * PAGE_SIZE);
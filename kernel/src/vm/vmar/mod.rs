@@ -16,7 +16,7 @@ use align_ext::AlignExt;
 use aster_rights::Rights;
 use ostd::{
     cpu::CpuExceptionInfo,
-    mm::{tlb::TlbFlushOp, PageFlags, PageProperty, VmSpace, MAX_USERSPACE_VADDR},
+    mm::{tlb::TlbFlushOp, Frame, PageFlags, PageProperty, VmIo, VmSpace, MAX_USERSPACE_VADDR},
 };
 
 use self::{
@@ -103,6 +103,49 @@ impl<R> Vmar<R> {
     pub fn resize_mapping(&self, map_addr: Vaddr, old_size: usize, new_size: usize) -> Result<()> {
         self.0.resize_mapping(map_addr, old_size, new_size)
     }
+
+    /// Unmaps the System V shared memory segment that is attached at `addr`.
+    ///
+    /// Like Linux's `shmdt`, the segment is found in the first mapping at or above `addr` that
+    /// maps the segment as if it was attached at `addr`. Then all the mappings of the same
+    /// segment attached at `addr` are unmapped, even if the original mapping has been split or
+    /// partially unmapped, while the other mappings in the range are kept intact.
+    pub fn detach_shm(&self, addr: Vaddr) -> Result<()> {
+        self.0.detach_shm(addr)
+    }
+
+    /// Reads the memory at `vaddr` into `buf` on behalf of another task, e.g., via `ptrace`.
+    ///
+    /// Unlike accessing the memory via [`VmSpace::reader`], this method works even if this VMAR
+    /// is not the one of the current task, and it ignores the permissions of the mappings.
+    pub fn read_foreign(&self, vaddr: Vaddr, buf: &mut [u8]) -> Result<()> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            let addr = vaddr + offset;
+            let len = (PAGE_SIZE - addr % PAGE_SIZE).min(buf.len() - offset);
+            let frame = self.0.get_frame_for_foreign_access(addr, false)?;
+            frame.read_bytes(addr % PAGE_SIZE, &mut buf[offset..offset + len])?;
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Writes `buf` to the memory at `vaddr` on behalf of another task, e.g., via `ptrace`.
+    ///
+    /// Unlike accessing the memory via [`VmSpace::writer`], this method works even if this VMAR
+    /// is not the one of the current task, and it can write to a non-writable private mapping
+    /// (e.g., to set a breakpoint in the code).
+    pub fn write_foreign(&self, vaddr: Vaddr, buf: &[u8]) -> Result<()> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            let addr = vaddr + offset;
+            let len = (PAGE_SIZE - addr % PAGE_SIZE).min(buf.len() - offset);
+            let frame = self.0.get_frame_for_foreign_access(addr, true)?;
+            frame.write_bytes(addr % PAGE_SIZE, &buf[offset..offset + len])?;
+            offset += len;
+        }
+        Ok(())
+    }
 }
 
 pub(super) struct Vmar_ {
@@ -322,6 +365,28 @@ impl Vmar_ {
         return_errno_with_message!(Errno::EACCES, "page fault addr is not in current vmar");
     }
 
+    /// Gets the frame at `address` for an access on behalf of another task.
+    ///
+    /// See [`VmMapping::get_frame_for_foreign_access`] for details.
+    fn get_frame_for_foreign_access(&self, address: Vaddr, is_write: bool) -> Result<Frame> {
+        if !(self.base..self.base + self.size).contains(&address) {
+            return_errno_with_message!(Errno::EFAULT, "the address is not in current vmar");
+        }
+
+        let inner = self.inner.lock();
+        if let Some(child_vmar) = inner.child_vmar_s.find_one(&address) {
+            debug_assert!(child_vmar.range().contains(&address));
+            return child_vmar.get_frame_for_foreign_access(address, is_write);
+        }
+
+        if let Some(vm_mapping) = inner.vm_mappings.find_one(&address) {
+            debug_assert!(vm_mapping.range().contains(&address));
+            return vm_mapping.get_frame_for_foreign_access(address, is_write);
+        }
+
+        return_errno_with_message!(Errno::EFAULT, "the address is not mapped");
+    }
+
     /// Clears all content of the root VMAR.
     fn clear_root_vmar(&self) -> Result<()> {
         debug_assert!(self.is_root_vmar());
@@ -391,6 +456,48 @@ impl Vmar_ {
         Ok(())
     }
 
+    fn detach_shm(&self, addr: Vaddr) -> Result<()> {
+        let shm_ranges = {
+            // A mapping is attached at `addr` if it maps the segment as if the segment started
+            // at `addr`.
+            let is_attached_at_addr = |vm_mapping: &Arc<VmMapping>| {
+                vm_mapping.shm().is_some()
+                    && vm_mapping.map_to_addr().checked_sub(addr) == vm_mapping.vmo_offset()
+            };
+
+            let inner = self.inner.lock();
+            let Some(shm) = inner
+                .vm_mappings
+                .range(addr..)
+                .map(|(_, vm_mapping)| vm_mapping)
+                .find(|vm_mapping| is_attached_at_addr(vm_mapping))
+                .and_then(|vm_mapping| vm_mapping.shm())
+                .cloned()
+            else {
+                return_errno_with_message!(Errno::EINVAL, "no segment is attached at addr");
+            };
+
+            let end = addr + shm.size().align_up(PAGE_SIZE);
+            inner
+                .vm_mappings
+                .range(addr..end)
+                .map(|(_, vm_mapping)| vm_mapping)
+                .filter(|vm_mapping| {
+                    is_attached_at_addr(vm_mapping)
+                        && vm_mapping
+                            .shm()
+                            .is_some_and(|mapped_shm| Arc::ptr_eq(mapped_shm, &shm))
+                })
+                .map(|vm_mapping| vm_mapping.range())
+                .collect::<Vec<_>>()
+        };
+
+        for range in shm_ranges {
+            self.destroy(range)?;
+        }
+        Ok(())
+    }
+
     fn resize_mapping(&self, map_addr: Vaddr, old_size: usize, new_size: usize) -> Result<()> {
         debug_assert!(map_addr % PAGE_SIZE == 0);
         debug_assert!(old_size % PAGE_SIZE == 0);
@@ -17,6 +17,7 @@ use ostd::mm::{
 
 use super::{interval::Interval, is_intersected, Vmar, Vmar_};
 use crate::{
+    ipc::shm::{shared_memory::SharedMemory, ShmAttachment},
     prelude::*,
     thread::exception::PageFaultInfo,
     vm::{
@@ -51,22 +52,59 @@ pub(super) struct VmMapping {
     /// or are carried through to the underlying file for
     /// file-backed shared mappings.
     is_shared: bool,
+    /// Whether the mapping may be made writable.
+    ///
+    /// If a shared mapping may be made writable, it is recorded in the VMO
+    /// (see [`Vmo::add_writable_mapping`]).
+    may_write: bool,
     /// Whether the mapping needs to handle surrounding pages when handling page fault.
     handle_page_faults_around: bool,
+    /// The attachment of the System V shared memory segment, if the mapping maps one.
+    shm_attachment: Option<ShmAttachment>,
 }
 
 impl VmMapping {
     pub fn try_clone(&self) -> Result<Self> {
         let inner = self.inner.lock().clone();
-        let vmo = self.vmo.as_ref().map(|vmo| vmo.dup()).transpose()?;
+        let vmo = self.dup_vmo()?;
         Ok(Self {
             inner: Mutex::new(inner),
             parent: self.parent.clone(),
             vmo,
             is_shared: self.is_shared,
+            may_write: self.may_write,
             handle_page_faults_around: self.handle_page_faults_around,
+            shm_attachment: self.shm_attachment.clone(),
         })
     }
+
+    /// Duplicates the mapped VMO for a new mapping with the same properties as this one.
+    fn dup_vmo(&self) -> Result<Option<MappedVmo>> {
+        let Some(vmo) = &self.vmo else {
+            return Ok(None);
+        };
+
+        let new_vmo = vmo.dup()?;
+        if self.is_writable_shared() {
+            new_vmo.vmo.add_writable_mapping()?;
+        }
+        Ok(Some(new_vmo))
+    }
+
+    /// Returns whether the mapping is a shared mapping that may be made writable.
+    fn is_writable_shared(&self) -> bool {
+        self.is_shared && self.may_write
+    }
+}
+
+impl Drop for VmMapping {
+    fn drop(&mut self) {
+        if let Some(vmo) = &self.vmo
+            && self.is_writable_shared()
+        {
+            vmo.vmo.remove_writable_mapping();
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -104,11 +142,23 @@ impl VmMapping {
             align,
             can_overwrite,
             is_shared,
+            may_write,
             handle_page_faults_around,
+            shm,
         } = option;
         let Vmar(parent_vmar, _) = parent;
-        let map_to_addr =
-            parent_vmar.allocate_free_region_for_mapping(size, offset, align, can_overwrite)?;
+
+        let writable_shared_vmo = vmo.as_ref().filter(|_| is_shared && may_write);
+        if let Some(vmo) = writable_shared_vmo {
+            vmo.add_writable_mapping()?;
+        }
+        let map_to_addr = parent_vmar
+            .allocate_free_region_for_mapping(size, offset, align, can_overwrite)
+            .inspect_err(|_| {
+                if let Some(vmo) = writable_shared_vmo {
+                    vmo.remove_writable_mapping();
+                }
+            })?;
         trace!(
             "build mapping, map_range = 0x{:x}- 0x{:x}",
             map_to_addr,
@@ -139,7 +189,9 @@ impl VmMapping {
             parent: Arc::downgrade(&parent_vmar),
             vmo,
             is_shared,
+            may_write,
             handle_page_faults_around,
+            shm_attachment: shm.map(ShmAttachment::new),
         })
     }
 
@@ -169,6 +221,16 @@ impl VmMapping {
         self.vmo.as_ref()
     }
 
+    /// Returns the System V shared memory segment mapped by the mapping, if any.
+    pub fn shm(&self) -> Option<&Arc<SharedMemory>> {
+        self.shm_attachment.as_ref().map(ShmAttachment::shm)
+    }
+
+    /// Returns the offset in the mapped VMO where the mapping starts.
+    pub fn vmo_offset(&self) -> Option<usize> {
+        self.inner.lock().vmo_offset
+    }
+
     /// Returns the mapping's start address.
     pub fn map_to_addr(&self) -> Vaddr {
         self.inner.lock().map_to_addr
@@ -286,6 +348,52 @@ impl VmMapping {
         Ok(())
     }
 
+    /// Gets the frame at `address` for an access on behalf of another task, e.g., via `ptrace`.
+    ///
+    /// Like `FOLL_FORCE` in Linux, the access is allowed even if the mapping is not readable or
+    /// writable, except that a write access to a non-writable shared mapping is not allowed. For
+    /// a write access to a private mapping, the page is copied if it has not been, so that the
+    /// write access will not be visible in other mappings.
+    pub fn get_frame_for_foreign_access(&self, address: Vaddr, is_write: bool) -> Result<Frame> {
+        let inner_lock = self.inner.lock();
+        if is_write && self.is_shared && !inner_lock.perms.contains(VmPerms::WRITE) {
+            return_errno_with_message!(Errno::EFAULT, "the shared mapping is not writable");
+        }
+
+        let page_aligned_addr = address.align_down(PAGE_SIZE);
+        let root_vmar = self.parent.upgrade().unwrap();
+        let mut cursor = root_vmar
+            .vm_space()
+            .cursor_mut(&(page_aligned_addr..page_aligned_addr + PAGE_SIZE))?;
+
+        match cursor.query().unwrap() {
+            VmItem::Mapped { frame, prop, .. } => {
+                if !is_write || self.is_shared || prop.flags.contains(PageFlags::W) {
+                    return Ok(frame);
+                }
+
+                // Perform COW while keeping the page property, which may be read-only.
+                let new_frame = duplicate_frame(&frame)?;
+                cursor.map(new_frame.clone(), prop);
+                Ok(new_frame)
+            }
+            VmItem::NotMapped { .. } => {
+                let (frame, is_readonly) = self.prepare_page(&inner_lock, address, is_write)?;
+
+                let mut vm_perms = inner_lock.perms;
+                if is_readonly {
+                    vm_perms -= VmPerms::WRITE;
+                }
+                let mut page_flags = vm_perms.into();
+                page_flags |= PageFlags::ACCESSED;
+                let map_prop = PageProperty::new(page_flags, CachePolicy::Writeback);
+
+                cursor.map(frame.clone(), map_prop);
+                Ok(frame)
+            }
+        }
+    }
+
     fn prepare_page(
         &self,
         inner_lock: &MutexGuard<VmMappingInner>,
@@ -380,6 +488,9 @@ impl VmMapping {
         if old_perms == new_perms {
             return Ok(());
         }
+        if new_perms.contains(VmPerms::WRITE) && self.is_shared && !self.may_write {
+            return_errno_with_message!(Errno::EACCES, "the shared mapping cannot be writable");
+        }
 
         // Protect permission for the perm in the VmMapping.
         self.protect_with_subdivision(&range, new_perms)?;
@@ -397,9 +508,11 @@ impl VmMapping {
         Ok(VmMapping {
             inner: Mutex::new(new_inner),
             parent: Arc::downgrade(new_parent),
-            vmo: self.vmo.as_ref().map(|vmo| vmo.dup()).transpose()?,
+            vmo: self.dup_vmo()?,
             is_shared: self.is_shared,
+            may_write: self.may_write,
             handle_page_faults_around: self.handle_page_faults_around,
+            shm_attachment: self.shm_attachment.clone(),
         })
     }
 
@@ -648,8 +761,12 @@ pub struct VmarMapOptions<R1, R2> {
     can_overwrite: bool,
     // Whether the mapping is mapped with `MAP_SHARED`
     is_shared: bool,
+    // Whether the mapping may be made writable
+    may_write: bool,
     // Whether the mapping needs to handle surrounding pages when handling page fault.
     handle_page_faults_around: bool,
+    // The System V shared memory segment that is mapped
+    shm: Option<Arc<SharedMemory>>,
 }
 
 impl<R1, R2> VmarMapOptions<R1, R2> {
@@ -671,7 +788,9 @@ impl<R1, R2> VmarMapOptions<R1, R2> {
             align: PAGE_SIZE,
             can_overwrite: false,
             is_shared: false,
+            may_write: true,
             handle_page_faults_around: false,
+            shm: None,
         }
     }
 
@@ -758,12 +877,31 @@ impl<R1, R2> VmarMapOptions<R1, R2> {
         self
     }
 
+    /// Sets whether the mapping may be made writable later, e.g., by `mprotect`.
+    ///
+    /// The default value is true.
+    ///
+    /// This value should be false for the shared mappings of a file that is
+    /// not opened for writing or is sealed for writing.
+    pub fn may_write(mut self, may_write: bool) -> Self {
+        self.may_write = may_write;
+        self
+    }
+
     /// Sets the mapping to handle surrounding pages when handling page fault.
     pub fn handle_page_faults_around(mut self) -> Self {
         self.handle_page_faults_around = true;
         self
     }
 
+    /// Sets the mapping to map the System V shared memory segment `shm`.
+    ///
+    /// The segment is attached as long as the mapping (or any part of it) is alive.
+    pub fn shm(mut self, shm: Arc<SharedMemory>) -> Self {
+        self.shm = Some(shm);
+        self
+    }
+
     /// Creates the mapping.
     ///
     /// All options will be checked at this point.
@@ -5,7 +5,10 @@
 
 //! Virtual Memory Objects (VMOs).
 
-use core::ops::Range;
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicIsize, AtomicUsize, Ordering},
+};
 
 use align_ext::AlignExt;
 use aster_rights::Rights;
@@ -14,7 +17,10 @@ use ostd::{
     mm::{Frame, FrameAllocOptions, VmReader, VmWriter},
 };
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    process::{cgroup::Cgroup, Process},
+};
 
 mod dyn_cap;
 mod options;
@@ -174,6 +180,110 @@ pub(super) struct Vmo_ {
     flags: VmoFlags,
     /// The virtual pages where the VMO resides.
     pages: Pages,
+    /// The charge of the committed pages to a memory cgroup.
+    ///
+    /// Only anonymous VMOs created by processes are charged.
+    mem_charge: Option<MemCharge>,
+    /// The writable shared mappings of the VMO.
+    writable_mappings: WritableMappings,
+}
+
+/// The charge of the committed pages of a VMO to the cgroup of the process that creates the VMO.
+struct MemCharge {
+    cgroup: Arc<Cgroup>,
+    nr_pages: AtomicUsize,
+}
+
+impl MemCharge {
+    /// Creates an empty charge to the cgroup of the current process.
+    ///
+    /// Returns `None` if the current task does not belong to a process.
+    fn new_for_current() -> Option<Self> {
+        let cgroup = Process::current()?.cgroup();
+        Some(Self {
+            cgroup,
+            nr_pages: AtomicUsize::new(0),
+        })
+    }
+
+    fn try_charge_pages(&self, nr_pages: usize) -> Result<()> {
+        self.cgroup.try_charge_memory(nr_pages * PAGE_SIZE)?;
+        self.nr_pages.fetch_add(nr_pages, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn force_charge_pages(&self, nr_pages: usize) {
+        self.cgroup.force_charge_memory(nr_pages * PAGE_SIZE);
+        self.nr_pages.fetch_add(nr_pages, Ordering::Relaxed);
+    }
+
+    fn uncharge_pages(&self, nr_pages: usize) {
+        self.cgroup.uncharge_memory(nr_pages * PAGE_SIZE);
+        self.nr_pages.fetch_sub(nr_pages, Ordering::Relaxed);
+    }
+}
+
+impl Clone for MemCharge {
+    /// Charges the cgroup again for the pages of the duplicated VMO.
+    fn clone(&self) -> Self {
+        let nr_pages = self.nr_pages.load(Ordering::Relaxed);
+        self.cgroup.force_charge_memory(nr_pages * PAGE_SIZE);
+        Self {
+            cgroup: self.cgroup.clone(),
+            nr_pages: AtomicUsize::new(nr_pages),
+        }
+    }
+}
+
+impl Drop for MemCharge {
+    fn drop(&mut self) {
+        let nr_pages = self.nr_pages.load(Ordering::Relaxed);
+        self.cgroup.uncharge_memory(nr_pages * PAGE_SIZE);
+    }
+}
+
+/// The number of the shared mappings through which a VMO may be written.
+///
+/// A negative value means that such mappings are denied, e.g., when the file
+/// of the VMO is sealed for writing. This is similar to `i_mmap_writable` in Linux.
+struct WritableMappings(AtomicIsize);
+
+impl WritableMappings {
+    fn new() -> Self {
+        Self(AtomicIsize::new(0))
+    }
+
+    fn try_add(&self) -> Result<()> {
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count >= 0).then_some(count + 1)
+            })
+            .map_err(|_| {
+                Error::with_message(Errno::EPERM, "writable shared mappings are denied")
+            })?;
+        Ok(())
+    }
+
+    fn remove(&self) {
+        let old_count = self.0.fetch_sub(1, Ordering::AcqRel);
+        debug_assert!(old_count > 0);
+    }
+
+    fn try_deny(&self) -> Result<()> {
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count <= 0).then_some(count - 1)
+            })
+            .map_err(|_| Error::with_message(Errno::EBUSY, "there are writable shared mappings"))?;
+        Ok(())
+    }
+}
+
+impl Clone for WritableMappings {
+    /// The duplicated VMO is not mapped anywhere.
+    fn clone(&self) -> Self {
+        Self::new()
+    }
 }
 
 bitflags! {
@@ -195,7 +305,7 @@ impl Vmo_ {
     /// Prepares a new `Frame` for the target index in pages, returns this new frame.
     fn prepare_page(&self, page_idx: usize) -> Result<Frame> {
         match &self.pager {
-            None => Ok(FrameAllocOptions::new(1).alloc_single()?),
+            None => self.alloc_page(),
             Some(pager) => pager.commit_page(page_idx),
         }
     }
@@ -205,7 +315,27 @@ impl Vmo_ {
         if let Some(pager) = &self.pager {
             pager.commit_overwrite(page_idx)
         } else {
-            Ok(FrameAllocOptions::new(1).alloc_single()?)
+            self.alloc_page()
+        }
+    }
+
+    /// Allocates a new `Frame` for the VMO without a pager and charges the memory cgroup for it.
+    fn alloc_page(&self) -> Result<Frame> {
+        let Some(mem_charge) = &self.mem_charge else {
+            return Ok(FrameAllocOptions::new(1).alloc_single()?);
+        };
+
+        mem_charge.try_charge_pages(1)?;
+        let frame = FrameAllocOptions::new(1)
+            .alloc_single()
+            .inspect_err(|_| mem_charge.uncharge_pages(1))?;
+        Ok(frame)
+    }
+
+    /// Uncharges the memory cgroup for a page that is removed from the VMO.
+    fn uncharge_page(&self) {
+        if let Some(mem_charge) = &self.mem_charge {
+            mem_charge.uncharge_pages(1);
         }
     }
 
@@ -251,10 +381,11 @@ impl Vmo_ {
                 return_errno_with_message!(Errno::EINVAL, "the offset is outside the VMO");
             }
             let mut cursor = pages.cursor_mut(page_idx as u64);
-            if cursor.remove().is_some()
-                && let Some(pager) = &self.pager
-            {
-                pager.decommit_page(page_idx)?;
+            if cursor.remove().is_some() {
+                self.uncharge_page();
+                if let Some(pager) = &self.pager {
+                    pager.decommit_page(page_idx)?;
+                }
             }
             Ok(())
         })
@@ -396,10 +527,11 @@ impl Vmo_ {
         let page_idx_range = get_page_idx_range(&range);
         let mut cursor = pages.cursor_mut(page_idx_range.start as u64);
         for page_idx in page_idx_range {
-            if cursor.remove().is_some()
-                && let Some(pager) = &self.pager
-            {
-                pager.decommit_page(page_idx)?;
+            if cursor.remove().is_some() {
+                self.uncharge_page();
+                if let Some(pager) = &self.pager {
+                    pager.decommit_page(page_idx)?;
+                }
             }
             cursor.next();
         }
@@ -422,6 +554,11 @@ impl Vmo_ {
             if page_idx >= size / PAGE_SIZE {
                 return_errno_with_message!(Errno::EINVAL, "the page index is outside of the vmo");
             }
+            if let Some(mem_charge) = &self.mem_charge
+                && pages.load(page_idx as u64).is_none()
+            {
+                mem_charge.force_charge_pages(1);
+            }
             pages.store(page_idx as u64, page);
             Ok(())
         })
@@ -438,6 +575,26 @@ impl<R> Vmo<R> {
     pub fn flags(&self) -> VmoFlags {
         self.0.flags()
     }
+
+    /// Records a new shared mapping through which the VMO may be written.
+    ///
+    /// This method fails with `EPERM` if such mappings are denied
+    /// by [`Self::deny_writable_mappings`].
+    pub fn add_writable_mapping(&self) -> Result<()> {
+        self.0.writable_mappings.try_add()
+    }
+
+    /// Removes a shared mapping recorded by [`Self::add_writable_mapping`].
+    pub fn remove_writable_mapping(&self) {
+        self.0.writable_mappings.remove()
+    }
+
+    /// Denies new shared mappings through which the VMO may be written.
+    ///
+    /// This method fails with `EBUSY` if there are such mappings.
+    pub fn deny_writable_mappings(&self) -> Result<()> {
+        self.0.writable_mappings.try_deny()
+    }
 }
 
 /// Gets the page index range that contains the offset range of VMO.
@@ -11,7 +11,7 @@ use ostd::{
     mm::{Frame, FrameAllocOptions},
 };
 
-use super::{Pager, Pages, Vmo, VmoFlags};
+use super::{MemCharge, Pager, Pages, Vmo, VmoFlags, WritableMappings};
 use crate::{prelude::*, vm::vmo::Vmo_};
 
 /// Options for allocating a root VMO.
@@ -122,8 +122,15 @@ impl<R: TRights> VmoOptions<TRightSet<R>> {
 
 fn alloc_vmo_(size: usize, flags: VmoFlags, pager: Option<Arc<dyn Pager>>) -> Result<Vmo_> {
     let size = size.align_up(PAGE_SIZE);
+    // The pages provided by a pager belong to the pager,
+    // so only the VMOs without pagers are charged.
+    let mem_charge = if pager.is_none() {
+        MemCharge::new_for_current()
+    } else {
+        None
+    };
     let pages = {
-        let pages = committed_pages_if_continuous(flags, size)?;
+        let pages = committed_pages_if_continuous(flags, size, mem_charge.as_ref())?;
         if flags.contains(VmoFlags::RESIZABLE) {
             Pages::Resizable(Mutex::new((pages, size)))
         } else {
@@ -134,13 +141,22 @@ fn alloc_vmo_(size: usize, flags: VmoFlags, pager: Option<Arc<dyn Pager>>) -> Re
         pager,
         flags,
         pages,
+        mem_charge,
+        writable_mappings: WritableMappings::new(),
     })
 }
 
-fn committed_pages_if_continuous(flags: VmoFlags, size: usize) -> Result<XArray<Frame>> {
+fn committed_pages_if_continuous(
+    flags: VmoFlags,
+    size: usize,
+    mem_charge: Option<&MemCharge>,
+) -> Result<XArray<Frame>> {
     if flags.contains(VmoFlags::CONTIGUOUS) {
         // if the vmo is continuous, we need to allocate frames for the vmo
         let frames_num = size / PAGE_SIZE;
+        if let Some(mem_charge) = mem_charge {
+            mem_charge.try_charge_pages(frames_num)?;
+        }
         let frames = FrameAllocOptions::new(frames_num)
             .is_contiguous(true)
             .alloc()?;
@@ -139,6 +139,13 @@ impl Drop for IrqCallbackHandle {
     }
 }
 
+/// Returns the address of the MSI messages that are delivered to the specified CPU.
+///
+/// Returns `None` since steering the MSIs to specific CPUs is not supported yet.
+pub(crate) fn msi_address_of_cpu(cpu_id: u32) -> Option<u32> {
+    None
+}
+
 /// Sends a general inter-processor interrupt (IPI) to the specified CPU.
 ///
 /// # Safety
@@ -156,6 +156,21 @@ impl Drop for IrqCallbackHandle {
     }
 }
 
+/// Returns the address of the MSI messages that are delivered to the specified CPU.
+///
+/// The destination ID field of the message address has only 8 bits, so this function returns
+/// `None` if the local APIC ID of the CPU is larger than 255, which can only be targeted with
+/// interrupt remapping. It also returns `None` if the local APIC of the CPU is not initialized.
+pub(crate) fn msi_address_of_cpu(cpu_id: u32) -> Option<u32> {
+    const MSI_ADDRESS_BASE: u32 = 0xFEE0_0000;
+
+    let apic_id = crate::arch::kernel::apic::local_apic_id(cpu_id)?;
+    if apic_id > 0xFF {
+        return None;
+    }
+    Some(MSI_ADDRESS_BASE | (apic_id << 12))
+}
+
 /// Sends a general inter-processor interrupt (IPI) to the specified CPU.
 ///
 /// # Safety
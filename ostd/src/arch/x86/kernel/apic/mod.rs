@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::boxed::Box;
-use core::cell::RefCell;
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use bit_field::BitField;
 use spin::Once;
@@ -14,8 +17,13 @@ pub mod xapic;
 
 cpu_local! {
     static APIC_INSTANCE: RefCell<Option<Box<dyn Apic + 'static>>> = RefCell::new(None);
+    /// The ID of the local APIC, which is recorded when the local APIC is initialized.
+    static APIC_ID: AtomicU32 = AtomicU32::new(UNKNOWN_APIC_ID);
 }
 
+/// The all-ones ID is reserved for broadcast, so it never identifies a local APIC.
+const UNKNOWN_APIC_ID: u32 = u32::MAX;
+
 static APIC_TYPE: Once<ApicType> = Once::new();
 
 /// Do something over the APIC instance for the current CPU.
@@ -73,6 +81,10 @@ pub fn with_borrow<R>(f: impl FnOnce(&mut (dyn Apic + 'static)) -> R) -> R {
             }
         });
 
+        APIC_ID
+            .get_with(&irq_guard)
+            .store(apic_init_ref.as_ref().unwrap().id(), Ordering::Relaxed);
+
         apic_init_ref.as_mut().unwrap()
     };
 
@@ -81,6 +93,16 @@ pub fn with_borrow<R>(f: impl FnOnce(&mut (dyn Apic + 'static)) -> R) -> R {
     ret
 }
 
+/// Returns the ID of the local APIC of a CPU.
+///
+/// The APIC IDs are assigned by the hardware and may be sparse, so they are not necessarily the
+/// same as the CPU IDs. This function returns `None` if the local APIC of the CPU has not been
+/// initialized yet.
+pub fn local_apic_id(cpu_id: u32) -> Option<u32> {
+    let apic_id = APIC_ID.get_on_cpu(cpu_id).load(Ordering::Relaxed);
+    (apic_id != UNKNOWN_APIC_ID).then_some(apic_id)
+}
+
 pub trait Apic: ApicTimer + Sync + Send {
     fn id(&self) -> u32;
 
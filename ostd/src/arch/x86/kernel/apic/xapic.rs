@@ -61,7 +61,8 @@ impl XApic {
 
 impl super::Apic for XApic {
     fn id(&self) -> u32 {
-        self.read(xapic::XAPIC_ID)
+        // The xAPIC ID is in bits 24-31 of the register.
+        self.read(xapic::XAPIC_ID) >> 24
     }
 
     fn version(&self) -> u32 {
@@ -182,6 +182,37 @@ impl CapabilityMsixData {
             .unwrap();
     }
 
+    /// Sets the CPU that the interrupt of an MSI-X entry is delivered to.
+    ///
+    /// By default, all the interrupts are delivered to the bootstrap processor. This function
+    /// returns `false` if the interrupt cannot be steered to the CPU, in which case the entry is
+    /// left unchanged.
+    pub fn set_interrupt_cpu(&mut self, index: u16, cpu_id: u32) -> bool {
+        if index >= self.table_size {
+            return false;
+        }
+        let Some(message_address) = crate::arch::irq::msi_address_of_cpu(cpu_id) else {
+            return false;
+        };
+
+        // Mask this msix vector before updating its message address
+        self.table_bar
+            .io_mem()
+            .write_once((16 * index + 12) as usize + self.table_offset, &1_u32)
+            .unwrap();
+        self.table_bar
+            .io_mem()
+            .write_once((16 * index) as usize + self.table_offset, &message_address)
+            .unwrap();
+        if self.irqs[index as usize].is_some() {
+            self.table_bar
+                .io_mem()
+                .write_once((16 * index + 12) as usize + self.table_offset, &0_u32)
+                .unwrap();
+        }
+        true
+    }
+
     /// Gets mutable IrqLine. User can register callbacks by using this function.
     pub fn irq_mut(&mut self, index: usize) -> Option<&mut IrqLine> {
         self.irqs[index].as_mut()
@@ -34,6 +34,11 @@ pub struct Task {
     /// kernel stack, note that the top is SyscallFrame/TrapFrame
     #[allow(dead_code)]
     kstack: KernelStack,
+    /// The CPU that the task is running on.
+    ///
+    /// It is set when the task is switched to and cleared only after the context
+    /// of the task has been saved when it is switched out.
+    on_cpu: AtomicCpuId,
 
     schedule_info: TaskScheduleInfo,
 }
@@ -168,6 +173,8 @@ impl TaskOptions {
         /// all task will entering this function
         /// this function is mean to executing the task_fn in Task
         extern "C" fn kernel_task_entry() {
+            processor::after_switch_to_task();
+
             let current_task = current_task()
                 .expect("no current task, it should have current task in kernel task entry");
             current_task.func.call(());
@@ -199,6 +206,7 @@ impl TaskOptions {
             user_space: self.user_space,
             ctx,
             kstack,
+            on_cpu: AtomicCpuId::default(),
             schedule_info: TaskScheduleInfo {
                 cpu: AtomicCpuId::default(),
             },
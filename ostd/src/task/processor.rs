@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::sync::Arc;
+use core::sync::atomic::{fence, Ordering};
 
-use super::{context_switch, Task, TaskContext};
-use crate::cpu_local_cell;
+use super::{context_switch, scheduler::info::CommonSchedInfo, Task, TaskContext};
+use crate::{cpu::PinCurrentCpu, cpu_local_cell};
 
 cpu_local_cell! {
     /// The `Arc<Task>` (casted by [`Arc::into_raw`]) that is the current task.
@@ -46,6 +47,7 @@ pub(super) fn switch_to_task(next_task: Arc<Task>) {
     super::atomic_mode::might_sleep();
 
     let irq_guard = crate::trap::disable_local();
+    let this_cpu = irq_guard.current_cpu();
 
     let current_task_ptr = CURRENT_TASK_PTR.load();
     let current_task_ctx_ptr = if current_task_ptr.is_null() {
@@ -63,6 +65,16 @@ pub(super) fn switch_to_task(next_task: Arc<Task>) {
         ctx_ptr
     };
 
+    // The next task may have just been switched out on another CPU. Its context can only be
+    // loaded after it has been completely saved there.
+    //
+    // The schedulers should avoid this by enqueuing the task to the CPU that it is running on
+    // (see `CommonSchedInfo::on_cpu`). Otherwise, this may wait for a while.
+    while next_task.on_cpu().is_some_and(|cpu| cpu != this_cpu) {
+        core::hint::spin_loop();
+    }
+    let _ = next_task.on_cpu.set_if_is_none(this_cpu);
+
     let next_task_ctx_ptr = next_task.ctx().get().cast_const();
     if let Some(next_user_space) = next_task.user_space() {
         next_user_space.vm_space().activate();
@@ -87,7 +99,9 @@ pub(super) fn switch_to_task(next_task: Arc<Task>) {
 
     // SAFETY:
     // 1. `ctx` is only used in `reschedule()`. We have exclusive access to both the current task
-    //    context and the next task context.
+    //    context and the next task context. The tasks can be migrated between CPUs, but the next
+    //    task is not running on other CPUs, as we have waited for it to be switched out above. The
+    //    current task will not run on other CPUs until `after_switch_to_task` is called.
     // 2. The next task context is a valid task context.
     unsafe {
         // This function may not return, for example, when the current task exits. So make sure
@@ -95,8 +109,33 @@ pub(super) fn switch_to_task(next_task: Arc<Task>) {
         context_switch(current_task_ctx_ptr, next_task_ctx_ptr);
     }
 
+    after_switch_to_task();
+
     // Now it's fine to drop `prev_task`. However, we choose not to do this because it is not
     // always possible. For example, `context_switch` can switch directly to the entry point of the
     // next task. Not dropping is just fine because the only consequence is that we delay the drop
     // to the next task switching.
 }
+
+/// Marks the previous task as no longer running on the processor.
+///
+/// This must be called once the current task starts running after switching from the
+/// previous task, i.e., after `context_switch` returns or at the entry point of a new task,
+/// when the context of the previous task has been saved.
+pub(super) fn after_switch_to_task() {
+    let _irq_guard = crate::trap::disable_local();
+
+    let prev_task_ptr = PREVIOUS_TASK_PTR.load();
+    // The task may switch to itself, and then it is still running.
+    if prev_task_ptr.is_null() || prev_task_ptr == CURRENT_TASK_PTR.load() {
+        return;
+    }
+
+    // SAFETY: The pointer is set by `switch_to_task` and is guaranteed to be built with
+    // `Arc::into_raw`. It will not be dropped until the next task switching, which cannot happen
+    // with interrupts disabled.
+    let prev_task = unsafe { &*prev_task_ptr };
+    // Make sure that the saved context is visible to the CPU that switches to the task next time.
+    fence(Ordering::Release);
+    prev_task.on_cpu.set_to_none();
+}
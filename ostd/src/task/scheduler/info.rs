@@ -2,7 +2,7 @@
 
 //! Scheduling related information in a task.
 
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{fence, AtomicU32, Ordering};
 
 use crate::task::Task;
 
@@ -41,6 +41,18 @@ impl AtomicCpuId {
             .compare_exchange(Self::NONE, cpu_id, Ordering::Relaxed, Ordering::Relaxed)
     }
 
+    /// Sets the inner value of an `AtomicCpuId` to `new_cpu_id` if it's `old_cpu_id`.
+    ///
+    /// This is useful for migrating a runnable task from one CPU to another
+    /// without making it empty in between.
+    ///
+    /// The return value is a result indicating whether the new value was written
+    /// and containing the previous value.
+    pub fn set_if_is(&self, old_cpu_id: u32, new_cpu_id: u32) -> core::result::Result<u32, u32> {
+        self.0
+            .compare_exchange(old_cpu_id, new_cpu_id, Ordering::Relaxed, Ordering::Relaxed)
+    }
+
     /// Sets the inner value of an `AtomicCpuId` to `AtomicCpuId::NONE`, i.e. makes
     /// an `AtomicCpuId` empty.
     pub fn set_to_none(&self) {
@@ -68,10 +80,27 @@ impl CommonSchedInfo for Task {
     fn cpu(&self) -> &AtomicCpuId {
         &self.schedule_info().cpu
     }
+
+    fn on_cpu(&self) -> Option<u32> {
+        let cpu_id = self.on_cpu.get();
+        // Pairs with the release fence before clearing the CPU, so that the saved context
+        // of the task is visible if it is no longer running.
+        fence(Ordering::Acquire);
+        cpu_id
+    }
 }
 
 /// Trait for fetching common scheduling information.
 pub trait CommonSchedInfo {
     /// Gets the CPU that the task is running on or lately ran on.
     fn cpu(&self) -> &AtomicCpuId;
+
+    /// Returns the CPU that the task is actually running on.
+    ///
+    /// Unlike [`Self::cpu`], this is not cleared until the context of the task has
+    /// been completely saved after it is switched out, which happens after the task is
+    /// dequeued or put back to the runqueue. Until then, the task must not be switched to
+    /// on other CPUs. So a scheduler should not migrate such a task, and should enqueue
+    /// such a task to the CPU that it is running on.
+    fn on_cpu(&self) -> Option<u32>;
 }
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <linux/sched.h>
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/mount.h>
+#include <sys/stat.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define CGROUP_ROOT "/tmp/cgroup"
+#define CGROUP_TEST CGROUP_ROOT "/test"
+#define CGROUP_SUB CGROUP_TEST "/sub"
+
+#define PAGE_SIZE 4096
+
+static char buf[256];
+
+// Reads the whole file into `buf` and returns the length
+static ssize_t read_file(const char *path)
+{
+	int fd;
+	ssize_t len;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+	len = read(fd, buf, sizeof(buf) - 1);
+	close(fd);
+	if (len >= 0)
+		buf[len] = '\0';
+	return len;
+}
+
+static ssize_t write_file(const char *path, const char *data)
+{
+	int fd;
+	ssize_t len;
+
+	fd = open(path, O_WRONLY);
+	if (fd < 0)
+		return -1;
+	len = write(fd, data, strlen(data));
+	close(fd);
+	return len;
+}
+
+static int file_equals(const char *path, const char *expected)
+{
+	return read_file(path) >= 0 && strcmp(buf, expected) == 0;
+}
+
+static long read_number(const char *path)
+{
+	if (read_file(path) < 0)
+		return -1;
+	return atol(buf);
+}
+
+// The exit status of the child is zero if all the conditions hold
+static int wait_child_success(pid_t pid)
+{
+	int status;
+
+	return waitpid(pid, &status, 0) == pid && WIFEXITED(status) &&
+	       WEXITSTATUS(status) == 0;
+}
+
+FN_SETUP(mount)
+{
+	if (mkdir(CGROUP_ROOT, 0755) < 0 && errno != EEXIST)
+		CHECK(-1);
+	CHECK(mount("none", CGROUP_ROOT, "cgroup2", 0, NULL));
+}
+END_SETUP()
+
+FN_TEST(root)
+{
+	TEST_RES(file_equals(CGROUP_ROOT "/cgroup.controllers",
+			     "cpu memory pids\n"),
+		 _ret);
+	TEST_ERRNO(open(CGROUP_ROOT "/cpu.max", O_RDONLY), ENOENT);
+	TEST_ERRNO(open(CGROUP_ROOT "/new_file", O_CREAT | O_WRONLY, 0644),
+		   EPERM);
+	TEST_RES(file_equals("/proc/self/cgroup", "0::/\n"), _ret);
+}
+END_TEST()
+
+FN_TEST(mkdir)
+{
+	TEST_SUCC(mkdir(CGROUP_TEST, 0755));
+	TEST_ERRNO(mkdir(CGROUP_TEST, 0755), EEXIST);
+	TEST_SUCC(access(CGROUP_TEST "/cpu.max", R_OK | W_OK));
+	TEST_RES(file_equals(CGROUP_TEST "/pids.current", "0\n"), _ret);
+	TEST_RES(file_equals(CGROUP_TEST "/cgroup.procs", ""), _ret);
+}
+END_TEST()
+
+FN_TEST(cpu_weight)
+{
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.weight", "100\n"), _ret);
+	TEST_RES(write_file(CGROUP_TEST "/cpu.weight", "200"), _ret == 3);
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.weight", "200\n"), _ret);
+	TEST_ERRNO(write_file(CGROUP_TEST "/cpu.weight", "0"), EINVAL);
+	TEST_ERRNO(write_file(CGROUP_TEST "/cpu.weight", "10001"), EINVAL);
+	TEST_ERRNO(write_file(CGROUP_TEST "/cpu.weight", "abc"), EINVAL);
+}
+END_TEST()
+
+FN_TEST(cpu_max)
+{
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.max", "max 100000\n"), _ret);
+	TEST_RES(write_file(CGROUP_TEST "/cpu.max", "50000 200000"),
+		 _ret == 12);
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.max", "50000 200000\n"), _ret);
+	TEST_RES(write_file(CGROUP_TEST "/cpu.max", "20000"), _ret == 5);
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.max", "20000 200000\n"), _ret);
+	TEST_ERRNO(write_file(CGROUP_TEST "/cpu.max", "500 100000"), EINVAL);
+	TEST_ERRNO(write_file(CGROUP_TEST "/cpu.max", "max 0"), EINVAL);
+	TEST_RES(write_file(CGROUP_TEST "/cpu.max", "max 100000"), _ret == 10);
+	TEST_RES(file_equals(CGROUP_TEST "/cpu.max", "max 100000\n"), _ret);
+}
+END_TEST()
+
+FN_TEST(pids_max)
+{
+	pid_t pid;
+
+	TEST_RES(file_equals(CGROUP_TEST "/pids.max", "max\n"), _ret);
+	TEST_RES(write_file(CGROUP_TEST "/pids.max", "1"), _ret == 1);
+	TEST_RES(file_equals(CGROUP_TEST "/pids.max", "1\n"), _ret);
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (write_file(CGROUP_TEST "/cgroup.procs", "0") < 0)
+			_exit(1);
+		if (!file_equals("/proc/self/cgroup", "0::/test\n") ||
+		    !file_equals(CGROUP_TEST "/pids.current", "1\n"))
+			_exit(1);
+		// The limit is reached, so no more tasks can be created
+		if (fork() >= 0 || errno != EAGAIN)
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(file_equals(CGROUP_TEST "/pids.current", "0\n"), _ret);
+
+	TEST_RES(write_file(CGROUP_TEST "/pids.max", "max"), _ret == 3);
+	TEST_ERRNO(write_file(CGROUP_TEST "/pids.max", "-1"), EINVAL);
+}
+END_TEST()
+
+static void *exit_thread(void *arg)
+{
+	return arg;
+}
+
+static void *spawn_threads(void *arg)
+{
+	volatile int *stop = arg;
+	pthread_t thread;
+
+	while (!*stop) {
+		if (pthread_create(&thread, NULL, exit_thread, NULL) != 0 ||
+		    pthread_join(thread, NULL) != 0)
+			break;
+	}
+	return NULL;
+}
+
+FN_TEST(pids_move)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		volatile int stop = 0;
+		pthread_t thread;
+		int i;
+
+		if (pthread_create(&thread, NULL, spawn_threads,
+				   (void *)&stop) != 0)
+			_exit(1);
+
+		// The charges of the threads move along with the process,
+		// even if the threads are being created and exiting
+		for (i = 0; i < 100; i++) {
+			if (write_file(CGROUP_TEST "/cgroup.procs", "0") < 0 ||
+			    write_file(CGROUP_ROOT "/cgroup.procs", "0") < 0)
+				_exit(1);
+		}
+		stop = 1;
+		if (pthread_join(thread, NULL) != 0)
+			_exit(1);
+
+		if (!file_equals(CGROUP_TEST "/pids.current", "0\n") ||
+		    write_file(CGROUP_TEST "/cgroup.procs", "0") < 0 ||
+		    !file_equals(CGROUP_TEST "/pids.current", "1\n"))
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(file_equals(CGROUP_TEST "/pids.current", "0\n"), _ret);
+}
+END_TEST()
+
+FN_TEST(memory_max)
+{
+	pid_t pid;
+	int status;
+
+	TEST_RES(file_equals(CGROUP_TEST "/memory.max", "max\n"), _ret);
+	TEST_RES(file_equals(CGROUP_TEST "/memory.current", "0\n"), _ret);
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		char *addr;
+		long before, after, limit;
+		int i;
+
+		if (write_file(CGROUP_TEST "/cgroup.procs", "0") < 0)
+			_exit(1);
+		addr = mmap(NULL, 64 * PAGE_SIZE, PROT_READ | PROT_WRITE,
+			    MAP_SHARED | MAP_ANONYMOUS, -1, 0);
+		if (addr == MAP_FAILED)
+			_exit(1);
+
+		// The committed pages are charged
+		before = read_number(CGROUP_TEST "/memory.current");
+		for (i = 0; i < 16; i++)
+			addr[i * PAGE_SIZE] = 1;
+		after = read_number(CGROUP_TEST "/memory.current");
+		if (before < 0 || after < before + 16 * PAGE_SIZE)
+			_exit(1);
+
+		// Exceeding the limit kills the process
+		limit = after + 16 * PAGE_SIZE;
+		snprintf(buf, sizeof(buf), "%ld", limit);
+		if (write_file(CGROUP_TEST "/memory.max", buf) < 0 ||
+		    read_number(CGROUP_TEST "/memory.max") != limit)
+			_exit(1);
+		for (i = 16; i < 64; i++)
+			addr[i * PAGE_SIZE] = 1;
+		_exit(1);
+	}
+	TEST_RES(waitpid(pid, &status, 0), _ret == pid && WIFSIGNALED(status));
+	TEST_RES(file_equals(CGROUP_TEST "/memory.current", "0\n"), _ret);
+
+	TEST_RES(write_file(CGROUP_TEST "/memory.max", "max"), _ret == 3);
+}
+END_TEST()
+
+FN_TEST(clone_into_cgroup)
+{
+	struct clone_args args = {};
+	int cgroup_fd;
+	pid_t pid;
+
+	cgroup_fd = TEST_SUCC(open(CGROUP_TEST, O_RDONLY | O_DIRECTORY));
+	args.flags = CLONE_INTO_CGROUP;
+	args.exit_signal = SIGCHLD;
+	args.cgroup = cgroup_fd;
+
+	pid = TEST_SUCC(syscall(SYS_clone3, &args, sizeof(args)));
+	if (pid == 0)
+		_exit(file_equals("/proc/self/cgroup", "0::/test\n") ? 0 : 1);
+	TEST_RES(wait_child_success(pid), _ret);
+
+	args.cgroup = 0;
+	TEST_ERRNO(syscall(SYS_clone3, &args, sizeof(args)), EBADF);
+
+	TEST_SUCC(close(cgroup_fd));
+}
+END_TEST()
+
+FN_TEST(rmdir)
+{
+	int pipe_fds[2];
+	pid_t pid;
+	char buf2[32];
+
+	TEST_SUCC(pipe(pipe_fds));
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		char c;
+
+		close(pipe_fds[1]);
+		read(pipe_fds[0], &c, 1);
+		_exit(0);
+	}
+	TEST_SUCC(close(pipe_fds[0]));
+
+	snprintf(buf2, sizeof(buf2), "%d", pid);
+	TEST_RES(write_file(CGROUP_TEST "/cgroup.procs", buf2),
+		 _ret == strlen(buf2));
+	strcat(buf2, "\n");
+	TEST_RES(file_equals(CGROUP_TEST "/cgroup.procs", buf2), _ret);
+	TEST_ERRNO(rmdir(CGROUP_TEST), EBUSY);
+
+	TEST_SUCC(close(pipe_fds[1]));
+	TEST_RES(wait_child_success(pid), _ret);
+
+	TEST_SUCC(mkdir(CGROUP_SUB, 0755));
+	TEST_ERRNO(rmdir(CGROUP_TEST), EBUSY);
+	TEST_SUCC(rmdir(CGROUP_SUB));
+	TEST_SUCC(rmdir(CGROUP_TEST));
+	TEST_ERRNO(access(CGROUP_TEST, F_OK), ENOENT);
+}
+END_TEST()
+
+FN_SETUP(umount)
+{
+	CHECK(umount(CGROUP_ROOT));
+}
+END_SETUP()
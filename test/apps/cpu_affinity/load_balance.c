@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#define WORK_MS 200
+#define MAX_WORKERS 64
+
+static long elapsed_ms(clockid_t clock, const struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(clock, &now);
+	return (now.tv_sec - start->tv_sec) * 1000 +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+static void spin_for_cpu_time(long ms)
+{
+	struct timespec start;
+
+	clock_gettime(CLOCK_PROCESS_CPUTIME_ID, &start);
+	while (elapsed_ms(CLOCK_PROCESS_CPUTIME_ID, &start) < ms)
+		;
+}
+
+static int nr_cpus;
+
+FN_SETUP(nr_cpus)
+{
+	cpu_set_t set;
+
+	CHECK(sched_getaffinity(0, sizeof(set), &set));
+	nr_cpus = CPU_COUNT(&set);
+	if (nr_cpus > MAX_WORKERS)
+		nr_cpus = MAX_WORKERS;
+}
+END_SETUP()
+
+// The workers are spawned on the CPU of the parent. If the idle CPUs do not
+// pull them, they run one after another and take `nr_cpus` times as long.
+FN_TEST(idle_cpus_get_work)
+{
+	struct timespec start;
+	pid_t pids[MAX_WORKERS];
+	int i, status;
+	long wall_ms;
+
+	// Nothing can be balanced with a single CPU.
+	if (nr_cpus < 2)
+		nr_cpus = 0;
+
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	for (i = 0; i < nr_cpus; ++i) {
+		pids[i] = TEST_SUCC(fork());
+		if (pids[i] == 0) {
+			spin_for_cpu_time(WORK_MS);
+			exit(EXIT_SUCCESS);
+		}
+	}
+	for (i = 0; i < nr_cpus; ++i)
+		TEST_RES(waitpid(pids[i], &status, 0),
+			 _ret == pids[i] && WIFEXITED(status) &&
+				 WEXITSTATUS(status) == 0);
+	wall_ms = elapsed_ms(CLOCK_MONOTONIC, &start);
+
+	TEST_RES(wall_ms, _ret < WORK_MS * (nr_cpus + 1) / 2 + 50);
+}
+END_TEST()
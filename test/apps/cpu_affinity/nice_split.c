@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#define SPIN_MS 1000
+
+static long elapsed_ms(clockid_t clock, const struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(clock, &now);
+	return (now.tv_sec - start->tv_sec) * 1000 +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+// Spins for `SPIN_MS` of wall time and reports the CPU time consumed.
+//
+// The test runs without SMP (see `run_general_test.sh`), so the spinners share
+// the only CPU.
+static pid_t spawn_spinner(int nice, int report_fd)
+{
+	struct timespec start, zero = {};
+	long cpu_ms;
+	pid_t pid;
+
+	pid = CHECK(fork());
+	if (pid != 0)
+		return pid;
+
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	// The nice value is changed while running, which should take effect at once
+	CHECK(setpriority(PRIO_PROCESS, 0, nice));
+	while (elapsed_ms(CLOCK_MONOTONIC, &start) < SPIN_MS)
+		;
+
+	cpu_ms = elapsed_ms(CLOCK_PROCESS_CPUTIME_ID, &zero);
+	CHECK_WITH(write(report_fd, &cpu_ms, sizeof(cpu_ms)),
+		   _ret == sizeof(cpu_ms));
+	_exit(EXIT_SUCCESS);
+}
+
+// A task with the nice value of 0 has a weight of 1024, and a task with the
+// nice value of 5 has a weight of 335. So the former should get about three
+// times as much CPU time as the latter when they share a CPU.
+FN_TEST(cpu_split_by_nice)
+{
+	int fds0[2], fds5[2];
+	long cpu_ms0, cpu_ms5;
+	pid_t pid0, pid5;
+	int status;
+
+	TEST_SUCC(pipe(fds0));
+	TEST_SUCC(pipe(fds5));
+	pid0 = spawn_spinner(0, fds0[1]);
+	pid5 = spawn_spinner(5, fds5[1]);
+
+	TEST_RES(read(fds0[0], &cpu_ms0, sizeof(cpu_ms0)),
+		 _ret == sizeof(cpu_ms0));
+	TEST_RES(read(fds5[0], &cpu_ms5, sizeof(cpu_ms5)),
+		 _ret == sizeof(cpu_ms5));
+	TEST_RES(waitpid(pid0, &status, 0),
+		 _ret == pid0 && WIFEXITED(status) && WEXITSTATUS(status) == 0);
+	TEST_RES(waitpid(pid5, &status, 0),
+		 _ret == pid5 && WIFEXITED(status) && WEXITSTATUS(status) == 0);
+
+	// The bounds are loose since the CPU time is accounted in ticks
+	TEST_RES(cpu_ms5, _ret > 0);
+	TEST_RES(cpu_ms0, _ret > cpu_ms5 * 2 && _ret < cpu_ms5 * 5);
+
+	TEST_SUCC(close(fds0[0]));
+	TEST_SUCC(close(fds0[1]));
+	TEST_SUCC(close(fds5[0]));
+	TEST_SUCC(close(fds5[1]));
+}
+END_TEST()
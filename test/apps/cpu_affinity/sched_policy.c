@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <stdint.h>
+#include <string.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define SCHED_ATTR_SIZE_VER0 48
+
+struct test_sched_attr {
+	uint32_t size;
+	uint32_t sched_policy;
+	uint64_t sched_flags;
+	int32_t sched_nice;
+	uint32_t sched_priority;
+	uint64_t sched_runtime;
+	uint64_t sched_deadline;
+	uint64_t sched_period;
+	uint32_t sched_util_min;
+	uint32_t sched_util_max;
+};
+
+static struct test_sched_attr attr;
+static struct sched_param param;
+
+FN_TEST(priority_range)
+{
+	TEST_RES(sched_get_priority_min(SCHED_FIFO), _ret == 1);
+	TEST_RES(sched_get_priority_max(SCHED_FIFO), _ret == 99);
+	TEST_RES(sched_get_priority_min(SCHED_RR), _ret == 1);
+	TEST_RES(sched_get_priority_max(SCHED_RR), _ret == 99);
+	TEST_RES(sched_get_priority_min(SCHED_OTHER), _ret == 0);
+	TEST_RES(sched_get_priority_max(SCHED_OTHER), _ret == 0);
+	TEST_ERRNO(sched_get_priority_max(4), EINVAL);
+	TEST_ERRNO(sched_get_priority_max(-1), EINVAL);
+}
+END_TEST()
+
+FN_TEST(invalid_target)
+{
+	TEST_ERRNO(sched_getscheduler(-1), EINVAL);
+	TEST_ERRNO(sched_getscheduler(0x3fffffff), ESRCH);
+}
+END_TEST()
+
+FN_TEST(set_scheduler)
+{
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+
+	param.sched_priority = 0;
+	TEST_ERRNO(sched_setscheduler(0, SCHED_FIFO, &param), EINVAL);
+	param.sched_priority = 100;
+	TEST_ERRNO(sched_setscheduler(0, SCHED_FIFO, &param), EINVAL);
+	param.sched_priority = 1;
+	TEST_ERRNO(sched_setscheduler(0, SCHED_OTHER, &param), EINVAL);
+
+	param.sched_priority = 50;
+	TEST_SUCC(sched_setscheduler(0, SCHED_FIFO, &param));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_FIFO);
+	param.sched_priority = 0;
+	TEST_RES(sched_getparam(0, &param), param.sched_priority == 50);
+
+	param.sched_priority = 60;
+	TEST_SUCC(sched_setparam(getpid(), &param));
+	param.sched_priority = 0;
+	TEST_RES(sched_getparam(0, &param), param.sched_priority == 60);
+
+	param.sched_priority = 10;
+	TEST_SUCC(sched_setscheduler(0, SCHED_RR, &param));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_RR);
+}
+END_TEST()
+
+FN_TEST(inherit_on_fork)
+{
+	pid_t pid;
+	int status;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		param.sched_priority = 0;
+		sched_getparam(0, &param);
+		_exit(sched_getscheduler(0) == SCHED_RR &&
+		      param.sched_priority == 10 ?
+			      0 :
+			      1);
+	}
+	TEST_RES(wait4(pid, &status, 0, NULL),
+		 _ret == pid && WIFEXITED(status) && WEXITSTATUS(status) == 0);
+}
+END_TEST()
+
+FN_TEST(set_attr)
+{
+	memset(&attr, 0, sizeof(attr));
+	TEST_RES(syscall(SYS_sched_getattr, 0, &attr, SCHED_ATTR_SIZE_VER0, 0),
+		 attr.size == SCHED_ATTR_SIZE_VER0 &&
+			 attr.sched_policy == SCHED_RR &&
+			 attr.sched_priority == 10);
+	TEST_ERRNO(syscall(SYS_sched_getattr, 0, &attr, 8, 0), EINVAL);
+
+	memset(&attr, 0, sizeof(attr));
+	attr.size = sizeof(attr);
+	attr.sched_policy = SCHED_OTHER;
+	attr.sched_nice = 5;
+	TEST_SUCC(syscall(SYS_sched_setattr, 0, &attr, 0));
+
+	memset(&attr, 0, sizeof(attr));
+	TEST_RES(syscall(SYS_sched_getattr, 0, &attr, sizeof(attr), 0),
+		 attr.size == sizeof(attr) &&
+			 attr.sched_policy == SCHED_OTHER &&
+			 attr.sched_nice == 5 && attr.sched_priority == 0);
+
+	memset(&attr, 0, sizeof(attr));
+	attr.size = 8;
+	TEST_ERRNO(syscall(SYS_sched_setattr, 0, &attr, 0), E2BIG);
+	TEST_RES(0, attr.size == sizeof(attr));
+
+	memset(&attr, 0, sizeof(attr));
+	attr.size = sizeof(attr);
+	attr.sched_policy = SCHED_OTHER;
+	TEST_SUCC(syscall(SYS_sched_setattr, 0, &attr, 0));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+}
+END_TEST()
+
+FN_TEST(set_affinity)
+{
+	cpu_set_t set;
+	int is_cpu0_set;
+
+	CPU_ZERO(&set);
+	TEST_ERRNO(sched_setaffinity(0, sizeof(set), &set), EINVAL);
+
+	CPU_SET(0, &set);
+	TEST_SUCC(sched_setaffinity(0, sizeof(set), &set));
+	CPU_ZERO(&set);
+	TEST_SUCC(sched_getaffinity(0, sizeof(set), &set));
+	TEST_RES(CPU_COUNT(&set), _ret == 1);
+	is_cpu0_set = CPU_ISSET(0, &set);
+	TEST_RES(is_cpu0_set, _ret);
+
+	// The raw system call returns the size of the mask.
+	TEST_RES(syscall(SYS_sched_getaffinity, 0, sizeof(set), &set),
+		 _ret > 0 && (_ret & (sizeof(long) - 1)) == 0);
+	TEST_ERRNO(syscall(SYS_sched_getaffinity, 0, 1, &set), EINVAL);
+}
+END_TEST()
+
+FN_TEST(migrate_on_set_affinity)
+{
+	cpu_set_t set;
+	int cpu;
+
+	if (sysconf(_SC_NPROCESSORS_ONLN) < 2)
+		return;
+
+	// The calling thread must leave a CPU that its new affinity excludes
+	// before `sched_setaffinity` returns.
+	for (cpu = 0; cpu < 2; ++cpu) {
+		CPU_ZERO(&set);
+		CPU_SET(cpu, &set);
+		TEST_SUCC(sched_setaffinity(0, sizeof(set), &set));
+		TEST_RES(sched_getcpu(), _ret == cpu);
+	}
+
+	CPU_ZERO(&set);
+	CPU_SET(0, &set);
+	TEST_SUCC(sched_setaffinity(0, sizeof(set), &set));
+}
+END_TEST()
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <limits.h>
+#include <sys/inotify.h>
+#include <sys/poll.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define TEST_DIR "/tmp/inotify_test"
+#define TEST_FILE TEST_DIR "/file"
+#define RENAMED_FILE TEST_DIR "/renamed"
+
+#define EVENT_BUF_LEN (16 * (sizeof(struct inotify_event) + NAME_MAX + 1))
+
+static int inotify_fd;
+static int dir_wd;
+
+FN_SETUP(init)
+{
+	mkdir(TEST_DIR, 0755);
+	inotify_fd = CHECK(inotify_init1(IN_NONBLOCK | IN_CLOEXEC));
+	dir_wd = CHECK(inotify_add_watch(inotify_fd, TEST_DIR, IN_ALL_EVENTS));
+}
+END_SETUP()
+
+// A single read may return several events, so the events that are read but not
+// yet consumed are kept in the buffer.
+static char event_buf[EVENT_BUF_LEN] __attribute__((aligned(8)));
+static size_t event_buf_off;
+static size_t event_buf_len;
+
+static int read_one_event(struct inotify_event *event, char *name)
+{
+	struct inotify_event *next;
+	ssize_t len;
+
+	if (event_buf_off >= event_buf_len) {
+		len = read(inotify_fd, event_buf, sizeof(event_buf));
+		if (len < 0)
+			return -1;
+		event_buf_off = 0;
+		event_buf_len = len;
+	}
+
+	next = (struct inotify_event *)(event_buf + event_buf_off);
+	event_buf_off += sizeof(struct inotify_event) + next->len;
+
+	*event = *next;
+	if (next->len > 0)
+		strcpy(name, next->name);
+	else
+		name[0] = '\0';
+
+	return sizeof(struct inotify_event) + next->len;
+}
+
+// Reads the next event of the watch `wd`, skipping the events of other watches
+// (e.g., the same event reported to the watch of the parent directory).
+static int read_watch_event(int wd, struct inotify_event *event, char *name)
+{
+	int len;
+
+	while ((len = read_one_event(event, name)) >= 0 && event->wd != wd)
+		;
+
+	return len;
+}
+
+FN_TEST(invalid_args)
+{
+	TEST_ERRNO(inotify_init1(-1), EINVAL);
+	TEST_ERRNO(inotify_add_watch(inotify_fd, TEST_DIR, 0), EINVAL);
+	TEST_ERRNO(inotify_add_watch(inotify_fd, "/nonexistent", IN_MODIFY),
+		   ENOENT);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, 12345), EINVAL);
+	TEST_ERRNO(inotify_add_watch(STDIN_FILENO, TEST_DIR, IN_MODIFY),
+		   EINVAL);
+}
+END_TEST()
+
+FN_TEST(empty_queue)
+{
+	char buf[EVENT_BUF_LEN];
+	struct pollfd pfd = { .fd = inotify_fd, .events = POLLIN };
+
+	TEST_ERRNO(read(inotify_fd, buf, sizeof(buf)), EAGAIN);
+	TEST_RES(poll(&pfd, 1, 0), _ret == 0 && pfd.revents == 0);
+}
+END_TEST()
+
+FN_TEST(create_modify_delete)
+{
+	struct inotify_event event;
+	char name[NAME_MAX + 1];
+	struct pollfd pfd = { .fd = inotify_fd, .events = POLLIN };
+	int fd;
+
+	fd = TEST_SUCC(open(TEST_FILE, O_CREAT | O_WRONLY, 0644));
+	TEST_RES(poll(&pfd, 1, 0), _ret == 1 && pfd.revents == POLLIN);
+
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && (event.mask & IN_CREATE) &&
+			 strcmp(name, "file") == 0);
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && (event.mask & IN_OPEN) &&
+			 strcmp(name, "file") == 0);
+
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && (event.mask & IN_MODIFY) &&
+			 strcmp(name, "file") == 0);
+
+	TEST_SUCC(close(fd));
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && (event.mask & IN_CLOSE_WRITE) &&
+			 strcmp(name, "file") == 0);
+
+	TEST_SUCC(unlink(TEST_FILE));
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && (event.mask & IN_DELETE) &&
+			 strcmp(name, "file") == 0);
+}
+END_TEST()
+
+FN_TEST(rename)
+{
+	struct inotify_event from, to;
+	char from_name[NAME_MAX + 1], to_name[NAME_MAX + 1];
+	int fd;
+
+	fd = TEST_SUCC(creat(TEST_FILE, 0644));
+	TEST_SUCC(close(fd));
+	while (read_one_event(&from, from_name) >= 0)
+		;
+
+	TEST_SUCC(rename(TEST_FILE, RENAMED_FILE));
+	TEST_RES(read_one_event(&from, from_name),
+		 (from.mask & IN_MOVED_FROM) &&
+			 strcmp(from_name, "file") == 0);
+	TEST_RES(read_one_event(&to, to_name),
+		 (to.mask & IN_MOVED_TO) && strcmp(to_name, "renamed") == 0 &&
+			 to.cookie == from.cookie && to.cookie != 0);
+
+	TEST_SUCC(unlink(RENAMED_FILE));
+	while (read_one_event(&from, from_name) >= 0)
+		;
+}
+END_TEST()
+
+FN_TEST(watch_file_self)
+{
+	struct inotify_event event;
+	char name[NAME_MAX + 1];
+	int fd, wd;
+
+	fd = TEST_SUCC(creat(TEST_FILE, 0644));
+	TEST_SUCC(close(fd));
+	wd = TEST_SUCC(inotify_add_watch(inotify_fd, TEST_FILE,
+					 IN_ATTRIB | IN_DELETE_SELF));
+	TEST_ERRNO(inotify_add_watch(inotify_fd, TEST_FILE,
+				     IN_ATTRIB | IN_MASK_CREATE),
+		   EEXIST);
+	TEST_ERRNO(inotify_add_watch(inotify_fd, TEST_FILE,
+				     IN_ATTRIB | IN_ONLYDIR),
+		   ENOTDIR);
+	while (read_one_event(&event, name) >= 0)
+		;
+
+	TEST_SUCC(chmod(TEST_FILE, 0600));
+	TEST_RES(read_watch_event(wd, &event, name),
+		 event.wd == wd && event.mask == IN_ATTRIB && event.len == 0);
+
+	TEST_SUCC(unlink(TEST_FILE));
+	TEST_RES(read_watch_event(wd, &event, name),
+		 event.wd == wd && event.mask == IN_DELETE_SELF);
+	TEST_RES(read_watch_event(wd, &event, name),
+		 event.wd == wd && event.mask == IN_IGNORED);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, wd), EINVAL);
+	while (read_one_event(&event, name) >= 0)
+		;
+}
+END_TEST()
+
+FN_TEST(exclude_unlinked)
+{
+	struct inotify_event event;
+	char name[NAME_MAX + 1];
+	int excl_fd, fd;
+
+	excl_fd = TEST_SUCC(inotify_init1(IN_NONBLOCK));
+	TEST_SUCC(inotify_add_watch(excl_fd, TEST_DIR,
+				    IN_MODIFY | IN_EXCL_UNLINK));
+	fd = TEST_SUCC(open(TEST_FILE, O_CREAT | O_WRONLY, 0644));
+	TEST_SUCC(unlink(TEST_FILE));
+	while (read_one_event(&event, name) >= 0)
+		;
+
+	// The watch without `IN_EXCL_UNLINK` still reports the event.
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_RES(read_watch_event(dir_wd, &event, name),
+		 (event.mask & IN_MODIFY) && strcmp(name, "file") == 0);
+	TEST_ERRNO(read(excl_fd, event_buf, sizeof(event_buf)), EAGAIN);
+
+	TEST_SUCC(close(fd));
+	TEST_SUCC(close(excl_fd));
+	while (read_one_event(&event, name) >= 0)
+		;
+}
+END_TEST()
+
+FN_TEST(rm_watch)
+{
+	struct inotify_event event;
+	char name[NAME_MAX + 1];
+
+	TEST_SUCC(inotify_rm_watch(inotify_fd, dir_wd));
+	TEST_RES(read_one_event(&event, name),
+		 event.wd == dir_wd && event.mask == IN_IGNORED);
+	TEST_ERRNO(inotify_rm_watch(inotify_fd, dir_wd), EINVAL);
+
+	TEST_SUCC(close(inotify_fd));
+	TEST_SUCC(rmdir(TEST_DIR));
+}
+END_TEST()
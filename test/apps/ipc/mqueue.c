@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <mqueue.h>
+#include <signal.h>
+#include <string.h>
+#include <sys/epoll.h>
+#include <time.h>
+#include <unistd.h>
+
+#define MQ_NAME "/test_mqueue"
+#define MQ_MAXMSG 4
+#define MQ_MSGSIZE 64
+
+static mqd_t mqd;
+
+FN_SETUP(create)
+{
+	struct mq_attr attr = { .mq_maxmsg = MQ_MAXMSG,
+				.mq_msgsize = MQ_MSGSIZE };
+
+	mq_unlink(MQ_NAME);
+	mqd = CHECK(mq_open(MQ_NAME, O_RDWR | O_CREAT | O_EXCL | O_NONBLOCK,
+			    0600, &attr));
+}
+END_SETUP()
+
+FN_TEST(open)
+{
+	struct mq_attr attr;
+	mqd_t mqd2;
+
+	TEST_ERRNO(mq_open(MQ_NAME, O_RDWR | O_CREAT | O_EXCL, 0600, NULL),
+		   EEXIST);
+	TEST_ERRNO(mq_open("/no_such_mqueue", O_RDWR), ENOENT);
+
+	TEST_RES(mq_getattr(mqd, &attr),
+		 attr.mq_maxmsg == MQ_MAXMSG &&
+			 attr.mq_msgsize == MQ_MSGSIZE &&
+			 attr.mq_curmsgs == 0 && attr.mq_flags == O_NONBLOCK);
+
+	// Another descriptor refers to the same queue
+	mqd2 = TEST_SUCC(mq_open(MQ_NAME, O_RDONLY));
+	TEST_RES(mq_getattr(mqd2, &attr),
+		 attr.mq_maxmsg == MQ_MAXMSG && attr.mq_flags == 0);
+	TEST_ERRNO(mq_send(mqd2, "x", 1, 0), EBADF);
+	TEST_SUCC(mq_close(mqd2));
+}
+END_TEST()
+
+FN_TEST(send_and_receive)
+{
+	char buf[MQ_MSGSIZE];
+	unsigned int prio;
+	struct mq_attr attr;
+
+	TEST_SUCC(mq_send(mqd, "low", 4, 1));
+	TEST_SUCC(mq_send(mqd, "high", 5, 10));
+	TEST_SUCC(mq_send(mqd, "low2", 5, 1));
+	TEST_RES(mq_getattr(mqd, &attr), attr.mq_curmsgs == 3);
+
+	// Messages are received by priority, and then by the order of sending
+	TEST_RES(mq_receive(mqd, buf, sizeof(buf), &prio),
+		 _ret == 5 && prio == 10 && strcmp(buf, "high") == 0);
+	TEST_RES(mq_receive(mqd, buf, sizeof(buf), &prio),
+		 _ret == 4 && prio == 1 && strcmp(buf, "low") == 0);
+	TEST_RES(mq_receive(mqd, buf, sizeof(buf), &prio),
+		 _ret == 5 && prio == 1 && strcmp(buf, "low2") == 0);
+
+	TEST_ERRNO(mq_receive(mqd, buf, sizeof(buf), NULL), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(errors)
+{
+	char buf[MQ_MSGSIZE + 1];
+	int i;
+
+	TEST_ERRNO(mq_send(mqd, buf, MQ_MSGSIZE + 1, 0), EMSGSIZE);
+	TEST_ERRNO(mq_receive(mqd, buf, MQ_MSGSIZE - 1, NULL), EMSGSIZE);
+	TEST_ERRNO(mq_send(mqd, buf, 1, 32768), EINVAL);
+
+	for (i = 0; i < MQ_MAXMSG; i++)
+		TEST_SUCC(mq_send(mqd, buf, 1, 0));
+	TEST_ERRNO(mq_send(mqd, buf, 1, 0), EAGAIN);
+	for (i = 0; i < MQ_MAXMSG; i++)
+		TEST_SUCC(mq_receive(mqd, buf, sizeof(buf), NULL));
+}
+END_TEST()
+
+FN_TEST(timed_receive)
+{
+	char buf[MQ_MSGSIZE];
+	struct mq_attr attr = { .mq_flags = 0 };
+	struct timespec timeout;
+
+	TEST_SUCC(mq_setattr(mqd, &attr, NULL));
+
+	TEST_SUCC(clock_gettime(CLOCK_REALTIME, &timeout));
+	timeout.tv_nsec += 100 * 1000 * 1000;
+	if (timeout.tv_nsec >= 1000 * 1000 * 1000) {
+		timeout.tv_sec += 1;
+		timeout.tv_nsec -= 1000 * 1000 * 1000;
+	}
+	TEST_ERRNO(mq_timedreceive(mqd, buf, sizeof(buf), NULL, &timeout),
+		   ETIMEDOUT);
+
+	attr.mq_flags = O_NONBLOCK;
+	TEST_SUCC(mq_setattr(mqd, &attr, NULL));
+}
+END_TEST()
+
+FN_TEST(epoll)
+{
+	char buf[MQ_MSGSIZE];
+	struct epoll_event event = { .events = EPOLLIN };
+	int epfd;
+
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, mqd, &event));
+
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+	TEST_SUCC(mq_send(mqd, "ping", 5, 0));
+	TEST_RES(epoll_wait(epfd, &event, 1, 0),
+		 _ret == 1 && event.events == EPOLLIN);
+	TEST_SUCC(mq_receive(mqd, buf, sizeof(buf), NULL));
+	TEST_RES(epoll_wait(epfd, &event, 1, 0), _ret == 0);
+
+	TEST_SUCC(close(epfd));
+}
+END_TEST()
+
+static volatile sig_atomic_t notified;
+
+static void notify_handler(int signo)
+{
+	notified = signo;
+}
+
+FN_TEST(notify)
+{
+	char buf[MQ_MSGSIZE];
+	struct sigevent sev = { .sigev_notify = SIGEV_SIGNAL,
+				.sigev_signo = SIGUSR1 };
+
+	TEST_SUCC(signal(SIGUSR1, notify_handler) == SIG_ERR ? -1 : 0);
+
+	TEST_SUCC(mq_notify(mqd, &sev));
+	TEST_ERRNO(mq_notify(mqd, &sev), EBUSY);
+
+	TEST_SUCC(mq_send(mqd, "notify", 7, 0));
+	TEST_RES(notified, _ret == SIGUSR1);
+	TEST_SUCC(mq_receive(mqd, buf, sizeof(buf), NULL));
+
+	// The notification is removed after being delivered
+	notified = 0;
+	TEST_SUCC(mq_send(mqd, "notify", 7, 0));
+	TEST_RES(notified, _ret == 0);
+	TEST_SUCC(mq_receive(mqd, buf, sizeof(buf), NULL));
+
+	// The notification can be removed explicitly
+	TEST_SUCC(mq_notify(mqd, &sev));
+	TEST_SUCC(mq_notify(mqd, NULL));
+	TEST_SUCC(mq_send(mqd, "notify", 7, 0));
+	TEST_RES(notified, _ret == 0);
+	TEST_SUCC(mq_receive(mqd, buf, sizeof(buf), NULL));
+}
+END_TEST()
+
+FN_TEST(unlink)
+{
+	char buf[MQ_MSGSIZE];
+
+	TEST_SUCC(mq_unlink(MQ_NAME));
+	TEST_ERRNO(mq_unlink(MQ_NAME), ENOENT);
+	TEST_ERRNO(mq_open(MQ_NAME, O_RDWR), ENOENT);
+
+	// The queue is still usable via the opened descriptor
+	TEST_SUCC(mq_send(mqd, "alive", 6, 0));
+	TEST_RES(mq_receive(mqd, buf, sizeof(buf), NULL), _ret == 6);
+
+	TEST_SUCC(mq_close(mqd));
+}
+END_TEST()
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <string.h>
+#include <sys/ipc.h>
+#include <sys/msg.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define MSG_KEY 0x6789
+#define MSG_LEN 16
+
+struct message {
+	long mtype;
+	char mtext[MSG_LEN];
+};
+
+static int msqid;
+
+static int send_msg(long mtype, const char *text, int flags)
+{
+	struct message msg = { .mtype = mtype };
+
+	strncpy(msg.mtext, text, MSG_LEN);
+	return msgsnd(msqid, &msg, strlen(text) + 1, flags);
+}
+
+FN_SETUP(create)
+{
+	msqid = CHECK(msgget(MSG_KEY, IPC_CREAT | IPC_EXCL | 0600));
+}
+END_SETUP()
+
+FN_TEST(get)
+{
+	TEST_RES(msgget(MSG_KEY, 0600), _ret == msqid);
+	TEST_RES(msgget(MSG_KEY, 0), _ret == msqid);
+	TEST_ERRNO(msgget(MSG_KEY, IPC_CREAT | IPC_EXCL | 0600), EEXIST);
+	TEST_ERRNO(msgget(MSG_KEY + 1, 0600), ENOENT);
+}
+END_TEST()
+
+FN_TEST(send_and_receive)
+{
+	struct message msg;
+	struct msqid_ds ds;
+
+	TEST_SUCC(send_msg(1, "first", 0));
+	TEST_SUCC(send_msg(2, "second", 0));
+	TEST_SUCC(send_msg(1, "third", 0));
+	TEST_RES(msgctl(msqid, IPC_STAT, &ds),
+		 ds.msg_qnum == 3 && ds.msg_lspid == getpid() &&
+			 ds.msg_perm.__key == MSG_KEY &&
+			 (ds.msg_perm.mode & 0777) == 0600);
+
+	// Receive by type
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 2, 0),
+		 _ret == 7 && msg.mtype == 2 &&
+			 strcmp(msg.mtext, "second") == 0);
+
+	// Copy without removing
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 1, MSG_COPY | IPC_NOWAIT),
+		 _ret == 6 && strcmp(msg.mtext, "third") == 0);
+
+	// Receive in order
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 0, 0),
+		 _ret == 6 && msg.mtype == 1 &&
+			 strcmp(msg.mtext, "first") == 0);
+
+	// The message is too long
+	TEST_ERRNO(msgrcv(msqid, &msg, 2, 0, 0), E2BIG);
+	TEST_RES(msgrcv(msqid, &msg, 2, 0, MSG_NOERROR),
+		 _ret == 2 && msg.mtext[0] == 't' && msg.mtext[1] == 'h');
+
+	TEST_ERRNO(msgrcv(msqid, &msg, MSG_LEN, 0, IPC_NOWAIT), ENOMSG);
+	TEST_RES(msgctl(msqid, IPC_STAT, &ds),
+		 ds.msg_qnum == 0 && ds.msg_cbytes == 0 &&
+			 ds.msg_lrpid == getpid());
+}
+END_TEST()
+
+FN_TEST(select_by_type)
+{
+	struct message msg;
+
+	TEST_SUCC(send_msg(3, "c", 0));
+	TEST_SUCC(send_msg(1, "a", 0));
+	TEST_SUCC(send_msg(2, "b", 0));
+
+	// Receive the lowest type that is less than or equal to 2
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, -2, 0),
+		 msg.mtype == 1 && strcmp(msg.mtext, "a") == 0);
+	// Receive the first message whose type is not 3
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 3, MSG_EXCEPT),
+		 msg.mtype == 2 && strcmp(msg.mtext, "b") == 0);
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 3, 0),
+		 msg.mtype == 3 && strcmp(msg.mtext, "c") == 0);
+}
+END_TEST()
+
+FN_TEST(send_errors)
+{
+	struct msqid_ds ds;
+
+	TEST_ERRNO(send_msg(0, "zero", 0), EINVAL);
+	TEST_ERRNO(msgsnd(-1, "", 0, 0), EINVAL);
+
+	// The queue is full
+	TEST_SUCC(msgctl(msqid, IPC_STAT, &ds));
+	ds.msg_qbytes = MSG_LEN;
+	TEST_SUCC(msgctl(msqid, IPC_SET, &ds));
+	TEST_SUCC(send_msg(1, "0123456789", 0));
+	TEST_ERRNO(send_msg(1, "0123456789", IPC_NOWAIT), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(blocking_receive)
+{
+	struct message msg;
+	int status;
+	pid_t pid;
+
+	TEST_SUCC(msgrcv(msqid, &msg, MSG_LEN, 0, 0));
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		usleep(100 * 1000);
+		exit(send_msg(5, "child", 0) == 0 ? EXIT_SUCCESS :
+						    EXIT_FAILURE);
+	}
+	TEST_RES(msgrcv(msqid, &msg, MSG_LEN, 5, 0),
+		 msg.mtype == 5 && strcmp(msg.mtext, "child") == 0);
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+}
+END_TEST()
+
+FN_TEST(remove)
+{
+	struct message msg;
+	struct msqid_ds ds;
+	int status;
+	pid_t pid;
+
+	// The blocked receiver fails once the queue is removed
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		exit(msgrcv(msqid, &msg, MSG_LEN, 0, 0) < 0 &&
+				     errno == EIDRM ?
+			     EXIT_SUCCESS :
+			     EXIT_FAILURE);
+	}
+	usleep(100 * 1000);
+	TEST_SUCC(msgctl(msqid, IPC_RMID, NULL));
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+
+	TEST_ERRNO(msgget(MSG_KEY, 0600), ENOENT);
+	TEST_ERRNO(msgctl(msqid, IPC_STAT, &ds), EINVAL);
+}
+END_TEST()
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sys/ipc.h>
+#include <sys/mman.h>
+#include <sys/shm.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define SHM_KEY 0x5678
+#define SHM_SIZE 8192
+
+static int shmid;
+
+FN_SETUP(create)
+{
+	shmid = CHECK(shmget(SHM_KEY, SHM_SIZE, IPC_CREAT | IPC_EXCL | 0600));
+}
+END_SETUP()
+
+FN_TEST(get)
+{
+	TEST_RES(shmget(SHM_KEY, SHM_SIZE, 0600), _ret == shmid);
+	TEST_RES(shmget(SHM_KEY, 0, 0), _ret == shmid);
+	TEST_ERRNO(shmget(SHM_KEY, SHM_SIZE, IPC_CREAT | IPC_EXCL | 0600),
+		   EEXIST);
+	TEST_ERRNO(shmget(SHM_KEY, SHM_SIZE * 2, 0600), EINVAL);
+	TEST_ERRNO(shmget(SHM_KEY + 1, SHM_SIZE, 0600), ENOENT);
+	TEST_ERRNO(shmget(IPC_PRIVATE, 0, IPC_CREAT | 0600), EINVAL);
+}
+END_TEST()
+
+FN_TEST(attach_and_share)
+{
+	struct shmid_ds ds;
+	char *addr;
+	char *addr2;
+	int status;
+	pid_t pid;
+
+	addr = (char *)TEST_SUCC((long)shmat(shmid, NULL, 0));
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds),
+		 ds.shm_nattch == 1 && ds.shm_segsz == SHM_SIZE &&
+			 ds.shm_perm.__key == SHM_KEY &&
+			 (ds.shm_perm.mode & 0777) == 0600 &&
+			 ds.shm_cpid == getpid() && ds.shm_lpid == getpid());
+
+	// The segment is shared with the child after fork
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		addr[0] = 'c';
+		addr[SHM_SIZE - 1] = 'd';
+		exit(shmdt(addr) == 0 ? EXIT_SUCCESS : EXIT_FAILURE);
+	}
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == EXIT_SUCCESS);
+	TEST_RES(addr[0] + addr[SHM_SIZE - 1], _ret == 'c' + 'd');
+
+	// The segment is mapped again at another address
+	addr2 = (char *)TEST_SUCC((long)shmat(shmid, NULL, SHM_RDONLY));
+	TEST_RES(addr2[0], addr2 != addr && _ret == 'c');
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 2);
+
+	TEST_SUCC(shmdt(addr2));
+	TEST_SUCC(shmdt(addr));
+	TEST_ERRNO(shmdt(addr), EINVAL);
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 0);
+}
+END_TEST()
+
+FN_TEST(attach_errors)
+{
+	TEST_ERRNO((long)shmat(-1, NULL, 0), EINVAL);
+	TEST_ERRNO((long)shmat(shmid + 100, NULL, 0), EINVAL);
+	TEST_ERRNO((long)shmat(shmid, (void *)0x1001, 0), EINVAL);
+	TEST_ERRNO((long)shmat(shmid, NULL, SHM_REMAP), EINVAL);
+}
+END_TEST()
+
+FN_TEST(unmap)
+{
+	struct shmid_ds ds;
+	long page_size = sysconf(_SC_PAGESIZE);
+	char *addr;
+
+	// Unmapping the mapping detaches the segment
+	addr = (char *)TEST_SUCC((long)shmat(shmid, NULL, 0));
+	TEST_SUCC(munmap(addr, SHM_SIZE));
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 0);
+	TEST_ERRNO(shmdt(addr), EINVAL);
+
+	// The rest of a partially unmapped mapping is detached by `shmdt`
+	addr = (char *)TEST_SUCC((long)shmat(shmid, NULL, 0));
+	TEST_SUCC(munmap(addr, page_size));
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 1);
+	TEST_SUCC(shmdt(addr));
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 0);
+	TEST_ERRNO(shmdt(addr), EINVAL);
+
+	// Overwriting the mapping with `MAP_FIXED` detaches the segment
+	addr = (char *)TEST_SUCC((long)shmat(shmid, NULL, 0));
+	TEST_RES((long)mmap(addr, SHM_SIZE, PROT_READ,
+			    MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0),
+		 _ret == (long)addr);
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds), ds.shm_nattch == 0);
+	TEST_ERRNO(shmdt(addr), EINVAL);
+	TEST_SUCC(munmap(addr, SHM_SIZE));
+}
+END_TEST()
+
+FN_TEST(remove)
+{
+	struct shmid_ds ds;
+	char *addr;
+
+	addr = (char *)TEST_SUCC((long)shmat(shmid, NULL, 0));
+	TEST_SUCC(shmctl(shmid, IPC_RMID, NULL));
+
+	// The segment is alive until it is detached
+	TEST_RES(shmctl(shmid, IPC_STAT, &ds),
+		 ds.shm_nattch == 1 && (ds.shm_perm.mode & SHM_DEST));
+	TEST_ERRNO(shmget(SHM_KEY, SHM_SIZE, 0600), ENOENT);
+	addr[0] = 'x';
+
+	TEST_SUCC(shmdt(addr));
+	TEST_ERRNO(shmctl(shmid, IPC_STAT, &ds), EINVAL);
+}
+END_TEST()
+
+FN_TEST(remove_and_unmap)
+{
+	struct shmid_ds ds;
+	char *addr;
+	int id;
+
+	// A removed segment is destroyed once its last mapping is unmapped
+	id = TEST_SUCC(shmget(IPC_PRIVATE, SHM_SIZE, IPC_CREAT | 0600));
+	addr = (char *)TEST_SUCC((long)shmat(id, NULL, 0));
+	TEST_SUCC(shmctl(id, IPC_RMID, NULL));
+	TEST_RES(shmctl(id, IPC_STAT, &ds), ds.shm_nattch == 1);
+
+	TEST_SUCC(munmap(addr, SHM_SIZE));
+	TEST_ERRNO(shmctl(id, IPC_STAT, &ds), EINVAL);
+}
+END_TEST()
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <stdint.h>
+#include <sys/epoll.h>
+#include <sys/timerfd.h>
+#include <time.h>
+#include <unistd.h>
+
+static int tfd;
+
+FN_SETUP(create)
+{
+	tfd = CHECK(timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK | TFD_CLOEXEC));
+}
+END_SETUP()
+
+FN_TEST(invalid_args)
+{
+	struct itimerspec its = { 0 };
+	uint32_t small_buf;
+
+	TEST_ERRNO(timerfd_create(CLOCK_MONOTONIC, 0x1234), EINVAL);
+	TEST_ERRNO(timerfd_create(-100, 0), EINVAL);
+	TEST_ERRNO(timerfd_settime(tfd, 0x1234, &its, NULL), EINVAL);
+	TEST_ERRNO(timerfd_settime(STDIN_FILENO, 0, &its, NULL), EINVAL);
+	TEST_ERRNO(timerfd_gettime(STDIN_FILENO, &its), EINVAL);
+	TEST_ERRNO(read(tfd, &small_buf, sizeof(small_buf)), EINVAL);
+}
+END_TEST()
+
+FN_TEST(disarmed)
+{
+	struct itimerspec its;
+	uint64_t ticks;
+
+	TEST_RES(timerfd_gettime(tfd, &its),
+		 its.it_value.tv_sec == 0 && its.it_value.tv_nsec == 0 &&
+			 its.it_interval.tv_sec == 0 &&
+			 its.it_interval.tv_nsec == 0);
+	TEST_ERRNO(read(tfd, &ticks, sizeof(ticks)), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(one_shot)
+{
+	struct itimerspec its = { .it_value = { .tv_nsec = 10 * 1000 * 1000 } };
+	struct itimerspec old_its;
+	uint64_t ticks;
+	int blocking_tfd;
+
+	blocking_tfd = TEST_SUCC(timerfd_create(CLOCK_REALTIME, 0));
+	TEST_SUCC(timerfd_settime(blocking_tfd, 0, &its, NULL));
+	TEST_RES(read(blocking_tfd, &ticks, sizeof(ticks)),
+		 _ret == sizeof(ticks) && ticks == 1);
+
+	its.it_value.tv_sec = 100;
+	TEST_SUCC(timerfd_settime(blocking_tfd, 0, &its, NULL));
+	its.it_value.tv_sec = 0;
+	its.it_value.tv_nsec = 0;
+	TEST_RES(timerfd_settime(blocking_tfd, 0, &its, &old_its),
+		 old_its.it_value.tv_sec > 0 && old_its.it_value.tv_sec <= 100);
+
+	TEST_SUCC(close(blocking_tfd));
+}
+END_TEST()
+
+FN_TEST(periodic_with_epoll)
+{
+	struct itimerspec its = {
+		.it_value = { .tv_nsec = 10 * 1000 * 1000 },
+		.it_interval = { .tv_nsec = 10 * 1000 * 1000 },
+	};
+	struct epoll_event ev = { .events = EPOLLIN, .data.fd = tfd };
+	struct timespec delay = { .tv_nsec = 50 * 1000 * 1000 };
+	uint64_t ticks;
+	int epfd;
+
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, tfd, &ev));
+	TEST_RES(epoll_wait(epfd, &ev, 1, 0), _ret == 0);
+
+	TEST_SUCC(timerfd_settime(tfd, 0, &its, NULL));
+	TEST_RES(epoll_wait(epfd, &ev, 1, 1000),
+		 _ret == 1 && ev.data.fd == tfd && ev.events == EPOLLIN);
+
+	TEST_SUCC(nanosleep(&delay, NULL));
+	TEST_RES(read(tfd, &ticks, sizeof(ticks)),
+		 _ret == sizeof(ticks) && ticks >= 2);
+
+	its.it_value.tv_nsec = 0;
+	TEST_SUCC(timerfd_settime(tfd, 0, &its, NULL));
+	TEST_ERRNO(read(tfd, &ticks, sizeof(ticks)), EAGAIN);
+	TEST_RES(epoll_wait(epfd, &ev, 1, 0), _ret == 0);
+
+	TEST_SUCC(close(epfd));
+}
+END_TEST()
+
+FN_TEST(reset_discards_expirations)
+{
+	struct itimerspec its = {
+		.it_value = { .tv_nsec = 1000 * 1000 },
+		.it_interval = { .tv_nsec = 1000 * 1000 },
+	};
+	struct itimerspec long_its = { .it_value = { .tv_sec = 100 } };
+	struct timespec delay = { .tv_nsec = 20 * 1000 * 1000 };
+	uint64_t ticks;
+
+	// The expirations of the old setting must not be counted after the reset,
+	// even if their callbacks are still in flight.
+	TEST_SUCC(timerfd_settime(tfd, 0, &its, NULL));
+	TEST_SUCC(nanosleep(&delay, NULL));
+	TEST_SUCC(timerfd_settime(tfd, 0, &long_its, NULL));
+	TEST_SUCC(nanosleep(&delay, NULL));
+	TEST_ERRNO(read(tfd, &ticks, sizeof(ticks)), EAGAIN);
+
+	TEST_SUCC(timerfd_settime(tfd, 0, &its, NULL));
+	TEST_SUCC(nanosleep(&delay, NULL));
+	long_its.it_value.tv_sec = 0;
+	TEST_SUCC(timerfd_settime(tfd, 0, &long_its, NULL));
+	TEST_SUCC(nanosleep(&delay, NULL));
+	TEST_ERRNO(read(tfd, &ticks, sizeof(ticks)), EAGAIN);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(tfd));
+}
+END_SETUP()
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#define PAGE_SIZE 4096
+
+static int memfd;
+
+FN_SETUP(create)
+{
+	memfd = CHECK(memfd_create("test_memfd", MFD_CLOEXEC | MFD_ALLOW_SEALING));
+}
+END_SETUP()
+
+FN_TEST(read_and_write)
+{
+	char buf[16];
+
+	TEST_RES(lseek(memfd, 0, SEEK_END), _ret == 0);
+	TEST_RES(write(memfd, "hello", 6), _ret == 6);
+	TEST_RES(pread(memfd, buf, sizeof(buf), 0),
+		 _ret == 6 && strcmp(buf, "hello") == 0);
+	TEST_RES(fcntl(memfd, F_GETFD), _ret == FD_CLOEXEC);
+}
+END_TEST()
+
+FN_TEST(mmap)
+{
+	char buf[16];
+	char *addr;
+
+	TEST_SUCC(ftruncate(memfd, PAGE_SIZE));
+	addr = (char *)TEST_SUCC((long)mmap(NULL, PAGE_SIZE,
+					    PROT_READ | PROT_WRITE, MAP_SHARED,
+					    memfd, 0));
+	TEST_RES(strcmp(addr, "hello"), _ret == 0);
+	strcpy(addr, "world");
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+
+	TEST_RES(pread(memfd, buf, sizeof(buf), 0),
+		 _ret == sizeof(buf) && strcmp(buf, "world") == 0);
+}
+END_TEST()
+
+FN_TEST(invalid_args)
+{
+	char name[256];
+
+	memset(name, 'a', sizeof(name) - 1);
+	name[sizeof(name) - 1] = '\0';
+
+	TEST_ERRNO(memfd_create("test_memfd", 0x100), EINVAL);
+	TEST_ERRNO(memfd_create(name, 0), EINVAL);
+}
+END_TEST()
+
+FN_TEST(seals)
+{
+	char *addr;
+
+	TEST_RES(fcntl(memfd, F_GET_SEALS), _ret == 0);
+	TEST_ERRNO(fcntl(memfd, F_ADD_SEALS, 0x100), EINVAL);
+
+	TEST_SUCC(fcntl(memfd, F_ADD_SEALS, F_SEAL_SHRINK | F_SEAL_GROW));
+	TEST_RES(fcntl(memfd, F_GET_SEALS),
+		 _ret == (F_SEAL_SHRINK | F_SEAL_GROW));
+	TEST_ERRNO(ftruncate(memfd, PAGE_SIZE / 2), EPERM);
+	TEST_ERRNO(ftruncate(memfd, PAGE_SIZE * 2), EPERM);
+	TEST_ERRNO(pwrite(memfd, "x", 1, PAGE_SIZE), EPERM);
+	TEST_RES(pwrite(memfd, "x", 1, 0), _ret == 1);
+
+	// The file cannot be sealed for writing with writable shared mappings
+	addr = (char *)TEST_SUCC((long)mmap(NULL, PAGE_SIZE,
+					    PROT_READ | PROT_WRITE, MAP_SHARED,
+					    memfd, 0));
+	TEST_ERRNO(fcntl(memfd, F_ADD_SEALS, F_SEAL_WRITE), EBUSY);
+	TEST_RES(fcntl(memfd, F_GET_SEALS),
+		 _ret == (F_SEAL_SHRINK | F_SEAL_GROW));
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+
+	TEST_SUCC(fcntl(memfd, F_ADD_SEALS, F_SEAL_WRITE));
+	TEST_ERRNO(pwrite(memfd, "x", 1, 0), EPERM);
+	TEST_ERRNO((long)mmap(NULL, PAGE_SIZE, PROT_READ | PROT_WRITE,
+			      MAP_SHARED, memfd, 0),
+		   EPERM);
+
+	// Private or read-only mappings are still allowed
+	addr = (char *)TEST_SUCC((long)mmap(NULL, PAGE_SIZE,
+					    PROT_READ | PROT_WRITE, MAP_PRIVATE,
+					    memfd, 0));
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+	addr = (char *)TEST_SUCC(
+		(long)mmap(NULL, PAGE_SIZE, PROT_READ, MAP_SHARED, memfd, 0));
+	// But the shared mappings cannot be made writable later
+	TEST_ERRNO(mprotect(addr, PAGE_SIZE, PROT_READ | PROT_WRITE), EACCES);
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+
+	TEST_SUCC(fcntl(memfd, F_ADD_SEALS, F_SEAL_SEAL));
+	TEST_ERRNO(fcntl(memfd, F_ADD_SEALS, F_SEAL_SHRINK), EPERM);
+}
+END_TEST()
+
+FN_TEST(not_sealable)
+{
+	int fd;
+
+	fd = TEST_SUCC(memfd_create("test_memfd", 0));
+	TEST_RES(fcntl(fd, F_GET_SEALS), _ret == F_SEAL_SEAL);
+	TEST_ERRNO(fcntl(fd, F_ADD_SEALS, F_SEAL_WRITE), EPERM);
+	TEST_RES(fcntl(fd, F_GETFD), _ret == 0);
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(memfd));
+}
+END_SETUP()
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <arpa/inet.h>
+#include <fcntl.h>
+#include <linux/capability.h>
+#include <net/if.h>
+#include <sched.h>
+#include <signal.h>
+#include <string.h>
+#include <sys/mount.h>
+#include <sys/shm.h>
+#include <sys/stat.h>
+#include <sys/syscall.h>
+#include <sys/utsname.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define SHM_KEY 0x4567
+#define CHILD_HOSTNAME "container"
+#define MNT_DIR "/tmp/namespace_mnt"
+
+static int shmid;
+static char init_hostname[65];
+static pid_t child;
+static int done_pipe[2];
+
+static int is_hostname(const char *hostname)
+{
+	struct utsname uts;
+
+	return uname(&uts) == 0 && strcmp(uts.nodename, hostname) == 0;
+}
+
+// The exit status of the child is zero if all the conditions hold
+static int wait_child_success(pid_t pid)
+{
+	int status;
+
+	return waitpid(pid, &status, 0) == pid && WIFEXITED(status) &&
+	       WEXITSTATUS(status) == 0;
+}
+
+FN_SETUP(init)
+{
+	struct utsname uts;
+
+	CHECK(uname(&uts));
+	strcpy(init_hostname, uts.nodename);
+	shmid = CHECK(shmget(SHM_KEY, 4096, IPC_CREAT | IPC_EXCL | 0600));
+}
+END_SETUP()
+
+FN_TEST(unshare_uts)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (unshare(CLONE_NEWUTS) < 0 ||
+		    sethostname(CHILD_HOSTNAME, strlen(CHILD_HOSTNAME)) < 0)
+			_exit(1);
+		_exit(is_hostname(CHILD_HOSTNAME) ? 0 : 1);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(is_hostname(init_hostname), _ret);
+}
+END_TEST()
+
+FN_TEST(unshare_ipc)
+{
+	pid_t pid;
+
+	TEST_RES(shmget(SHM_KEY, 0, 0), _ret == shmid);
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (unshare(CLONE_NEWIPC) < 0)
+			_exit(1);
+		if (shmget(SHM_KEY, 0, 0) >= 0 || errno != ENOENT)
+			_exit(1);
+		if (shmget(SHM_KEY, 4096, IPC_CREAT | IPC_EXCL | 0600) < 0)
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(shmget(SHM_KEY, 0, 0), _ret == shmid);
+}
+END_TEST()
+
+FN_TEST(clone_new_uts)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(syscall(SYS_clone, CLONE_NEWUTS | SIGCHLD, 0, 0, 0, 0));
+	if (pid == 0) {
+		if (sethostname(CHILD_HOSTNAME, strlen(CHILD_HOSTNAME)) < 0)
+			_exit(1);
+		_exit(is_hostname(CHILD_HOSTNAME) ? 0 : 1);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(is_hostname(init_hostname), _ret);
+
+	TEST_ERRNO(syscall(SYS_clone, CLONE_NEWNS | CLONE_FS | SIGCHLD, 0, 0, 0,
+			   0),
+		   EINVAL);
+}
+END_TEST()
+
+// Runs in the init process of a new PID namespace
+static int pid_ns_init(void)
+{
+	char buf[16];
+	pid_t pid;
+	ssize_t len;
+
+	if (getpid() != 1 || getppid() != 0)
+		return 1;
+
+	// The processes in the namespace are numbered from 1
+	pid = fork();
+	if (pid < 0)
+		return 1;
+	if (pid == 0)
+		_exit(getpid() == 2 && getppid() == 1 ? 0 : 1);
+	if (pid != 2 || !wait_child_success(pid))
+		return 1;
+
+	// The reaped child and the processes outside the namespace are not visible
+	if (kill(2, 0) == 0 || errno != ESRCH)
+		return 1;
+
+	// A procfs instance shows the PIDs in the namespace of the mounter
+	if (unshare(CLONE_NEWNS) < 0 || mkdir(MNT_DIR, 0755) < 0 ||
+	    mount("proc", MNT_DIR, "proc", 0, NULL) < 0)
+		return 1;
+	len = readlink(MNT_DIR "/self", buf, sizeof(buf) - 1);
+	if (len < 0)
+		return 1;
+	buf[len] = '\0';
+	if (strcmp(buf, "1") != 0 || access(MNT_DIR "/2", F_OK) == 0)
+		return 1;
+	if (umount(MNT_DIR) < 0 || rmdir(MNT_DIR) < 0)
+		return 1;
+
+	return 0;
+}
+
+FN_TEST(unshare_pid)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		pid_t self = getpid();
+		pid_t init;
+
+		if (unshare(CLONE_NEWPID) < 0)
+			_exit(1);
+		// The caller itself stays in the old namespace
+		if (getpid() != self)
+			_exit(1);
+		// The namespace for the children can only be changed once
+		if (unshare(CLONE_NEWPID) == 0 || errno != EINVAL)
+			_exit(1);
+
+		init = fork();
+		if (init < 0)
+			_exit(1);
+		if (init == 0)
+			_exit(pid_ns_init());
+		// The parent sees the child with the PID in its own namespace
+		if (init == 1 || kill(init, 0) < 0)
+			_exit(1);
+		_exit(wait_child_success(init) ? 0 : 1);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+}
+END_TEST()
+
+FN_TEST(clone_new_pid)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(syscall(SYS_clone, CLONE_NEWPID | SIGCHLD, 0, 0, 0, 0));
+	if (pid == 0)
+		_exit(getpid() == 1 && getppid() == 0 ? 0 : 1);
+	TEST_RES(pid, _ret != 1);
+	TEST_RES(wait_child_success(pid), _ret);
+}
+END_TEST()
+
+static int has_only_loopback(void)
+{
+	struct if_nameindex *ifaces;
+	int ret;
+
+	ifaces = if_nameindex();
+	if (ifaces == NULL)
+		return 0;
+	ret = ifaces[0].if_index == 1 && strcmp(ifaces[0].if_name, "lo") == 0 &&
+	      ifaces[1].if_index == 0;
+	if_freenameindex(ifaces);
+
+	return ret;
+}
+
+static int can_bind(const char *addr)
+{
+	struct sockaddr_in sin = {
+		.sin_family = AF_INET,
+		.sin_port = 0,
+	};
+	int fd, ret;
+
+	inet_pton(AF_INET, addr, &sin.sin_addr);
+	fd = socket(AF_INET, SOCK_STREAM, 0);
+	if (fd < 0)
+		return 0;
+	ret = bind(fd, (struct sockaddr *)&sin, sizeof(sin)) == 0;
+	close(fd);
+
+	return ret;
+}
+
+FN_TEST(unshare_net)
+{
+	unsigned int lo_index;
+	pid_t pid;
+
+	lo_index = TEST_RES(if_nametoindex("lo"), _ret != 0);
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		int fd = open("/proc/self/ns/net", O_RDONLY);
+
+		if (fd < 0 || unshare(CLONE_NEWNET) < 0)
+			_exit(1);
+		// Only a new loopback iface exists in the new namespace
+		if (!has_only_loopback() || !can_bind("127.0.0.1") ||
+		    can_bind("10.0.2.15"))
+			_exit(1);
+
+		// Entering the old namespace brings back the old ifaces
+		if (setns(fd, CLONE_NEWNET) < 0 ||
+		    if_nametoindex("lo") != lo_index)
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_RES(if_nametoindex("lo"), _ret == lo_index);
+
+	pid = TEST_SUCC(syscall(SYS_clone, CLONE_NEWNET | SIGCHLD, 0, 0, 0, 0));
+	if (pid == 0)
+		_exit(has_only_loopback() ? 0 : 1);
+	TEST_RES(wait_child_success(pid), _ret);
+}
+END_TEST()
+
+FN_TEST(unshare_mnt)
+{
+	pid_t pid;
+
+	TEST_SUCC(mkdir(MNT_DIR, 0755));
+	TEST_SUCC(mount("/proc", MNT_DIR, NULL, MS_BIND, NULL));
+
+	// Unmounting the copy in the new namespace should not affect this one
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		if (unshare(CLONE_NEWNS) < 0 || umount(MNT_DIR) < 0)
+			_exit(1);
+		if (access(MNT_DIR "/self", F_OK) == 0 || errno != ENOENT)
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+	TEST_SUCC(access(MNT_DIR "/self", F_OK));
+	TEST_ERRNO(rmdir(MNT_DIR), EBUSY);
+
+	TEST_SUCC(umount(MNT_DIR));
+	TEST_ERRNO(access(MNT_DIR "/self", F_OK), ENOENT);
+	TEST_SUCC(rmdir(MNT_DIR));
+}
+END_TEST()
+
+FN_SETUP(spawn_child)
+{
+	int ready_pipe[2];
+	char buf;
+
+	CHECK(pipe(ready_pipe));
+	CHECK(pipe(done_pipe));
+	child = CHECK(fork());
+	if (child == 0) {
+		CHECK(close(ready_pipe[0]));
+		CHECK(close(done_pipe[1]));
+		CHECK(unshare(CLONE_NEWUTS));
+		CHECK(sethostname(CHILD_HOSTNAME, strlen(CHILD_HOSTNAME)));
+		// Notify the parent and wait until the parent finishes
+		CHECK(write(ready_pipe[1], "", 1));
+		CHECK(read(done_pipe[0], &buf, 1));
+		_exit(0);
+	}
+	CHECK(close(ready_pipe[1]));
+	CHECK(close(done_pipe[0]));
+	CHECK_WITH(read(ready_pipe[0], &buf, 1), _ret == 1);
+	CHECK(close(ready_pipe[0]));
+}
+END_SETUP()
+
+FN_TEST(setns)
+{
+	char path[64];
+	int self_fd, child_fd;
+
+	self_fd = TEST_SUCC(open("/proc/self/ns/uts", O_RDONLY));
+	snprintf(path, sizeof(path), "/proc/%d/ns/uts", child);
+	child_fd = TEST_SUCC(open(path, O_RDONLY));
+
+	TEST_ERRNO(setns(child_fd, CLONE_NEWIPC), EINVAL);
+	TEST_ERRNO(setns(done_pipe[1], 0), EINVAL);
+	TEST_RES(is_hostname(init_hostname), _ret);
+
+	TEST_SUCC(setns(child_fd, CLONE_NEWUTS));
+	TEST_RES(is_hostname(CHILD_HOSTNAME), _ret);
+
+	TEST_SUCC(setns(self_fd, 0));
+	TEST_RES(is_hostname(init_hostname), _ret);
+
+	TEST_SUCC(close(self_fd));
+	TEST_SUCC(close(child_fd));
+}
+END_TEST()
+
+FN_SETUP(reap_child)
+{
+	CHECK(close(done_pipe[1]));
+	CHECK_WITH(wait_child_success(child), _ret);
+}
+END_SETUP()
+
+FN_TEST(invalid_args)
+{
+	char name[66];
+
+	memset(name, 'a', sizeof(name));
+	TEST_ERRNO(sethostname(name, 65), EINVAL);
+	TEST_ERRNO(setdomainname(name, 65), EINVAL);
+	TEST_ERRNO(setns(-1, 0), EBADF);
+}
+END_TEST()
+
+FN_TEST(no_permission)
+{
+	pid_t pid;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		struct __user_cap_header_struct header = {
+			.version = _LINUX_CAPABILITY_VERSION_3,
+			.pid = 0,
+		};
+		struct __user_cap_data_struct data[2] = {};
+
+		// Drop all the capabilities
+		if (syscall(SYS_capset, &header, data) < 0)
+			_exit(1);
+		if (unshare(CLONE_NEWUTS) == 0 || errno != EPERM)
+			_exit(1);
+		if (sethostname(CHILD_HOSTNAME, strlen(CHILD_HOSTNAME)) == 0 ||
+		    errno != EPERM)
+			_exit(1);
+		_exit(0);
+	}
+	TEST_RES(wait_child_success(pid), _ret);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(shmctl(shmid, IPC_RMID, NULL));
+}
+END_SETUP()
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/ioctl.h>
+#include <sys/socket.h>
+#include <net/if.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+// The DNS server and the DHCP server of the QEMU user networking
+#define PNP_CONTENT "#PROTO: DHCP\nnameserver 10.0.2.3\nbootserver 10.0.2.2\n"
+
+static char buf[256];
+
+// `DHCP` is set by the kernel command line if the kernel runs with `DHCP=1`
+FN_TEST(proc_net_pnp)
+{
+	int fd;
+
+	if (getenv("DHCP") == NULL) {
+		TEST_ERRNO(open("/proc/net/pnp", O_RDONLY), ENOENT);
+	} else {
+		fd = TEST_SUCC(open("/proc/net/pnp", O_RDONLY));
+		TEST_RES(read(fd, buf, sizeof(buf) - 1),
+			 _ret == strlen(PNP_CONTENT) &&
+				 memcmp(buf, PNP_CONTENT, _ret) == 0);
+		TEST_SUCC(close(fd));
+	}
+}
+END_TEST()
+
+// The address is the first one that the QEMU user networking allocates, which
+// is the same as the static configuration
+FN_TEST(leased_addr)
+{
+	struct ifreq ifreq;
+	struct sockaddr_in *addr = (struct sockaddr_in *)&ifreq.ifr_addr;
+	int sk;
+
+	sk = TEST_SUCC(socket(PF_INET, SOCK_DGRAM, 0));
+	strcpy(ifreq.ifr_name, "eth0");
+
+	TEST_RES(ioctl(sk, SIOCGIFADDR, &ifreq),
+		 addr->sin_addr.s_addr == inet_addr("10.0.2.15"));
+	TEST_RES(ioctl(sk, SIOCGIFNETMASK, &ifreq),
+		 addr->sin_addr.s_addr == inet_addr("255.255.255.0"));
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/poll.h>
+#include <netinet/in.h>
+#include <netinet/ip_icmp.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+static struct sockaddr_in sk_addr;
+
+#define C_IDENT htons(0x1234)
+#define C_PORT htons(0x4321)
+
+static int sk_ping;
+static int sk_udp;
+
+FN_SETUP(general)
+{
+	sk_addr.sin_family = AF_INET;
+	CHECK(inet_aton("127.0.0.1", &sk_addr.sin_addr));
+}
+END_SETUP()
+
+FN_SETUP(ping)
+{
+	sk_ping = CHECK(socket(PF_INET, SOCK_DGRAM, IPPROTO_ICMP));
+
+	sk_addr.sin_port = C_IDENT;
+	CHECK(bind(sk_ping, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+}
+END_SETUP()
+
+FN_SETUP(udp)
+{
+	sk_udp = CHECK(socket(PF_INET, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+
+	// Nothing is listening on `C_PORT`
+	sk_addr.sin_port = C_PORT;
+	CHECK(connect(sk_udp, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+}
+END_SETUP()
+
+FN_TEST(getsockname)
+{
+	struct sockaddr_in saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	TEST_RES(getsockname(sk_ping, (struct sockaddr *)&saddr, &addrlen),
+		 addrlen == sizeof(saddr) && saddr.sin_port == C_IDENT);
+}
+END_TEST()
+
+FN_TEST(echo)
+{
+	struct icmphdr hdr = { .type = ICMP_ECHO };
+	char buf[sizeof(hdr) + 8];
+	struct icmphdr *reply = (struct icmphdr *)buf;
+	struct sockaddr_in saddr;
+	socklen_t addrlen = sizeof(saddr);
+	int i;
+
+	for (i = 1; i <= 3; ++i) {
+		hdr.un.echo.sequence = htons(i);
+		memcpy(buf, &hdr, sizeof(hdr));
+		memcpy(buf + sizeof(hdr), "abcdefgh", 8);
+
+		sk_addr.sin_port = 0;
+		TEST_RES(sendto(sk_ping, buf, sizeof(buf), 0,
+				(struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+			 _ret == sizeof(buf));
+
+		memset(buf, 0, sizeof(buf));
+		TEST_RES(recvfrom(sk_ping, buf, sizeof(buf), 0,
+				  (struct sockaddr *)&saddr, &addrlen),
+			 _ret == sizeof(buf) && addrlen == sizeof(saddr) &&
+				 saddr.sin_addr.s_addr ==
+					 sk_addr.sin_addr.s_addr &&
+				 reply->type == ICMP_ECHOREPLY &&
+				 reply->code == 0 &&
+				 reply->un.echo.id == C_IDENT &&
+				 reply->un.echo.sequence == htons(i) &&
+				 memcmp(buf + sizeof(hdr), "abcdefgh", 8) == 0);
+	}
+}
+END_TEST()
+
+FN_TEST(echo_invalid)
+{
+	struct icmphdr hdr = { .type = ICMP_ECHOREPLY };
+
+	sk_addr.sin_port = 0;
+
+	TEST_ERRNO(sendto(sk_ping, &hdr, sizeof(hdr), 0,
+			  (struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+		   EINVAL);
+
+	hdr.type = ICMP_ECHO;
+	TEST_ERRNO(sendto(sk_ping, &hdr, sizeof(hdr) - 1, 0,
+			  (struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+		   EINVAL);
+
+	TEST_ERRNO(recv(sk_ping, &hdr, sizeof(hdr), MSG_DONTWAIT), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(port_unreachable)
+{
+	struct pollfd pfd = { .fd = sk_udp, .events = POLLIN };
+	char buf[1] = { 'z' };
+	int err;
+	socklen_t optlen = sizeof(err);
+
+	TEST_RES(send(sk_udp, buf, 1, 0), _ret == 1);
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && pfd.revents == POLLERR);
+	TEST_ERRNO(recv(sk_udp, buf, 1, 0), ECONNREFUSED);
+
+	// The error is reported only once
+	TEST_ERRNO(recv(sk_udp, buf, 1, 0), EAGAIN);
+
+	TEST_RES(send(sk_udp, buf, 1, 0), _ret == 1);
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && pfd.revents == POLLERR);
+	TEST_RES(getsockopt(sk_udp, SOL_SOCKET, SO_ERROR, &err, &optlen),
+		 err == ECONNREFUSED);
+	TEST_RES(getsockopt(sk_udp, SOL_SOCKET, SO_ERROR, &err, &optlen),
+		 err == 0);
+	TEST_ERRNO(recv(sk_udp, buf, 1, 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(recv_err)
+{
+	int enable = 1;
+	int val;
+	socklen_t optlen = sizeof(val);
+
+	TEST_RES(getsockopt(sk_ping, SOL_IP, IP_RECVERR, &val, &optlen),
+		 optlen == sizeof(val) && val == 0);
+	TEST_SUCC(setsockopt(sk_ping, SOL_IP, IP_RECVERR, &enable,
+			     sizeof(enable)));
+	TEST_RES(getsockopt(sk_ping, SOL_IP, IP_RECVERR, &val, &optlen),
+		 optlen == sizeof(val) && val == 1);
+}
+END_TEST()
+
+FN_TEST(port_unreachable_recv_err)
+{
+	struct pollfd pfd = { .events = POLLIN };
+	char buf[1] = { 'z' };
+	int enable = 1;
+	int sk;
+
+	sk = TEST_SUCC(socket(PF_INET, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+	pfd.fd = sk;
+	sk_addr.sin_port = C_PORT;
+
+	// Without `IP_RECVERR`, unconnected sockets ignore the ICMP errors
+	TEST_RES(sendto(sk, buf, 1, 0, (struct sockaddr *)&sk_addr,
+			sizeof(sk_addr)),
+		 _ret == 1);
+	TEST_RES(poll(&pfd, 1, 100), _ret == 0);
+	TEST_ERRNO(recv(sk, buf, 1, 0), EAGAIN);
+
+	// With `IP_RECVERR`, unconnected sockets report the ICMP errors
+	TEST_SUCC(setsockopt(sk, SOL_IP, IP_RECVERR, &enable, sizeof(enable)));
+	TEST_RES(sendto(sk, buf, 1, 0, (struct sockaddr *)&sk_addr,
+			sizeof(sk_addr)),
+		 _ret == 1);
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && pfd.revents == POLLERR);
+	TEST_ERRNO(recv(sk, buf, 1, 0), ECONNREFUSED);
+	TEST_ERRNO(recv(sk, buf, 1, 0), EAGAIN);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_ping));
+	CHECK(close(sk_udp));
+}
+END_SETUP()
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+static struct sockaddr_in6 sk_addr;
+static struct sockaddr_in6 sk_mapped_addr;
+
+#define C_PORT htons(0x1235)
+#define S_PORT htons(0x1236)
+
+FN_SETUP(general)
+{
+	sk_addr.sin6_family = AF_INET6;
+	sk_addr.sin6_addr = in6addr_loopback;
+
+	sk_mapped_addr.sin6_family = AF_INET6;
+	sk_mapped_addr.sin6_port = S_PORT;
+	CHECK(inet_pton(AF_INET6, "::ffff:127.0.0.1",
+			&sk_mapped_addr.sin6_addr));
+}
+END_SETUP()
+
+static int sk_unbound;
+static int sk_inet;
+
+FN_SETUP(unbound)
+{
+	sk_unbound = CHECK(socket(PF_INET6, SOCK_DGRAM, 0));
+	sk_inet = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+}
+END_SETUP()
+
+FN_TEST(getsockname)
+{
+	struct sockaddr_in6 saddr;
+	struct sockaddr *psaddr = (struct sockaddr *)&saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	TEST_RES(getsockname(sk_unbound, psaddr, &addrlen),
+		 addrlen == sizeof(saddr) && saddr.sin6_family == AF_INET6 &&
+			 saddr.sin6_port == 0);
+}
+END_TEST()
+
+FN_TEST(v6_only)
+{
+	int sk;
+	int v6_only;
+	socklen_t optlen = sizeof(v6_only);
+
+	TEST_RES(getsockopt(sk_unbound, IPPROTO_IPV6, IPV6_V6ONLY, &v6_only,
+			    &optlen),
+		 optlen == sizeof(v6_only) && v6_only == 0);
+
+	TEST_ERRNO(getsockopt(sk_inet, IPPROTO_IPV6, IPV6_V6ONLY, &v6_only,
+			      &optlen),
+		   ENOPROTOOPT);
+
+	sk = TEST_SUCC(socket(PF_INET6, SOCK_DGRAM, 0));
+
+	v6_only = 1;
+	TEST_SUCC(setsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6_only,
+			     sizeof(v6_only)));
+	TEST_RES(getsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6_only, &optlen),
+		 v6_only == 1);
+
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&sk_mapped_addr,
+			   sizeof(sk_mapped_addr)),
+		   ENETUNREACH);
+
+	sk_addr.sin6_port = C_PORT;
+	TEST_SUCC(bind(sk, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+
+	v6_only = 0;
+	TEST_ERRNO(setsockopt(sk, IPPROTO_IPV6, IPV6_V6ONLY, &v6_only,
+			      sizeof(v6_only)),
+		   EINVAL);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(udp_loopback)
+{
+	int sk_send;
+	int sk_recv;
+	char buf[6];
+	struct sockaddr_in6 saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	sk_recv = TEST_SUCC(socket(PF_INET6, SOCK_DGRAM, 0));
+	sk_send = TEST_SUCC(socket(PF_INET6, SOCK_DGRAM, 0));
+
+	sk_addr.sin6_port = S_PORT;
+	TEST_SUCC(bind(sk_recv, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+
+	TEST_RES(sendto(sk_send, "hello", 6, 0, (struct sockaddr *)&sk_addr,
+			sizeof(sk_addr)),
+		 _ret == 6);
+
+	TEST_RES(recvfrom(sk_recv, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&saddr, &addrlen),
+		 _ret == 6 && strcmp(buf, "hello") == 0 &&
+			 addrlen == sizeof(saddr) &&
+			 saddr.sin6_family == AF_INET6 &&
+			 memcmp(&saddr.sin6_addr, &in6addr_loopback,
+				sizeof(in6addr_loopback)) == 0);
+
+	TEST_SUCC(close(sk_send));
+	TEST_SUCC(close(sk_recv));
+}
+END_TEST()
+
+FN_TEST(tcp_loopback)
+{
+	int sk_listen;
+	int sk_connect;
+	int sk_accept;
+	struct sockaddr_in6 saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	sk_listen = TEST_SUCC(socket(PF_INET6, SOCK_STREAM, 0));
+	sk_connect = TEST_SUCC(socket(PF_INET6, SOCK_STREAM, 0));
+
+	sk_addr.sin6_port = S_PORT;
+	TEST_SUCC(
+		bind(sk_listen, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+	TEST_SUCC(listen(sk_listen, 1));
+
+	TEST_SUCC(connect(sk_connect, (struct sockaddr *)&sk_addr,
+			  sizeof(sk_addr)));
+
+	sk_accept = TEST_RES(accept(sk_listen, (struct sockaddr *)&saddr,
+				    &addrlen),
+			     addrlen == sizeof(saddr) &&
+				     saddr.sin6_family == AF_INET6);
+
+	TEST_RES(getpeername(sk_connect, (struct sockaddr *)&saddr, &addrlen),
+		 saddr.sin6_family == AF_INET6 && saddr.sin6_port == S_PORT);
+
+	TEST_SUCC(close(sk_accept));
+	TEST_SUCC(close(sk_connect));
+	TEST_SUCC(close(sk_listen));
+}
+END_TEST()
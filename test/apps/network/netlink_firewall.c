@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+#include <linux/netlink.h>
+
+#include "test.h"
+
+// The messages of `NETLINK_FIREWALL` are specific to Asterinas.
+
+#define FWM_NEWRULE 16
+#define FWM_DELRULE 17
+#define FWM_GETRULE 18
+
+#define FW_HOOK_INGRESS 0
+#define FW_HOOK_EGRESS 1
+
+#define FW_ACCEPT 0
+#define FW_DROP 1
+#define FW_REJECT 2
+
+#define FWA_SRC 1
+#define FWA_DST 2
+#define FWA_SPORT 3
+#define FWA_DPORT 4
+
+struct fwrulemsg {
+	unsigned char fw_family;
+	unsigned char fw_hook;
+	unsigned char fw_verdict;
+	unsigned char fw_protocol;
+	unsigned char fw_src_len;
+	unsigned char fw_dst_len;
+	unsigned short fw_pad;
+	unsigned int fw_index;
+};
+
+struct rule_req {
+	struct nlmsghdr hdr;
+	struct fwrulemsg fwm;
+	struct nlattr dst_nla;
+	struct in_addr dst;
+	struct nlattr dport_nla;
+	unsigned short dport;
+	unsigned short pad;
+};
+
+#define C_PORT htons(0x1239)
+#define S_PORT htons(0x123A)
+
+static int sk_fw;
+static int sk_server;
+static int sk_client;
+static struct sockaddr_in sk_addr;
+
+static char buffer[8192];
+
+FN_SETUP(general)
+{
+	sk_fw = CHECK(socket(AF_NETLINK, SOCK_RAW, NETLINK_FIREWALL));
+
+	sk_addr.sin_family = AF_INET;
+	CHECK(inet_aton("127.0.0.1", &sk_addr.sin_addr));
+
+	sk_server = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+	sk_addr.sin_port = S_PORT;
+	CHECK(bind(sk_server, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+
+	sk_client = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+	sk_addr.sin_port = C_PORT;
+	CHECK(bind(sk_client, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+
+	sk_addr.sin_port = S_PORT;
+	CHECK(connect(sk_client, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+}
+END_SETUP()
+
+static int modify_rule(int type, int flags, int verdict)
+{
+	struct rule_req req;
+	struct nlmsgerr *err;
+	int len;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = type;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_ACK | flags;
+	req.hdr.nlmsg_seq = 1;
+	req.fwm.fw_family = AF_INET;
+	req.fwm.fw_hook = FW_HOOK_INGRESS;
+	req.fwm.fw_verdict = verdict;
+	req.fwm.fw_protocol = IPPROTO_UDP;
+	req.fwm.fw_dst_len = 32;
+	req.dst_nla.nla_len = NLA_HDRLEN + sizeof(req.dst);
+	req.dst_nla.nla_type = FWA_DST;
+	req.dst = sk_addr.sin_addr;
+	req.dport_nla.nla_len = NLA_HDRLEN + sizeof(req.dport);
+	req.dport_nla.nla_type = FWA_DPORT;
+	req.dport = S_PORT;
+
+	if (send(sk_fw, &req, sizeof(req), 0) < 0)
+		return -1;
+	len = recv(sk_fw, buffer, sizeof(buffer), 0);
+	if (len < 0)
+		return -1;
+
+	err = NLMSG_DATA(buffer);
+	if (((struct nlmsghdr *)buffer)->nlmsg_type != NLMSG_ERROR)
+		return -1;
+	if (err->error < 0) {
+		errno = -err->error;
+		return -1;
+	}
+	return 0;
+}
+
+static int count_rules(int verdict)
+{
+	struct {
+		struct nlmsghdr hdr;
+		struct fwrulemsg fwm;
+	} req;
+	struct nlmsghdr *nlh;
+	struct fwrulemsg *fwm;
+	int len;
+	int count = 0;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = FWM_GETRULE;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_DUMP;
+	req.hdr.nlmsg_seq = 2;
+
+	if (send(sk_fw, &req, sizeof(req), 0) < 0)
+		return -1;
+	len = recv(sk_fw, buffer, sizeof(buffer), 0);
+	if (len < 0)
+		return -1;
+
+	for (nlh = (struct nlmsghdr *)buffer; NLMSG_OK(nlh, len);
+	     nlh = NLMSG_NEXT(nlh, len)) {
+		if (nlh->nlmsg_type == NLMSG_DONE)
+			return count;
+		if (nlh->nlmsg_type != FWM_NEWRULE)
+			return -1;
+
+		fwm = NLMSG_DATA(nlh);
+		if (fwm->fw_protocol == IPPROTO_UDP &&
+		    fwm->fw_verdict == verdict && fwm->fw_dst_len == 32)
+			count++;
+	}
+
+	return -1;
+}
+
+FN_TEST(invalid_rule)
+{
+	TEST_ERRNO(modify_rule(FWM_NEWRULE, 0, 0xff), EINVAL);
+	TEST_ERRNO(modify_rule(FWM_DELRULE, 0, FW_DROP), ENOENT);
+}
+END_TEST()
+
+FN_TEST(drop)
+{
+	char c;
+
+	TEST_RES(send(sk_client, "a", 1, 0), _ret == 1);
+	TEST_RES(recv(sk_server, &c, 1, 0), _ret == 1 && c == 'a');
+
+	TEST_SUCC(modify_rule(FWM_NEWRULE, NLM_F_CREATE | NLM_F_EXCL, FW_DROP));
+	TEST_RES(count_rules(FW_DROP), _ret > 0);
+	TEST_ERRNO(modify_rule(FWM_NEWRULE, NLM_F_CREATE | NLM_F_EXCL, FW_DROP),
+		   EEXIST);
+
+	TEST_RES(send(sk_client, "b", 1, 0), _ret == 1);
+	TEST_ERRNO(recv(sk_server, &c, 1, MSG_DONTWAIT), EAGAIN);
+
+	TEST_SUCC(modify_rule(FWM_DELRULE, 0, FW_DROP));
+	TEST_RES(count_rules(FW_DROP), _ret == 0);
+
+	TEST_RES(send(sk_client, "c", 1, 0), _ret == 1);
+	TEST_RES(recv(sk_server, &c, 1, 0), _ret == 1 && c == 'c');
+}
+END_TEST()
+
+FN_TEST(accept_before_drop)
+{
+	char c;
+
+	TEST_SUCC(modify_rule(FWM_NEWRULE, NLM_F_CREATE | NLM_F_APPEND,
+			      FW_DROP));
+	// Without `NLM_F_APPEND`, the rule is inserted before the existing rules.
+	TEST_SUCC(modify_rule(FWM_NEWRULE, NLM_F_CREATE, FW_ACCEPT));
+
+	TEST_RES(send(sk_client, "d", 1, 0), _ret == 1);
+	TEST_RES(recv(sk_server, &c, 1, 0), _ret == 1 && c == 'd');
+
+	TEST_SUCC(modify_rule(FWM_DELRULE, 0, FW_ACCEPT));
+	TEST_SUCC(modify_rule(FWM_DELRULE, 0, FW_DROP));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_client));
+	CHECK(close(sk_server));
+	CHECK(close(sk_fw));
+}
+END_SETUP()
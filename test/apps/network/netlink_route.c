@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/ioctl.h>
+#include <net/if.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+#include <linux/netlink.h>
+#include <linux/rtnetlink.h>
+
+#include "test.h"
+
+static int sk_route;
+static int lo_index;
+
+FN_SETUP(general)
+{
+	sk_route = CHECK(socket(AF_NETLINK, SOCK_RAW, NETLINK_ROUTE));
+}
+END_SETUP()
+
+FN_TEST(invalid_socket)
+{
+	TEST_ERRNO(socket(AF_NETLINK, SOCK_STREAM, NETLINK_ROUTE),
+		   ESOCKTNOSUPPORT);
+	TEST_ERRNO(socket(AF_NETLINK, SOCK_RAW, MAX_LINKS), EPROTONOSUPPORT);
+}
+END_TEST()
+
+FN_TEST(getsockname)
+{
+	struct sockaddr_nl saddr;
+	socklen_t addrlen = sizeof(saddr);
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_NETLINK, SOCK_DGRAM, NETLINK_ROUTE));
+
+	TEST_RES(getsockname(sk, (struct sockaddr *)&saddr, &addrlen),
+		 addrlen == sizeof(saddr) && saddr.nl_family == AF_NETLINK &&
+			 saddr.nl_pid == 0);
+
+	memset(&saddr, 0, sizeof(saddr));
+	saddr.nl_family = AF_NETLINK;
+	TEST_SUCC(bind(sk, (struct sockaddr *)&saddr, sizeof(saddr)));
+
+	TEST_RES(getsockname(sk, (struct sockaddr *)&saddr, &addrlen),
+		 addrlen == sizeof(saddr) && saddr.nl_pid != 0);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(ioctl_lo)
+{
+	struct ifreq ifr;
+
+	memset(&ifr, 0, sizeof(ifr));
+	strcpy(ifr.ifr_name, "lo");
+	TEST_RES(ioctl(sk_route, SIOCGIFINDEX, &ifr), ifr.ifr_ifindex > 0);
+	lo_index = ifr.ifr_ifindex;
+
+	TEST_RES(ioctl(sk_route, SIOCGIFFLAGS, &ifr),
+		 (ifr.ifr_flags & IFF_UP) && (ifr.ifr_flags & IFF_LOOPBACK));
+
+	TEST_RES(ioctl(sk_route, SIOCGIFADDR, &ifr),
+		 ifr.ifr_addr.sa_family == AF_INET &&
+			 ((struct sockaddr_in *)&ifr.ifr_addr)->sin_addr.s_addr ==
+				 htonl(INADDR_LOOPBACK));
+
+	memset(&ifr, 0, sizeof(ifr));
+	ifr.ifr_ifindex = lo_index;
+	TEST_RES(ioctl(sk_route, SIOCGIFNAME, &ifr),
+		 strcmp(ifr.ifr_name, "lo") == 0);
+
+	memset(&ifr, 0, sizeof(ifr));
+	strcpy(ifr.ifr_name, "nonexistent");
+	TEST_ERRNO(ioctl(sk_route, SIOCGIFINDEX, &ifr), ENODEV);
+}
+END_TEST()
+
+FN_TEST(ioctl_not_socket)
+{
+	struct ifreq ifr;
+
+	memset(&ifr, 0, sizeof(ifr));
+	strcpy(ifr.ifr_name, "lo");
+	TEST_ERRNO(ioctl(STDIN_FILENO, SIOCGIFINDEX, &ifr), ENOTTY);
+}
+END_TEST()
+
+static char buffer[8192];
+
+FN_TEST(get_link)
+{
+	struct {
+		struct nlmsghdr hdr;
+		struct ifinfomsg ifi;
+	} req;
+	struct nlmsghdr *nlh;
+	struct ifinfomsg *ifi;
+	struct rtattr *rta;
+	int len, rta_len;
+	int found_lo = 0;
+	int done = 0;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = RTM_GETLINK;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_DUMP;
+	req.hdr.nlmsg_seq = 1;
+	req.ifi.ifi_family = AF_UNSPEC;
+
+	TEST_RES(send(sk_route, &req, sizeof(req), 0), _ret == sizeof(req));
+
+	len = TEST_SUCC(recv(sk_route, buffer, sizeof(buffer), 0));
+
+	for (nlh = (struct nlmsghdr *)buffer; NLMSG_OK(nlh, len);
+	     nlh = NLMSG_NEXT(nlh, len)) {
+		if (nlh->nlmsg_type == NLMSG_DONE) {
+			done = 1;
+			break;
+		}
+		if (nlh->nlmsg_type != RTM_NEWLINK || nlh->nlmsg_seq != 1)
+			break;
+
+		ifi = NLMSG_DATA(nlh);
+		if (ifi->ifi_index != lo_index)
+			continue;
+
+		rta_len = IFLA_PAYLOAD(nlh);
+		for (rta = IFLA_RTA(ifi); RTA_OK(rta, rta_len);
+		     rta = RTA_NEXT(rta, rta_len))
+			if (rta->rta_type == IFLA_IFNAME &&
+			    strcmp(RTA_DATA(rta), "lo") == 0)
+				found_lo = 1;
+	}
+
+	TEST_RES(0, found_lo && done);
+}
+END_TEST()
+
+FN_TEST(new_addr_invalid_index)
+{
+	struct {
+		struct nlmsghdr hdr;
+		struct ifaddrmsg ifa;
+		struct rtattr rta;
+		struct in_addr addr;
+	} req;
+	struct nlmsgerr *err;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = RTM_NEWADDR;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE;
+	req.hdr.nlmsg_seq = 2;
+	req.ifa.ifa_family = AF_INET;
+	req.ifa.ifa_prefixlen = 8;
+	req.ifa.ifa_index = 0x12345;
+	req.rta.rta_len = RTA_LENGTH(sizeof(req.addr));
+	req.rta.rta_type = IFA_LOCAL;
+	req.addr.s_addr = htonl(INADDR_LOOPBACK);
+
+	TEST_RES(send(sk_route, &req, sizeof(req), 0), _ret == sizeof(req));
+
+	err = NLMSG_DATA(buffer);
+	TEST_RES(recv(sk_route, buffer, sizeof(buffer), 0),
+		 ((struct nlmsghdr *)buffer)->nlmsg_type == NLMSG_ERROR &&
+			 ((struct nlmsghdr *)buffer)->nlmsg_seq == 2 &&
+			 err->error == -ENODEV);
+}
+END_TEST()
+
+static int find_route(int family, const void *dst, int dst_len, int oif)
+{
+	struct {
+		struct nlmsghdr hdr;
+		struct rtmsg rtm;
+	} req;
+	struct nlmsghdr *nlh;
+	struct rtmsg *rtm;
+	struct rtattr *rta;
+	int len, rta_len;
+	int found = 0;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = RTM_GETROUTE;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_DUMP;
+	req.hdr.nlmsg_seq = 3;
+	req.rtm.rtm_family = family;
+
+	if (send(sk_route, &req, sizeof(req), 0) < 0)
+		return -1;
+	len = recv(sk_route, buffer, sizeof(buffer), 0);
+	if (len < 0)
+		return -1;
+
+	for (nlh = (struct nlmsghdr *)buffer; NLMSG_OK(nlh, len);
+	     nlh = NLMSG_NEXT(nlh, len)) {
+		int dst_matched = 0, oif_matched = 0;
+
+		if (nlh->nlmsg_type != RTM_NEWROUTE)
+			break;
+
+		rtm = NLMSG_DATA(nlh);
+		if (rtm->rtm_family != family || rtm->rtm_dst_len != dst_len)
+			continue;
+
+		rta_len = RTM_PAYLOAD(nlh);
+		for (rta = RTM_RTA(rtm); RTA_OK(rta, rta_len);
+		     rta = RTA_NEXT(rta, rta_len)) {
+			if (rta->rta_type == RTA_DST &&
+			    memcmp(RTA_DATA(rta), dst, sizeof(struct in_addr)) ==
+				    0)
+				dst_matched = 1;
+			if (rta->rta_type == RTA_OIF &&
+			    *(int *)RTA_DATA(rta) == oif)
+				oif_matched = 1;
+		}
+
+		if (dst_matched && oif_matched)
+			found = 1;
+	}
+
+	return found;
+}
+
+static int modify_route(int type, int flags, const struct in_addr *dst,
+			int dst_len, int oif)
+{
+	struct {
+		struct nlmsghdr hdr;
+		struct rtmsg rtm;
+		struct rtattr dst_rta;
+		struct in_addr dst;
+		struct rtattr oif_rta;
+		int oif;
+	} req;
+	struct nlmsgerr *err;
+	int len;
+
+	memset(&req, 0, sizeof(req));
+	req.hdr.nlmsg_len = sizeof(req);
+	req.hdr.nlmsg_type = type;
+	req.hdr.nlmsg_flags = NLM_F_REQUEST | NLM_F_ACK | flags;
+	req.hdr.nlmsg_seq = 4;
+	req.rtm.rtm_family = AF_INET;
+	req.rtm.rtm_dst_len = dst_len;
+	req.rtm.rtm_table = RT_TABLE_MAIN;
+	req.rtm.rtm_protocol = RTPROT_BOOT;
+	req.rtm.rtm_scope = RT_SCOPE_LINK;
+	req.rtm.rtm_type = RTN_UNICAST;
+	req.dst_rta.rta_len = RTA_LENGTH(sizeof(req.dst));
+	req.dst_rta.rta_type = RTA_DST;
+	req.dst = *dst;
+	req.oif_rta.rta_len = RTA_LENGTH(sizeof(req.oif));
+	req.oif_rta.rta_type = RTA_OIF;
+	req.oif = oif;
+
+	if (send(sk_route, &req, sizeof(req), 0) < 0)
+		return -1;
+	len = recv(sk_route, buffer, sizeof(buffer), 0);
+	if (len < 0)
+		return -1;
+
+	err = NLMSG_DATA(buffer);
+	if (((struct nlmsghdr *)buffer)->nlmsg_type != NLMSG_ERROR)
+		return -1;
+	if (err->error < 0) {
+		errno = -err->error;
+		return -1;
+	}
+	return 0;
+}
+
+FN_TEST(route)
+{
+	struct in_addr lo_subnet = { htonl(0x7f000000) };
+	struct in_addr test_subnet = { htonl(0xc0000200) };
+
+	TEST_RES(find_route(AF_INET, &lo_subnet, 8, lo_index), _ret == 1);
+
+	TEST_SUCC(modify_route(RTM_NEWROUTE, NLM_F_CREATE | NLM_F_EXCL,
+			       &test_subnet, 24, lo_index));
+	TEST_RES(find_route(AF_INET, &test_subnet, 24, lo_index), _ret == 1);
+	TEST_ERRNO(modify_route(RTM_NEWROUTE, NLM_F_CREATE | NLM_F_EXCL,
+				&test_subnet, 24, lo_index),
+		   EEXIST);
+
+	TEST_SUCC(modify_route(RTM_DELROUTE, 0, &test_subnet, 24, lo_index));
+	TEST_RES(find_route(AF_INET, &test_subnet, 24, lo_index), _ret == 0);
+	TEST_ERRNO(modify_route(RTM_DELROUTE, 0, &test_subnet, 24, lo_index),
+		   ESRCH);
+}
+END_TEST()
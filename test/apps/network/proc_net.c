@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+#define S_PORT htons(0x1238)
+#define UNIX_PATH "/tmp/proc_net.sock"
+
+static char buf[8192];
+
+static int read_file(const char *path)
+{
+	int fd;
+	ssize_t len;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+
+	len = read(fd, buf, sizeof(buf) - 1);
+	close(fd);
+	if (len < 0)
+		return -1;
+
+	buf[len] = 0;
+	return 0;
+}
+
+FN_SETUP(tcp_listen)
+{
+	struct sockaddr_in addr;
+	int sk;
+
+	addr.sin_family = AF_INET;
+	addr.sin_port = S_PORT;
+	CHECK(inet_aton("127.0.0.1", &addr.sin_addr));
+
+	sk = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+	CHECK(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+	CHECK(listen(sk, 1));
+}
+END_SETUP()
+
+FN_SETUP(udp_bind)
+{
+	struct sockaddr_in addr;
+	int sk;
+
+	addr.sin_family = AF_INET;
+	addr.sin_port = S_PORT;
+	CHECK(inet_aton("127.0.0.1", &addr.sin_addr));
+
+	sk = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+	CHECK(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+}
+END_SETUP()
+
+FN_SETUP(unix_listen)
+{
+	struct sockaddr_un addr;
+	int sk;
+
+	addr.sun_family = AF_UNIX;
+	strcpy(addr.sun_path, UNIX_PATH);
+
+	sk = CHECK(socket(PF_UNIX, SOCK_STREAM, 0));
+	CHECK(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+	CHECK(listen(sk, 1));
+}
+END_SETUP()
+
+FN_TEST(tcp)
+{
+	TEST_RES(read_file("/proc/net/tcp"),
+		 strncmp(buf, "  sl  local_address rem_address   st", 36) == 0 &&
+			 strstr(buf, "0100007F:1238 00000000:0000 0A") != NULL);
+}
+END_TEST()
+
+FN_TEST(udp)
+{
+	TEST_RES(read_file("/proc/net/udp"),
+		 strstr(buf, "0100007F:1238 00000000:0000 07") != NULL);
+}
+END_TEST()
+
+FN_TEST(unix)
+{
+	TEST_RES(read_file("/proc/net/unix"),
+		 strstr(buf, "00010000 0001 01     0 " UNIX_PATH "\n") != NULL);
+}
+END_TEST()
+
+FN_TEST(dev)
+{
+	TEST_RES(read_file("/proc/net/dev"), strstr(buf, "    lo: ") != NULL);
+}
+END_TEST()
+
+FN_TEST(snmp)
+{
+	TEST_RES(read_file("/proc/net/snmp"),
+		 strstr(buf, "\nTcp: RtoAlgorithm RtoMin") != NULL &&
+			 strstr(buf, "\nUdp: InDatagrams NoPorts") != NULL);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(unlink(UNIX_PATH));
+}
+END_SETUP()
@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/poll.h>
+#include <net/if.h>
+#include <netinet/in.h>
+#include <netinet/ip.h>
+#include <netinet/ip_icmp.h>
+#include <netinet/if_ether.h>
+#include <netpacket/packet.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+static struct sockaddr_in sk_addr;
+
+#define C_IDENT htons(0x1234)
+#define C_ETH_TYPE 0x88b5
+
+// From <linux/icmp.h>, which conflicts with <netinet/ip_icmp.h>
+#define ICMP_FILTER 1
+struct icmp_filter {
+	unsigned int data;
+};
+
+static int sk_raw;
+static int sk_packet;
+static int eth0_index;
+
+static unsigned short checksum(void *data, size_t len)
+{
+	unsigned short *p = data;
+	unsigned int sum = 0;
+
+	for (; len > 1; len -= 2)
+		sum += *p++;
+	if (len == 1)
+		sum += *(unsigned char *)p;
+
+	sum = (sum >> 16) + (sum & 0xffff);
+	sum += sum >> 16;
+	return ~sum;
+}
+
+FN_SETUP(general)
+{
+	sk_addr.sin_family = AF_INET;
+	CHECK(inet_aton("127.0.0.1", &sk_addr.sin_addr));
+
+	eth0_index = CHECK(if_nametoindex("eth0"));
+}
+END_SETUP()
+
+FN_SETUP(raw)
+{
+	sk_raw = CHECK(socket(PF_INET, SOCK_RAW, IPPROTO_ICMP));
+}
+END_SETUP()
+
+FN_SETUP(packet)
+{
+	struct sockaddr_ll addr = { .sll_family = AF_PACKET,
+				    .sll_protocol = htons(ETH_P_ALL),
+				    .sll_ifindex = eth0_index };
+
+	sk_packet = CHECK(socket(PF_PACKET, SOCK_RAW | SOCK_NONBLOCK,
+				 htons(ETH_P_ALL)));
+	CHECK(bind(sk_packet, (struct sockaddr *)&addr, sizeof(addr)));
+}
+END_SETUP()
+
+FN_TEST(raw_getsockname)
+{
+	struct sockaddr_in saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	TEST_RES(getsockname(sk_raw, (struct sockaddr *)&saddr, &addrlen),
+		 addrlen == sizeof(saddr) &&
+			 saddr.sin_port == htons(IPPROTO_ICMP));
+}
+END_TEST()
+
+FN_TEST(raw_invalid_protocol)
+{
+	TEST_ERRNO(socket(PF_INET, SOCK_RAW, 0), EPROTONOSUPPORT);
+}
+END_TEST()
+
+FN_TEST(raw_echo)
+{
+	char buf[sizeof(struct iphdr) + sizeof(struct icmphdr) + 8];
+	struct icmphdr hdr = { .type = ICMP_ECHO };
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct icmphdr *icmp = (struct icmphdr *)(buf + sizeof(*ip));
+	struct sockaddr_in saddr;
+	socklen_t addrlen = sizeof(saddr);
+
+	hdr.un.echo.id = C_IDENT;
+	hdr.un.echo.sequence = htons(1);
+	memcpy(buf, &hdr, sizeof(hdr));
+	memcpy(buf + sizeof(hdr), "abcdefgh", 8);
+	((struct icmphdr *)buf)->checksum =
+		checksum(buf, sizeof(hdr) + 8);
+
+	TEST_RES(sendto(sk_raw, buf, sizeof(hdr) + 8, 0,
+			(struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+		 _ret == sizeof(hdr) + 8);
+
+	// The raw socket sees the echo request, which includes the IP header
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recvfrom(sk_raw, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&saddr, &addrlen),
+		 _ret == sizeof(buf) && addrlen == sizeof(saddr) &&
+			 saddr.sin_addr.s_addr == sk_addr.sin_addr.s_addr &&
+			 ip->version == 4 && ip->ihl == 5 &&
+			 ip->protocol == IPPROTO_ICMP &&
+			 icmp->type == ICMP_ECHO &&
+			 icmp->un.echo.id == C_IDENT);
+
+	// Then it sees the echo reply
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recvfrom(sk_raw, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&saddr, &addrlen),
+		 _ret == sizeof(buf) && addrlen == sizeof(saddr) &&
+			 saddr.sin_addr.s_addr == sk_addr.sin_addr.s_addr &&
+			 ip->protocol == IPPROTO_ICMP &&
+			 icmp->type == ICMP_ECHOREPLY &&
+			 icmp->un.echo.id == C_IDENT &&
+			 icmp->un.echo.sequence == htons(1) &&
+			 memcmp(buf + sizeof(*ip) + sizeof(hdr), "abcdefgh",
+				8) == 0);
+
+	TEST_ERRNO(recv(sk_raw, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(raw_icmp_filter)
+{
+	char buf[sizeof(struct iphdr) + sizeof(struct icmphdr)];
+	struct icmphdr hdr = { .type = ICMP_ECHO };
+	struct icmphdr *icmp = (struct icmphdr *)(buf + sizeof(struct iphdr));
+	struct icmp_filter filter = { .data = 1 << ICMP_ECHO };
+	socklen_t optlen = sizeof(filter);
+	int sk;
+
+	TEST_SUCC(setsockopt(sk_raw, SOL_RAW, ICMP_FILTER, &filter,
+			     sizeof(filter)));
+	filter.data = 0;
+	TEST_RES(getsockopt(sk_raw, SOL_RAW, ICMP_FILTER, &filter, &optlen),
+		 optlen == sizeof(filter) && filter.data == 1 << ICMP_ECHO);
+
+	hdr.un.echo.id = C_IDENT;
+	hdr.un.echo.sequence = htons(2);
+	hdr.checksum = checksum(&hdr, sizeof(hdr));
+	TEST_RES(sendto(sk_raw, &hdr, sizeof(hdr), 0,
+			(struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+		 _ret == sizeof(hdr));
+
+	// The echo request is filtered out, so only the echo reply is seen
+	TEST_RES(recv(sk_raw, buf, sizeof(buf), 0),
+		 _ret == sizeof(buf) && icmp->type == ICMP_ECHOREPLY &&
+			 icmp->un.echo.sequence == htons(2));
+	TEST_ERRNO(recv(sk_raw, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+
+	filter.data = 0;
+	TEST_SUCC(setsockopt(sk_raw, SOL_RAW, ICMP_FILTER, &filter,
+			     sizeof(filter)));
+
+	// The filter is only available for raw sockets of the ICMP protocol
+	sk = TEST_SUCC(socket(PF_INET, SOCK_RAW, IPPROTO_UDP));
+	TEST_ERRNO(setsockopt(sk, SOL_RAW, ICMP_FILTER, &filter,
+			      sizeof(filter)),
+		   EOPNOTSUPP);
+	TEST_SUCC(close(sk));
+
+	sk = TEST_SUCC(socket(PF_INET, SOCK_DGRAM, 0));
+	TEST_ERRNO(setsockopt(sk, SOL_RAW, ICMP_FILTER, &filter,
+			      sizeof(filter)),
+		   ENOPROTOOPT);
+	TEST_ERRNO(setsockopt(sk, SOL_IP, IP_HDRINCL, &filter.data,
+			      sizeof(filter.data)),
+		   ENOPROTOOPT);
+	TEST_ERRNO(setsockopt(sk, IPPROTO_UDP, 1, &filter.data,
+			      sizeof(filter.data)),
+		   ENOPROTOOPT);
+	TEST_ERRNO(setsockopt(sk, 12345, 1, &filter.data,
+			      sizeof(filter.data)),
+		   ENOPROTOOPT);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(raw_hdrincl)
+{
+	char buf[sizeof(struct iphdr) + sizeof(struct icmphdr)];
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct icmphdr *icmp = (struct icmphdr *)(buf + sizeof(*ip));
+	int enable = 1;
+	int val;
+	socklen_t optlen = sizeof(val);
+	int sk;
+
+	sk = TEST_SUCC(socket(PF_INET, SOCK_RAW, IPPROTO_ICMP));
+	TEST_RES(getsockopt(sk, SOL_IP, IP_HDRINCL, &val, &optlen),
+		 optlen == sizeof(val) && val == 0);
+	TEST_SUCC(setsockopt(sk, SOL_IP, IP_HDRINCL, &enable, sizeof(enable)));
+	TEST_RES(getsockopt(sk, SOL_IP, IP_HDRINCL, &val, &optlen),
+		 optlen == sizeof(val) && val == 1);
+
+	// The total length, the checksum, and the source address are filled in
+	memset(buf, 0, sizeof(buf));
+	ip->version = 4;
+	ip->ihl = 5;
+	ip->ttl = 33;
+	ip->protocol = IPPROTO_ICMP;
+	ip->daddr = sk_addr.sin_addr.s_addr;
+	icmp->type = ICMP_ECHO;
+	icmp->un.echo.id = C_IDENT;
+	icmp->un.echo.sequence = htons(3);
+	icmp->checksum = checksum(icmp, sizeof(*icmp));
+	TEST_RES(sendto(sk, buf, sizeof(buf), 0, (struct sockaddr *)&sk_addr,
+			sizeof(sk_addr)),
+		 _ret == sizeof(buf));
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_raw, buf, sizeof(buf), 0),
+		 _ret == sizeof(buf) && ip->ttl == 33 &&
+			 ip->saddr == sk_addr.sin_addr.s_addr &&
+			 ip->tot_len == htons(sizeof(buf)) &&
+			 icmp->type == ICMP_ECHO &&
+			 icmp->un.echo.sequence == htons(3));
+	TEST_RES(recv(sk_raw, buf, sizeof(buf), 0),
+		 _ret == sizeof(buf) && icmp->type == ICMP_ECHOREPLY &&
+			 icmp->un.echo.sequence == htons(3));
+	TEST_ERRNO(recv(sk_raw, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+
+	// The IP header must be complete
+	TEST_ERRNO(sendto(sk, buf, sizeof(*ip) - 1, 0,
+			  (struct sockaddr *)&sk_addr, sizeof(sk_addr)),
+		   EINVAL);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(packet_getsockname)
+{
+	struct sockaddr_ll addr;
+	socklen_t addrlen = sizeof(addr);
+
+	TEST_RES(getsockname(sk_packet, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == sizeof(addr) && addr.sll_family == AF_PACKET &&
+			 addr.sll_protocol == htons(ETH_P_ALL) &&
+			 addr.sll_ifindex == eth0_index);
+}
+END_TEST()
+
+FN_TEST(packet_no_iface)
+{
+	int sk;
+	char frame[ETH_ZLEN] = { 0 };
+
+	sk = TEST_SUCC(socket(PF_PACKET, SOCK_RAW, htons(ETH_P_ALL)));
+	TEST_ERRNO(send(sk, frame, sizeof(frame), 0), ENXIO);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(packet_inject)
+{
+	char frame[ETH_ZLEN] = { 0 };
+	char buf[ETH_FRAME_LEN];
+	struct ethhdr *eth = (struct ethhdr *)frame;
+	struct sockaddr_ll addr = { .sll_family = AF_PACKET,
+				    .sll_ifindex = eth0_index };
+	socklen_t addrlen = 0;
+	struct pollfd pfd = { .fd = sk_packet, .events = POLLIN };
+	int found = 0;
+	int i;
+
+	memset(eth->h_dest, 0xff, ETH_ALEN);
+	eth->h_proto = htons(C_ETH_TYPE);
+	memcpy(frame + sizeof(*eth), "abcdefgh", 8);
+
+	TEST_ERRNO(sendto(sk_packet, frame, sizeof(*eth) - 1, 0,
+			  (struct sockaddr *)&addr, sizeof(addr)),
+		   EINVAL);
+	TEST_RES(sendto(sk_packet, frame, sizeof(frame), 0,
+			(struct sockaddr *)&addr, sizeof(addr)),
+		 _ret == sizeof(frame));
+
+	// The injected frame is seen as an outgoing frame, but other frames
+	// may arrive in the meantime
+	for (i = 0; i < 16 && !found; ++i) {
+		if (poll(&pfd, 1, 1000) != 1)
+			break;
+
+		addrlen = sizeof(addr);
+		if (recvfrom(sk_packet, buf, sizeof(buf), 0,
+			     (struct sockaddr *)&addr, &addrlen) < 0)
+			break;
+
+		found = addr.sll_pkttype == PACKET_OUTGOING &&
+			addr.sll_protocol == htons(C_ETH_TYPE) &&
+			memcmp(buf, frame, sizeof(frame)) == 0;
+	}
+	TEST_RES(found, _ret == 1 && addrlen == sizeof(addr) &&
+				addr.sll_ifindex == eth0_index &&
+				addr.sll_halen == ETH_ALEN);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_raw));
+	CHECK(close(sk_packet));
+}
+END_SETUP()
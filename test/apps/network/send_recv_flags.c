@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <signal.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+static struct sockaddr_in sk_addr;
+
+#define S_PORT htons(0x1237)
+#define C_PORT htons(0x1238)
+
+static int sk_listen;
+static int sk_connected;
+static int sk_accepted;
+static int sk_udp_server;
+static int sk_udp_client;
+static int sk_unix[2];
+static int sk_seqpacket[2];
+
+static volatile sig_atomic_t sigpipe_count;
+
+static void sigpipe_handler(int sig)
+{
+	sigpipe_count++;
+}
+
+FN_SETUP(general)
+{
+	struct sigaction sa = { .sa_handler = sigpipe_handler };
+
+	sk_addr.sin_family = AF_INET;
+	sk_addr.sin_port = S_PORT;
+	CHECK(inet_aton("127.0.0.1", &sk_addr.sin_addr));
+
+	CHECK(sigaction(SIGPIPE, &sa, NULL));
+}
+END_SETUP()
+
+FN_SETUP(tcp)
+{
+	sk_listen = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+	CHECK(bind(sk_listen, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+	CHECK(listen(sk_listen, 1));
+
+	sk_connected = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+	CHECK(connect(sk_connected, (struct sockaddr *)&sk_addr,
+		      sizeof(sk_addr)));
+
+	sk_accepted = CHECK(accept(sk_listen, NULL, NULL));
+}
+END_SETUP()
+
+FN_SETUP(udp)
+{
+	struct sockaddr_in addr = sk_addr;
+
+	sk_udp_server = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+	CHECK(bind(sk_udp_server, (struct sockaddr *)&sk_addr,
+		   sizeof(sk_addr)));
+
+	addr.sin_port = C_PORT;
+	sk_udp_client = CHECK(socket(PF_INET, SOCK_DGRAM, 0));
+	CHECK(bind(sk_udp_client, (struct sockaddr *)&addr, sizeof(addr)));
+	CHECK(connect(sk_udp_client, (struct sockaddr *)&sk_addr,
+		      sizeof(sk_addr)));
+}
+END_SETUP()
+
+FN_SETUP(unix)
+{
+	CHECK(socketpair(PF_UNIX, SOCK_STREAM, 0, sk_unix));
+	CHECK(socketpair(PF_UNIX, SOCK_SEQPACKET, 0, sk_seqpacket));
+}
+END_SETUP()
+
+FN_TEST(tcp_peek)
+{
+	char buf[8];
+
+	TEST_ERRNO(recv(sk_accepted, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+
+	TEST_RES(send(sk_connected, "hello", 5, 0), _ret == 5);
+
+	// Peeking does not consume the data
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), MSG_PEEK),
+		 _ret == 5 && memcmp(buf, "hello", 5) == 0);
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_accepted, buf, 2, MSG_PEEK),
+		 _ret == 2 && memcmp(buf, "he", 2) == 0);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 5 && memcmp(buf, "hello", 5) == 0);
+
+	TEST_ERRNO(recv(sk_accepted, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(tcp_waitall)
+{
+	char buf[4];
+
+	TEST_RES(send(sk_connected, "ab", 2, 0), _ret == 2);
+	TEST_RES(send(sk_connected, "cd", 2, 0), _ret == 2);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), MSG_WAITALL),
+		 _ret == 4 && memcmp(buf, "abcd", 4) == 0);
+}
+END_TEST()
+
+FN_TEST(udp_peek_trunc)
+{
+	char buf[8];
+
+	TEST_ERRNO(recv(sk_udp_server, buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+
+	TEST_RES(send(sk_udp_client, "abcdef", 6, 0), _ret == 6);
+
+	// With `MSG_TRUNC`, the real length of the packet is returned
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_udp_server, buf, 3, MSG_PEEK | MSG_TRUNC),
+		 _ret == 6 && memcmp(buf, "abc\0", 4) == 0);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_udp_server, buf, sizeof(buf), 0),
+		 _ret == 6 && memcmp(buf, "abcdef", 6) == 0);
+
+	TEST_ERRNO(recv(sk_udp_server, buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+}
+END_TEST()
+
+FN_TEST(unix_peek_waitall)
+{
+	char buf[8];
+
+	TEST_ERRNO(recv(sk_unix[1], buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+
+	TEST_RES(send(sk_unix[0], "xyz", 3, 0), _ret == 3);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_unix[1], buf, sizeof(buf), MSG_PEEK),
+		 _ret == 3 && memcmp(buf, "xyz", 3) == 0);
+
+	TEST_RES(send(sk_unix[0], "w", 1, 0), _ret == 1);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_unix[1], buf, 4, MSG_WAITALL),
+		 _ret == 4 && memcmp(buf, "xyzw", 4) == 0);
+
+	TEST_ERRNO(recv(sk_unix[1], buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(seqpacket_peek)
+{
+	char buf[8];
+
+	TEST_RES(send(sk_seqpacket[0], "abc", 3, 0), _ret == 3);
+	TEST_RES(send(sk_seqpacket[0], "de", 2, 0), _ret == 2);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_seqpacket[1], buf, 2, MSG_PEEK | MSG_TRUNC),
+		 _ret == 3 && memcmp(buf, "ab\0", 3) == 0);
+
+	memset(buf, 0, sizeof(buf));
+	TEST_RES(recv(sk_seqpacket[1], buf, sizeof(buf), MSG_WAITALL),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0);
+	TEST_RES(recv(sk_seqpacket[1], buf, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0);
+}
+END_TEST()
+
+FN_TEST(sigpipe)
+{
+	char buf[1] = { 'a' };
+
+	sigpipe_count = 0;
+
+	TEST_SUCC(shutdown(sk_connected, SHUT_WR));
+	TEST_ERRNO(send(sk_connected, buf, 1, 0), EPIPE);
+	TEST_RES(sigpipe_count, _ret == 1);
+	TEST_ERRNO(send(sk_connected, buf, 1, MSG_NOSIGNAL), EPIPE);
+	TEST_RES(sigpipe_count, _ret == 1);
+
+	TEST_SUCC(shutdown(sk_unix[0], SHUT_WR));
+	TEST_ERRNO(send(sk_unix[0], buf, 1, 0), EPIPE);
+	TEST_RES(sigpipe_count, _ret == 2);
+	TEST_ERRNO(send(sk_unix[0], buf, 1, MSG_NOSIGNAL), EPIPE);
+	TEST_RES(sigpipe_count, _ret == 2);
+
+	// `SOCK_SEQPACKET` sockets never raise `SIGPIPE`
+	TEST_SUCC(shutdown(sk_seqpacket[0], SHUT_WR));
+	TEST_ERRNO(send(sk_seqpacket[0], buf, 1, 0), EPIPE);
+	TEST_RES(sigpipe_count, _ret == 2);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_listen));
+	CHECK(close(sk_connected));
+	CHECK(close(sk_accepted));
+	CHECK(close(sk_udp_server));
+	CHECK(close(sk_udp_client));
+	CHECK(close(sk_unix[0]));
+	CHECK(close(sk_unix[1]));
+	CHECK(close(sk_seqpacket[0]));
+	CHECK(close(sk_seqpacket[1]));
+}
+END_SETUP()
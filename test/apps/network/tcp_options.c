@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/time.h>
+#include <netinet/in.h>
+#include <netinet/tcp.h>
+#include <arpa/inet.h>
+
+#include "test.h"
+
+static struct sockaddr_in sk_addr;
+
+#define S_PORT htons(0x1236)
+
+static int sk_unbound;
+static int sk_listen;
+static int sk_connected;
+static int sk_accepted;
+
+FN_SETUP(general)
+{
+	sk_addr.sin_family = AF_INET;
+	sk_addr.sin_port = S_PORT;
+	CHECK(inet_aton("127.0.0.1", &sk_addr.sin_addr));
+}
+END_SETUP()
+
+FN_SETUP(unbound)
+{
+	sk_unbound = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+}
+END_SETUP()
+
+FN_SETUP(listen)
+{
+	int rcvbuf = 10000;
+
+	sk_listen = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+
+	CHECK(setsockopt(sk_listen, SOL_SOCKET, SO_RCVBUF, &rcvbuf,
+			 sizeof(rcvbuf)));
+
+	CHECK(bind(sk_listen, (struct sockaddr *)&sk_addr, sizeof(sk_addr)));
+	CHECK(listen(sk_listen, 2));
+}
+END_SETUP()
+
+FN_SETUP(connected)
+{
+	sk_connected = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+
+	CHECK(connect(sk_connected, (struct sockaddr *)&sk_addr,
+		      sizeof(sk_addr)));
+}
+END_SETUP()
+
+FN_SETUP(accepted)
+{
+	sk_accepted = CHECK(accept(sk_listen, NULL, NULL));
+}
+END_SETUP()
+
+FN_TEST(buffer_size)
+{
+	int val;
+	socklen_t len = sizeof(val);
+
+	// The value is doubled
+	val = 10000;
+	TEST_SUCC(setsockopt(sk_unbound, SOL_SOCKET, SO_RCVBUF, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_unbound, SOL_SOCKET, SO_RCVBUF, &val, &len),
+		 len == sizeof(val) && val == 20000);
+
+	val = 20000;
+	TEST_SUCC(setsockopt(sk_unbound, SOL_SOCKET, SO_SNDBUF, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_unbound, SOL_SOCKET, SO_SNDBUF, &val, &len),
+		 len == sizeof(val) && val == 40000);
+
+	// The value is rounded up to the minimum
+	val = 1;
+	TEST_SUCC(setsockopt(sk_unbound, SOL_SOCKET, SO_RCVBUF, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_unbound, SOL_SOCKET, SO_RCVBUF, &val, &len),
+		 len == sizeof(val) && val == 2304);
+
+	// The accepted socket inherits the value from the listening socket
+	TEST_RES(getsockopt(sk_accepted, SOL_SOCKET, SO_RCVBUF, &val, &len),
+		 len == sizeof(val) && val == 20000);
+}
+END_TEST()
+
+FN_TEST(keepalive)
+{
+	int val;
+	socklen_t len = sizeof(val);
+	char buf[1];
+
+	TEST_RES(getsockopt(sk_connected, SOL_SOCKET, SO_KEEPALIVE, &val,
+			    &len),
+		 len == sizeof(val) && val == 0);
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPIDLE, &val,
+			    &len),
+		 len == sizeof(val) && val == 7200);
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPINTVL, &val,
+			    &len),
+		 len == sizeof(val) && val == 75);
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPCNT, &val,
+			    &len),
+		 len == sizeof(val) && val == 9);
+
+	val = 1;
+	TEST_SUCC(setsockopt(sk_connected, SOL_SOCKET, SO_KEEPALIVE, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, SOL_SOCKET, SO_KEEPALIVE, &val,
+			    &len),
+		 len == sizeof(val) && val == 1);
+
+	val = 60;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPIDLE, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPIDLE, &val,
+			    &len),
+		 len == sizeof(val) && val == 60);
+
+	val = 10;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPINTVL, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPINTVL, &val,
+			    &len),
+		 len == sizeof(val) && val == 10);
+
+	val = 3;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPCNT, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPCNT, &val,
+			    &len),
+		 len == sizeof(val) && val == 3);
+
+	val = 0;
+	TEST_ERRNO(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPIDLE, &val,
+			      sizeof(val)),
+		   EINVAL);
+	TEST_ERRNO(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPINTVL, &val,
+			      sizeof(val)),
+		   EINVAL);
+	val = 128;
+	TEST_ERRNO(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPCNT, &val,
+			      sizeof(val)),
+		   EINVAL);
+
+	// The connection survives the probes because the peer answers them
+	val = 1;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPIDLE, &val,
+			     sizeof(val)));
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPINTVL, &val,
+			     sizeof(val)));
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_KEEPCNT, &val,
+			     sizeof(val)));
+	sleep(3);
+	TEST_RES(send(sk_connected, "k", 1, 0), _ret == 1);
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'k');
+
+	val = 0;
+	TEST_SUCC(setsockopt(sk_connected, SOL_SOCKET, SO_KEEPALIVE, &val,
+			     sizeof(val)));
+}
+END_TEST()
+
+FN_TEST(nodelay_and_cork)
+{
+	int val;
+	socklen_t len = sizeof(val);
+	char buf[1];
+
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val, &len),
+		 len == sizeof(val) && val == 0);
+
+	val = 1;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val, &len),
+		 len == sizeof(val) && val == 1);
+
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_NODELAY, &val,
+			     sizeof(val)));
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_NODELAY, &val,
+			    &len),
+		 len == sizeof(val) && val == 1);
+
+	// Uncorking flushes the pending data
+	val = 0;
+	TEST_RES(send(sk_connected, "a", 1, 0), _ret == 1);
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val,
+			     sizeof(val)));
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'a');
+
+	// The pending data is flushed after 200 milliseconds
+	val = 1;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val,
+			     sizeof(val)));
+	TEST_RES(send(sk_connected, "b", 1, 0), _ret == 1);
+	TEST_ERRNO(recv(sk_accepted, buf, sizeof(buf), MSG_DONTWAIT), EAGAIN);
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'b');
+
+	val = 0;
+	TEST_SUCC(setsockopt(sk_connected, IPPROTO_TCP, TCP_CORK, &val,
+			     sizeof(val)));
+}
+END_TEST()
+
+FN_TEST(recv_timeout)
+{
+	struct timeval tv;
+	socklen_t len = sizeof(tv);
+	char buf[1];
+
+	TEST_RES(getsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv, &len),
+		 len == sizeof(tv) && tv.tv_sec == 0 && tv.tv_usec == 0);
+
+	tv.tv_sec = 0;
+	tv.tv_usec = 1000000;
+	TEST_ERRNO(setsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv,
+			      sizeof(tv)),
+		   EDOM);
+	TEST_ERRNO(setsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv,
+			      sizeof(tv) - 1),
+		   EINVAL);
+
+	tv.tv_usec = 100000;
+	TEST_SUCC(setsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv,
+			     sizeof(tv)));
+	TEST_RES(getsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv, &len),
+		 len == sizeof(tv) && tv.tv_sec == 0 && tv.tv_usec == 100000);
+
+	// The blocking receive times out
+	TEST_ERRNO(recv(sk_accepted, buf, sizeof(buf), 0), EAGAIN);
+
+	// Data that arrives in time is received
+	TEST_RES(send(sk_connected, "b", 1, 0), _ret == 1);
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'b');
+
+	tv.tv_usec = 0;
+	TEST_SUCC(setsockopt(sk_accepted, SOL_SOCKET, SO_RCVTIMEO, &tv,
+			     sizeof(tv)));
+}
+END_TEST()
+
+FN_TEST(send_timeout)
+{
+	struct timeval tv = { .tv_sec = 1, .tv_usec = 0 };
+	socklen_t len = sizeof(tv);
+
+	TEST_SUCC(setsockopt(sk_connected, SOL_SOCKET, SO_SNDTIMEO, &tv,
+			     sizeof(tv)));
+	TEST_RES(getsockopt(sk_connected, SOL_SOCKET, SO_SNDTIMEO, &tv, &len),
+		 len == sizeof(tv) && tv.tv_sec == 1 && tv.tv_usec == 0);
+}
+END_TEST()
+
+FN_TEST(tcp_info)
+{
+	struct tcp_info info;
+	socklen_t len;
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_unbound, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_CLOSE);
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_listen, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_LISTEN);
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_connected, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_ESTABLISHED &&
+			 info.tcpi_snd_mss > 0);
+
+	// The information is truncated if the buffer is too short
+	memset(&info, 0, sizeof(info));
+	len = 1;
+	TEST_RES(getsockopt(sk_accepted, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == 1 && info.tcpi_state == TCP_ESTABLISHED);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_unbound));
+	CHECK(close(sk_listen));
+	CHECK(close(sk_connected));
+	CHECK(close(sk_accepted));
+}
+END_SETUP()
@@ -0,0 +1,325 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <unistd.h>
+#include <string.h>
+#include <fcntl.h>
+#include <poll.h>
+#include <linux/capability.h>
+#include <sys/resource.h>
+#include <sys/socket.h>
+#include <sys/syscall.h>
+#include <sys/un.h>
+#include <sys/wait.h>
+
+#include "test.h"
+
+static int sk_pair_stream[2];
+static int sk_pair_dgram[2];
+static int pipe_fds[2];
+static char buf[64];
+
+static ssize_t send_fds(int sk, const int *fds, int num_fds)
+{
+	char control[CMSG_SPACE(sizeof(int) * 4)] = { 0 };
+	struct iovec iov = { .iov_base = "x", .iov_len = 1 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = CMSG_SPACE(sizeof(int) * num_fds),
+	};
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_RIGHTS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(int) * num_fds);
+	memcpy(CMSG_DATA(cmsg), fds, sizeof(int) * num_fds);
+
+	return sendmsg(sk, &msg, 0);
+}
+
+static ssize_t send_creds(int sk, const struct ucred *cred)
+{
+	char control[CMSG_SPACE(sizeof(struct ucred))] = { 0 };
+	struct iovec iov = { .iov_base = "x", .iov_len = 1 };
+	struct msghdr msg = {
+		.msg_iov = &iov,
+		.msg_iovlen = 1,
+		.msg_control = control,
+		.msg_controllen = sizeof(control),
+	};
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_CREDENTIALS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(struct ucred));
+	memcpy(CMSG_DATA(cmsg), cred, sizeof(struct ucred));
+
+	return sendmsg(sk, &msg, 0);
+}
+
+static struct msghdr recv_msg;
+static struct iovec recv_iov;
+static char recv_control[CMSG_SPACE(sizeof(int) * 4) +
+			 CMSG_SPACE(sizeof(struct ucred))];
+
+static ssize_t recv_with_control(int sk, size_t control_len, int flags)
+{
+	memset(recv_control, 0, sizeof(recv_control));
+
+	recv_iov.iov_base = buf;
+	recv_iov.iov_len = sizeof(buf);
+
+	memset(&recv_msg, 0, sizeof(recv_msg));
+	recv_msg.msg_iov = &recv_iov;
+	recv_msg.msg_iovlen = 1;
+	recv_msg.msg_control = recv_control;
+	recv_msg.msg_controllen = control_len;
+
+	return recvmsg(sk, &recv_msg, flags);
+}
+
+static struct cmsghdr *find_cmsg(int type)
+{
+	struct cmsghdr *cmsg;
+
+	for (cmsg = CMSG_FIRSTHDR(&recv_msg); cmsg != NULL;
+	     cmsg = CMSG_NXTHDR(&recv_msg, cmsg))
+		if (cmsg->cmsg_level == SOL_SOCKET && cmsg->cmsg_type == type)
+			return cmsg;
+
+	return NULL;
+}
+
+static int received_fd(void)
+{
+	struct cmsghdr *cmsg = find_cmsg(SCM_RIGHTS);
+	int fd;
+
+	if (cmsg == NULL || cmsg->cmsg_len != CMSG_LEN(sizeof(int)))
+		return -1;
+
+	memcpy(&fd, CMSG_DATA(cmsg), sizeof(int));
+	return fd;
+}
+
+static int received_creds_match(void)
+{
+	struct cmsghdr *cmsg = find_cmsg(SCM_CREDENTIALS);
+	struct ucred cred;
+
+	if (cmsg == NULL || cmsg->cmsg_len != CMSG_LEN(sizeof(cred)))
+		return 0;
+
+	memcpy(&cred, CMSG_DATA(cmsg), sizeof(cred));
+	return cred.pid == getpid() && cred.uid == geteuid() &&
+	       cred.gid == getegid();
+}
+
+FN_SETUP(socketpair)
+{
+	CHECK(socketpair(AF_UNIX, SOCK_STREAM, 0, sk_pair_stream));
+	CHECK(socketpair(AF_UNIX, SOCK_DGRAM, 0, sk_pair_dgram));
+	CHECK(pipe(pipe_fds));
+}
+END_SETUP()
+
+FN_TEST(scm_rights)
+{
+	int i;
+
+	for (i = 0; i < 2; ++i) {
+		int *sk = i == 0 ? sk_pair_stream : sk_pair_dgram;
+		int fd;
+
+		TEST_RES(send_fds(sk[0], &pipe_fds[1], 1), _ret == 1);
+		TEST_RES(recv_with_control(sk[1], sizeof(recv_control), 0),
+			 _ret == 1 && buf[0] == 'x' &&
+				 !(recv_msg.msg_flags & MSG_CTRUNC));
+
+		fd = received_fd();
+		TEST_RES(fd, _ret >= 0 && _ret != pipe_fds[1]);
+		TEST_RES(fcntl(fd, F_GETFD), _ret == 0);
+
+		TEST_RES(write(fd, "pipe", 4), _ret == 4);
+		TEST_RES(read(pipe_fds[0], buf, sizeof(buf)),
+			 _ret == 4 && memcmp(buf, "pipe", 4) == 0);
+
+		TEST_SUCC(close(fd));
+	}
+}
+END_TEST()
+
+FN_TEST(scm_rights_cloexec)
+{
+	int fd;
+
+	TEST_RES(send_fds(sk_pair_stream[0], &pipe_fds[0], 1), _ret == 1);
+	TEST_RES(recv_with_control(sk_pair_stream[1], sizeof(recv_control),
+				   MSG_CMSG_CLOEXEC),
+		 _ret == 1);
+
+	fd = received_fd();
+	TEST_RES(fcntl(fd, F_GETFD), _ret == FD_CLOEXEC);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(scm_rights_boundary)
+{
+	int fd;
+
+	// The data sent with files is never merged with the data sent before
+	TEST_RES(send(sk_pair_stream[0], "ab", 2, 0), _ret == 2);
+	TEST_RES(send_fds(sk_pair_stream[0], &pipe_fds[0], 1), _ret == 1);
+
+	TEST_RES(recv_with_control(sk_pair_stream[1], sizeof(recv_control), 0),
+		 _ret == 2 && memcmp(buf, "ab", 2) == 0 &&
+			 received_fd() == -1);
+	TEST_RES(recv_with_control(sk_pair_stream[1], sizeof(recv_control), 0),
+		 _ret == 1 && buf[0] == 'x');
+
+	fd = received_fd();
+	TEST_RES(fd, _ret >= 0);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(scm_rights_ctrunc)
+{
+	TEST_RES(send_fds(sk_pair_dgram[0], &pipe_fds[0], 1), _ret == 1);
+	TEST_RES(recv_with_control(sk_pair_dgram[1], 0, 0),
+		 _ret == 1 && (recv_msg.msg_flags & MSG_CTRUNC) &&
+			 recv_msg.msg_controllen == 0);
+}
+END_TEST()
+
+FN_TEST(scm_rights_invalid)
+{
+	int bad_fd = 1000;
+
+	TEST_ERRNO(send_fds(sk_pair_dgram[0], &bad_fd, 1), EBADF);
+	TEST_ERRNO(recv(sk_pair_dgram[1], buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+}
+END_TEST()
+
+FN_TEST(scm_rights_cycle)
+{
+	int sk_a[2], sk_b[2];
+	int fds[2];
+	struct pollfd pfd;
+
+	TEST_SUCC(socketpair(AF_UNIX, SOCK_STREAM, 0, sk_a));
+	TEST_SUCC(socketpair(AF_UNIX, SOCK_STREAM, 0, sk_b));
+
+	// Send `sk_a[0]` to itself, along with `sk_b[0]`
+	fds[0] = sk_a[0];
+	fds[1] = sk_b[0];
+	TEST_RES(send_fds(sk_a[1], fds, 2), _ret == 1);
+
+	// Now `sk_a[0]` and `sk_b[0]` can only be reached from the receive queue of `sk_a[0]`
+	TEST_SUCC(close(sk_a[0]));
+	TEST_SUCC(close(sk_b[0]));
+	TEST_SUCC(close(sk_a[1]));
+
+	// The garbage collector should release `sk_b[0]`, so its peer sees the end of the stream
+	pfd.fd = sk_b[1];
+	pfd.events = POLLIN;
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && (pfd.revents & POLLIN));
+	TEST_RES(read(sk_b[1], buf, sizeof(buf)), _ret == 0);
+
+	TEST_SUCC(close(sk_b[1]));
+}
+END_TEST()
+
+#define MAX_INFLIGHT 16
+
+FN_TEST(scm_rights_limit)
+{
+	pid_t pid;
+	int status;
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		struct __user_cap_header_struct header = {
+			.version = _LINUX_CAPABILITY_VERSION_3,
+			.pid = 0,
+		};
+		struct __user_cap_data_struct data[2] = {};
+		struct rlimit rlim = { .rlim_cur = MAX_INFLIGHT,
+				       .rlim_max = MAX_INFLIGHT };
+		int sk[2];
+		int i;
+
+		// Drop all the capabilities so that the limit applies
+		if (socketpair(AF_UNIX, SOCK_STREAM, 0, sk) < 0 ||
+		    setrlimit(RLIMIT_NOFILE, &rlim) < 0 ||
+		    syscall(SYS_capset, &header, data) < 0)
+			_exit(1);
+
+		// The limit is exceeded only if more than `MAX_INFLIGHT` files are in flight
+		for (i = 0; i <= MAX_INFLIGHT; ++i)
+			if (send_fds(sk[0], &pipe_fds[0], 1) != 1)
+				_exit(1);
+		if (send_fds(sk[0], &pipe_fds[0], 1) >= 0 ||
+		    errno != ETOOMANYREFS)
+			_exit(1);
+
+		// The received files are no longer in flight
+		if (recv_with_control(sk[1], sizeof(recv_control), 0) != 1 ||
+		    received_fd() < 0 || send_fds(sk[0], &pipe_fds[0], 1) != 1)
+			_exit(1);
+		_exit(0);
+	}
+
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) && WEXITSTATUS(status) == 0);
+}
+END_TEST()
+
+FN_TEST(scm_credentials)
+{
+	struct ucred cred = { .pid = getpid(),
+			      .uid = geteuid(),
+			      .gid = getegid() };
+	int one = 1;
+	int i;
+
+	for (i = 0; i < 2; ++i) {
+		int *sk = i == 0 ? sk_pair_stream : sk_pair_dgram;
+
+		// Without `SO_PASSCRED`, no credentials are received
+		TEST_RES(send_creds(sk[0], &cred), _ret == 1);
+		TEST_RES(recv_with_control(sk[1], sizeof(recv_control), 0),
+			 _ret == 1 && find_cmsg(SCM_CREDENTIALS) == NULL);
+
+		TEST_SUCC(setsockopt(sk[1], SOL_SOCKET, SO_PASSCRED, &one,
+				     sizeof(one)));
+
+		TEST_RES(send_creds(sk[0], &cred), _ret == 1);
+		TEST_RES(recv_with_control(sk[1], sizeof(recv_control), 0),
+			 _ret == 1 && received_creds_match());
+
+		// With `SO_PASSCRED`, credentials are received even if the sender does not send them
+		TEST_RES(send(sk[0], "y", 1, 0), _ret == 1);
+		TEST_RES(recv_with_control(sk[1], sizeof(recv_control), 0),
+			 _ret == 1 && buf[0] == 'y' && received_creds_match());
+	}
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_pair_stream[0]));
+	CHECK(close(sk_pair_stream[1]));
+	CHECK(close(sk_pair_dgram[0]));
+	CHECK(close(sk_pair_dgram[1]));
+	CHECK(close(pipe_fds[0]));
+	CHECK(close(pipe_fds[1]));
+}
+END_SETUP()
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <unistd.h>
+#include <string.h>
+#include <stddef.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+
+#include "test.h"
+
+#define DGRAM_PATH "/tmp/unix_msg_dgram"
+#define SEQPACKET_NAME "\0unix_msg_seqpacket"
+
+static int sk_pair_dgram[2];
+static int sk_pair_seqpacket[2];
+static char buf[64];
+
+FN_SETUP(socketpair)
+{
+	CHECK(socketpair(AF_UNIX, SOCK_DGRAM, 0, sk_pair_dgram));
+	CHECK(socketpair(AF_UNIX, SOCK_SEQPACKET, 0, sk_pair_seqpacket));
+}
+END_SETUP()
+
+FN_TEST(message_boundaries)
+{
+	int i;
+
+	for (i = 0; i < 2; ++i) {
+		int *sk = i == 0 ? sk_pair_dgram : sk_pair_seqpacket;
+
+		TEST_RES(send(sk[0], "hello", 5, 0), _ret == 5);
+		TEST_RES(send(sk[0], "", 0, 0), _ret == 0);
+		TEST_RES(send(sk[0], "world!", 6, 0), _ret == 6);
+
+		TEST_RES(recv(sk[1], buf, sizeof(buf), 0),
+			 _ret == 5 && memcmp(buf, "hello", 5) == 0);
+		TEST_RES(recv(sk[1], buf, sizeof(buf), 0), _ret == 0);
+		TEST_RES(recv(sk[1], buf, 3, 0),
+			 _ret == 3 && memcmp(buf, "wor", 3) == 0);
+	}
+
+	TEST_ERRNO(recv(sk_pair_dgram[1], buf, sizeof(buf), MSG_DONTWAIT),
+		   EAGAIN);
+}
+END_TEST()
+
+FN_TEST(dgram_peek_trunc)
+{
+	TEST_RES(send(sk_pair_dgram[1], "message", 7, 0), _ret == 7);
+
+	TEST_RES(recv(sk_pair_dgram[0], buf, 3, MSG_PEEK | MSG_TRUNC),
+		 _ret == 7 && memcmp(buf, "mes", 3) == 0);
+	TEST_RES(recv(sk_pair_dgram[0], buf, sizeof(buf), 0),
+		 _ret == 7 && memcmp(buf, "message", 7) == 0);
+}
+END_TEST()
+
+FN_TEST(dgram_path)
+{
+	struct sockaddr_un addr = { .sun_family = AF_UNIX, .sun_path = DGRAM_PATH };
+	struct sockaddr_un from;
+	socklen_t from_len = sizeof(from);
+	int sk_server, sk_client;
+
+	unlink(DGRAM_PATH);
+
+	sk_server = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+	sk_client = TEST_SUCC(socket(AF_UNIX, SOCK_DGRAM, 0));
+
+	TEST_ERRNO(sendto(sk_client, "ping", 4, 0, (struct sockaddr *)&addr,
+			  sizeof(addr)),
+		   ENOENT);
+	TEST_ERRNO(send(sk_client, "ping", 4, 0), ENOTCONN);
+
+	TEST_SUCC(bind(sk_server, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_RES(sendto(sk_client, "ping", 4, 0, (struct sockaddr *)&addr,
+			sizeof(addr)),
+		 _ret == 4);
+
+	TEST_RES(recvfrom(sk_server, buf, sizeof(buf), 0,
+			  (struct sockaddr *)&from, &from_len),
+		 _ret == 4 && memcmp(buf, "ping", 4) == 0 &&
+			 from_len == sizeof(sa_family_t) &&
+			 from.sun_family == AF_UNIX);
+
+	TEST_SUCC(connect(sk_client, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_RES(send(sk_client, "pong", 4, 0), _ret == 4);
+	TEST_RES(recv(sk_server, buf, sizeof(buf), 0),
+		 _ret == 4 && memcmp(buf, "pong", 4) == 0);
+
+	TEST_SUCC(close(sk_server));
+	TEST_ERRNO(send(sk_client, "ping", 4, 0), ECONNREFUSED);
+
+	TEST_SUCC(close(sk_client));
+	TEST_SUCC(unlink(DGRAM_PATH));
+}
+END_TEST()
+
+FN_TEST(seqpacket_abstract)
+{
+	struct sockaddr_un addr = { .sun_family = AF_UNIX };
+	socklen_t addr_len =
+		offsetof(struct sockaddr_un, sun_path) + sizeof(SEQPACKET_NAME) - 1;
+	int sk_listen, sk_client, sk_accepted, sk_stream;
+
+	memcpy(addr.sun_path, SEQPACKET_NAME, sizeof(SEQPACKET_NAME) - 1);
+
+	sk_listen = TEST_SUCC(socket(AF_UNIX, SOCK_SEQPACKET, 0));
+	sk_client = TEST_SUCC(socket(AF_UNIX, SOCK_SEQPACKET, 0));
+	sk_stream = TEST_SUCC(socket(AF_UNIX, SOCK_STREAM, 0));
+
+	TEST_SUCC(bind(sk_listen, (struct sockaddr *)&addr, addr_len));
+	TEST_SUCC(listen(sk_listen, 2));
+
+	TEST_ERRNO(connect(sk_stream, (struct sockaddr *)&addr, addr_len),
+		   EPROTOTYPE);
+	TEST_SUCC(connect(sk_client, (struct sockaddr *)&addr, addr_len));
+	sk_accepted = TEST_SUCC(accept(sk_listen, NULL, NULL));
+
+	TEST_RES(send(sk_client, "abc", 3, 0), _ret == 3);
+	TEST_RES(send(sk_client, "de", 2, 0), _ret == 2);
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0);
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0);
+
+	TEST_SUCC(close(sk_client));
+	TEST_RES(recv(sk_accepted, buf, sizeof(buf), 0), _ret == 0);
+
+	TEST_SUCC(close(sk_accepted));
+	TEST_SUCC(close(sk_stream));
+	TEST_SUCC(close(sk_listen));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_pair_dgram[0]));
+	CHECK(close(sk_pair_dgram[1]));
+	CHECK(close(sk_pair_seqpacket[0]));
+	CHECK(close(sk_pair_seqpacket[1]));
+}
+END_SETUP()
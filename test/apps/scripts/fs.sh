@@ -64,3 +64,4 @@ echo "All fdatasync test passed."
 pipe/pipe_err
 pipe/short_rw
 epoll/epoll_err
+inotify/inotify_err
@@ -23,6 +23,17 @@ echo "Start network test......"
 ./http_client
 ./tcp_err
 ./udp_err
+./ipv6
+./netlink_route
+./netlink_firewall
 ./unix_err
+./unix_msg
+./unix_cmsg
+./icmp_ping
+./raw_socket
+./tcp_options
+./send_recv_flags
+./proc_net
+./dhcp_pnp
 
 echo "All network test passed"
@@ -10,9 +10,13 @@ cd ${SCRIPT_DIR}/..
 echo "Start process test......"
 # These test programs are sorted by name.
 tests="
+cgroup/cgroup
 clone3/clone_exit_signal
 clone3/clone_no_exit_signal
 clone3/clone_process
+cpu_affinity/load_balance
+cpu_affinity/nice_split
+cpu_affinity/sched_policy
 execve/execve
 eventfd2/eventfd2
 fork/fork
@@ -20,15 +24,23 @@ fork_c/fork
 getpid/getpid
 hello_pie/hello
 hello_world/hello_world
+ipc/mqueue
+ipc/msg
+ipc/shm
 itimer/setitimer
 itimer/timer_create
+itimer/timerfd
+mmap/memfd
 mmap/mmap_and_fork
 mmap/mmap_shared_filebacked
 mmap/mmap_readahead
+namespace/namespace
 pthread/pthread_test
 pty/open_pty
 signal_c/parent_death_signal
+signal_c/ptrace
 signal_c/signal_test
+signal_c/signalfd
 "
 
 for testcase in ${tests}
@@ -0,0 +1,16 @@
+#!/bin/sh
+
+# SPDX-License-Identifier: MPL-2.0
+
+# To successfully run the virtio-net test, you should
+# 1. Create the multiqueue tap device `aster-tap0` with the address 10.0.2.2/24 on the host
+# 2. Run a TCP echo server binding port 7007 on the host, before running ./tcp_streams
+
+set -e
+
+VIRTIO_NET_DIR=/test/virtio_net
+cd ${VIRTIO_NET_DIR}
+
+echo "Start virtio-net test......"
+./tcp_streams
+echo "Virtio-net test passed."
@@ -4,7 +4,8 @@
 
 # To successfully run the vsock test, you should
 # 1. Run vsock server binding port 1234 on the host, before running ./vsock_client
-# 2. Run vsock client connecting (cid,port)=(3,4321) on the host, after running ./vsock_server
+# 2. Run vsock seqpacket echo server binding port 1235 on the host, before running ./vsock_seqpacket
+# 3. Run vsock client connecting (cid,port)=(3,4321) on the host, after running ./vsock_server
 
 set -e
 
@@ -13,5 +14,7 @@ cd ${VSOCK_DIR}
 
 echo "Start vsock test......"
 ./vsock_client
+./vsock_connect
+./vsock_seqpacket
 ./vsock_server
 echo "Vsock test passed."
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <signal.h>
+#include <sys/prctl.h>
+#include <sys/ptrace.h>
+#include <sys/syscall.h>
+#include <sys/user.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+static volatile long value = 0x1234;
+static pid_t child;
+
+FN_SETUP(fork_tracee)
+{
+	child = CHECK(fork());
+	if (child == 0) {
+		CHECK(ptrace(PTRACE_TRACEME, 0, NULL, NULL));
+		CHECK(syscall(SYS_kill, syscall(SYS_getpid), SIGSTOP));
+		syscall(SYS_getpid);
+		_exit(value == 0x5678 ? 0 : 1);
+	}
+}
+END_SETUP()
+
+FN_TEST(traceme_stop)
+{
+	int status;
+
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSTOPPED(status) &&
+			 WSTOPSIG(status) == SIGSTOP);
+	TEST_RES(waitpid(child, &status, WNOHANG), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(invalid_args)
+{
+	TEST_ERRNO(ptrace(PTRACE_CONT, getpid(), NULL, NULL), ESRCH);
+	TEST_ERRNO(ptrace(PTRACE_ATTACH, getpid(), NULL, NULL), EPERM);
+	TEST_ERRNO(ptrace(PTRACE_ATTACH, child, NULL, NULL), EPERM);
+	TEST_ERRNO(ptrace(PTRACE_CONT, child, NULL, (void *)1000), EIO);
+	TEST_ERRNO(ptrace(0x1234, child, NULL, NULL), EIO);
+}
+END_TEST()
+
+FN_TEST(peek_poke)
+{
+	TEST_RES(ptrace(PTRACE_PEEKDATA, child, &value, NULL), _ret == 0x1234);
+	TEST_SUCC(ptrace(PTRACE_POKEDATA, child, &value, (void *)0x5678));
+	TEST_RES(ptrace(PTRACE_PEEKDATA, child, &value, NULL), _ret == 0x5678);
+	TEST_RES(value, _ret == 0x1234);
+}
+END_TEST()
+
+#ifdef __x86_64__
+FN_TEST(syscall_stops)
+{
+	struct user_regs_struct regs;
+	int status;
+	int i;
+
+	TEST_SUCC(ptrace(PTRACE_SETOPTIONS, child, NULL,
+			 (void *)PTRACE_O_TRACESYSGOOD));
+
+	// Find the syscall-entry stop of `getpid`
+	for (i = 0; i < 10; ++i) {
+		CHECK(ptrace(PTRACE_SYSCALL, child, NULL, NULL));
+		CHECK_WITH(waitpid(child, &status, 0),
+			   _ret == child && WIFSTOPPED(status) &&
+				   WSTOPSIG(status) == (SIGTRAP | 0x80));
+		CHECK(ptrace(PTRACE_GETREGS, child, NULL, &regs));
+		if (regs.orig_rax == SYS_getpid)
+			break;
+	}
+	TEST_RES(regs.orig_rax, _ret == SYS_getpid);
+
+	TEST_SUCC(ptrace(PTRACE_SYSCALL, child, NULL, NULL));
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSTOPPED(status) &&
+			 WSTOPSIG(status) == (SIGTRAP | 0x80));
+	TEST_SUCC(ptrace(PTRACE_GETREGS, child, NULL, &regs));
+	TEST_RES(regs.orig_rax, _ret == SYS_getpid);
+	TEST_RES(regs.rax, _ret == child);
+}
+END_TEST()
+#endif
+
+FN_TEST(cont_exit)
+{
+	int status;
+
+	TEST_SUCC(ptrace(PTRACE_CONT, child, NULL, NULL));
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == 0);
+	TEST_ERRNO(ptrace(PTRACE_CONT, child, NULL, NULL), ESRCH);
+}
+END_TEST()
+
+#ifdef __x86_64__
+FN_TEST(skip_syscall)
+{
+	struct user_regs_struct regs;
+	int status;
+
+	child = TEST_SUCC(fork());
+	if (child == 0) {
+		CHECK(ptrace(PTRACE_TRACEME, 0, NULL, NULL));
+		CHECK(syscall(SYS_kill, syscall(SYS_getpid), SIGSTOP));
+		_exit(syscall(SYS_getpid) == 0x42 ? 0 : 1);
+	}
+
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSTOPPED(status) &&
+			 WSTOPSIG(status) == SIGSTOP);
+
+	// Find the syscall-entry stop of `getpid`
+	do {
+		CHECK(ptrace(PTRACE_SYSCALL, child, NULL, NULL));
+		CHECK_WITH(waitpid(child, &status, 0),
+			   _ret == child && WIFSTOPPED(status));
+		CHECK(ptrace(PTRACE_GETREGS, child, NULL, &regs));
+	} while (regs.orig_rax != SYS_getpid);
+
+	// Skip the syscall and provide its return value
+	regs.orig_rax = -1;
+	regs.rax = 0x42;
+	TEST_SUCC(ptrace(PTRACE_SETREGS, child, NULL, &regs));
+
+	TEST_SUCC(ptrace(PTRACE_SYSCALL, child, NULL, NULL));
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSTOPPED(status));
+	TEST_SUCC(ptrace(PTRACE_GETREGS, child, NULL, &regs));
+	TEST_RES(regs.rax, _ret == 0x42);
+
+	TEST_SUCC(ptrace(PTRACE_CONT, child, NULL, NULL));
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFEXITED(status) &&
+			 WEXITSTATUS(status) == 0);
+}
+END_TEST()
+#endif
+
+FN_TEST(dumpable)
+{
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 1);
+	TEST_SUCC(prctl(PR_SET_DUMPABLE, 0));
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 0);
+	TEST_ERRNO(prctl(PR_SET_DUMPABLE, 2), EINVAL);
+	TEST_SUCC(prctl(PR_SET_DUMPABLE, 1));
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 1);
+}
+END_TEST()
+
+FN_TEST(kill_stopped)
+{
+	int status;
+
+	child = TEST_SUCC(fork());
+	if (child == 0) {
+		CHECK(ptrace(PTRACE_TRACEME, 0, NULL, NULL));
+		CHECK(syscall(SYS_kill, syscall(SYS_getpid), SIGSTOP));
+		_exit(0);
+	}
+
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSTOPPED(status) &&
+			 WSTOPSIG(status) == SIGSTOP);
+
+	// A stopped tracee can still be killed without being resumed
+	TEST_SUCC(kill(child, SIGKILL));
+	TEST_RES(waitpid(child, &status, 0),
+		 _ret == child && WIFSIGNALED(status) &&
+			 WTERMSIG(status) == SIGKILL);
+}
+END_TEST()
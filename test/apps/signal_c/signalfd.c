@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <signal.h>
+#include <sys/epoll.h>
+#include <sys/signalfd.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+static int sfd;
+static sigset_t mask;
+
+FN_SETUP(block_signals)
+{
+	sigemptyset(&mask);
+	sigaddset(&mask, SIGUSR1);
+	sigaddset(&mask, SIGUSR2);
+	CHECK(sigprocmask(SIG_BLOCK, &mask, NULL));
+
+	sfd = CHECK(signalfd(-1, &mask, SFD_NONBLOCK | SFD_CLOEXEC));
+}
+END_SETUP()
+
+FN_TEST(invalid_args)
+{
+	struct signalfd_siginfo info;
+
+	TEST_ERRNO(syscall(SYS_signalfd4, -1, &mask, 4, 0), EINVAL);
+	TEST_ERRNO(signalfd(-1, &mask, 0x1234), EINVAL);
+	TEST_ERRNO(signalfd(STDIN_FILENO, &mask, 0), EINVAL);
+	TEST_ERRNO(read(sfd, &info, sizeof(info) - 1), EINVAL);
+	TEST_ERRNO(write(sfd, &info, sizeof(info)), EINVAL);
+}
+END_TEST()
+
+FN_TEST(read_signal)
+{
+	struct signalfd_siginfo info[2];
+
+	TEST_ERRNO(read(sfd, &info, sizeof(info)), EAGAIN);
+
+	TEST_SUCC(kill(getpid(), SIGUSR1));
+	TEST_SUCC(kill(getpid(), SIGUSR2));
+	TEST_RES(read(sfd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info[0].ssi_signo == SIGUSR1 &&
+			 info[1].ssi_signo == SIGUSR2 &&
+			 info[0].ssi_code == SI_USER &&
+			 info[0].ssi_pid == getpid() &&
+			 info[0].ssi_uid == getuid());
+
+	TEST_ERRNO(read(sfd, &info, sizeof(info)), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(update_mask)
+{
+	struct signalfd_siginfo info;
+	sigset_t new_mask;
+	sigset_t pending;
+
+	sigemptyset(&new_mask);
+	sigaddset(&new_mask, SIGUSR2);
+	TEST_RES(signalfd(sfd, &new_mask, 0), _ret == sfd);
+
+	TEST_SUCC(kill(getpid(), SIGUSR1));
+	TEST_ERRNO(read(sfd, &info, sizeof(info)), EAGAIN);
+
+	TEST_SUCC(kill(getpid(), SIGUSR2));
+	TEST_RES(read(sfd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR2);
+
+	// The SIGUSR1 is still pending
+	TEST_RES(sigpending(&pending), sigismember(&pending, SIGUSR1) == 1);
+
+	TEST_RES(signalfd(sfd, &mask, 0), _ret == sfd);
+	TEST_RES(read(sfd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR1);
+}
+END_TEST()
+
+FN_TEST(epoll)
+{
+	struct signalfd_siginfo info;
+	struct epoll_event ev = { .events = EPOLLIN, .data.fd = sfd };
+	int epfd;
+
+	epfd = TEST_SUCC(epoll_create1(0));
+	TEST_SUCC(epoll_ctl(epfd, EPOLL_CTL_ADD, sfd, &ev));
+	TEST_RES(epoll_wait(epfd, &ev, 1, 0), _ret == 0);
+
+	TEST_SUCC(kill(getpid(), SIGUSR2));
+	TEST_RES(epoll_wait(epfd, &ev, 1, 1000),
+		 _ret == 1 && ev.data.fd == sfd && ev.events == EPOLLIN);
+
+	TEST_RES(read(sfd, &info, sizeof(info)),
+		 _ret == sizeof(info) && info.ssi_signo == SIGUSR2);
+	TEST_RES(epoll_wait(epfd, &ev, 1, 0), _ret == 0);
+
+	TEST_SUCC(close(epfd));
+}
+END_TEST()
+
+FN_TEST(epoll_in_child)
+{
+	struct signalfd_siginfo info;
+	struct epoll_event ev = { .events = EPOLLIN, .data.fd = sfd };
+	int pipe_fds[2];
+	int epfd, status;
+	pid_t pid;
+	char c;
+
+	TEST_SUCC(pipe(pipe_fds));
+
+	pid = TEST_SUCC(fork());
+	if (pid == 0) {
+		// The child inherits the signalfd and the blocked signals,
+		// and must be woken up by the signals sent to itself.
+		epfd = epoll_create1(0);
+		if (epfd < 0 || epoll_ctl(epfd, EPOLL_CTL_ADD, sfd, &ev) < 0)
+			_exit(1);
+		if (write(pipe_fds[1], "r", 1) != 1)
+			_exit(1);
+		if (epoll_wait(epfd, &ev, 1, 1000) != 1 || ev.data.fd != sfd)
+			_exit(2);
+		if (read(sfd, &info, sizeof(info)) != sizeof(info) ||
+		    info.ssi_signo != SIGUSR1 || info.ssi_pid != getppid())
+			_exit(3);
+		_exit(0);
+	}
+
+	TEST_RES(read(pipe_fds[0], &c, 1), _ret == 1);
+	TEST_SUCC(kill(pid, SIGUSR1));
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFEXITED(status) && WEXITSTATUS(status) == 0);
+
+	// The signal is sent to the child, not to the parent
+	TEST_ERRNO(read(sfd, &info, sizeof(info)), EAGAIN);
+
+	TEST_SUCC(close(pipe_fds[0]));
+	TEST_SUCC(close(pipe_fds[1]));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sfd));
+}
+END_SETUP()
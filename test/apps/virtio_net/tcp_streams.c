@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <poll.h>
+#include <string.h>
+#include <unistd.h>
+#include <sys/socket.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+
+#include "../network/test.h"
+
+// The host runs a TCP echo server on this address and this port
+#define ECHO_ADDR "10.0.2.2"
+#define ECHO_PORT 7007
+
+#define NR_STREAMS 4
+#define STREAM_LEN (4 << 20)
+
+struct stream {
+	int sk;
+	size_t sent;
+	size_t received;
+	// The offset of the first corrupted byte, or -1 if there is none
+	long corrupted_at;
+	int done;
+};
+
+static struct stream streams[NR_STREAMS];
+static unsigned char buf[65536];
+
+// Different streams and different pages have different patterns, so swapped,
+// lost, or duplicated data can be detected
+static unsigned char pattern(int stream, size_t offset)
+{
+	return (offset * 131 + (offset >> 12) + stream * 7) & 0xff;
+}
+
+FN_SETUP(connect)
+{
+	struct sockaddr_in addr;
+	int i;
+
+	addr.sin_family = AF_INET;
+	addr.sin_port = htons(ECHO_PORT);
+	CHECK(inet_aton(ECHO_ADDR, &addr.sin_addr));
+
+	for (i = 0; i < NR_STREAMS; ++i) {
+		streams[i].sk = CHECK(socket(PF_INET, SOCK_STREAM, 0));
+		CHECK(connect(streams[i].sk, (struct sockaddr *)&addr,
+			      sizeof(addr)));
+		CHECK(fcntl(streams[i].sk, F_SETFL, O_NONBLOCK));
+		streams[i].corrupted_at = -1;
+	}
+}
+END_SETUP()
+
+static void send_stream(int i)
+{
+	struct stream *stream = &streams[i];
+	size_t len, j;
+	ssize_t ret;
+
+	len = STREAM_LEN - stream->sent;
+	if (len > sizeof(buf))
+		len = sizeof(buf);
+	for (j = 0; j < len; ++j)
+		buf[j] = pattern(i, stream->sent + j);
+
+	ret = send(stream->sk, buf, len, 0);
+	if (ret > 0)
+		stream->sent += ret;
+}
+
+static int recv_stream(int i)
+{
+	struct stream *stream = &streams[i];
+	ssize_t ret, j;
+
+	ret = recv(stream->sk, buf, sizeof(buf), 0);
+	if (ret < 0)
+		return errno == EAGAIN ? 0 : -1;
+	if (ret == 0)
+		return -1;
+
+	for (j = 0; j < ret && stream->corrupted_at < 0; ++j)
+		if (buf[j] != pattern(i, stream->received + j))
+			stream->corrupted_at = stream->received + j;
+	stream->received += ret;
+
+	return 0;
+}
+
+// All streams are sent and received at the same time, so that the packets of
+// them are spread over multiple queues of the device
+FN_TEST(echo_streams)
+{
+	struct pollfd pfds[NR_STREAMS];
+	int i, nr_active = NR_STREAMS;
+
+	while (nr_active > 0) {
+		for (i = 0; i < NR_STREAMS; ++i) {
+			pfds[i].fd = streams[i].done ? -1 : streams[i].sk;
+			pfds[i].events = POLLIN;
+			if (streams[i].sent < STREAM_LEN)
+				pfds[i].events |= POLLOUT;
+		}
+
+		if (poll(pfds, NR_STREAMS, 10000) <= 0)
+			break;
+
+		for (i = 0; i < NR_STREAMS; ++i) {
+			if (pfds[i].revents & POLLOUT)
+				send_stream(i);
+			if ((pfds[i].revents & (POLLIN | POLLHUP | POLLERR)) &&
+			    (recv_stream(i) < 0 ||
+			     streams[i].received >= STREAM_LEN)) {
+				streams[i].done = 1;
+				--nr_active;
+			}
+		}
+	}
+
+	for (i = 0; i < NR_STREAMS; ++i) {
+		TEST_RES(streams[i].received, _ret == STREAM_LEN);
+		TEST_RES(streams[i].corrupted_at, _ret == -1);
+	}
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	int i;
+
+	for (i = 0; i < NR_STREAMS; ++i)
+		CHECK(close(streams[i].sk));
+}
+END_SETUP()
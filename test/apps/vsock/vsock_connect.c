@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <fcntl.h>
+#include <poll.h>
+#include <sys/socket.h>
+#include <linux/vm_sockets.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+// The host runs a `SOCK_STREAM` server on this port
+#define HOST_PORT 1234
+#define LISTEN_PORT 4322
+// The host drops the packets to an unknown CID, so connecting to it times out
+#define UNKNOWN_CID 100
+
+#ifndef SO_VM_SOCKETS_CONNECT_TIMEOUT
+#define SO_VM_SOCKETS_CONNECT_TIMEOUT 6
+#endif
+
+static long elapsed_ms(const struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(CLOCK_MONOTONIC, &now);
+	return (now.tv_sec - start->tv_sec) * 1000 +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+FN_TEST(unsupported_type)
+{
+	// virtio-vsock does not support datagrams
+	TEST_ERRNO(socket(AF_VSOCK, SOCK_DGRAM, 0), ESOCKTNOSUPPORT);
+}
+END_TEST()
+
+FN_TEST(nonblocking_connect)
+{
+	struct sockaddr_vm addr = {
+		.svm_family = AF_VSOCK,
+		.svm_cid = VMADDR_CID_HOST,
+		.svm_port = HOST_PORT,
+	};
+	struct pollfd pfd;
+	int err;
+	socklen_t errlen = sizeof(err);
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_VSOCK, SOCK_STREAM | SOCK_NONBLOCK, 0));
+	pfd.fd = sk;
+	pfd.events = POLLOUT;
+
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&addr, sizeof(addr)),
+		   EINPROGRESS);
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && (pfd.revents & POLLOUT));
+	TEST_RES(getsockopt(sk, SOL_SOCKET, SO_ERROR, &err, &errlen),
+		 errlen == sizeof(err) && err == 0);
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&addr, sizeof(addr)),
+		   EISCONN);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(nonblocking_accept)
+{
+	struct sockaddr_vm addr = {
+		.svm_family = AF_VSOCK,
+		.svm_cid = VMADDR_CID_ANY,
+		.svm_port = LISTEN_PORT,
+	};
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_VSOCK, SOCK_STREAM, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(listen(sk, 1));
+	TEST_SUCC(fcntl(sk, F_SETFL, O_NONBLOCK));
+
+	TEST_ERRNO(accept(sk, NULL, NULL), EAGAIN);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(connect_timeout_option)
+{
+	struct timeval tv;
+	socklen_t tvlen = sizeof(tv);
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_VSOCK, SOCK_STREAM, 0));
+
+	// The default timeout is 2 seconds
+	TEST_RES(getsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			    &tvlen),
+		 tvlen == sizeof(tv) && tv.tv_sec == 2 && tv.tv_usec == 0);
+
+	tv.tv_sec = 1;
+	tv.tv_usec = 500000;
+	TEST_SUCC(setsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			     sizeof(tv)));
+	TEST_RES(getsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			    &tvlen),
+		 tv.tv_sec == 1 && tv.tv_usec == 500000);
+
+	// A zero timeout restores the default one
+	tv.tv_sec = 0;
+	tv.tv_usec = 0;
+	TEST_SUCC(setsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			     sizeof(tv)));
+	TEST_RES(getsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			    &tvlen),
+		 tv.tv_sec == 2 && tv.tv_usec == 0);
+
+	tv.tv_sec = -1;
+	TEST_ERRNO(setsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			      sizeof(tv)),
+		   ERANGE);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(connect_timeout)
+{
+	struct sockaddr_vm addr = {
+		.svm_family = AF_VSOCK,
+		.svm_cid = UNKNOWN_CID,
+		.svm_port = HOST_PORT,
+	};
+	struct timeval tv = { .tv_sec = 0, .tv_usec = 500000 };
+	struct timeval sndtimeo = { .tv_sec = 0, .tv_usec = 100000 };
+	struct timespec start;
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_VSOCK, SOCK_STREAM, 0));
+	TEST_SUCC(setsockopt(sk, AF_VSOCK, SO_VM_SOCKETS_CONNECT_TIMEOUT, &tv,
+			     sizeof(tv)));
+	// `SO_SNDTIMEO` does not affect connecting
+	TEST_SUCC(setsockopt(sk, SOL_SOCKET, SO_SNDTIMEO, &sndtimeo,
+			     sizeof(sndtimeo)));
+
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&addr, sizeof(addr)),
+		   ETIMEDOUT);
+	TEST_RES(elapsed_ms(&start), _ret >= 400 && _ret < 1500);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
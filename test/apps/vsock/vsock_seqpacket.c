@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <string.h>
+#include <sys/socket.h>
+#include <linux/vm_sockets.h>
+#include <unistd.h>
+
+#include "../network/test.h"
+
+// The host runs a `SOCK_SEQPACKET` server on this port that echoes every
+// message back
+#define ECHO_PORT 1235
+
+static int sk_echo;
+
+FN_SETUP(connect_echo)
+{
+	struct sockaddr_vm addr = {
+		.svm_family = AF_VSOCK,
+		.svm_cid = VMADDR_CID_HOST,
+		.svm_port = ECHO_PORT,
+	};
+
+	sk_echo = CHECK(socket(AF_VSOCK, SOCK_SEQPACKET, 0));
+	CHECK(connect(sk_echo, (struct sockaddr *)&addr, sizeof(addr)));
+}
+END_SETUP()
+
+FN_TEST(message_boundaries)
+{
+	char buf[64];
+
+	TEST_RES(send(sk_echo, "hello", 5, 0), _ret == 5);
+	TEST_RES(send(sk_echo, "world!", 6, 0), _ret == 6);
+
+	// The messages are not merged, even if the buffer is large enough
+	TEST_RES(recv(sk_echo, buf, sizeof(buf), MSG_WAITALL),
+		 _ret == 5 && memcmp(buf, "hello", 5) == 0);
+	TEST_RES(recv(sk_echo, buf, sizeof(buf), MSG_WAITALL),
+		 _ret == 6 && memcmp(buf, "world!", 6) == 0);
+}
+END_TEST()
+
+FN_TEST(message_truncation)
+{
+	char buf[64];
+
+	TEST_RES(send(sk_echo, "truncated", 9, 0), _ret == 9);
+	TEST_RES(send(sk_echo, "whole", 5, 0), _ret == 5);
+
+	// The rest of a truncated message is discarded
+	TEST_RES(recv(sk_echo, buf, 5, 0),
+		 _ret == 5 && memcmp(buf, "trunc", 5) == 0);
+	TEST_RES(recv(sk_echo, buf, sizeof(buf), 0),
+		 _ret == 5 && memcmp(buf, "whole", 5) == 0);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_echo));
+}
+END_SETUP()